@@ -215,6 +215,43 @@ fn agent_event_to_session_updates(event: &AgentEvent) -> Vec<acp::SessionUpdate>
             // Cancellation is handled separately
             vec![]
         }
+        AgentEvent::MaxTurnsExceeded { max_turns } => {
+            vec![acp::SessionUpdate::AgentThoughtChunk(acp::ContentChunk::new(
+                acp::ContentBlock::Text(acp::TextContent::new(format!(
+                    "Aborted: reached max_turns limit ({})",
+                    max_turns
+                ))),
+            ))]
+        }
+        AgentEvent::TokenBudgetExceeded { budget, used } => {
+            vec![acp::SessionUpdate::AgentThoughtChunk(acp::ContentChunk::new(
+                acp::ContentBlock::Text(acp::TextContent::new(format!(
+                    "Aborted: token budget spent ({} tokens used, budget {})",
+                    used, budget
+                ))),
+            ))]
+        }
+        AgentEvent::RepeatedToolFailures { tool, error, count } => {
+            vec![acp::SessionUpdate::AgentThoughtChunk(acp::ContentChunk::new(
+                acp::ContentBlock::Text(acp::TextContent::new(format!(
+                    "Aborted: '{}' failed {} times consecutively with: {}",
+                    tool, count, error
+                ))),
+            ))]
+        }
+        AgentEvent::Thinking(text) => {
+            vec![acp::SessionUpdate::AgentThoughtChunk(acp::ContentChunk::new(
+                acp::ContentBlock::Text(acp::TextContent::new(text.clone())),
+            ))]
+        }
+        AgentEvent::UserSteering(message) => {
+            vec![acp::SessionUpdate::AgentThoughtChunk(acp::ContentChunk::new(
+                acp::ContentBlock::Text(acp::TextContent::new(format!(
+                    "Steering injected: {}",
+                    message
+                ))),
+            ))]
+        }
         AgentEvent::Retry {
             attempt,
             max_attempts,
@@ -21,7 +21,11 @@ use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 
 use crate::plan::is_tool_allowed_in_plan_mode;
-use crate::tools::CleminiToolService;
+use crate::provider::{
+    GeminiProvider, ModelProvider, ProviderRequest, ToolResultContent, TurnContent,
+};
+use crate::tokens::{HeuristicTokenCounter, TokenCounter};
+use crate::tools::{CleminiToolService, tool_is_read_only};
 
 /// Calculate exponential backoff delay with saturation to prevent overflow.
 fn calculate_backoff_delay(attempt: u32, base: Duration) -> Duration {
@@ -84,6 +88,12 @@ pub enum AgentEvent {
     /// Streaming text chunk from model.
     TextDelta(String),
 
+    /// Streaming reasoning/thought chunk from model.
+    /// Kept separate from `TextDelta` so UIs can render it distinctly
+    /// (dimmed in the terminal, thought chunks over ACP) instead of mixing
+    /// it into the response.
+    Thinking(String),
+
     /// Tool execution about to start.
     /// Contains function call info from genai-rs.
     ToolExecuting(Vec<OwnedFunctionCallInfo>),
@@ -109,6 +119,37 @@ pub enum AgentEvent {
     /// Tools emit this for visual output instead of calling log_event() directly.
     ToolOutput(String),
 
+    /// Agent loop aborted after reaching the configured turn limit.
+    /// Emitted when the model keeps issuing tool calls without completing.
+    MaxTurnsExceeded {
+        /// The turn limit that was hit.
+        max_turns: usize,
+    },
+
+    /// Agent loop aborted after the cumulative token budget was spent.
+    /// Emitted when `RetryConfig::max_total_tokens` is set and exceeded.
+    TokenBudgetExceeded {
+        /// The configured budget.
+        budget: u32,
+        /// Tokens actually consumed when the budget check fired.
+        used: u32,
+    },
+
+    /// Agent loop aborted after the same tool failed with the same error
+    /// too many times in a row (see `RetryConfig::max_consecutive_tool_failures`).
+    RepeatedToolFailures {
+        /// The tool that kept failing.
+        tool: String,
+        /// The error it kept returning.
+        error: String,
+        /// How many consecutive times it failed.
+        count: usize,
+    },
+
+    /// A queued user steering message was injected at a turn boundary.
+    /// Emitted once per message so UIs can show where the correction landed.
+    UserSteering(String),
+
     /// API call retrying due to transient failure.
     Retry {
         /// Current retry attempt number (1-based).
@@ -122,7 +163,7 @@ pub enum AgentEvent {
     },
 }
 
-/// Configuration for API retries.
+/// Configuration for API retries and agent loop limits.
 #[derive(Debug, Clone, Copy)]
 pub struct RetryConfig {
     /// Maximum number of extra retry attempts after initial failure.
@@ -130,6 +171,24 @@ pub struct RetryConfig {
     pub max_extra_retries: u32,
     /// Base delay for exponential backoff.
     pub retry_delay_base: Duration,
+    /// Cap on the backoff delay (including server-suggested delays).
+    pub max_retry_delay: Duration,
+    /// Whether to add random jitter (up to 20%) to retry delays.
+    /// Disable for deterministic timing in tests.
+    pub jitter: bool,
+    /// Maximum number of agent turns (model responses) per interaction.
+    /// A safeguard against runaway tool-call loops; when hit, the interaction
+    /// aborts with a `MaxTurnsExceeded` event and an error.
+    pub max_turns: usize,
+    /// Abort after the same tool fails with the same error this many times
+    /// consecutively (e.g., `edit` repeatedly called with a bad `old_string`).
+    /// 0 disables the check.
+    pub max_consecutive_tool_failures: usize,
+    /// Cumulative token budget for the interaction (input + output across all
+    /// turns). When exceeded the interaction aborts with a
+    /// `TokenBudgetExceeded` event instead of continuing to spend. `None`
+    /// disables the check; `task` subagent limits pass this via `--max-tokens`.
+    pub max_total_tokens: Option<u32>,
 }
 
 impl Default for RetryConfig {
@@ -137,7 +196,172 @@ impl Default for RetryConfig {
         Self {
             max_extra_retries: 2,
             retry_delay_base: Duration::from_secs(1),
+            max_retry_delay: Duration::from_secs(30),
+            jitter: true,
+            max_turns: 100,
+            max_consecutive_tool_failures: 5,
+            max_total_tokens: None,
+        }
+    }
+}
+
+/// Token count for one turn of outbound content, via `counter`. Used to keep
+/// an approximate context size when the provider doesn't report usage.
+async fn approx_turn_tokens(content: &TurnContent, counter: &dyn TokenCounter) -> u32 {
+    match content {
+        TurnContent::UserText(text) => counter.count(text).await,
+        TurnContent::ToolResults { results, steering } => {
+            let mut total: u32 = 0;
+            for result in results {
+                total = total.saturating_add(counter.count(&result.result.to_string()).await);
+            }
+            for message in steering {
+                total = total.saturating_add(counter.count(message).await);
+            }
+            total
+        }
+    }
+}
+
+/// Tracks consecutive identical tool failures so the agent loop can abort
+/// instead of letting the model retry the same broken call indefinitely.
+#[derive(Default)]
+struct FailureTracker {
+    /// (tool name, error message) of the most recent failure streak.
+    last: Option<(String, String)>,
+    count: usize,
+}
+
+impl FailureTracker {
+    /// Record one turn of tool results and return the current streak length.
+    /// Any successful result breaks the streak; a failure with a different
+    /// tool or error starts a new one.
+    fn record(&mut self, results: &[ToolResultContent]) -> usize {
+        for result in results {
+            match result.result.get("error").and_then(|e| e.as_str()) {
+                Some(error) => {
+                    let key = (result.name.clone(), error.to_string());
+                    if self.last.as_ref() == Some(&key) {
+                        self.count += 1;
+                    } else {
+                        self.last = Some(key);
+                        self.count = 1;
+                    }
+                }
+                None => {
+                    self.last = None;
+                    self.count = 0;
+                }
+            }
         }
+        self.count
+    }
+}
+
+/// Token usage for an interaction, accumulated across all turns.
+///
+/// Unlike `InteractionResult::total_tokens` (which reflects the final context
+/// size), these counts sum every turn's usage, so they match what the API
+/// actually bills.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokenUsage {
+    /// Input (prompt) tokens across all turns.
+    pub input_tokens: u32,
+    /// Output (completion) tokens across all turns.
+    pub output_tokens: u32,
+    /// Tokens served from context cache. genai-rs does not expose cached
+    /// counts yet, so this stays 0 until it does.
+    pub cached_tokens: u32,
+    /// Total tokens across all turns.
+    pub total_tokens: u32,
+}
+
+impl TokenUsage {
+    /// Accumulate another usage record into this one.
+    pub fn add(&mut self, other: &TokenUsage) {
+        self.input_tokens = self.input_tokens.saturating_add(other.input_tokens);
+        self.output_tokens = self.output_tokens.saturating_add(other.output_tokens);
+        self.cached_tokens = self.cached_tokens.saturating_add(other.cached_tokens);
+        self.total_tokens = self.total_tokens.saturating_add(other.total_tokens);
+    }
+
+    /// Compute the dollar cost of this usage for the given model.
+    /// Returns `None` when the model isn't in the pricing table.
+    pub fn cost_usd(&self, model: &str) -> Option<f64> {
+        let pricing = model_pricing(model)?;
+        let uncached_input = self.input_tokens.saturating_sub(self.cached_tokens);
+        Some(
+            f64::from(uncached_input) / 1e6 * pricing.input_per_mtok
+                + f64::from(self.cached_tokens) / 1e6 * pricing.cached_per_mtok
+                + f64::from(self.output_tokens) / 1e6 * pricing.output_per_mtok,
+        )
+    }
+}
+
+/// Per-million-token pricing for a model (USD).
+#[derive(Debug, Clone, Copy)]
+pub struct ModelPricing {
+    pub input_per_mtok: f64,
+    pub output_per_mtok: f64,
+    pub cached_per_mtok: f64,
+}
+
+/// Pricing table for known Gemini model families (USD per million tokens).
+///
+/// Matched by prefix so preview suffixes (e.g., `gemini-3-flash-preview`)
+/// resolve to their family. Returns `None` for unknown models rather than
+/// guessing.
+pub fn model_pricing(model: &str) -> Option<ModelPricing> {
+    // Check "-lite" variants before their base models (longest prefix wins)
+    if model.starts_with("gemini-3-flash-lite") || model.starts_with("gemini-2.5-flash-lite") {
+        Some(ModelPricing {
+            input_per_mtok: 0.10,
+            output_per_mtok: 0.40,
+            cached_per_mtok: 0.025,
+        })
+    } else if model.starts_with("gemini-3-flash") || model.starts_with("gemini-2.5-flash") {
+        Some(ModelPricing {
+            input_per_mtok: 0.30,
+            output_per_mtok: 2.50,
+            cached_per_mtok: 0.075,
+        })
+    } else if model.starts_with("gemini-3-pro") || model.starts_with("gemini-2.5-pro") {
+        Some(ModelPricing {
+            input_per_mtok: 1.25,
+            output_per_mtok: 10.00,
+            cached_per_mtok: 0.3125,
+        })
+    } else {
+        None
+    }
+}
+
+/// Queue of user steering messages for a running interaction.
+///
+/// UIs hand the agent a clone of this queue and push corrections into it while
+/// tools run; the agent drains it at each turn boundary and injects the
+/// messages as user text alongside the tool results, emitting
+/// `AgentEvent::UserSteering` per message. Messages still queued when the
+/// interaction completes are dropped (the user can simply send them as the
+/// next prompt).
+#[derive(Debug, Clone, Default)]
+pub struct SteeringQueue {
+    messages: Arc<std::sync::Mutex<std::collections::VecDeque<String>>>,
+}
+
+impl SteeringQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a steering message for injection at the next turn boundary.
+    pub fn push(&self, message: impl Into<String>) {
+        self.messages.lock().unwrap().push_back(message.into());
+    }
+
+    /// Take all queued messages, leaving the queue empty.
+    fn drain(&self) -> Vec<String> {
+        self.messages.lock().unwrap().drain(..).collect()
     }
 }
 
@@ -152,12 +376,21 @@ pub struct InteractionResult {
     pub response: String,
     pub context_size: u32,
     pub total_tokens: u32,
+    /// Token usage accumulated across all turns of this interaction.
+    pub usage: TokenUsage,
     pub tool_calls: Vec<String>,
+    /// Per-tool stats (calls, errors, duration, token estimate) for this
+    /// interaction only - a delta of the service's session-wide counters.
+    pub tool_stats: crate::tools::ToolStatsMap,
     pub needs_confirmation: Option<serde_json::Value>,
+    /// True if the interaction was cancelled before completing. The partial
+    /// response, completed tool calls, and `id` are still populated so the
+    /// next prompt can continue from where it stopped.
+    pub cancelled: bool,
 }
 
 struct ToolExecutionResult {
-    results: Vec<Content>,
+    results: Vec<ToolResultContent>,
     cancelled: bool,
     needs_confirmation: Option<Value>,
 }
@@ -173,6 +406,24 @@ fn check_context_window(total_tokens: u32, events_tx: &mpsc::Sender<AgentEvent>)
     }
 }
 
+/// Execute a single tool call, converting errors to JSON so Gemini can see
+/// them and retry.
+async fn execute_single_tool(
+    tool_service: &Arc<CleminiToolService>,
+    call_name: &str,
+    call_args: &Value,
+) -> (Value, Duration) {
+    let start = Instant::now();
+    let result = match tool_service.execute(call_name, call_args.clone()).await {
+        Ok(v) => v,
+        Err(e) => {
+            tracing::warn!("Tool {} failed: {}", call_name, e);
+            serde_json::json!({"error": e.to_string()})
+        }
+    };
+    (result, start.elapsed())
+}
+
 async fn execute_tools(
     tool_service: &Arc<CleminiToolService>,
     accumulated_function_calls: &[(Option<String>, String, Value)],
@@ -193,7 +444,8 @@ async fn execute_tools(
         .collect();
     let _ = events_tx.try_send(AgentEvent::ToolExecuting(owned_calls));
 
-    for (call_id, call_name, call_args) in accumulated_function_calls {
+    let mut index = 0;
+    while index < accumulated_function_calls.len() {
         if cancellation_token.is_cancelled() {
             return ToolExecutionResult {
                 results,
@@ -202,6 +454,63 @@ async fn execute_tools(
             };
         }
 
+        let (_, batch_name, _) = &accumulated_function_calls[index];
+
+        // Runs of consecutive read-only calls (e.g., three reads in one turn)
+        // are independent, so execute them concurrently. Mutating tools stay
+        // serial to preserve ordering guarantees between writes.
+        if tool_is_read_only(batch_name) {
+            let batch_end = index
+                + accumulated_function_calls[index..]
+                    .iter()
+                    .take_while(|(_, name, _)| tool_is_read_only(name))
+                    .count();
+            let batch = &accumulated_function_calls[index..batch_end];
+
+            let futures = batch
+                .iter()
+                .map(|(_, name, args)| execute_single_tool(tool_service, name, args));
+            let batch_results: Vec<(Value, Duration)> = tokio::select! {
+                res = futures_util::future::join_all(futures) => res,
+                _ = cancellation_token.cancelled() => {
+                    return ToolExecutionResult {
+                        results,
+                        cancelled: true,
+                        needs_confirmation: None,
+                    };
+                }
+            };
+
+            // Emit ToolResult events and collect results in the original call
+            // order, regardless of completion order.
+            for ((call_id, call_name, call_args), (result, duration)) in
+                batch.iter().zip(batch_results)
+            {
+                tool_calls.push(call_name.to_string());
+
+                let execution_result = FunctionExecutionResult::new(
+                    call_name.clone(),
+                    call_id.clone().unwrap_or_default(),
+                    call_args.clone(),
+                    result.clone(),
+                    duration,
+                );
+                let _ = events_tx.try_send(AgentEvent::ToolResult(execution_result));
+
+                results.push(ToolResultContent {
+                    name: call_name.to_string(),
+                    call_id: call_id.clone().unwrap_or_default(),
+                    result,
+                });
+            }
+
+            index = batch_end;
+            continue;
+        }
+
+        let (call_id, call_name, call_args) = &accumulated_function_calls[index];
+        index += 1;
+
         // Check if we're in plan mode and this tool is blocked
         let in_plan_mode = tool_service
             .plan_manager()
@@ -226,12 +535,12 @@ async fn execute_tools(
             );
             let _ = events_tx.try_send(AgentEvent::ToolResult(execution_result));
 
-            // Add to results as Content for the model
-            results.push(Content::function_result(
-                call_name.to_string(),
-                call_id.clone().unwrap_or_default(),
+            // Add to results for the model
+            results.push(ToolResultContent {
+                name: call_name.to_string(),
+                call_id: call_id.clone().unwrap_or_default(),
                 result,
-            ));
+            });
             continue;
         }
 
@@ -267,11 +576,11 @@ async fn execute_tools(
         );
         let _ = events_tx.try_send(AgentEvent::ToolResult(execution_result));
 
-        results.push(Content::function_result(
-            call_name.to_string(),
-            call_id.clone().unwrap_or_default(),
-            result.clone(),
-        ));
+        results.push(ToolResultContent {
+            name: call_name.to_string(),
+            call_id: call_id.clone().unwrap_or_default(),
+            result: result.clone(),
+        });
 
         // If tool requires confirmation, stop and return to caller.
         // This prevents Gemini from self-confirming.
@@ -295,6 +604,28 @@ async fn execute_tools(
     }
 }
 
+/// Extract the text of a reasoning/thought part, if this content is one.
+///
+/// genai-rs doesn't expose a dedicated accessor for thought parts yet, so we
+/// inspect the serialized part for the API's `thought` flag. Non-thought
+/// content (and content that doesn't serialize) returns `None` and flows
+/// through the normal text path.
+fn thought_text(content: &Content) -> Option<String> {
+    let value = serde_json::to_value(content).ok()?;
+    if value
+        .get("thought")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+    {
+        value
+            .get("text")
+            .and_then(|v| v.as_str())
+            .map(String::from)
+    } else {
+        None
+    }
+}
+
 #[derive(Debug)]
 struct StreamProcessingResult {
     response: Option<InteractionResponse>,
@@ -339,7 +670,9 @@ where
         match event {
             Ok(event) => match event.chunk {
                 StreamChunk::Delta(content) => {
-                    if let Some(text) = content.as_text() {
+                    if let Some(thought) = thought_text(&content) {
+                        let _ = events_tx.try_send(AgentEvent::Thinking(thought));
+                    } else if let Some(text) = content.as_text() {
                         let _ = events_tx.try_send(AgentEvent::TextDelta(text.to_string()));
                         full_response.push_str(text);
                     }
@@ -367,6 +700,10 @@ where
 
 /// Run an interaction with Gemini, sending events through the channel.
 ///
+/// Convenience wrapper around [`run_interaction_with_provider`] using the
+/// default Gemini backend. Kept as the primary entry point since most callers
+/// (MCP, ACP, tests) only target Gemini.
+///
 /// # Arguments
 ///
 /// * `client` - genai-rs Client
@@ -377,7 +714,7 @@ where
 /// * `system_prompt` - System instruction
 /// * `events_tx` - Channel to send AgentEvents to UI
 /// * `cancellation_token` - Token for cancellation
-#[allow(clippy::too_many_lines, clippy::too_many_arguments)]
+#[allow(clippy::too_many_arguments)]
 pub async fn run_interaction(
     client: &Client,
     tool_service: &Arc<CleminiToolService>,
@@ -388,6 +725,95 @@ pub async fn run_interaction(
     events_tx: mpsc::Sender<AgentEvent>,
     cancellation_token: CancellationToken,
     retry_config: RetryConfig,
+) -> Result<InteractionResult> {
+    let provider = GeminiProvider::new(client);
+    // Gemini reports usage on (nearly) every turn, so the heuristic counter
+    // suffices for the rare fallback here; the CLI paths pass a
+    // `GeminiTokenCounter` for real counts.
+    run_interaction_with_provider(
+        &provider,
+        tool_service,
+        input,
+        previous_interaction_id,
+        model,
+        system_prompt,
+        events_tx,
+        cancellation_token,
+        retry_config,
+        SteeringQueue::new(),
+        Arc::new(HeuristicTokenCounter),
+    )
+    .await
+}
+
+/// Run an interaction against any [`ModelProvider`] backend.
+///
+/// This is the core agent loop: stream one model turn, execute any function
+/// calls, send results back, repeat until the model completes without calls.
+/// The provider abstracts the backend; event emission, retries, cancellation,
+/// and token accounting are identical across providers. Messages pushed into
+/// `steering` while tools run are injected at the next turn boundary.
+/// `token_counter` backs the context-size estimate on turns where the
+/// provider doesn't report usage (server-reported counts always win) - pass
+/// a [`crate::tokens::GeminiTokenCounter`] for real `countTokens` numbers or
+/// [`HeuristicTokenCounter`] for the free estimate.
+///
+/// Note on context caching: the large static prefix (system prompt +
+/// CLAUDE.md) is resent every turn. Gemini's explicit `cachedContents` API
+/// would let us create a handle for it once and reference it per-turn, but
+/// genai-rs doesn't expose cached-content handles on the interaction builder
+/// or `cachedContentTokenCount` in usage yet, so there's nothing to attach a
+/// cache to here. Revisit when the upstream API lands (see the genai-rs
+/// integration notes in CLAUDE.md).
+#[allow(clippy::too_many_arguments)]
+pub async fn run_interaction_with_provider(
+    provider: &dyn ModelProvider,
+    tool_service: &Arc<CleminiToolService>,
+    input: &str,
+    previous_interaction_id: Option<&str>,
+    model: &str,
+    system_prompt: &str,
+    events_tx: mpsc::Sender<AgentEvent>,
+    cancellation_token: CancellationToken,
+    retry_config: RetryConfig,
+    steering: SteeringQueue,
+    token_counter: Arc<dyn TokenCounter>,
+) -> Result<InteractionResult> {
+    let result = interaction_loop(
+        provider,
+        tool_service,
+        input,
+        previous_interaction_id,
+        model,
+        system_prompt,
+        events_tx,
+        cancellation_token,
+        retry_config,
+        steering,
+        token_counter,
+    )
+    .await;
+
+    // Persistent bash sessions are scoped to a single interaction - tear
+    // them down regardless of how the interaction ended
+    crate::tools::cleanup_shell_sessions().await;
+
+    result
+}
+
+#[allow(clippy::too_many_lines, clippy::too_many_arguments)]
+async fn interaction_loop(
+    provider: &dyn ModelProvider,
+    tool_service: &Arc<CleminiToolService>,
+    input: &str,
+    previous_interaction_id: Option<&str>,
+    model: &str,
+    system_prompt: &str,
+    events_tx: mpsc::Sender<AgentEvent>,
+    cancellation_token: CancellationToken,
+    retry_config: RetryConfig,
+    steering: SteeringQueue,
+    token_counter: Arc<dyn TokenCounter>,
 ) -> Result<InteractionResult> {
     let functions: Vec<_> = tool_service
         .tools()
@@ -398,28 +824,31 @@ pub async fn run_interaction(
     let mut last_id = previous_interaction_id.map(String::from);
     let mut current_context_size: u32 = 0;
     let mut total_tokens: u32 = 0;
+    let mut usage = TokenUsage::default();
     let mut tool_calls: Vec<String> = Vec::new();
     let mut full_response = String::new();
     let mut last_response: Option<InteractionResponse> = None;
-    let mut next_turn_content: Vec<Content> = vec![Content::text(input)];
-
-    const MAX_ITERATIONS: usize = 100;
-    for _ in 0..MAX_ITERATIONS {
+    let mut next_turn = TurnContent::UserText(input.to_string());
+
+    let mut completed = false;
+    let mut failure_tracker = FailureTracker::default();
+    // Snapshot session stats so the result can report this interaction's
+    // tool usage as a delta.
+    let stats_before = tool_service.tool_stats();
+    // Running estimate of context size, used only when the provider doesn't
+    // report usage (server-reported counts always win). Counted lazily via
+    // `token_counter` so usage-reporting providers never pay for it.
+    let mut approx_context_tokens: u32 = 0;
+    for _ in 0..retry_config.max_turns {
         let mut attempt = 0;
         let stream_result = loop {
-            let mut interaction = client
-                .interaction()
-                .with_model(model)
-                .add_functions(functions.clone())
-                .with_system_instruction(system_prompt);
-
-            if let Some(prev_id) = &last_id {
-                interaction = interaction.with_previous_interaction(prev_id);
-            }
-
-            interaction = interaction.with_content(next_turn_content.clone());
-
-            let stream = Box::pin(interaction.create_stream());
+            let stream = provider.create_stream(ProviderRequest {
+                model,
+                system_prompt,
+                previous_interaction_id: last_id.as_deref(),
+                functions: &functions,
+                content: next_turn.clone(),
+            });
             match process_interaction_stream(
                 stream,
                 &events_tx,
@@ -432,10 +861,14 @@ pub async fn run_interaction(
                 Err(e) if e.is_retryable() && attempt < retry_config.max_extra_retries => {
                     attempt += 1;
 
-                    // Use server-suggested delay if available, otherwise exponential backoff
-                    let delay = e.retry_after().unwrap_or_else(|| {
-                        calculate_backoff_delay(attempt, retry_config.retry_delay_base)
-                    });
+                    // Use server-suggested delay if available, otherwise
+                    // exponential backoff; either way capped at max_retry_delay
+                    let delay = e
+                        .retry_after()
+                        .unwrap_or_else(|| {
+                            calculate_backoff_delay(attempt, retry_config.retry_delay_base)
+                        })
+                        .min(retry_config.max_retry_delay);
 
                     let _ = events_tx.try_send(AgentEvent::Retry {
                         attempt,
@@ -444,7 +877,11 @@ pub async fn run_interaction(
                         error: e.to_string(),
                     });
 
-                    sleep_with_jitter(delay).await;
+                    if retry_config.jitter {
+                        sleep_with_jitter(delay).await;
+                    } else {
+                        tokio::time::sleep(delay).await;
+                    }
 
                     // If we had some response, clear it for the retry to avoid duplication
                     // (Note: TextDelta events were already sent, so UI might still show them)
@@ -460,8 +897,11 @@ pub async fn run_interaction(
                 response: full_response,
                 context_size: current_context_size,
                 total_tokens,
+                usage,
                 tool_calls,
+                tool_stats: crate::tools::stats::diff(&stats_before, &tool_service.tool_stats()),
                 needs_confirmation: None,
+                cancelled: true,
             });
         }
 
@@ -473,25 +913,63 @@ pub async fn run_interaction(
         last_id = resp.id.clone();
         last_response = Some(resp.clone());
 
-        // Update token count
-        if let Some(usage) = &resp.usage {
-            let turn_tokens = usage.total_tokens.unwrap_or_else(|| {
-                usage.total_input_tokens.unwrap_or(0) + usage.total_output_tokens.unwrap_or(0)
+        // Update token counts
+        if let Some(turn_usage) = &resp.usage {
+            let input_tokens = turn_usage.total_input_tokens.unwrap_or(0);
+            let output_tokens = turn_usage.total_output_tokens.unwrap_or(0);
+            let turn_tokens = turn_usage
+                .total_tokens
+                .unwrap_or(input_tokens + output_tokens);
+            usage.add(&TokenUsage {
+                input_tokens,
+                output_tokens,
+                cached_tokens: 0,
+                total_tokens: turn_tokens,
             });
             if turn_tokens > 0 {
                 current_context_size = turn_tokens;
                 total_tokens = turn_tokens;
             }
+        } else {
+            // Providers without usage reporting (e.g., some OpenAI-compatible
+            // servers): count the turn ourselves so context warnings still
+            // fire instead of staying silent forever. With a
+            // `GeminiTokenCounter` these are real `countTokens` numbers
+            // (cached per text, heuristic on network failure); otherwise the
+            // ~4 chars/token estimate.
+            if approx_context_tokens == 0 {
+                approx_context_tokens = token_counter.count(system_prompt).await;
+            }
+            approx_context_tokens = approx_context_tokens
+                .saturating_add(approx_turn_tokens(&next_turn, token_counter.as_ref()).await)
+                .saturating_add(token_counter.count(&full_response).await);
+            current_context_size = approx_context_tokens;
+            total_tokens = approx_context_tokens;
         }
 
         // Use accumulated function calls from Delta chunks (streaming mode doesn't populate Complete.outputs)
         if accumulated_function_calls.is_empty() {
             // No more function calls - interaction complete
+            completed = true;
             break;
         }
 
-        // Process function calls (accumulated from Delta chunks)
-        full_response.clear(); // Clear accumulated text before tools as we'll only return text after final tool
+        // Stop before spending more if the cumulative budget is spent. Checked
+        // only when the model wants to keep going - a completed interaction
+        // that lands over budget is still returned normally above.
+        if let Some(budget) = retry_config.max_total_tokens
+            && usage.total_tokens >= budget
+        {
+            let _ = events_tx.try_send(AgentEvent::TokenBudgetExceeded {
+                budget,
+                used: usage.total_tokens,
+            });
+            return Err(anyhow::anyhow!(
+                "Interaction aborted: token budget spent ({} tokens used, budget {}).",
+                usage.total_tokens,
+                budget
+            ));
+        }
 
         let tool_result = execute_tools(
             tool_service,
@@ -504,13 +982,18 @@ pub async fn run_interaction(
 
         if tool_result.cancelled {
             let _ = events_tx.try_send(AgentEvent::Cancelled);
+            // Keep the partial text and completed tool results: with the
+            // interaction ID the next prompt can pick up where this stopped.
             return Ok(InteractionResult {
                 id: last_id,
                 response: full_response,
                 context_size: current_context_size,
                 total_tokens,
+                usage,
                 tool_calls,
+                tool_stats: crate::tools::stats::diff(&stats_before, &tool_service.tool_stats()),
                 needs_confirmation: None,
+                cancelled: true,
             });
         }
 
@@ -520,12 +1003,60 @@ pub async fn run_interaction(
                 response: full_response,
                 context_size: current_context_size,
                 total_tokens,
+                usage,
                 tool_calls,
+                tool_stats: crate::tools::stats::diff(&stats_before, &tool_service.tool_stats()),
                 needs_confirmation: Some(confirmation),
+                cancelled: false,
             });
         }
 
-        next_turn_content = tool_result.results;
+        // Abort if the model keeps repeating the same failing call
+        let failures = failure_tracker.record(&tool_result.results);
+        if retry_config.max_consecutive_tool_failures > 0
+            && failures >= retry_config.max_consecutive_tool_failures
+            && let Some((tool, error)) = failure_tracker.last.clone()
+        {
+            let _ = events_tx.try_send(AgentEvent::RepeatedToolFailures {
+                tool: tool.clone(),
+                error: error.clone(),
+                count: failures,
+            });
+            return Err(anyhow::anyhow!(
+                "Interaction aborted: tool '{}' failed {} times consecutively \
+                 with the same error: {}",
+                tool,
+                failures,
+                error
+            ));
+        }
+
+        // Inject any steering messages queued while the tools ran
+        let steering_messages = steering.drain();
+        for message in &steering_messages {
+            let _ = events_tx.try_send(AgentEvent::UserSteering(message.clone()));
+        }
+
+        // Clear accumulated text only once this turn's tools are done: we
+        // return text from the final turn, but a cancellation mid-tool above
+        // still surfaces the partial narration.
+        full_response.clear();
+
+        next_turn = TurnContent::ToolResults {
+            results: tool_result.results,
+            steering: steering_messages,
+        };
+    }
+
+    if !completed {
+        let _ = events_tx.try_send(AgentEvent::MaxTurnsExceeded {
+            max_turns: retry_config.max_turns,
+        });
+        return Err(anyhow::anyhow!(
+            "Interaction aborted: reached max_turns limit ({}) without completing. \
+             The model may be stuck in a tool-call loop.",
+            retry_config.max_turns
+        ));
     }
 
     // Check context window and send warning if needed
@@ -546,11 +1077,226 @@ pub async fn run_interaction(
         response: full_response,
         context_size: current_context_size,
         total_tokens,
+        usage,
         tool_calls,
+        tool_stats: crate::tools::stats::diff(&stats_before, &tool_service.tool_stats()),
         needs_confirmation: None,
+        cancelled: false,
     })
 }
 
+/// The model backend an [`Agent`] talks to.
+enum AgentBackend {
+    /// The default: an owned Gemini client, wrapped in a `GeminiProvider`
+    /// per prompt (the provider borrows the client, so it can't be stored).
+    Gemini(Client),
+    /// Any other [`ModelProvider`] (OpenAI-compatible, test doubles, ...).
+    Provider(Box<dyn ModelProvider>),
+}
+
+/// High-level embedding API around [`run_interaction`].
+///
+/// Wraps the channel plumbing so library users don't have to spawn an mpsc
+/// drain task and call `dispatch_event` themselves: register an
+/// [`EventHandler`](crate::events::EventHandler) with [`with_handler`], or
+/// closures with [`on_event`], and they're invoked for every [`AgentEvent`]
+/// as a prompt runs. The interaction ID is tracked across prompts, so
+/// multi-turn conversations need no bookkeeping; call [`clear_history`] to
+/// start fresh.
+///
+/// [`with_handler`]: Agent::with_handler
+/// [`on_event`]: Agent::on_event
+/// [`clear_history`]: Agent::clear_history
+pub struct Agent {
+    backend: AgentBackend,
+    tool_service: Arc<CleminiToolService>,
+    model: String,
+    system_prompt: String,
+    retry_config: RetryConfig,
+    handler: Option<Box<dyn crate::events::EventHandler + Send>>,
+    callbacks: Vec<Box<dyn FnMut(&AgentEvent) + Send>>,
+    steering: SteeringQueue,
+    cancellation_token: CancellationToken,
+    last_interaction_id: Option<String>,
+    token_counter: Arc<dyn TokenCounter>,
+}
+
+impl Agent {
+    /// Create an agent backed by the Gemini API.
+    pub fn new(
+        client: Client,
+        tool_service: Arc<CleminiToolService>,
+        model: impl Into<String>,
+        system_prompt: impl Into<String>,
+    ) -> Self {
+        Self::with_backend(AgentBackend::Gemini(client), tool_service, model, system_prompt)
+    }
+
+    /// Create an agent backed by any [`ModelProvider`] (e.g., from
+    /// [`provider_from_config`](crate::provider::provider_from_config)).
+    pub fn from_provider(
+        provider: Box<dyn ModelProvider>,
+        tool_service: Arc<CleminiToolService>,
+        model: impl Into<String>,
+        system_prompt: impl Into<String>,
+    ) -> Self {
+        Self::with_backend(
+            AgentBackend::Provider(provider),
+            tool_service,
+            model,
+            system_prompt,
+        )
+    }
+
+    fn with_backend(
+        backend: AgentBackend,
+        tool_service: Arc<CleminiToolService>,
+        model: impl Into<String>,
+        system_prompt: impl Into<String>,
+    ) -> Self {
+        Self {
+            backend,
+            tool_service,
+            model: model.into(),
+            system_prompt: system_prompt.into(),
+            retry_config: RetryConfig::default(),
+            handler: None,
+            callbacks: Vec::new(),
+            steering: SteeringQueue::new(),
+            cancellation_token: CancellationToken::new(),
+            last_interaction_id: None,
+            token_counter: Arc::new(HeuristicTokenCounter),
+        }
+    }
+
+    /// Override retry and loop-limit behavior (defaults: [`RetryConfig::default`]).
+    pub fn with_retry_config(mut self, retry_config: RetryConfig) -> Self {
+        self.retry_config = retry_config;
+        self
+    }
+
+    /// Override the counter backing the fallback context-size estimate
+    /// (default: the ~4 chars/token heuristic). Pass a
+    /// [`crate::tokens::GeminiTokenCounter`] for real `countTokens` numbers
+    /// when the provider doesn't report usage.
+    pub fn with_token_counter(mut self, token_counter: Arc<dyn TokenCounter>) -> Self {
+        self.token_counter = token_counter;
+        self
+    }
+
+    /// Attach an [`EventHandler`](crate::events::EventHandler) dispatched for
+    /// every event. Replaces any previously attached handler.
+    pub fn with_handler(mut self, handler: Box<dyn crate::events::EventHandler + Send>) -> Self {
+        self.handler = Some(handler);
+        self
+    }
+
+    /// Register a closure invoked for every [`AgentEvent`]. May be called
+    /// multiple times; callbacks run in registration order, after the handler.
+    pub fn on_event(mut self, callback: impl FnMut(&AgentEvent) + Send + 'static) -> Self {
+        self.callbacks.push(Box::new(callback));
+        self
+    }
+
+    /// Handle for queueing mid-task corrections; see [`SteeringQueue`].
+    pub fn steering(&self) -> SteeringQueue {
+        self.steering.clone()
+    }
+
+    /// Token that cancels the in-flight prompt when triggered. After a
+    /// cancellation a fresh token is armed for the next prompt.
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation_token.clone()
+    }
+
+    /// ID of the most recent interaction, if any (conversation state lives
+    /// server-side; this is all the agent needs to continue it).
+    pub fn last_interaction_id(&self) -> Option<&str> {
+        self.last_interaction_id.as_deref()
+    }
+
+    /// Forget the conversation; the next prompt starts fresh.
+    pub fn clear_history(&mut self) {
+        self.last_interaction_id = None;
+    }
+
+    /// Run one prompt, continuing from the previous interaction if there was
+    /// one. Events are delivered to the attached handler and callbacks as the
+    /// interaction streams.
+    pub async fn prompt(&mut self, input: &str) -> Result<InteractionResult> {
+        // Take the subscribers out so the dispatch closure's mutable borrows
+        // don't conflict with the interaction's borrows of self's fields.
+        let mut handler = self.handler.take();
+        let mut callbacks = std::mem::take(&mut self.callbacks);
+        let mut dispatch = |event: &AgentEvent| {
+            if let Some(h) = handler.as_deref_mut() {
+                crate::events::dispatch_event(h, event);
+            }
+            for callback in callbacks.iter_mut() {
+                callback(event);
+            }
+        };
+
+        let (result, mut events_rx) = {
+            let (events_tx, mut events_rx) = mpsc::channel::<AgentEvent>(100);
+            let _events_guard = self.tool_service.with_events_tx(events_tx.clone());
+
+            let gemini_provider;
+            let provider: &dyn ModelProvider = match &self.backend {
+                AgentBackend::Gemini(client) => {
+                    gemini_provider = GeminiProvider::new(client);
+                    &gemini_provider
+                }
+                AgentBackend::Provider(provider) => provider.as_ref(),
+            };
+
+            let run = run_interaction_with_provider(
+                provider,
+                &self.tool_service,
+                input,
+                self.last_interaction_id.as_deref(),
+                &self.model,
+                &self.system_prompt,
+                events_tx,
+                self.cancellation_token.clone(),
+                self.retry_config,
+                self.steering.clone(),
+                self.token_counter.clone(),
+            );
+            tokio::pin!(run);
+
+            // Drive the interaction and dispatch events as they arrive. The
+            // run future holds a sender, so recv can't return None before it
+            // completes.
+            let result = loop {
+                tokio::select! {
+                    res = &mut run => break res,
+                    Some(event) = events_rx.recv() => dispatch(&event),
+                }
+            };
+            (result, events_rx)
+        };
+
+        // All senders are gone now; deliver anything still buffered
+        while let Ok(event) = events_rx.try_recv() {
+            dispatch(&event);
+        }
+
+        drop(dispatch);
+        self.handler = handler;
+        self.callbacks = callbacks;
+
+        let result = result?;
+        self.last_interaction_id = result.id.clone();
+        if result.cancelled {
+            // A cancelled token stays cancelled; arm a fresh one so the next
+            // prompt isn't stillborn
+            self.cancellation_token = CancellationToken::new();
+        }
+        Ok(result)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -947,6 +1693,101 @@ mod tests {
         let _ = rx.recv().await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_execute_tools_read_only_batch_preserves_order() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::write(temp.path().join("a.txt"), "alpha").unwrap();
+        std::fs::write(temp.path().join("b.txt"), "beta").unwrap();
+
+        let tool_service = Arc::new(CleminiToolService::new(
+            temp.path().to_path_buf(),
+            120,
+            false,
+            vec![temp.path().to_path_buf()],
+            "fake-key".to_string(),
+        ));
+        let (tx, mut rx) = mpsc::channel(10);
+        let token = CancellationToken::new();
+        let mut tool_calls = Vec::new();
+
+        // Two read calls - both read-only, so they execute concurrently
+        let calls = vec![
+            (
+                Some("call-1".to_string()),
+                "read_file".to_string(),
+                serde_json::json!({"file_path": temp.path().join("a.txt").to_str().unwrap()}),
+            ),
+            (
+                Some("call-2".to_string()),
+                "read_file".to_string(),
+                serde_json::json!({"file_path": temp.path().join("b.txt").to_str().unwrap()}),
+            ),
+        ];
+
+        let result = execute_tools(&tool_service, &calls, &mut tool_calls, &token, &tx).await;
+
+        assert!(!result.cancelled);
+        assert_eq!(result.results.len(), 2);
+        assert_eq!(tool_calls, vec!["read_file", "read_file"]);
+
+        // ToolResult events must arrive in original call order even though
+        // execution is concurrent
+        let _ = rx.recv().await.unwrap(); // ToolExecuting
+        match rx.recv().await.unwrap() {
+            AgentEvent::ToolResult(res) => assert_eq!(res.call_id, "call-1"),
+            _ => panic!("Expected ToolResult event"),
+        }
+        match rx.recv().await.unwrap() {
+            AgentEvent::ToolResult(res) => assert_eq!(res.call_id, "call-2"),
+            _ => panic!("Expected ToolResult event"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_tools_mixed_read_only_and_mutating() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::write(temp.path().join("a.txt"), "alpha").unwrap();
+
+        let tool_service = Arc::new(CleminiToolService::new(
+            temp.path().to_path_buf(),
+            120,
+            false,
+            vec![temp.path().to_path_buf()],
+            "fake-key".to_string(),
+        ));
+        let (tx, _rx) = mpsc::channel(10);
+        let token = CancellationToken::new();
+        let mut tool_calls = Vec::new();
+
+        // read_file (concurrent batch of one) then write_file (serial) then read_file again
+        let calls = vec![
+            (
+                Some("call-1".to_string()),
+                "read_file".to_string(),
+                serde_json::json!({"file_path": temp.path().join("a.txt").to_str().unwrap()}),
+            ),
+            (
+                Some("call-2".to_string()),
+                "write_file".to_string(),
+                serde_json::json!({
+                    "file_path": temp.path().join("b.txt").to_str().unwrap(),
+                    "content": "beta"
+                }),
+            ),
+            (
+                Some("call-3".to_string()),
+                "read_file".to_string(),
+                serde_json::json!({"file_path": temp.path().join("b.txt").to_str().unwrap()}),
+            ),
+        ];
+
+        let result = execute_tools(&tool_service, &calls, &mut tool_calls, &token, &tx).await;
+
+        assert!(!result.cancelled);
+        assert_eq!(result.results.len(), 3);
+        assert_eq!(tool_calls, vec!["read_file", "write_file", "read_file"]);
+    }
+
     #[tokio::test]
     async fn test_execute_tools_error() {
         let temp = tempfile::tempdir().unwrap();
@@ -1009,6 +1850,62 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("API Error"));
     }
 
+    #[test]
+    fn test_token_usage_add_accumulates() {
+        let mut usage = TokenUsage::default();
+        usage.add(&TokenUsage {
+            input_tokens: 100,
+            output_tokens: 50,
+            cached_tokens: 10,
+            total_tokens: 150,
+        });
+        usage.add(&TokenUsage {
+            input_tokens: 200,
+            output_tokens: 25,
+            cached_tokens: 0,
+            total_tokens: 225,
+        });
+
+        assert_eq!(usage.input_tokens, 300);
+        assert_eq!(usage.output_tokens, 75);
+        assert_eq!(usage.cached_tokens, 10);
+        assert_eq!(usage.total_tokens, 375);
+    }
+
+    #[test]
+    fn test_model_pricing_known_families() {
+        // Preview suffixes resolve via prefix matching
+        assert!(model_pricing("gemini-3-flash-preview").is_some());
+        assert!(model_pricing("gemini-2.5-flash").is_some());
+        assert!(model_pricing("gemini-2.5-pro").is_some());
+
+        // Lite variants are cheaper than their base models
+        let lite = model_pricing("gemini-2.5-flash-lite").unwrap();
+        let flash = model_pricing("gemini-2.5-flash").unwrap();
+        assert!(lite.input_per_mtok < flash.input_per_mtok);
+
+        // Unknown models return None rather than a guess
+        assert!(model_pricing("llama-3").is_none());
+        assert!(model_pricing("").is_none());
+    }
+
+    #[test]
+    fn test_token_usage_cost() {
+        let usage = TokenUsage {
+            input_tokens: 1_000_000,
+            output_tokens: 1_000_000,
+            cached_tokens: 0,
+            total_tokens: 2_000_000,
+        };
+
+        // Flash: $0.30 input + $2.50 output per MTok
+        let cost = usage.cost_usd("gemini-3-flash-preview").unwrap();
+        assert!((cost - 2.80).abs() < 1e-9);
+
+        // Unknown model has no cost
+        assert!(usage.cost_usd("unknown-model").is_none());
+    }
+
     #[test]
     fn test_needs_confirmation_detection() {
         use serde_json::json;
@@ -1035,4 +1932,497 @@ mod tests {
                 .unwrap_or(false)
         );
     }
+
+    /// Mock provider that replays scripted turns and records each request's
+    /// turn content, so the agent loop can be tested without an API.
+    struct ScriptedProvider {
+        turns: std::sync::Mutex<std::collections::VecDeque<Vec<genai_rs::StreamEvent>>>,
+        requests: std::sync::Mutex<Vec<TurnContent>>,
+    }
+
+    impl ScriptedProvider {
+        fn new(turns: Vec<Vec<genai_rs::StreamEvent>>) -> Self {
+            Self {
+                turns: std::sync::Mutex::new(turns.into()),
+                requests: std::sync::Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    impl crate::provider::ModelProvider for ScriptedProvider {
+        fn name(&self) -> &str {
+            "scripted"
+        }
+
+        fn create_stream<'a>(
+            &'a self,
+            request: crate::provider::ProviderRequest<'a>,
+        ) -> futures_util::stream::BoxStream<'a, Result<genai_rs::StreamEvent, genai_rs::GenaiError>>
+        {
+            self.requests.lock().unwrap().push(request.content);
+            let events = self.turns.lock().unwrap().pop_front().unwrap_or_default();
+            Box::pin(futures_util::stream::iter(events.into_iter().map(Ok)))
+        }
+    }
+
+    fn complete_event(id: &str) -> genai_rs::StreamEvent {
+        genai_rs::StreamEvent::new(
+            StreamChunk::Complete(InteractionResponse {
+                id: Some(id.to_string()),
+                model: None,
+                agent: None,
+                input: vec![],
+                outputs: vec![],
+                status: genai_rs::InteractionStatus::Completed,
+                usage: None,
+                tools: None,
+                grounding_metadata: None,
+                url_context_metadata: None,
+                previous_interaction_id: None,
+                created: None,
+                updated: None,
+            }),
+            None,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_steering_injected_at_turn_boundary() {
+        let temp = tempfile::tempdir().unwrap();
+        let tool_service = Arc::new(CleminiToolService::new(
+            temp.path().to_path_buf(),
+            120,
+            false,
+            vec![temp.path().to_path_buf()],
+            "fake-key".to_string(),
+        ));
+
+        // Turn 1: model issues a tool call; turn 2: model completes with text
+        let provider = ScriptedProvider::new(vec![
+            vec![
+                genai_rs::StreamEvent::new(
+                    StreamChunk::Delta(Content::FunctionCall {
+                        id: Some("call-1".to_string()),
+                        name: "todo_write".to_string(),
+                        args: serde_json::json!({"todos": []}),
+                    }),
+                    None,
+                ),
+                complete_event("id-1"),
+            ],
+            vec![
+                genai_rs::StreamEvent::new(StreamChunk::Delta(Content::text("done")), None),
+                complete_event("id-2"),
+            ],
+        ]);
+
+        let (events_tx, mut events_rx) = mpsc::channel(100);
+        let steering = SteeringQueue::new();
+        // Queued before the loop runs, so it's pending at the first turn boundary
+        steering.push("actually use the dev database");
+
+        let result = run_interaction_with_provider(
+            &provider,
+            &tool_service,
+            "set up todos",
+            None,
+            "test-model",
+            "test prompt",
+            events_tx,
+            CancellationToken::new(),
+            RetryConfig::default(),
+            steering,
+            Arc::new(HeuristicTokenCounter),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.id, Some("id-2".to_string()));
+        assert_eq!(result.response, "done");
+
+        // Second request carries the tool results plus the steering message
+        let requests = provider.requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        match &requests[1] {
+            TurnContent::ToolResults { results, steering } => {
+                assert_eq!(results.len(), 1);
+                assert_eq!(steering, &vec!["actually use the dev database".to_string()]);
+            }
+            other => panic!("Expected ToolResults with steering, got {:?}", other),
+        }
+
+        // A UserSteering event was emitted for the injected message
+        let mut saw_steering = false;
+        while let Ok(event) = events_rx.try_recv() {
+            if let AgentEvent::UserSteering(message) = event {
+                assert_eq!(message, "actually use the dev database");
+                saw_steering = true;
+            }
+        }
+        assert!(saw_steering, "Expected a UserSteering event");
+    }
+
+    #[tokio::test]
+    async fn test_approx_turn_tokens() {
+        let counter = HeuristicTokenCounter;
+        let text_turn = TurnContent::UserText("x".repeat(40));
+        assert_eq!(approx_turn_tokens(&text_turn, &counter).await, 10);
+
+        let results_turn = TurnContent::ToolResults {
+            results: vec![ToolResultContent {
+                name: "read_file".to_string(),
+                call_id: "call-1".to_string(),
+                result: serde_json::json!({"content": "x".repeat(40)}),
+            }],
+            steering: vec!["y".repeat(20)],
+        };
+        // Serialized result (~52 chars) plus steering (20 chars), both /4
+        assert!(approx_turn_tokens(&results_turn, &counter).await >= 15);
+    }
+
+    #[tokio::test]
+    async fn test_context_size_estimated_when_usage_missing() {
+        let temp = tempfile::tempdir().unwrap();
+        let tool_service = Arc::new(CleminiToolService::new(
+            temp.path().to_path_buf(),
+            120,
+            false,
+            vec![temp.path().to_path_buf()],
+            "fake-key".to_string(),
+        ));
+
+        // complete_event carries usage: None, like an OpenAI-compatible server
+        let provider = ScriptedProvider::new(vec![vec![
+            genai_rs::StreamEvent::new(StreamChunk::Delta(Content::text("a response")), None),
+            complete_event("id-1"),
+        ]]);
+
+        let (events_tx, _events_rx) = mpsc::channel(100);
+        let result = run_interaction_with_provider(
+            &provider,
+            &tool_service,
+            "a prompt that is long enough to register",
+            None,
+            "test-model",
+            "test system prompt",
+            events_tx,
+            CancellationToken::new(),
+            RetryConfig::default(),
+            SteeringQueue::new(),
+            Arc::new(HeuristicTokenCounter),
+        )
+        .await
+        .unwrap();
+
+        assert!(
+            result.context_size > 0,
+            "expected estimated context size, got 0"
+        );
+    }
+
+    #[test]
+    fn test_failure_tracker_counts_identical_failures() {
+        let mut tracker = FailureTracker::default();
+        let failure = ToolResultContent {
+            name: "edit".to_string(),
+            call_id: "call-1".to_string(),
+            result: serde_json::json!({"error": "old_string not found"}),
+        };
+
+        assert_eq!(tracker.record(std::slice::from_ref(&failure)), 1);
+        assert_eq!(tracker.record(std::slice::from_ref(&failure)), 2);
+        assert_eq!(tracker.record(std::slice::from_ref(&failure)), 3);
+    }
+
+    #[test]
+    fn test_failure_tracker_resets_on_success_or_different_error() {
+        let mut tracker = FailureTracker::default();
+        let failure = ToolResultContent {
+            name: "edit".to_string(),
+            call_id: "call-1".to_string(),
+            result: serde_json::json!({"error": "old_string not found"}),
+        };
+        let success = ToolResultContent {
+            name: "read_file".to_string(),
+            call_id: "call-2".to_string(),
+            result: serde_json::json!({"content": "ok"}),
+        };
+        let other_failure = ToolResultContent {
+            name: "edit".to_string(),
+            call_id: "call-3".to_string(),
+            result: serde_json::json!({"error": "file not found"}),
+        };
+
+        tracker.record(std::slice::from_ref(&failure));
+        tracker.record(std::slice::from_ref(&failure));
+        assert_eq!(tracker.record(std::slice::from_ref(&success)), 0);
+
+        tracker.record(std::slice::from_ref(&failure));
+        // Different error message starts a new streak
+        assert_eq!(tracker.record(std::slice::from_ref(&other_failure)), 1);
+    }
+
+    #[tokio::test]
+    async fn test_repeated_tool_failures_abort_interaction() {
+        let temp = tempfile::tempdir().unwrap();
+        let tool_service = Arc::new(CleminiToolService::new(
+            temp.path().to_path_buf(),
+            120,
+            false,
+            vec![temp.path().to_path_buf()],
+            "fake-key".to_string(),
+        ));
+
+        // Every turn issues the same failing read of a nonexistent file
+        let failing_turn = |n: usize| {
+            vec![
+                genai_rs::StreamEvent::new(
+                    StreamChunk::Delta(Content::FunctionCall {
+                        id: Some(format!("call-{n}")),
+                        name: "read_file".to_string(),
+                        args: serde_json::json!({"file_path": "missing.txt"}),
+                    }),
+                    None,
+                ),
+                complete_event(&format!("id-{n}")),
+            ]
+        };
+        let provider = ScriptedProvider::new((0..5).map(failing_turn).collect());
+
+        let (events_tx, mut events_rx) = mpsc::channel(100);
+        let result = run_interaction_with_provider(
+            &provider,
+            &tool_service,
+            "read that file",
+            None,
+            "test-model",
+            "test prompt",
+            events_tx,
+            CancellationToken::new(),
+            RetryConfig {
+                max_consecutive_tool_failures: 3,
+                ..RetryConfig::default()
+            },
+            SteeringQueue::new(),
+            Arc::new(HeuristicTokenCounter),
+        )
+        .await;
+
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("failed 3 times consecutively"), "got: {err}");
+        assert!(err.contains("read_file"), "got: {err}");
+
+        let mut saw_abort = false;
+        while let Ok(event) = events_rx.try_recv() {
+            if let AgentEvent::RepeatedToolFailures { tool, count, .. } = event {
+                assert_eq!(tool, "read_file");
+                assert_eq!(count, 3);
+                saw_abort = true;
+            }
+        }
+        assert!(saw_abort, "Expected a RepeatedToolFailures event");
+    }
+
+    #[tokio::test]
+    async fn test_cancellation_mid_tool_returns_partial_result() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::write(temp.path().join("notes.txt"), "some notes").unwrap();
+        let tool_service = Arc::new(CleminiToolService::new(
+            temp.path().to_path_buf(),
+            120,
+            false,
+            vec![temp.path().to_path_buf()],
+            "fake-key".to_string(),
+        ));
+
+        // One turn: partial narration, a fast read, then a long-running bash
+        // command that the cancellation will interrupt.
+        let provider = ScriptedProvider::new(vec![vec![
+            genai_rs::StreamEvent::new(StreamChunk::Delta(Content::text("working on it")), None),
+            genai_rs::StreamEvent::new(
+                StreamChunk::Delta(Content::FunctionCall {
+                    id: Some("call-1".to_string()),
+                    name: "read_file".to_string(),
+                    args: serde_json::json!({"file_path": "notes.txt"}),
+                }),
+                None,
+            ),
+            genai_rs::StreamEvent::new(
+                StreamChunk::Delta(Content::FunctionCall {
+                    id: Some("call-2".to_string()),
+                    name: "bash".to_string(),
+                    args: serde_json::json!({"command": "sleep 10"}),
+                }),
+                None,
+            ),
+            complete_event("id-1"),
+        ]]);
+
+        let cancellation_token = CancellationToken::new();
+        let cancel = cancellation_token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            cancel.cancel();
+        });
+
+        let (events_tx, _events_rx) = mpsc::channel(100);
+        let result = run_interaction_with_provider(
+            &provider,
+            &tool_service,
+            "do the thing",
+            None,
+            "test-model",
+            "test prompt",
+            events_tx,
+            cancellation_token,
+            RetryConfig::default(),
+            SteeringQueue::new(),
+            Arc::new(HeuristicTokenCounter),
+        )
+        .await
+        .unwrap();
+
+        // Partial work survives: the narration, the completed read, and the
+        // interaction ID the next prompt needs to resume.
+        assert!(result.cancelled);
+        assert_eq!(result.id, Some("id-1".to_string()));
+        assert_eq!(result.response, "working on it");
+        assert!(result.tool_calls.contains(&"read_file".to_string()));
+    }
+
+    /// Delegates to a shared `ScriptedProvider` so tests can keep a handle to
+    /// the recorded requests after boxing the provider into an `Agent`.
+    struct SharedProvider(Arc<ScriptedProvider>);
+
+    impl crate::provider::ModelProvider for SharedProvider {
+        fn name(&self) -> &str {
+            self.0.name()
+        }
+
+        fn create_stream<'a>(
+            &'a self,
+            request: crate::provider::ProviderRequest<'a>,
+        ) -> futures_util::stream::BoxStream<'a, Result<genai_rs::StreamEvent, genai_rs::GenaiError>>
+        {
+            self.0.create_stream(request)
+        }
+    }
+
+    fn test_agent(provider: ScriptedProvider) -> Agent {
+        let temp = tempfile::tempdir().unwrap();
+        let tool_service = Arc::new(CleminiToolService::new(
+            temp.path().to_path_buf(),
+            120,
+            false,
+            vec![temp.path().to_path_buf()],
+            "fake-key".to_string(),
+        ));
+        Agent::from_provider(
+            Box::new(SharedProvider(Arc::new(provider))),
+            tool_service,
+            "test-model",
+            "test prompt",
+        )
+    }
+
+    #[tokio::test]
+    async fn test_agent_on_event_delivers_events() {
+        let provider = ScriptedProvider::new(vec![vec![
+            genai_rs::StreamEvent::new(StreamChunk::Delta(Content::text("done")), None),
+            complete_event("id-1"),
+        ]]);
+
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen_by_callback = seen.clone();
+        let mut agent = test_agent(provider).on_event(move |event| {
+            let label = match event {
+                AgentEvent::TextDelta(text) => format!("text:{text}"),
+                AgentEvent::Complete { interaction_id, .. } => {
+                    format!("complete:{}", interaction_id.as_deref().unwrap_or(""))
+                }
+                other => format!("{other:?}"),
+            };
+            seen_by_callback.lock().unwrap().push(label);
+        });
+
+        let result = agent.prompt("say done").await.unwrap();
+        assert_eq!(result.response, "done");
+        assert_eq!(agent.last_interaction_id(), Some("id-1"));
+
+        let seen = seen.lock().unwrap();
+        assert!(seen.contains(&"text:done".to_string()), "got: {seen:?}");
+        assert!(seen.contains(&"complete:id-1".to_string()), "got: {seen:?}");
+    }
+
+    #[tokio::test]
+    async fn test_agent_tracks_interaction_id_across_prompts() {
+        let provider = ScriptedProvider::new(vec![
+            vec![
+                genai_rs::StreamEvent::new(StreamChunk::Delta(Content::text("one")), None),
+                complete_event("id-1"),
+            ],
+            vec![
+                genai_rs::StreamEvent::new(StreamChunk::Delta(Content::text("two")), None),
+                complete_event("id-2"),
+            ],
+        ]);
+
+        let mut agent = test_agent(provider);
+        agent.prompt("first").await.unwrap();
+        assert_eq!(agent.last_interaction_id(), Some("id-1"));
+
+        agent.prompt("second").await.unwrap();
+        assert_eq!(agent.last_interaction_id(), Some("id-2"));
+
+        agent.clear_history();
+        assert_eq!(agent.last_interaction_id(), None);
+    }
+
+    #[tokio::test]
+    async fn test_agent_dispatches_to_attached_handler() {
+        crate::logging::disable_logging();
+
+        struct CollectingHandler(Arc<std::sync::Mutex<Vec<String>>>);
+        impl crate::events::EventHandler for CollectingHandler {
+            fn on_text_delta(&mut self, text: &str) {
+                self.0.lock().unwrap().push(format!("text:{text}"));
+            }
+            fn on_tool_executing(&mut self, call: &OwnedFunctionCallInfo) {
+                self.0.lock().unwrap().push(format!("executing:{}", call.name));
+            }
+            fn on_tool_result(&mut self, result: &FunctionExecutionResult) {
+                self.0.lock().unwrap().push(format!("result:{}", result.name));
+            }
+            fn on_context_warning(&mut self, _warning: &ContextWarning) {}
+        }
+
+        let provider = ScriptedProvider::new(vec![vec![
+            genai_rs::StreamEvent::new(StreamChunk::Delta(Content::text("hi")), None),
+            complete_event("id-1"),
+        ]]);
+
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut agent =
+            test_agent(provider).with_handler(Box::new(CollectingHandler(seen.clone())));
+
+        agent.prompt("greet").await.unwrap();
+
+        let seen = seen.lock().unwrap();
+        assert!(seen.contains(&"text:hi".to_string()), "got: {seen:?}");
+    }
+
+    #[test]
+    fn test_thought_text_plain_text_is_not_thought() {
+        assert!(thought_text(&Content::text("regular response")).is_none());
+    }
+
+    #[test]
+    fn test_steering_queue_drain_empties() {
+        let queue = SteeringQueue::new();
+        queue.push("first");
+        queue.push("second");
+
+        assert_eq!(queue.drain(), vec!["first", "second"]);
+        assert!(queue.drain().is_empty());
+    }
 }
@@ -0,0 +1,227 @@
+//! Append-only audit journal of mutating tool calls.
+//!
+//! Every mutating tool call executed through `CleminiToolService` (write,
+//! edit, bash, delete, ...) is appended to `~/.clemini/audit.jsonl` as one
+//! JSON object per line, regardless of the logging sink - the record exists
+//! even when logging is redirected or disabled. Intended for work
+//! repositories with compliance requirements: reconstructing what a session
+//! changed is a `jq` query over the journal, not log archaeology.
+
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::tools::memory::fnv1a_hex;
+
+/// One audit record: a single mutating tool call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// RFC 3339 timestamp of when the call completed.
+    pub timestamp: String,
+    /// Identifies the process that made the call (stable for the session).
+    pub session_id: String,
+    /// Declared tool name (`write_file`, `bash`, ...).
+    pub tool: String,
+    /// FNV-1a hash of the serialized argument JSON. The raw args may hold
+    /// secrets or whole file bodies; the hash still proves what was run
+    /// without copying sensitive content into the journal.
+    pub args_hash: String,
+    /// Human-oriented one-liner: the bash command, the touched path with a
+    /// diff line count - whatever the call makes available.
+    pub summary: String,
+    /// Whether the call returned an error result.
+    pub is_error: bool,
+}
+
+/// Stable per-process session ID shared by every entry this run.
+pub fn session_id() -> &'static str {
+    use std::sync::OnceLock;
+    static ID: OnceLock<String> = OnceLock::new();
+    ID.get_or_init(|| {
+        format!(
+            "{}-{}",
+            chrono::Local::now().format("%Y%m%d-%H%M%S"),
+            std::process::id()
+        )
+    })
+}
+
+/// Path of the audit journal.
+pub fn audit_file_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".clemini")
+        .join("audit.jsonl")
+}
+
+/// Summarize a mutating call in one line: the bash command, or the touched
+/// path annotated with the result's diff size when one was reported.
+fn summarize(args: &serde_json::Value, result: Option<&serde_json::Value>) -> String {
+    if let Some(command) = args.get("command").and_then(|v| v.as_str()) {
+        return one_line(command, 200);
+    }
+
+    let path = args
+        .get("file_path")
+        .or_else(|| args.get("path"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+
+    if let Some(diff) = result.and_then(|r| r.get("diff")).and_then(|v| v.as_str()) {
+        let added = diff.lines().filter(|l| l.starts_with('+')).count();
+        let removed = diff.lines().filter(|l| l.starts_with('-')).count();
+        return format!("{path} (+{added}/-{removed})").trim().to_string();
+    }
+
+    path.to_string()
+}
+
+/// First line of `s`, truncated to `max` characters on a char boundary.
+fn one_line(s: &str, max: usize) -> String {
+    let line = s.lines().next().unwrap_or("");
+    if line.chars().count() > max {
+        let truncated: String = line.chars().take(max).collect();
+        format!("{truncated}...")
+    } else {
+        line.to_string()
+    }
+}
+
+/// Test-only capture of journaled tool names. Lets unit tests assert which
+/// tools reached the journal (and which didn't) without appending test
+/// entries to the real `~/.clemini/audit.jsonl`.
+#[cfg(test)]
+static RECORDED_TOOLS: std::sync::Mutex<Vec<String>> = std::sync::Mutex::new(Vec::new());
+
+/// Tool names recorded so far in this test process.
+#[cfg(test)]
+pub(crate) fn recorded_tools() -> Vec<String> {
+    match RECORDED_TOOLS.lock() {
+        Ok(guard) => guard.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    }
+}
+
+/// Record a completed mutating tool call in the journal.
+/// Failures are logged, never fatal - an unwritable journal shouldn't break
+/// tool execution.
+pub fn record(
+    tool: &str,
+    args: &serde_json::Value,
+    result: Option<&serde_json::Value>,
+    is_error: bool,
+) {
+    let entry = AuditEntry {
+        timestamp: chrono::Local::now().to_rfc3339(),
+        session_id: session_id().to_string(),
+        tool: tool.to_string(),
+        args_hash: fnv1a_hex(&args.to_string()),
+        summary: summarize(args, result),
+        is_error,
+    };
+    #[cfg(test)]
+    match RECORDED_TOOLS.lock() {
+        Ok(mut guard) => guard.push(entry.tool),
+        Err(poisoned) => poisoned.into_inner().push(entry.tool),
+    }
+    #[cfg(not(test))]
+    append_to(&audit_file_path(), &entry);
+}
+
+/// Append one entry to the journal at `path`, creating it (and its parent
+/// directory) on first use.
+fn append_to(path: &Path, entry: &AuditEntry) {
+    let line = match serde_json::to_string(entry) {
+        Ok(line) => line,
+        Err(e) => {
+            tracing::warn!("Failed to serialize audit entry: {e}");
+            return;
+        }
+    };
+    if let Some(parent) = path.parent()
+        && let Err(e) = std::fs::create_dir_all(parent)
+    {
+        tracing::warn!("Failed to create audit log directory: {e}");
+        return;
+    }
+    let result = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| writeln!(file, "{line}"));
+    if let Err(e) = result {
+        tracing::warn!("Failed to append audit entry to {}: {e}", path.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_session_id_is_stable() {
+        assert_eq!(session_id(), session_id());
+        assert!(session_id().contains('-'));
+    }
+
+    #[test]
+    fn test_summarize_bash_command() {
+        let args = json!({"command": "rm -rf target"});
+        assert_eq!(summarize(&args, None), "rm -rf target");
+
+        // Multi-line commands collapse to their first line.
+        let args = json!({"command": "echo one\necho two"});
+        assert_eq!(summarize(&args, None), "echo one");
+    }
+
+    #[test]
+    fn test_summarize_truncates_long_commands() {
+        let args = json!({"command": "x".repeat(500)});
+        let summary = summarize(&args, None);
+        assert!(summary.ends_with("..."));
+        assert!(summary.chars().count() <= 203);
+    }
+
+    #[test]
+    fn test_summarize_path_with_diff() {
+        let args = json!({"file_path": "src/main.rs"});
+        let result = json!({"diff": "--- a\n+++ b\n+new line\n-old line\n-gone"});
+        // Header lines count too; the summary is a rough size, not a parse.
+        assert_eq!(summarize(&args, Some(&result)), "src/main.rs (+2/-3)");
+    }
+
+    #[test]
+    fn test_summarize_path_only() {
+        let args = json!({"path": "notes.txt"});
+        assert_eq!(
+            summarize(&args, Some(&json!({"success": true}))),
+            "notes.txt"
+        );
+    }
+
+    #[test]
+    fn test_append_to_creates_and_appends() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("audit.jsonl");
+
+        let entry = AuditEntry {
+            timestamp: "2026-01-01T00:00:00Z".to_string(),
+            session_id: "test-session".to_string(),
+            tool: "bash".to_string(),
+            args_hash: "abc123".to_string(),
+            summary: "make build".to_string(),
+            is_error: false,
+        };
+        append_to(&path, &entry);
+        append_to(&path, &entry);
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let parsed: AuditEntry = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(parsed.tool, "bash");
+        assert_eq!(parsed.summary, "make build");
+        assert!(!parsed.is_error);
+    }
+}
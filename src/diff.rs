@@ -1,28 +1,16 @@
-//! Diff formatting utilities for visualizing text changes.
+//! Diff formatting utilities for visualizing text changes, plus parsing and
+//! applying unified diffs (used by the `apply_patch` tool).
 
 use colored::Colorize;
 use similar::{ChangeTag, TextDiff};
 use std::sync::LazyLock;
 use syntect::easy::HighlightLines;
-use syntect::highlighting::{Style, Theme, ThemeSet};
+use syntect::highlighting::Style;
 use syntect::parsing::SyntaxSet;
 
 /// Syntax set for language detection and parsing.
 static SYNTAX_SET: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
 
-/// Catppuccin Mocha theme (bundled from catppuccin/bat).
-static CATPPUCCIN_MOCHA: LazyLock<Theme> = LazyLock::new(|| {
-    let theme_bytes = include_bytes!("../themes/catppuccin-mocha.tmTheme");
-    let mut cursor = std::io::Cursor::new(theme_bytes);
-    ThemeSet::load_from_reader(&mut cursor).expect("bundled theme should be valid")
-});
-
-/// Background colors for diff lines.
-/// Slightly brighter backgrounds to distinguish from terminal background while
-/// keeping syntax colors readable.
-const DELETE_BG: (u8, u8, u8) = (80, 40, 40); // Dark red
-const INSERT_BG: (u8, u8, u8) = (40, 80, 40); // Dark green
-
 /// Apply syntax highlighting to a line with a background color.
 /// Each token gets its foreground color from syntect and the specified background.
 fn highlight_line_with_bg(
@@ -57,6 +45,30 @@ fn apply_style(text: &str, style: Style, bg: Option<(u8, u8, u8)>) -> String {
     result
 }
 
+/// Highlighter for a fenced code block's language tag, if syntect recognizes
+/// it (by name like `rust` or by extension like `rs`). Used by
+/// `format::highlight_code_blocks` for streamed chat output.
+pub(crate) fn code_block_highlighter(lang: &str) -> Option<HighlightLines<'static>> {
+    let syntax = SYNTAX_SET
+        .find_syntax_by_token(lang)
+        .or_else(|| SYNTAX_SET.find_syntax_by_extension(lang))?;
+    Some(HighlightLines::new(syntax, crate::theme::syntax_theme()))
+}
+
+/// Syntax-highlight a single code line with no background color. Falls back
+/// to the line unchanged if highlighting fails.
+pub(crate) fn highlight_code_line(line: &str, highlighter: &mut HighlightLines) -> String {
+    let ranges = match highlighter.highlight_line(line, &SYNTAX_SET) {
+        Ok(ranges) => ranges,
+        Err(_) => return line.to_string(),
+    };
+    let mut output = String::new();
+    for (style, text) in ranges {
+        output.push_str(&apply_style(text, style, None));
+    }
+    output
+}
+
 /// Fallback coloring when syntax highlighting fails.
 fn fallback_color(line: &str, bg: Option<(u8, u8, u8)>) -> String {
     match bg {
@@ -105,7 +117,7 @@ pub fn format_diff(old: &str, new: &str, context_lines: usize, file_path: Option
             .extension()
             .and_then(|ext| ext.to_str())?;
         let syntax = SYNTAX_SET.find_syntax_by_extension(extension)?;
-        Some(HighlightLines::new(syntax, &CATPPUCCIN_MOCHA))
+        Some(HighlightLines::new(syntax, crate::theme::syntax_theme()))
     });
 
     let old_lines: Vec<&str> = old.lines().collect();
@@ -126,12 +138,13 @@ pub fn format_diff(old: &str, new: &str, context_lines: usize, file_path: Option
 ///   + new content
 /// ```
 fn format_simple_diff(old: &str, new: &str, mut highlighter: Option<HighlightLines>) -> String {
+    let theme = crate::theme::active();
     let indent = common_indent(old, new);
     let mut output = String::new();
 
     for line in old.lines() {
         let stripped = strip_indent(line, indent);
-        let content = format_line_content(stripped, &mut highlighter, Some(DELETE_BG));
+        let content = format_line_content(stripped, &mut highlighter, Some(theme.diff_delete_bg));
         output.push_str(&format!("  {} {}\n", "-".red(), content));
     }
     // Handle empty old string (pure addition)
@@ -140,13 +153,13 @@ fn format_simple_diff(old: &str, new: &str, mut highlighter: Option<HighlightLin
     } else if old.lines().count() == 0 && !old.is_empty() {
         // Single line without newline
         let stripped = strip_indent(old, indent);
-        let content = format_line_content(stripped, &mut highlighter, Some(DELETE_BG));
+        let content = format_line_content(stripped, &mut highlighter, Some(theme.diff_delete_bg));
         output.push_str(&format!("  {} {}\n", "-".red(), content));
     }
 
     for line in new.lines() {
         let stripped = strip_indent(line, indent);
-        let content = format_line_content(stripped, &mut highlighter, Some(INSERT_BG));
+        let content = format_line_content(stripped, &mut highlighter, Some(theme.diff_insert_bg));
         output.push_str(&format!("  {} {}\n", "+".green(), content));
     }
     // Handle empty new string (pure deletion)
@@ -155,7 +168,7 @@ fn format_simple_diff(old: &str, new: &str, mut highlighter: Option<HighlightLin
     } else if new.lines().count() == 0 && !new.is_empty() {
         // Single line without newline
         let stripped = strip_indent(new, indent);
-        let content = format_line_content(stripped, &mut highlighter, Some(INSERT_BG));
+        let content = format_line_content(stripped, &mut highlighter, Some(theme.diff_insert_bg));
         output.push_str(&format!("  {} {}\n", "+".green(), content));
     }
 
@@ -187,6 +200,7 @@ fn format_unified_diff(
     context_lines: usize,
     mut highlighter: Option<HighlightLines>,
 ) -> String {
+    let theme = crate::theme::active();
     let indent = common_indent(old, new);
     let diff = TextDiff::from_lines(old, new);
     let mut output = String::new();
@@ -201,11 +215,13 @@ fn format_unified_diff(
             let stripped = strip_indent(line, indent);
             match change.tag() {
                 ChangeTag::Delete => {
-                    let content = format_line_content(stripped, &mut highlighter, Some(DELETE_BG));
+                    let content =
+                        format_line_content(stripped, &mut highlighter, Some(theme.diff_delete_bg));
                     output.push_str(&format!("  {} {}\n", "-".red(), content));
                 }
                 ChangeTag::Insert => {
-                    let content = format_line_content(stripped, &mut highlighter, Some(INSERT_BG));
+                    let content =
+                        format_line_content(stripped, &mut highlighter, Some(theme.diff_insert_bg));
                     output.push_str(&format!("  {} {}\n", "+".green(), content));
                 }
                 ChangeTag::Equal => {
@@ -219,6 +235,304 @@ fn format_unified_diff(
     output.trim_end().to_string()
 }
 
+/// Produce a plain-text unified diff (no ANSI colors) between two strings.
+/// Suitable for embedding in tool JSON results; `format_diff` remains the
+/// colored variant for terminal output.
+///
+/// If `file_path` is provided, standard `--- a/path` / `+++ b/path` headers
+/// are included so the output can be fed back to `apply_patch` or `git apply`.
+pub fn unified_diff(old: &str, new: &str, context_lines: usize, file_path: Option<&str>) -> String {
+    if old == new {
+        return String::new();
+    }
+
+    let diff = TextDiff::from_lines(old, new);
+    let mut udiff = diff.unified_diff();
+    udiff.context_radius(context_lines);
+    if let Some(path) = file_path {
+        udiff.header(&format!("a/{path}"), &format!("b/{path}"));
+    }
+    udiff.to_string()
+}
+
+// ============================================================================
+// Unified diff parsing and application
+// ============================================================================
+
+/// One file entry of a parsed unified diff.
+#[derive(Debug)]
+pub struct PatchedFile {
+    /// Source path (`None` for `/dev/null`, i.e. file creation).
+    pub old_path: Option<String>,
+    /// Destination path (`None` for `/dev/null`, i.e. file deletion).
+    pub new_path: Option<String>,
+    pub hunks: Vec<Hunk>,
+}
+
+/// One `@@` hunk of a unified diff.
+#[derive(Debug)]
+pub struct Hunk {
+    /// 1-based line number from the `@@ -start,count ...` header. A hint, not
+    /// a requirement: application searches nearby when it doesn't match.
+    pub old_start: usize,
+    pub lines: Vec<HunkLine>,
+}
+
+#[derive(Debug)]
+pub enum HunkLine {
+    Context(String),
+    Remove(String),
+    Add(String),
+}
+
+/// Where and how one hunk was applied.
+#[derive(Debug)]
+pub struct HunkApplication {
+    /// 1-based line in the original content where the hunk matched.
+    pub line: usize,
+    /// True if the match needed whitespace-tolerant comparison.
+    pub fuzzy: bool,
+}
+
+/// Strip the `a/`/`b/` prefix and any trailing timestamp from a diff header
+/// path; `/dev/null` becomes `None`.
+fn parse_header_path(raw: &str) -> Option<String> {
+    let path = raw.split('\t').next().unwrap_or(raw).trim();
+    if path == "/dev/null" {
+        return None;
+    }
+    let path = path
+        .strip_prefix("a/")
+        .or_else(|| path.strip_prefix("b/"))
+        .unwrap_or(path);
+    Some(path.to_string())
+}
+
+/// Parse `@@ -old_start,old_count +new_start,new_count @@`, with counts
+/// defaulting to 1 when omitted.
+fn parse_hunk_header(header: &str) -> Result<(usize, usize, usize), String> {
+    let inner = header
+        .strip_prefix("@@ ")
+        .and_then(|rest| rest.split(" @@").next())
+        .ok_or_else(|| format!("Malformed hunk header: {}", header))?;
+
+    let mut parts = inner.split_whitespace();
+    let old = parts
+        .next()
+        .and_then(|p| p.strip_prefix('-'))
+        .ok_or_else(|| format!("Malformed hunk header: {}", header))?;
+    let new = parts
+        .next()
+        .and_then(|p| p.strip_prefix('+'))
+        .ok_or_else(|| format!("Malformed hunk header: {}", header))?;
+
+    let parse_range = |range: &str| -> Result<(usize, usize), String> {
+        let mut nums = range.splitn(2, ',');
+        let start = nums
+            .next()
+            .and_then(|n| n.parse().ok())
+            .ok_or_else(|| format!("Malformed hunk header: {}", header))?;
+        let count = match nums.next() {
+            Some(n) => n
+                .parse()
+                .map_err(|_| format!("Malformed hunk header: {}", header))?,
+            None => 1,
+        };
+        Ok((start, count))
+    };
+
+    let (old_start, old_count) = parse_range(old)?;
+    let (_, new_count) = parse_range(new)?;
+    Ok((old_start, old_count, new_count))
+}
+
+/// Parse a unified diff, possibly spanning multiple files. Tolerates
+/// `diff --git`, `index`, and mode lines between file entries; hunk content
+/// is consumed by the line counts in the `@@` header, so content lines that
+/// themselves start with `---` don't confuse the parser.
+pub fn parse_unified_patch(patch: &str) -> Result<Vec<PatchedFile>, String> {
+    let mut files: Vec<PatchedFile> = Vec::new();
+    let mut lines = patch.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        let Some(old_raw) = line.strip_prefix("--- ") else {
+            // Git decoration lines (diff --git, index, modes) and prose
+            // between files are ignored
+            continue;
+        };
+        let new_raw = lines
+            .next()
+            .and_then(|l| l.strip_prefix("+++ "))
+            .ok_or_else(|| format!("Expected '+++' line after '--- {}'", old_raw))?;
+
+        let mut file = PatchedFile {
+            old_path: parse_header_path(old_raw),
+            new_path: parse_header_path(new_raw),
+            hunks: Vec::new(),
+        };
+
+        while let Some(header) = lines.peek().filter(|l| l.starts_with("@@")) {
+            let (old_start, old_count, new_count) = parse_hunk_header(header)?;
+            lines.next();
+
+            let mut hunk = Hunk {
+                old_start,
+                lines: Vec::new(),
+            };
+            let (mut old_seen, mut new_seen) = (0usize, 0usize);
+            while old_seen < old_count || new_seen < new_count {
+                let content = lines.next().ok_or_else(|| {
+                    "Patch ended mid-hunk (line counts don't match the @@ header)".to_string()
+                })?;
+                match content.chars().next() {
+                    Some(' ') => {
+                        hunk.lines.push(HunkLine::Context(content[1..].to_string()));
+                        old_seen += 1;
+                        new_seen += 1;
+                    }
+                    Some('-') => {
+                        hunk.lines.push(HunkLine::Remove(content[1..].to_string()));
+                        old_seen += 1;
+                    }
+                    Some('+') => {
+                        hunk.lines.push(HunkLine::Add(content[1..].to_string()));
+                        new_seen += 1;
+                    }
+                    // "\ No newline at end of file"
+                    Some('\\') => {}
+                    // Some producers emit completely empty context lines
+                    None => {
+                        hunk.lines.push(HunkLine::Context(String::new()));
+                        old_seen += 1;
+                        new_seen += 1;
+                    }
+                    _ => {
+                        return Err(format!(
+                            "Unexpected line in hunk (expected ' ', '-', or '+' prefix): {}",
+                            content
+                        ));
+                    }
+                }
+            }
+            file.hunks.push(hunk);
+        }
+
+        if file.hunks.is_empty() {
+            return Err(format!(
+                "No hunks found for {}",
+                file.new_path
+                    .as_deref()
+                    .or(file.old_path.as_deref())
+                    .unwrap_or("/dev/null")
+            ));
+        }
+        files.push(file);
+    }
+
+    if files.is_empty() {
+        return Err("No file headers ('--- a/...' / '+++ b/...') found in patch".to_string());
+    }
+    Ok(files)
+}
+
+/// Whether `block` matches `lines` at `pos`, either exactly or (when `fuzzy`)
+/// ignoring trailing whitespace and indentation differences.
+fn block_matches(lines: &[String], pos: usize, block: &[&str], fuzzy: bool) -> bool {
+    if pos + block.len() > lines.len() {
+        return false;
+    }
+    block.iter().enumerate().all(|(i, expected)| {
+        let actual = &lines[pos + i];
+        if fuzzy {
+            actual.trim() == expected.trim()
+        } else {
+            actual == expected
+        }
+    })
+}
+
+/// Find where `block` matches, preferring positions closest to `hint`
+/// (0-based). Exact matches win over fuzzy ones regardless of distance.
+fn find_block(lines: &[String], hint: usize, block: &[&str]) -> Option<(usize, bool)> {
+    for fuzzy in [false, true] {
+        let mut best: Option<usize> = None;
+        for pos in 0..=lines.len().saturating_sub(block.len()) {
+            if block_matches(lines, pos, block, fuzzy)
+                && best.is_none_or(|b| pos.abs_diff(hint) < b.abs_diff(hint))
+            {
+                best = Some(pos);
+            }
+        }
+        if let Some(pos) = best {
+            return Some((pos, fuzzy));
+        }
+    }
+    None
+}
+
+/// Apply `hunks` to `content`, returning the new content and where each hunk
+/// landed. Hunk positions from the `@@` headers are treated as hints: each
+/// hunk's context+removal block is located exactly first, then with
+/// whitespace-tolerant matching, preferring the position closest to the hint.
+/// Fails (without partial results) if any hunk's block can't be found.
+pub fn apply_hunks(
+    content: &str,
+    hunks: &[Hunk],
+) -> Result<(String, Vec<HunkApplication>), String> {
+    let had_trailing_newline = content.ends_with('\n') || content.is_empty();
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let mut applications = Vec::new();
+    // Tracks how much earlier hunks have shifted later line numbers
+    let mut offset: isize = 0;
+
+    for (index, hunk) in hunks.iter().enumerate() {
+        let old_block: Vec<&str> = hunk
+            .lines
+            .iter()
+            .filter_map(|l| match l {
+                HunkLine::Context(s) | HunkLine::Remove(s) => Some(s.as_str()),
+                HunkLine::Add(_) => None,
+            })
+            .collect();
+        let new_block: Vec<String> = hunk
+            .lines
+            .iter()
+            .filter_map(|l| match l {
+                HunkLine::Context(s) | HunkLine::Add(s) => Some(s.clone()),
+                HunkLine::Remove(_) => None,
+            })
+            .collect();
+
+        let hint = (hunk.old_start as isize - 1 + offset).max(0) as usize;
+
+        let (pos, fuzzy) = if old_block.is_empty() {
+            // Pure addition with no context: insert at the hinted position
+            (hint.min(lines.len()), false)
+        } else {
+            find_block(&lines, hint, &old_block).ok_or_else(|| {
+                format!(
+                    "Hunk {} does not apply: context not found near line {}",
+                    index + 1,
+                    hunk.old_start
+                )
+            })?
+        };
+
+        lines.splice(pos..pos + old_block.len(), new_block.iter().cloned());
+        offset += new_block.len() as isize - old_block.len() as isize;
+        applications.push(HunkApplication {
+            line: pos + 1,
+            fuzzy,
+        });
+    }
+
+    let mut result = lines.join("\n");
+    if had_trailing_newline && !result.is_empty() {
+        result.push('\n');
+    }
+    Ok((result, applications))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -419,13 +733,6 @@ mod tests {
         assert!(plain.contains("+ let x = 10;") || plain.contains("+     let x = 10;"));
     }
 
-    #[test]
-    fn test_catppuccin_theme_loads() {
-        // Verify the bundled Catppuccin theme loads without panic
-        let _ = &*CATPPUCCIN_MOCHA;
-        // If we get here, the theme loaded successfully
-    }
-
     #[test]
     fn test_background_colors_differ_for_delete_and_insert() {
         colored::control::set_override(true);
@@ -435,13 +742,13 @@ mod tests {
 
         let diff = format_diff(old, new, 2, Some("test.rs"));
 
-        // Deletion should have DELETE_BG (80, 40, 40)
+        // Deletion should have the dark preset delete background (80, 40, 40)
         assert!(
             diff.contains("\x1b[48;2;80;40;40m"),
             "deletion should have red background"
         );
 
-        // Addition should have INSERT_BG (40, 80, 40)
+        // Addition should have the dark preset insert background (40, 80, 40)
         assert!(
             diff.contains("\x1b[48;2;40;80;40m"),
             "addition should have green background"
@@ -467,7 +774,7 @@ mod tests {
             let trimmed = plain.trim();
             // Context lines start with 4 spaces (no - or + marker)
             if plain.starts_with("    ") && !trimmed.is_empty() {
-                // Context line - should not have DELETE_BG or INSERT_BG
+                // Context line - should not have a diff background
                 assert!(
                     !line.contains("\x1b[48;2;80;40;40m") && !line.contains("\x1b[48;2;40;80;40m"),
                     "context line should not have diff background: {:?}",
@@ -493,6 +800,241 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_unified_diff_plain_text() {
+        let old = "one\ntwo\nthree\n";
+        let new = "one\n2\nthree\n";
+
+        let diff = unified_diff(old, new, 1, Some("test.txt"));
+
+        assert!(diff.contains("--- a/test.txt"));
+        assert!(diff.contains("+++ b/test.txt"));
+        assert!(diff.contains("-two"));
+        assert!(diff.contains("+2"));
+        assert!(diff.contains("@@"));
+        // Plain text: no ANSI escape codes
+        assert!(!diff.contains('\x1b'));
+    }
+
+    #[test]
+    fn test_unified_diff_no_header_without_path() {
+        let diff = unified_diff("a\n", "b\n", 0, None);
+        assert!(!diff.contains("---"));
+        assert!(diff.contains("-a"));
+        assert!(diff.contains("+b"));
+    }
+
+    #[test]
+    fn test_unified_diff_identical_is_empty() {
+        assert_eq!(unified_diff("same\n", "same\n", 2, Some("f.txt")), "");
+    }
+
+    #[test]
+    fn test_unified_diff_round_trips_through_apply() {
+        let old = "one\ntwo\nthree\nfour\n";
+        let new = "one\n2\nthree\nfour\nfive\n";
+
+        let patch = unified_diff(old, new, 2, Some("test.txt"));
+        let files = parse_unified_patch(&patch).unwrap();
+        assert_eq!(files.len(), 1);
+
+        let (result, _) = apply_hunks(old, &files[0].hunks).unwrap();
+        assert_eq!(result, new);
+    }
+
+    #[test]
+    fn test_parse_unified_patch_single_file() {
+        let patch = "\
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,3 @@
+ fn main() {
+-    old();
++    new();
+ }
+";
+        let files = parse_unified_patch(patch).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].old_path.as_deref(), Some("src/lib.rs"));
+        assert_eq!(files[0].new_path.as_deref(), Some("src/lib.rs"));
+        assert_eq!(files[0].hunks.len(), 1);
+        assert_eq!(files[0].hunks[0].old_start, 1);
+        assert_eq!(files[0].hunks[0].lines.len(), 4);
+    }
+
+    #[test]
+    fn test_parse_unified_patch_multi_file_with_git_decorations() {
+        let patch = "\
+diff --git a/one.txt b/one.txt
+index 1234567..89abcde 100644
+--- a/one.txt
++++ b/one.txt
+@@ -1 +1 @@
+-alpha
++first
+diff --git a/two.txt b/two.txt
+--- a/two.txt
++++ b/two.txt
+@@ -1 +1 @@
+-beta
++second
+";
+        let files = parse_unified_patch(patch).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].new_path.as_deref(), Some("one.txt"));
+        assert_eq!(files[1].new_path.as_deref(), Some("two.txt"));
+    }
+
+    #[test]
+    fn test_parse_unified_patch_dev_null_for_creation() {
+        let patch = "\
+--- /dev/null
++++ b/new.txt
+@@ -0,0 +1,2 @@
++hello
++world
+";
+        let files = parse_unified_patch(patch).unwrap();
+        assert!(files[0].old_path.is_none());
+        assert_eq!(files[0].new_path.as_deref(), Some("new.txt"));
+    }
+
+    #[test]
+    fn test_parse_unified_patch_counts_disambiguate_dashes() {
+        // A removed line starting with "--" must not be misread as a header
+        let patch = "\
+--- a/t.txt
++++ b/t.txt
+@@ -1,2 +1,1 @@
+--- not a header
+ keep
+";
+        let files = parse_unified_patch(patch).unwrap();
+        assert_eq!(files.len(), 1);
+        match &files[0].hunks[0].lines[0] {
+            HunkLine::Remove(s) => assert_eq!(s, "-- not a header"),
+            other => panic!("Expected Remove, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_unified_patch_rejects_garbage() {
+        assert!(parse_unified_patch("not a patch at all").is_err());
+        assert!(parse_unified_patch("--- a/x.txt\nno plus line").is_err());
+    }
+
+    #[test]
+    fn test_apply_hunks_exact_match() {
+        let content = "fn main() {\n    old();\n}\n";
+        let hunks = vec![Hunk {
+            old_start: 1,
+            lines: vec![
+                HunkLine::Context("fn main() {".to_string()),
+                HunkLine::Remove("    old();".to_string()),
+                HunkLine::Add("    new();".to_string()),
+                HunkLine::Context("}".to_string()),
+            ],
+        }];
+
+        let (result, applied) = apply_hunks(content, &hunks).unwrap();
+        assert_eq!(result, "fn main() {\n    new();\n}\n");
+        assert_eq!(applied.len(), 1);
+        assert_eq!(applied[0].line, 1);
+        assert!(!applied[0].fuzzy);
+    }
+
+    #[test]
+    fn test_apply_hunks_finds_drifted_context() {
+        // The hunk header says line 1, but the block is actually at line 4
+        let content = "a\nb\nc\nfn main() {\n    old();\n}\n";
+        let hunks = vec![Hunk {
+            old_start: 1,
+            lines: vec![
+                HunkLine::Context("fn main() {".to_string()),
+                HunkLine::Remove("    old();".to_string()),
+                HunkLine::Add("    new();".to_string()),
+            ],
+        }];
+
+        let (result, applied) = apply_hunks(content, &hunks).unwrap();
+        assert!(result.contains("    new();"));
+        assert_eq!(applied[0].line, 4);
+        assert!(!applied[0].fuzzy);
+    }
+
+    #[test]
+    fn test_apply_hunks_fuzzy_whitespace_match() {
+        // File uses tabs; patch context uses spaces
+        let content = "fn main() {\n\told();\n}\n";
+        let hunks = vec![Hunk {
+            old_start: 1,
+            lines: vec![
+                HunkLine::Context("fn main() {".to_string()),
+                HunkLine::Remove("    old();".to_string()),
+                HunkLine::Add("    new();".to_string()),
+            ],
+        }];
+
+        let (result, applied) = apply_hunks(content, &hunks).unwrap();
+        assert!(result.contains("    new();"));
+        assert!(applied[0].fuzzy);
+    }
+
+    #[test]
+    fn test_apply_hunks_missing_context_fails() {
+        let content = "completely different\n";
+        let hunks = vec![Hunk {
+            old_start: 1,
+            lines: vec![
+                HunkLine::Remove("nonexistent".to_string()),
+                HunkLine::Add("replacement".to_string()),
+            ],
+        }];
+
+        let err = apply_hunks(content, &hunks).unwrap_err();
+        assert!(err.contains("Hunk 1 does not apply"), "got: {err}");
+    }
+
+    #[test]
+    fn test_apply_hunks_multiple_hunks_track_offset() {
+        let content = "one\ntwo\nthree\nfour\nfive\n";
+        let hunks = vec![
+            Hunk {
+                old_start: 1,
+                lines: vec![
+                    HunkLine::Remove("one".to_string()),
+                    HunkLine::Add("1".to_string()),
+                    HunkLine::Add("1.5".to_string()),
+                ],
+            },
+            Hunk {
+                old_start: 4,
+                lines: vec![
+                    HunkLine::Remove("four".to_string()),
+                    HunkLine::Add("4".to_string()),
+                ],
+            },
+        ];
+
+        let (result, applied) = apply_hunks(content, &hunks).unwrap();
+        assert_eq!(result, "1\n1.5\ntwo\nthree\n4\nfive\n");
+        assert_eq!(applied.len(), 2);
+    }
+
+    #[test]
+    fn test_apply_hunks_pure_addition_to_empty_file() {
+        let hunks = vec![Hunk {
+            old_start: 0,
+            lines: vec![
+                HunkLine::Add("hello".to_string()),
+                HunkLine::Add("world".to_string()),
+            ],
+        }];
+
+        let (result, _) = apply_hunks("", &hunks).unwrap();
+        assert_eq!(result, "hello\nworld\n");
+    }
+
     #[test]
     fn test_javascript_syntax_highlighting() {
         colored::control::set_override(true);
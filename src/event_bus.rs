@@ -79,9 +79,17 @@ pub struct GetEventsOptions<'a> {
     pub limit: usize,
     pub session_id: Option<&'a str>,
     pub order: &'a str,
+    /// Channel name, or a wildcard pattern (`build.*`, `repo:*`) using
+    /// `*`/`?` glob syntax.
     pub channel: Option<&'a str>,
     pub resume: bool,
     pub event_types: Option<&'a [String]>,
+    /// Only events with an ID strictly greater than this, regardless of
+    /// `order` - a monotonic "new since last poll" cursor.
+    pub since_id: Option<i64>,
+    /// Only events whose payload parses as JSON and has each of these
+    /// fields equal to the given value.
+    pub payload_filters: Option<&'a serde_json::Map<String, serde_json::Value>>,
 }
 
 impl Default for GetEventsOptions<'_> {
@@ -100,6 +108,8 @@ impl<'a> GetEventsOptions<'a> {
             channel: None,
             resume: false,
             event_types: None,
+            since_id: None,
+            payload_filters: None,
         }
     }
 }
@@ -473,10 +483,21 @@ impl EventBus {
         }
 
         if let Some(ch) = opts.channel {
-            sql.push_str(" AND channel = ?");
+            // Wildcard patterns use SQLite GLOB; plain names use equality so
+            // channels containing glob metacharacters can't surprise anyone.
+            if ch.contains('*') || ch.contains('?') {
+                sql.push_str(" AND channel GLOB ?");
+            } else {
+                sql.push_str(" AND channel = ?");
+            }
             params_vec.push(Box::new(ch.to_string()));
         }
 
+        if let Some(since) = opts.since_id {
+            sql.push_str(" AND id > ?");
+            params_vec.push(Box::new(since));
+        }
+
         if let Some(types) = opts.event_types
             && !types.is_empty()
         {
@@ -487,8 +508,13 @@ impl EventBus {
             }
         }
 
-        sql.push_str(&format!(" ORDER BY id {} LIMIT ?", order_dir));
-        params_vec.push(Box::new(opts.limit as i64));
+        sql.push_str(&format!(" ORDER BY id {}", order_dir));
+        // Payload filtering happens in Rust, so the SQL limit would
+        // undercount; apply the limit after filtering instead.
+        if opts.payload_filters.is_none() {
+            sql.push_str(" LIMIT ?");
+            params_vec.push(Box::new(opts.limit as i64));
+        }
 
         let params_refs: Vec<&dyn rusqlite::ToSql> =
             params_vec.iter().map(|p| p.as_ref()).collect();
@@ -507,6 +533,12 @@ impl EventBus {
             })?
             .collect::<std::result::Result<Vec<_>, _>>()?;
 
+        let mut events = events;
+        if let Some(filters) = opts.payload_filters {
+            events.retain(|e| payload_matches(&e.payload, filters));
+            events.truncate(opts.limit);
+        }
+
         // Update session cursor if we got events
         let new_cursor = events.last().map(|e| e.id);
         if let (Some(sid), Some(nc)) = (opts.session_id, new_cursor) {
@@ -557,6 +589,15 @@ impl EventBus {
     }
 }
 
+/// Whether `payload` parses as JSON and carries every filter field with an
+/// equal value. Non-JSON payloads never match a field filter.
+fn payload_matches(payload: &str, filters: &serde_json::Map<String, serde_json::Value>) -> bool {
+    let Ok(parsed) = serde_json::from_str::<serde_json::Value>(payload) else {
+        return false;
+    };
+    filters.iter().all(|(key, value)| parsed.get(key) == Some(value))
+}
+
 /// Generate a UUID v4 (random).
 fn uuid_v4() -> String {
     uuid::Uuid::new_v4().to_string()
@@ -712,6 +753,100 @@ mod tests {
         assert_eq!(events.len(), 2);
     }
 
+    #[test]
+    fn test_channel_wildcard_filtering() {
+        let bus = EventBus::open_in_memory().unwrap();
+
+        bus.publish_event("e1", "p1", None, "build.linux").unwrap();
+        bus.publish_event("e2", "p2", None, "build.macos").unwrap();
+        bus.publish_event("e3", "p3", None, "deploy.prod").unwrap();
+
+        let (events, _) = bus
+            .get_events(&GetEventsOptions {
+                limit: 10,
+                order: "asc",
+                channel: Some("build.*"),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|e| e.channel.starts_with("build.")));
+    }
+
+    #[test]
+    fn test_since_id_ignores_order() {
+        let bus = EventBus::open_in_memory().unwrap();
+
+        let first = bus.publish_event("e1", "p1", None, "all").unwrap();
+        bus.publish_event("e2", "p2", None, "all").unwrap();
+        bus.publish_event("e3", "p3", None, "all").unwrap();
+
+        // Even in desc order (where cursor means "older than"), since_id
+        // returns only events newer than the given ID.
+        let (events, _) = bus
+            .get_events(&GetEventsOptions {
+                limit: 10,
+                order: "desc",
+                since_id: Some(first.id),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|e| e.id > first.id));
+    }
+
+    #[test]
+    fn test_payload_field_filtering() {
+        let bus = EventBus::open_in_memory().unwrap();
+
+        bus.publish_event("ci", r#"{"status": "failed", "job": "test"}"#, None, "all")
+            .unwrap();
+        bus.publish_event("ci", r#"{"status": "passed", "job": "test"}"#, None, "all")
+            .unwrap();
+        bus.publish_event("ci", "not json", None, "all").unwrap();
+
+        let filters = serde_json::json!({"status": "failed"});
+        let (events, _) = bus
+            .get_events(&GetEventsOptions {
+                limit: 10,
+                order: "asc",
+                payload_filters: filters.as_object(),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(events[0].payload.contains("failed"));
+    }
+
+    #[test]
+    fn test_payload_filter_limit_applies_after_filtering() {
+        let bus = EventBus::open_in_memory().unwrap();
+
+        // Interleave matching and non-matching events; a SQL-side limit of 2
+        // would have returned only non-matching rows.
+        bus.publish_event("e", r#"{"ok": false}"#, None, "all")
+            .unwrap();
+        bus.publish_event("e", r#"{"ok": false}"#, None, "all")
+            .unwrap();
+        bus.publish_event("e", r#"{"ok": true}"#, None, "all")
+            .unwrap();
+        bus.publish_event("e", r#"{"ok": true}"#, None, "all")
+            .unwrap();
+        bus.publish_event("e", r#"{"ok": true}"#, None, "all")
+            .unwrap();
+
+        let filters = serde_json::json!({"ok": true});
+        let (events, _) = bus
+            .get_events(&GetEventsOptions {
+                limit: 2,
+                order: "asc",
+                payload_filters: filters.as_object(),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(events.len(), 2);
+    }
+
     #[test]
     fn test_cursor_tracking() {
         let bus = EventBus::open_in_memory().unwrap();
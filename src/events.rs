@@ -29,6 +29,10 @@ pub trait EventHandler {
     /// Handle streaming text (should append to current line, not create new line).
     fn on_text_delta(&mut self, text: &str);
 
+    /// Handle streaming reasoning/thought text (optional, default no-op).
+    /// Handlers that render it should keep it visually distinct from response text.
+    fn on_thinking(&mut self, _text: &str) {}
+
     /// Handle tool starting execution.
     fn on_tool_executing(&mut self, call: &OwnedFunctionCallInfo);
 
@@ -55,6 +59,20 @@ pub trait EventHandler {
 
     /// Handle API retry notification.
     fn on_retry(&mut self, _attempt: u32, _max_attempts: u32, _delay: Duration, _error: &str) {}
+
+    /// Handle the agent loop hitting its turn limit (optional, default no-op).
+    fn on_max_turns_exceeded(&mut self, _max_turns: usize) {}
+
+    /// Handle the agent loop aborting after its token budget is spent
+    /// (optional, default no-op).
+    fn on_token_budget_exceeded(&mut self, _budget: u32, _used: u32) {}
+
+    /// Handle the agent loop aborting on repeated identical tool failures
+    /// (optional, default no-op).
+    fn on_repeated_tool_failures(&mut self, _tool: &str, _error: &str, _count: usize) {}
+
+    /// Handle a steering message being injected at a turn boundary (optional, default no-op).
+    fn on_user_steering(&mut self, _message: &str) {}
 }
 
 /// Event handler for terminal output (plain REPL and non-interactive modes).
@@ -63,6 +81,9 @@ pub trait EventHandler {
 /// Text is accumulated in `TextBuffer` and flushed at event boundaries.
 pub struct TerminalEventHandler {
     text_buffer: TextBuffer,
+    /// Reasoning text accumulated from `Thinking` events, rendered dimmed
+    /// as one block before the next non-thinking output.
+    thinking_buffer: String,
     model: String,
 }
 
@@ -70,21 +91,42 @@ impl TerminalEventHandler {
     pub fn new(model: String) -> Self {
         Self {
             text_buffer: TextBuffer::new(),
+            thinking_buffer: String::new(),
             model,
         }
     }
+
+    /// Render any accumulated reasoning text (dimmed) and clear the buffer.
+    fn flush_thinking(&mut self) {
+        if !self.thinking_buffer.is_empty() {
+            crate::logging::log_event(&crate::format::format_thinking(&self.thinking_buffer));
+            self.thinking_buffer.clear();
+        }
+    }
+
+    /// Flush buffered streamed text, syntax-highlighting any fenced code
+    /// blocks before it reaches the sink.
+    fn flush_text(&mut self) {
+        if let Some(rendered) = self.text_buffer.flush() {
+            crate::logging::log_event_line(&crate::format::highlight_code_blocks(&rendered));
+        }
+    }
 }
 
 impl EventHandler for TerminalEventHandler {
     fn on_text_delta(&mut self, text: &str) {
+        self.flush_thinking();
         self.text_buffer.push(text);
     }
 
+    fn on_thinking(&mut self, text: &str) {
+        self.thinking_buffer.push_str(text);
+    }
+
     fn on_tool_executing(&mut self, _call: &OwnedFunctionCallInfo) {
-        // Flush buffer before tool output
-        if let Some(rendered) = self.text_buffer.flush() {
-            crate::logging::log_event_line(&rendered);
-        }
+        // Flush buffers before tool output
+        self.flush_thinking();
+        self.flush_text();
     }
 
     fn on_tool_result(&mut self, _result: &FunctionExecutionResult) {
@@ -101,9 +143,8 @@ impl EventHandler for TerminalEventHandler {
         _response: &genai_rs::InteractionResponse,
     ) {
         // Flush any remaining buffered text
-        if let Some(rendered) = self.text_buffer.flush() {
-            crate::logging::log_event_line(&rendered);
-        }
+        self.flush_thinking();
+        self.flush_text();
 
         // Print interaction ID and model for session continuity
         if let Some(id) = interaction_id {
@@ -113,9 +154,27 @@ impl EventHandler for TerminalEventHandler {
 
     fn on_retry(&mut self, _attempt: u32, _max_attempts: u32, _delay: Duration, _error: &str) {
         // Flush buffer before retry message
-        if let Some(rendered) = self.text_buffer.flush() {
-            crate::logging::log_event_line(&rendered);
-        }
+        self.flush_text();
+    }
+
+    fn on_max_turns_exceeded(&mut self, _max_turns: usize) {
+        // Flush buffer before the abort message
+        self.flush_text();
+    }
+
+    fn on_user_steering(&mut self, _message: &str) {
+        // Flush buffer before the steering notice
+        self.flush_text();
+    }
+
+    fn on_token_budget_exceeded(&mut self, _budget: u32, _used: u32) {
+        // Flush buffer before the abort message
+        self.flush_text();
+    }
+
+    fn on_repeated_tool_failures(&mut self, _tool: &str, _error: &str, _count: usize) {
+        // Flush buffer before the abort message
+        self.flush_text();
     }
 }
 
@@ -123,11 +182,13 @@ impl EventHandler for TerminalEventHandler {
 ///
 /// This function handles logging centrally so handlers don't need to duplicate
 /// log_event calls. The order is: handler method first (to flush buffers), then log.
-pub fn dispatch_event<H: EventHandler>(handler: &mut H, event: &crate::agent::AgentEvent) {
+pub fn dispatch_event<H: EventHandler + ?Sized>(handler: &mut H, event: &crate::agent::AgentEvent) {
     use crate::agent::AgentEvent;
 
     match event {
         AgentEvent::TextDelta(text) => handler.on_text_delta(text),
+        // Like TextDelta, thinking is streamed; handlers own its rendering
+        AgentEvent::Thinking(text) => handler.on_thinking(text),
         AgentEvent::ToolExecuting(calls) => {
             for call in calls {
                 handler.on_tool_executing(call);
@@ -173,6 +234,24 @@ pub fn dispatch_event<H: EventHandler>(handler: &mut H, event: &crate::agent::Ag
                 error,
             ));
         }
+        AgentEvent::MaxTurnsExceeded { max_turns } => {
+            handler.on_max_turns_exceeded(*max_turns);
+            crate::logging::log_event(&crate::format::format_max_turns_exceeded(*max_turns));
+        }
+        AgentEvent::TokenBudgetExceeded { budget, used } => {
+            handler.on_token_budget_exceeded(*budget, *used);
+            crate::logging::log_event(&crate::format::format_token_budget_exceeded(*budget, *used));
+        }
+        AgentEvent::RepeatedToolFailures { tool, error, count } => {
+            handler.on_repeated_tool_failures(tool, error, *count);
+            crate::logging::log_event(&crate::format::format_repeated_tool_failures(
+                tool, error, *count,
+            ));
+        }
+        AgentEvent::UserSteering(message) => {
+            handler.on_user_steering(message);
+            crate::logging::log_event(&crate::format::format_user_steering(message));
+        }
     }
 }
 
@@ -269,6 +348,35 @@ mod tests {
                 .borrow_mut()
                 .push(format!("tool_output:{}", output));
         }
+
+        fn on_max_turns_exceeded(&mut self, max_turns: usize) {
+            self.events
+                .borrow_mut()
+                .push(format!("max_turns_exceeded:{}", max_turns));
+        }
+
+        fn on_user_steering(&mut self, message: &str) {
+            self.events
+                .borrow_mut()
+                .push(format!("user_steering:{}", message));
+        }
+
+        fn on_token_budget_exceeded(&mut self, budget: u32, used: u32) {
+            self.events
+                .borrow_mut()
+                .push(format!("token_budget_exceeded:{}:{}", budget, used));
+        }
+
+        fn on_repeated_tool_failures(&mut self, tool: &str, error: &str, count: usize) {
+            self.events.borrow_mut().push(format!(
+                "repeated_tool_failures:{}:{}:{}",
+                tool, error, count
+            ));
+        }
+
+        fn on_thinking(&mut self, text: &str) {
+            self.events.borrow_mut().push(format!("thinking:{}", text));
+        }
     }
 
     // =========================================
@@ -413,6 +521,86 @@ mod tests {
         assert_eq!(events.borrow()[0], "cancelled");
     }
 
+    #[test]
+    fn test_dispatch_max_turns_exceeded() {
+        use crate::agent::AgentEvent;
+
+        crate::logging::disable_logging();
+
+        let (mut handler, events) = RecordingHandler::new();
+        let event = AgentEvent::MaxTurnsExceeded { max_turns: 100 };
+        dispatch_event(&mut handler, &event);
+
+        assert_eq!(events.borrow().len(), 1);
+        assert_eq!(events.borrow()[0], "max_turns_exceeded:100");
+    }
+
+    #[test]
+    fn test_dispatch_token_budget_exceeded() {
+        use crate::agent::AgentEvent;
+
+        crate::logging::disable_logging();
+
+        let (mut handler, events) = RecordingHandler::new();
+        let event = AgentEvent::TokenBudgetExceeded {
+            budget: 50_000,
+            used: 51_234,
+        };
+        dispatch_event(&mut handler, &event);
+
+        assert_eq!(events.borrow().len(), 1);
+        assert_eq!(events.borrow()[0], "token_budget_exceeded:50000:51234");
+    }
+
+    #[test]
+    fn test_dispatch_repeated_tool_failures() {
+        use crate::agent::AgentEvent;
+
+        crate::logging::disable_logging();
+
+        let (mut handler, events) = RecordingHandler::new();
+        let event = AgentEvent::RepeatedToolFailures {
+            tool: "edit".to_string(),
+            error: "old_string not found".to_string(),
+            count: 5,
+        };
+        dispatch_event(&mut handler, &event);
+
+        assert_eq!(events.borrow().len(), 1);
+        assert_eq!(
+            events.borrow()[0],
+            "repeated_tool_failures:edit:old_string not found:5"
+        );
+    }
+
+    #[test]
+    fn test_dispatch_thinking() {
+        use crate::agent::AgentEvent;
+
+        crate::logging::disable_logging();
+
+        let (mut handler, events) = RecordingHandler::new();
+        let event = AgentEvent::Thinking("considering options".to_string());
+        dispatch_event(&mut handler, &event);
+
+        assert_eq!(events.borrow().len(), 1);
+        assert_eq!(events.borrow()[0], "thinking:considering options");
+    }
+
+    #[test]
+    fn test_dispatch_user_steering() {
+        use crate::agent::AgentEvent;
+
+        crate::logging::disable_logging();
+
+        let (mut handler, events) = RecordingHandler::new();
+        let event = AgentEvent::UserSteering("focus on the parser".to_string());
+        dispatch_event(&mut handler, &event);
+
+        assert_eq!(events.borrow().len(), 1);
+        assert_eq!(events.borrow()[0], "user_steering:focus on the parser");
+    }
+
     // =========================================
     // Full flow tests
     // =========================================
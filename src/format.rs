@@ -100,6 +100,94 @@ pub fn format_startup_tip() -> String {
     )
 }
 
+/// Format the abort message shown when the agent loop hits its turn limit.
+pub fn format_max_turns_exceeded(max_turns: usize) -> String {
+    format!(
+        "Interaction aborted: reached max_turns limit ({})",
+        max_turns
+    )
+    .red()
+    .to_string()
+}
+
+/// Format the abort message shown when the interaction's token budget is spent.
+pub fn format_token_budget_exceeded(budget: u32, used: u32) -> String {
+    format!(
+        "Interaction aborted: token budget spent ({} tokens used, budget {})",
+        used, budget
+    )
+    .red()
+    .to_string()
+}
+
+/// Format the abort message shown when a tool keeps failing identically.
+pub fn format_repeated_tool_failures(tool: &str, error: &str, count: usize) -> String {
+    format!(
+        "Interaction aborted: '{}' failed {} times consecutively with: {}",
+        tool, count, error
+    )
+    .red()
+    .to_string()
+}
+
+/// Format a block of model reasoning text (dimmed, separate from response text).
+pub fn format_thinking(text: &str) -> String {
+    format!("[thinking] {}", text.trim_end())
+        .dimmed()
+        .to_string()
+}
+
+/// Format the notice shown when a queued steering message is injected.
+pub fn format_user_steering(message: &str) -> String {
+    format!("[steering] {}", message).cyan().to_string()
+}
+
+/// Syntax-highlight fenced code blocks in rendered streamed text.
+///
+/// `TextBuffer` renders markdown but leaves fenced code block contents as
+/// plain text, so long code responses stream in white. This pass walks the
+/// rendered output and highlights the lines inside ``` fences with the same
+/// syntect setup the diff renderer uses. Fence lines are kept (dimmed) so
+/// block boundaries stay visible; blocks with no or unknown language tags
+/// and lines already carrying ANSI escapes pass through unchanged.
+pub fn highlight_code_blocks(text: &str) -> String {
+    let mut in_block = false;
+    let mut highlighter: Option<syntect::easy::HighlightLines> = None;
+    let mut out: Vec<String> = Vec::with_capacity(text.lines().count());
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") {
+            if in_block {
+                in_block = false;
+                highlighter = None;
+            } else {
+                in_block = true;
+                let lang = trimmed.trim_start_matches('`').trim();
+                highlighter = if lang.is_empty() {
+                    None
+                } else {
+                    crate::diff::code_block_highlighter(lang)
+                };
+            }
+            out.push(line.dimmed().to_string());
+            continue;
+        }
+        match highlighter.as_mut() {
+            Some(h) if !line.contains('\x1b') => {
+                out.push(crate::diff::highlight_code_line(line, h));
+            }
+            _ => out.push(line.to_string()),
+        }
+    }
+
+    let mut result = out.join("\n");
+    if text.ends_with('\n') {
+        result.push('\n');
+    }
+    result
+}
+
 /// Format MCP server startup message.
 pub fn format_mcp_startup() -> String {
     format!(
@@ -137,6 +225,42 @@ pub fn format_builtin_shell(output: &str) -> String {
     format!("\n{}\n", output.dimmed())
 }
 
+/// Format /cost command output (dimmed).
+pub fn format_builtin_cost(usage: &crate::agent::TokenUsage, model: &str) -> String {
+    let cost = match usage.cost_usd(model) {
+        Some(c) => format!("${:.4}", c),
+        None => "unknown (no pricing for model)".to_string(),
+    };
+    format!(
+        "\n{}\n",
+        format!(
+            "Session usage: {} input + {} output = {} tokens | cost: {}",
+            usage.input_tokens, usage.output_tokens, usage.total_tokens, cost
+        )
+        .dimmed()
+    )
+}
+
+/// Format /stats command output (dimmed per-tool usage table).
+pub fn format_builtin_stats(stats: &crate::tools::ToolStatsMap) -> String {
+    if stats.is_empty() {
+        return format!("\n{}\n", "No tool calls this session.".dimmed());
+    }
+    let width = stats.keys().map(String::len).max().unwrap_or(0);
+    let mut lines = vec!["Tool usage this session:".to_string()];
+    for (name, tool) in stats {
+        lines.push(format!(
+            "  {:<width$}  {:>4} calls  {:>3} errors  avg {:>5}ms  ~{} tok",
+            name,
+            tool.calls,
+            tool.errors,
+            tool.avg_duration_ms(),
+            tool.total_tokens,
+        ));
+    }
+    format!("\n{}\n", lines.join("\n").dimmed())
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -270,6 +394,62 @@ mod tests {
         assert!(tip.contains("Remember to take breaks"));
     }
 
+    #[test]
+    fn test_format_max_turns_exceeded() {
+        colored::control::set_override(false);
+
+        let msg = format_max_turns_exceeded(100);
+        assert_eq!(msg, "Interaction aborted: reached max_turns limit (100)");
+
+        colored::control::unset_override();
+    }
+
+    #[test]
+    fn test_format_token_budget_exceeded() {
+        colored::control::set_override(false);
+
+        let msg = format_token_budget_exceeded(50_000, 51_234);
+        assert_eq!(
+            msg,
+            "Interaction aborted: token budget spent (51234 tokens used, budget 50000)"
+        );
+
+        colored::control::unset_override();
+    }
+
+    #[test]
+    fn test_format_repeated_tool_failures() {
+        colored::control::set_override(false);
+
+        let msg = format_repeated_tool_failures("edit", "old_string not found", 5);
+        assert_eq!(
+            msg,
+            "Interaction aborted: 'edit' failed 5 times consecutively with: old_string not found"
+        );
+
+        colored::control::unset_override();
+    }
+
+    #[test]
+    fn test_format_thinking() {
+        colored::control::set_override(false);
+
+        let msg = format_thinking("weighing both approaches\n");
+        assert_eq!(msg, "[thinking] weighing both approaches");
+
+        colored::control::unset_override();
+    }
+
+    #[test]
+    fn test_format_user_steering() {
+        colored::control::set_override(false);
+
+        let msg = format_user_steering("use the staging config");
+        assert_eq!(msg, "[steering] use the staging config");
+
+        colored::control::unset_override();
+    }
+
     #[test]
     fn test_format_mcp_startup() {
         colored::control::set_override(false);
@@ -281,6 +461,57 @@ mod tests {
         colored::control::unset_override();
     }
 
+    #[test]
+    fn test_highlight_code_blocks_rust_fence() {
+        colored::control::set_override(false);
+
+        let text = "Here:\n```rust\nfn main() {}\n```\ndone\n";
+        let out = highlight_code_blocks(text);
+        assert!(
+            out.contains("\x1b[38;2;"),
+            "code line should carry truecolor syntax escapes, got: {:?}",
+            out
+        );
+        assert!(out.starts_with("Here:"), "prose before the fence untouched");
+        assert!(out.contains("```rust"), "fence line preserved");
+        assert!(out.ends_with("done\n"), "prose after the fence untouched");
+
+        colored::control::unset_override();
+    }
+
+    #[test]
+    fn test_highlight_code_blocks_unknown_language_passthrough() {
+        colored::control::set_override(false);
+
+        let text = "```nosuchlang\nplain body\n```";
+        let out = highlight_code_blocks(text);
+        assert!(out.contains("plain body"));
+        assert!(!out.contains("\x1b[38;2;"));
+
+        colored::control::unset_override();
+    }
+
+    #[test]
+    fn test_highlight_code_blocks_no_fences_unchanged() {
+        let text = "just prose\nwith a second line\n";
+        assert_eq!(highlight_code_blocks(text), text);
+    }
+
+    #[test]
+    fn test_highlight_code_blocks_skips_pre_colored_lines() {
+        colored::control::set_override(false);
+
+        let text = "```rust\n\x1b[31mred\x1b[0m\n```";
+        let out = highlight_code_blocks(text);
+        assert!(
+            out.contains("\x1b[31mred\x1b[0m"),
+            "already-styled lines pass through untouched"
+        );
+        assert!(!out.contains("\x1b[38;2;"));
+
+        colored::control::unset_override();
+    }
+
     // =========================================
     // Builtin command format tests
     // =========================================
@@ -351,6 +582,74 @@ mod tests {
         colored::control::unset_override();
     }
 
+    #[test]
+    fn test_format_builtin_cost() {
+        colored::control::set_override(false);
+
+        let usage = crate::agent::TokenUsage {
+            input_tokens: 1_000_000,
+            output_tokens: 100_000,
+            cached_tokens: 0,
+            total_tokens: 1_100_000,
+        };
+        let output = format_builtin_cost(&usage, "gemini-3-flash-preview");
+        assert!(output.starts_with('\n'), "must start with newline");
+        assert!(output.ends_with('\n'), "must end with newline");
+        // 1M input at $0.30/MTok + 100k output at $2.50/MTok = $0.55
+        assert_eq!(
+            output,
+            "\nSession usage: 1000000 input + 100000 output = 1100000 tokens | cost: $0.5500\n"
+        );
+
+        colored::control::unset_override();
+    }
+
+    #[test]
+    fn test_format_builtin_cost_unknown_model() {
+        colored::control::set_override(false);
+
+        let usage = crate::agent::TokenUsage::default();
+        let output = format_builtin_cost(&usage, "some-local-model");
+        assert!(output.contains("unknown (no pricing for model)"));
+
+        colored::control::unset_override();
+    }
+
+    #[test]
+    fn test_format_builtin_stats() {
+        colored::control::set_override(false);
+
+        let mut stats = crate::tools::ToolStatsMap::new();
+        stats
+            .entry("bash".to_string())
+            .or_default()
+            .record(Duration::from_millis(400), 120, true);
+        stats.entry("read_file".to_string()).or_default().record(
+            Duration::from_millis(10),
+            40,
+            false,
+        );
+        let output = format_builtin_stats(&stats);
+        assert!(output.starts_with('\n'), "must start with newline");
+        assert!(output.ends_with('\n'), "must end with newline");
+        assert!(output.contains("Tool usage this session:"));
+        assert!(output.contains("bash"));
+        assert!(output.contains("1 errors"));
+        assert!(output.contains("~120 tok"));
+
+        colored::control::unset_override();
+    }
+
+    #[test]
+    fn test_format_builtin_stats_empty() {
+        colored::control::set_override(false);
+
+        let output = format_builtin_stats(&crate::tools::ToolStatsMap::new());
+        assert_eq!(output, "\nNo tool calls this session.\n");
+
+        colored::control::unset_override();
+    }
+
     #[test]
     fn test_format_builtin_shell_empty() {
         colored::control::set_override(false);
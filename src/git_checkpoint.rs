@@ -0,0 +1,189 @@
+//! Opt-in shadow git checkpoints, one commit per agent turn.
+//!
+//! With `git_checkpoints = true` in config (or `--git-checkpoints`), every
+//! interaction that ran a mutating tool is recorded as a real commit under
+//! `refs/clemini/checkpoints`, built through a scratch index so HEAD, the
+//! user's index, and the working tree are untouched. Recovery is then plain
+//! git: `git log refs/clemini/checkpoints` lists the per-turn chain,
+//! `git diff refs/clemini/checkpoints~1 refs/clemini/checkpoints` shows what
+//! one turn changed, and `git checkout refs/clemini/checkpoints -- <path>`
+//! restores a file. Complements `tools::checkpoint`, which works without git
+//! but only covers the file-editing tools; the git checkpoint captures the
+//! whole tree, including bash-driven changes.
+
+use std::path::Path;
+use std::process::Command;
+
+/// Ref the checkpoint chain lives under. Outside `refs/heads/`, so it never
+/// shows up in `git branch` or gets pushed by default.
+pub const CHECKPOINT_REF: &str = "refs/clemini/checkpoints";
+
+/// Run git in `cwd` with extra environment variables, returning trimmed
+/// stdout on success and trimmed stderr on failure.
+fn git(cwd: &Path, envs: &[(&str, &str)], args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .current_dir(cwd)
+        .envs(envs.iter().copied())
+        .args(args)
+        .output()
+        .map_err(|e| format!("failed to run git: {e}"))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// Checkpoint commits get a fixed identity so they work in repos (and CI
+/// sandboxes) without a configured `user.name`.
+const IDENTITY: [(&str, &str); 4] = [
+    ("GIT_AUTHOR_NAME", "clemini"),
+    ("GIT_AUTHOR_EMAIL", "checkpoint@clemini.local"),
+    ("GIT_COMMITTER_NAME", "clemini"),
+    ("GIT_COMMITTER_EMAIL", "checkpoint@clemini.local"),
+];
+
+/// Record a checkpoint commit of the working tree, tagged with the
+/// interaction ID. Returns the new commit's hash, or `None` when the tree is
+/// identical to the previous checkpoint (or to HEAD when the chain is empty)
+/// and there is nothing to record.
+pub fn record(cwd: &Path, interaction_id: Option<&str>) -> Result<Option<String>, String> {
+    // Errors here mean "not a git repository" - the caller treats that as a
+    // reason to warn, since the user opted in.
+    let git_dir = git(cwd, &[], &["rev-parse", "--absolute-git-dir"])?;
+
+    // Stage the whole tree into a scratch index so the user's real index is
+    // untouched. A fresh index means `add -A` stages exactly the working
+    // tree (minus ignores), including untracked files.
+    let index = Path::new(&git_dir).join("clemini-checkpoint-index");
+    let _ = std::fs::remove_file(&index);
+    let index_str = index.to_str().ok_or("non-UTF-8 git dir path")?.to_string();
+    let index_env = [("GIT_INDEX_FILE", index_str.as_str())];
+    git(cwd, &index_env, &["add", "-A"])?;
+    let tree = git(cwd, &index_env, &["write-tree"])?;
+    let _ = std::fs::remove_file(&index);
+
+    let head = git(cwd, &[], &["rev-parse", "--verify", "HEAD"]).ok();
+    let prev = git(cwd, &[], &["rev-parse", "--verify", CHECKPOINT_REF]).ok();
+
+    // Skip no-op turns: nothing changed since the last checkpoint.
+    let parent = prev.as_deref().or(head.as_deref());
+    if let Some(base) = parent
+        && git(cwd, &[], &["rev-parse", &format!("{base}^{{tree}}")]).ok() == Some(tree.clone())
+    {
+        return Ok(None);
+    }
+
+    let message = format!(
+        "clemini checkpoint: interaction {}",
+        interaction_id.unwrap_or("(none)")
+    );
+    // Chain onto the previous checkpoint so the ref accumulates per-turn
+    // history; the first checkpoint hangs off HEAD. A brand-new repo with no
+    // commits yet gets a parentless checkpoint.
+    let mut args = vec!["commit-tree", tree.as_str(), "-m", message.as_str()];
+    if let Some(parent) = parent {
+        args.push("-p");
+        args.push(parent);
+    }
+    let commit = git(cwd, &IDENTITY, &args)?;
+    git(cwd, &[], &["update-ref", CHECKPOINT_REF, &commit])?;
+    Ok(Some(commit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn init_repo(dir: &Path) {
+        git(dir, &[], &["init", "-q"]).unwrap();
+        fs::write(dir.join("a.txt"), "one\n").unwrap();
+        git(dir, &[], &["add", "-A"]).unwrap();
+        git(dir, &IDENTITY, &["commit", "-q", "-m", "initial"]).unwrap();
+    }
+
+    #[test]
+    fn test_record_creates_checkpoint_ref() {
+        let dir = tempdir().unwrap();
+        init_repo(dir.path());
+        fs::write(dir.path().join("a.txt"), "two\n").unwrap();
+
+        let sha = record(dir.path(), Some("itx-1")).unwrap().unwrap();
+        let resolved = git(dir.path(), &[], &["rev-parse", CHECKPOINT_REF]).unwrap();
+        assert_eq!(sha, resolved);
+
+        let message = git(
+            dir.path(),
+            &[],
+            &["log", "-1", "--format=%s", CHECKPOINT_REF],
+        )
+        .unwrap();
+        assert_eq!(message, "clemini checkpoint: interaction itx-1");
+
+        // HEAD and the working tree are untouched.
+        let status = git(dir.path(), &[], &["status", "--porcelain"]).unwrap();
+        assert_eq!(status, " M a.txt");
+    }
+
+    #[test]
+    fn test_record_skips_unchanged_tree() {
+        let dir = tempdir().unwrap();
+        init_repo(dir.path());
+
+        // Tree matches HEAD - nothing to record.
+        assert!(record(dir.path(), Some("itx-1")).unwrap().is_none());
+
+        fs::write(dir.path().join("a.txt"), "two\n").unwrap();
+        assert!(record(dir.path(), Some("itx-2")).unwrap().is_some());
+        // Same tree as the previous checkpoint - skipped again.
+        assert!(record(dir.path(), Some("itx-3")).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_checkpoints_chain_per_turn() {
+        let dir = tempdir().unwrap();
+        init_repo(dir.path());
+
+        fs::write(dir.path().join("a.txt"), "two\n").unwrap();
+        record(dir.path(), Some("itx-1")).unwrap().unwrap();
+        fs::write(dir.path().join("b.txt"), "new\n").unwrap();
+        record(dir.path(), Some("itx-2")).unwrap().unwrap();
+
+        // The second checkpoint's parent is the first, so per-turn diffs
+        // work: this one only added b.txt.
+        let diff = git(
+            dir.path(),
+            &[],
+            &[
+                "diff",
+                "--name-only",
+                &format!("{CHECKPOINT_REF}~1"),
+                CHECKPOINT_REF,
+            ],
+        )
+        .unwrap();
+        assert_eq!(diff, "b.txt");
+    }
+
+    #[test]
+    fn test_record_includes_untracked_files() {
+        let dir = tempdir().unwrap();
+        init_repo(dir.path());
+        fs::write(dir.path().join("untracked.txt"), "content\n").unwrap();
+
+        record(dir.path(), None).unwrap().unwrap();
+        let files = git(dir.path(), &[], &["ls-tree", "--name-only", CHECKPOINT_REF]).unwrap();
+        assert!(files.contains("untracked.txt"));
+        // The user's index is still clean of it.
+        let staged = git(dir.path(), &[], &["diff", "--cached", "--name-only"]).unwrap();
+        assert_eq!(staged, "");
+    }
+
+    #[test]
+    fn test_record_outside_git_repo() {
+        let dir = tempdir().unwrap();
+        assert!(record(dir.path(), None).is_err());
+    }
+}
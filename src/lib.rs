@@ -6,16 +6,30 @@
 pub mod acp;
 pub mod acp_client;
 pub mod agent;
+pub mod audit;
 pub mod diff;
 pub mod event_bus;
 pub mod events;
 pub mod format;
+pub mod git_checkpoint;
 pub mod logging;
 pub mod plan;
+pub mod provider;
+pub mod redact;
+pub mod repo_map;
+pub mod term_image;
+pub mod theme;
+pub mod tokens;
 pub mod tools;
+pub mod transcript;
 
 // Re-export commonly used types
 pub use acp_client::{SubagentResult, spawn_subagent};
-pub use agent::{AgentEvent, InteractionResult, RetryConfig, run_interaction};
+pub use agent::{
+    Agent, AgentEvent, InteractionResult, RetryConfig, SteeringQueue, TokenUsage, run_interaction,
+    run_interaction_with_provider,
+};
 pub use logging::{OutputSink, log_event, set_output_sink};
-pub use tools::CleminiToolService;
+pub use provider::{ModelProvider, provider_from_config};
+pub use tokens::{GeminiTokenCounter, HeuristicTokenCounter, TokenCounter};
+pub use tools::{CleminiToolService, ModelRouting, ToolFilter};
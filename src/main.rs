@@ -3,14 +3,17 @@ use clap::Parser;
 use colored::Colorize;
 use genai_rs::Client;
 use reedline::{
-    EditCommand, Emacs, FileBackedHistory, KeyCode, KeyModifiers, Prompt, PromptHistorySearch,
-    Reedline, ReedlineEvent, Signal, default_emacs_keybindings, kitty_protocol_available,
+    ColumnarMenu, Completer, EditCommand, EditMode, Emacs, FileBackedHistory, KeyCode,
+    KeyModifiers, Keybindings, ListMenu, Prompt, PromptHistorySearch, Reedline, ReedlineEvent,
+    ReedlineMenu, Signal, Span, Suggestion, Vi, default_emacs_keybindings,
+    default_vi_insert_keybindings, default_vi_normal_keybindings, kitty_protocol_available,
 };
 use serde::Deserialize;
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::env;
 use std::io::{self, IsTerminal, Read, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
@@ -18,11 +21,19 @@ use tokio_util::sync::CancellationToken;
 mod mcp;
 
 use clemini::acp::AcpServer;
-use clemini::agent::{self, AgentEvent, run_interaction};
+use clemini::agent::{self, AgentEvent, run_interaction_with_provider};
 use clemini::events;
 use clemini::format;
 use clemini::logging::OutputSink;
-use clemini::tools::{self, CleminiToolService};
+use clemini::provider::ModelProvider;
+use clemini::repo_map;
+use clemini::theme::{self, ThemeToml};
+use clemini::tools::{
+    self, AgentProfile, BashSafetyToml, CleminiToolService, CustomToolsToml, LspConfigToml,
+    ModelRouting, PermissionMode, SafetyPolicy, SearchConfig, TaskLimits, TimeoutsToml, ToolFilter,
+};
+use clemini::transcript::TranscriptRecorder;
+use genai_rs::ToolService;
 
 const DEFAULT_MODEL: &str = "gemini-3-flash-preview";
 
@@ -96,6 +107,16 @@ fn log_event_to_file(message: &str, with_block_separator: bool) {
         return;
     }
 
+    // Inline image previews (kitty graphics escapes) are terminal-only;
+    // keep their base64 streams out of the log files.
+    let stripped;
+    let message = if message.contains("\x1b_G") {
+        stripped = clemini::term_image::strip_graphics_sequences(message);
+        stripped.as_str()
+    } else {
+        message
+    };
+
     colored::control::set_override(true);
 
     // Write to the stable log location: clemini.log.YYYY-MM-DD
@@ -142,6 +163,41 @@ fn write_to_log_file(
 
 const SYSTEM_PROMPT: &str = include_str!("system_prompt.md");
 
+/// Load the system prompt template: `~/.clemini/system_prompt.md` if present,
+/// otherwise the compiled-in default. Lets users customize behavior without
+/// recompiling.
+fn load_system_prompt_template() -> String {
+    let override_path = clemini_dir().join("system_prompt.md");
+    match std::fs::read_to_string(&override_path) {
+        Ok(content) if !content.trim().is_empty() => content,
+        _ => SYSTEM_PROMPT.to_string(),
+    }
+}
+
+/// Expand `{{cwd}}`, `{{model}}`, `{{os}}`, `{{date}}`, and `{{git_branch}}`
+/// placeholders in a system prompt template. Unknown placeholders are left
+/// as-is so prompt text that happens to contain braces isn't mangled.
+fn expand_prompt_template(template: &str, cwd: &Path, model: &str) -> String {
+    template
+        .replace("{{cwd}}", &cwd.display().to_string())
+        .replace("{{model}}", model)
+        .replace("{{os}}", std::env::consts::OS)
+        .replace(
+            "{{date}}",
+            &chrono::Local::now().format("%Y-%m-%d").to_string(),
+        )
+        .replace("{{git_branch}}", &git_branch(cwd).unwrap_or_default())
+}
+
+/// Current git branch of `cwd`, read from `.git/HEAD` (no subprocess).
+/// Returns None outside a git repo or on a detached HEAD.
+fn git_branch(cwd: &Path) -> Option<String> {
+    let head = std::fs::read_to_string(cwd.join(".git").join("HEAD")).ok()?;
+    head.trim()
+        .strip_prefix("ref: refs/heads/")
+        .map(|branch| branch.to_string())
+}
+
 fn expand_tilde(path_str: &str) -> PathBuf {
     if path_str.starts_with('~') {
         home::home_dir()
@@ -162,9 +218,94 @@ struct Config {
     bash_timeout: Option<u64>,
     #[serde(default = "default_allowed_paths")]
     allowed_paths: Vec<String>,
-    /// Maximum extra retries after initial failure. Default 2 = 3 total attempts.
-    max_extra_retries: Option<u32>,
-    retry_delay_base_secs: Option<u64>,
+    /// API retry settings ([retry] section).
+    #[serde(default)]
+    retry: RetryToml,
+    /// Maximum agent turns per interaction before aborting. Default 100.
+    max_turns: Option<usize>,
+    /// Wall-clock limit in seconds for a whole interaction; on expiry it is
+    /// cancelled cleanly with partial progress reported. Default: no limit.
+    interaction_timeout: Option<u64>,
+    /// Abort after the same tool fails identically this many times in a row.
+    /// Default 5; 0 disables the check.
+    max_consecutive_tool_failures: Option<usize>,
+    /// Record a shadow git checkpoint commit after each turn that runs a
+    /// mutating tool (see `git_checkpoint`). Default false.
+    git_checkpoints: Option<bool>,
+    /// Only expose these tools to the model (declared names). CLI flag overrides.
+    allowed_tools: Option<Vec<String>>,
+    /// Hide these tools from the model (declared names). CLI flag overrides.
+    disallowed_tools: Option<Vec<String>>,
+    /// Model backend: "gemini" (default), "openai-compatible", or "ollama".
+    provider: Option<String>,
+    /// Base URL for OpenAI-compatible providers (e.g., "http://localhost:8080/v1").
+    provider_base_url: Option<String>,
+    /// API key for OpenAI-compatible providers (optional; local servers don't need one).
+    provider_api_key: Option<String>,
+    /// Per-operation model overrides for internal LLM calls ([models] section).
+    #[serde(default)]
+    models: ModelRouting,
+    /// User-supplied bash safety patterns ([bash] section): `blocked` and
+    /// `caution` regexes merged with the built-ins, `allow` regexes
+    /// exempting specific commands from both.
+    #[serde(default)]
+    bash: BashSafetyToml,
+    /// Per-language server command overrides ([lsp] section).
+    #[serde(default)]
+    lsp: LspConfigToml,
+    /// Token budget for the repository map appended to the system prompt.
+    /// 0 disables the map. Default 1024.
+    repo_map_tokens: Option<u64>,
+    /// Web search backend: "duckduckgo" (default, keyless), "brave",
+    /// "serpapi", or "google".
+    search_provider: Option<String>,
+    /// API key for keyed search providers.
+    search_api_key: Option<String>,
+    /// Google CSE engine ID (`cx`); only the google provider uses it.
+    search_engine_id: Option<String>,
+    /// Cache TTL in seconds for `web_fetch` (default 900, 0 disables).
+    web_cache_ttl: Option<u64>,
+    /// Allow `web_fetch` to render JS-heavy pages with headless Chromium
+    /// (requires a chromium/chrome binary on PATH). Default false.
+    web_render: Option<bool>,
+    /// Hosts `http_request` may contact (exact or parent domain). Unset
+    /// permits every host.
+    http_allowed_hosts: Option<Vec<String>>,
+    /// Per-tool timeouts in seconds ([timeouts] section).
+    #[serde(default)]
+    timeouts: TimeoutsToml,
+    /// Extra regex patterns to redact from tool output, on top of the
+    /// built-in secret patterns (AWS/Google/GitHub/Slack keys, .env
+    /// assignments, secret-named env var values).
+    redact_patterns: Option<Vec<String>>,
+    /// Named subagent profiles for the `task` tool ([agents.<name>] sections).
+    #[serde(default)]
+    agents: HashMap<String, AgentProfile>,
+    /// Subagent delegation guardrails ([task] section): nesting depth,
+    /// concurrency cap, per-subagent token budget.
+    #[serde(default)]
+    task: TaskLimits,
+    /// Color preset and overrides for clemini-side rendering ([theme] section).
+    #[serde(default)]
+    theme: ThemeToml,
+    /// REPL input keybinding flavor: "emacs"/"default" (default) or "vim"/"vi".
+    keybindings: Option<String>,
+    /// Maximum REPL history entries kept per project. Default 10000.
+    history_size: Option<usize>,
+}
+
+/// The `[retry]` section of config.toml. Unset fields fall back to
+/// `RetryConfig` defaults (3 attempts, 1s initial delay, 30s cap, jitter on).
+#[derive(Debug, Default, Deserialize)]
+struct RetryToml {
+    /// Total attempts (initial + retries). Default 3.
+    max_attempts: Option<u32>,
+    /// Base delay for exponential backoff, in milliseconds. Default 1000.
+    initial_delay_ms: Option<u64>,
+    /// Cap on the backoff delay, in milliseconds. Default 30000.
+    max_delay_ms: Option<u64>,
+    /// Whether to add random jitter to retry delays. Default true.
+    jitter: Option<bool>,
 }
 
 impl Default for Config {
@@ -173,22 +314,93 @@ impl Default for Config {
             model: None,
             bash_timeout: None,
             allowed_paths: default_allowed_paths(),
-            max_extra_retries: None,
-            retry_delay_base_secs: None,
+            retry: RetryToml::default(),
+            max_turns: None,
+            interaction_timeout: None,
+            max_consecutive_tool_failures: None,
+            git_checkpoints: None,
+            allowed_tools: None,
+            disallowed_tools: None,
+            provider: None,
+            provider_base_url: None,
+            provider_api_key: None,
+            models: ModelRouting::default(),
+            bash: BashSafetyToml::default(),
+            lsp: LspConfigToml::default(),
+            repo_map_tokens: None,
+            search_provider: None,
+            search_api_key: None,
+            search_engine_id: None,
+            web_cache_ttl: None,
+            web_render: None,
+            http_allowed_hosts: None,
+            timeouts: TimeoutsToml::default(),
+            redact_patterns: None,
+            agents: HashMap::new(),
+            task: TaskLimits::default(),
+            theme: ThemeToml::default(),
+            keybindings: None,
+            history_size: None,
         }
     }
 }
 
-fn load_config() -> Config {
-    home::home_dir()
-        .map(|mut p| {
-            p.push(".clemini");
-            p.push("config.toml");
-            p
-        })
-        .filter(|p| p.exists())
-        .and_then(|p| std::fs::read_to_string(p).ok())
-        .and_then(|s| toml::from_str(&s).ok())
+/// Deep-merge `overlay` into `base`: tables merge key by key (recursively),
+/// everything else - scalars and arrays alike - is replaced by the overlay
+/// value.
+fn merge_toml(base: &mut toml::Value, overlay: toml::Value) {
+    match (base, overlay) {
+        (toml::Value::Table(base_table), toml::Value::Table(overlay_table)) => {
+            for (key, value) in overlay_table {
+                match base_table.get_mut(&key) {
+                    Some(existing) => merge_toml(existing, value),
+                    None => {
+                        base_table.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+fn read_config_toml(path: &std::path::Path) -> Option<toml::Value> {
+    if !path.exists() {
+        return None;
+    }
+    let raw = std::fs::read_to_string(path).ok()?;
+    match toml::from_str(&raw) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            tracing::warn!("Ignoring unparseable {}: {e}", path.display());
+            None
+        }
+    }
+}
+
+/// The merged raw config: `~/.clemini/config.toml` with the project-local
+/// `<cwd>/.clemini/config.toml` merged over it. `None` when neither exists.
+fn merged_config_value(cwd: &std::path::Path) -> Option<toml::Value> {
+    let global = home::home_dir()
+        .map(|p| p.join(".clemini").join("config.toml"))
+        .and_then(|p| read_config_toml(&p));
+    let project = read_config_toml(&cwd.join(".clemini").join("config.toml"));
+
+    match (global, project) {
+        (Some(mut base), Some(overlay)) => {
+            merge_toml(&mut base, overlay);
+            Some(base)
+        }
+        (base, overlay) => overlay.or(base),
+    }
+}
+
+/// Load `~/.clemini/config.toml`, then merge the project-local
+/// `<cwd>/.clemini/config.toml` over it (so teams can check in model
+/// defaults, bash timeout, allowed paths, and tool settings per repo).
+fn load_config(cwd: &std::path::Path) -> Config {
+    merged_config_value(cwd)
+        .and_then(|value| value.try_into().ok())
         .unwrap_or_default()
 }
 
@@ -207,6 +419,86 @@ mod tests {
         assert_eq!(expand_tilde("/tmp"), PathBuf::from("/tmp"));
     }
 
+    #[test]
+    fn test_slash_command_suggestions_filters_by_prefix() {
+        let suggestions = slash_command_suggestions("/re", 3);
+        let values: Vec<&str> = suggestions.iter().map(|s| s.value.as_str()).collect();
+        assert!(values.contains(&"/review"));
+        assert!(values.contains(&"/rewind"));
+        assert!(!values.contains(&"/copy"));
+        assert!(
+            suggestions.iter().all(|s| s.description.is_some()),
+            "every suggestion should carry its help description"
+        );
+    }
+
+    #[test]
+    fn test_slash_command_suggestions_strips_argument_hints() {
+        let suggestions = slash_command_suggestions("/mo", 3);
+        let values: Vec<&str> = suggestions.iter().map(|s| s.value.as_str()).collect();
+        assert!(values.contains(&"/mode"), "got: {values:?}");
+        assert!(values.contains(&"/model"));
+    }
+
+    #[test]
+    fn test_get_help_text_lists_all_builtins() {
+        let help = get_help_text();
+        for (command, description) in BUILTIN_COMMANDS {
+            assert!(help.contains(command), "help is missing {command}");
+            assert!(help.contains(description));
+        }
+    }
+
+    #[test]
+    fn test_expand_file_mentions_existing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("notes.md"), "hi").unwrap();
+
+        let expanded = expand_file_mentions("summarize @notes.md please", dir.path());
+        assert!(expanded.starts_with("summarize @notes.md please"));
+        assert!(expanded.contains("read them before answering: notes.md"));
+    }
+
+    #[test]
+    fn test_expand_file_mentions_ignores_nonexistent() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = "email me at user@example.com";
+        assert_eq!(expand_file_mentions(input, dir.path()), input);
+    }
+
+    #[test]
+    fn test_file_mention_candidates_ranks_substring_first() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("main.rs"), "").unwrap();
+        std::fs::write(dir.path().join("m_a_i_n_spread.txt"), "").unwrap();
+        std::fs::write(dir.path().join("other.txt"), "").unwrap();
+
+        let candidates = file_mention_candidates(dir.path(), "main");
+        assert_eq!(candidates[0], "main.rs");
+        assert!(candidates.contains(&"m_a_i_n_spread.txt".to_string()));
+        assert!(!candidates.contains(&"other.txt".to_string()));
+    }
+
+    #[test]
+    fn test_last_code_block_picks_last_fence() {
+        let text = "First:\n```rust\nfn one() {}\n```\nThen:\n```python\nprint(2)\n```\ndone";
+        assert_eq!(last_code_block(text), Some("print(2)".to_string()));
+    }
+
+    #[test]
+    fn test_last_code_block_none_without_fences() {
+        assert_eq!(last_code_block("just prose\nno code here"), None);
+    }
+
+    #[test]
+    fn test_last_code_block_unclosed_fence() {
+        let text = "Output:\n```\ntruncated mid-stream";
+        assert_eq!(
+            last_code_block(text),
+            Some("truncated mid-stream".to_string())
+        );
+    }
+
     #[test]
     fn test_config_defaults() {
         let config = Config::default();
@@ -231,6 +523,527 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_expand_prompt_template() {
+        let cwd = PathBuf::from("/work/project");
+        let expanded = expand_prompt_template(
+            "dir={{cwd}} model={{model}} os={{os}} unknown={{nope}}",
+            &cwd,
+            "test-model",
+        );
+
+        assert!(expanded.contains("dir=/work/project"));
+        assert!(expanded.contains("model=test-model"));
+        assert!(expanded.contains(&format!("os={}", std::env::consts::OS)));
+        // Unknown placeholders pass through untouched
+        assert!(expanded.contains("unknown={{nope}}"));
+    }
+
+    #[test]
+    fn test_expand_prompt_template_date() {
+        let expanded = expand_prompt_template("today is {{date}}", &PathBuf::from("/"), "m");
+        let expected = chrono::Local::now().format("%Y-%m-%d").to_string();
+        assert_eq!(expanded, format!("today is {}", expected));
+    }
+
+    #[test]
+    fn test_git_branch_from_head_file() {
+        let temp = tempfile::tempdir().unwrap();
+
+        // No .git directory
+        assert!(git_branch(temp.path()).is_none());
+
+        let git_dir = temp.path().join(".git");
+        std::fs::create_dir(&git_dir).unwrap();
+        std::fs::write(git_dir.join("HEAD"), "ref: refs/heads/feature/x\n").unwrap();
+        assert_eq!(git_branch(temp.path()).as_deref(), Some("feature/x"));
+
+        // Detached HEAD (raw commit hash) has no branch
+        std::fs::write(git_dir.join("HEAD"), "a1b2c3d4\n").unwrap();
+        assert!(git_branch(temp.path()).is_none());
+    }
+
+    #[test]
+    fn test_config_retry_section() {
+        let toml_str = r#"
+            [retry]
+            max_attempts = 5
+            initial_delay_ms = 250
+            jitter = false
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.retry.max_attempts, Some(5));
+        assert_eq!(config.retry.initial_delay_ms, Some(250));
+        assert!(config.retry.max_delay_ms.is_none());
+        assert_eq!(config.retry.jitter, Some(false));
+
+        // Section is optional
+        let config: Config = toml::from_str("").unwrap();
+        assert!(config.retry.max_attempts.is_none());
+    }
+
+    #[test]
+    fn test_config_model_routing_section() {
+        let toml_str = r#"
+            [models]
+            web_fetch = "gemini-flash-lite-latest"
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            config.models.web_fetch,
+            Some("gemini-flash-lite-latest".to_string())
+        );
+        assert!(config.models.task.is_none());
+
+        // Section is optional
+        let config: Config = toml::from_str("").unwrap();
+        assert!(config.models.web_fetch.is_none());
+    }
+
+    #[test]
+    fn test_config_bash_safety_section() {
+        let toml_str = r#"
+            [bash]
+            blocked = ['terraform\s+destroy']
+            caution = ['kubectl\s+delete']
+            allow = ['git\s+push\s+.*--force-with-lease']
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.bash.blocked, vec![r"terraform\s+destroy"]);
+        assert_eq!(config.bash.caution, vec![r"kubectl\s+delete"]);
+        assert_eq!(
+            config.bash.allow,
+            vec![r"git\s+push\s+.*--force-with-lease"]
+        );
+
+        // Section and individual keys are optional
+        let config: Config = toml::from_str("[bash]\nallow = ['foo']").unwrap();
+        assert!(config.bash.blocked.is_empty());
+        assert_eq!(config.bash.allow, vec!["foo"]);
+        let config: Config = toml::from_str("").unwrap();
+        assert!(config.bash.blocked.is_empty());
+    }
+
+    #[test]
+    fn test_config_theme_section() {
+        let toml_str = r#"
+            [theme]
+            preset = "light"
+            user_prompt = "magenta"
+            diff_insert_bg = [220, 255, 220]
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.theme.preset.as_deref(), Some("light"));
+        assert_eq!(config.theme.user_prompt.as_deref(), Some("magenta"));
+        assert_eq!(config.theme.diff_insert_bg, Some([220, 255, 220]));
+        assert!(config.theme.diff_delete_bg.is_none());
+
+        // Section is optional
+        let config: Config = toml::from_str("").unwrap();
+        assert!(config.theme.preset.is_none());
+    }
+
+    #[test]
+    fn test_keybinding_mode_parse() {
+        assert_eq!(KeybindingMode::parse("vim"), Some(KeybindingMode::Vim));
+        assert_eq!(KeybindingMode::parse("vi"), Some(KeybindingMode::Vim));
+        assert_eq!(KeybindingMode::parse("emacs"), Some(KeybindingMode::Emacs));
+        assert_eq!(
+            KeybindingMode::parse("default"),
+            Some(KeybindingMode::Emacs)
+        );
+        assert_eq!(KeybindingMode::parse("kakoune"), None);
+    }
+
+    #[test]
+    fn test_dedup_history_keeps_most_recent_occurrence() {
+        let raw = "ls\ncargo test\nls\ngit status\n";
+        assert_eq!(
+            dedup_history_lines(raw, 100),
+            "cargo test\nls\ngit status\n"
+        );
+    }
+
+    #[test]
+    fn test_dedup_history_trims_to_max_entries() {
+        let raw = "one\ntwo\nthree\nfour\n";
+        // Newest entries win when trimming
+        assert_eq!(dedup_history_lines(raw, 2), "three\nfour\n");
+    }
+
+    #[test]
+    fn test_dedup_history_empty_input() {
+        assert_eq!(dedup_history_lines("", 10), "");
+        assert_eq!(dedup_history_lines("\n\n", 10), "");
+    }
+
+    #[test]
+    fn test_prepare_history_seeds_from_legacy() {
+        let dir = tempfile::tempdir().unwrap();
+        let legacy = dir.path().join("history.txt");
+        let project = dir.path().join("project.txt");
+        std::fs::write(&legacy, "old command\nold command\nnewer\n").unwrap();
+
+        prepare_history_file(&project, &legacy, 100);
+        assert_eq!(
+            std::fs::read_to_string(&project).unwrap(),
+            "old command\nnewer\n"
+        );
+
+        // An existing project file is not overwritten by the legacy one
+        std::fs::write(&legacy, "unrelated\n").unwrap();
+        prepare_history_file(&project, &legacy, 100);
+        assert_eq!(
+            std::fs::read_to_string(&project).unwrap(),
+            "old command\nnewer\n"
+        );
+    }
+
+    #[test]
+    fn test_config_lsp_section() {
+        let toml_str = r#"
+            [lsp.servers]
+            rust = "rust-analyzer"
+            python = "pyright-langserver --stdio"
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            config.lsp.servers.get("rust").map(String::as_str),
+            Some("rust-analyzer")
+        );
+        assert_eq!(
+            config.lsp.servers.get("python").map(String::as_str),
+            Some("pyright-langserver --stdio")
+        );
+
+        // Section is optional
+        let config: Config = toml::from_str("").unwrap();
+        assert!(config.lsp.servers.is_empty());
+    }
+
+    #[test]
+    fn test_config_repo_map_tokens() {
+        let config: Config = toml::from_str("repo_map_tokens = 2048").unwrap();
+        assert_eq!(config.repo_map_tokens, Some(2048));
+
+        // 0 disables the map; unset falls back to the default budget
+        let config: Config = toml::from_str("repo_map_tokens = 0").unwrap();
+        assert_eq!(config.repo_map_tokens, Some(0));
+        let config: Config = toml::from_str("").unwrap();
+        assert!(config.repo_map_tokens.is_none());
+    }
+
+    #[test]
+    fn test_config_interaction_timeout() {
+        let config: Config = toml::from_str("interaction_timeout = 300").unwrap();
+        assert_eq!(config.interaction_timeout, Some(300));
+
+        // Unset means no limit
+        let config: Config = toml::from_str("").unwrap();
+        assert!(config.interaction_timeout.is_none());
+    }
+
+    #[test]
+    fn test_config_git_checkpoints() {
+        let config: Config = toml::from_str("git_checkpoints = true").unwrap();
+        assert_eq!(config.git_checkpoints, Some(true));
+
+        // Unset means off
+        let config: Config = toml::from_str("").unwrap();
+        assert!(config.git_checkpoints.is_none());
+    }
+
+    #[test]
+    fn test_probe_project_rust() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Cargo.toml"), "[package]").unwrap();
+        std::fs::write(dir.path().join("Makefile"), "check:").unwrap();
+
+        let probe = probe_project(dir.path());
+        assert_eq!(probe.language, Some("Rust"));
+        assert_eq!(probe.build, Some("cargo build"));
+        assert!(probe.has_makefile);
+    }
+
+    #[test]
+    fn test_probe_project_typescript_with_pnpm() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("package.json"), "{}").unwrap();
+        std::fs::write(dir.path().join("tsconfig.json"), "{}").unwrap();
+        std::fs::write(dir.path().join("pnpm-lock.yaml"), "").unwrap();
+
+        let probe = probe_project(dir.path());
+        assert_eq!(probe.language, Some("TypeScript"));
+        assert_eq!(probe.test, Some("pnpm test"));
+    }
+
+    #[test]
+    fn test_probe_project_unknown_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(probe_project(dir.path()), ProjectProbe::default());
+    }
+
+    #[test]
+    fn test_starter_claude_md_includes_detected_commands() {
+        let probe = ProjectProbe {
+            language: Some("Go"),
+            build: Some("go build ./..."),
+            test: Some("go test ./..."),
+            lint: Some("go vet ./..."),
+            has_makefile: false,
+        };
+        let md = starter_claude_md(&probe);
+        assert!(md.starts_with("# CLAUDE.md"));
+        assert!(md.contains("A Go project."));
+        assert!(md.contains("go test ./..."));
+        assert!(md.contains("TODO"));
+    }
+
+    #[test]
+    fn test_starter_claude_md_unknown_project_keeps_todos() {
+        let md = starter_claude_md(&ProjectProbe::default());
+        assert!(md.contains("TODO: describe what this project does."));
+        assert!(md.contains("# TODO: build and test commands"));
+    }
+
+    #[test]
+    fn test_starter_project_config_is_inert_and_parseable() {
+        // Every key is commented out, so the starter must deserialize to
+        // the defaults
+        let config: Config = toml::from_str(starter_project_config()).unwrap();
+        assert!(config.model.is_none());
+        assert!(config.bash_timeout.is_none());
+    }
+
+    #[test]
+    fn test_init_write_refuses_overwrite() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("CLAUDE.md");
+        std::fs::write(&path, "existing").unwrap();
+
+        init_write(&path, "new content").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "existing");
+    }
+
+    #[test]
+    fn test_first_line_truncates_multiline_errors() {
+        assert_eq!(first_line("bad value\n  |\n3 | x = y\n  ^"), "bad value");
+        assert_eq!(first_line("single"), "single");
+        assert_eq!(first_line(""), "");
+    }
+
+    #[test]
+    fn test_binary_version_missing_binary_is_none() {
+        assert!(binary_version("definitely-not-a-real-binary-xyz").is_none());
+    }
+
+    #[test]
+    fn test_parse_config_value_types() {
+        assert_eq!(parse_config_value("300"), toml::Value::Integer(300));
+        assert_eq!(parse_config_value("true"), toml::Value::Boolean(true));
+        assert_eq!(
+            parse_config_value("\"quoted\""),
+            toml::Value::String("quoted".to_string())
+        );
+        // Bare words that aren't valid TOML fall back to strings
+        assert_eq!(
+            parse_config_value("gemini-3-flash-preview"),
+            toml::Value::String("gemini-3-flash-preview".to_string())
+        );
+        assert_eq!(
+            parse_config_value("[\"a\", \"b\"]"),
+            toml::Value::Array(vec![
+                toml::Value::String("a".to_string()),
+                toml::Value::String("b".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_toml_get_dotted_key() {
+        let value: toml::Value =
+            toml::from_str("model = \"m\"\n[retry]\nmax_attempts = 5").unwrap();
+        assert_eq!(
+            toml_get(&value, "retry.max_attempts"),
+            Some(&toml::Value::Integer(5))
+        );
+        assert_eq!(
+            toml_get(&value, "model"),
+            Some(&toml::Value::String("m".to_string()))
+        );
+        assert!(toml_get(&value, "retry.missing").is_none());
+        assert!(toml_get(&value, "nope").is_none());
+    }
+
+    #[test]
+    fn test_toml_set_creates_nested_tables() {
+        let mut doc = toml::Value::Table(toml::map::Map::new());
+        toml_set(&mut doc, "retry.max_attempts", toml::Value::Integer(5)).unwrap();
+        assert_eq!(
+            toml_get(&doc, "retry.max_attempts"),
+            Some(&toml::Value::Integer(5))
+        );
+    }
+
+    #[test]
+    fn test_toml_set_rejects_scalar_parent() {
+        let mut doc: toml::Value = toml::from_str("model = \"m\"").unwrap();
+        assert!(toml_set(&mut doc, "model.nested", toml::Value::Integer(1)).is_err());
+    }
+
+    #[test]
+    fn test_config_schema_validation() {
+        // Wrong type for a known key is rejected
+        let mut doc = toml::Value::Table(toml::map::Map::new());
+        toml_set(&mut doc, "bash_timeout", parse_config_value("fast")).unwrap();
+        assert!(doc.try_into::<Config>().is_err());
+
+        // A well-typed nested key passes
+        let mut doc = toml::Value::Table(toml::map::Map::new());
+        toml_set(&mut doc, "retry.max_attempts", parse_config_value("5")).unwrap();
+        assert!(doc.try_into::<Config>().is_ok());
+    }
+
+    #[test]
+    fn test_session_path_rejects_traversal() {
+        assert!(session_path("../etc/passwd").is_err());
+        assert!(session_path("a/b").is_err());
+        assert!(session_path("a\\b").is_err());
+        assert!(session_path("").is_err());
+    }
+
+    #[test]
+    fn test_format_age_units() {
+        use std::time::Duration;
+        assert_eq!(format_age(Duration::from_secs(42)), "42s");
+        assert_eq!(format_age(Duration::from_secs(5 * 60)), "5m");
+        assert_eq!(format_age(Duration::from_secs(3 * 3600)), "3h");
+        assert_eq!(format_age(Duration::from_secs(2 * 86400)), "2d");
+    }
+
+    #[test]
+    fn test_format_size_units() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(3 * 1024 + 512), "3.5 KB");
+        assert_eq!(format_size(1024 * 1024 + 1024 * 200), "1.2 MB");
+    }
+
+    #[tokio::test]
+    async fn test_arm_interaction_timeout_cancels_token() {
+        let token = CancellationToken::new();
+        let fired = arm_interaction_timeout(&token, Some(std::time::Duration::from_millis(10)));
+
+        // The flag is set before the token is cancelled, so once cancellation
+        // is observed the flag must read true
+        token.cancelled().await;
+        assert!(fired.load(std::sync::atomic::Ordering::Relaxed));
+    }
+
+    #[tokio::test]
+    async fn test_arm_interaction_timeout_none_never_fires() {
+        let token = CancellationToken::new();
+        let fired = arm_interaction_timeout(&token, None);
+
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        assert!(!token.is_cancelled());
+        assert!(!fired.load(std::sync::atomic::Ordering::Relaxed));
+    }
+
+    #[test]
+    fn test_format_timeout_report() {
+        let result = agent::InteractionResult {
+            id: Some("int-123".to_string()),
+            response: "partial".to_string(),
+            context_size: 0,
+            total_tokens: 0,
+            usage: agent::TokenUsage::default(),
+            tool_calls: vec!["read_file".to_string(), "bash".to_string()],
+            tool_stats: Default::default(),
+            needs_confirmation: None,
+            cancelled: true,
+        };
+        assert_eq!(
+            format_timeout_report(300, &result),
+            "[interaction timed out after 300s: 2 tool call(s) completed; \
+             resume with --interaction int-123]"
+        );
+
+        let no_id = agent::InteractionResult { id: None, ..result };
+        assert_eq!(
+            format_timeout_report(60, &no_id),
+            "[interaction timed out after 60s: 2 tool call(s) completed; \
+             no interaction to resume]"
+        );
+    }
+
+    #[test]
+    fn test_merge_toml_scalar_override() {
+        let mut base: toml::Value =
+            toml::from_str("model = \"global\"\nbash_timeout = 120").unwrap();
+        let overlay: toml::Value = toml::from_str("model = \"project\"").unwrap();
+        merge_toml(&mut base, overlay);
+
+        let config: Config = base.try_into().unwrap();
+        assert_eq!(config.model.as_deref(), Some("project"));
+        assert_eq!(
+            config.bash_timeout,
+            Some(120),
+            "unset keys keep the global value"
+        );
+    }
+
+    #[test]
+    fn test_merge_toml_tables_merge_recursively() {
+        let mut base: toml::Value =
+            toml::from_str("[retry]\nmax_attempts = 5\ninitial_delay_ms = 500").unwrap();
+        let overlay: toml::Value = toml::from_str("[retry]\nmax_attempts = 2").unwrap();
+        merge_toml(&mut base, overlay);
+
+        let config: Config = base.try_into().unwrap();
+        assert_eq!(config.retry.max_attempts, Some(2));
+        assert_eq!(
+            config.retry.initial_delay_ms,
+            Some(500),
+            "sibling keys in the same section survive"
+        );
+    }
+
+    #[test]
+    fn test_merge_toml_arrays_replace() {
+        let mut base: toml::Value = toml::from_str("allowed_paths = [\"/a\", \"/b\"]").unwrap();
+        let overlay: toml::Value = toml::from_str("allowed_paths = [\"/c\"]").unwrap();
+        merge_toml(&mut base, overlay);
+
+        let config: Config = base.try_into().unwrap();
+        assert_eq!(
+            config.allowed_paths,
+            vec!["/c"],
+            "arrays are replaced, not appended"
+        );
+    }
+
+    #[test]
+    fn test_load_config_project_only() {
+        // No global entanglement: exercise the project side of load_config
+        // through the same merge path with no base.
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".clemini")).unwrap();
+        std::fs::write(dir.path().join(".clemini/config.toml"), "bash_timeout = 42").unwrap();
+
+        let value = read_config_toml(&dir.path().join(".clemini/config.toml")).unwrap();
+        let config: Config = value.try_into().unwrap();
+        assert_eq!(config.bash_timeout, Some(42));
+    }
+
+    #[test]
+    fn test_read_config_toml_unparseable_is_none() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "not [ valid").unwrap();
+        assert!(read_config_toml(&path).is_none());
+        assert!(read_config_toml(&dir.path().join("absent.toml")).is_none());
+    }
+
     #[test]
     fn test_config_deserialization_override() {
         let toml_str = r#"
@@ -388,24 +1201,936 @@ struct Args {
     #[arg(short, long)]
     model: Option<String>,
 
-    /// Continue from a previous interaction ID
-    #[arg(short, long)]
-    interaction: Option<String>,
+    /// Continue from a previous interaction ID
+    #[arg(short, long)]
+    interaction: Option<String>,
+
+    /// Only expose these tools to the model (comma-separated declared names, e.g. "read_file,grep,glob")
+    #[arg(long, value_delimiter = ',')]
+    allowed_tools: Option<Vec<String>>,
+
+    /// Hide these tools from the model (comma-separated declared names, e.g. "web_fetch,web_search")
+    #[arg(long, value_delimiter = ',')]
+    disallowed_tools: Option<Vec<String>>,
+
+    /// Append extra instructions to the system prompt (used by `task` agent
+    /// profiles to assign a subagent its role)
+    #[arg(long, value_name = "TEXT")]
+    append_system_prompt: Option<String>,
+
+    /// Cap the number of model turns per interaction (overrides the
+    /// max_turns config key)
+    #[arg(long, value_name = "N")]
+    max_turns: Option<usize>,
+
+    /// Cumulative token budget for the interaction, input + output across
+    /// all turns (used by `task` subagent budgets to bound delegation cost)
+    #[arg(long, value_name = "N")]
+    max_tokens: Option<u32>,
+
+    /// Preview mode: write/edit report diffs as if they succeeded and bash
+    /// commands are skipped, without touching disk
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Permission mode for mutating tools: auto (run, the default), ask
+    /// (prompt per call with a diff preview), or read-only (reject)
+    #[arg(long, value_name = "MODE")]
+    permission_mode: Option<String>,
+
+    /// Record a shadow git checkpoint commit (under refs/clemini/checkpoints)
+    /// after each turn that runs a mutating tool; overrides config
+    #[arg(long)]
+    git_checkpoints: bool,
+
+    /// Wall-clock limit in seconds for the interaction; on expiry it is
+    /// cancelled cleanly and partial progress is reported
+    #[arg(long, value_name = "SECONDS")]
+    max_time: Option<u64>,
+
+    /// Start as an MCP server (stdio mode)
+    #[arg(long)]
+    mcp_server: bool,
+
+    /// Start as an ACP server (Agent Client Protocol)
+    #[arg(long)]
+    acp_server: bool,
+
+    #[command(subcommand)]
+    command: Option<Commands>,
+}
+
+#[derive(clap::Subcommand)]
+enum Commands {
+    /// Export the most recent session transcript (Markdown, or JSON for .json paths)
+    Export {
+        /// Destination path (format chosen by extension)
+        output: PathBuf,
+    },
+    /// Manage autosaved session transcripts
+    Sessions {
+        #[command(subcommand)]
+        action: SessionsAction,
+    },
+    /// Read and write config without hand-editing TOML
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Check the environment for common problems (API key, binaries,
+    /// config, logs, terminal) and print actionable fixes
+    Doctor,
+    /// Bootstrap project context: generate a starter CLAUDE.md and
+    /// .clemini/config.toml from what the repo's marker files reveal
+    Init,
+}
+
+#[derive(clap::Subcommand)]
+enum ConfigAction {
+    /// Print one value from the merged config (dotted keys reach into
+    /// sections, e.g. `retry.max_attempts`)
+    Get {
+        /// Config key, dotted for nested sections
+        key: String,
+    },
+    /// Set a value, validating the result against the config schema
+    Set {
+        /// Config key, dotted for nested sections
+        key: String,
+        /// New value, parsed as TOML (bare words become strings)
+        value: String,
+        /// Write the project-local .clemini/config.toml instead of the global one
+        #[arg(long)]
+        project: bool,
+    },
+    /// Print the merged effective config as TOML
+    List,
+    /// Open the config file in $EDITOR, validating it afterwards
+    Edit {
+        /// Edit the project-local .clemini/config.toml instead of the global one
+        #[arg(long)]
+        project: bool,
+    },
+}
+
+#[derive(clap::Subcommand)]
+enum SessionsAction {
+    /// List saved sessions with age, size, and workspace
+    List,
+    /// Print a saved session as Markdown
+    Show {
+        /// Session ID from `clemini sessions list`
+        id: String,
+    },
+    /// Delete a saved session
+    Delete {
+        /// Session ID from `clemini sessions list`
+        id: String,
+    },
+    /// Start the REPL continuing from a saved session's last interaction
+    Resume {
+        /// Session ID from `clemini sessions list`
+        id: String,
+    },
+}
+
+/// Cancel `token` after `timeout` elapses so a hung interaction can't block
+/// forever (e.g., a CI pipeline). Returns a flag set when the timeout fires,
+/// so callers can distinguish a timeout from a user ctrl-c. `None` disables
+/// the timeout and the flag never fires.
+fn arm_interaction_timeout(
+    token: &CancellationToken,
+    timeout: Option<std::time::Duration>,
+) -> Arc<std::sync::atomic::AtomicBool> {
+    let fired = Arc::new(std::sync::atomic::AtomicBool::new(false));
+    if let Some(timeout) = timeout {
+        let token = token.clone();
+        let fired = fired.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = tokio::time::sleep(timeout) => {
+                    fired.store(true, std::sync::atomic::Ordering::Relaxed);
+                    token.cancel();
+                }
+                // Interaction finished (or was cancelled) first - stand down
+                _ = token.cancelled() => {}
+            }
+        });
+    }
+    fired
+}
+
+/// Summarize how far a timed-out interaction got, including the interaction
+/// ID so the next prompt can resume from the partial work.
+fn format_timeout_report(timeout_secs: u64, result: &agent::InteractionResult) -> String {
+    let resume = match &result.id {
+        Some(id) => format!("resume with --interaction {id}"),
+        None => "no interaction to resume".to_string(),
+    };
+    format!(
+        "[interaction timed out after {}s: {} tool call(s) completed; {}]",
+        timeout_secs,
+        result.tool_calls.len(),
+        resume
+    )
+}
+
+/// Record a shadow git checkpoint for the turn when enabled and the
+/// interaction actually ran a mutating tool. Failures are logged, not fatal -
+/// a checkpoint problem shouldn't interrupt the session.
+fn maybe_git_checkpoint(enabled: bool, cwd: &std::path::Path, result: &agent::InteractionResult) {
+    if !enabled
+        || !result
+            .tool_stats
+            .keys()
+            .any(|tool| !clemini::tools::tool_is_read_only(tool))
+    {
+        return;
+    }
+    match clemini::git_checkpoint::record(cwd, result.id.as_deref()) {
+        Ok(Some(commit)) => tracing::info!("Recorded git checkpoint {commit}"),
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Failed to record git checkpoint: {e}"),
+    }
+}
+
+/// Directory where REPL sessions autosave their transcripts.
+fn transcripts_dir() -> PathBuf {
+    clemini_dir().join("transcripts")
+}
+
+/// Autosave the session transcript as JSON for later `clemini export`.
+/// Failures are logged, not fatal - losing a transcript shouldn't break exit.
+fn autosave_transcript(transcript: &Arc<std::sync::Mutex<TranscriptRecorder>>) {
+    let mut recorder = match transcript.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    if recorder.is_empty() {
+        return;
+    }
+    let path = transcripts_dir().join(format!(
+        "session-{}.json",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    if let Err(e) = recorder.save(&path) {
+        tracing::warn!("Failed to autosave transcript to {:?}: {}", path, e);
+    }
+}
+
+/// Export the most recently autosaved transcript to `output`.
+fn export_latest_transcript(output: &std::path::Path) -> Result<()> {
+    let dir = transcripts_dir();
+    let latest = std::fs::read_dir(&dir)
+        .ok()
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+        .max_by_key(|e| {
+            e.metadata()
+                .and_then(|m| m.modified())
+                .unwrap_or(std::time::SystemTime::UNIX_EPOCH)
+        })
+        .map(|e| e.path())
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "No saved transcripts in {} (transcripts autosave when a REPL session ends)",
+                dir.display()
+            )
+        })?;
+
+    let content = std::fs::read_to_string(&latest)?;
+    if output.extension().is_some_and(|ext| ext == "json") {
+        std::fs::write(output, &content)?;
+    } else {
+        let entries = clemini::transcript::parse_json(&content).map_err(anyhow::Error::msg)?;
+        std::fs::write(output, clemini::transcript::render_markdown(&entries))?;
+    }
+    eprintln!("Exported {} to {}", latest.display(), output.display());
+    Ok(())
+}
+
+/// Resolve a session ID (with or without `.json`) to its transcript path.
+/// Rejects anything that could escape the transcripts directory.
+fn session_path(id: &str) -> Result<PathBuf> {
+    if id.is_empty() || id.contains('/') || id.contains('\\') || id.contains("..") {
+        anyhow::bail!("Invalid session ID '{}'", id);
+    }
+    let file = if id.ends_with(".json") {
+        id.to_string()
+    } else {
+        format!("{id}.json")
+    };
+    let path = transcripts_dir().join(file);
+    if !path.exists() {
+        anyhow::bail!(
+            "No session '{}' in {} (see `clemini sessions list`)",
+            id,
+            transcripts_dir().display()
+        );
+    }
+    Ok(path)
+}
+
+/// Format a session age compactly: `42s`, `5m`, `3h`, `2d`.
+fn format_age(age: std::time::Duration) -> String {
+    let secs = age.as_secs();
+    match secs {
+        0..60 => format!("{}s", secs),
+        60..3600 => format!("{}m", secs / 60),
+        3600..86400 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
+}
+
+/// Format a file size compactly: `512 B`, `3.4 KB`, `1.2 MB`.
+fn format_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
+
+/// List autosaved sessions, newest first, with age, size, and workspace.
+fn list_sessions() -> Result<()> {
+    let dir = transcripts_dir();
+    let mut sessions: Vec<(String, std::time::SystemTime, u64, String)> = std::fs::read_dir(&dir)
+        .ok()
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+        .map(|e| {
+            let path = e.path();
+            let id = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let meta = e.metadata().ok();
+            let modified = meta
+                .as_ref()
+                .and_then(|m| m.modified().ok())
+                .unwrap_or(std::time::SystemTime::UNIX_EPOCH);
+            let size = meta.map(|m| m.len()).unwrap_or(0);
+            // Pre-cwd transcripts (and unparseable files) show "-"
+            let cwd = std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok())
+                .and_then(|v| v.get("cwd").and_then(|c| c.as_str()).map(String::from))
+                .unwrap_or_else(|| "-".to_string());
+            (id, modified, size, cwd)
+        })
+        .collect();
+    if sessions.is_empty() {
+        eprintln!(
+            "No saved sessions in {} (transcripts autosave when a REPL session ends)",
+            dir.display()
+        );
+        return Ok(());
+    }
+    sessions.sort_by(|a, b| b.1.cmp(&a.1));
+
+    let id_width = sessions
+        .iter()
+        .map(|s| s.0.len())
+        .max()
+        .unwrap_or(0)
+        .max("SESSION".len());
+    println!("{:<id_width$}  {:>4}  {:>8}  CWD", "SESSION", "AGE", "SIZE");
+    for (id, modified, size, cwd) in sessions {
+        let age = modified
+            .elapsed()
+            .map(format_age)
+            .unwrap_or_else(|_| "-".to_string());
+        println!(
+            "{:<id_width$}  {:>4}  {:>8}  {}",
+            id,
+            age,
+            format_size(size),
+            cwd
+        );
+    }
+    Ok(())
+}
+
+/// Print a saved session as Markdown on stdout.
+fn show_session(id: &str) -> Result<()> {
+    let path = session_path(id)?;
+    let content = std::fs::read_to_string(&path)?;
+    let entries = clemini::transcript::parse_json(&content).map_err(anyhow::Error::msg)?;
+    print!("{}", clemini::transcript::render_markdown(&entries));
+    Ok(())
+}
+
+/// Delete a saved session transcript.
+fn delete_session(id: &str) -> Result<()> {
+    let path = session_path(id)?;
+    std::fs::remove_file(&path)?;
+    eprintln!("Deleted {}", path.display());
+    Ok(())
+}
+
+/// The interaction ID to continue from when resuming a saved session.
+fn resume_interaction_id(id: &str) -> Result<String> {
+    let path = session_path(id)?;
+    let content = std::fs::read_to_string(&path)?;
+    let entries = clemini::transcript::parse_json(&content).map_err(anyhow::Error::msg)?;
+    clemini::transcript::last_interaction_id(&entries).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Session '{}' has no completed interaction to resume (try `clemini sessions show {}`)",
+            id,
+            id
+        )
+    })
+}
+
+/// The config file a `clemini config` invocation writes: project-local or
+/// global.
+fn config_file(project: bool, cwd: &std::path::Path) -> Result<PathBuf> {
+    if project {
+        Ok(cwd.join(".clemini").join("config.toml"))
+    } else {
+        home::home_dir()
+            .map(|p| p.join(".clemini").join("config.toml"))
+            .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))
+    }
+}
+
+/// Look up a dotted key (`retry.max_attempts`) in a TOML value.
+fn toml_get<'a>(value: &'a toml::Value, key: &str) -> Option<&'a toml::Value> {
+    key.split('.').try_fold(value, |v, part| v.get(part))
+}
+
+/// Set a dotted key in a TOML document, creating intermediate tables.
+fn toml_set(root: &mut toml::Value, key: &str, new: toml::Value) -> Result<()> {
+    let parts: Vec<&str> = key.split('.').collect();
+    let mut current = root;
+    for part in &parts[..parts.len() - 1] {
+        let table = current
+            .as_table_mut()
+            .ok_or_else(|| anyhow::anyhow!("'{}' is not a table", key))?;
+        current = table
+            .entry(part.to_string())
+            .or_insert_with(|| toml::Value::Table(toml::map::Map::new()));
+    }
+    let leaf = parts.last().expect("split always yields at least one part");
+    let table = current
+        .as_table_mut()
+        .ok_or_else(|| anyhow::anyhow!("'{}' is not a table", key))?;
+    table.insert(leaf.to_string(), new);
+    Ok(())
+}
+
+/// Parse a CLI value string as TOML; bare words fall back to strings, so
+/// `set model gemini-3-flash-preview` works without quoting.
+fn parse_config_value(raw: &str) -> toml::Value {
+    toml::from_str::<toml::Value>(&format!("v = {raw}"))
+        .ok()
+        .and_then(|mut v| v.as_table_mut().and_then(|t| t.remove("v")))
+        .unwrap_or_else(|| toml::Value::String(raw.to_string()))
+}
+
+/// Print one value from the merged effective config.
+fn config_get(cwd: &std::path::Path, key: &str) -> Result<()> {
+    let merged = merged_config_value(cwd)
+        .ok_or_else(|| anyhow::anyhow!("No config found (see `clemini config list`)"))?;
+    match toml_get(&merged, key) {
+        // Strings print bare so output is pipeable; everything else as TOML
+        Some(toml::Value::String(s)) => println!("{}", s),
+        Some(value) => println!("{}", value),
+        None => anyhow::bail!("Key '{}' is not set (see `clemini config list`)", key),
+    }
+    Ok(())
+}
+
+/// Set a config key, refusing to write a document the `Config` schema
+/// rejects (wrong types, malformed sections).
+fn config_set(cwd: &std::path::Path, key: &str, value: &str, project: bool) -> Result<()> {
+    let path = config_file(project, cwd)?;
+    let mut doc =
+        read_config_toml(&path).unwrap_or_else(|| toml::Value::Table(toml::map::Map::new()));
+    toml_set(&mut doc, key, parse_config_value(value))?;
+
+    if let Err(e) = doc.clone().try_into::<Config>() {
+        anyhow::bail!("Rejected: {} = {} fails validation: {}", key, value, e);
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, toml::to_string_pretty(&doc)?)?;
+    eprintln!("Set {} = {} in {}", key, value, path.display());
+    Ok(())
+}
+
+/// Print the merged effective config as a TOML document.
+fn config_list(cwd: &std::path::Path) -> Result<()> {
+    match merged_config_value(cwd) {
+        Some(merged) => print!("{}", toml::to_string_pretty(&merged)?),
+        None => eprintln!(
+            "No config found (create ~/.clemini/config.toml or .clemini/config.toml, \
+             or use `clemini config set`)"
+        ),
+    }
+    Ok(())
+}
+
+/// Open the config file in `$VISUAL`/`$EDITOR` (fallback: vi) and validate
+/// the result. Invalid TOML is a warning, not an error - the file is the
+/// user's to fix, and load_config already ignores unparseable configs.
+fn config_edit(cwd: &std::path::Path, project: bool) -> Result<()> {
+    let path = config_file(project, cwd)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let status = std::process::Command::new(&editor).arg(&path).status()?;
+    if !status.success() {
+        anyhow::bail!("{} exited with {}", editor, status);
+    }
+
+    if path.exists() {
+        let raw = std::fs::read_to_string(&path)?;
+        match toml::from_str::<Config>(&raw) {
+            Ok(_) => eprintln!("{} is valid", path.display()),
+            Err(e) => eprintln!("Warning: {} fails validation: {}", path.display(), e),
+        }
+    }
+    Ok(())
+}
+
+/// Collects `clemini doctor` results, printing each check as it runs.
+struct Doctor {
+    failures: usize,
+}
+
+impl Doctor {
+    fn new() -> Self {
+        Self { failures: 0 }
+    }
+
+    fn pass(&self, name: &str, detail: &str) {
+        println!("ok    {}: {}", name, detail);
+    }
+
+    fn fail(&mut self, name: &str, problem: &str, fix: &str) {
+        self.failures += 1;
+        println!("FAIL  {}: {}", name, problem);
+        println!("      fix: {}", fix);
+    }
+
+    fn info(&self, name: &str, detail: &str) {
+        println!("info  {}: {}", name, detail);
+    }
+}
+
+/// First line of a multi-line error message (TOML errors include a caret
+/// rendering that would wreck the one-line-per-check layout).
+fn first_line(text: &str) -> &str {
+    text.lines().next().unwrap_or(text)
+}
+
+/// Version string of an external binary, or `None` if it isn't runnable.
+fn binary_version(bin: &str) -> Option<String> {
+    let out = std::process::Command::new(bin)
+        .arg("--version")
+        .output()
+        .ok()?;
+    out.status.success().then(|| {
+        String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .to_string()
+    })
+}
+
+/// Cheap reachability check: a one-word `countTokens` call (the same
+/// endpoint `TokenCounter` uses), which validates both the key and the
+/// configured model without burning generation quota.
+async fn ping_gemini(api_key: &str, model: &str) -> Result<(), String> {
+    let client = tools::create_http_client()?;
+    let url = format!(
+        "https://generativelanguage.googleapis.com/v1beta/models/{}:countTokens?key={}",
+        model, api_key
+    );
+    let resp = client
+        .post(&url)
+        .json(&serde_json::json!({"contents": [{"parts": [{"text": "ping"}]}]}))
+        .send()
+        .await
+        .map_err(|e| format!("request failed: {}", e))?;
+    let status = resp.status();
+    if status.is_success() {
+        Ok(())
+    } else {
+        let body = resp.text().await.unwrap_or_default();
+        let detail = serde_json::from_str::<serde_json::Value>(&body)
+            .ok()
+            .and_then(|v| {
+                v.pointer("/error/message")
+                    .and_then(|m| m.as_str())
+                    .map(String::from)
+            })
+            .unwrap_or_else(|| status.to_string());
+        Err(format!("HTTP {}: {}", status.as_u16(), first_line(&detail)))
+    }
+}
+
+/// Run the environment checks behind `clemini doctor`. Each check prints a
+/// pass/fail line; failures come with a fix and make the command exit
+/// non-zero so it can gate scripts.
+async fn run_doctor(cwd: &std::path::Path) -> Result<()> {
+    let mut doctor = Doctor::new();
+
+    // Config files parse against the schema (load_config silently falls
+    // back to defaults on errors, which is exactly what doctor surfaces)
+    let config_paths = [
+        home::home_dir().map(|p| p.join(".clemini").join("config.toml")),
+        Some(cwd.join(".clemini").join("config.toml")),
+    ];
+    let mut found_config = false;
+    for path in config_paths.into_iter().flatten() {
+        let Ok(raw) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        found_config = true;
+        match toml::from_str::<Config>(&raw) {
+            Ok(_) => doctor.pass("config", &format!("{} parses", path.display())),
+            Err(e) => doctor.fail(
+                "config",
+                &format!("{}: {}", path.display(), first_line(&e.to_string())),
+                "fix the key shown above, or run `clemini config edit`",
+            ),
+        }
+    }
+    if !found_config {
+        doctor.info("config", "none found, defaults in use");
+    }
+
+    // API key validity via a cheap countTokens ping
+    let model = load_config(cwd)
+        .model
+        .unwrap_or_else(|| DEFAULT_MODEL.to_string());
+    match env::var("GEMINI_API_KEY") {
+        Err(_) => doctor.fail(
+            "GEMINI_API_KEY",
+            "not set",
+            "export GEMINI_API_KEY=<key> (create one at https://aistudio.google.com/apikey)",
+        ),
+        Ok(key) => match ping_gemini(&key, &model).await {
+            Ok(()) => doctor.pass("GEMINI_API_KEY", &format!("valid, {} reachable", model)),
+            Err(e) => doctor.fail(
+                "GEMINI_API_KEY",
+                &e,
+                "check the key at https://aistudio.google.com/apikey and the `model` config value",
+            ),
+        },
+    }
+
+    // External binaries the agent leans on
+    match binary_version("git") {
+        Some(version) => doctor.pass("git", &version),
+        None => doctor.fail(
+            "git",
+            "not found on PATH",
+            "install git (checkpoints, /diff, and repo context need it)",
+        ),
+    }
+    match binary_version("rg") {
+        Some(version) => doctor.pass("rg", &version),
+        None => doctor.fail(
+            "rg",
+            "not found on PATH",
+            "install ripgrep (the model reaches for it in bash): https://github.com/BurntSushi/ripgrep",
+        ),
+    }
+    match binary_version("gh") {
+        Some(version) => doctor.pass("gh", first_line(&version)),
+        None => doctor.info(
+            "gh",
+            "not found (optional; install https://cli.github.com for PR/issue workflows)",
+        ),
+    }
+
+    // Log directory writability
+    let log_dir = clemini_dir().join("logs");
+    let probe = log_dir.join(".doctor-probe");
+    let writable = std::fs::create_dir_all(&log_dir)
+        .and_then(|_| std::fs::write(&probe, b"ok"))
+        .and_then(|_| std::fs::remove_file(&probe));
+    match writable {
+        Ok(()) => doctor.pass("logs", &format!("{} is writable", log_dir.display())),
+        Err(e) => doctor.fail(
+            "logs",
+            &format!("{}: {}", log_dir.display(), e),
+            "check ownership/permissions on ~/.clemini",
+        ),
+    }
+
+    // Terminal capabilities (informational)
+    let term = env::var("TERM").unwrap_or_else(|_| "unset".to_string());
+    let colors = if env::var_os("NO_COLOR").is_some() || term == "dumb" {
+        "colors off"
+    } else {
+        "colors on"
+    };
+    doctor.info("terminal", &format!("TERM={}, {}", term, colors));
+    doctor.info(
+        "inline images",
+        if clemini::term_image::terminal_supports_kitty_graphics() {
+            "supported (kitty graphics protocol)"
+        } else {
+            "not supported, image tools fall back to text summaries"
+        },
+    );
+
+    // API error pressure from today's log
+    let log_path = log_dir.join(format!(
+        "clemini.log.{}",
+        chrono::Local::now().format("%Y-%m-%d")
+    ));
+    match std::fs::read_to_string(&log_path) {
+        Ok(content) => {
+            let retries = content
+                .lines()
+                .filter(|l| l.to_lowercase().contains("retry"))
+                .count();
+            if retries > 0 {
+                doctor.info(
+                    "api retries",
+                    &format!(
+                        "{} retry line(s) in today's log - frequent retries usually mean \
+                         rate limiting (429) or an overloaded model (503)",
+                        retries
+                    ),
+                );
+            } else {
+                doctor.info("api retries", "none in today's log");
+            }
+        }
+        Err(_) => doctor.info("api retries", "no log for today"),
+    }
+
+    if doctor.failures > 0 {
+        anyhow::bail!("{} problem(s) found", doctor.failures);
+    }
+    eprintln!("\nNo problems found");
+    Ok(())
+}
+
+/// What `clemini init` detected about a repository from its marker files.
+#[derive(Debug, Default, PartialEq)]
+struct ProjectProbe {
+    language: Option<&'static str>,
+    build: Option<&'static str>,
+    test: Option<&'static str>,
+    lint: Option<&'static str>,
+    has_makefile: bool,
+}
+
+/// Detect language, build system, test command, and lint tooling from
+/// marker files. First match wins; a Makefile is noted alongside whatever
+/// else is found since it's usually the intended entry point.
+fn probe_project(cwd: &std::path::Path) -> ProjectProbe {
+    let exists = |name: &str| cwd.join(name).exists();
+    let mut probe = ProjectProbe {
+        has_makefile: exists("Makefile") || exists("makefile"),
+        ..Default::default()
+    };
+
+    if exists("Cargo.toml") {
+        probe.language = Some("Rust");
+        probe.build = Some("cargo build");
+        probe.test = Some("cargo test");
+        probe.lint = Some("cargo clippy --all-targets -- -D warnings && cargo fmt --check");
+    } else if exists("package.json") {
+        probe.language = Some(if exists("tsconfig.json") {
+            "TypeScript"
+        } else {
+            "JavaScript"
+        });
+        let runner = if exists("pnpm-lock.yaml") {
+            ("pnpm build", "pnpm test", "pnpm lint")
+        } else if exists("yarn.lock") {
+            ("yarn build", "yarn test", "yarn lint")
+        } else {
+            ("npm run build", "npm test", "npm run lint")
+        };
+        probe.build = Some(runner.0);
+        probe.test = Some(runner.1);
+        probe.lint = Some(runner.2);
+    } else if exists("go.mod") {
+        probe.language = Some("Go");
+        probe.build = Some("go build ./...");
+        probe.test = Some("go test ./...");
+        probe.lint = Some("go vet ./...");
+    } else if exists("pyproject.toml") || exists("setup.py") || exists("requirements.txt") {
+        probe.language = Some("Python");
+        probe.test = Some("pytest");
+        probe.lint = Some("ruff check .");
+    }
+    probe
+}
+
+/// Render the starter CLAUDE.md from a probe. TODO markers flag the parts
+/// only a human (or a longer model session) can fill in.
+fn starter_claude_md(probe: &ProjectProbe) -> String {
+    let mut out = String::from(
+        "# CLAUDE.md\n\n\
+         This file gives coding agents context about the project. Generated by\n\
+         `clemini init` from the repo's marker files - edit it to taste.\n\n\
+         ## Project Overview\n\n",
+    );
+    match probe.language {
+        Some(language) => out.push_str(&format!(
+            "A {} project. TODO: describe what it does in a sentence or two.\n",
+            language
+        )),
+        None => out.push_str("TODO: describe what this project does.\n"),
+    }
+
+    out.push_str("\n## Build & Test\n\n```bash\n");
+    if probe.has_makefile {
+        out.push_str("make        # see the Makefile for targets\n");
+    }
+    for (label, command) in [
+        ("build", probe.build),
+        ("test", probe.test),
+        ("lint", probe.lint),
+    ] {
+        if let Some(command) = command {
+            out.push_str(&format!("{:<12}# {}\n", command, label));
+        }
+    }
+    if !probe.has_makefile && probe.build.is_none() && probe.test.is_none() {
+        out.push_str("# TODO: build and test commands\n");
+    }
+    out.push_str("```\n");
+
+    out.push_str(
+        "\n## Conventions\n\n\
+         - TODO: code style, module layout, and error handling patterns\n\
+         - TODO: what must pass before a change is considered done\n",
+    );
+    out
+}
 
-    /// Start as an MCP server (stdio mode)
-    #[arg(long)]
-    mcp_server: bool,
+/// Starter project-local config: every key commented out, so checking it in
+/// changes nothing until someone uncomments a line.
+fn starter_project_config() -> &'static str {
+    "# Project-local clemini config, merged over ~/.clemini/config.toml\n\
+     # (tables merge key by key; scalars and arrays replace).\n\n\
+     # model = \"gemini-3-flash-preview\"\n\
+     # bash_timeout = 120\n\
+     # allowed_paths = []\n\
+     # git_checkpoints = true\n"
+}
 
-    /// Start as an ACP server (Agent Client Protocol)
-    #[arg(long)]
-    acp_server: bool,
+/// Write `content` to `path` unless it already exists; reports either way.
+fn init_write(path: &std::path::Path, content: &str) -> Result<()> {
+    if path.exists() {
+        eprintln!("{} already exists, leaving it alone", path.display());
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, content)?;
+    eprintln!("Wrote {}", path.display());
+    Ok(())
+}
+
+/// Bootstrap project context files for `clemini init`.
+fn run_init(cwd: &std::path::Path) -> Result<()> {
+    let probe = probe_project(cwd);
+    match probe.language {
+        Some(language) => eprintln!("Detected {} project", language),
+        None => eprintln!("No recognized project markers; generating a skeleton"),
+    }
+    init_write(&cwd.join("CLAUDE.md"), &starter_claude_md(&probe))?;
+    init_write(
+        &cwd.join(".clemini").join("config.toml"),
+        starter_project_config(),
+    )?;
+    eprintln!("Fill in the TODOs - clemini sends CLAUDE.md with every turn");
+    Ok(())
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     init_logging();
-    let args = Args::parse();
-    let config = load_config();
+    let mut args = Args::parse();
+
+    // Subcommands that don't need an API key or tool service
+    if let Some(Commands::Export { output }) = &args.command {
+        return export_latest_transcript(output);
+    }
+    if let Some(Commands::Config { action }) = &args.command {
+        // Project config lives under the (possibly not-yet-canonical) cwd
+        let cwd = std::fs::canonicalize(&args.cwd).unwrap_or_else(|_| PathBuf::from(&args.cwd));
+        return match action {
+            ConfigAction::Get { key } => config_get(&cwd, key),
+            ConfigAction::Set {
+                key,
+                value,
+                project,
+            } => config_set(&cwd, key, value, *project),
+            ConfigAction::List => config_list(&cwd),
+            ConfigAction::Edit { project } => config_edit(&cwd, *project),
+        };
+    }
+    if let Some(Commands::Doctor) = &args.command {
+        let cwd = std::fs::canonicalize(&args.cwd).unwrap_or_else(|_| PathBuf::from(&args.cwd));
+        return run_doctor(&cwd).await;
+    }
+    if let Some(Commands::Init) = &args.command {
+        let cwd = std::fs::canonicalize(&args.cwd)?;
+        return run_init(&cwd);
+    }
+    let mut resumed = None;
+    if let Some(Commands::Sessions { action }) = &args.command {
+        match action {
+            SessionsAction::List => return list_sessions(),
+            SessionsAction::Show { id } => return show_session(id),
+            SessionsAction::Delete { id } => return delete_session(id),
+            // Resume falls through to normal REPL startup with the saved
+            // session's last interaction ID
+            SessionsAction::Resume { id } => {
+                let interaction_id = resume_interaction_id(id)?;
+                eprintln!("[resuming session {} from {}]", id, interaction_id);
+                resumed = Some(interaction_id);
+            }
+        }
+    }
+    if resumed.is_some() {
+        args.interaction = resumed;
+    }
+
+    let cwd = std::fs::canonicalize(&args.cwd)?;
+
+    let config = load_config(&cwd);
+
+    // Install the color theme before anything renders (diffs, code blocks,
+    // and the REPL prompt all read the process-wide theme).
+    theme::set_active(theme::Theme::from_config(&config.theme));
+
+    // REPL input keybinding flavor; unknown values fall back to emacs.
+    let keybinding_mode = match config.keybindings.as_deref() {
+        Some(name) => KeybindingMode::parse(name).unwrap_or_else(|| {
+            tracing::warn!("Unknown keybindings '{}', using emacs", name);
+            KeybindingMode::default()
+        }),
+        None => KeybindingMode::default(),
+    };
+    let history_size = config.history_size.unwrap_or(10_000);
 
     let model = args
         .model
@@ -418,8 +2143,6 @@ async fn main() -> Result<()> {
         .map_err(|e| anyhow::anyhow!("GEMINI_API_KEY environment variable not set: {}", e))?;
     let client = Client::new(api_key.clone());
 
-    let cwd = std::fs::canonicalize(&args.cwd)?;
-
     // Resolve allowed paths
     let mut allowed_paths = Vec::new();
     // Always allowed: CWD and tmp
@@ -442,20 +2165,183 @@ async fn main() -> Result<()> {
     ));
     // Note: events_tx is set per-interaction via tool_service.set_events_tx()
 
-    let mut system_prompt = SYSTEM_PROMPT.to_string();
+    // Reattach background tasks persisted by previous processes so
+    // `task_output bg-1` keeps working across restarts (and clean up
+    // entries whose processes died in the meantime).
+    tools::tasks::reattach_persisted_tasks();
+
+    // Tool allow/deny lists: CLI flags override config. Applies to all modes
+    // (REPL, single-prompt, MCP, ACP) since they share the tool service.
+    let allowed_tools = args.allowed_tools.clone().or(config.allowed_tools.clone());
+    let disallowed_tools = args
+        .disallowed_tools
+        .clone()
+        .or(config.disallowed_tools.clone())
+        .unwrap_or_default();
+    if allowed_tools.is_some() || !disallowed_tools.is_empty() {
+        let filter = ToolFilter::new(allowed_tools, disallowed_tools);
+        let declared: Vec<String> = tool_service
+            .tools()
+            .iter()
+            .map(|t| t.declaration().name().to_string())
+            .collect();
+        let declared: Vec<&str> = declared.iter().map(String::as_str).collect();
+        for name in filter.unknown_names(&declared) {
+            tracing::warn!("Tool filter references unknown tool: {}", name);
+        }
+        tool_service.set_tool_filter(filter);
+    }
+
+    if args.dry_run {
+        tool_service.set_dry_run(true);
+        eprintln!("[dry-run mode: mutations will be previewed, not applied]");
+    }
+
+    // Permission mode gating mutating tools (--permission-mode, later
+    // adjustable via /mode in the REPL).
+    if let Some(mode) = &args.permission_mode {
+        match PermissionMode::parse(mode) {
+            Some(mode) => {
+                tool_service.set_permission_mode(mode);
+                if mode != PermissionMode::Auto {
+                    eprintln!("[permission mode: {}]", mode.as_str());
+                }
+            }
+            None => {
+                anyhow::bail!("Unknown permission mode '{mode}'. Use auto, ask, or read-only.")
+            }
+        }
+    }
+
+    // Route internal LLM operations (web_fetch extraction, task subagents) to
+    // cheaper models when configured via the [models] config section.
+    tool_service.set_model_routing(config.models.clone());
+
+    // Merge user-configured bash safety patterns ([bash] config section)
+    // with the built-in defaults.
+    tool_service.set_safety_policy(SafetyPolicy::from_config(&config.bash));
+
+    // Per-language server overrides for the lsp tool ([lsp] config section).
+    tool_service.set_lsp_config(config.lsp.clone());
+
+    // Web search backend (search_provider/search_api_key config keys).
+    tool_service.set_search_config(SearchConfig {
+        provider: config.search_provider.clone(),
+        api_key: config.search_api_key.clone(),
+        engine_id: config.search_engine_id.clone(),
+    });
+
+    // Cache TTL for web_fetch (web_cache_ttl config key).
+    tool_service.set_web_cache_ttl(config.web_cache_ttl);
+
+    // Headless rendering opt-in for web_fetch (web_render config key).
+    tool_service.set_web_render(config.web_render.unwrap_or(false));
+
+    // Host allowlist for http_request (http_allowed_hosts config key).
+    tool_service.set_http_allowed_hosts(config.http_allowed_hosts.clone());
+
+    // Per-tool timeouts ([timeouts] config section).
+    tool_service.set_timeouts(config.timeouts.clone());
+
+    // Secrets redaction for tool results (redact_patterns config key adds
+    // user patterns on top of the built-ins).
+    tool_service.set_redact_patterns(config.redact_patterns.as_deref().unwrap_or(&[]));
+
+    // Custom user-defined tools from ~/.clemini/tools.toml, plus any
+    // project-local .clemini/tools.toml (project definitions win on name).
+    tool_service.set_custom_tools(CustomToolsToml::load_for(&cwd).tools);
+
+    // Named subagent profiles for the task tool ([agents] config sections).
+    tool_service.set_agent_profiles(config.agents.clone());
+
+    // Subagent delegation guardrails ([task] config section).
+    tool_service.set_task_limits(config.task.clone());
+
+    let mut base_system_prompt =
+        expand_prompt_template(&load_system_prompt_template(), &cwd, &model);
+    // Global guidance accumulated by the `remember` tool, before the
+    // project context so project-specific instructions win on conflict.
+    if let Ok(clemini_md) = std::fs::read_to_string(tools::remember::global_guidance_path()) {
+        let clemini_md = clemini_md.trim();
+        if !clemini_md.is_empty() {
+            base_system_prompt.push_str("\n\n## Global Guidance\n\n");
+            base_system_prompt.push_str(clemini_md);
+        }
+    }
     if let Ok(claude_md) = std::fs::read_to_string(cwd.join("CLAUDE.md")) {
         let claude_md = claude_md.trim();
         if !claude_md.is_empty() {
-            system_prompt.push_str("\n\n## Project Context\n\n");
-            system_prompt.push_str(claude_md);
+            base_system_prompt.push_str("\n\n## Project Context\n\n");
+            base_system_prompt.push_str(claude_md);
         }
     }
 
+    // Durable notes stored by the `memory` tool in previous sessions
+    // (~/.clemini/memory/<project-hash>.md).
+    if let Some(memory) = tools::memory::load_memory(&cwd) {
+        base_system_prompt.push_str("\n\n## Project Memory\n\nNotes you stored in previous sessions with the `memory` tool:\n\n");
+        base_system_prompt.push_str(&memory);
+    }
+
+    // Role instructions passed on the command line - how `task` agent
+    // profiles assign a subagent its role.
+    if let Some(extra) = &args.append_system_prompt {
+        base_system_prompt.push_str("\n\n## Role\n\n");
+        base_system_prompt.push_str(extra);
+    }
+
+    // Repository map: a ranked symbol overview of the workspace appended to
+    // the system prompt (repo_map_tokens = 0 disables it). The REPL
+    // re-injects per turn so the map tracks file changes; one-shot modes
+    // take the startup snapshot.
+    let repo_map_tokens = config
+        .repo_map_tokens
+        .map(|t| t as usize)
+        .unwrap_or(repo_map::DEFAULT_REPO_MAP_TOKENS);
+    let mut repo_map =
+        (repo_map_tokens > 0).then(|| repo_map::RepoMap::new(cwd.clone(), repo_map_tokens));
+    let system_prompt = match repo_map.as_mut() {
+        Some(map) => map.inject(&base_system_prompt),
+        None => base_system_prompt.clone(),
+    };
+
+    let retry_defaults = agent::RetryConfig::default();
     let retry_config = agent::RetryConfig {
-        max_extra_retries: config.max_extra_retries.unwrap_or(2),
-        retry_delay_base: std::time::Duration::from_secs(config.retry_delay_base_secs.unwrap_or(1)),
+        // Config exposes total attempts; RetryConfig counts extra retries.
+        max_extra_retries: config
+            .retry
+            .max_attempts
+            .map(|attempts| attempts.saturating_sub(1))
+            .unwrap_or(retry_defaults.max_extra_retries),
+        retry_delay_base: config
+            .retry
+            .initial_delay_ms
+            .map(std::time::Duration::from_millis)
+            .unwrap_or(retry_defaults.retry_delay_base),
+        max_retry_delay: config
+            .retry
+            .max_delay_ms
+            .map(std::time::Duration::from_millis)
+            .unwrap_or(retry_defaults.max_retry_delay),
+        jitter: config.retry.jitter.unwrap_or(retry_defaults.jitter),
+        // CLI flag wins over config (task profiles pass --max-turns).
+        max_turns: args
+            .max_turns
+            .or(config.max_turns)
+            .unwrap_or(retry_defaults.max_turns),
+        max_consecutive_tool_failures: config
+            .max_consecutive_tool_failures
+            .unwrap_or(retry_defaults.max_consecutive_tool_failures),
+        // Task budgets pass --max-tokens; unset means unbudgeted.
+        max_total_tokens: args.max_tokens,
     };
 
+    // Wall-clock limit per interaction (CLI flag overrides config), in seconds
+    let interaction_timeout = args.max_time.or(config.interaction_timeout);
+
+    // Shadow git checkpoints per turn (CLI flag or config opt-in)
+    let git_checkpoints = args.git_checkpoints || config.git_checkpoints.unwrap_or(false);
+
     // MCP server mode - handle early before consuming stdin or printing banner
     if args.mcp_server {
         logging::set_output_sink(Arc::new(FileSink));
@@ -484,6 +2370,26 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
+    // Provider selection (CLI modes only; MCP/ACP servers always use Gemini)
+    let provider = clemini::provider::provider_from_config(
+        config.provider.as_deref().unwrap_or("gemini"),
+        config.provider_base_url.clone(),
+        config.provider_api_key.clone(),
+        &client,
+    )?;
+
+    // Real token counts for the context-size fallback (turns where the
+    // provider doesn't report usage); each count degrades to the heuristic
+    // on network failure, so a missing Gemini key just means estimates.
+    let token_counter: Arc<dyn clemini::tokens::TokenCounter> =
+        match clemini::tokens::GeminiTokenCounter::new(api_key.clone(), model.clone()) {
+            Ok(counter) => Arc::new(counter),
+            Err(e) => {
+                tracing::warn!("countTokens client unavailable, using heuristic: {e}");
+                Arc::new(clemini::tokens::HeuristicTokenCounter)
+            }
+        };
+
     eprintln!(
         "{}",
         clemini::format::format_startup_banner(
@@ -520,6 +2426,15 @@ async fn main() -> Result<()> {
         user_prompt
     };
 
+    // When resuming a previous interaction, show the todo list as it was
+    // left; the model can rehydrate it with todo_read.
+    if args.interaction.is_some()
+        && let Some(summary) = tools::todo_write::restore_summary(&cwd)
+    {
+        eprintln!("[restored todo list]");
+        eprintln!("{summary}");
+    }
+
     if let Some(prompt) = combined_prompt {
         logging::set_output_sink(Arc::new(TerminalSink));
         // Non-interactive mode: run single prompt
@@ -532,23 +2447,37 @@ async fn main() -> Result<()> {
             tracing::warn!("Failed to set ctrl-c handler: {}", e);
         }
 
+        let timeout_fired = arm_interaction_timeout(
+            &cancellation_token,
+            interaction_timeout.map(std::time::Duration::from_secs),
+        );
+
         // Create channel for agent events
         let (events_tx, mut events_rx) = mpsc::channel::<AgentEvent>(100);
 
+        let mut recorder = TranscriptRecorder::new();
+        recorder.set_cwd(&cwd);
+        recorder.record_user_prompt(&prompt);
+        let transcript = Arc::new(std::sync::Mutex::new(recorder));
+
         // Spawn task to handle events using EventHandler
         let model_for_handler = model.clone();
+        let transcript_for_handler = transcript.clone();
         let event_handler = tokio::spawn(async move {
             let mut handler = events::TerminalEventHandler::new(model_for_handler);
             while let Some(event) = events_rx.recv().await {
                 events::dispatch_event(&mut handler, &event);
+                if let Ok(mut recorder) = transcript_for_handler.lock() {
+                    recorder.record_event(&event);
+                }
             }
         });
 
         // Set events_tx for tools - guard clears it when dropped
         let _events_guard = tool_service.with_events_tx(events_tx.clone());
 
-        run_interaction(
-            &client,
+        let result = run_interaction_with_provider(
+            provider.as_ref(),
             &tool_service,
             &prompt,
             args.interaction.as_deref(),
@@ -557,25 +2486,44 @@ async fn main() -> Result<()> {
             events_tx,
             cancellation_token,
             retry_config,
+            agent::SteeringQueue::new(),
+            token_counter.clone(),
         )
         .await?;
 
+        if let Some(secs) = interaction_timeout
+            && timeout_fired.load(std::sync::atomic::Ordering::Relaxed)
+            && result.cancelled
+        {
+            eprintln!("\n{}", format_timeout_report(secs, &result).yellow());
+        }
+
+        maybe_git_checkpoint(git_checkpoints, &cwd, &result);
+
         // Drop events_guard to close the channel, allowing event handler to exit
         drop(_events_guard);
 
         // Wait for event handler to finish
         let _ = event_handler.await;
+
+        autosave_transcript(&transcript);
     } else {
         // Interactive REPL mode
         logging::set_output_sink(Arc::new(TerminalSink));
         run_plain_repl(
-            &client,
+            provider.as_ref(),
             &tool_service,
             cwd,
             &model,
-            system_prompt,
+            base_system_prompt,
+            repo_map,
             retry_config,
             args.interaction,
+            interaction_timeout,
+            git_checkpoints,
+            keybinding_mode,
+            history_size,
+            token_counter,
         )
         .await?;
     }
@@ -583,6 +2531,66 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Prepare the history file before reedline opens it: seed a new
+/// per-project file from the legacy shared `history.txt`, then deduplicate
+/// and trim so the file doesn't grow without bound (reedline rewrites it
+/// wholesale on every entry, so keeping it small matters).
+fn prepare_history_file(path: &Path, legacy: &Path, max_entries: usize) {
+    if !path.exists() && legacy.exists() {
+        let _ = std::fs::copy(legacy, path);
+    }
+    let Ok(raw) = std::fs::read_to_string(path) else {
+        return;
+    };
+    let deduped = dedup_history_lines(&raw, max_entries);
+    if deduped != raw {
+        let _ = std::fs::write(path, deduped);
+    }
+}
+
+/// Deduplicate history lines, keeping the most recent occurrence of each
+/// entry and at most `max_entries` lines (newest win). Order among kept
+/// lines is preserved.
+fn dedup_history_lines(raw: &str, max_entries: usize) -> String {
+    let mut seen = std::collections::HashSet::new();
+    let mut kept_rev: Vec<&str> = Vec::new();
+    for line in raw.lines().rev() {
+        if line.is_empty() {
+            continue;
+        }
+        if seen.insert(line) {
+            kept_rev.push(line);
+            if kept_rev.len() == max_entries {
+                break;
+            }
+        }
+    }
+    if kept_rev.is_empty() {
+        return String::new();
+    }
+    let mut out = kept_rev.into_iter().rev().collect::<Vec<&str>>().join("\n");
+    out.push('\n');
+    out
+}
+
+/// REPL input keybinding flavor (`keybindings` config key).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum KeybindingMode {
+    #[default]
+    Emacs,
+    Vim,
+}
+
+impl KeybindingMode {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "emacs" | "default" => Some(Self::Emacs),
+            "vim" | "vi" => Some(Self::Vim),
+            _ => None,
+        }
+    }
+}
+
 /// Events from the reedline input thread to the async REPL loop.
 enum InputEvent {
     /// User submitted a line of input.
@@ -632,6 +2640,8 @@ fn process_input(input: &str, model: &str, cwd: &std::path::Path) -> InputAction
 fn spawn_reedline_thread(
     cwd: PathBuf,
     model: String,
+    keybinding_mode: KeybindingMode,
+    history_size: usize,
 ) -> (
     mpsc::UnboundedReceiver<InputEvent>,
     std::sync::mpsc::Sender<()>,
@@ -640,8 +2650,13 @@ fn spawn_reedline_thread(
     let (ready_tx, ready_rx) = std::sync::mpsc::channel::<()>();
 
     std::thread::spawn(move || {
-        // Set up history file
-        let history_path = clemini_dir().join("history.txt");
+        // Per-project history file, keyed by workspace hash like the memory
+        // and todo files. New projects are seeded from the legacy shared
+        // history.txt so existing history isn't lost.
+        let canonical = cwd.canonicalize().unwrap_or_else(|_| cwd.clone());
+        let history_path = clemini_dir()
+            .join("history")
+            .join(format!("{}.txt", tools::memory::project_hash(&canonical)));
 
         // Ensure directory exists
         if let Some(parent) = history_path.parent()
@@ -653,8 +2668,13 @@ fn spawn_reedline_thread(
                 e
             );
         }
+        prepare_history_file(
+            &history_path,
+            &clemini_dir().join("history.txt"),
+            history_size,
+        );
 
-        let history = match FileBackedHistory::with_file(10_000, history_path.clone()) {
+        let history = match FileBackedHistory::with_file(history_size, history_path.clone()) {
             Ok(h) => Some(Box::new(h)),
             Err(e) => {
                 tracing::warn!(
@@ -666,22 +2686,53 @@ fn spawn_reedline_thread(
             }
         };
 
-        // Create reedline editor with custom keybindings
-        let mut keybindings = default_emacs_keybindings();
-        // Shift+Enter inserts a newline for multiline input
-        // Requires kitty keyboard protocol for Shift modifier detection
-        keybindings.add_binding(
-            KeyModifiers::SHIFT,
-            KeyCode::Enter,
-            ReedlineEvent::Edit(vec![EditCommand::InsertNewline]),
-        );
-        // Also bind Alt+Enter as fallback for terminals without kitty protocol
-        keybindings.add_binding(
-            KeyModifiers::ALT,
-            KeyCode::Enter,
-            ReedlineEvent::Edit(vec![EditCommand::InsertNewline]),
-        );
-        let edit_mode = Box::new(Emacs::new(keybindings));
+        // Layer clemini's bindings on the configured flavor. Vim mode gets
+        // them in insert mode and keeps reedline's stock vi normal-mode
+        // motions (hjkl, dd, ciw, ...); Esc switches modes as usual.
+        let add_repl_bindings = |keybindings: &mut Keybindings| {
+            // Shift+Enter inserts a newline for multiline input
+            // Requires kitty keyboard protocol for Shift modifier detection
+            keybindings.add_binding(
+                KeyModifiers::SHIFT,
+                KeyCode::Enter,
+                ReedlineEvent::Edit(vec![EditCommand::InsertNewline]),
+            );
+            // Also bind Alt+Enter as fallback for terminals without kitty protocol
+            keybindings.add_binding(
+                KeyModifiers::ALT,
+                KeyCode::Enter,
+                ReedlineEvent::Edit(vec![EditCommand::InsertNewline]),
+            );
+            // Tab opens the @file completion menu (or cycles it when already open)
+            keybindings.add_binding(
+                KeyModifiers::NONE,
+                KeyCode::Tab,
+                ReedlineEvent::UntilFound(vec![
+                    ReedlineEvent::Menu("completion_menu".to_string()),
+                    ReedlineEvent::MenuNext,
+                ]),
+            );
+            // Ctrl-R opens the interactive history search menu (type to
+            // filter, Enter to select) instead of the one-match-at-a-time
+            // inline search
+            keybindings.add_binding(
+                KeyModifiers::CONTROL,
+                KeyCode::Char('r'),
+                ReedlineEvent::Menu("history_menu".to_string()),
+            );
+        };
+        let edit_mode: Box<dyn EditMode> = match keybinding_mode {
+            KeybindingMode::Emacs => {
+                let mut keybindings = default_emacs_keybindings();
+                add_repl_bindings(&mut keybindings);
+                Box::new(Emacs::new(keybindings))
+            }
+            KeybindingMode::Vim => {
+                let mut insert = default_vi_insert_keybindings();
+                add_repl_bindings(&mut insert);
+                Box::new(Vi::new(insert, default_vi_normal_keybindings()))
+            }
+        };
 
         // Enable kitty keyboard protocol for better modifier key detection
         // (Shift+Enter, etc.) in supported terminals (iTerm2, kitty, WezTerm, alacritty)
@@ -694,7 +2745,14 @@ fn spawn_reedline_thread(
             .with_edit_mode(edit_mode)
             .use_kitty_keyboard_enhancement(use_kitty)
             // Enable bracketed paste so multiline pastes don't auto-submit on each newline
-            .use_bracketed_paste(true);
+            .use_bracketed_paste(true)
+            .with_completer(Box::new(FileMentionCompleter { cwd: cwd.clone() }))
+            .with_menu(ReedlineMenu::EngineCompleter(Box::new(
+                ColumnarMenu::default().with_name("completion_menu"),
+            )))
+            .with_menu(ReedlineMenu::HistoryMenu(Box::new(
+                ListMenu::default().with_name("history_menu"),
+            )));
         if let Some(h) = history {
             line_editor = line_editor.with_history(h);
         }
@@ -704,7 +2762,9 @@ fn spawn_reedline_thread(
 
         impl Prompt for SimplePrompt {
             fn render_prompt_left(&self) -> Cow<'_, str> {
-                Cow::Borrowed("〉")
+                // Themed via the [theme] config section; reedline accepts
+                // ANSI escapes in prompt strings.
+                Cow::Owned("〉".color(theme::active().user_prompt).to_string())
             }
 
             fn render_prompt_right(&self) -> Cow<'_, str> {
@@ -793,19 +2853,38 @@ fn spawn_reedline_thread(
 }
 
 /// Plain text REPL
+#[allow(clippy::too_many_arguments)]
 async fn run_plain_repl(
-    client: &Client,
+    provider: &dyn ModelProvider,
     tool_service: &Arc<CleminiToolService>,
     cwd: std::path::PathBuf,
     model: &str,
-    system_prompt: String,
+    base_system_prompt: String,
+    mut repo_map: Option<repo_map::RepoMap>,
     retry_config: agent::RetryConfig,
     initial_interaction_id: Option<String>,
+    interaction_timeout: Option<u64>,
+    git_checkpoints: bool,
+    keybinding_mode: KeybindingMode,
+    history_size: usize,
+    token_counter: Arc<dyn clemini::tokens::TokenCounter>,
 ) -> Result<()> {
     let mut last_interaction_id: Option<String> = initial_interaction_id;
+    let mut last_response: Option<String> = None;
+    // Last prompt sent plus the interaction ID it was sent against, for /retry.
+    let mut last_prompt: Option<(String, Option<String>)> = None;
+    let mut session_usage = agent::TokenUsage::default();
+    let mut recorder = TranscriptRecorder::new();
+    recorder.set_cwd(&cwd);
+    let transcript = Arc::new(std::sync::Mutex::new(recorder));
 
     // Spawn reedline input thread
-    let (mut input_rx, ready_tx) = spawn_reedline_thread(cwd.clone(), model.to_string());
+    let (mut input_rx, ready_tx) = spawn_reedline_thread(
+        cwd.clone(),
+        model.to_string(),
+        keybinding_mode,
+        history_size,
+    );
 
     loop {
         // Receive input from reedline thread
@@ -837,37 +2916,295 @@ async fn run_plain_repl(
             continue;
         }
 
+        if input == "/cost" {
+            eprint!(
+                "{}",
+                clemini::format::format_builtin_cost(&session_usage, model)
+            );
+            let _ = ready_tx.send(());
+            continue;
+        }
+
+        if input == "/stats" {
+            eprint!(
+                "{}",
+                clemini::format::format_builtin_stats(&tool_service.tool_stats())
+            );
+            let _ = ready_tx.send(());
+            continue;
+        }
+
+        if input == "/mode" || input.starts_with("/mode ") {
+            let rest = input.trim_start_matches("/mode").trim();
+            if rest.is_empty() {
+                eprintln!(
+                    "[permission mode: {}]",
+                    tool_service.permission_mode().as_str()
+                );
+            } else {
+                match PermissionMode::parse(rest) {
+                    Some(mode) => {
+                        tool_service.set_permission_mode(mode);
+                        eprintln!("[permission mode: {}]", mode.as_str());
+                    }
+                    None => eprintln!("Unknown mode '{rest}'. Use auto, ask, or read-only."),
+                }
+            }
+            let _ = ready_tx.send(());
+            continue;
+        }
+
+        if input == "/undo" {
+            match clemini::tools::checkpoint::undo_last(&cwd) {
+                Ok(Some(entry)) => eprintln!("[reverted {}]", entry.path),
+                Ok(None) => eprintln!("[nothing to undo]"),
+                Err(e) => eprintln!("[undo failed: {e}]"),
+            }
+            let _ = ready_tx.send(());
+            continue;
+        }
+
+        if input == "/rewind" || input.starts_with("/rewind ") {
+            let rest = input.trim_start_matches("/rewind").trim();
+            let n = if rest.is_empty() {
+                Some(1)
+            } else {
+                rest.parse::<usize>().ok()
+            };
+            match n {
+                Some(n) if n > 0 => match clemini::tools::checkpoint::rewind(&cwd, n) {
+                    Ok(restored) if restored.is_empty() => eprintln!("[nothing to undo]"),
+                    Ok(restored) => {
+                        for entry in restored {
+                            eprintln!("[reverted {}]", entry.path);
+                        }
+                    }
+                    Err(e) => eprintln!("[rewind failed: {e}]"),
+                },
+                _ => eprintln!("Usage: /rewind <n> (revert the last n writes/edits)"),
+            }
+            let _ = ready_tx.send(());
+            continue;
+        }
+
+        if input == "/review" {
+            if tool_service.review_mode() {
+                let (count, diff) = tool_service.review_pending();
+                if count == 0 {
+                    eprintln!("[review mode on - no pending changes]");
+                } else {
+                    eprintln!("{diff}");
+                    eprintln!("[{count} file(s) pending - /apply to write, /discard to drop]");
+                }
+            } else {
+                tool_service.set_review_mode(true);
+                eprintln!(
+                    "[review mode on - file edits accumulate until /apply or /discard; other mutating tools are blocked]"
+                );
+            }
+            let _ = ready_tx.send(());
+            continue;
+        }
+
+        if input == "/apply" {
+            if tool_service.review_mode() {
+                match tool_service.apply_review() {
+                    Ok(applied) if applied.is_empty() => {
+                        eprintln!("[no pending changes - review mode off]");
+                    }
+                    Ok(applied) => {
+                        for path in &applied {
+                            eprintln!("[applied {}]", path.display());
+                        }
+                        eprintln!("[review mode off]");
+                    }
+                    Err(e) => eprintln!("[apply failed: {e}; remaining changes still staged]"),
+                }
+            } else {
+                eprintln!("[review mode is off - nothing to apply]");
+            }
+            let _ = ready_tx.send(());
+            continue;
+        }
+
+        if input == "/discard" {
+            if tool_service.review_mode() {
+                let count = tool_service.discard_review();
+                eprintln!("[discarded {count} pending change(s) - review mode off]");
+            } else {
+                eprintln!("[review mode is off - nothing to discard]");
+            }
+            let _ = ready_tx.send(());
+            continue;
+        }
+
+        if input == "/copy" || input.starts_with("/copy ") {
+            let rest = input.trim_start_matches("/copy").trim();
+            if !rest.is_empty() && rest != "code" {
+                eprintln!("Usage: /copy [code] (last response, or its last fenced code block)");
+            } else {
+                let text = if rest == "code" {
+                    last_response.as_deref().and_then(last_code_block)
+                } else {
+                    last_response.clone()
+                };
+                match text {
+                    Some(text) if !text.is_empty() => match copy_to_clipboard(&text) {
+                        Ok(bytes) => eprintln!("[copied {bytes} bytes to clipboard]"),
+                        Err(e) => eprintln!("[copy failed: {e}]"),
+                    },
+                    _ if rest == "code" => eprintln!("[no code block in the last response]"),
+                    _ => eprintln!("[nothing to copy yet]"),
+                }
+            }
+            let _ = ready_tx.send(());
+            continue;
+        }
+
+        if input == "/save" || input.starts_with("/save ") {
+            let rest = input.strip_prefix("/save").unwrap_or("").trim();
+            let path = if rest.is_empty() {
+                format!(
+                    "clemini-session-{}.md",
+                    chrono::Local::now().format("%Y%m%d-%H%M%S")
+                )
+            } else {
+                rest.to_string()
+            };
+            let result = match transcript.lock() {
+                Ok(mut recorder) => recorder.save(std::path::Path::new(&path)),
+                Err(poisoned) => poisoned.into_inner().save(std::path::Path::new(&path)),
+            };
+            match result {
+                Ok(()) => eprintln!("[transcript saved to {path}]"),
+                Err(e) => eprintln!("[save failed: {e}]"),
+            }
+            let _ = ready_tx.send(());
+            continue;
+        }
+
+        if let Some(rest) = input.strip_prefix("/export") {
+            let path = rest.trim();
+            if path.is_empty() {
+                eprintln!("Usage: /export <path> (.json for JSON, anything else for Markdown)");
+            } else {
+                let result = match transcript.lock() {
+                    Ok(mut recorder) => recorder.save(std::path::Path::new(path)),
+                    Err(poisoned) => poisoned.into_inner().save(std::path::Path::new(path)),
+                };
+                match result {
+                    Ok(()) => eprintln!("[transcript exported to {path}]"),
+                    Err(e) => eprintln!("[export failed: {e}]"),
+                }
+            }
+            let _ = ready_tx.send(());
+            continue;
+        }
+
+        // /retry resends the stored (already-expanded) prompt against the
+        // interaction ID from before it first ran, so the failed turn drops
+        // out of the conversation instead of stacking on top of it.
+        let input = if input == "/retry" {
+            match &last_prompt {
+                Some((prompt, base_id)) => {
+                    last_interaction_id = base_id.clone();
+                    eprintln!("[retrying last prompt]");
+                    prompt.clone()
+                }
+                None => {
+                    eprintln!("[nothing to retry yet]");
+                    let _ = ready_tx.send(());
+                    continue;
+                }
+            }
+        } else {
+            // Expand @file mentions into a read instruction for the model
+            let expanded = expand_file_mentions(&input, &cwd);
+            last_prompt = Some((expanded.clone(), last_interaction_id.clone()));
+            expanded
+        };
+
+        if let Ok(mut recorder) = transcript.lock() {
+            recorder.record_user_prompt(&input);
+        }
+
         println!();
 
         // Use tokio's signal handling - works with async and can be called multiple times
         let cancellation_token = CancellationToken::new();
         let ct_for_signal = cancellation_token.clone();
 
-        // Spawn a task to listen for ctrl-c and cancel the token
+        // Spawn a task to listen for ctrl-c. Instead of cancelling outright,
+        // it offers a choice: type a correction to steer the running task at
+        // the next turn boundary, or press Enter to cancel as before.
+        let steering = agent::SteeringQueue::new();
+        let steering_for_signal = steering.clone();
         let signal_task = tokio::spawn(async move {
-            if tokio::signal::ctrl_c().await.is_ok() {
-                eprintln!("\n{}", clemini::format::format_ctrl_c().yellow());
-                ct_for_signal.cancel();
+            while tokio::signal::ctrl_c().await.is_ok() {
+                eprintln!(
+                    "\n{}",
+                    "[ctrl-c] type a message to steer the task, or press Enter to cancel".yellow()
+                );
+                // Blocking stdin read: the reedline thread isn't reading while
+                // an interaction runs (it waits on ready_rx), so this owns
+                // stdin the same way the bash confirmation prompt does. If the
+                // interaction finishes before the user answers, the pending
+                // read swallows one line - a rare, harmless hiccup.
+                let line = tokio::task::spawn_blocking(|| {
+                    let mut buf = String::new();
+                    std::io::stdin().read_line(&mut buf).map(|_| buf)
+                })
+                .await;
+                match line {
+                    Ok(Ok(text)) if !text.trim().is_empty() => {
+                        steering_for_signal.push(text.trim());
+                        eprintln!(
+                            "{}",
+                            "[steering queued; applies at the next turn boundary]".yellow()
+                        );
+                    }
+                    _ => {
+                        eprintln!("{}", clemini::format::format_ctrl_c().yellow());
+                        ct_for_signal.cancel();
+                        break;
+                    }
+                }
             }
         });
 
+        let timeout_fired = arm_interaction_timeout(
+            &cancellation_token,
+            interaction_timeout.map(std::time::Duration::from_secs),
+        );
+
         // Create channel for agent events
         let (events_tx, mut events_rx) = mpsc::channel::<AgentEvent>(100);
 
         // Spawn task to handle events using EventHandler
         let model_for_handler = model.to_string();
+        let transcript_for_handler = transcript.clone();
         let event_handler = tokio::spawn(async move {
             let mut handler = events::TerminalEventHandler::new(model_for_handler);
             while let Some(event) = events_rx.recv().await {
                 events::dispatch_event(&mut handler, &event);
+                if let Ok(mut recorder) = transcript_for_handler.lock() {
+                    recorder.record_event(&event);
+                }
             }
         });
 
         // Set events_tx for tools - guard clears it when dropped
         let _events_guard = tool_service.with_events_tx(events_tx.clone());
 
-        match run_interaction(
-            client,
+        // Re-inject the repo map so it reflects files changed since the
+        // last turn (served from cache when nothing changed)
+        let system_prompt = match repo_map.as_mut() {
+            Some(map) => map.inject(&base_system_prompt),
+            None => base_system_prompt.clone(),
+        };
+
+        match run_interaction_with_provider(
+            provider,
             tool_service,
             &input,
             last_interaction_id.as_deref(),
@@ -876,11 +3213,24 @@ async fn run_plain_repl(
             events_tx,
             cancellation_token,
             retry_config,
+            steering,
+            token_counter.clone(),
         )
         .await
         {
             Ok(result) => {
                 last_interaction_id = result.id.clone();
+                if !result.response.is_empty() {
+                    last_response = Some(result.response.clone());
+                }
+                session_usage.add(&result.usage);
+                if let Some(secs) = interaction_timeout
+                    && timeout_fired.load(std::sync::atomic::Ordering::Relaxed)
+                    && result.cancelled
+                {
+                    eprintln!("\n{}", format_timeout_report(secs, &result).yellow());
+                }
+                maybe_git_checkpoint(git_checkpoints, &cwd, &result);
             }
             Err(e) => {
                 eprintln!("\n{}", format!("[error: {e}]").bright_red());
@@ -900,6 +3250,8 @@ async fn run_plain_repl(
         let _ = ready_tx.send(());
     }
 
+    autosave_transcript(&transcript);
+
     Ok(())
 }
 
@@ -923,17 +3275,55 @@ fn handle_builtin_command(input: &str, model: &str, cwd: &std::path::Path) -> Op
     }
 }
 
+/// REPL builtin commands (display form with aliases and argument hints,
+/// description). Single source for `get_help_text()` and the slash-command
+/// completion menu.
+const BUILTIN_COMMANDS: &[(&str, &str)] = &[
+    ("/q, /quit, /exit", "Exit the REPL"),
+    ("/c, /clear", "Clear conversation history"),
+    ("/m, /model", "Show model name"),
+    ("/pwd, /cwd", "Show current working directory"),
+    ("/cost", "Show session token usage and cost"),
+    ("/stats", "Show per-tool usage statistics"),
+    (
+        "/mode [mode]",
+        "Show or set permission mode (auto, ask, read-only)",
+    ),
+    ("/undo", "Revert the last write/edit from its checkpoint"),
+    ("/rewind <n>", "Revert the last n writes/edits"),
+    ("/review", "Enter review mode / show the pending changeset"),
+    ("/apply", "Write all pending review changes to disk"),
+    ("/discard", "Drop all pending review changes"),
+    (
+        "/retry",
+        "Resend the last prompt, dropping the failed turn (Up arrow recalls it for editing)",
+    ),
+    (
+        "/copy [code]",
+        "Copy the last response (or its last code block) to the clipboard",
+    ),
+    (
+        "/save [path]",
+        "Save the transcript as Markdown (default: clemini-session-<timestamp>.md)",
+    ),
+    (
+        "/export <path>",
+        "Export session transcript (.json or Markdown)",
+    ),
+    ("/h, /help", "Show this help message"),
+];
+
 fn get_help_text() -> String {
-    [
-        "Commands:",
-        "  /q, /quit, /exit  Exit the REPL",
-        "  /c, /clear        Clear conversation history",
-        "  /m, /model        Show model name",
-        "  /pwd, /cwd        Show current working directory",
-        "  /h, /help         Show this help message",
+    let mut lines = vec!["Commands:".to_string()];
+    for (command, description) in BUILTIN_COMMANDS {
+        lines.push(format!("  {command:<18}{description}"));
+    }
+    for line in [
         "",
         "Controls:",
         "  Enter             Submit input",
+        "  Shift/Alt-Enter   Insert a newline (multiline input; pastes stay verbatim)",
+        "  @file + Tab       Complete a workspace file path to point the model at it",
         "  Ctrl-C            Cancel current operation / clear line",
         "  Ctrl-D            Quit",
         "  Up/Down           Navigate history",
@@ -941,14 +3331,164 @@ fn get_help_text() -> String {
         "",
         "Shell escape:",
         "  !<command>        Run a shell command directly",
-    ]
-    .join("\n")
+    ] {
+        lines.push(line.to_string());
+    }
+    lines.join("\n")
 }
 
 fn print_help() {
     eprint!("{}", clemini::format::format_builtin_help(&get_help_text()));
 }
 
+/// Reedline completer for `@file` mentions: when the token under the cursor
+/// starts with `@`, completes it against workspace file paths.
+struct FileMentionCompleter {
+    cwd: PathBuf,
+}
+
+impl Completer for FileMentionCompleter {
+    fn complete(&mut self, line: &str, pos: usize) -> Vec<Suggestion> {
+        let before = &line[..pos];
+        // Slash command completion: only for the first token of the line
+        if before.starts_with('/') && !before.contains(char::is_whitespace) {
+            return slash_command_suggestions(before, pos);
+        }
+        let start = before.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        let Some(partial) = before[start..].strip_prefix('@') else {
+            return Vec::new();
+        };
+        file_mention_candidates(&self.cwd, partial)
+            .into_iter()
+            .map(|path| Suggestion {
+                value: format!("@{path}"),
+                description: None,
+                style: None,
+                extra: None,
+                span: Span::new(start, pos),
+                append_whitespace: true,
+            })
+            .collect()
+    }
+}
+
+/// Completion suggestions for a partial slash command, one per builtin,
+/// with its help description shown alongside. Argument hints like
+/// `/mode [mode]` are stripped so selecting inserts just the command.
+fn slash_command_suggestions(partial: &str, pos: usize) -> Vec<Suggestion> {
+    let mut suggestions = Vec::new();
+    for (display, description) in BUILTIN_COMMANDS {
+        let matching = display
+            .split(", ")
+            .filter_map(|alias| alias.split_whitespace().next())
+            .filter(|command| command.starts_with(partial))
+            .max_by_key(|command| command.len());
+        if let Some(command) = matching {
+            suggestions.push(Suggestion {
+                value: command.to_string(),
+                description: Some(description.to_string()),
+                style: None,
+                extra: None,
+                span: Span::new(0, pos),
+                append_whitespace: false,
+            });
+        }
+    }
+    suggestions
+}
+
+/// Workspace files matching an `@` mention prefix, best matches first.
+/// Walks with gitignore rules (same as the search tools) and matches
+/// case-insensitively: substring hits rank before subsequence hits,
+/// shorter paths first within each tier.
+fn file_mention_candidates(cwd: &Path, partial: &str) -> Vec<String> {
+    const MAX_WALK: usize = 10_000;
+    const MAX_SUGGESTIONS: usize = 30;
+
+    let needle = partial.to_lowercase();
+    let mut substring: Vec<String> = Vec::new();
+    let mut subsequence: Vec<String> = Vec::new();
+    for entry in ignore::WalkBuilder::new(cwd)
+        .build()
+        .flatten()
+        .take(MAX_WALK)
+    {
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let Ok(rel) = entry.path().strip_prefix(cwd) else {
+            continue;
+        };
+        let rel = rel.to_string_lossy().to_string();
+        let hay = rel.to_lowercase();
+        if needle.is_empty() || hay.contains(&needle) {
+            substring.push(rel);
+        } else if is_subsequence(&needle, &hay) {
+            subsequence.push(rel);
+        }
+    }
+    substring.sort_by_key(String::len);
+    subsequence.sort_by_key(String::len);
+    substring.extend(subsequence);
+    substring.truncate(MAX_SUGGESTIONS);
+    substring
+}
+
+/// True if all of `needle`'s characters appear in `hay` in order.
+fn is_subsequence(needle: &str, hay: &str) -> bool {
+    let mut hay_chars = hay.chars();
+    needle.chars().all(|c| hay_chars.any(|h| h == c))
+}
+
+/// Rewrite `@path` file mentions into a trailing read instruction so the
+/// model opens the mentioned files instead of guessing at the bare token.
+/// Only tokens naming an existing file under `cwd` count as mentions.
+fn expand_file_mentions(input: &str, cwd: &Path) -> String {
+    let mentioned: Vec<&str> = input
+        .split_whitespace()
+        .filter_map(|token| token.strip_prefix('@'))
+        .filter(|rest| !rest.is_empty() && cwd.join(rest).is_file())
+        .collect();
+    if mentioned.is_empty() {
+        return input.to_string();
+    }
+    format!(
+        "{}\n\n(Mentioned files - read them before answering: {})",
+        input,
+        mentioned.join(", ")
+    )
+}
+
+/// Copy `text` to the system clipboard. Returns the byte count copied.
+fn copy_to_clipboard(text: &str) -> Result<usize, String> {
+    let mut clipboard = arboard::Clipboard::new().map_err(|e| e.to_string())?;
+    clipboard
+        .set_text(text.to_string())
+        .map_err(|e| e.to_string())?;
+    Ok(text.len())
+}
+
+/// Extract the last fenced code block from markdown text, without the fences.
+/// An unclosed trailing fence counts as a block (streaming can end mid-fence).
+fn last_code_block(text: &str) -> Option<String> {
+    let mut blocks: Vec<String> = Vec::new();
+    let mut current: Option<Vec<&str>> = None;
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            match current.take() {
+                Some(block) => blocks.push(block.join("\n")),
+                None => current = Some(Vec::new()),
+            }
+        } else if let Some(block) = current.as_mut() {
+            block.push(line);
+        }
+    }
+    if let Some(block) = current {
+        blocks.push(block.join("\n"));
+    }
+    blocks.pop()
+}
+
 fn run_shell_command_capture(command: &str) -> String {
     let output = if cfg!(target_os = "windows") {
         std::process::Command::new("cmd")
@@ -0,0 +1,605 @@
+//! Model provider abstraction.
+//!
+//! `run_interaction` talks to model backends through the `ModelProvider` trait
+//! rather than `genai_rs::Client` directly, so clemini can target backends
+//! beyond Gemini. Two providers ship today:
+//!
+//! - `GeminiProvider` - the default, wrapping `genai_rs::Client`
+//! - `OpenAiCompatibleProvider` - any OpenAI-compatible `/chat/completions`
+//!   endpoint, including local Ollama and llama.cpp servers
+//!
+//! Providers are selected via `provider = "..."` in `~/.clemini/config.toml`
+//! (`gemini`, `openai-compatible`, or `ollama`), with `provider_base_url` and
+//! `provider_api_key` for non-Gemini backends.
+//!
+//! # Interaction IDs
+//!
+//! Gemini stores conversation state server-side, addressed by interaction ID.
+//! OpenAI-compatible endpoints are stateless, so `OpenAiCompatibleProvider`
+//! keeps per-interaction message history in memory and issues its own IDs
+//! (`oai_<uuid>`), preserving the multi-turn semantics the agent loop relies
+//! on. These IDs do not survive a process restart.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use futures_util::StreamExt;
+use futures_util::stream::BoxStream;
+use genai_rs::{
+    Client, Content, FunctionDeclaration, GenaiError, InteractionResponse, InteractionStatus,
+    StreamChunk, StreamEvent,
+};
+use serde_json::{Value, json};
+use tokio::sync::mpsc;
+
+/// A tool result being sent back to the model.
+#[derive(Debug, Clone)]
+pub struct ToolResultContent {
+    pub name: String,
+    pub call_id: String,
+    pub result: Value,
+}
+
+/// Content for one turn of the agent loop, in provider-neutral form.
+/// Each provider lowers this to its own wire format.
+#[derive(Debug, Clone)]
+pub enum TurnContent {
+    /// The user's prompt (first turn).
+    UserText(String),
+    /// Tool results from the previous turn's function calls, plus any user
+    /// steering messages queued while those tools ran.
+    ToolResults {
+        results: Vec<ToolResultContent>,
+        steering: Vec<String>,
+    },
+}
+
+impl TurnContent {
+    /// Lower to genai-rs `Content` for the Gemini API.
+    pub fn to_gemini_content(&self) -> Vec<Content> {
+        match self {
+            TurnContent::UserText(text) => vec![Content::text(text)],
+            TurnContent::ToolResults { results, steering } => {
+                let mut content: Vec<Content> = results
+                    .iter()
+                    .map(|r| {
+                        Content::function_result(
+                            r.name.clone(),
+                            r.call_id.clone(),
+                            r.result.clone(),
+                        )
+                    })
+                    .collect();
+                // Steering messages follow the tool results as plain user text,
+                // so the model sees them at the turn boundary
+                content.extend(steering.iter().map(|message| Content::text(message)));
+                content
+            }
+        }
+    }
+}
+
+/// A single model request from the agent loop.
+pub struct ProviderRequest<'a> {
+    pub model: &'a str,
+    pub system_prompt: &'a str,
+    pub previous_interaction_id: Option<&'a str>,
+    pub functions: &'a [FunctionDeclaration],
+    pub content: TurnContent,
+}
+
+/// Abstraction over model backends.
+///
+/// Implementations translate a `ProviderRequest` into a stream of genai-rs
+/// `StreamEvent`s, which is the common currency of the agent loop
+/// (`process_interaction_stream` consumes them regardless of backend).
+pub trait ModelProvider: Send + Sync {
+    /// Provider name for logs and diagnostics (e.g. "gemini").
+    fn name(&self) -> &str;
+
+    /// Create a streaming request for one turn.
+    fn create_stream<'a>(
+        &'a self,
+        request: ProviderRequest<'a>,
+    ) -> BoxStream<'a, Result<StreamEvent, GenaiError>>;
+}
+
+// ============================================================================
+// GeminiProvider
+// ============================================================================
+
+/// The default provider, wrapping `genai_rs::Client`.
+pub struct GeminiProvider<'c> {
+    client: &'c Client,
+}
+
+impl<'c> GeminiProvider<'c> {
+    pub fn new(client: &'c Client) -> Self {
+        Self { client }
+    }
+}
+
+impl ModelProvider for GeminiProvider<'_> {
+    fn name(&self) -> &str {
+        "gemini"
+    }
+
+    fn create_stream<'a>(
+        &'a self,
+        request: ProviderRequest<'a>,
+    ) -> BoxStream<'a, Result<StreamEvent, GenaiError>> {
+        let mut interaction = self
+            .client
+            .interaction()
+            .with_model(request.model)
+            .add_functions(request.functions.to_vec())
+            .with_system_instruction(request.system_prompt);
+
+        if let Some(prev_id) = request.previous_interaction_id {
+            interaction = interaction.with_previous_interaction(prev_id);
+        }
+
+        interaction = interaction.with_content(request.content.to_gemini_content());
+
+        Box::pin(interaction.create_stream())
+    }
+}
+
+// ============================================================================
+// OpenAiCompatibleProvider
+// ============================================================================
+
+/// Provider for OpenAI-compatible `/chat/completions` endpoints.
+///
+/// Works with OpenAI itself, Ollama (`http://localhost:11434/v1`), llama.cpp's
+/// server, and other compatible gateways. Token usage is not reported for this
+/// provider yet, so cost accounting shows zero.
+pub struct OpenAiCompatibleProvider {
+    http: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+    /// Message history per interaction ID, emulating Gemini's server-side
+    /// conversation state. Excludes the system message (sent every turn).
+    histories: Arc<Mutex<HashMap<String, Vec<Value>>>>,
+}
+
+impl OpenAiCompatibleProvider {
+    pub fn new(base_url: impl Into<String>, api_key: Option<String>) -> Result<Self, String> {
+        Ok(Self {
+            http: crate::tools::create_http_client()?,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            api_key,
+            histories: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    /// Assemble the message list for one request: prior history (if any)
+    /// followed by this turn's content. The system message is prepended
+    /// separately and never stored in history.
+    fn build_messages(history: &[Value], content: &TurnContent) -> Vec<Value> {
+        let mut messages: Vec<Value> = history.to_vec();
+        match content {
+            TurnContent::UserText(text) => {
+                messages.push(json!({"role": "user", "content": text}));
+            }
+            TurnContent::ToolResults { results, steering } => {
+                for r in results {
+                    messages.push(json!({
+                        "role": "tool",
+                        "tool_call_id": r.call_id,
+                        "content": r.result.to_string(),
+                    }));
+                }
+                for message in steering {
+                    messages.push(json!({"role": "user", "content": message}));
+                }
+            }
+        }
+        messages
+    }
+
+    /// Map genai-rs function declarations to OpenAI tool definitions.
+    /// Both sides use `{name, description, parameters}` with JSON-schema
+    /// parameters; OpenAI just wraps it in a `function` envelope.
+    fn build_tools(functions: &[FunctionDeclaration]) -> Vec<Value> {
+        functions
+            .iter()
+            .filter_map(|decl| {
+                let function = serde_json::to_value(decl).ok()?;
+                Some(json!({"type": "function", "function": function}))
+            })
+            .collect()
+    }
+}
+
+impl ModelProvider for OpenAiCompatibleProvider {
+    fn name(&self) -> &str {
+        "openai-compatible"
+    }
+
+    fn create_stream<'a>(
+        &'a self,
+        request: ProviderRequest<'a>,
+    ) -> BoxStream<'a, Result<StreamEvent, GenaiError>> {
+        let history = request
+            .previous_interaction_id
+            .and_then(|id| self.histories.lock().unwrap().get(id).cloned())
+            .unwrap_or_default();
+
+        let messages = Self::build_messages(&history, &request.content);
+
+        let mut wire_messages = vec![json!({"role": "system", "content": request.system_prompt})];
+        wire_messages.extend(messages.iter().cloned());
+
+        let mut body = json!({
+            "model": request.model,
+            "messages": wire_messages,
+            "stream": true,
+        });
+        let tools = Self::build_tools(request.functions);
+        if !tools.is_empty() {
+            body["tools"] = json!(tools);
+        }
+
+        // The request runs in a spawned task pushing events through a channel,
+        // so the returned stream is cancellation-safe: dropping it closes the
+        // receiver and the task exits on its next send.
+        let (tx, rx) = mpsc::channel::<Result<StreamEvent, GenaiError>>(32);
+        let task = ChatStreamTask {
+            http: self.http.clone(),
+            url: format!("{}/chat/completions", self.base_url),
+            api_key: self.api_key.clone(),
+            body,
+            messages,
+            histories: self.histories.clone(),
+        };
+        tokio::spawn(task.run(tx));
+
+        Box::pin(futures_util::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|event| (event, rx))
+        }))
+    }
+}
+
+/// State for one streaming chat-completions request.
+struct ChatStreamTask {
+    http: reqwest::Client,
+    url: String,
+    api_key: Option<String>,
+    body: Value,
+    /// Messages sent this turn (without the system message), used as the base
+    /// for the stored history once the assistant response completes.
+    messages: Vec<Value>,
+    histories: Arc<Mutex<HashMap<String, Vec<Value>>>>,
+}
+
+/// A tool call accumulated from streaming deltas.
+#[derive(Default)]
+struct PartialToolCall {
+    id: String,
+    name: String,
+    arguments: String,
+}
+
+impl ChatStreamTask {
+    async fn run(mut self, tx: mpsc::Sender<Result<StreamEvent, GenaiError>>) {
+        let mut request = self.http.post(&self.url).json(&self.body);
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+
+        let response = match request.send().await {
+            Ok(r) => r,
+            Err(e) => {
+                let _ = tx
+                    .send(Err(GenaiError::Internal(format!("Request failed: {}", e))))
+                    .await;
+                return;
+            }
+        };
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let detail = response.text().await.unwrap_or_default();
+            let _ = tx
+                .send(Err(GenaiError::Internal(format!(
+                    "Provider returned {}: {}",
+                    status, detail
+                ))))
+                .await;
+            return;
+        }
+
+        let mut buffer = String::new();
+        let mut text = String::new();
+        let mut tool_calls: Vec<PartialToolCall> = Vec::new();
+        let mut byte_stream = response.bytes_stream();
+
+        'outer: while let Some(chunk) = byte_stream.next().await {
+            let bytes = match chunk {
+                Ok(b) => b,
+                Err(e) => {
+                    let _ = tx
+                        .send(Err(GenaiError::Internal(format!("Stream error: {}", e))))
+                        .await;
+                    return;
+                }
+            };
+            buffer.push_str(&String::from_utf8_lossy(&bytes));
+
+            // SSE frames are newline-delimited `data: <json>` lines
+            while let Some(pos) = buffer.find('\n') {
+                let line = buffer[..pos].trim().to_string();
+                buffer.drain(..=pos);
+
+                let Some(data) = line.strip_prefix("data:") else {
+                    continue;
+                };
+                let data = data.trim();
+                if data == "[DONE]" {
+                    break 'outer;
+                }
+
+                let Ok(parsed) = serde_json::from_str::<Value>(data) else {
+                    tracing::warn!("Skipping unparseable SSE chunk: {}", data);
+                    continue;
+                };
+                let delta = &parsed["choices"][0]["delta"];
+
+                if let Some(content) = delta["content"].as_str()
+                    && !content.is_empty()
+                {
+                    text.push_str(content);
+                    if tx
+                        .send(Ok(StreamEvent::new(
+                            StreamChunk::Delta(Content::text(content)),
+                            None,
+                        )))
+                        .await
+                        .is_err()
+                    {
+                        return; // Receiver dropped (cancelled)
+                    }
+                }
+
+                if let Some(calls) = delta["tool_calls"].as_array() {
+                    for call in calls {
+                        let index = call["index"].as_u64().unwrap_or(0) as usize;
+                        while tool_calls.len() <= index {
+                            tool_calls.push(PartialToolCall::default());
+                        }
+                        let partial = &mut tool_calls[index];
+                        if let Some(id) = call["id"].as_str() {
+                            partial.id.push_str(id);
+                        }
+                        if let Some(name) = call["function"]["name"].as_str() {
+                            partial.name.push_str(name);
+                        }
+                        if let Some(args) = call["function"]["arguments"].as_str() {
+                            partial.arguments.push_str(args);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Emit accumulated tool calls as FunctionCall deltas (the agent loop
+        // collects these, mirroring how Gemini streams function calls)
+        let mut assistant_tool_calls: Vec<Value> = Vec::new();
+        for partial in &tool_calls {
+            let args: Value = serde_json::from_str(&partial.arguments).unwrap_or(json!({}));
+            assistant_tool_calls.push(json!({
+                "id": partial.id,
+                "type": "function",
+                "function": {"name": partial.name, "arguments": partial.arguments},
+            }));
+            if tx
+                .send(Ok(StreamEvent::new(
+                    StreamChunk::Delta(Content::FunctionCall {
+                        id: Some(partial.id.clone()),
+                        name: partial.name.clone(),
+                        args,
+                    }),
+                    None,
+                )))
+                .await
+                .is_err()
+            {
+                return;
+            }
+        }
+
+        // Record the assistant turn and issue a new interaction ID
+        let mut assistant = json!({"role": "assistant"});
+        assistant["content"] = if text.is_empty() {
+            Value::Null
+        } else {
+            json!(text)
+        };
+        if !assistant_tool_calls.is_empty() {
+            assistant["tool_calls"] = json!(assistant_tool_calls);
+        }
+        self.messages.push(assistant);
+
+        let interaction_id = format!("oai_{}", uuid::Uuid::new_v4());
+        self.histories
+            .lock()
+            .unwrap()
+            .insert(interaction_id.clone(), self.messages);
+
+        let _ = tx
+            .send(Ok(StreamEvent::new(
+                StreamChunk::Complete(InteractionResponse {
+                    id: Some(interaction_id),
+                    model: None,
+                    agent: None,
+                    input: vec![],
+                    outputs: vec![],
+                    status: InteractionStatus::Completed,
+                    // OpenAI-compatible usage reporting not wired up yet
+                    usage: None,
+                    tools: None,
+                    grounding_metadata: None,
+                    url_context_metadata: None,
+                    previous_interaction_id: None,
+                    created: None,
+                    updated: None,
+                }),
+                None,
+            )))
+            .await;
+    }
+}
+
+// ============================================================================
+// Provider selection
+// ============================================================================
+
+/// Default base URL for a local Ollama server's OpenAI-compatible API.
+const OLLAMA_DEFAULT_BASE_URL: &str = "http://localhost:11434/v1";
+
+/// Construct a provider from config values.
+///
+/// `name` comes from `provider` in config.toml (default "gemini").
+pub fn provider_from_config<'c>(
+    name: &str,
+    base_url: Option<String>,
+    api_key: Option<String>,
+    client: &'c Client,
+) -> anyhow::Result<Box<dyn ModelProvider + 'c>> {
+    match name {
+        "gemini" => Ok(Box::new(GeminiProvider::new(client))),
+        "openai" | "openai-compatible" => {
+            let base_url = base_url.ok_or_else(|| {
+                anyhow::anyhow!("provider \"{}\" requires provider_base_url in config.toml", name)
+            })?;
+            Ok(Box::new(
+                OpenAiCompatibleProvider::new(base_url, api_key).map_err(anyhow::Error::msg)?,
+            ))
+        }
+        "ollama" => Ok(Box::new(
+            OpenAiCompatibleProvider::new(
+                base_url.unwrap_or_else(|| OLLAMA_DEFAULT_BASE_URL.to_string()),
+                api_key,
+            )
+            .map_err(anyhow::Error::msg)?,
+        )),
+        other => anyhow::bail!(
+            "Unknown provider \"{}\" (expected gemini, openai-compatible, or ollama)",
+            other
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turn_content_user_text_to_gemini() {
+        let content = TurnContent::UserText("hello".to_string());
+        let gemini = content.to_gemini_content();
+        assert_eq!(gemini.len(), 1);
+        assert_eq!(gemini[0].as_text(), Some("hello"));
+    }
+
+    #[test]
+    fn test_turn_content_tool_results_to_gemini() {
+        let content = TurnContent::ToolResults {
+            results: vec![
+                ToolResultContent {
+                    name: "read_file".to_string(),
+                    call_id: "call-1".to_string(),
+                    result: json!({"content": "data"}),
+                },
+                ToolResultContent {
+                    name: "grep".to_string(),
+                    call_id: "call-2".to_string(),
+                    result: json!({"matches": []}),
+                },
+            ],
+            steering: vec![],
+        };
+        assert_eq!(content.to_gemini_content().len(), 2);
+    }
+
+    #[test]
+    fn test_turn_content_steering_appended_after_results() {
+        let content = TurnContent::ToolResults {
+            results: vec![ToolResultContent {
+                name: "read_file".to_string(),
+                call_id: "call-1".to_string(),
+                result: json!({"content": "data"}),
+            }],
+            steering: vec!["actually, check the tests directory instead".to_string()],
+        };
+        let gemini = content.to_gemini_content();
+        assert_eq!(gemini.len(), 2);
+        assert_eq!(
+            gemini[1].as_text(),
+            Some("actually, check the tests directory instead")
+        );
+    }
+
+    #[test]
+    fn test_build_messages_user_text() {
+        let content = TurnContent::UserText("fix the bug".to_string());
+        let messages = OpenAiCompatibleProvider::build_messages(&[], &content);
+
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0]["role"], "user");
+        assert_eq!(messages[0]["content"], "fix the bug");
+    }
+
+    #[test]
+    fn test_build_messages_tool_results_after_history() {
+        let history = vec![
+            json!({"role": "user", "content": "run ls"}),
+            json!({"role": "assistant", "content": null, "tool_calls": [{"id": "c1"}]}),
+        ];
+        let content = TurnContent::ToolResults {
+            results: vec![ToolResultContent {
+                name: "bash".to_string(),
+                call_id: "c1".to_string(),
+                result: json!({"output": "file.txt"}),
+            }],
+            steering: vec!["skip hidden files".to_string()],
+        };
+        let messages = OpenAiCompatibleProvider::build_messages(&history, &content);
+
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[2]["role"], "tool");
+        assert_eq!(messages[2]["tool_call_id"], "c1");
+        assert!(messages[2]["content"].as_str().unwrap().contains("file.txt"));
+        assert_eq!(messages[3]["role"], "user");
+        assert_eq!(messages[3]["content"], "skip hidden files");
+    }
+
+    #[test]
+    fn test_provider_from_config_gemini_default() {
+        let client = Client::new("fake-key".to_string());
+        let provider = provider_from_config("gemini", None, None, &client).unwrap();
+        assert_eq!(provider.name(), "gemini");
+    }
+
+    #[test]
+    fn test_provider_from_config_ollama_default_base_url() {
+        let client = Client::new("fake-key".to_string());
+        let provider = provider_from_config("ollama", None, None, &client).unwrap();
+        assert_eq!(provider.name(), "openai-compatible");
+    }
+
+    #[test]
+    fn test_provider_from_config_openai_requires_base_url() {
+        let client = Client::new("fake-key".to_string());
+        let err = provider_from_config("openai-compatible", None, None, &client).unwrap_err();
+        assert!(err.to_string().contains("provider_base_url"));
+    }
+
+    #[test]
+    fn test_provider_from_config_unknown() {
+        let client = Client::new("fake-key".to_string());
+        let err = provider_from_config("bedrock", None, None, &client).unwrap_err();
+        assert!(err.to_string().contains("Unknown provider"));
+    }
+}
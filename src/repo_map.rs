@@ -0,0 +1,313 @@
+//! Aider-style repository map for system context.
+//!
+//! First-turn orientation in an unfamiliar repo is expensive: without a map
+//! the model spends tool calls discovering what files exist and what's in
+//! them. `RepoMap` walks the workspace (respecting .gitignore), extracts
+//! each source file's symbol skeleton via tree-sitter, ranks files by how
+//! often other files reference them (a cheap import-graph approximation),
+//! and renders the result under a token budget for injection into the
+//! system prompt. The map is cached and only regenerated when files change.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::tools::outline;
+
+/// Default token budget for the rendered map.
+pub const DEFAULT_REPO_MAP_TOKENS: usize = 1024;
+
+/// Rough chars-per-token estimate for budgeting.
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Files larger than this are skipped (generated code, vendored bundles).
+const MAX_FILE_SIZE: u64 = 200_000;
+
+/// Cap on scanned files so huge monorepos don't stall startup.
+const MAX_FILES: usize = 2_000;
+
+/// Cap on outline items rendered per file.
+const MAX_ITEMS_PER_FILE: usize = 20;
+
+/// Snapshot of a file used for staleness detection.
+#[derive(PartialEq, Clone)]
+struct FileStamp {
+    mtime: Option<SystemTime>,
+    len: u64,
+}
+
+pub struct RepoMap {
+    root: PathBuf,
+    budget_tokens: usize,
+    /// Last rendered map plus the file stamps it was built from.
+    cache: Option<(String, HashMap<PathBuf, FileStamp>)>,
+}
+
+impl RepoMap {
+    pub fn new(root: PathBuf, budget_tokens: usize) -> Self {
+        Self {
+            root,
+            budget_tokens,
+            cache: None,
+        }
+    }
+
+    /// Append the current repository map to `base_prompt`. Regenerates only
+    /// when files have changed since the last call; returns the prompt
+    /// unchanged when the workspace has no outlinable files.
+    pub fn inject(&mut self, base_prompt: &str) -> String {
+        let map = self.generate();
+        if map.is_empty() {
+            return base_prompt.to_string();
+        }
+        format!(
+            "{}\n\n## Repository Map\n\nRanked overview of this workspace (line-numbered symbols; most-referenced files first). Use `outline` or `read_file` for detail:\n\n{}",
+            base_prompt, map
+        )
+    }
+
+    /// The rendered map, from cache when nothing changed on disk.
+    pub fn generate(&mut self) -> String {
+        let stamps = self.scan();
+        if let Some((rendered, cached_stamps)) = &self.cache
+            && *cached_stamps == stamps
+        {
+            return rendered.clone();
+        }
+
+        let rendered = self.build(&stamps);
+        self.cache = Some((rendered.clone(), stamps));
+        rendered
+    }
+
+    /// Walk the workspace and stamp every outlinable source file.
+    fn scan(&self) -> HashMap<PathBuf, FileStamp> {
+        let mut stamps = HashMap::new();
+        for entry in ignore::WalkBuilder::new(&self.root).build().flatten() {
+            if stamps.len() >= MAX_FILES {
+                break;
+            }
+            let path = entry.path();
+            if !entry.file_type().is_some_and(|t| t.is_file())
+                || outline::language_for_path(path).is_none()
+            {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.len() > MAX_FILE_SIZE {
+                continue;
+            }
+            stamps.insert(
+                path.to_path_buf(),
+                FileStamp {
+                    mtime: metadata.modified().ok(),
+                    len: metadata.len(),
+                },
+            );
+        }
+        stamps
+    }
+
+    fn build(&self, stamps: &HashMap<PathBuf, FileStamp>) -> String {
+        // Parse every file once
+        let mut files: Vec<(PathBuf, String, Vec<serde_json::Value>)> = stamps
+            .keys()
+            .filter_map(|path| {
+                let source = std::fs::read_to_string(path).ok()?;
+                let items = outline::outline_items(path, &source)?;
+                Some((path.clone(), source, items))
+            })
+            .collect();
+        // Deterministic base order before ranking
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+
+        // Rank by incoming references: how many *other* files mention this
+        // file's identifying name as a whole word. `mod.rs`/`index.ts`-style
+        // names identify their directory instead.
+        let scores: Vec<usize> = files
+            .iter()
+            .map(|(path, _, _)| {
+                let Some(name) = identifying_name(path) else {
+                    return 0;
+                };
+                files
+                    .iter()
+                    .filter(|(other, source, _)| other != path && mentions_word(source, &name))
+                    .count()
+            })
+            .collect();
+
+        let mut order: Vec<usize> = (0..files.len()).collect();
+        order.sort_by(|&a, &b| scores[b].cmp(&scores[a]));
+
+        let budget_chars = self.budget_tokens * CHARS_PER_TOKEN;
+        let mut rendered = String::new();
+        for &i in &order {
+            let (path, _, items) = &files[i];
+            let display = path.strip_prefix(&self.root).unwrap_or(path);
+
+            let mut section = format!("{}:\n", display.display());
+            for item in items.iter().take(MAX_ITEMS_PER_FILE) {
+                // Top two levels: file items plus their direct members
+                if item["depth"].as_u64().unwrap_or(0) > 1 {
+                    continue;
+                }
+                section.push_str(&format!(
+                    "{:>5} {}{}\n",
+                    item["line"],
+                    "  ".repeat(item["depth"].as_u64().unwrap_or(0) as usize + 1),
+                    item["signature"].as_str().unwrap_or("")
+                ));
+            }
+
+            if rendered.len() + section.len() > budget_chars {
+                // Always include at least the top-ranked file, truncated,
+                // so tiny budgets still produce something useful
+                if rendered.is_empty() {
+                    let mut end = budget_chars.min(section.len());
+                    while end > 0 && !section.is_char_boundary(end) {
+                        end -= 1;
+                    }
+                    rendered.push_str(&section[..end]);
+                }
+                break;
+            }
+            rendered.push_str(&section);
+        }
+        rendered.trim_end().to_string()
+    }
+}
+
+/// The name other files would reference this file by: its stem, or its
+/// directory for `mod.rs`/`lib.rs`/`index.*`/`__init__.py`-style files.
+fn identifying_name(path: &Path) -> Option<String> {
+    let stem = path.file_stem()?.to_str()?;
+    if matches!(stem, "mod" | "lib" | "main" | "index" | "__init__") {
+        return path.parent()?.file_name()?.to_str().map(|s| s.to_string());
+    }
+    Some(stem.to_string())
+}
+
+/// Whole-word occurrence check (so `agent` doesn't match `agents_rx`).
+fn mentions_word(text: &str, word: &str) -> bool {
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    let mut start = 0;
+    while let Some(pos) = text[start..].find(word) {
+        let begin = start + pos;
+        let end = begin + word.len();
+        let before_ok = begin == 0 || !text[..begin].chars().next_back().is_some_and(is_word_char);
+        let after_ok = end == text.len() || !text[end..].chars().next().is_some_and(is_word_char);
+        if before_ok && after_ok {
+            return true;
+        }
+        start = end;
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write(dir: &Path, name: &str, content: &str) {
+        std::fs::write(dir.join(name), content).unwrap();
+    }
+
+    #[test]
+    fn test_mentions_word_boundaries() {
+        assert!(mentions_word("use crate::agent::AgentEvent;", "agent"));
+        assert!(!mentions_word("let agents_rx = 1;", "agent"));
+        assert!(!mentions_word("reagent", "agent"));
+        assert!(mentions_word("agent", "agent"));
+    }
+
+    #[test]
+    fn test_identifying_name() {
+        assert_eq!(
+            identifying_name(Path::new("src/agent.rs")),
+            Some("agent".to_string())
+        );
+        assert_eq!(
+            identifying_name(Path::new("src/tools/mod.rs")),
+            Some("tools".to_string())
+        );
+        assert_eq!(
+            identifying_name(Path::new("pkg/store/__init__.py")),
+            Some("store".to_string())
+        );
+    }
+
+    #[test]
+    fn test_map_ranks_referenced_files_first() {
+        let dir = tempdir().unwrap();
+        // core.rs is referenced by both others; helper.rs by none
+        write(dir.path(), "core.rs", "pub struct Core;\npub fn run() {}\n");
+        write(dir.path(), "a.rs", "use crate::core::Core;\nfn a() {}\n");
+        write(dir.path(), "helper.rs", "fn helper() {}\n");
+
+        let mut map = RepoMap::new(dir.path().to_path_buf(), DEFAULT_REPO_MAP_TOKENS);
+        let rendered = map.generate();
+
+        assert!(rendered.contains("pub struct Core"), "map:\n{rendered}");
+        assert!(rendered.contains("fn helper"), "map:\n{rendered}");
+        let core_pos = rendered.find("core.rs").unwrap();
+        let helper_pos = rendered.find("helper.rs").unwrap();
+        assert!(core_pos < helper_pos, "map:\n{rendered}");
+    }
+
+    #[test]
+    fn test_map_respects_token_budget() {
+        let dir = tempdir().unwrap();
+        for i in 0..50 {
+            let body: String = (0..20)
+                .map(|j| format!("pub fn function_number_{}_{j}() {{}}\n", i))
+                .collect();
+            write(dir.path(), &format!("file{}.rs", i), &body);
+        }
+
+        let mut map = RepoMap::new(dir.path().to_path_buf(), 100);
+        let rendered = map.generate();
+
+        assert!(
+            rendered.len() <= 100 * CHARS_PER_TOKEN,
+            "len: {}",
+            rendered.len()
+        );
+        assert!(!rendered.is_empty());
+    }
+
+    #[test]
+    fn test_map_regenerates_when_files_change() {
+        let dir = tempdir().unwrap();
+        write(dir.path(), "a.rs", "fn original() {}\n");
+
+        let mut map = RepoMap::new(dir.path().to_path_buf(), DEFAULT_REPO_MAP_TOKENS);
+        let first = map.generate();
+        assert!(first.contains("fn original"));
+
+        // Unchanged workspace is served from cache
+        assert_eq!(map.generate(), first);
+
+        write(dir.path(), "a.rs", "fn original() {}\nfn added() {}\n");
+        let second = map.generate();
+        assert!(second.contains("fn added"), "map:\n{second}");
+    }
+
+    #[test]
+    fn test_inject_appends_section_or_leaves_prompt_alone() {
+        let dir = tempdir().unwrap();
+        let mut map = RepoMap::new(dir.path().to_path_buf(), DEFAULT_REPO_MAP_TOKENS);
+
+        // No outlinable files: prompt passes through untouched
+        assert_eq!(map.inject("base prompt"), "base prompt");
+
+        write(dir.path(), "a.rs", "fn thing() {}\n");
+        let injected = map.inject("base prompt");
+        assert!(injected.starts_with("base prompt"));
+        assert!(injected.contains("## Repository Map"));
+        assert!(injected.contains("fn thing"));
+    }
+}
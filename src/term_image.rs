@@ -0,0 +1,131 @@
+//! Inline terminal image previews via the kitty graphics protocol.
+//!
+//! Terminals implementing the protocol (kitty, ghostty, WezTerm) can render
+//! PNG data sent as APC escape sequences. [`kitty_inline_png`] returns the
+//! escape string when the terminal supports it; callers fall back to
+//! printing the image path otherwise, so piped output and unsupported
+//! terminals see the same summary line they do today. Sixel output would
+//! need pixel decoding (an image crate dependency), so only the kitty
+//! protocol is wired up.
+
+use base64::Engine as _;
+
+/// Images larger than this are not previewed inline (the escape stream
+/// would dwarf the rest of the tool output).
+const MAX_PREVIEW_BYTES: usize = 4 * 1024 * 1024;
+
+/// Base64 payload bytes per escape sequence, per the kitty protocol spec.
+const CHUNK_SIZE: usize = 4096;
+
+/// Whether the terminal advertises kitty graphics protocol support.
+/// Detection is env-based: the protocol has a runtime query, but that
+/// needs raw-mode terminal I/O this side doesn't own.
+pub fn terminal_supports_kitty_graphics() -> bool {
+    if std::env::var_os("KITTY_WINDOW_ID").is_some()
+        || std::env::var_os("GHOSTTY_RESOURCES_DIR").is_some()
+    {
+        return true;
+    }
+    if std::env::var("TERM")
+        .map(|t| t.contains("kitty") || t.contains("ghostty"))
+        .unwrap_or(false)
+    {
+        return true;
+    }
+    matches!(std::env::var("TERM_PROGRAM").as_deref(), Ok("WezTerm"))
+}
+
+/// Escape sequence rendering `png_bytes` inline in a kitty-protocol
+/// terminal, or `None` when the terminal doesn't support the protocol or
+/// the image is too large to stream. Only PNG data is accepted (the
+/// protocol's `f=100` passthrough format).
+pub fn kitty_inline_png(png_bytes: &[u8]) -> Option<String> {
+    if !terminal_supports_kitty_graphics() || png_bytes.len() > MAX_PREVIEW_BYTES {
+        return None;
+    }
+    Some(kitty_escape(png_bytes))
+}
+
+/// Build the chunked kitty graphics escape stream for PNG data:
+/// `f=100` (PNG passthrough), `a=T` (transmit and display), payload split
+/// into 4 KiB base64 chunks with `m=1` continuation markers.
+fn kitty_escape(png_bytes: &[u8]) -> String {
+    let data = base64::engine::general_purpose::STANDARD.encode(png_bytes);
+    let chunks: Vec<&[u8]> = data.as_bytes().chunks(CHUNK_SIZE).collect();
+    let mut out = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        // Chunks are slices of base64 output, always valid ASCII
+        let payload = std::str::from_utf8(chunk).expect("base64 output is ASCII");
+        let more = if i + 1 == chunks.len() { 0 } else { 1 };
+        if i == 0 {
+            out.push_str(&format!("\x1b_Gf=100,a=T,m={more};{payload}\x1b\\"));
+        } else {
+            out.push_str(&format!("\x1b_Gm={more};{payload}\x1b\\"));
+        }
+    }
+    out
+}
+
+/// Remove kitty graphics APC sequences (`ESC _ G ... ESC \`) from text.
+/// Sinks that persist output (log files, transcripts) use this so inline
+/// previews don't dump megabytes of base64 into them.
+pub fn strip_graphics_sequences(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("\x1b_G") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start..];
+        match after.find("\x1b\\") {
+            Some(end) => rest = &after[end + 2..],
+            None => return out, // Unterminated sequence: drop the tail
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A minimal valid PNG header is enough: the escape builder treats the
+    // bytes as opaque.
+    const FAKE_PNG: &[u8] = b"\x89PNG\r\n\x1a\nfake image data";
+
+    #[test]
+    fn test_kitty_escape_structure() {
+        let escape = kitty_escape(FAKE_PNG);
+        assert!(escape.starts_with("\x1b_Gf=100,a=T,m=0;"));
+        assert!(escape.ends_with("\x1b\\"));
+    }
+
+    #[test]
+    fn test_kitty_escape_chunks_large_payloads() {
+        let big = vec![0u8; 10_000];
+        let escape = kitty_escape(&big);
+        // First chunk signals continuation, final chunk ends it
+        assert!(escape.starts_with("\x1b_Gf=100,a=T,m=1;"));
+        assert!(escape.contains("\x1b_Gm=0;"));
+        // Continuation chunks don't repeat the format/action keys
+        assert!(escape.contains("\x1b_Gm=1;"));
+    }
+
+    #[test]
+    fn test_strip_graphics_sequences() {
+        let text = format!("  captured shot.png\n{}", kitty_escape(FAKE_PNG));
+        let stripped = strip_graphics_sequences(&text);
+        assert_eq!(stripped, "  captured shot.png\n");
+    }
+
+    #[test]
+    fn test_strip_graphics_preserves_other_escapes() {
+        let text = "\x1b[31mred\x1b[0m";
+        assert_eq!(strip_graphics_sequences(text), text);
+    }
+
+    #[test]
+    fn test_strip_graphics_drops_unterminated_tail() {
+        let text = "before\x1b_Gf=100;abcdef";
+        assert_eq!(strip_graphics_sequences(text), "before");
+    }
+}
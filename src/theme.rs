@@ -0,0 +1,254 @@
+//! Color theme selection for clemini-side rendering.
+//!
+//! The `[theme]` config section picks a preset (`dark`, the default, or
+//! `light` for light-background terminals) with optional per-color
+//! overrides. The resolved [`Theme`] is installed process-wide at startup
+//! via [`set_active`] and consulted by the diff renderer, the code-block
+//! highlighter, and the REPL prompt. Colors painted by clemitui itself
+//! (tool lines, TUI borders) are not themeable from this side yet - see
+//! the clemitui gaps list in CLAUDE.md.
+
+use colored::Color;
+use serde::Deserialize;
+use std::sync::{LazyLock, RwLock};
+use syntect::highlighting::{Theme as SyntaxTheme, ThemeSet};
+
+/// Catppuccin Mocha theme (bundled from catppuccin/bat). Dark preset.
+static CATPPUCCIN_MOCHA: LazyLock<SyntaxTheme> = LazyLock::new(|| {
+    let theme_bytes = include_bytes!("../themes/catppuccin-mocha.tmTheme");
+    let mut cursor = std::io::Cursor::new(theme_bytes);
+    ThemeSet::load_from_reader(&mut cursor).expect("bundled theme should be valid")
+});
+
+/// InspiredGitHub from syntect's default theme set. Light preset.
+static INSPIRED_GITHUB: LazyLock<SyntaxTheme> = LazyLock::new(|| {
+    ThemeSet::load_defaults()
+        .themes
+        .remove("InspiredGitHub")
+        .expect("syntect default themes include InspiredGitHub")
+});
+
+/// Built-in theme presets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Preset {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl Preset {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::Dark),
+            "light" => Some(Self::Light),
+            _ => None,
+        }
+    }
+}
+
+/// Resolved color palette for clemini-side rendering.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    pub preset: Preset,
+    /// Background for removed diff lines (truecolor RGB).
+    pub diff_delete_bg: (u8, u8, u8),
+    /// Background for added diff lines (truecolor RGB).
+    pub diff_insert_bg: (u8, u8, u8),
+    /// Color of the REPL prompt glyph.
+    pub user_prompt: Color,
+}
+
+impl Theme {
+    /// Default palette: dark backgrounds that read well on dark terminals.
+    pub fn dark() -> Self {
+        Self {
+            preset: Preset::Dark,
+            diff_delete_bg: (80, 40, 40),
+            diff_insert_bg: (40, 80, 40),
+            user_prompt: Color::Cyan,
+        }
+    }
+
+    /// Light-terminal-friendly palette: pale diff backgrounds and a darker
+    /// syntax theme so highlighted code stays legible on white.
+    pub fn light() -> Self {
+        Self {
+            preset: Preset::Light,
+            diff_delete_bg: (255, 220, 220),
+            diff_insert_bg: (215, 245, 215),
+            user_prompt: Color::Blue,
+        }
+    }
+
+    /// Resolve the `[theme]` config section: pick the preset, then apply
+    /// any explicit overrides on top. Unknown values are logged and
+    /// ignored, not fatal.
+    pub fn from_config(toml: &ThemeToml) -> Self {
+        let preset = match toml.preset.as_deref() {
+            Some(name) => Preset::parse(name).unwrap_or_else(|| {
+                tracing::warn!("Unknown theme preset '{}', using dark", name);
+                Preset::Dark
+            }),
+            None => Preset::Dark,
+        };
+        let mut theme = match preset {
+            Preset::Dark => Self::dark(),
+            Preset::Light => Self::light(),
+        };
+        if let Some([r, g, b]) = toml.diff_delete_bg {
+            theme.diff_delete_bg = (r, g, b);
+        }
+        if let Some([r, g, b]) = toml.diff_insert_bg {
+            theme.diff_insert_bg = (r, g, b);
+        }
+        if let Some(name) = &toml.user_prompt {
+            match parse_color(name) {
+                Some(color) => theme.user_prompt = color,
+                None => tracing::warn!("Unknown theme color '{}' for user_prompt", name),
+            }
+        }
+        theme
+    }
+}
+
+/// The `[theme]` section of config.toml.
+#[derive(Debug, Default, Deserialize)]
+pub struct ThemeToml {
+    /// Preset name: "dark" (default) or "light".
+    pub preset: Option<String>,
+    /// Named color for the prompt glyph (e.g. "magenta", "bright blue").
+    pub user_prompt: Option<String>,
+    /// RGB override for the removed-line diff background.
+    pub diff_delete_bg: Option<[u8; 3]>,
+    /// RGB override for the added-line diff background.
+    pub diff_insert_bg: Option<[u8; 3]>,
+}
+
+/// Parse a named terminal color. Supports the 16 standard names the
+/// `colored` crate draws; returns `None` for anything else.
+fn parse_color(name: &str) -> Option<Color> {
+    match name.to_lowercase().as_str() {
+        "black" => Some(Color::Black),
+        "red" => Some(Color::Red),
+        "green" => Some(Color::Green),
+        "yellow" => Some(Color::Yellow),
+        "blue" => Some(Color::Blue),
+        "magenta" | "purple" => Some(Color::Magenta),
+        "cyan" => Some(Color::Cyan),
+        "white" => Some(Color::White),
+        "bright black" => Some(Color::BrightBlack),
+        "bright red" => Some(Color::BrightRed),
+        "bright green" => Some(Color::BrightGreen),
+        "bright yellow" => Some(Color::BrightYellow),
+        "bright blue" => Some(Color::BrightBlue),
+        "bright magenta" => Some(Color::BrightMagenta),
+        "bright cyan" => Some(Color::BrightCyan),
+        "bright white" => Some(Color::BrightWhite),
+        _ => None,
+    }
+}
+
+/// The process-wide active theme. Defaults to dark until `set_active` runs.
+static ACTIVE: LazyLock<RwLock<Theme>> = LazyLock::new(|| RwLock::new(Theme::dark()));
+
+/// Install the resolved theme. Called once at startup after config load.
+pub fn set_active(theme: Theme) {
+    let mut guard = ACTIVE.write().unwrap_or_else(|poisoned| {
+        tracing::warn!("theme lock was poisoned, recovering");
+        poisoned.into_inner()
+    });
+    *guard = theme;
+}
+
+/// The currently active theme.
+pub fn active() -> Theme {
+    *ACTIVE.read().unwrap_or_else(|poisoned| {
+        tracing::warn!("theme lock was poisoned, recovering");
+        poisoned.into_inner()
+    })
+}
+
+/// Syntect theme matching the active preset, for diff and code-block
+/// highlighting.
+pub fn syntax_theme() -> &'static SyntaxTheme {
+    match active().preset {
+        Preset::Dark => &CATPPUCCIN_MOCHA,
+        Preset::Light => &INSPIRED_GITHUB,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catppuccin_theme_loads() {
+        // Verify the bundled Catppuccin theme loads without panic
+        let _ = &*CATPPUCCIN_MOCHA;
+    }
+
+    #[test]
+    fn test_light_syntax_theme_loads() {
+        let _ = &*INSPIRED_GITHUB;
+    }
+
+    #[test]
+    fn test_preset_parse() {
+        assert_eq!(Preset::parse("dark"), Some(Preset::Dark));
+        assert_eq!(Preset::parse("light"), Some(Preset::Light));
+        assert_eq!(Preset::parse("solarized"), None);
+    }
+
+    #[test]
+    fn test_from_config_defaults_to_dark() {
+        let theme = Theme::from_config(&ThemeToml::default());
+        assert_eq!(theme, Theme::dark());
+    }
+
+    #[test]
+    fn test_from_config_light_preset() {
+        let toml = ThemeToml {
+            preset: Some("light".to_string()),
+            ..Default::default()
+        };
+        let theme = Theme::from_config(&toml);
+        assert_eq!(theme, Theme::light());
+        assert_eq!(theme.diff_insert_bg, (215, 245, 215));
+    }
+
+    #[test]
+    fn test_from_config_overrides_on_top_of_preset() {
+        let toml = ThemeToml {
+            preset: Some("light".to_string()),
+            user_prompt: Some("magenta".to_string()),
+            diff_delete_bg: Some([250, 200, 200]),
+            diff_insert_bg: None,
+        };
+        let theme = Theme::from_config(&toml);
+        assert_eq!(theme.preset, Preset::Light);
+        assert_eq!(theme.user_prompt, Color::Magenta);
+        assert_eq!(theme.diff_delete_bg, (250, 200, 200));
+        // Untouched field keeps the preset value
+        assert_eq!(theme.diff_insert_bg, Theme::light().diff_insert_bg);
+    }
+
+    #[test]
+    fn test_from_config_unknown_values_fall_back() {
+        let toml = ThemeToml {
+            preset: Some("neon".to_string()),
+            user_prompt: Some("chartreuse".to_string()),
+            ..Default::default()
+        };
+        let theme = Theme::from_config(&toml);
+        assert_eq!(theme.preset, Preset::Dark);
+        assert_eq!(theme.user_prompt, Theme::dark().user_prompt);
+    }
+
+    #[test]
+    fn test_parse_color_names() {
+        assert_eq!(parse_color("cyan"), Some(Color::Cyan));
+        assert_eq!(parse_color("Bright Blue"), Some(Color::BrightBlue));
+        assert_eq!(parse_color("purple"), Some(Color::Magenta));
+        assert_eq!(parse_color("mauve"), None);
+    }
+}
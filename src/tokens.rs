@@ -0,0 +1,170 @@
+//! Token counting behind a `TokenCounter` abstraction.
+//!
+//! Two implementations:
+//! - `HeuristicTokenCounter` - the classic ~4 chars/token estimate, free and
+//!   synchronous. Used for display (`~N tok`) and as a fallback.
+//! - `GeminiTokenCounter` - the Gemini `countTokens` endpoint with an
+//!   in-memory cache, for callers that need real counts (e.g., a context
+//!   gauge). Falls back to the heuristic on network failure.
+//!
+//! The agent loop prefers server-reported usage; the counter only fills in
+//! when a provider doesn't report usage (e.g., some OpenAI-compatible
+//! servers), so context warnings still fire. The CLI passes a
+//! `GeminiTokenCounter` into `run_interaction_with_provider`; embedding
+//! callers pick theirs via `Agent::with_token_counter` (default: heuristic).
+
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use serde_json::json;
+
+/// Estimate tokens from text length (~4 characters per token).
+pub fn heuristic_tokens(text: &str) -> u32 {
+    (text.len() / 4) as u32
+}
+
+/// Counts tokens in text. Implementations may consult a remote endpoint.
+#[async_trait]
+pub trait TokenCounter: Send + Sync {
+    async fn count(&self, text: &str) -> u32;
+}
+
+/// Free, synchronous ~4 chars/token estimate.
+pub struct HeuristicTokenCounter;
+
+#[async_trait]
+impl TokenCounter for HeuristicTokenCounter {
+    async fn count(&self, text: &str) -> u32 {
+        heuristic_tokens(text)
+    }
+}
+
+/// Real token counts via the Gemini `countTokens` endpoint, cached by text
+/// hash so repeated counts of the same content hit the network once.
+pub struct GeminiTokenCounter {
+    api_key: String,
+    model: String,
+    client: reqwest::Client,
+    cache: Mutex<HashMap<u64, u32>>,
+}
+
+impl GeminiTokenCounter {
+    pub fn new(api_key: String, model: String) -> Result<Self, String> {
+        Ok(Self {
+            api_key,
+            model,
+            client: crate::tools::create_http_client()?,
+            cache: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn cache_key(text: &str) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        text.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn cached(&self, key: u64) -> Option<u32> {
+        self.cache.lock().ok()?.get(&key).copied()
+    }
+
+    fn store(&self, key: u64, count: u32) {
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.insert(key, count);
+        }
+    }
+
+    async fn count_remote(&self, text: &str) -> Result<u32, String> {
+        let url = format!(
+            "https://generativelanguage.googleapis.com/v1beta/models/{}:countTokens?key={}",
+            self.model, self.api_key
+        );
+        let body = json!({"contents": [{"parts": [{"text": text}]}]});
+
+        let resp = self
+            .client
+            .post(&url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| format!("countTokens request failed: {}", e))?;
+        if !resp.status().is_success() {
+            return Err(format!("countTokens HTTP error: {}", resp.status()));
+        }
+
+        let value: serde_json::Value = resp
+            .json()
+            .await
+            .map_err(|e| format!("countTokens response parse failed: {}", e))?;
+        value
+            .get("totalTokens")
+            .and_then(serde_json::Value::as_u64)
+            .map(|n| n as u32)
+            .ok_or_else(|| "countTokens response missing totalTokens".to_string())
+    }
+}
+
+#[async_trait]
+impl TokenCounter for GeminiTokenCounter {
+    async fn count(&self, text: &str) -> u32 {
+        let key = Self::cache_key(text);
+        if let Some(count) = self.cached(key) {
+            return count;
+        }
+        match self.count_remote(text).await {
+            Ok(count) => {
+                self.store(key, count);
+                count
+            }
+            Err(e) => {
+                // Graceful unknown: log and fall back rather than fail the caller
+                tracing::warn!("Token counting fell back to heuristic: {}", e);
+                heuristic_tokens(text)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heuristic_tokens() {
+        assert_eq!(heuristic_tokens(""), 0);
+        assert_eq!(heuristic_tokens("abcd"), 1);
+        assert_eq!(heuristic_tokens(&"x".repeat(400)), 100);
+    }
+
+    #[tokio::test]
+    async fn test_heuristic_counter_matches_free_function() {
+        let counter = HeuristicTokenCounter;
+        assert_eq!(counter.count("abcdefgh").await, heuristic_tokens("abcdefgh"));
+    }
+
+    #[tokio::test]
+    async fn test_gemini_counter_uses_cache_without_network() {
+        let counter =
+            GeminiTokenCounter::new("fake-key".to_string(), "test-model".to_string()).unwrap();
+
+        let key = GeminiTokenCounter::cache_key("hello world");
+        counter.store(key, 42);
+
+        // Cached entry is returned without hitting the endpoint
+        assert_eq!(counter.count("hello world").await, 42);
+    }
+
+    #[test]
+    fn test_cache_key_is_stable() {
+        assert_eq!(
+            GeminiTokenCounter::cache_key("same text"),
+            GeminiTokenCounter::cache_key("same text")
+        );
+        assert_ne!(
+            GeminiTokenCounter::cache_key("one"),
+            GeminiTokenCounter::cache_key("two")
+        );
+    }
+}
@@ -0,0 +1,499 @@
+use crate::agent::AgentEvent;
+use async_trait::async_trait;
+use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use tokio::sync::mpsc;
+use tracing::instrument;
+
+use super::{ToolEmitter, error_codes, error_response, resolve_and_validate_path};
+use crate::diff::{apply_hunks, parse_unified_patch};
+
+/// Applies a unified diff (possibly multi-file) atomically: the whole patch
+/// is parsed and applied in memory before anything touches disk, so a hunk
+/// that doesn't apply leaves every file unchanged. Hunk positions are hints -
+/// context is matched exactly first, then whitespace-tolerantly (see
+/// `crate::diff::apply_hunks`).
+pub struct ApplyPatchTool {
+    cwd: PathBuf,
+    allowed_paths: Vec<PathBuf>,
+    events_tx: Option<mpsc::Sender<AgentEvent>>,
+    dry_run: bool,
+}
+
+impl ApplyPatchTool {
+    pub fn new(
+        cwd: PathBuf,
+        allowed_paths: Vec<PathBuf>,
+        events_tx: Option<mpsc::Sender<AgentEvent>>,
+    ) -> Self {
+        Self {
+            cwd,
+            allowed_paths,
+            events_tx,
+            dry_run: false,
+        }
+    }
+
+    /// In dry-run mode the patched files are emitted as diffs and reported
+    /// as a success, but nothing is written or deleted.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+}
+
+impl ToolEmitter for ApplyPatchTool {
+    fn events_tx(&self) -> &Option<mpsc::Sender<AgentEvent>> {
+        &self.events_tx
+    }
+}
+
+/// One file's fully-resolved pending change, held in memory until the whole
+/// patch has validated.
+struct PendingChange {
+    display_path: String,
+    path: PathBuf,
+    old_content: String,
+    new_content: Option<String>, // None = delete the file
+    hunk_results: Vec<Value>,
+    created: bool,
+}
+
+#[async_trait]
+impl CallableFunction for ApplyPatchTool {
+    fn declaration(&self) -> FunctionDeclaration {
+        FunctionDeclaration::new(
+            "apply_patch".to_string(),
+            "Apply a unified diff to the working tree. Accepts standard single- or multi-file unified diff format ('--- a/path', '+++ b/path', '@@' hunks; git decorations tolerated). Hunk line numbers are hints - context is matched exactly first, then whitespace-tolerantly. The whole patch applies atomically: if any hunk fails, no file is modified. Use '/dev/null' paths for file creation/deletion. Returns: {success, files: [{path, hunks: [{line, fuzzy}]}]} or {error}".to_string(),
+            FunctionParameters::new(
+                "object".to_string(),
+                json!({
+                    "patch": {
+                        "type": "string",
+                        "description": "The unified diff to apply"
+                    }
+                }),
+                vec!["patch".to_string()],
+            ),
+        )
+    }
+
+    #[instrument(skip(self, args))]
+    async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        let patch = args
+            .get("patch")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing patch".to_string()))?;
+
+        let files = match parse_unified_patch(patch) {
+            Ok(files) => files,
+            Err(e) => {
+                return Ok(error_response(
+                    &format!("Failed to parse patch: {}", e),
+                    error_codes::INVALID_ARGUMENT,
+                    json!({}),
+                ));
+            }
+        };
+
+        // Validate and apply everything in memory first; the patch is atomic
+        let mut pending: Vec<PendingChange> = Vec::new();
+        for file in &files {
+            let display_path = match file.new_path.as_deref().or(file.old_path.as_deref()) {
+                Some(p) => p.to_string(),
+                None => {
+                    return Ok(error_response(
+                        "Patch entry has /dev/null for both old and new path.",
+                        error_codes::INVALID_ARGUMENT,
+                        json!({}),
+                    ));
+                }
+            };
+
+            let path =
+                match resolve_and_validate_path(&display_path, &self.cwd, &self.allowed_paths) {
+                    Ok(p) => p,
+                    Err(e) => {
+                        return Ok(error_response(
+                            &format!("Access denied: {}. Path must be within allowed paths.", e),
+                            error_codes::ACCESS_DENIED,
+                            json!({"path": display_path}),
+                        ));
+                    }
+                };
+
+            let creating = file.old_path.is_none();
+            let deleting = file.new_path.is_none();
+
+            let old_content = if creating {
+                if path.exists() {
+                    return Ok(error_response(
+                        &format!(
+                            "Patch creates {} but it already exists. Diff against the current content instead.",
+                            display_path
+                        ),
+                        error_codes::INVALID_ARGUMENT,
+                        json!({"path": display_path}),
+                    ));
+                }
+                String::new()
+            } else {
+                match tokio::fs::read_to_string(&path).await {
+                    Ok(c) => c,
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                        return Ok(error_response(
+                            &format!("File not found: {}. No files were modified.", display_path),
+                            error_codes::NOT_FOUND,
+                            json!({"path": display_path}),
+                        ));
+                    }
+                    Err(e) => {
+                        return Ok(error_response(
+                            &format!("Failed to read {}: {}", path.display(), e),
+                            error_codes::IO_ERROR,
+                            json!({"path": display_path}),
+                        ));
+                    }
+                }
+            };
+
+            let (new_content, applications) = match apply_hunks(&old_content, &file.hunks) {
+                Ok(result) => result,
+                Err(e) => {
+                    return Ok(error_response(
+                        &format!("{} in {}. No files were modified.", e, display_path),
+                        error_codes::NOT_FOUND,
+                        json!({"path": display_path}),
+                    ));
+                }
+            };
+
+            let hunk_results = applications
+                .iter()
+                .map(|a| json!({"line": a.line, "fuzzy": a.fuzzy}))
+                .collect();
+
+            pending.push(PendingChange {
+                display_path,
+                path,
+                old_content,
+                new_content: if deleting { None } else { Some(new_content) },
+                hunk_results,
+                created: creating,
+            });
+        }
+
+        // Everything validated - write the changes (unless previewing)
+        let mut file_results = Vec::new();
+        for change in &pending {
+            if !self.dry_run {
+                // Snapshot each file's pre-patch state (including deletions)
+                // so /undo and revert_file can restore it.
+                super::checkpoint::snapshot(&self.cwd, &change.path);
+                match &change.new_content {
+                    Some(content) => {
+                        if let Err(e) = tokio::fs::write(&change.path, content).await {
+                            return Ok(error_response(
+                                &format!(
+                                    "Failed to write {}: {}. Earlier files in the patch may already be written.",
+                                    change.path.display(),
+                                    e
+                                ),
+                                error_codes::IO_ERROR,
+                                json!({"path": change.display_path}),
+                            ));
+                        }
+                    }
+                    None => {
+                        if let Err(e) = tokio::fs::remove_file(&change.path).await {
+                            return Ok(error_response(
+                                &format!(
+                                    "Failed to delete {}: {}. Earlier files in the patch may already be written.",
+                                    change.path.display(),
+                                    e
+                                ),
+                                error_codes::IO_ERROR,
+                                json!({"path": change.display_path}),
+                            ));
+                        }
+                    }
+                }
+            }
+
+            let diff_output = crate::diff::format_diff(
+                &change.old_content,
+                change.new_content.as_deref().unwrap_or(""),
+                2,
+                Some(&change.display_path),
+            );
+            if !diff_output.is_empty() {
+                self.emit(&diff_output);
+            }
+
+            let mut entry = json!({
+                "path": change.display_path,
+                "hunks": change.hunk_results,
+            });
+            if change.created {
+                entry["created"] = json!(true);
+            }
+            if change.new_content.is_none() {
+                entry["deleted"] = json!(true);
+            }
+            file_results.push(entry);
+        }
+
+        let mut result = json!({
+            "success": true,
+            "files": file_results
+        });
+        if self.dry_run {
+            result["dry_run"] = json!(true);
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_apply_patch_single_file() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("main.rs");
+        fs::write(&file_path, "fn main() {\n    old();\n}\n").unwrap();
+
+        let tool = ApplyPatchTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let patch = "\
+--- a/main.rs
++++ b/main.rs
+@@ -1,3 +1,3 @@
+ fn main() {
+-    old();
++    new();
+ }
+";
+        let result = tool.call(json!({"patch": patch})).await.unwrap();
+        assert!(result["success"].as_bool().unwrap(), "got: {result}");
+        assert_eq!(result["files"][0]["path"], "main.rs");
+        assert_eq!(result["files"][0]["hunks"][0]["fuzzy"], false);
+        assert_eq!(
+            fs::read_to_string(&file_path).unwrap(),
+            "fn main() {\n    new();\n}\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_apply_patch_checkpoints_for_undo() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("main.rs");
+        fs::write(&file_path, "fn main() {\n    old();\n}\n").unwrap();
+
+        let tool = ApplyPatchTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let patch = "\
+--- a/main.rs
++++ b/main.rs
+@@ -1,3 +1,3 @@
+ fn main() {
+-    old();
++    new();
+ }
+";
+        let result = tool.call(json!({"patch": patch})).await.unwrap();
+        assert!(result["success"].as_bool().unwrap(), "got: {result}");
+
+        // /undo restores the pre-patch state from the snapshot.
+        let entry = crate::tools::checkpoint::undo_last(&cwd).unwrap();
+        assert!(
+            entry.is_some(),
+            "apply_patch must checkpoint before writing"
+        );
+        assert_eq!(
+            fs::read_to_string(&file_path).unwrap(),
+            "fn main() {\n    old();\n}\n"
+        );
+        let _ = fs::remove_file(crate::tools::checkpoint::journal_path(&cwd));
+    }
+
+    #[tokio::test]
+    async fn test_apply_patch_multi_file_atomic_on_failure() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("one.txt"), "alpha\n").unwrap();
+        fs::write(cwd.join("two.txt"), "beta\n").unwrap();
+
+        let tool = ApplyPatchTool::new(cwd.clone(), vec![cwd.clone()], None);
+        // Second file's hunk doesn't match its content
+        let patch = "\
+--- a/one.txt
++++ b/one.txt
+@@ -1 +1 @@
+-alpha
++first
+--- a/two.txt
++++ b/two.txt
+@@ -1 +1 @@
+-wrong
++second
+";
+        let result = tool.call(json!({"patch": patch})).await.unwrap();
+        assert!(result["error"].as_str().unwrap().contains("does not apply"));
+        assert_eq!(result["error_code"], error_codes::NOT_FOUND);
+        // Neither file was touched
+        assert_eq!(fs::read_to_string(cwd.join("one.txt")).unwrap(), "alpha\n");
+        assert_eq!(fs::read_to_string(cwd.join("two.txt")).unwrap(), "beta\n");
+    }
+
+    #[tokio::test]
+    async fn test_apply_patch_multi_file_success() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("one.txt"), "alpha\n").unwrap();
+        fs::write(cwd.join("two.txt"), "beta\n").unwrap();
+
+        let tool = ApplyPatchTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let patch = "\
+diff --git a/one.txt b/one.txt
+--- a/one.txt
++++ b/one.txt
+@@ -1 +1 @@
+-alpha
++first
+diff --git a/two.txt b/two.txt
+--- a/two.txt
++++ b/two.txt
+@@ -1 +1 @@
+-beta
++second
+";
+        let result = tool.call(json!({"patch": patch})).await.unwrap();
+        assert!(result["success"].as_bool().unwrap(), "got: {result}");
+        assert_eq!(result["files"].as_array().unwrap().len(), 2);
+        assert_eq!(fs::read_to_string(cwd.join("one.txt")).unwrap(), "first\n");
+        assert_eq!(fs::read_to_string(cwd.join("two.txt")).unwrap(), "second\n");
+    }
+
+    #[tokio::test]
+    async fn test_apply_patch_creates_file() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+
+        let tool = ApplyPatchTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let patch = "\
+--- /dev/null
++++ b/new.txt
+@@ -0,0 +1,2 @@
++hello
++world
+";
+        let result = tool.call(json!({"patch": patch})).await.unwrap();
+        assert!(result["success"].as_bool().unwrap(), "got: {result}");
+        assert_eq!(result["files"][0]["created"], true);
+        assert_eq!(
+            fs::read_to_string(cwd.join("new.txt")).unwrap(),
+            "hello\nworld\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_apply_patch_deletes_file() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("gone.txt"), "goodbye\n").unwrap();
+
+        let tool = ApplyPatchTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let patch = "\
+--- a/gone.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-goodbye
+";
+        let result = tool.call(json!({"patch": patch})).await.unwrap();
+        assert!(result["success"].as_bool().unwrap(), "got: {result}");
+        assert_eq!(result["files"][0]["deleted"], true);
+        assert!(!cwd.join("gone.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_apply_patch_fuzzy_match_reported() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        // File uses tabs; patch uses spaces
+        let file_path = cwd.join("main.rs");
+        fs::write(&file_path, "fn main() {\n\told();\n}\n").unwrap();
+
+        let tool = ApplyPatchTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let patch = "\
+--- a/main.rs
++++ b/main.rs
+@@ -1,3 +1,3 @@
+ fn main() {
+-    old();
++    new();
+ }
+";
+        let result = tool.call(json!({"patch": patch})).await.unwrap();
+        assert!(result["success"].as_bool().unwrap(), "got: {result}");
+        assert_eq!(result["files"][0]["hunks"][0]["fuzzy"], true);
+        assert!(fs::read_to_string(&file_path).unwrap().contains("new();"));
+    }
+
+    #[tokio::test]
+    async fn test_apply_patch_invalid_patch() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+
+        let tool = ApplyPatchTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let result = tool
+            .call(json!({"patch": "this is not a diff"}))
+            .await
+            .unwrap();
+        assert!(result["error"].as_str().unwrap().contains("parse"));
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+    }
+
+    #[tokio::test]
+    async fn test_apply_patch_missing_file() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+
+        let tool = ApplyPatchTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let patch = "\
+--- a/nope.txt
++++ b/nope.txt
+@@ -1 +1 @@
+-x
++y
+";
+        let result = tool.call(json!({"patch": patch})).await.unwrap();
+        assert!(result["error"].as_str().unwrap().contains("File not found"));
+        assert_eq!(result["error_code"], error_codes::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_apply_patch_dry_run() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("main.rs");
+        fs::write(&file_path, "old\n").unwrap();
+
+        let tool = ApplyPatchTool::new(cwd.clone(), vec![cwd.clone()], None).with_dry_run(true);
+        let patch = "\
+--- a/main.rs
++++ b/main.rs
+@@ -1 +1 @@
+-old
++new
+";
+        let result = tool.call(json!({"patch": patch})).await.unwrap();
+        assert!(result["success"].as_bool().unwrap());
+        assert!(result["dry_run"].as_bool().unwrap());
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "old\n");
+    }
+}
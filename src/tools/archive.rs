@@ -0,0 +1,537 @@
+use async_trait::async_trait;
+use colored::Colorize;
+use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+use tokio::sync::mpsc;
+use tracing::instrument;
+
+use super::{ToolEmitter, error_codes, error_response, resolve_and_validate_path};
+use crate::agent::AgentEvent;
+
+/// Archive format, detected from the file name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Zip,
+    TarGz,
+    Tar,
+}
+
+impl Format {
+    fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_lowercase();
+        if name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(Self::Tar)
+        } else {
+            None
+        }
+    }
+}
+
+/// Create and extract zip/tar archives, confined to allowed paths.
+///
+/// Shells out to `tar`/`unzip`/`zip` (the same externals-over-vendoring
+/// trade the github and screenshot tools make) but owns the flags, so
+/// "unpack this vendored dependency" doesn't depend on whatever tar
+/// invocation the model remembers. Archive entries are listed before
+/// extraction and absolute or `..` paths are refused.
+pub struct ArchiveTool {
+    cwd: PathBuf,
+    allowed_paths: Vec<PathBuf>,
+    events_tx: Option<mpsc::Sender<AgentEvent>>,
+    dry_run: bool,
+}
+
+impl ArchiveTool {
+    pub fn new(
+        cwd: PathBuf,
+        allowed_paths: Vec<PathBuf>,
+        events_tx: Option<mpsc::Sender<AgentEvent>>,
+    ) -> Self {
+        Self {
+            cwd,
+            allowed_paths,
+            events_tx,
+            dry_run: false,
+        }
+    }
+
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// First archive entry that would escape the destination (absolute
+    /// path or a `..` component), if any. Zip-slip protection.
+    fn unsafe_entry(listing: &str) -> Option<&str> {
+        listing.lines().map(str::trim).find(|entry| {
+            entry.starts_with('/')
+                || Path::new(entry)
+                    .components()
+                    .any(|c| matches!(c, std::path::Component::ParentDir))
+        })
+    }
+
+    /// Run a command, mapping a missing binary to a structured NOT_FOUND
+    /// error and a nonzero exit to IO_ERROR with stderr attached.
+    async fn run(mut command: Command, program: &str) -> Result<std::process::Output, Value> {
+        match command.output().await {
+            Ok(output) if output.status.success() => Ok(output),
+            Ok(output) => {
+                let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+                Err(error_response(
+                    &format!("{} failed: {}", program, stderr),
+                    error_codes::IO_ERROR,
+                    json!({"program": program}),
+                ))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(error_response(
+                &format!("{} not found on PATH. Install it to work with archives.", program),
+                error_codes::NOT_FOUND,
+                json!({"program": program}),
+            )),
+            Err(e) => Err(error_response(
+                &format!("Failed to run {}: {}", program, e),
+                error_codes::IO_ERROR,
+                json!({"program": program}),
+            )),
+        }
+    }
+
+    /// List archive entries (one per line).
+    async fn list_entries(format: Format, archive: &Path) -> Result<String, Value> {
+        let (program, args): (&str, Vec<String>) = match format {
+            Format::Zip => ("unzip", vec!["-Z1".into(), archive.display().to_string()]),
+            Format::TarGz => (
+                "tar",
+                vec!["-tzf".into(), archive.display().to_string()],
+            ),
+            Format::Tar => ("tar", vec!["-tf".into(), archive.display().to_string()]),
+        };
+        let mut command = Command::new(program);
+        command.args(&args);
+        let output = Self::run(command, program).await?;
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    async fn extract(&self, format: Format, archive: &Path, dest: &Path) -> Result<Value, Value> {
+        let listing = Self::list_entries(format, archive).await?;
+        if let Some(entry) = Self::unsafe_entry(&listing) {
+            return Err(error_response(
+                &format!(
+                    "Archive entry '{}' would escape the destination. Extraction refused.",
+                    entry
+                ),
+                error_codes::BLOCKED,
+                json!({"entry": entry}),
+            ));
+        }
+        let entry_count = listing.lines().filter(|l| !l.trim().is_empty()).count();
+
+        tokio::fs::create_dir_all(dest).await.map_err(|e| {
+            error_response(
+                &format!("Failed to create destination {}: {}", dest.display(), e),
+                error_codes::IO_ERROR,
+                json!({"path": dest.display().to_string()}),
+            )
+        })?;
+
+        let (program, args): (&str, Vec<String>) = match format {
+            Format::Zip => (
+                "unzip",
+                vec![
+                    "-o".into(),
+                    archive.display().to_string(),
+                    "-d".into(),
+                    dest.display().to_string(),
+                ],
+            ),
+            Format::TarGz => (
+                "tar",
+                vec![
+                    "-xzf".into(),
+                    archive.display().to_string(),
+                    "-C".into(),
+                    dest.display().to_string(),
+                ],
+            ),
+            Format::Tar => (
+                "tar",
+                vec![
+                    "-xf".into(),
+                    archive.display().to_string(),
+                    "-C".into(),
+                    dest.display().to_string(),
+                ],
+            ),
+        };
+        let mut command = Command::new(program);
+        command.args(&args);
+        Self::run(command, program).await?;
+
+        Ok(json!({
+            "action": "extract",
+            "path": archive.display().to_string(),
+            "dest": dest.display().to_string(),
+            "entries": entry_count,
+            "success": true
+        }))
+    }
+
+    async fn create(&self, format: Format, archive: &Path, source: &Path) -> Result<Value, Value> {
+        // Pack relative to the source's parent so the archive contains
+        // `name/...` instead of absolute paths.
+        let parent = source.parent().unwrap_or(Path::new("."));
+        let name = source
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| ".".to_string());
+
+        let (program, command) = match format {
+            Format::Zip => {
+                let mut command = Command::new("zip");
+                command
+                    .arg("-r")
+                    .arg(archive)
+                    .arg(&name)
+                    .current_dir(parent);
+                ("zip", command)
+            }
+            Format::TarGz => {
+                let mut command = Command::new("tar");
+                command
+                    .arg("-czf")
+                    .arg(archive)
+                    .arg("-C")
+                    .arg(parent)
+                    .arg(&name);
+                ("tar", command)
+            }
+            Format::Tar => {
+                let mut command = Command::new("tar");
+                command
+                    .arg("-cf")
+                    .arg(archive)
+                    .arg("-C")
+                    .arg(parent)
+                    .arg(&name);
+                ("tar", command)
+            }
+        };
+        Self::run(command, program).await?;
+
+        let size = tokio::fs::metadata(archive).await.map(|m| m.len()).ok();
+        Ok(json!({
+            "action": "create",
+            "path": archive.display().to_string(),
+            "src": source.display().to_string(),
+            "size": size,
+            "success": true
+        }))
+    }
+}
+
+impl ToolEmitter for ArchiveTool {
+    fn events_tx(&self) -> &Option<mpsc::Sender<AgentEvent>> {
+        &self.events_tx
+    }
+}
+
+#[async_trait]
+impl CallableFunction for ArchiveTool {
+    fn declaration(&self) -> FunctionDeclaration {
+        FunctionDeclaration::new(
+            "archive".to_string(),
+            "Create or extract zip/tar.gz/tar archives within the sandbox. Extraction refuses \
+             entries with absolute or '..' paths. Formats are detected from the file name. \
+             Returns: {action, path, dest?|src?, entries?|size?, success}"
+                .to_string(),
+            FunctionParameters::new(
+                "object".to_string(),
+                json!({
+                    "action": {
+                        "type": "string",
+                        "description": "'extract' unpacks the archive at path, 'create' packs src into a new archive at path"
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "The archive file (.zip, .tar.gz, .tgz, or .tar), absolute or relative to cwd"
+                    },
+                    "dest": {
+                        "type": "string",
+                        "description": "Extraction destination directory, created if missing. (default: cwd)"
+                    },
+                    "src": {
+                        "type": "string",
+                        "description": "File or directory to pack (required for create)"
+                    }
+                }),
+                vec!["action".to_string(), "path".to_string()],
+            ),
+        )
+    }
+
+    #[instrument(skip(self, args))]
+    async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        let action = args
+            .get("action")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing action".to_string()))?;
+        let path_arg = args
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing path".to_string()))?;
+
+        let archive = match resolve_and_validate_path(path_arg, &self.cwd, &self.allowed_paths) {
+            Ok(p) => p,
+            Err(e) => {
+                return Ok(error_response(
+                    &format!("Access denied: {}. Path must be within allowed paths.", e),
+                    error_codes::ACCESS_DENIED,
+                    json!({"path": path_arg}),
+                ));
+            }
+        };
+
+        let Some(format) = Format::from_path(&archive) else {
+            return Ok(error_response(
+                &format!(
+                    "Unsupported archive format: {}. Use .zip, .tar.gz, .tgz, or .tar.",
+                    path_arg
+                ),
+                error_codes::INVALID_ARGUMENT,
+                json!({"path": path_arg}),
+            ));
+        };
+
+        match action {
+            "extract" => {
+                if !archive.exists() {
+                    return Ok(error_response(
+                        &format!("Archive not found: {}", path_arg),
+                        error_codes::NOT_FOUND,
+                        json!({"path": path_arg}),
+                    ));
+                }
+                let dest_arg = args.get("dest").and_then(|v| v.as_str()).unwrap_or(".");
+                let dest =
+                    match resolve_and_validate_path(dest_arg, &self.cwd, &self.allowed_paths) {
+                        Ok(p) => p,
+                        Err(e) => {
+                            return Ok(error_response(
+                                &format!(
+                                    "Access denied: {}. Path must be within allowed paths.",
+                                    e
+                                ),
+                                error_codes::ACCESS_DENIED,
+                                json!({"path": dest_arg}),
+                            ));
+                        }
+                    };
+                if self.dry_run {
+                    self.emit(&format!("  DRY RUN (not extracting): {}", path_arg));
+                    return Ok(json!({
+                        "action": "extract", "path": path_arg, "dry_run": true, "success": true
+                    }));
+                }
+                match self.extract(format, &archive, &dest).await {
+                    Ok(response) => {
+                        self.emit(
+                            &format!(
+                                "  extracted {} entries to {}",
+                                response["entries"], dest_arg
+                            )
+                            .dimmed()
+                            .to_string(),
+                        );
+                        Ok(response)
+                    }
+                    Err(error) => Ok(error),
+                }
+            }
+            "create" => {
+                let Some(src_arg) = args.get("src").and_then(|v| v.as_str()) else {
+                    return Err(FunctionError::ArgumentMismatch(
+                        "Missing src (required for create)".to_string(),
+                    ));
+                };
+                let source =
+                    match resolve_and_validate_path(src_arg, &self.cwd, &self.allowed_paths) {
+                        Ok(p) if p.exists() => p,
+                        Ok(_) => {
+                            return Ok(error_response(
+                                &format!("Source not found: {}", src_arg),
+                                error_codes::NOT_FOUND,
+                                json!({"path": src_arg}),
+                            ));
+                        }
+                        Err(e) => {
+                            return Ok(error_response(
+                                &format!(
+                                    "Access denied: {}. Path must be within allowed paths.",
+                                    e
+                                ),
+                                error_codes::ACCESS_DENIED,
+                                json!({"path": src_arg}),
+                            ));
+                        }
+                    };
+                if self.dry_run {
+                    self.emit(&format!("  DRY RUN (not creating): {}", path_arg));
+                    return Ok(json!({
+                        "action": "create", "path": path_arg, "dry_run": true, "success": true
+                    }));
+                }
+                match self.create(format, &archive, &source).await {
+                    Ok(response) => {
+                        self.emit(
+                            &format!("  created {} from {}", path_arg, src_arg)
+                                .dimmed()
+                                .to_string(),
+                        );
+                        Ok(response)
+                    }
+                    Err(error) => Ok(error),
+                }
+            }
+            other => Ok(error_response(
+                &format!("Unknown action '{}'. Use 'extract' or 'create'.", other),
+                error_codes::INVALID_ARGUMENT,
+                json!({"action": other}),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_format_detection() {
+        assert_eq!(Format::from_path(Path::new("a.zip")), Some(Format::Zip));
+        assert_eq!(Format::from_path(Path::new("a.tar.gz")), Some(Format::TarGz));
+        assert_eq!(Format::from_path(Path::new("a.tgz")), Some(Format::TarGz));
+        assert_eq!(Format::from_path(Path::new("a.tar")), Some(Format::Tar));
+        assert_eq!(Format::from_path(Path::new("a.rar")), None);
+    }
+
+    #[test]
+    fn test_unsafe_entry_detection() {
+        assert_eq!(
+            ArchiveTool::unsafe_entry("src/main.rs\n../escape.txt\n"),
+            Some("../escape.txt")
+        );
+        assert_eq!(
+            ArchiveTool::unsafe_entry("/etc/passwd\n"),
+            Some("/etc/passwd")
+        );
+        assert_eq!(
+            ArchiveTool::unsafe_entry("nested/../../escape\n"),
+            Some("nested/../../escape")
+        );
+        assert_eq!(ArchiveTool::unsafe_entry("src/main.rs\nREADME.md\n"), None);
+    }
+
+    #[tokio::test]
+    async fn test_create_and_extract_tar_gz_roundtrip() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::create_dir(cwd.join("pkg")).unwrap();
+        fs::write(cwd.join("pkg/lib.rs"), "pub fn f() {}\n").unwrap();
+        fs::write(cwd.join("pkg/README.md"), "# pkg\n").unwrap();
+
+        let tool = ArchiveTool::new(cwd.clone(), vec![cwd.clone()], None);
+
+        let created = tool
+            .call(json!({"action": "create", "path": "pkg.tar.gz", "src": "pkg"}))
+            .await
+            .unwrap();
+        assert!(created["success"].as_bool().unwrap(), "got: {created}");
+        assert!(cwd.join("pkg.tar.gz").exists());
+
+        let extracted = tool
+            .call(json!({"action": "extract", "path": "pkg.tar.gz", "dest": "out"}))
+            .await
+            .unwrap();
+        assert!(extracted["success"].as_bool().unwrap(), "got: {extracted}");
+        assert_eq!(
+            fs::read_to_string(cwd.join("out/pkg/lib.rs")).unwrap(),
+            "pub fn f() {}\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_extract_missing_archive() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let tool = ArchiveTool::new(cwd.clone(), vec![cwd], None);
+
+        let result = tool
+            .call(json!({"action": "extract", "path": "missing.tar.gz"}))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_format() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let tool = ArchiveTool::new(cwd.clone(), vec![cwd], None);
+
+        let result = tool
+            .call(json!({"action": "extract", "path": "blob.rar"}))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+    }
+
+    #[tokio::test]
+    async fn test_archive_outside_sandbox_denied() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let tool = ArchiveTool::new(cwd.clone(), vec![cwd], None);
+
+        let result = tool
+            .call(json!({"action": "extract", "path": "/tmp/other.tar.gz"}))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::ACCESS_DENIED);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_touches_nothing() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("f.txt"), "x").unwrap();
+        let tool = ArchiveTool::new(cwd.clone(), vec![cwd.clone()], None).with_dry_run(true);
+
+        let result = tool
+            .call(json!({"action": "create", "path": "out.tar.gz", "src": "f.txt"}))
+            .await
+            .unwrap();
+        assert!(result["dry_run"].as_bool().unwrap());
+        assert!(!cwd.join("out.tar.gz").exists());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_action() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let tool = ArchiveTool::new(cwd.clone(), vec![cwd], None);
+
+        let result = tool
+            .call(json!({"action": "inspect", "path": "a.tar.gz"}))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+    }
+}
@@ -4,10 +4,41 @@ use async_trait::async_trait;
 use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
 use serde_json::{Value, json};
 use std::io;
+use std::path::PathBuf;
 use tokio::sync::mpsc;
 use tracing::instrument;
 
+/// What kind of input the question expects. Drives prompt rendering and
+/// how the raw answer is interpreted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum InputKind {
+    /// Free text, or single-choice when options are given.
+    Text,
+    /// Multiple selections from options, comma-separated.
+    MultiSelect,
+    /// Yes/no; anything not recognized as yes counts as no.
+    Confirm,
+    /// Masked input; never echoed to the terminal.
+    Secret,
+    /// A path, reported with whether it exists.
+    FilePath,
+}
+
+impl InputKind {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "text" => Some(InputKind::Text),
+            "multi_select" => Some(InputKind::MultiSelect),
+            "confirm" => Some(InputKind::Confirm),
+            "secret" => Some(InputKind::Secret),
+            "file_path" => Some(InputKind::FilePath),
+            _ => None,
+        }
+    }
+}
+
 pub struct AskUserTool {
+    cwd: PathBuf,
     events_tx: Option<mpsc::Sender<AgentEvent>>,
 }
 
@@ -18,8 +49,8 @@ impl ToolEmitter for AskUserTool {
 }
 
 impl AskUserTool {
-    pub fn new(events_tx: Option<mpsc::Sender<AgentEvent>>) -> Self {
-        Self { events_tx }
+    pub fn new(cwd: PathBuf, events_tx: Option<mpsc::Sender<AgentEvent>>) -> Self {
+        Self { cwd, events_tx }
     }
 
     /// Resolve user's answer - if they entered a number matching an option, return the option value
@@ -34,7 +65,30 @@ impl AskUserTool {
         answer.to_string()
     }
 
-    fn parse_args(&self, args: Value) -> Result<(String, Option<Vec<String>>), FunctionError> {
+    /// Resolve a comma-separated multi-select answer ("1,3" or "red, green")
+    /// to option values, skipping empty segments.
+    fn resolve_multi(answer: &str, options: &Option<Vec<String>>) -> Vec<String> {
+        answer
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| Self::resolve_answer(s, options))
+            .collect()
+    }
+
+    /// Interpret a confirm answer. Only an explicit yes confirms.
+    /// Shared with `request_path_access`, which uses the same y/n prompt.
+    pub(crate) fn parse_confirm(answer: &str) -> bool {
+        matches!(
+            answer.trim().to_lowercase().as_str(),
+            "y" | "yes" | "true" | "1"
+        )
+    }
+
+    fn parse_args(
+        &self,
+        args: &Value,
+    ) -> Result<(String, Option<Vec<String>>, InputKind), FunctionError> {
         let question = args
             .get("question")
             .and_then(|v| v.as_str())
@@ -47,7 +101,16 @@ impl AskUserTool {
                 .collect()
         });
 
-        Ok((question, options))
+        let kind = match args.get("kind").and_then(|v| v.as_str()) {
+            Some(s) => InputKind::parse(s).ok_or_else(|| {
+                FunctionError::ArgumentMismatch(format!(
+                    "Unknown kind '{s}'. Use text, multi_select, confirm, secret, or file_path."
+                ))
+            })?,
+            None => InputKind::Text,
+        };
+
+        Ok((question, options, kind))
     }
 }
 
@@ -56,7 +119,7 @@ impl CallableFunction for AskUserTool {
     fn declaration(&self) -> FunctionDeclaration {
         FunctionDeclaration::new(
             "ask_user".to_string(),
-            "Ask the user a question and wait for their response. Use this when you need clarification or a decision from the user. Returns: {answer}. When options are provided, they are displayed numbered and the user's selection is resolved to the option value.".to_string(),
+            "Ask the user a question and wait for their response. Use this when you need clarification or a decision from the user. Returns: {answer} (plus {answers} for multi_select, {confirmed} for confirm, {exists} for file_path). When options are provided, they are displayed numbered and the user's selection is resolved to the option value.".to_string(),
             FunctionParameters::new(
                 "object".to_string(),
                 json!({
@@ -68,6 +131,11 @@ impl CallableFunction for AskUserTool {
                         "type": "array",
                         "items": { "type": "string" },
                         "description": "Optional list of options for multiple choice"
+                    },
+                    "kind": {
+                        "type": "string",
+                        "enum": ["text", "multi_select", "confirm", "secret", "file_path"],
+                        "description": "Input kind: multi_select allows comma-separated choices from options, confirm is yes/no, secret masks the input (for tokens/passwords), file_path reports whether the entered path exists. (default: text)"
                     }
                 }),
                 vec!["question".to_string()],
@@ -77,7 +145,7 @@ impl CallableFunction for AskUserTool {
 
     #[instrument(skip(self, args))]
     async fn call(&self, args: Value) -> Result<Value, FunctionError> {
-        let (question, options) = self.parse_args(args)?;
+        let (question, options, kind) = self.parse_args(&args)?;
 
         self.emit(&format!("  {}", question));
 
@@ -94,14 +162,58 @@ impl CallableFunction for AskUserTool {
                 self.emit(&format!("  {}", opt));
             }
         }
+        match kind {
+            InputKind::MultiSelect => self.emit("  (comma-separated, e.g. 1,3)"),
+            InputKind::Confirm => self.emit("  (y/n)"),
+            InputKind::Secret => self.emit("  (input hidden)"),
+            _ => {}
+        }
+
+        if kind == InputKind::Secret {
+            // Masked read: the answer is never echoed. It still flows back to
+            // the model as a tool result, so only use this for values the
+            // model genuinely needs.
+            return match rpassword::read_password() {
+                Ok(secret) => Ok(json!({ "answer": secret, "secret": true })),
+                Err(e) => Ok(json!({
+                    "error": format!("Failed to read secret input: {}", e)
+                })),
+            };
+        }
 
         let mut answer = String::new();
         match io::stdin().read_line(&mut answer) {
             Ok(_) => {
                 let answer = answer.trim();
-
-                let resolved = Self::resolve_answer(answer, &options);
-                Ok(json!({ "answer": resolved }))
+                match kind {
+                    InputKind::Text => {
+                        let resolved = Self::resolve_answer(answer, &options);
+                        Ok(json!({ "answer": resolved }))
+                    }
+                    InputKind::MultiSelect => {
+                        let answers = Self::resolve_multi(answer, &options);
+                        Ok(json!({ "answer": answers.join(", "), "answers": answers }))
+                    }
+                    InputKind::Confirm => {
+                        let confirmed = Self::parse_confirm(answer);
+                        Ok(json!({
+                            "answer": if confirmed { "yes" } else { "no" },
+                            "confirmed": confirmed
+                        }))
+                    }
+                    InputKind::FilePath => {
+                        let path = if std::path::Path::new(answer).is_absolute() {
+                            PathBuf::from(answer)
+                        } else {
+                            self.cwd.join(answer)
+                        };
+                        Ok(json!({
+                            "answer": answer,
+                            "exists": path.exists()
+                        }))
+                    }
+                    InputKind::Secret => unreachable!("handled above"),
+                }
             }
             Err(e) => Ok(json!({
                 "error": format!("Failed to read from stdin: {}", e)
@@ -116,9 +228,13 @@ mod tests {
     use genai_rs::CallableFunction;
     use serde_json::json;
 
+    fn test_tool() -> AskUserTool {
+        AskUserTool::new(std::env::temp_dir(), None)
+    }
+
     #[test]
     fn test_declaration() {
-        let tool = AskUserTool::new(None);
+        let tool = test_tool();
         let decl = tool.declaration();
 
         assert_eq!(decl.name(), "ask_user");
@@ -136,17 +252,21 @@ mod tests {
 
         assert_eq!(properties["question"]["type"], "string");
         assert_eq!(properties["options"]["type"], "array");
+        assert_eq!(
+            properties["kind"]["enum"],
+            json!(["text", "multi_select", "confirm", "secret", "file_path"])
+        );
     }
 
     #[test]
     fn test_parse_args_success() {
-        let tool = AskUserTool::new(None);
+        let tool = test_tool();
         let args = json!({
             "question": "What is your favorite color?",
             "options": ["Red", "Blue", "Green"]
         });
 
-        let (question, options) = tool.parse_args(args).unwrap();
+        let (question, options, kind) = tool.parse_args(&args).unwrap();
         assert_eq!(question, "What is your favorite color?");
         assert_eq!(
             options,
@@ -156,28 +276,29 @@ mod tests {
                 "Green".to_string()
             ])
         );
+        assert_eq!(kind, InputKind::Text);
     }
 
     #[test]
     fn test_parse_args_no_options() {
-        let tool = AskUserTool::new(None);
+        let tool = test_tool();
         let args = json!({
             "question": "How are you?"
         });
 
-        let (question, options) = tool.parse_args(args).unwrap();
+        let (question, options, _) = tool.parse_args(&args).unwrap();
         assert_eq!(question, "How are you?");
         assert_eq!(options, None);
     }
 
     #[test]
     fn test_parse_args_missing_question() {
-        let tool = AskUserTool::new(None);
+        let tool = test_tool();
         let args = json!({
             "options": ["Yes", "No"]
         });
 
-        let result = tool.parse_args(args);
+        let result = tool.parse_args(&args);
         assert!(result.is_err());
         match result {
             Err(FunctionError::ArgumentMismatch(msg)) => assert_eq!(msg, "Missing question"),
@@ -187,39 +308,39 @@ mod tests {
 
     #[test]
     fn test_parse_args_empty_options() {
-        let tool = AskUserTool::new(None);
+        let tool = test_tool();
         let args = json!({
             "question": "Empty options?",
             "options": []
         });
 
-        let (question, options) = tool.parse_args(args).unwrap();
+        let (question, options, _) = tool.parse_args(&args).unwrap();
         assert_eq!(question, "Empty options?");
         assert_eq!(options, Some(vec![]));
     }
 
     #[test]
     fn test_parse_args_null_options() {
-        let tool = AskUserTool::new(None);
+        let tool = test_tool();
         let args = json!({
             "question": "Null options?",
             "options": null
         });
 
-        let (question, options) = tool.parse_args(args).unwrap();
+        let (question, options, _) = tool.parse_args(&args).unwrap();
         assert_eq!(question, "Null options?");
         assert_eq!(options, None);
     }
 
     #[test]
     fn test_parse_args_invalid_options_items() {
-        let tool = AskUserTool::new(None);
+        let tool = test_tool();
         let args = json!({
             "question": "Mixed options?",
             "options": ["Valid", 123, null, "Also Valid"]
         });
 
-        let (question, options) = tool.parse_args(args).unwrap();
+        let (question, options, _) = tool.parse_args(&args).unwrap();
         assert_eq!(question, "Mixed options?");
         assert_eq!(
             options,
@@ -227,6 +348,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_args_kinds() {
+        let tool = test_tool();
+        for (name, expected) in [
+            ("text", InputKind::Text),
+            ("multi_select", InputKind::MultiSelect),
+            ("confirm", InputKind::Confirm),
+            ("secret", InputKind::Secret),
+            ("file_path", InputKind::FilePath),
+        ] {
+            let (_, _, kind) = tool
+                .parse_args(&json!({"question": "?", "kind": name}))
+                .unwrap();
+            assert_eq!(kind, expected);
+        }
+
+        let result = tool.parse_args(&json!({"question": "?", "kind": "telepathy"}));
+        match result {
+            Err(FunctionError::ArgumentMismatch(msg)) => assert!(msg.contains("telepathy")),
+            _ => panic!("Expected ArgumentMismatch error"),
+        }
+    }
+
     #[test]
     fn test_resolve_answer_with_number() {
         let options = Some(vec![
@@ -262,4 +406,34 @@ mod tests {
         assert_eq!(AskUserTool::resolve_answer("1", &None), "1");
         assert_eq!(AskUserTool::resolve_answer("hello", &None), "hello");
     }
+
+    #[test]
+    fn test_resolve_multi() {
+        let options = Some(vec![
+            "red".to_string(),
+            "blue".to_string(),
+            "green".to_string(),
+        ]);
+        assert_eq!(
+            AskUserTool::resolve_multi("1,3", &options),
+            vec!["red", "green"]
+        );
+        assert_eq!(
+            AskUserTool::resolve_multi(" 2 , blue ", &options),
+            vec!["blue", "blue"]
+        );
+        // Empty segments are skipped
+        assert_eq!(AskUserTool::resolve_multi("1,,2,", &options).len(), 2);
+        assert!(AskUserTool::resolve_multi("", &options).is_empty());
+    }
+
+    #[test]
+    fn test_parse_confirm() {
+        for yes in ["y", "Y", "yes", "YES", "true", "1"] {
+            assert!(AskUserTool::parse_confirm(yes), "{yes} should confirm");
+        }
+        for no in ["n", "no", "nope", "", "maybe", "2"] {
+            assert!(!AskUserTool::parse_confirm(no), "{no} should not confirm");
+        }
+    }
 }
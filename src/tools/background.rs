@@ -1,10 +1,14 @@
+use colored::Colorize;
+use std::io::Write;
 use std::sync::atomic::{AtomicBool, AtomicI32};
 use std::sync::{Arc, Mutex};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Child;
+use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 
 use super::MAX_BACKGROUND_BUFFER_LEN;
+use crate::agent::AgentEvent;
 
 /// Represents a running or completed background task.
 pub struct BackgroundTask {
@@ -79,7 +83,21 @@ impl BackgroundTask {
 
     /// Create a new background task from a spawned child process.
     /// Starts background tasks to collect stdout and stderr.
-    pub fn new(mut child: Child) -> Self {
+    pub fn new(child: Child) -> Self {
+        Self::new_streaming(child, None, "", None)
+    }
+
+    /// Like [`BackgroundTask::new`], but also streams each output line to the
+    /// UI as `AgentEvent::ToolOutput` as it arrives, tagged with `task_id` so
+    /// lines from interleaved tasks stay distinguishable. When `log_file` is
+    /// provided, both streams are additionally teed to it (interleaved) so
+    /// the output survives a clemini restart.
+    pub fn new_streaming(
+        mut child: Child,
+        events_tx: Option<mpsc::Sender<AgentEvent>>,
+        task_id: &str,
+        log_file: Option<std::fs::File>,
+    ) -> Self {
         let stdout = child.stdout.take();
         let stderr = child.stderr.take();
 
@@ -88,8 +106,24 @@ impl BackgroundTask {
         let completed = Arc::new(AtomicBool::new(false));
         let exit_code = Arc::new(AtomicI32::new(0));
 
-        let stdout_task = stdout.map(|s| spawn_output_collector(s, stdout_buffer.clone()));
-        let stderr_task = stderr.map(|s| spawn_output_collector(s, stderr_buffer.clone()));
+        let log_file = log_file.map(|f| Arc::new(Mutex::new(f)));
+
+        let stdout_task = stdout.map(|s| {
+            spawn_output_collector(
+                s,
+                stdout_buffer.clone(),
+                events_tx.clone().map(|tx| (tx, task_id.to_string())),
+                log_file.clone(),
+            )
+        });
+        let stderr_task = stderr.map(|s| {
+            spawn_output_collector(
+                s,
+                stderr_buffer.clone(),
+                events_tx.map(|tx| (tx, task_id.to_string())),
+                log_file,
+            )
+        });
 
         // Status is checked lazily via update_status() when TaskOutput is called.
 
@@ -139,30 +173,54 @@ impl BackgroundTask {
 }
 
 /// Helper to spawn a task that reads a stream into a buffer.
+///
+/// When `events` is provided, each line is also forwarded to the UI as
+/// `AgentEvent::ToolOutput`, prefixed with the task ID. When `log_file` is
+/// provided, each line is appended to it (shared between the stdout and
+/// stderr collectors, so the log interleaves both).
 fn spawn_output_collector<R: tokio::io::AsyncRead + Unpin + Send + 'static>(
     stream: R,
     buffer: Arc<Mutex<String>>,
+    events: Option<(mpsc::Sender<AgentEvent>, String)>,
+    log_file: Option<Arc<Mutex<std::fs::File>>>,
 ) -> JoinHandle<()> {
     tokio::spawn(async move {
         let mut reader = BufReader::new(stream).lines();
         loop {
             match reader.next_line().await {
                 Ok(Some(line)) => {
-                    let mut buf = match buffer.lock() {
-                        Ok(guard) => guard,
-                        Err(poisoned) => {
-                            tracing::warn!("buffer lock poisoned during collection, recovering");
-                            poisoned.into_inner()
+                    {
+                        let mut buf = match buffer.lock() {
+                            Ok(guard) => guard,
+                            Err(poisoned) => {
+                                tracing::warn!(
+                                    "buffer lock poisoned during collection, recovering"
+                                );
+                                poisoned.into_inner()
+                            }
+                        };
+                        buf.push_str(&line);
+                        buf.push('\n');
+                        // Limit buffer size to prevent memory exhaustion
+                        if buf.len() > MAX_BACKGROUND_BUFFER_LEN {
+                            let len = buf.len();
+                            buf.truncate(MAX_BACKGROUND_BUFFER_LEN);
+                            buf.push_str(&format!("\n... [truncated, {} bytes total]", len));
+                            break;
                         }
-                    };
-                    buf.push_str(&line);
-                    buf.push('\n');
-                    // Limit buffer size to prevent memory exhaustion
-                    if buf.len() > MAX_BACKGROUND_BUFFER_LEN {
-                        let len = buf.len();
-                        buf.truncate(MAX_BACKGROUND_BUFFER_LEN);
-                        buf.push_str(&format!("\n... [truncated, {} bytes total]", len));
-                        break;
+                    }
+                    if let Some(log) = &log_file {
+                        let mut file = match log.lock() {
+                            Ok(guard) => guard,
+                            Err(poisoned) => poisoned.into_inner(),
+                        };
+                        let _ = writeln!(file, "{}", line);
+                    }
+                    if let Some((tx, tag)) = &events {
+                        let _ = tx.try_send(AgentEvent::ToolOutput(format!(
+                            "  {}",
+                            format!("[{}] {}", tag, line).dimmed()
+                        )));
                     }
                 }
                 Ok(None) => break, // EOF
@@ -234,6 +292,57 @@ mod tests {
         assert!(task.stderr().contains("hello_stderr"));
     }
 
+    #[tokio::test]
+    async fn test_background_task_streams_tagged_output_events() {
+        let (tx, mut rx) = mpsc::channel(100);
+        let child = Command::new("sh")
+            .arg("-c")
+            .arg("echo line_one; echo line_two >&2")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let _task = BackgroundTask::new_streaming(child, Some(tx), "bg-test", None);
+
+        // Wait for output collection
+        sleep(Duration::from_millis(100)).await;
+
+        let mut lines = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            let AgentEvent::ToolOutput(line) = event else {
+                panic!("unexpected event type");
+            };
+            lines.push(line);
+        }
+
+        // Both stdout and stderr lines stream, each tagged with the task ID
+        assert!(
+            lines.iter().any(|l| l.contains("[bg-test] line_one")),
+            "lines: {lines:?}"
+        );
+        assert!(
+            lines.iter().any(|l| l.contains("[bg-test] line_two")),
+            "lines: {lines:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_background_task_without_events_still_buffers() {
+        let child = Command::new("echo")
+            .arg("buffered")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let task = BackgroundTask::new_streaming(child, None, "bg-unused", None);
+
+        sleep(Duration::from_millis(100)).await;
+
+        assert!(task.stdout().contains("buffered"));
+    }
+
     #[tokio::test]
     async fn test_update_status_detects_completion() {
         let child = Command::new("echo")
@@ -4,16 +4,20 @@
 //! - Pattern-based safety validation (blocked and caution patterns)
 //! - Confirmation flow for destructive commands
 //! - Background task support
+//! - Persistent shell sessions (state survives across calls)
 //! - Streaming output capture
 //! - Timeout handling
 
+mod pty;
 mod safety;
+mod session;
 
-pub use safety::{is_blocked, needs_caution};
+pub use pty::PtyTask;
+pub use safety::{BashSafetyToml, SafetyPolicy, is_blocked, needs_caution};
+pub use session::cleanup_sessions;
 
 use crate::agent::AgentEvent;
-use crate::tools::background::BackgroundTask;
-use crate::tools::tasks::register_background_task;
+use crate::tools::tasks::{register_pty_task, register_streaming_background_task};
 use crate::tools::{MAX_TOOL_OUTPUT_LEN, ToolEmitter, error_codes, error_response};
 use async_trait::async_trait;
 use colored::Colorize;
@@ -39,6 +43,10 @@ pub struct BashTool {
     /// Shared with CleminiToolService to track confirmation state across tool invocations.
     /// When None, confirmation tracking is disabled (used in tests).
     pending_confirmations: Option<Arc<RwLock<HashSet<String>>>>,
+    dry_run: bool,
+    /// Safety patterns: built-in defaults plus user-configured additions
+    /// and exemptions from the `[bash]` config section.
+    safety: SafetyPolicy,
 }
 
 impl BashTool {
@@ -57,9 +65,25 @@ impl BashTool {
             is_mcp_mode,
             events_tx,
             pending_confirmations: Some(pending_confirmations),
+            dry_run: false,
+            safety: SafetyPolicy::default(),
         }
     }
 
+    /// In dry-run mode no command is executed - any command could mutate
+    /// state, so all are reported back as proposed (not run).
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Replace the default safety policy (built-in patterns only) with one
+    /// that includes user-configured patterns from the `[bash]` config section.
+    pub fn with_safety_policy(mut self, safety: SafetyPolicy) -> Self {
+        self.safety = safety;
+        self
+    }
+
     /// Create a BashTool without confirmation tracking (for tests).
     #[cfg(test)]
     pub fn new_without_confirmation_tracking(
@@ -76,10 +100,12 @@ impl BashTool {
             is_mcp_mode,
             events_tx,
             pending_confirmations: None,
+            dry_run: false,
+            safety: SafetyPolicy::default(),
         }
     }
 
-    fn truncate_output(output: String, max_len: usize) -> String {
+    pub(crate) fn truncate_output(output: String, max_len: usize) -> String {
         if output.len() > max_len {
             // Find last valid UTF-8 boundary at or before max_len
             let mut end = max_len;
@@ -96,7 +122,10 @@ impl BashTool {
         }
     }
 
-    fn confirm_execution(&self, command: &str) -> bool {
+    /// Prompt the user before running a command that matched `pattern`.
+    /// `a`/`always` approves and exempts the pattern for the session, so
+    /// repeated hits on the same caution pattern stop prompting.
+    fn confirm_execution(&self, command: &str, pattern: &str) -> bool {
         let msg = format!(
             "\n⚠️  This command may be destructive:\n    {}",
             command.bold()
@@ -104,12 +133,16 @@ impl BashTool {
         eprintln!("{}", msg);
         self.emit(&msg);
 
-        eprint!("Proceed? [y/N] ");
+        eprint!("Proceed? [y/N/a=always allow '{pattern}' this session] ");
         let _ = io::stderr().flush();
 
         let mut answer = String::new();
         if io::stdin().read_line(&mut answer).is_ok() {
             let answer = answer.trim().to_lowercase();
+            if answer == "a" || answer == "always" {
+                self.safety.exempt_caution_pattern(pattern);
+                return true;
+            }
             answer == "y" || answer == "yes"
         } else {
             false
@@ -128,7 +161,7 @@ impl CallableFunction for BashTool {
     fn declaration(&self) -> FunctionDeclaration {
         FunctionDeclaration::new(
             "bash".to_string(),
-            "Execute a bash command and return the output. Use for builds, tests, git, and shell commands. Returns: {stdout, stderr, exit_code} or {task_id, status} when run_in_background=true".to_string(),
+            "Execute a bash command and return the output. Use for builds, tests, git, and shell commands. Pass a 'session_id' to run in a persistent shell where environment variables, cd, and virtualenv activation survive across calls. Returns: {stdout, stderr, exit_code} or {task_id, status} when run_in_background=true".to_string(),
             FunctionParameters::new(
                 "object".to_string(),
                 json!({
@@ -155,6 +188,14 @@ impl CallableFunction for BashTool {
                     "run_in_background": {
                         "type": "boolean",
                         "description": "If true, run the command in the background and return a task_id immediately. (default: false)"
+                    },
+                    "session_id": {
+                        "type": "string",
+                        "description": "Run the command in a persistent named shell session (created on first use). Shell state - exported variables, cd, activated environments - persists across calls with the same session_id. Sessions are cleaned up when the interaction ends. Cannot be combined with run_in_background."
+                    },
+                    "interactive": {
+                        "type": "boolean",
+                        "description": "If true, run the command under a pseudo-terminal for programs that require a TTY (interactive rebases, REPLs, pagers). Returns a task_id immediately; poll output with task_output and inject keystrokes with send_input (e.g. relaying an answer from ask_user). (default: false)"
                     }
                 }),
                 vec!["command".to_string()],
@@ -186,6 +227,29 @@ impl CallableFunction for BashTool {
             .and_then(serde_json::Value::as_bool)
             .unwrap_or(false);
 
+        let session_id = args.get("session_id").and_then(|v| v.as_str());
+
+        let interactive = args
+            .get("interactive")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false);
+
+        if session_id.is_some() && run_in_background {
+            return Ok(error_response(
+                "'session_id' cannot be combined with 'run_in_background': session commands run to completion in the shared shell.",
+                error_codes::INVALID_ARGUMENT,
+                json!({"command": command}),
+            ));
+        }
+
+        if interactive && (run_in_background || session_id.is_some()) {
+            return Ok(error_response(
+                "'interactive' cannot be combined with 'run_in_background' or 'session_id': PTY tasks already run in the background with their own terminal.",
+                error_codes::INVALID_ARGUMENT,
+                json!({"command": command}),
+            ));
+        }
+
         let working_dir = if let Some(wd) = args.get("working_directory").and_then(|v| v.as_str()) {
             match crate::tools::resolve_and_validate_path(wd, &self.cwd, &self.allowed_paths) {
                 Ok(path) => path,
@@ -205,7 +269,7 @@ impl CallableFunction for BashTool {
         };
 
         // Safety check
-        if let Some(pattern) = is_blocked(command) {
+        if let Some(pattern) = self.safety.is_blocked(command) {
             let msg = format!(
                 "  {} {}",
                 format!("BLOCKED (matches pattern: {pattern}):").red(),
@@ -222,7 +286,24 @@ impl CallableFunction for BashTool {
             ));
         }
 
-        if needs_caution(command) {
+        if self.dry_run {
+            let msg = format!(
+                "  {} {}",
+                "DRY RUN (not executed):".yellow(),
+                command.dimmed()
+            );
+            self.emit(&msg);
+            return Ok(json!({
+                "stdout": "",
+                "stderr": "",
+                "exit_code": 0,
+                "dry_run": true,
+                "command": command,
+                "note": "Dry-run mode: command was not executed."
+            }));
+        }
+
+        if let Some(caution_pattern) = self.safety.caution_pattern(command) {
             if self.is_mcp_mode {
                 if !confirmed {
                     // Add to pending confirmations so we can verify later
@@ -278,7 +359,7 @@ impl CallableFunction for BashTool {
                         ));
                     }
                 }
-            } else if !confirmed && !self.confirm_execution(command) {
+            } else if !confirmed && !self.confirm_execution(command, &caution_pattern) {
                 let msg = format!("  {} {}", "CANCELLED:".red(), command.dimmed());
                 self.emit(&msg);
                 return Ok(error_response(
@@ -298,6 +379,111 @@ impl CallableFunction for BashTool {
             self.emit(&msg);
         }
 
+        if interactive {
+            let task = match pty::PtyTask::spawn(command, &working_dir, self.events_tx.clone()) {
+                Ok(t) => t,
+                Err(e) => {
+                    return Ok(error_response(
+                        &format!("Failed to spawn PTY for command: {}", e),
+                        error_codes::IO_ERROR,
+                        json!({"command": command}),
+                    ));
+                }
+            };
+            let task_id = register_pty_task(task);
+
+            let mut response = json!({
+                "command": command,
+                "task_id": task_id,
+                "status": "running",
+                "note": "Interactive PTY task. Poll output with task_output; inject keystrokes with send_input; kill with kill_shell."
+            });
+            if let Some(desc) = description {
+                response["description"] = json!(desc);
+            }
+            return Ok(response);
+        }
+
+        if let Some(session_id) = session_id {
+            let session = match session::get_or_create_session(session_id, &working_dir) {
+                Ok(s) => s,
+                Err(e) => {
+                    return Ok(error_response(
+                        &format!("Failed to start shell session '{}': {}", session_id, e),
+                        error_codes::IO_ERROR,
+                        json!({"command": command, "session_id": session_id}),
+                    ));
+                }
+            };
+
+            let timeout_duration = std::time::Duration::from_secs(timeout_secs);
+            let result = session.lock().await.run(command, timeout_duration).await;
+
+            return match result {
+                Ok(output) => {
+                    const MAX_LOG_LINES: usize = 10;
+                    for (count, line) in output
+                        .stdout
+                        .lines()
+                        .chain(output.stderr.lines())
+                        .enumerate()
+                    {
+                        if count < MAX_LOG_LINES {
+                            self.emit(&format!("  {}", line.dimmed()));
+                        } else {
+                            self.emit(&format!("  {}", "[...more output...]".dimmed()));
+                            break;
+                        }
+                    }
+
+                    let mut response = json!({
+                        "command": command,
+                        "session_id": session_id,
+                        "exit_code": output.exit_code,
+                        "stdout": Self::truncate_output(output.stdout, MAX_TOOL_OUTPUT_LEN),
+                        "stderr": Self::truncate_output(output.stderr, MAX_TOOL_OUTPUT_LEN),
+                        "success": output.exit_code == 0
+                    });
+                    if let Some(desc) = description {
+                        response["description"] = json!(desc);
+                    }
+                    Ok(response)
+                }
+                Err(e) => {
+                    // The session is unusable in every error case - drop it
+                    // so the next call with this ID starts a fresh shell
+                    session::remove_session(session_id);
+                    match e {
+                        session::SessionError::TimedOut => Ok(error_response(
+                            &format!(
+                                "Command timed out after {} seconds; shell session '{}' was terminated. A new session will be created on the next call with this session_id.",
+                                timeout_secs, session_id
+                            ),
+                            error_codes::TIMEOUT,
+                            json!({
+                                "command": command,
+                                "session_id": session_id,
+                                "timeout_seconds": timeout_secs
+                            }),
+                        )),
+                        session::SessionError::Exited => Ok(error_response(
+                            &format!(
+                                "Shell session '{}' exited before the command completed (did the command run 'exit'?). A new session will be created on the next call with this session_id.",
+                                session_id
+                            ),
+                            error_codes::IO_ERROR,
+                            json!({"command": command, "session_id": session_id}),
+                        )),
+                        session::SessionError::Io(msg) => Ok(error_response(
+                            &format!("Shell session '{}' failed: {}", session_id, msg),
+                            error_codes::IO_ERROR,
+                            json!({"command": command, "session_id": session_id}),
+                        )),
+                    }
+                }
+            };
+        }
+
         if run_in_background {
             let child = Command::new("bash")
                 .arg("-c")
@@ -311,8 +497,10 @@ impl CallableFunction for BashTool {
                     FunctionError::ExecutionError(format!("Failed to spawn process: {}", e).into())
                 })?;
 
-            // Register in unified task registry with namespaced ID (bg-1, bg-2, etc.)
-            let task_id = register_background_task(BackgroundTask::new(child));
+            // Register in unified task registry with namespaced ID (bg-1, bg-2, etc.).
+            // Output lines stream to the UI as they arrive, tagged with the ID.
+            let task_id =
+                register_streaming_background_task(child, self.events_tx.clone(), command);
 
             let mut response = json!({
                 "command": command,
@@ -478,6 +666,72 @@ mod tests {
         assert_eq!(result["stdout"].as_str().unwrap().trim(), "hello world");
     }
 
+    #[tokio::test]
+    async fn test_bash_tool_dry_run_skips_execution() {
+        let dir = tempdir().unwrap();
+        let marker = dir.path().join("marker.txt");
+        let tool = BashTool::new_without_confirmation_tracking(
+            dir.path().to_path_buf(),
+            vec![dir.path().to_path_buf()],
+            5,
+            false,
+            None,
+        )
+        .with_dry_run(true);
+        let args = json!({ "command": format!("touch {}", marker.display()) });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["dry_run"].as_bool().unwrap());
+        assert_eq!(result["exit_code"], 0);
+        assert!(!marker.exists());
+    }
+
+    #[tokio::test]
+    async fn test_bash_tool_dry_run_still_blocks_dangerous_commands() {
+        let dir = tempdir().unwrap();
+        let tool = BashTool::new_without_confirmation_tracking(
+            dir.path().to_path_buf(),
+            vec![dir.path().to_path_buf()],
+            5,
+            false,
+            None,
+        )
+        .with_dry_run(true);
+        let args = json!({ "command": "rm -rf /" });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["error"].as_str().unwrap().contains("blocked"));
+        assert_eq!(result["error_code"], error_codes::BLOCKED);
+    }
+
+    #[tokio::test]
+    async fn test_bash_tool_configured_blocked_pattern() {
+        let dir = tempdir().unwrap();
+        let tool = BashTool::new_without_confirmation_tracking(
+            dir.path().to_path_buf(),
+            vec![dir.path().to_path_buf()],
+            5,
+            false,
+            None,
+        )
+        .with_safety_policy(SafetyPolicy::from_config(&BashSafetyToml {
+            blocked: vec![r"echo\s+forbidden".to_string()],
+            ..Default::default()
+        }));
+
+        let result = tool
+            .call(json!({ "command": "echo forbidden" }))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::BLOCKED);
+
+        // Unrelated commands (and built-in checks) are unaffected
+        let result = tool.call(json!({ "command": "echo fine" })).await.unwrap();
+        assert_eq!(result["exit_code"], 0);
+        let result = tool.call(json!({ "command": "rm -rf /" })).await.unwrap();
+        assert_eq!(result["error_code"], error_codes::BLOCKED);
+    }
+
     #[tokio::test]
     async fn test_bash_tool_description() {
         let dir = tempdir().unwrap();
@@ -670,6 +924,44 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_bash_tool_background_streams_tagged_output() {
+        let dir = tempdir().unwrap();
+        let (tx, mut rx) = mpsc::channel(100);
+        let tool = BashTool::new_without_confirmation_tracking(
+            dir.path().to_path_buf(),
+            vec![dir.path().to_path_buf()],
+            5,
+            false,
+            Some(tx),
+        );
+
+        let result = tool
+            .call(json!({
+                "command": "echo live_line",
+                "run_in_background": true
+            }))
+            .await
+            .unwrap();
+        let task_id = result["task_id"].as_str().unwrap().to_string();
+
+        // Give the reader tasks time to stream the line
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+
+        let mut saw_tagged_line = false;
+        while let Ok(event) = rx.try_recv() {
+            if let AgentEvent::ToolOutput(line) = event
+                && line.contains(&format!("[{}] live_line", task_id))
+            {
+                saw_tagged_line = true;
+            }
+        }
+        assert!(saw_tagged_line, "no tagged output line for {task_id}");
+
+        // Cleanup
+        TASKS.lock().unwrap().remove(&task_id);
+    }
+
     #[tokio::test]
     async fn test_bash_tool_background_unique_ids() {
         let dir = tempdir().unwrap();
@@ -718,6 +1010,97 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_bash_tool_session_state_persists() {
+        let dir = tempdir().unwrap();
+        let tool = BashTool::new_without_confirmation_tracking(
+            dir.path().to_path_buf(),
+            vec![dir.path().to_path_buf()],
+            5,
+            false,
+            None,
+        );
+
+        let result = tool
+            .call(json!({
+                "command": "export SESSION_TEST_VAR=persisted",
+                "session_id": "test-bash-tool-session"
+            }))
+            .await
+            .unwrap();
+        assert!(result["success"].as_bool().unwrap(), "got: {result}");
+        assert_eq!(result["session_id"], "test-bash-tool-session");
+
+        let result = tool
+            .call(json!({
+                "command": "echo $SESSION_TEST_VAR",
+                "session_id": "test-bash-tool-session"
+            }))
+            .await
+            .unwrap();
+        assert!(result["success"].as_bool().unwrap());
+        assert_eq!(result["stdout"].as_str().unwrap().trim(), "persisted");
+
+        // Cleanup
+        session::remove_session("test-bash-tool-session");
+    }
+
+    #[tokio::test]
+    async fn test_bash_tool_session_conflicts_with_background() {
+        let dir = tempdir().unwrap();
+        let tool = BashTool::new_without_confirmation_tracking(
+            dir.path().to_path_buf(),
+            vec![dir.path().to_path_buf()],
+            5,
+            false,
+            None,
+        );
+
+        let result = tool
+            .call(json!({
+                "command": "echo hi",
+                "session_id": "test-conflict-session",
+                "run_in_background": true
+            }))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+    }
+
+    #[tokio::test]
+    async fn test_bash_tool_session_timeout_recreates_session() {
+        let dir = tempdir().unwrap();
+        let tool = BashTool::new_without_confirmation_tracking(
+            dir.path().to_path_buf(),
+            vec![dir.path().to_path_buf()],
+            1,
+            false,
+            None,
+        );
+
+        let result = tool
+            .call(json!({
+                "command": "sleep 5",
+                "session_id": "test-timeout-session"
+            }))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::TIMEOUT);
+
+        // The dead session was removed; the same ID gets a fresh shell
+        let result = tool
+            .call(json!({
+                "command": "echo recovered",
+                "session_id": "test-timeout-session"
+            }))
+            .await
+            .unwrap();
+        assert!(result["success"].as_bool().unwrap(), "got: {result}");
+        assert_eq!(result["stdout"].as_str().unwrap().trim(), "recovered");
+
+        session::remove_session("test-timeout-session");
+    }
+
     #[test]
     fn test_truncate_output_utf8() {
         // Multi-byte character: "🦀" is 4 bytes [240, 159, 166, 128]
@@ -0,0 +1,236 @@
+//! PTY-backed execution for interactive commands.
+//!
+//! Commands that require a TTY (`git rebase -i`, REPLs, pagers) fail or hang
+//! under piped stdio. A `PtyTask` runs the command under a pseudo-terminal:
+//! output streams into a buffer (and to the UI via `ToolOutput` events) and
+//! keystrokes can be injected with the `send_input` tool - e.g. relaying an
+//! answer collected via `ask_user`.
+//!
+//! The PTY merges stdout and stderr into one stream; that's inherent to
+//! terminals, not a limitation of this module.
+
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use std::sync::{Arc, Mutex};
+
+use portable_pty::{CommandBuilder, PtySize, native_pty_system};
+use tokio::sync::mpsc;
+
+use crate::agent::AgentEvent;
+use crate::tools::MAX_BACKGROUND_BUFFER_LEN;
+
+/// A command running under a pseudo-terminal.
+pub struct PtyTask {
+    child: Box<dyn portable_pty::Child + Send + Sync>,
+    /// Writer to the PTY master - keystrokes injected here reach the command.
+    writer: Box<dyn Write + Send>,
+    /// Combined output (PTYs merge stdout and stderr).
+    output_buffer: Arc<Mutex<String>>,
+    completed: Arc<AtomicBool>,
+    exit_code: Arc<AtomicI32>,
+}
+
+impl PtyTask {
+    /// Spawn `command` via `bash -c` under a fresh PTY rooted at `cwd`.
+    ///
+    /// A reader thread collects output into a buffer and, when `events_tx`
+    /// is provided, streams each line to the UI as it arrives.
+    pub fn spawn(
+        command: &str,
+        cwd: &Path,
+        events_tx: Option<mpsc::Sender<AgentEvent>>,
+    ) -> anyhow::Result<Self> {
+        let pty_system = native_pty_system();
+        let pair = pty_system.openpty(PtySize {
+            rows: 24,
+            cols: 80,
+            pixel_width: 0,
+            pixel_height: 0,
+        })?;
+
+        let mut builder = CommandBuilder::new("bash");
+        builder.args(["-c", command]);
+        builder.cwd(cwd);
+
+        let child = pair.slave.spawn_command(builder)?;
+        // Drop the slave so the master sees EOF when the command exits
+        drop(pair.slave);
+
+        let mut reader = pair.master.try_clone_reader()?;
+        let writer = pair.master.take_writer()?;
+
+        let output_buffer = Arc::new(Mutex::new(String::new()));
+        let buffer = output_buffer.clone();
+
+        // PTY reads are blocking, so collection runs on a dedicated OS
+        // thread rather than a tokio task
+        std::thread::spawn(move || {
+            let mut chunk = [0u8; 4096];
+            let mut pending_line = String::new();
+            loop {
+                match reader.read(&mut chunk) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => {
+                        let text = String::from_utf8_lossy(&chunk[..n]);
+
+                        let mut buf = match buffer.lock() {
+                            Ok(guard) => guard,
+                            Err(poisoned) => poisoned.into_inner(),
+                        };
+                        buf.push_str(&text);
+                        if buf.len() > MAX_BACKGROUND_BUFFER_LEN {
+                            let len = buf.len();
+                            buf.truncate(MAX_BACKGROUND_BUFFER_LEN);
+                            buf.push_str(&format!("\n... [truncated, {} bytes total]", len));
+                            break;
+                        }
+                        drop(buf);
+
+                        // Stream complete lines to the UI
+                        if let Some(tx) = &events_tx {
+                            pending_line.push_str(&text);
+                            while let Some(pos) = pending_line.find('\n') {
+                                let line: String = pending_line.drain(..=pos).collect();
+                                let _ = tx.try_send(AgentEvent::ToolOutput(format!(
+                                    "  {}",
+                                    line.trim_end()
+                                )));
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            child,
+            writer,
+            output_buffer,
+            completed: Arc::new(AtomicBool::new(false)),
+            exit_code: Arc::new(AtomicI32::new(0)),
+        })
+    }
+
+    /// Check if the command has exited.
+    pub fn is_completed(&self) -> bool {
+        self.completed.load(Ordering::SeqCst)
+    }
+
+    /// Get the exit code (only meaningful if completed).
+    pub fn exit_code(&self) -> i32 {
+        self.exit_code.load(Ordering::SeqCst)
+    }
+
+    /// Get a copy of the combined output buffer.
+    pub fn output(&self) -> String {
+        match self.output_buffer.lock() {
+            Ok(guard) => guard.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// Check if the process has exited and update status fields.
+    pub fn update_status(&mut self) {
+        match self.child.try_wait() {
+            Ok(Some(status)) => {
+                self.completed.store(true, Ordering::SeqCst);
+                self.exit_code
+                    .store(status.exit_code() as i32, Ordering::SeqCst);
+            }
+            Ok(None) => {} // Still running
+            Err(e) => {
+                tracing::warn!("Error checking PTY process status: {}", e);
+                self.completed.store(true, Ordering::SeqCst);
+                self.exit_code.store(-1, Ordering::SeqCst);
+            }
+        }
+    }
+
+    /// Inject keystrokes into the PTY. The text is sent verbatim - include
+    /// a trailing newline to submit a line.
+    pub fn send_input(&mut self, input: &str) -> std::io::Result<()> {
+        self.writer.write_all(input.as_bytes())?;
+        self.writer.flush()
+    }
+
+    /// The PID of the command (None if it has already been reaped).
+    pub fn process_id(&self) -> Option<u32> {
+        self.child.process_id()
+    }
+
+    /// Kill the command.
+    pub fn kill(&mut self) -> std::io::Result<()> {
+        self.child.kill()
+    }
+}
+
+impl Drop for PtyTask {
+    fn drop(&mut self) {
+        // Mirror kill_on_drop semantics of other task types
+        if !self.is_completed() {
+            let _ = self.child.kill();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::time::{Duration, sleep};
+
+    #[tokio::test]
+    async fn test_pty_task_runs_under_tty() {
+        let dir = tempfile::tempdir().unwrap();
+        // `test -t 1` succeeds only when stdout is a terminal
+        let mut task = PtyTask::spawn("test -t 1 && echo IS_TTY", dir.path(), None).unwrap();
+
+        sleep(Duration::from_millis(300)).await;
+        task.update_status();
+
+        assert!(task.is_completed());
+        assert_eq!(task.exit_code(), 0);
+        assert!(task.output().contains("IS_TTY"));
+    }
+
+    #[tokio::test]
+    async fn test_pty_task_send_input() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut task = PtyTask::spawn("read -r line; echo \"got: $line\"", dir.path(), None)
+            .unwrap();
+
+        task.send_input("hello\n").unwrap();
+
+        sleep(Duration::from_millis(300)).await;
+        task.update_status();
+
+        assert!(task.is_completed());
+        assert!(task.output().contains("got: hello"), "output: {}", task.output());
+    }
+
+    #[tokio::test]
+    async fn test_pty_task_kill() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut task = PtyTask::spawn("sleep 30", dir.path(), None).unwrap();
+
+        task.update_status();
+        assert!(!task.is_completed());
+
+        task.kill().unwrap();
+        sleep(Duration::from_millis(200)).await;
+        task.update_status();
+        assert!(task.is_completed());
+    }
+
+    #[tokio::test]
+    async fn test_pty_task_exit_code() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut task = PtyTask::spawn("exit 7", dir.path(), None).unwrap();
+
+        sleep(Duration::from_millis(300)).await;
+        task.update_status();
+
+        assert!(task.is_completed());
+        assert_eq!(task.exit_code(), 7);
+    }
+}
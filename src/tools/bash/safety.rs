@@ -1,10 +1,14 @@
 //! Safety validation for bash commands.
 //!
 //! This module provides pattern-based validation to block dangerous commands
-//! and flag commands that require user confirmation.
+//! and flag commands that require user confirmation. The built-in patterns
+//! can be extended (and selectively exempted) via the `[bash]` config
+//! section, merged into a [`SafetyPolicy`] at `BashTool` construction.
 
 use regex::Regex;
-use std::sync::LazyLock;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::sync::{Arc, LazyLock, RwLock};
 
 /// Blocked command patterns that are always rejected.
 pub static BLOCKED_PATTERNS: LazyLock<Vec<Regex>> = LazyLock::new(|| {
@@ -49,6 +53,135 @@ pub static CAUTION_PATTERNS: LazyLock<Vec<Regex>> = LazyLock::new(|| {
     ]
 });
 
+/// The `[bash]` section of config.toml: user-supplied safety patterns.
+///
+/// ```toml
+/// [bash]
+/// blocked = ['terraform\s+destroy']
+/// caution = ['kubectl\s+delete']
+/// allow = ['git\s+push\s+.*--force-with-lease']
+/// ```
+///
+/// `blocked` and `caution` are merged with the built-in defaults; `allow`
+/// exempts matching commands from all blocked/caution checks (built-in and
+/// configured), e.g. to stop `--force-with-lease` pushes from tripping the
+/// `--force` caution pattern.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BashSafetyToml {
+    /// Additional regexes whose matches are always rejected.
+    #[serde(default)]
+    pub blocked: Vec<String>,
+    /// Additional regexes whose matches require user confirmation.
+    #[serde(default)]
+    pub caution: Vec<String>,
+    /// Regexes that exempt matching commands from blocked/caution checks.
+    #[serde(default)]
+    pub allow: Vec<String>,
+}
+
+/// Effective safety policy: the built-in patterns plus user-configured
+/// additions and exemptions. The default policy is the built-ins alone.
+#[derive(Clone, Default)]
+pub struct SafetyPolicy {
+    extra_blocked: Vec<Regex>,
+    extra_caution: Vec<Regex>,
+    allow: Vec<Regex>,
+    /// Caution patterns the user answered "always" to this session; commands
+    /// matching only these no longer prompt. Behind an `Arc` so per-call tool
+    /// clones share the set with the service's policy.
+    session_exempt: Arc<RwLock<HashSet<String>>>,
+}
+
+impl SafetyPolicy {
+    /// Build a policy from the `[bash]` config section. Invalid regexes are
+    /// logged and skipped rather than aborting startup.
+    pub fn from_config(config: &BashSafetyToml) -> Self {
+        Self {
+            extra_blocked: compile_patterns(&config.blocked, "blocked"),
+            extra_caution: compile_patterns(&config.caution, "caution"),
+            allow: compile_patterns(&config.allow, "allow"),
+            session_exempt: Arc::new(RwLock::new(HashSet::new())),
+        }
+    }
+
+    /// Check if a command matches an `allow` pattern (exempt from all checks).
+    fn is_allowed(&self, command: &str) -> bool {
+        self.allow.iter().any(|pattern| pattern.is_match(command))
+    }
+
+    /// Like the free [`is_blocked`], but with configured additions and
+    /// `allow` exemptions applied.
+    pub fn is_blocked(&self, command: &str) -> Option<String> {
+        if self.is_allowed(command) {
+            return None;
+        }
+        if let Some(pattern) = is_blocked(command) {
+            return Some(pattern);
+        }
+        self.extra_blocked
+            .iter()
+            .find(|pattern| pattern.is_match(command))
+            .map(|pattern| pattern.as_str().to_string())
+    }
+
+    /// Like the free [`needs_caution`], but with configured additions,
+    /// `allow` exemptions, and session exemptions applied.
+    pub fn needs_caution(&self, command: &str) -> bool {
+        self.caution_pattern(command).is_some()
+    }
+
+    /// The caution pattern a command matches, if any, skipping patterns the
+    /// user already answered "always" to this session.
+    pub fn caution_pattern(&self, command: &str) -> Option<String> {
+        if self.is_allowed(command) {
+            return None;
+        }
+        let session_exempt = match self.session_exempt.read() {
+            Ok(set) => set,
+            Err(poisoned) => {
+                tracing::warn!("session_exempt lock was poisoned, recovering");
+                poisoned.into_inner()
+            }
+        };
+        CAUTION_PATTERNS
+            .iter()
+            .chain(self.extra_caution.iter())
+            .filter(|pattern| pattern.is_match(command))
+            .map(|pattern| pattern.as_str().to_string())
+            .find(|pattern| !session_exempt.contains(pattern))
+    }
+
+    /// Exempt a caution pattern for the rest of the session (the "always"
+    /// answer at the confirmation prompt). Shared across clones, so the
+    /// per-call tool's answer sticks in the service's policy.
+    pub fn exempt_caution_pattern(&self, pattern: &str) {
+        match self.session_exempt.write() {
+            Ok(mut set) => {
+                set.insert(pattern.to_string());
+            }
+            Err(poisoned) => {
+                tracing::warn!("session_exempt lock was poisoned, recovering");
+                poisoned.into_inner().insert(pattern.to_string());
+            }
+        }
+    }
+}
+
+/// Compile config-supplied patterns, warning about (and dropping) any that
+/// fail to parse so one typo doesn't take the whole policy down.
+fn compile_patterns(patterns: &[String], kind: &str) -> Vec<Regex> {
+    patterns
+        .iter()
+        .filter_map(|p| match Regex::new(p) {
+            Ok(regex) => Some(regex),
+            Err(e) => {
+                tracing::warn!("Ignoring invalid [bash] {} pattern '{}': {}", kind, p, e);
+                None
+            }
+        })
+        .collect()
+}
+
 /// Check if a command matches any blocked pattern.
 /// Returns the matching pattern if blocked, None if allowed.
 pub fn is_blocked(command: &str) -> Option<String> {
@@ -107,6 +240,25 @@ mod tests {
         assert!(!needs_caution("ls -l"));
     }
 
+    #[test]
+    fn test_caution_pattern_session_exemption() {
+        let policy = SafetyPolicy::default();
+        let pattern = policy
+            .caution_pattern("git push --force")
+            .expect("force push should need caution");
+
+        policy.exempt_caution_pattern(&pattern);
+        assert!(policy.caution_pattern("git push --force origin").is_none());
+        assert!(!policy.needs_caution("git push -f"));
+
+        // Clones share the exemption set (service policy vs per-call tool)
+        let clone = policy.clone();
+        assert!(!clone.needs_caution("git push --force"));
+
+        // Unrelated caution patterns still prompt
+        assert!(policy.needs_caution("rm file.txt"));
+    }
+
     #[test]
     fn test_caution_patterns_edge_cases() {
         assert!(needs_caution("rm\tfile.txt"), "Should catch tab separator");
@@ -125,6 +277,80 @@ mod tests {
         assert!(needs_caution("rm"), "Should catch bare rm");
     }
 
+    #[test]
+    fn test_safety_policy_default_matches_builtins() {
+        let policy = SafetyPolicy::default();
+        assert!(policy.is_blocked("rm -rf /").is_some());
+        assert!(policy.needs_caution("git push --force"));
+        assert!(policy.is_blocked("ls -l").is_none());
+        assert!(!policy.needs_caution("ls -l"));
+    }
+
+    #[test]
+    fn test_safety_policy_extra_blocked() {
+        let policy = SafetyPolicy::from_config(&BashSafetyToml {
+            blocked: vec![r"terraform\s+destroy".to_string()],
+            ..Default::default()
+        });
+        assert!(
+            policy
+                .is_blocked("terraform destroy -auto-approve")
+                .is_some()
+        );
+        assert!(policy.is_blocked("terraform plan").is_none());
+        // Built-ins still apply
+        assert!(policy.is_blocked("rm -rf /").is_some());
+    }
+
+    #[test]
+    fn test_safety_policy_extra_caution() {
+        let policy = SafetyPolicy::from_config(&BashSafetyToml {
+            caution: vec![r"kubectl\s+delete".to_string()],
+            ..Default::default()
+        });
+        assert!(policy.needs_caution("kubectl delete pod foo"));
+        assert!(!policy.needs_caution("kubectl get pods"));
+        // Built-ins still apply
+        assert!(policy.needs_caution("sudo apt update"));
+    }
+
+    #[test]
+    fn test_safety_policy_allow_exempts_caution() {
+        let policy = SafetyPolicy::from_config(&BashSafetyToml {
+            allow: vec![r"git\s+push\s+.*--force-with-lease".to_string()],
+            ..Default::default()
+        });
+        // The built-in `--force` caution pattern matches, but the allow
+        // pattern exempts it
+        assert!(!policy.needs_caution("git push --force-with-lease origin main"));
+        // Plain --force still needs confirmation
+        assert!(policy.needs_caution("git push --force origin main"));
+    }
+
+    #[test]
+    fn test_safety_policy_allow_exempts_blocked() {
+        let policy = SafetyPolicy::from_config(&BashSafetyToml {
+            allow: vec![r"dd\s+if=/dev/zero\s+of=/tmp/".to_string()],
+            ..Default::default()
+        });
+        assert!(
+            policy
+                .is_blocked("dd if=/dev/zero of=/tmp/testfile bs=1M count=1")
+                .is_none()
+        );
+        assert!(policy.is_blocked("dd if=/dev/zero of=/dev/sda").is_some());
+    }
+
+    #[test]
+    fn test_safety_policy_invalid_regex_is_skipped() {
+        let policy = SafetyPolicy::from_config(&BashSafetyToml {
+            blocked: vec!["[invalid".to_string(), r"terraform\s+destroy".to_string()],
+            ..Default::default()
+        });
+        // The bad pattern is dropped; the valid one still works
+        assert!(policy.is_blocked("terraform destroy").is_some());
+    }
+
     #[test]
     fn test_caution_false_positives() {
         assert!(
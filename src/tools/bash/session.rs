@@ -0,0 +1,290 @@
+//! Persistent shell sessions for the bash tool.
+//!
+//! A session is a long-lived `bash` process with piped stdin/stdout/stderr.
+//! Commands are written to stdin followed by sentinel markers on both output
+//! streams, so state (environment variables, `cd`, virtualenv activation,
+//! shell functions) persists across calls. Sessions are keyed by a
+//! model-chosen ID, created on first use, and killed when the interaction
+//! ends so they don't leak across unrelated prompts.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStderr, ChildStdin, ChildStdout, Command};
+
+pub struct ShellSession {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+    stderr: Lines<BufReader<ChildStderr>>,
+    /// Per-call counter so sentinel markers are unique even if a command
+    /// echoes a previous marker back.
+    next_marker: u64,
+}
+
+/// Output of one command run inside a session.
+pub struct SessionOutput {
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: i32,
+}
+
+/// Why a session command failed. All variants mean the session is no longer
+/// usable and should be removed from the registry.
+pub enum SessionError {
+    /// The command exceeded its timeout; the shell process was killed.
+    TimedOut,
+    /// The shell process exited before completing the command (e.g. the
+    /// command ran `exit` or the process was killed externally).
+    Exited,
+    /// Writing to the shell's stdin failed.
+    Io(String),
+}
+
+impl ShellSession {
+    /// Spawn a fresh bash process rooted at `cwd`.
+    pub fn spawn(cwd: &Path) -> std::io::Result<Self> {
+        let mut child = Command::new("bash")
+            .current_dir(cwd)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+
+        let stdin = child.stdin.take().expect("stdin was requested");
+        let stdout = BufReader::new(child.stdout.take().expect("stdout was requested")).lines();
+        let stderr = BufReader::new(child.stderr.take().expect("stderr was requested")).lines();
+
+        Ok(Self {
+            child,
+            stdin,
+            stdout,
+            stderr,
+            next_marker: 0,
+        })
+    }
+
+    /// Run a command in the session and wait for it to complete.
+    ///
+    /// The command's exit code is captured via a sentinel marker echoed to
+    /// both streams after it finishes. On timeout the shell process is
+    /// killed - a still-running command can't be skipped past, so the whole
+    /// session is sacrificed rather than left in an unknown state.
+    pub async fn run(
+        &mut self,
+        command: &str,
+        timeout: Duration,
+    ) -> Result<SessionOutput, SessionError> {
+        self.next_marker += 1;
+        let marker = format!("__clemini_done_{}__", self.next_marker);
+
+        // Sentinel on stdout carries the exit code; sentinel on stderr just
+        // marks end-of-stream so both readers know when to stop.
+        let script = format!(
+            "{command}\nprintf '%s %s\\n' '{marker}' \"$?\"\nprintf '%s\\n' '{marker}' >&2\n"
+        );
+
+        if let Err(e) = self.stdin.write_all(script.as_bytes()).await {
+            return Err(SessionError::Io(e.to_string()));
+        }
+        if let Err(e) = self.stdin.flush().await {
+            return Err(SessionError::Io(e.to_string()));
+        }
+
+        let stdout_reader = &mut self.stdout;
+        let stderr_reader = &mut self.stderr;
+
+        let read_until_markers = async {
+            let mut stdout_buf = String::new();
+            let mut stderr_buf = String::new();
+            let mut exit_code: Option<i32> = None;
+            let mut stdout_done = false;
+            let mut stderr_done = false;
+
+            while !(stdout_done && stderr_done) {
+                tokio::select! {
+                    line = stdout_reader.next_line(), if !stdout_done => {
+                        match line {
+                            Ok(Some(line)) => {
+                                if let Some(rest) = line.strip_prefix(marker.as_str()) {
+                                    exit_code = rest.trim().parse().ok();
+                                    stdout_done = true;
+                                } else {
+                                    stdout_buf.push_str(&line);
+                                    stdout_buf.push('\n');
+                                }
+                            }
+                            // EOF: the shell exited before the sentinel
+                            _ => {
+                                stdout_done = true;
+                            }
+                        }
+                    }
+                    line = stderr_reader.next_line(), if !stderr_done => {
+                        match line {
+                            Ok(Some(line)) => {
+                                if line == marker {
+                                    stderr_done = true;
+                                } else {
+                                    stderr_buf.push_str(&line);
+                                    stderr_buf.push('\n');
+                                }
+                            }
+                            _ => {
+                                stderr_done = true;
+                            }
+                        }
+                    }
+                }
+            }
+
+            (stdout_buf, stderr_buf, exit_code)
+        };
+
+        match tokio::time::timeout(timeout, read_until_markers).await {
+            Ok((stdout, stderr, Some(exit_code))) => Ok(SessionOutput {
+                stdout,
+                stderr,
+                exit_code,
+            }),
+            Ok((_, _, None)) => {
+                let _ = self.child.kill().await;
+                Err(SessionError::Exited)
+            }
+            Err(_) => {
+                let _ = self.child.kill().await;
+                Err(SessionError::TimedOut)
+            }
+        }
+    }
+
+    /// Kill the underlying shell process.
+    pub async fn kill(&mut self) {
+        let _ = self.child.kill().await;
+    }
+}
+
+/// Live shell sessions, keyed by the model-chosen session ID.
+static SHELL_SESSIONS: LazyLock<Mutex<HashMap<String, Arc<tokio::sync::Mutex<ShellSession>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Get the session for `id`, spawning a fresh shell rooted at `cwd` on first
+/// use.
+pub fn get_or_create_session(
+    id: &str,
+    cwd: &Path,
+) -> std::io::Result<Arc<tokio::sync::Mutex<ShellSession>>> {
+    let mut sessions = SHELL_SESSIONS.lock().unwrap();
+    if let Some(session) = sessions.get(id) {
+        return Ok(session.clone());
+    }
+    let session = Arc::new(tokio::sync::Mutex::new(ShellSession::spawn(cwd)?));
+    sessions.insert(id.to_string(), session.clone());
+    Ok(session)
+}
+
+/// Remove a session from the registry (e.g. after it died or timed out).
+pub fn remove_session(id: &str) -> Option<Arc<tokio::sync::Mutex<ShellSession>>> {
+    SHELL_SESSIONS.lock().unwrap().remove(id)
+}
+
+/// Kill and drop every live session. Called when an interaction ends so
+/// shell state doesn't leak across unrelated prompts.
+pub async fn cleanup_sessions() {
+    let sessions: Vec<_> = {
+        let mut map = SHELL_SESSIONS.lock().unwrap();
+        map.drain().map(|(_, session)| session).collect()
+    };
+    for session in sessions {
+        session.lock().await.kill().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_session_persists_environment() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut session = ShellSession::spawn(dir.path()).unwrap();
+
+        let result = session
+            .run("export FOO=bar", Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(result.exit_code, 0);
+
+        let result = session
+            .run("echo $FOO", Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(result.stdout.trim(), "bar");
+    }
+
+    #[tokio::test]
+    async fn test_session_persists_cwd() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("subdir")).unwrap();
+        let mut session = ShellSession::spawn(dir.path()).unwrap();
+
+        session
+            .run("cd subdir", Duration::from_secs(5))
+            .await
+            .unwrap();
+        let result = session.run("pwd", Duration::from_secs(5)).await.unwrap();
+        assert!(result.stdout.trim().ends_with("subdir"));
+    }
+
+    #[tokio::test]
+    async fn test_session_captures_stderr_and_exit_code() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut session = ShellSession::spawn(dir.path()).unwrap();
+
+        let result = session
+            .run("echo oops >&2; false", Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(result.stdout, "");
+        assert_eq!(result.stderr.trim(), "oops");
+        assert_eq!(result.exit_code, 1);
+    }
+
+    #[tokio::test]
+    async fn test_session_timeout_kills_shell() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut session = ShellSession::spawn(dir.path()).unwrap();
+
+        let result = session.run("sleep 5", Duration::from_millis(200)).await;
+        assert!(matches!(result, Err(SessionError::TimedOut)));
+    }
+
+    #[tokio::test]
+    async fn test_session_exit_reported_as_dead() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut session = ShellSession::spawn(dir.path()).unwrap();
+
+        let result = session.run("exit 0", Duration::from_secs(5)).await;
+        assert!(matches!(result, Err(SessionError::Exited)));
+    }
+
+    #[tokio::test]
+    async fn test_registry_get_or_create_and_remove() {
+        let dir = tempfile::tempdir().unwrap();
+        let id = "test-registry-session";
+
+        let first = get_or_create_session(id, dir.path()).unwrap();
+        let second = get_or_create_session(id, dir.path()).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        let removed = remove_session(id);
+        assert!(removed.is_some());
+        assert!(remove_session(id).is_none());
+
+        removed.unwrap().lock().await.kill().await;
+    }
+}
@@ -0,0 +1,294 @@
+//! Content-addressed file checkpoints for undo.
+//!
+//! Before any file-mutating tool (`write_file`, `edit`, `multi_edit`,
+//! `edit_lines`, `apply_patch`, `replace`, `notebook_edit`, ...) touches a
+//! file, its current contents are snapshotted into
+//! `~/.clemini/checkpoints/objects/<hash>` and an entry is appended to a
+//! per-workspace journal. `/undo` and `/rewind <n>` in the
+//! REPL and the `revert_file` tool restore from the journal, so recovering
+//! from a bad edit doesn't require git archaeology - or git at all.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::memory::{fnv1a_hex_bytes, project_hash};
+
+/// One journal entry: the state of a file just before a mutation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckpointEntry {
+    /// RFC 3339 timestamp of the snapshot.
+    pub timestamp: String,
+    /// Absolute path of the file at snapshot time.
+    pub path: String,
+    /// Hash of the stored contents; `None` means the file did not exist yet,
+    /// so undoing the mutation deletes it.
+    pub hash: Option<String>,
+}
+
+fn checkpoints_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".clemini")
+        .join("checkpoints")
+}
+
+/// Objects are content-addressed and shared across workspaces, so repeated
+/// edits to the same content store it once.
+fn objects_dir() -> PathBuf {
+    checkpoints_dir().join("objects")
+}
+
+/// Path of the workspace's checkpoint journal. The cwd is canonicalized so
+/// `/proj` and `/proj/.` map to the same journal.
+pub fn journal_path(cwd: &Path) -> PathBuf {
+    let canonical = cwd.canonicalize().unwrap_or_else(|_| cwd.to_path_buf());
+    checkpoints_dir().join(format!("{}.jsonl", project_hash(&canonical)))
+}
+
+/// Load the workspace's journal, oldest first. Unparseable lines are
+/// skipped rather than failing the whole journal.
+pub fn load_journal(cwd: &Path) -> Vec<CheckpointEntry> {
+    let Ok(contents) = std::fs::read_to_string(journal_path(cwd)) else {
+        return Vec::new();
+    };
+    contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+fn save_journal(cwd: &Path, entries: &[CheckpointEntry]) -> Result<(), String> {
+    let path = journal_path(cwd);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let mut out = String::new();
+    for entry in entries {
+        out.push_str(&serde_json::to_string(entry).map_err(|e| e.to_string())?);
+        out.push('\n');
+    }
+    std::fs::write(&path, out).map_err(|e| e.to_string())
+}
+
+/// Snapshot `file`'s current state before a mutation. Best-effort: a failed
+/// snapshot is logged and never blocks the write it precedes.
+pub fn snapshot(cwd: &Path, file: &Path) {
+    if let Err(e) = try_snapshot(cwd, file) {
+        tracing::warn!("Failed to checkpoint {}: {e}", file.display());
+    }
+}
+
+fn try_snapshot(cwd: &Path, file: &Path) -> Result<(), String> {
+    let hash = match std::fs::read(file) {
+        Ok(bytes) => {
+            let hash = fnv1a_hex_bytes(&bytes);
+            let object = objects_dir().join(&hash);
+            if !object.exists() {
+                std::fs::create_dir_all(objects_dir()).map_err(|e| e.to_string())?;
+                std::fs::write(&object, &bytes).map_err(|e| e.to_string())?;
+            }
+            Some(hash)
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.to_string()),
+    };
+
+    let mut journal = load_journal(cwd);
+    // Skip no-op entries: the file hasn't changed since its last snapshot,
+    // so another entry would make /undo a no-op step.
+    if journal
+        .last()
+        .is_some_and(|last| last.path == file.display().to_string() && last.hash == hash)
+    {
+        return Ok(());
+    }
+    journal.push(CheckpointEntry {
+        timestamp: chrono::Local::now().to_rfc3339(),
+        path: file.display().to_string(),
+        hash,
+    });
+    save_journal(cwd, &journal)
+}
+
+/// Restore `entry`: write the stored object back, or delete the file for a
+/// pre-creation snapshot.
+fn restore(entry: &CheckpointEntry) -> Result<(), String> {
+    let path = Path::new(&entry.path);
+    match &entry.hash {
+        Some(hash) => {
+            let bytes = std::fs::read(objects_dir().join(hash))
+                .map_err(|e| format!("checkpoint object {hash} missing: {e}"))?;
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            std::fs::write(path, bytes).map_err(|e| e.to_string())
+        }
+        None => match std::fs::remove_file(path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.to_string()),
+        },
+    }
+}
+
+/// Undo the most recent mutation by restoring its snapshot and popping it
+/// from the journal. Returns the restored entry, or `None` when the journal
+/// is empty.
+pub fn undo_last(cwd: &Path) -> Result<Option<CheckpointEntry>, String> {
+    let mut journal = load_journal(cwd);
+    let Some(entry) = journal.pop() else {
+        return Ok(None);
+    };
+    restore(&entry)?;
+    save_journal(cwd, &journal)?;
+    Ok(Some(entry))
+}
+
+/// Undo the last `n` mutations, newest first. Stops early when the journal
+/// runs out; returns the entries restored in undo order.
+pub fn rewind(cwd: &Path, n: usize) -> Result<Vec<CheckpointEntry>, String> {
+    let mut restored = Vec::new();
+    for _ in 0..n {
+        match undo_last(cwd)? {
+            Some(entry) => restored.push(entry),
+            None => break,
+        }
+    }
+    Ok(restored)
+}
+
+/// Restore the most recent snapshot of `file` specifically (regardless of
+/// mutations to other files since) and drop it from the journal. Returns
+/// `None` when the file was never checkpointed.
+pub fn revert_file(cwd: &Path, file: &Path) -> Result<Option<CheckpointEntry>, String> {
+    let mut journal = load_journal(cwd);
+    let Some(pos) = journal
+        .iter()
+        .rposition(|entry| Path::new(&entry.path) == file)
+    else {
+        return Ok(None);
+    };
+    let entry = journal.remove(pos);
+    restore(&entry)?;
+    save_journal(cwd, &journal)?;
+    Ok(Some(entry))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    /// Remove the tempdir-keyed journal so tests don't accumulate files
+    /// under the real ~/.clemini/checkpoints/.
+    struct JournalCleanup(PathBuf);
+    impl Drop for JournalCleanup {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(journal_path(&self.0));
+        }
+    }
+
+    #[test]
+    fn test_snapshot_and_undo_restores_content() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let _cleanup = JournalCleanup(cwd.clone());
+        let file = cwd.join("a.txt");
+        fs::write(&file, "before").unwrap();
+
+        snapshot(&cwd, &file);
+        fs::write(&file, "after").unwrap();
+
+        let entry = undo_last(&cwd).unwrap().unwrap();
+        assert_eq!(entry.path, file.display().to_string());
+        assert_eq!(fs::read_to_string(&file).unwrap(), "before");
+        // Journal is now empty.
+        assert!(undo_last(&cwd).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_undo_deletes_file_created_after_snapshot() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let _cleanup = JournalCleanup(cwd.clone());
+        let file = cwd.join("new.txt");
+
+        snapshot(&cwd, &file); // file doesn't exist yet
+        fs::write(&file, "created").unwrap();
+
+        let entry = undo_last(&cwd).unwrap().unwrap();
+        assert!(entry.hash.is_none());
+        assert!(!file.exists());
+    }
+
+    #[test]
+    fn test_rewind_undoes_newest_first() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let _cleanup = JournalCleanup(cwd.clone());
+        let a = cwd.join("a.txt");
+        let b = cwd.join("b.txt");
+        fs::write(&a, "a1").unwrap();
+
+        snapshot(&cwd, &a);
+        fs::write(&a, "a2").unwrap();
+        snapshot(&cwd, &b);
+        fs::write(&b, "b1").unwrap();
+
+        let restored = rewind(&cwd, 5).unwrap();
+        assert_eq!(restored.len(), 2);
+        assert_eq!(restored[0].path, b.display().to_string());
+        assert_eq!(restored[1].path, a.display().to_string());
+        assert_eq!(fs::read_to_string(&a).unwrap(), "a1");
+        assert!(!b.exists());
+    }
+
+    #[test]
+    fn test_revert_file_targets_specific_path() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let _cleanup = JournalCleanup(cwd.clone());
+        let a = cwd.join("a.txt");
+        let b = cwd.join("b.txt");
+        fs::write(&a, "a1").unwrap();
+        fs::write(&b, "b1").unwrap();
+
+        snapshot(&cwd, &a);
+        fs::write(&a, "a2").unwrap();
+        snapshot(&cwd, &b);
+        fs::write(&b, "b2").unwrap();
+
+        // Reverting a skips b's newer snapshot.
+        let entry = revert_file(&cwd, &a).unwrap().unwrap();
+        assert_eq!(entry.path, a.display().to_string());
+        assert_eq!(fs::read_to_string(&a).unwrap(), "a1");
+        assert_eq!(fs::read_to_string(&b).unwrap(), "b2");
+
+        // b's snapshot is still undoable.
+        assert_eq!(load_journal(&cwd).len(), 1);
+    }
+
+    #[test]
+    fn test_revert_file_without_checkpoint() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let _cleanup = JournalCleanup(cwd.clone());
+
+        let result = revert_file(&cwd, &cwd.join("never-touched.txt")).unwrap();
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_snapshot_skips_unchanged_duplicate() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let _cleanup = JournalCleanup(cwd.clone());
+        let file = cwd.join("a.txt");
+        fs::write(&file, "same").unwrap();
+
+        snapshot(&cwd, &file);
+        snapshot(&cwd, &file);
+        assert_eq!(load_journal(&cwd).len(), 1);
+    }
+}
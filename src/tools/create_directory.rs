@@ -0,0 +1,231 @@
+use async_trait::async_trait;
+use colored::Colorize;
+use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use tokio::sync::mpsc;
+use tracing::instrument;
+
+use super::{ToolEmitter, error_codes, error_response, resolve_and_validate_path};
+use crate::agent::AgentEvent;
+
+pub struct CreateDirectoryTool {
+    cwd: PathBuf,
+    allowed_paths: Vec<PathBuf>,
+    events_tx: Option<mpsc::Sender<AgentEvent>>,
+    dry_run: bool,
+}
+
+impl CreateDirectoryTool {
+    pub fn new(
+        cwd: PathBuf,
+        allowed_paths: Vec<PathBuf>,
+        events_tx: Option<mpsc::Sender<AgentEvent>>,
+    ) -> Self {
+        Self {
+            cwd,
+            allowed_paths,
+            events_tx,
+            dry_run: false,
+        }
+    }
+
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+}
+
+impl ToolEmitter for CreateDirectoryTool {
+    fn events_tx(&self) -> &Option<mpsc::Sender<AgentEvent>> {
+        &self.events_tx
+    }
+}
+
+#[async_trait]
+impl CallableFunction for CreateDirectoryTool {
+    fn declaration(&self) -> FunctionDeclaration {
+        FunctionDeclaration::new(
+            "create_directory".to_string(),
+            "Create a directory. Succeeds without change if it already exists. The path is validated against allowed paths - prefer this over `bash mkdir`. Returns: {path, created, success}".to_string(),
+            FunctionParameters::new(
+                "object".to_string(),
+                json!({
+                    "path": {
+                        "type": "string",
+                        "description": "The directory to create (absolute or relative to cwd)"
+                    },
+                    "recursive": {
+                        "type": "boolean",
+                        "description": "Create missing parent directories too, like mkdir -p. (default: true)"
+                    }
+                }),
+                vec!["path".to_string()],
+            ),
+        )
+    }
+
+    #[instrument(skip(self, args))]
+    async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        let dir_path = args
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing path".to_string()))?;
+        let recursive = args
+            .get("recursive")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+
+        let path = match resolve_and_validate_path(dir_path, &self.cwd, &self.allowed_paths) {
+            Ok(p) => p,
+            Err(e) => {
+                return Ok(error_response(
+                    &format!("Access denied: {}. Path must be within allowed paths.", e),
+                    error_codes::ACCESS_DENIED,
+                    json!({"path": dir_path}),
+                ));
+            }
+        };
+
+        if path.is_dir() {
+            // Idempotent: scaffolding shouldn't fail because a step already ran
+            return Ok(json!({
+                "path": dir_path,
+                "created": false,
+                "success": true
+            }));
+        }
+        if path.exists() {
+            return Ok(error_response(
+                &format!("{} already exists and is not a directory", dir_path),
+                error_codes::INVALID_ARGUMENT,
+                json!({"path": dir_path}),
+            ));
+        }
+
+        if self.dry_run {
+            self.emit(&format!(
+                "  {} {}/",
+                "DRY RUN (not created):".yellow(),
+                dir_path
+            ));
+            return Ok(json!({
+                "path": dir_path,
+                "created": true,
+                "dry_run": true,
+                "success": true
+            }));
+        }
+
+        let result = if recursive {
+            tokio::fs::create_dir_all(&path).await
+        } else {
+            tokio::fs::create_dir(&path).await
+        };
+
+        match result {
+            Ok(()) => {
+                self.emit(&format!("  created {}/", dir_path).dimmed().to_string());
+                Ok(json!({
+                    "path": dir_path,
+                    "created": true,
+                    "success": true
+                }))
+            }
+            Err(e) => Ok(error_response(
+                &format!("Failed to create directory {}: {}", dir_path, e),
+                error_codes::IO_ERROR,
+                json!({"path": dir_path}),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_creates_directory() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let tool = CreateDirectoryTool::new(cwd.clone(), vec![cwd.clone()], None);
+
+        let result = tool.call(json!({"path": "newdir"})).await.unwrap();
+        assert!(result["success"].as_bool().unwrap(), "got: {result}");
+        assert!(result["created"].as_bool().unwrap());
+        assert!(cwd.join("newdir").is_dir());
+    }
+
+    #[tokio::test]
+    async fn test_creates_nested_directories_by_default() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let tool = CreateDirectoryTool::new(cwd.clone(), vec![cwd.clone()], None);
+
+        let result = tool.call(json!({"path": "a/b/c"})).await.unwrap();
+        assert!(result["success"].as_bool().unwrap(), "got: {result}");
+        assert!(cwd.join("a/b/c").is_dir());
+    }
+
+    #[tokio::test]
+    async fn test_non_recursive_requires_parent() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let tool = CreateDirectoryTool::new(cwd.clone(), vec![cwd.clone()], None);
+
+        let result = tool
+            .call(json!({"path": "a/b/c", "recursive": false}))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::IO_ERROR);
+        assert!(!cwd.join("a").exists());
+    }
+
+    #[tokio::test]
+    async fn test_existing_directory_is_idempotent() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::create_dir(cwd.join("existing")).unwrap();
+        let tool = CreateDirectoryTool::new(cwd.clone(), vec![cwd.clone()], None);
+
+        let result = tool.call(json!({"path": "existing"})).await.unwrap();
+        assert!(result["success"].as_bool().unwrap());
+        assert!(!result["created"].as_bool().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_existing_file_is_an_error() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("taken"), "file").unwrap();
+        let tool = CreateDirectoryTool::new(cwd.clone(), vec![cwd.clone()], None);
+
+        let result = tool.call(json!({"path": "taken"})).await.unwrap();
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+    }
+
+    #[tokio::test]
+    async fn test_outside_allowed_paths() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let tool = CreateDirectoryTool::new(cwd.clone(), vec![cwd.clone()], None);
+
+        let result = tool.call(json!({"path": "../escaped"})).await.unwrap();
+        assert_eq!(result["error_code"], error_codes::ACCESS_DENIED);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_touches_nothing() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let tool =
+            CreateDirectoryTool::new(cwd.clone(), vec![cwd.clone()], None).with_dry_run(true);
+
+        let result = tool.call(json!({"path": "newdir"})).await.unwrap();
+        assert!(result["dry_run"].as_bool().unwrap());
+        assert!(!cwd.join("newdir").exists());
+    }
+}
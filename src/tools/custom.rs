@@ -0,0 +1,480 @@
+//! Custom user-defined tools loaded from `~/.clemini/tools.toml`.
+//!
+//! Each entry declares a name, description, typed arguments, and a shell
+//! command template; the service materializes it as a regular tool the model
+//! can call. This lets users add project-specific tools (e.g.,
+//! `run_migration`) without forking the crate:
+//!
+//! ```toml
+//! [[tools]]
+//! name = "run_migration"
+//! description = "Run a database migration up or down"
+//! command = "scripts/migrate.sh {direction}"
+//!
+//! [[tools.args]]
+//! name = "direction"
+//! description = "Either 'up' or 'down'"
+//! required = true
+//! ```
+//!
+//! String argument values are shell-quoted before substitution, so a value
+//! can't inject extra commands into the template.
+
+use async_trait::async_trait;
+use colored::Colorize;
+use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use tokio::sync::mpsc;
+use tracing::instrument;
+
+use super::{BashTool, MAX_TOOL_OUTPUT_LEN, ToolEmitter, error_codes, error_response};
+use crate::agent::AgentEvent;
+
+/// The contents of `~/.clemini/tools.toml`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CustomToolsToml {
+    #[serde(default)]
+    pub tools: Vec<CustomToolDef>,
+}
+
+impl CustomToolsToml {
+    /// Load `~/.clemini/tools.toml` merged with the project-local
+    /// `<cwd>/.clemini/tools.toml`; a project tool replaces a global one
+    /// with the same name. Either file may be absent, and parse errors are
+    /// logged and treated as empty rather than failing startup.
+    pub fn load_for(cwd: &std::path::Path) -> Self {
+        let global = home::home_dir()
+            .map(|p| p.join(".clemini").join("tools.toml"))
+            .map(|p| Self::load_path(&p))
+            .unwrap_or_default();
+        let project = Self::load_path(&cwd.join(".clemini").join("tools.toml"));
+
+        let mut tools = global.tools;
+        for tool in project.tools {
+            tools.retain(|existing| existing.name != tool.name);
+            tools.push(tool);
+        }
+        Self { tools }
+    }
+
+    fn load_path(path: &std::path::Path) -> Self {
+        if !path.exists() {
+            return Self::default();
+        }
+        match std::fs::read_to_string(path) {
+            Ok(contents) => match toml::from_str(&contents) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    tracing::warn!("Ignoring unparseable {}: {e}", path.display());
+                    Self::default()
+                }
+            },
+            Err(e) => {
+                tracing::warn!("Failed to read {}: {e}", path.display());
+                Self::default()
+            }
+        }
+    }
+}
+
+/// One `[[tools]]` entry: a declared tool backed by a shell command template.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CustomToolDef {
+    /// Tool name as the model sees it (snake_case identifier).
+    pub name: String,
+    /// Description shown to the model.
+    pub description: String,
+    /// Shell command with `{arg_name}` placeholders.
+    pub command: String,
+    /// Declared arguments, substituted into the command template.
+    #[serde(default)]
+    pub args: Vec<CustomArgDef>,
+}
+
+/// One `[[tools.args]]` entry.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CustomArgDef {
+    pub name: String,
+    /// JSON schema type: "string" (default), "number", "boolean", "integer".
+    #[serde(default = "default_arg_type", rename = "type")]
+    pub arg_type: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub required: bool,
+}
+
+fn default_arg_type() -> String {
+    "string".to_string()
+}
+
+impl CustomToolDef {
+    /// Structural validation: identifier-shaped name, and every `{placeholder}`
+    /// in the command template matches a declared argument. Returns the reason
+    /// when invalid so the caller can log why a definition was skipped.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.is_empty()
+            || !self
+                .name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        {
+            return Err(format!(
+                "name '{}' must be a snake_case identifier",
+                self.name
+            ));
+        }
+        if self.command.trim().is_empty() {
+            return Err("command is empty".to_string());
+        }
+        for placeholder in template_placeholders(&self.command) {
+            if !self.args.iter().any(|a| a.name == placeholder) {
+                return Err(format!(
+                    "command references undeclared argument '{{{placeholder}}}'"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Extract `{name}` placeholders from a command template.
+fn template_placeholders(template: &str) -> Vec<String> {
+    let mut placeholders = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rest = &rest[start + 1..];
+        if let Some(end) = rest.find('}') {
+            let name = &rest[..end];
+            if !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_')
+            {
+                placeholders.push(name.to_string());
+            }
+            rest = &rest[end + 1..];
+        } else {
+            break;
+        }
+    }
+    placeholders
+}
+
+/// Single-quote a value for POSIX shell so it substitutes as one literal word.
+pub(crate) fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// A tool materialized from a [`CustomToolDef`]: declares the configured
+/// schema and executes the templated command via `sh -c` in the working
+/// directory.
+pub struct CustomTool {
+    def: CustomToolDef,
+    cwd: PathBuf,
+    timeout: u64,
+    events_tx: Option<mpsc::Sender<AgentEvent>>,
+    dry_run: bool,
+}
+
+impl CustomTool {
+    pub fn new(
+        def: CustomToolDef,
+        cwd: PathBuf,
+        timeout: u64,
+        events_tx: Option<mpsc::Sender<AgentEvent>>,
+    ) -> Self {
+        Self {
+            def,
+            cwd,
+            timeout,
+            events_tx,
+            dry_run: false,
+        }
+    }
+
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Substitute declared arguments into the command template.
+    /// Strings are shell-quoted; numbers and booleans substitute literally.
+    fn render_command(&self, args: &Value) -> Result<String, FunctionError> {
+        let mut command = self.def.command.clone();
+        for arg in &self.def.args {
+            let placeholder = format!("{{{}}}", arg.name);
+            let value = match args.get(&arg.name) {
+                Some(Value::String(s)) => Some(shell_quote(s)),
+                Some(Value::Number(n)) => Some(n.to_string()),
+                Some(Value::Bool(b)) => Some(b.to_string()),
+                Some(_) | None => None,
+            };
+            match value {
+                Some(v) => command = command.replace(&placeholder, &v),
+                None if arg.required => {
+                    return Err(FunctionError::ArgumentMismatch(format!(
+                        "Missing {}",
+                        arg.name
+                    )));
+                }
+                // Optional and absent: drop the placeholder.
+                None => command = command.replace(&placeholder, ""),
+            }
+        }
+        Ok(command)
+    }
+}
+
+impl ToolEmitter for CustomTool {
+    fn events_tx(&self) -> &Option<mpsc::Sender<AgentEvent>> {
+        &self.events_tx
+    }
+}
+
+#[async_trait]
+impl CallableFunction for CustomTool {
+    fn declaration(&self) -> FunctionDeclaration {
+        let mut properties = serde_json::Map::new();
+        for arg in &self.def.args {
+            properties.insert(
+                arg.name.clone(),
+                json!({
+                    "type": arg.arg_type,
+                    "description": arg.description,
+                }),
+            );
+        }
+        let required = self
+            .def
+            .args
+            .iter()
+            .filter(|a| a.required)
+            .map(|a| a.name.clone())
+            .collect();
+        FunctionDeclaration::new(
+            self.def.name.clone(),
+            format!(
+                "{} Returns: {{stdout, stderr, exit_code, success}}",
+                self.def.description
+            ),
+            FunctionParameters::new(
+                "object".to_string(),
+                Value::Object(properties),
+                required,
+            ),
+        )
+    }
+
+    #[instrument(skip(self, args))]
+    async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        let command = self.render_command(&args)?;
+
+        if self.dry_run {
+            self.emit(&format!(
+                "  {} {}",
+                "DRY RUN (not executed):".yellow(),
+                command
+            ));
+            return Ok(json!({
+                "command": command,
+                "dry_run": true,
+                "success": true
+            }));
+        }
+
+        self.emit(&format!("  $ {command}").dimmed().to_string());
+
+        let result = tokio::time::timeout(
+            std::time::Duration::from_secs(self.timeout),
+            tokio::process::Command::new("sh")
+                .arg("-c")
+                .arg(&command)
+                .current_dir(&self.cwd)
+                .output(),
+        )
+        .await;
+
+        match result {
+            Ok(Ok(output)) => {
+                let exit_code = output.status.code().unwrap_or(-1);
+                let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+                let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+                Ok(json!({
+                    "command": command,
+                    "stdout": BashTool::truncate_output(stdout, MAX_TOOL_OUTPUT_LEN),
+                    "stderr": BashTool::truncate_output(stderr, MAX_TOOL_OUTPUT_LEN),
+                    "exit_code": exit_code,
+                    "success": output.status.success()
+                }))
+            }
+            Ok(Err(e)) => Ok(error_response(
+                &format!("Failed to execute {}: {e}", self.def.name),
+                error_codes::IO_ERROR,
+                json!({"command": command}),
+            )),
+            Err(_) => Ok(error_response(
+                &format!("{} timed out after {}s", self.def.name, self.timeout),
+                error_codes::TIMEOUT,
+                json!({"command": command, "timeout_secs": self.timeout}),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn echo_def() -> CustomToolDef {
+        CustomToolDef {
+            name: "greet".to_string(),
+            description: "Greet someone".to_string(),
+            command: "echo hello {who}".to_string(),
+            args: vec![CustomArgDef {
+                name: "who".to_string(),
+                arg_type: "string".to_string(),
+                description: "Who to greet".to_string(),
+                required: true,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_parse_tools_toml() {
+        let parsed: CustomToolsToml = toml::from_str(
+            r#"
+            [[tools]]
+            name = "run_migration"
+            description = "Run a database migration"
+            command = "scripts/migrate.sh {direction}"
+
+            [[tools.args]]
+            name = "direction"
+            description = "Either 'up' or 'down'"
+            required = true
+            "#,
+        )
+        .unwrap();
+        assert_eq!(parsed.tools.len(), 1);
+        let def = &parsed.tools[0];
+        assert_eq!(def.name, "run_migration");
+        assert_eq!(def.args[0].arg_type, "string");
+        assert!(def.args[0].required);
+        assert!(def.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_definitions() {
+        let mut def = echo_def();
+        def.name = "Bad Name".to_string();
+        assert!(def.validate().is_err());
+
+        let mut def = echo_def();
+        def.command = "echo {nope}".to_string();
+        assert!(
+            def.validate()
+                .unwrap_err()
+                .contains("undeclared argument '{nope}'")
+        );
+
+        let mut def = echo_def();
+        def.command = "  ".to_string();
+        assert!(def.validate().is_err());
+    }
+
+    #[test]
+    fn test_declaration_matches_definition() {
+        let tool = CustomTool::new(echo_def(), PathBuf::from("."), 10, None);
+        let decl = tool.declaration();
+        assert_eq!(decl.name(), "greet");
+        assert!(decl.description().contains("Greet someone"));
+        assert!(decl.description().contains("Returns:"));
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("plain"), "'plain'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    }
+
+    #[tokio::test]
+    async fn test_executes_templated_command() {
+        let dir = tempdir().unwrap();
+        let tool = CustomTool::new(echo_def(), dir.path().to_path_buf(), 10, None);
+
+        let result = tool.call(json!({"who": "world"})).await.unwrap();
+        assert!(result["success"].as_bool().unwrap(), "got: {result}");
+        assert_eq!(result["stdout"].as_str().unwrap().trim(), "hello world");
+        assert_eq!(result["exit_code"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_string_values_are_quoted_not_injected() {
+        let dir = tempdir().unwrap();
+        let tool = CustomTool::new(echo_def(), dir.path().to_path_buf(), 10, None);
+
+        let result = tool
+            .call(json!({"who": "world; touch pwned"}))
+            .await
+            .unwrap();
+        assert!(result["success"].as_bool().unwrap(), "got: {result}");
+        assert_eq!(
+            result["stdout"].as_str().unwrap().trim(),
+            "hello world; touch pwned"
+        );
+        assert!(!dir.path().join("pwned").exists());
+    }
+
+    #[tokio::test]
+    async fn test_missing_required_argument() {
+        let tool = CustomTool::new(echo_def(), PathBuf::from("."), 10, None);
+        let err = tool.call(json!({})).await.unwrap_err();
+        assert!(err.to_string().contains("Missing who"));
+    }
+
+    #[tokio::test]
+    async fn test_optional_argument_drops_placeholder() {
+        let dir = tempdir().unwrap();
+        let mut def = echo_def();
+        def.args[0].required = false;
+        let tool = CustomTool::new(def, dir.path().to_path_buf(), 10, None);
+
+        let result = tool.call(json!({})).await.unwrap();
+        assert_eq!(result["stdout"].as_str().unwrap().trim(), "hello");
+    }
+
+    #[tokio::test]
+    async fn test_timeout_returns_timeout_error() {
+        let dir = tempdir().unwrap();
+        let def = CustomToolDef {
+            name: "sleeper".to_string(),
+            description: "Sleep".to_string(),
+            command: "sleep 30".to_string(),
+            args: vec![],
+        };
+        let tool = CustomTool::new(def, dir.path().to_path_buf(), 1, None);
+
+        let result = tool.call(json!({})).await.unwrap();
+        assert_eq!(result["error_code"], error_codes::TIMEOUT);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_does_not_execute() {
+        let dir = tempdir().unwrap();
+        let def = CustomToolDef {
+            name: "toucher".to_string(),
+            description: "Touch a file".to_string(),
+            command: "touch created".to_string(),
+            args: vec![],
+        };
+        let tool = CustomTool::new(def, dir.path().to_path_buf(), 10, None).with_dry_run(true);
+
+        let result = tool.call(json!({})).await.unwrap();
+        assert!(result["dry_run"].as_bool().unwrap());
+        assert!(!dir.path().join("created").exists());
+    }
+}
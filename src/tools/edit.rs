@@ -15,6 +15,8 @@ pub struct EditTool {
     cwd: PathBuf,
     allowed_paths: Vec<PathBuf>,
     events_tx: Option<mpsc::Sender<AgentEvent>>,
+    dry_run: bool,
+    review: Option<super::review::ReviewChanges>,
 }
 
 impl EditTool {
@@ -27,8 +29,40 @@ impl EditTool {
             cwd,
             allowed_paths,
             events_tx,
+            dry_run: false,
+            review: None,
         }
     }
+
+    /// In dry-run mode the proposed edit is emitted as a diff and reported
+    /// as a success, but the file is not modified.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// In review mode edits are staged into the pending changeset instead
+    /// of hitting disk, until the user runs `/apply` or `/discard`.
+    pub fn with_review(mut self, review: Option<super::review::ReviewChanges>) -> Self {
+        self.review = review;
+        self
+    }
+
+    /// Write `content` to `path` (checkpointing the pre-edit state first),
+    /// or stage it into the pending review changeset when review mode is on.
+    /// Returns whether it was staged.
+    async fn write_or_stage(&self, path: &std::path::Path, content: &str) -> Result<bool, String> {
+        if let Some(review) = &self.review {
+            super::review::stage(review, path, content.to_string());
+            return Ok(true);
+        }
+        // Snapshot the pre-edit state so /undo and revert_file can restore it.
+        super::checkpoint::snapshot(&self.cwd, path);
+        tokio::fs::write(path, content)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(false)
+    }
 }
 
 impl ToolEmitter for EditTool {
@@ -37,13 +71,13 @@ impl ToolEmitter for EditTool {
     }
 }
 
-fn offset_to_line(content: &str, offset: usize) -> usize {
+pub(super) fn offset_to_line(content: &str, offset: usize) -> usize {
     content[..offset].lines().count() + 1
 }
 
 /// Find strings in content similar to the target.
 /// Returns up to `max_suggestions` matches with similarity >= `threshold`.
-fn find_similar_strings(
+pub(super) fn find_similar_strings(
     content: &str,
     target: &str,
     max_suggestions: usize,
@@ -80,12 +114,68 @@ fn find_similar_strings(
     candidates
 }
 
+/// Find line ranges of `content` whose whitespace-normalized lines equal the
+/// normalized lines of `target`. Returns 0-based starting line indices; the
+/// match is always `target.lines().count()` lines long.
+fn find_fuzzy_matches(content: &str, target: &str) -> Vec<usize> {
+    let target_lines: Vec<&str> = target.lines().map(str::trim).collect();
+    if target_lines.is_empty() {
+        return Vec::new();
+    }
+    let content_lines: Vec<&str> = content.lines().collect();
+
+    (0..=content_lines.len().saturating_sub(target_lines.len()))
+        .filter(|&i| {
+            content_lines[i..i + target_lines.len()]
+                .iter()
+                .zip(&target_lines)
+                .all(|(actual, expected)| actual.trim() == *expected)
+        })
+        .collect()
+}
+
+/// Re-indent `new_string` for a fuzzy replacement: if the matched region's
+/// first line is indented deeper than the target's, the difference is
+/// prepended to every non-empty line so the replacement sits at the file's
+/// actual indentation, not the model's guess.
+fn reindent_for_match(
+    new_string: &str,
+    matched_first_line: &str,
+    target_first_line: &str,
+) -> String {
+    let indent_of = |line: &str| &line[..line.len() - line.trim_start().len()];
+    let matched_indent = indent_of(matched_first_line);
+    let target_indent = indent_of(target_first_line);
+
+    let Some(extra) = matched_indent.strip_suffix(target_indent) else {
+        return new_string.to_string();
+    };
+    if extra.is_empty() {
+        return new_string.to_string();
+    }
+
+    let mut lines: Vec<String> = new_string
+        .lines()
+        .map(|line| {
+            if line.trim().is_empty() {
+                line.to_string()
+            } else {
+                format!("{}{}", extra, line)
+            }
+        })
+        .collect();
+    if new_string.ends_with('\n') {
+        lines.push(String::new());
+    }
+    lines.join("\n")
+}
+
 #[async_trait]
 impl CallableFunction for EditTool {
     fn declaration(&self) -> FunctionDeclaration {
         FunctionDeclaration::new(
             "edit".to_string(),
-            "Replace a specific string in a file with new content. If 'replace_all' is true, all occurrences are replaced. Otherwise, 'old_string' must match exactly and uniquely in the file. Returns: {success, replacements, file_size} or {error, suggestions?}".to_string(),
+            "Replace a specific string in a file with new content. If 'replace_all' is true, all occurrences are replaced. Otherwise, 'old_string' must match exactly and uniquely in the file. Set 'fuzzy' to true to tolerate indentation/whitespace differences when no exact match exists. Set 'preview' to true to get a unified diff of the would-be change without writing. Returns: {success, replacements, file_size, diff?} or {error, suggestions?, closest_match?}".to_string(),
             FunctionParameters::new(
                 "object".to_string(),
                 json!({
@@ -108,6 +198,14 @@ impl CallableFunction for EditTool {
                     "create_if_not_exists": {
                         "type": "boolean",
                         "description": "If true, create the file if it does not exist. In this case, 'old_string' is ignored and the file is created with 'new_string' as its content. (default: false)"
+                    },
+                    "fuzzy": {
+                        "type": "boolean",
+                        "description": "If true and 'old_string' has no exact match, fall back to whitespace-normalized matching (per-line, ignoring indentation and trailing whitespace); the replacement is re-indented to the file's actual indentation. The fuzzy match must still be unique. (default: false)"
+                    },
+                    "preview": {
+                        "type": "boolean",
+                        "description": "If true, compute the change and return it as a unified diff in the 'diff' field without modifying the file. (default: false)"
                     }
                 }),
                 vec!["file_path".to_string(), "new_string".to_string()],
@@ -139,6 +237,17 @@ impl CallableFunction for EditTool {
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
 
+        let fuzzy = args.get("fuzzy").and_then(|v| v.as_bool()).unwrap_or(false);
+
+        let preview = args
+            .get("preview")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        // Preview behaves like a per-call dry run: compute everything,
+        // write nothing
+        let skip_write = self.dry_run || preview;
+
         if let Some(old) = old_string
             && old == new_string
         {
@@ -161,35 +270,73 @@ impl CallableFunction for EditTool {
             }
         };
 
-        // Read the file
-        let content = match tokio::fs::read_to_string(&path).await {
-            Ok(c) => Some(c),
-            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
-            Err(e) => {
-                return Ok(error_response(
-                    &format!(
-                        "Failed to read {}: {}. Ensure the file exists and is not a directory.",
-                        path.display(),
-                        e
-                    ),
-                    error_codes::IO_ERROR,
-                    json!({"path": file_path}),
-                ));
-            }
+        // Read the file; staged review content shadows the disk so repeated
+        // edits in review mode build on each other
+        let content = match super::review::staged_content(&self.review, &path) {
+            Some(staged) => Some(staged),
+            None => match tokio::fs::read_to_string(&path).await {
+                Ok(c) => Some(c),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+                Err(e) => {
+                    return Ok(error_response(
+                        &format!(
+                            "Failed to read {}: {}. Ensure the file exists and is not a directory.",
+                            path.display(),
+                            e
+                        ),
+                        error_codes::IO_ERROR,
+                        json!({"path": file_path}),
+                    ));
+                }
+            },
         };
 
         let content = match content {
             Some(c) => c,
             None if create_if_not_exists => {
+                if skip_write {
+                    let diff_output = crate::diff::format_diff("", new_string, 2, Some(file_path));
+                    if !diff_output.is_empty() {
+                        self.emit(&diff_output);
+                    }
+                    let mut result = json!({
+                        "file_path": file_path,
+                        "success": true,
+                        "created": true,
+                        "file_size": new_string.len()
+                    });
+                    if self.dry_run {
+                        result["dry_run"] = json!(true);
+                    }
+                    if preview {
+                        result["preview"] = json!(true);
+                        result["diff"] = json!(crate::diff::unified_diff(
+                            "",
+                            new_string,
+                            2,
+                            Some(file_path)
+                        ));
+                    }
+                    return Ok(result);
+                }
                 // Create new file
-                match tokio::fs::write(&path, new_string).await {
-                    Ok(()) => {
-                        return Ok(json!({
+                match self.write_or_stage(&path, new_string).await {
+                    Ok(staged) => {
+                        let diff_output =
+                            crate::diff::format_diff("", new_string, 2, Some(file_path));
+                        if !diff_output.is_empty() {
+                            self.emit(&diff_output);
+                        }
+                        let mut result = json!({
                             "file_path": file_path,
                             "success": true,
                             "created": true,
                             "file_size": new_string.len()
-                        }));
+                        });
+                        if staged {
+                            result["staged"] = json!(true);
+                        }
+                        return Ok(result);
                     }
                     Err(e) => {
                         return Ok(error_response(
@@ -227,12 +374,128 @@ impl CallableFunction for EditTool {
         let matches: Vec<_> = content.match_indices(old_string).collect();
 
         if matches.is_empty() {
+            // Opt-in fallback: whitespace-normalized matching, so slightly
+            // wrong indentation or trailing whitespace doesn't force a retry
+            if fuzzy {
+                let fuzzy_matches = find_fuzzy_matches(&content, old_string);
+                match fuzzy_matches.as_slice() {
+                    [] => {} // fall through to the NOT_FOUND error below
+                    [start] => {
+                        let content_lines: Vec<&str> = content.lines().collect();
+                        let line_count = old_string.lines().count();
+                        let matched_text = content_lines[*start..*start + line_count].join("\n");
+                        let replacement = reindent_for_match(
+                            new_string,
+                            content_lines[*start],
+                            old_string.lines().next().unwrap_or(""),
+                        );
+                        let new_content = content.replacen(&matched_text, &replacement, 1);
+
+                        let mut staged = false;
+                        if !skip_write {
+                            match self.write_or_stage(&path, &new_content).await {
+                                Ok(s) => staged = s,
+                                Err(e) => {
+                                    return Ok(error_response(
+                                        &format!(
+                                            "Failed to write {}: {}. Check file permissions.",
+                                            path.display(),
+                                            e
+                                        ),
+                                        error_codes::IO_ERROR,
+                                        json!({"path": file_path}),
+                                    ));
+                                }
+                            }
+                        }
+
+                        let diff_output = crate::diff::format_diff(
+                            &matched_text,
+                            &replacement,
+                            2,
+                            Some(file_path),
+                        );
+                        if !diff_output.is_empty() {
+                            self.emit(&diff_output);
+                        }
+
+                        let mut result = json!({
+                            "file_path": file_path,
+                            "success": true,
+                            "old_length": matched_text.len(),
+                            "new_length": replacement.len(),
+                            "file_size": new_content.len(),
+                            "replacements": 1,
+                            "fuzzy": true,
+                            "matched_line": start + 1
+                        });
+                        if self.dry_run {
+                            result["dry_run"] = json!(true);
+                        }
+                        if staged {
+                            result["staged"] = json!(true);
+                        }
+                        if preview {
+                            result["preview"] = json!(true);
+                            result["diff"] = json!(crate::diff::unified_diff(
+                                &content,
+                                &new_content,
+                                2,
+                                Some(file_path)
+                            ));
+                        }
+                        return Ok(result);
+                    }
+                    starts => {
+                        let lines: Vec<usize> = starts.iter().map(|s| s + 1).collect();
+                        let lines_str = lines
+                            .iter()
+                            .map(|l| l.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        return Ok(error_response(
+                            &format!(
+                                "The 'old_string' fuzzy-matches {} regions in {} at lines {}. It must be unique to ensure the correct replacement. Provide more surrounding context.",
+                                starts.len(),
+                                file_path,
+                                lines_str
+                            ),
+                            error_codes::NOT_UNIQUE,
+                            json!({
+                                "path": file_path,
+                                "occurrences": starts.len(),
+                                "lines": lines,
+                                "fuzzy": true
+                            }),
+                        ));
+                    }
+                }
+            }
+
             let suggestions = find_similar_strings(&content, old_string, 3, 0.6);
 
             let mut context = json!({
                 "path": file_path
             });
 
+            // In fuzzy mode, surface the single closest region (with score)
+            // even below the suggestion threshold, so the model can
+            // self-correct in one retry
+            if fuzzy
+                && let Some((text, line, similarity)) =
+                    find_similar_strings(&content, old_string, 1, 0.3).first()
+            {
+                context["closest_match"] = json!({
+                    "line": line,
+                    "similarity": format!("{:.0}%", similarity * 100.0),
+                    "text": if text.len() > MAX_SUGGESTION_PREVIEW_LEN {
+                        format!("{}...", &text[..MAX_SUGGESTION_PREVIEW_LEN])
+                    } else {
+                        text.clone()
+                    }
+                });
+            }
+
             if !suggestions.is_empty() {
                 let suggestion_details: Vec<Value> = suggestions
                     .iter()
@@ -300,9 +563,37 @@ impl CallableFunction for EditTool {
             (content.replacen(old_string, new_string, 1), 1)
         };
 
+        if skip_write {
+            let diff_output = crate::diff::format_diff(old_string, new_string, 2, Some(file_path));
+            if !diff_output.is_empty() {
+                self.emit(&diff_output);
+            }
+            let mut result = json!({
+                "file_path": file_path,
+                "success": true,
+                "old_length": old_string.len(),
+                "new_length": new_string.len(),
+                "file_size": new_content.len(),
+                "replacements": count
+            });
+            if self.dry_run {
+                result["dry_run"] = json!(true);
+            }
+            if preview {
+                result["preview"] = json!(true);
+                result["diff"] = json!(crate::diff::unified_diff(
+                    &content,
+                    &new_content,
+                    2,
+                    Some(file_path)
+                ));
+            }
+            return Ok(result);
+        }
+
         // Write the file
-        match tokio::fs::write(&path, &new_content).await {
-            Ok(()) => {
+        match self.write_or_stage(&path, &new_content).await {
+            Ok(staged) => {
                 // Log the diff
                 let diff_output =
                     crate::diff::format_diff(old_string, new_string, 2, Some(file_path));
@@ -310,14 +601,18 @@ impl CallableFunction for EditTool {
                     self.emit(&diff_output);
                 }
 
-                Ok(json!({
+                let mut result = json!({
                     "file_path": file_path,
                     "success": true,
                     "old_length": old_string.len(),
                     "new_length": new_string.len(),
                     "file_size": new_content.len(),
                     "replacements": count
-                }))
+                });
+                if staged {
+                    result["staged"] = json!(true);
+                }
+                Ok(result)
             }
             Err(e) => Ok(error_response(
                 &format!(
@@ -360,6 +655,46 @@ mod tests {
         assert_eq!(saved_content, "updated content");
     }
 
+    #[tokio::test]
+    async fn test_edit_tool_dry_run_does_not_modify_file() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("test.txt");
+        fs::write(&file_path, "original content").unwrap();
+
+        let tool = EditTool::new(cwd.clone(), vec![cwd.clone()], None).with_dry_run(true);
+        let args = json!({
+            "file_path": "test.txt",
+            "old_string": "original",
+            "new_string": "updated"
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["success"].as_bool().unwrap());
+        assert!(result["dry_run"].as_bool().unwrap());
+        assert_eq!(result["replacements"], 1);
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "original content");
+    }
+
+    #[tokio::test]
+    async fn test_edit_tool_dry_run_create_if_not_exists() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+
+        let tool = EditTool::new(cwd.clone(), vec![cwd.clone()], None).with_dry_run(true);
+        let args = json!({
+            "file_path": "new.txt",
+            "new_string": "content",
+            "create_if_not_exists": true
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["success"].as_bool().unwrap());
+        assert!(result["dry_run"].as_bool().unwrap());
+        assert!(result["created"].as_bool().unwrap());
+        assert!(!cwd.join("new.txt").exists());
+    }
+
     #[tokio::test]
     async fn test_edit_tool_not_found() {
         let dir = tempdir().unwrap();
@@ -538,6 +873,237 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_edit_tool_preview_returns_diff_without_writing() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("test.txt");
+        fs::write(&file_path, "line one\nline two\nline three").unwrap();
+
+        let tool = EditTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "test.txt",
+            "old_string": "line two",
+            "new_string": "line 2",
+            "preview": true
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["success"].as_bool().unwrap());
+        assert!(result["preview"].as_bool().unwrap());
+        assert!(result["dry_run"].is_null());
+
+        let diff = result["diff"].as_str().unwrap();
+        assert!(diff.contains("--- a/test.txt"));
+        assert!(diff.contains("-line two"));
+        assert!(diff.contains("+line 2"));
+
+        assert_eq!(
+            fs::read_to_string(&file_path).unwrap(),
+            "line one\nline two\nline three"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_edit_tool_preview_create_if_not_exists() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+
+        let tool = EditTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "new.txt",
+            "new_string": "content",
+            "create_if_not_exists": true,
+            "preview": true
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["success"].as_bool().unwrap());
+        assert!(result["preview"].as_bool().unwrap());
+        assert!(result["diff"].as_str().unwrap().contains("+content"));
+        assert!(!cwd.join("new.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_edit_tool_preview_with_fuzzy_match() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("test.rs");
+        fs::write(&file_path, "fn f() {\n        old();\n}").unwrap();
+
+        let tool = EditTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "test.rs",
+            "old_string": "    old();",
+            "new_string": "    updated();",
+            "fuzzy": true,
+            "preview": true
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["success"].as_bool().unwrap(), "got: {result}");
+        assert!(result["preview"].as_bool().unwrap());
+        assert!(result["fuzzy"].as_bool().unwrap());
+        // Diff reflects the re-indented replacement
+        assert!(
+            result["diff"]
+                .as_str()
+                .unwrap()
+                .contains("+        updated();")
+        );
+        assert_eq!(
+            fs::read_to_string(&file_path).unwrap(),
+            "fn f() {\n        old();\n}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_edit_tool_fuzzy_mode_matches_despite_indentation() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("test.rs");
+        // File indented with 8 spaces; model asks with 4
+        fs::write(&file_path, "fn f() {\n        let x = 5;\n}").unwrap();
+
+        let tool = EditTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "test.rs",
+            "old_string": "    let x = 5;",
+            "new_string": "    let x = 10;",
+            "fuzzy": true
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["success"].as_bool().unwrap(), "got: {result}");
+        assert!(result["fuzzy"].as_bool().unwrap());
+        assert_eq!(result["matched_line"], 2);
+
+        // Replacement is re-indented to the file's actual indentation
+        assert_eq!(
+            fs::read_to_string(&file_path).unwrap(),
+            "fn f() {\n        let x = 10;\n}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_edit_tool_fuzzy_mode_trailing_whitespace() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("test.txt");
+        fs::write(&file_path, "hello world\nnext line").unwrap();
+
+        let tool = EditTool::new(cwd.clone(), vec![cwd.clone()], None);
+        // Model's old_string has spurious trailing whitespace
+        let args = json!({
+            "file_path": "test.txt",
+            "old_string": "hello world   ",
+            "new_string": "goodbye world",
+            "fuzzy": true
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["success"].as_bool().unwrap(), "got: {result}");
+        assert!(result["fuzzy"].as_bool().unwrap());
+        assert!(
+            fs::read_to_string(&file_path)
+                .unwrap()
+                .starts_with("goodbye world")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_edit_tool_fuzzy_mode_ambiguous_is_not_unique() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("test.txt");
+        // Neither line contains "    same();" exactly, but both match it
+        // with whitespace normalized
+        fs::write(&file_path, "  same();\n\tsame();").unwrap();
+
+        let tool = EditTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "test.txt",
+            "old_string": "    same();",
+            "new_string": "different();",
+            "fuzzy": true
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert_eq!(result["error_code"], error_codes::NOT_UNIQUE);
+        assert_eq!(result["context"]["lines"], json!([1, 2]));
+        assert_eq!(result["context"]["fuzzy"], true);
+        assert_eq!(
+            fs::read_to_string(&file_path).unwrap(),
+            "  same();\n\tsame();"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_edit_tool_fuzzy_mode_failure_includes_closest_match() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("test.rs");
+        fs::write(&file_path, "fn hello_world() {\n    go();\n}").unwrap();
+
+        let tool = EditTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "test.rs",
+            "old_string": "fn hello_wrold() {",  // typo - fuzzy can't fix this
+            "new_string": "fn greet() {",
+            "fuzzy": true
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert_eq!(result["error_code"], error_codes::NOT_FOUND);
+        let closest = &result["context"]["closest_match"];
+        assert_eq!(closest["text"], "fn hello_world() {");
+        assert_eq!(closest["line"], 1);
+        assert!(closest["similarity"].as_str().unwrap().ends_with('%'));
+    }
+
+    #[tokio::test]
+    async fn test_edit_tool_fuzzy_mode_exact_match_still_preferred() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("test.txt");
+        fs::write(&file_path, "exact\n  exact").unwrap();
+
+        let tool = EditTool::new(cwd.clone(), vec![cwd.clone()], None);
+        // "exact\n" appears... exact matching runs first, and "  exact" also
+        // contains the substring, so this is NOT_UNIQUE via the exact path
+        let args = json!({
+            "file_path": "test.txt",
+            "old_string": "exact",
+            "new_string": "changed",
+            "fuzzy": true
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert_eq!(result["error_code"], error_codes::NOT_UNIQUE);
+        // Exact matching reported it, not the fuzzy fallback
+        assert!(result["context"].get("fuzzy").is_none());
+    }
+
+    #[test]
+    fn test_find_fuzzy_matches_multiline() {
+        let content = "fn f() {\n\tone();\n\ttwo();\n}";
+        let target = "    one();\n    two();";
+        assert_eq!(find_fuzzy_matches(content, target), vec![1]);
+    }
+
+    #[test]
+    fn test_reindent_for_match() {
+        // Matched line is deeper by 4 spaces; replacement gains them
+        assert_eq!(
+            reindent_for_match("    a();\n    b();", "        old();", "    old();"),
+            "        a();\n        b();"
+        );
+        // Same indentation - unchanged
+        assert_eq!(reindent_for_match("a();", "old();", "old();"), "a();");
+        // Incompatible indent styles - replacement used as-is
+        assert_eq!(reindent_for_match("a();", "\told();", "  old();"), "a();");
+    }
+
     #[tokio::test]
     async fn test_edit_tool_file_not_exists_default() {
         let dir = tempdir().unwrap();
@@ -619,4 +1185,38 @@ mod tests {
         );
         assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
     }
+
+    #[tokio::test]
+    async fn test_edit_tool_review_stages_and_compounds() {
+        use std::sync::{Arc, RwLock};
+
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("test.txt");
+        fs::write(&file_path, "one").unwrap();
+
+        let review: super::super::review::ReviewChanges =
+            Arc::new(RwLock::new(super::super::review::PendingChanges::default()));
+        let tool =
+            EditTool::new(cwd.clone(), vec![cwd.clone()], None).with_review(Some(review.clone()));
+
+        let result = tool
+            .call(json!({"file_path": "test.txt", "old_string": "one", "new_string": "two"}))
+            .await
+            .unwrap();
+        assert!(result["staged"].as_bool().unwrap());
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "one");
+
+        // The second edit matches against the staged content, so a chain of
+        // edits in review mode composes like it would on disk.
+        let result = tool
+            .call(json!({"file_path": "test.txt", "old_string": "two", "new_string": "three"}))
+            .await
+            .unwrap();
+        assert!(result["staged"].as_bool().unwrap());
+
+        let staged =
+            super::super::review::staged_content(&Some(review), &file_path.canonicalize().unwrap());
+        assert_eq!(staged.as_deref(), Some("three"));
+    }
 }
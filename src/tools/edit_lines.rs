@@ -0,0 +1,529 @@
+use crate::agent::AgentEvent;
+use async_trait::async_trait;
+use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use tokio::sync::mpsc;
+use tracing::instrument;
+
+use super::{ToolEmitter, error_codes, error_response, resolve_and_validate_path};
+
+pub struct EditLinesTool {
+    cwd: PathBuf,
+    allowed_paths: Vec<PathBuf>,
+    events_tx: Option<mpsc::Sender<AgentEvent>>,
+    dry_run: bool,
+    review: Option<super::review::ReviewChanges>,
+}
+
+impl EditLinesTool {
+    pub fn new(
+        cwd: PathBuf,
+        allowed_paths: Vec<PathBuf>,
+        events_tx: Option<mpsc::Sender<AgentEvent>>,
+    ) -> Self {
+        Self {
+            cwd,
+            allowed_paths,
+            events_tx,
+            dry_run: false,
+            review: None,
+        }
+    }
+
+    /// In dry-run mode the proposed edit is emitted as a diff and reported
+    /// as a success, but the file is not modified.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// In review mode the edited content is staged into the pending
+    /// changeset instead of hitting disk, until the user runs `/apply` or
+    /// `/discard`.
+    pub fn with_review(mut self, review: Option<super::review::ReviewChanges>) -> Self {
+        self.review = review;
+        self
+    }
+}
+
+impl ToolEmitter for EditLinesTool {
+    fn events_tx(&self) -> &Option<mpsc::Sender<AgentEvent>> {
+        &self.events_tx
+    }
+}
+
+#[async_trait]
+impl CallableFunction for EditLinesTool {
+    fn declaration(&self) -> FunctionDeclaration {
+        FunctionDeclaration::new(
+            "edit_lines".to_string(),
+            "Replace an inclusive 1-indexed line range in a file with new content. Line numbers match what read_file reports, so read the file first and use this for large block replacements where an exact-string anchor would be brittle. An empty 'new_content' deletes the range. Returns: {success, lines_replaced, lines_inserted, file_size}".to_string(),
+            FunctionParameters::new(
+                "object".to_string(),
+                json!({
+                    "file_path": {
+                        "type": "string",
+                        "description": "Path to the file to edit"
+                    },
+                    "start_line": {
+                        "type": "integer",
+                        "description": "First line of the range to replace (1-indexed, inclusive)"
+                    },
+                    "end_line": {
+                        "type": "integer",
+                        "description": "Last line of the range to replace (1-indexed, inclusive). Must be >= start_line."
+                    },
+                    "new_content": {
+                        "type": "string",
+                        "description": "The replacement for the line range. May span any number of lines; an empty string deletes the range."
+                    }
+                }),
+                vec![
+                    "file_path".to_string(),
+                    "start_line".to_string(),
+                    "end_line".to_string(),
+                    "new_content".to_string(),
+                ],
+            ),
+        )
+    }
+
+    #[instrument(skip(self, args))]
+    async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        let file_path = args
+            .get("file_path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing file_path".to_string()))?;
+
+        let start_line = args
+            .get("start_line")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing start_line".to_string()))?
+            as usize;
+
+        let end_line = args
+            .get("end_line")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing end_line".to_string()))?
+            as usize;
+
+        let new_content = args
+            .get("new_content")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing new_content".to_string()))?;
+
+        if start_line == 0 {
+            return Ok(error_response(
+                "'start_line' is 1-indexed; 0 is not a valid line number.",
+                error_codes::INVALID_ARGUMENT,
+                json!({"start_line": start_line}),
+            ));
+        }
+
+        if end_line < start_line {
+            return Ok(error_response(
+                &format!(
+                    "'end_line' ({}) must be >= 'start_line' ({}).",
+                    end_line, start_line
+                ),
+                error_codes::INVALID_ARGUMENT,
+                json!({"start_line": start_line, "end_line": end_line}),
+            ));
+        }
+
+        // Resolve and validate path
+        let path = match resolve_and_validate_path(file_path, &self.cwd, &self.allowed_paths) {
+            Ok(p) => p,
+            Err(e) => {
+                return Ok(error_response(
+                    &format!("Access denied: {}. Path must be within allowed paths.", e),
+                    error_codes::ACCESS_DENIED,
+                    json!({"path": file_path}),
+                ));
+            }
+        };
+
+        // Staged review content shadows the disk so repeated edits in review
+        // mode build on each other (and line numbers match what read_file
+        // reported).
+        let content = match super::review::staged_content(&self.review, &path) {
+            Some(staged) => staged,
+            None => match tokio::fs::read_to_string(&path).await {
+                Ok(c) => c,
+                Err(e) => {
+                    return Ok(error_response(
+                        &format!(
+                            "Failed to read {}: {}. Ensure the file exists and is not a directory.",
+                            path.display(),
+                            e
+                        ),
+                        error_codes::NOT_FOUND,
+                        json!({"path": file_path}),
+                    ));
+                }
+            },
+        };
+
+        let lines: Vec<&str> = content.lines().collect();
+        let total_lines = lines.len();
+
+        if end_line > total_lines {
+            return Ok(error_response(
+                &format!(
+                    "Line range {}-{} is out of bounds: {} has {} lines. Re-read the file to get current line numbers.",
+                    start_line, end_line, file_path, total_lines
+                ),
+                error_codes::INVALID_ARGUMENT,
+                json!({
+                    "path": file_path,
+                    "start_line": start_line,
+                    "end_line": end_line,
+                    "total_lines": total_lines
+                }),
+            ));
+        }
+
+        let old_block = lines[start_line - 1..end_line].join("\n");
+
+        let mut new_lines: Vec<&str> = Vec::with_capacity(total_lines);
+        new_lines.extend(&lines[..start_line - 1]);
+        new_lines.extend(new_content.lines());
+        new_lines.extend(&lines[end_line..]);
+        let lines_inserted = new_content.lines().count();
+
+        let mut result = new_lines.join("\n");
+        if content.ends_with('\n') && !result.is_empty() {
+            result.push('\n');
+        }
+
+        let mut staged = false;
+        if !self.dry_run {
+            if let Some(review) = &self.review {
+                super::review::stage(review, &path, result.clone());
+                staged = true;
+            } else {
+                // Snapshot the pre-edit state so /undo and revert_file can
+                // restore it.
+                super::checkpoint::snapshot(&self.cwd, &path);
+                if let Err(e) = tokio::fs::write(&path, &result).await {
+                    return Ok(error_response(
+                        &format!(
+                            "Failed to write {}: {}. Check file permissions.",
+                            path.display(),
+                            e
+                        ),
+                        error_codes::IO_ERROR,
+                        json!({"path": file_path}),
+                    ));
+                }
+            }
+        }
+
+        let diff_output = crate::diff::format_diff(&old_block, new_content, 2, Some(file_path));
+        if !diff_output.is_empty() {
+            self.emit(&diff_output);
+        }
+
+        let mut response = json!({
+            "file_path": file_path,
+            "success": true,
+            "lines_replaced": end_line - start_line + 1,
+            "lines_inserted": lines_inserted,
+            "file_size": result.len()
+        });
+        if self.dry_run {
+            response["dry_run"] = json!(true);
+        }
+        if staged {
+            response["staged"] = json!(true);
+        }
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_edit_lines_replaces_range() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("test.txt");
+        fs::write(&file_path, "one\ntwo\nthree\nfour\n").unwrap();
+
+        let tool = EditLinesTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "test.txt",
+            "start_line": 2,
+            "end_line": 3,
+            "new_content": "2\n3"
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["success"].as_bool().unwrap(), "got: {result}");
+        assert_eq!(result["lines_replaced"], 2);
+        assert_eq!(result["lines_inserted"], 2);
+
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "one\n2\n3\nfour\n");
+    }
+
+    #[tokio::test]
+    async fn test_edit_lines_single_line() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("test.txt");
+        fs::write(&file_path, "one\ntwo\nthree\n").unwrap();
+
+        let tool = EditLinesTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "test.txt",
+            "start_line": 1,
+            "end_line": 1,
+            "new_content": "ONE"
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["success"].as_bool().unwrap());
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "ONE\ntwo\nthree\n");
+    }
+
+    #[tokio::test]
+    async fn test_edit_lines_empty_content_deletes_range() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("test.txt");
+        fs::write(&file_path, "one\ntwo\nthree\n").unwrap();
+
+        let tool = EditLinesTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "test.txt",
+            "start_line": 2,
+            "end_line": 2,
+            "new_content": ""
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["success"].as_bool().unwrap());
+        assert_eq!(result["lines_inserted"], 0);
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "one\nthree\n");
+    }
+
+    #[tokio::test]
+    async fn test_edit_lines_delete_everything_leaves_empty_file() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("test.txt");
+        fs::write(&file_path, "one\ntwo\n").unwrap();
+
+        let tool = EditLinesTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "test.txt",
+            "start_line": 1,
+            "end_line": 2,
+            "new_content": ""
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["success"].as_bool().unwrap());
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "");
+    }
+
+    #[tokio::test]
+    async fn test_edit_lines_grows_range() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("test.txt");
+        fs::write(&file_path, "one\ntwo\n").unwrap();
+
+        let tool = EditLinesTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "test.txt",
+            "start_line": 2,
+            "end_line": 2,
+            "new_content": "two\nthree\nfour"
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["success"].as_bool().unwrap());
+        assert_eq!(result["lines_replaced"], 1);
+        assert_eq!(result["lines_inserted"], 3);
+        assert_eq!(
+            fs::read_to_string(&file_path).unwrap(),
+            "one\ntwo\nthree\nfour\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_edit_lines_preserves_missing_trailing_newline() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("test.txt");
+        fs::write(&file_path, "one\ntwo").unwrap();
+
+        let tool = EditLinesTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "test.txt",
+            "start_line": 1,
+            "end_line": 1,
+            "new_content": "ONE"
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["success"].as_bool().unwrap());
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "ONE\ntwo");
+    }
+
+    #[tokio::test]
+    async fn test_edit_lines_review_stages_instead_of_writing() {
+        use std::sync::{Arc, RwLock};
+
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("test.txt");
+        fs::write(&file_path, "one\ntwo\nthree\n").unwrap();
+
+        let review: super::super::review::ReviewChanges =
+            Arc::new(RwLock::new(super::super::review::PendingChanges::default()));
+        let tool = EditLinesTool::new(cwd.clone(), vec![cwd.clone()], None)
+            .with_review(Some(review.clone()));
+
+        let result = tool
+            .call(json!({
+                "file_path": "test.txt",
+                "start_line": 2,
+                "end_line": 2,
+                "new_content": "TWO"
+            }))
+            .await
+            .unwrap();
+        assert!(result["staged"].as_bool().unwrap(), "got: {result}");
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "one\ntwo\nthree\n");
+
+        // A follow-up edit addresses lines in the staged content.
+        let result = tool
+            .call(json!({
+                "file_path": "test.txt",
+                "start_line": 3,
+                "end_line": 3,
+                "new_content": "THREE"
+            }))
+            .await
+            .unwrap();
+        assert!(result["staged"].as_bool().unwrap());
+
+        let staged =
+            super::super::review::staged_content(&Some(review), &file_path.canonicalize().unwrap());
+        assert_eq!(staged.as_deref(), Some("one\nTWO\nTHREE\n"));
+    }
+
+    #[tokio::test]
+    async fn test_edit_lines_out_of_bounds() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("test.txt");
+        fs::write(&file_path, "one\ntwo\n").unwrap();
+
+        let tool = EditLinesTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "test.txt",
+            "start_line": 2,
+            "end_line": 5,
+            "new_content": "x"
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+        assert_eq!(result["context"]["total_lines"], 2);
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "one\ntwo\n");
+    }
+
+    #[tokio::test]
+    async fn test_edit_lines_invalid_range() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("test.txt"), "one\n").unwrap();
+
+        let tool = EditLinesTool::new(cwd.clone(), vec![cwd.clone()], None);
+
+        let result = tool
+            .call(json!({
+                "file_path": "test.txt",
+                "start_line": 0,
+                "end_line": 1,
+                "new_content": "x"
+            }))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+
+        let result = tool
+            .call(json!({
+                "file_path": "test.txt",
+                "start_line": 3,
+                "end_line": 2,
+                "new_content": "x"
+            }))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+    }
+
+    #[tokio::test]
+    async fn test_edit_lines_file_not_found() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+
+        let tool = EditLinesTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "missing.txt",
+            "start_line": 1,
+            "end_line": 1,
+            "new_content": "x"
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert_eq!(result["error_code"], error_codes::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_edit_lines_dry_run_does_not_modify_file() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("test.txt");
+        fs::write(&file_path, "one\ntwo\n").unwrap();
+
+        let tool = EditLinesTool::new(cwd.clone(), vec![cwd.clone()], None).with_dry_run(true);
+        let args = json!({
+            "file_path": "test.txt",
+            "start_line": 1,
+            "end_line": 1,
+            "new_content": "ONE"
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["success"].as_bool().unwrap());
+        assert!(result["dry_run"].as_bool().unwrap());
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "one\ntwo\n");
+    }
+
+    #[tokio::test]
+    async fn test_edit_lines_outside_cwd() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+
+        let tool = EditLinesTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "../outside.txt",
+            "start_line": 1,
+            "end_line": 1,
+            "new_content": "x"
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert_eq!(result["error_code"], error_codes::ACCESS_DENIED);
+    }
+}
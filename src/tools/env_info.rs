@@ -0,0 +1,174 @@
+use async_trait::async_trait;
+use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use tokio::process::Command;
+use tokio::sync::mpsc;
+use tracing::instrument;
+
+use colored::Colorize;
+
+use super::ToolEmitter;
+use crate::agent::AgentEvent;
+
+/// Toolchains probed for versions, in report order.
+const TOOLCHAINS: &[&str] = &["rustc", "cargo", "node", "python3", "go", "git"];
+
+/// Report the execution environment in one structured call.
+///
+/// OS, arch, shell, PATH, toolchain versions, and git identity - the
+/// things the model otherwise discovers with a flurry of `bash --version`
+/// probes at the start of a session.
+pub struct EnvInfoTool {
+    cwd: PathBuf,
+    events_tx: Option<mpsc::Sender<AgentEvent>>,
+}
+
+impl EnvInfoTool {
+    pub fn new(cwd: PathBuf, events_tx: Option<mpsc::Sender<AgentEvent>>) -> Self {
+        Self { cwd, events_tx }
+    }
+
+    /// Run `program args...` and return the first line of stdout (falling
+    /// back to stderr - some tools print versions there), or `None` if the
+    /// program isn't installed or exits nonzero.
+    async fn probe(program: &str, args: &[&str]) -> Option<String> {
+        let output = Command::new(program).args(args).output().await.ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let text = if output.stdout.is_empty() {
+            String::from_utf8_lossy(&output.stderr).to_string()
+        } else {
+            String::from_utf8_lossy(&output.stdout).to_string()
+        };
+        text.lines().next().map(|l| l.trim().to_string())
+    }
+
+    /// Toolchain versions as `{name: "version line" | null}`.
+    async fn toolchain_versions() -> Value {
+        let mut versions = serde_json::Map::new();
+        for program in TOOLCHAINS {
+            versions.insert(
+                program.to_string(),
+                Self::probe(program, &["--version"]).await.into(),
+            );
+        }
+        Value::Object(versions)
+    }
+
+    /// Git identity from the repo at cwd (falls back to global config).
+    async fn git_identity(cwd: &PathBuf) -> Value {
+        let field = |key: &'static str| async move {
+            let output = Command::new("git")
+                .args(["config", "--get", key])
+                .current_dir(cwd)
+                .output()
+                .await
+                .ok()?;
+            if !output.status.success() {
+                return None;
+            }
+            let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            (!value.is_empty()).then_some(value)
+        };
+        json!({
+            "name": field("user.name").await,
+            "email": field("user.email").await,
+        })
+    }
+}
+
+impl ToolEmitter for EnvInfoTool {
+    fn events_tx(&self) -> &Option<mpsc::Sender<AgentEvent>> {
+        &self.events_tx
+    }
+}
+
+#[async_trait]
+impl CallableFunction for EnvInfoTool {
+    fn declaration(&self) -> FunctionDeclaration {
+        FunctionDeclaration::new(
+            "env_info".to_string(),
+            "Report the execution environment: OS, arch, shell, PATH entries, installed toolchain \
+             versions (rustc, node, python3, ...), and git identity. Use this once instead of \
+             probing each tool with bash --version. \
+             Returns: {os, arch, shell, cwd, path, toolchains, git}"
+                .to_string(),
+            FunctionParameters::new("object".to_string(), json!({}), vec![]),
+        )
+    }
+
+    #[instrument(skip(self, _args))]
+    async fn call(&self, _args: Value) -> Result<Value, FunctionError> {
+        let path_entries: Vec<String> = std::env::var("PATH")
+            .unwrap_or_default()
+            .split(':')
+            .filter(|p| !p.is_empty())
+            .map(String::from)
+            .collect();
+
+        let info = json!({
+            "os": std::env::consts::OS,
+            "arch": std::env::consts::ARCH,
+            "shell": std::env::var("SHELL").ok(),
+            "cwd": self.cwd.to_string_lossy(),
+            "path": path_entries,
+            "toolchains": Self::toolchain_versions().await,
+            "git": Self::git_identity(&self.cwd).await,
+        });
+
+        self.emit(
+            &format!("  {} ({})", std::env::consts::OS, std::env::consts::ARCH)
+                .dimmed()
+                .to_string(),
+        );
+
+        Ok(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_env_info_reports_os_and_arch() {
+        let dir = tempdir().unwrap();
+        let tool = EnvInfoTool::new(dir.path().to_path_buf(), None);
+
+        let result = tool.call(json!({})).await.unwrap();
+        assert_eq!(result["os"], std::env::consts::OS);
+        assert_eq!(result["arch"], std::env::consts::ARCH);
+        assert!(result["path"].is_array());
+    }
+
+    #[tokio::test]
+    async fn test_env_info_probes_all_toolchains() {
+        let dir = tempdir().unwrap();
+        let tool = EnvInfoTool::new(dir.path().to_path_buf(), None);
+
+        let result = tool.call(json!({})).await.unwrap();
+        let toolchains = result["toolchains"].as_object().unwrap();
+        // Every probed toolchain appears, present or not (null when missing).
+        for program in TOOLCHAINS {
+            assert!(toolchains.contains_key(*program), "missing {program}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_probe_missing_program_is_none() {
+        let version = EnvInfoTool::probe("definitely-not-a-real-binary-xyz", &["--version"]).await;
+        assert!(version.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_git_identity_outside_repo_uses_global_or_none() {
+        let dir = tempdir().unwrap();
+        let identity = EnvInfoTool::git_identity(&dir.path().to_path_buf()).await;
+        // Fields exist even when unset.
+        assert!(identity.get("name").is_some());
+        assert!(identity.get("email").is_some());
+    }
+}
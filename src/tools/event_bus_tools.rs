@@ -327,7 +327,7 @@ impl CallableFunction for EventBusGetEventsTool {
                     },
                     "channel": {
                         "type": "string",
-                        "description": "Filter to specific channel"
+                        "description": "Filter to a channel, or a wildcard pattern like 'build.*' or 'repo:*'"
                     },
                     "resume": {
                         "type": "boolean",
@@ -337,6 +337,14 @@ impl CallableFunction for EventBusGetEventsTool {
                         "type": "array",
                         "items": { "type": "string" },
                         "description": "Filter by event types (e.g., ['task_completed', 'ci_passed'])"
+                    },
+                    "since_id": {
+                        "type": "integer",
+                        "description": "Only events with ID greater than this, regardless of order - use the cursor from the previous poll to get just new events"
+                    },
+                    "payload_filters": {
+                        "type": "object",
+                        "description": "Only events whose JSON payload has each of these fields equal to the given value (e.g., {\"status\": \"failed\"})"
                     }
                 }),
                 vec![],
@@ -362,6 +370,8 @@ impl CallableFunction for EventBusGetEventsTool {
                     .filter_map(|v| v.as_str().map(String::from))
                     .collect()
             });
+        let since_id = args.get("since_id").and_then(|v| v.as_i64());
+        let payload_filters = args.get("payload_filters").and_then(|v| v.as_object());
 
         let bus = EventBus::open().map_err(|e| {
             FunctionError::ExecutionError(format!("Failed to open event bus: {}", e).into())
@@ -375,6 +385,8 @@ impl CallableFunction for EventBusGetEventsTool {
             channel,
             resume,
             event_types: event_types.as_deref(),
+            since_id,
+            payload_filters,
         };
         let (events, new_cursor) = bus.get_events(&opts).map_err(|e| {
             FunctionError::ExecutionError(format!("Failed to get events: {}", e).into())
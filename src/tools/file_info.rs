@@ -0,0 +1,302 @@
+use async_trait::async_trait;
+use colored::Colorize;
+use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use tokio::sync::mpsc;
+use tracing::instrument;
+
+use super::{ToolEmitter, error_codes, error_response, resolve_and_validate_path};
+use crate::agent::AgentEvent;
+
+/// Stat a file in one structured call: size, mtime, permissions, line
+/// count, detected language, and a content hash.
+///
+/// The hash (stable FNV-1a over the raw bytes, same family the memory and
+/// cache files use) answers "did the build regenerate this artifact?"
+/// without bash `stat`/`md5sum` parsing, and works on binaries the read
+/// tool refuses.
+pub struct FileInfoTool {
+    cwd: PathBuf,
+    allowed_paths: Vec<PathBuf>,
+    events_tx: Option<mpsc::Sender<AgentEvent>>,
+}
+
+impl FileInfoTool {
+    pub fn new(
+        cwd: PathBuf,
+        allowed_paths: Vec<PathBuf>,
+        events_tx: Option<mpsc::Sender<AgentEvent>>,
+    ) -> Self {
+        Self {
+            cwd,
+            allowed_paths,
+            events_tx,
+        }
+    }
+
+    /// Language name by extension. Broader than the outline tool's
+    /// tree-sitter map - this is informational, not a parser selector.
+    fn language_for_path(path: &Path) -> Option<&'static str> {
+        match path.extension()?.to_str()? {
+            "rs" => Some("rust"),
+            "py" | "pyi" => Some("python"),
+            "js" | "jsx" | "mjs" => Some("javascript"),
+            "ts" | "tsx" => Some("typescript"),
+            "go" => Some("go"),
+            "c" | "h" => Some("c"),
+            "cc" | "cpp" | "cxx" | "hpp" => Some("cpp"),
+            "java" => Some("java"),
+            "rb" => Some("ruby"),
+            "sh" | "bash" => Some("shell"),
+            "md" => Some("markdown"),
+            "toml" => Some("toml"),
+            "json" => Some("json"),
+            "yaml" | "yml" => Some("yaml"),
+            "html" => Some("html"),
+            "css" => Some("css"),
+            "sql" => Some("sql"),
+            _ => None,
+        }
+    }
+
+    /// File mode as an octal string (e.g. "644"), unix only.
+    #[cfg(unix)]
+    fn permissions(metadata: &std::fs::Metadata) -> String {
+        use std::os::unix::fs::PermissionsExt;
+        format!("{:o}", metadata.permissions().mode() & 0o7777)
+    }
+
+    #[cfg(not(unix))]
+    fn permissions(metadata: &std::fs::Metadata) -> String {
+        if metadata.permissions().readonly() {
+            "read-only".to_string()
+        } else {
+            "read-write".to_string()
+        }
+    }
+}
+
+impl ToolEmitter for FileInfoTool {
+    fn events_tx(&self) -> &Option<mpsc::Sender<AgentEvent>> {
+        &self.events_tx
+    }
+}
+
+#[async_trait]
+impl CallableFunction for FileInfoTool {
+    fn declaration(&self) -> FunctionDeclaration {
+        FunctionDeclaration::new(
+            "file_info".to_string(),
+            "Stat a file: size, mtime, permissions, line count, detected language, and a stable \
+             content hash. Works on binary files. Compare hashes across calls to tell whether a \
+             build regenerated an artifact. \
+             Returns: {path, size, mtime, permissions, hash?, line_count?, language?, binary?}"
+                .to_string(),
+            FunctionParameters::new(
+                "object".to_string(),
+                json!({
+                    "path": {
+                        "type": "string",
+                        "description": "The file or directory to stat (absolute or relative to cwd)"
+                    }
+                }),
+                vec!["path".to_string()],
+            ),
+        )
+    }
+
+    #[instrument(skip(self, args))]
+    async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        let file_path = args
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing path".to_string()))?;
+
+        let path = match resolve_and_validate_path(file_path, &self.cwd, &self.allowed_paths) {
+            Ok(p) => p,
+            Err(e) => {
+                return Ok(error_response(
+                    &format!("Access denied: {}. Path must be within allowed paths.", e),
+                    error_codes::ACCESS_DENIED,
+                    json!({"path": file_path}),
+                ));
+            }
+        };
+
+        let metadata = match tokio::fs::metadata(&path).await {
+            Ok(m) => m,
+            Err(_) => {
+                return Ok(error_response(
+                    &format!("Path not found: {}", file_path),
+                    error_codes::NOT_FOUND,
+                    json!({"path": file_path}),
+                ));
+            }
+        };
+
+        let mtime = metadata
+            .modified()
+            .ok()
+            .map(|t| chrono::DateTime::<chrono::Local>::from(t).to_rfc3339());
+
+        let mut response = json!({
+            "path": path.display().to_string(),
+            "size": metadata.len(),
+            "mtime": mtime,
+            "permissions": Self::permissions(&metadata),
+            "success": true
+        });
+
+        if metadata.is_dir() {
+            response["is_dir"] = json!(true);
+            if let Ok(mut entries) = tokio::fs::read_dir(&path).await {
+                let mut count = 0u64;
+                while let Ok(Some(_)) = entries.next_entry().await {
+                    count += 1;
+                }
+                response["entries"] = json!(count);
+            }
+            self.emit(&format!("  directory, {} bytes", metadata.len()).dimmed().to_string());
+            return Ok(response);
+        }
+
+        let bytes = match tokio::fs::read(&path).await {
+            Ok(b) => b,
+            Err(e) => {
+                return Ok(error_response(
+                    &format!("Failed to read {}: {}", file_path, e),
+                    error_codes::IO_ERROR,
+                    json!({"path": file_path}),
+                ));
+            }
+        };
+
+        response["hash"] = json!(super::memory::fnv1a_hex_bytes(&bytes));
+        if super::read::is_binary(&bytes) {
+            response["binary"] = json!(true);
+        } else {
+            response["line_count"] = json!(bytes.split(|&b| b == b'\n').count()
+                - usize::from(bytes.last() == Some(&b'\n') || bytes.is_empty()));
+            if let Some(language) = Self::language_for_path(&path) {
+                response["language"] = json!(language);
+            }
+        }
+
+        self.emit(
+            &format!("  {} bytes, {}", metadata.len(), response["hash"].as_str().unwrap_or(""))
+                .dimmed()
+                .to_string(),
+        );
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_file_info_text_file() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("main.rs"), "fn main() {}\nfn helper() {}\n").unwrap();
+
+        let tool = FileInfoTool::new(cwd.clone(), vec![cwd], None);
+        let result = tool.call(json!({"path": "main.rs"})).await.unwrap();
+
+        assert!(result["success"].as_bool().unwrap());
+        assert_eq!(result["size"], 28);
+        assert_eq!(result["line_count"], 2);
+        assert_eq!(result["language"], "rust");
+        assert!(result["binary"].is_null());
+        assert!(result["mtime"].as_str().is_some());
+        assert_eq!(result["hash"].as_str().unwrap().len(), 16);
+    }
+
+    #[tokio::test]
+    async fn test_file_info_hash_tracks_content_changes() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file = cwd.join("artifact.bin");
+        fs::write(&file, "v1").unwrap();
+
+        let tool = FileInfoTool::new(cwd.clone(), vec![cwd], None);
+        let before = tool.call(json!({"path": "artifact.bin"})).await.unwrap();
+        let same = tool.call(json!({"path": "artifact.bin"})).await.unwrap();
+        assert_eq!(before["hash"], same["hash"]);
+
+        fs::write(&file, "v2").unwrap();
+        let after = tool.call(json!({"path": "artifact.bin"})).await.unwrap();
+        assert_ne!(before["hash"], after["hash"]);
+    }
+
+    #[tokio::test]
+    async fn test_file_info_binary_file_skips_line_count() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("blob"), [0u8, 159, 146, 150, 0, 1, 2]).unwrap();
+
+        let tool = FileInfoTool::new(cwd.clone(), vec![cwd], None);
+        let result = tool.call(json!({"path": "blob"})).await.unwrap();
+
+        assert!(result["binary"].as_bool().unwrap());
+        assert!(result["line_count"].is_null());
+        assert!(result["hash"].as_str().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_file_info_directory() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::create_dir(cwd.join("sub")).unwrap();
+        fs::write(cwd.join("sub/a.txt"), "a").unwrap();
+        fs::write(cwd.join("sub/b.txt"), "b").unwrap();
+
+        let tool = FileInfoTool::new(cwd.clone(), vec![cwd], None);
+        let result = tool.call(json!({"path": "sub"})).await.unwrap();
+
+        assert!(result["is_dir"].as_bool().unwrap());
+        assert_eq!(result["entries"], 2);
+        assert!(result["hash"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_file_info_not_found() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+
+        let tool = FileInfoTool::new(cwd.clone(), vec![cwd], None);
+        let result = tool.call(json!({"path": "missing.txt"})).await.unwrap();
+        assert_eq!(result["error_code"], error_codes::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_file_info_outside_sandbox() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+
+        let tool = FileInfoTool::new(cwd.clone(), vec![cwd], None);
+        let result = tool.call(json!({"path": "/etc/passwd"})).await.unwrap();
+        assert_eq!(result["error_code"], error_codes::ACCESS_DENIED);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_file_info_permissions_octal() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file = cwd.join("script.sh");
+        fs::write(&file, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let tool = FileInfoTool::new(cwd.clone(), vec![cwd], None);
+        let result = tool.call(json!({"path": "script.sh"})).await.unwrap();
+        assert_eq!(result["permissions"], "755");
+        assert_eq!(result["language"], "shell");
+    }
+}
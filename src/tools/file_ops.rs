@@ -0,0 +1,679 @@
+//! File management tools: move, copy, delete.
+//!
+//! Renames through `bash mv` bypass path validation and leave no undo
+//! trail. These tools validate source and destination against
+//! `allowed_paths`, and deletions go to a per-session trash directory
+//! (`~/.clemini/trash/<session>/`) instead of being destroyed - the
+//! response reports the trashed path so a `move_file` can restore it.
+
+use async_trait::async_trait;
+use colored::Colorize;
+use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::sync::mpsc;
+use tracing::instrument;
+
+use super::{ToolEmitter, error_codes, error_response, resolve_and_validate_path};
+use crate::agent::AgentEvent;
+
+/// Trash directory for this process, created lazily on first delete.
+static TRASH_DIR: LazyLock<PathBuf> = LazyLock::new(|| {
+    let session = format!(
+        "{}-{}",
+        chrono::Local::now().format("%Y%m%d-%H%M%S"),
+        std::process::id()
+    );
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".clemini")
+        .join("trash")
+        .join(session)
+});
+
+/// Monotonic counter so same-named files trashed twice don't collide.
+static TRASH_COUNTER: AtomicUsize = AtomicUsize::new(1);
+
+/// Move `path` into the session trash. Returns the trashed path.
+fn move_to_trash(path: &Path) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(&*TRASH_DIR)
+        .map_err(|e| format!("Failed to create trash directory: {}", e))?;
+
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "unnamed".to_string());
+    let trashed = TRASH_DIR.join(format!(
+        "{}-{}",
+        TRASH_COUNTER.fetch_add(1, Ordering::SeqCst),
+        file_name
+    ));
+
+    match std::fs::rename(path, &trashed) {
+        Ok(()) => Ok(trashed),
+        // Rename fails across filesystems; fall back to copy+remove for files
+        Err(_) if path.is_file() => {
+            std::fs::copy(path, &trashed)
+                .map_err(|e| format!("Failed to copy {} to trash: {}", path.display(), e))?;
+            std::fs::remove_file(path)
+                .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+            Ok(trashed)
+        }
+        Err(e) => Err(format!(
+            "Failed to move {} to trash: {}",
+            path.display(),
+            e
+        )),
+    }
+}
+
+/// Validate a source path that must exist.
+fn validate_source(
+    file_path: &str,
+    cwd: &Path,
+    allowed_paths: &[PathBuf],
+) -> Result<PathBuf, Value> {
+    let path = resolve_and_validate_path(file_path, cwd, allowed_paths).map_err(|e| {
+        error_response(
+            &format!("Access denied: {}. Path must be within allowed paths.", e),
+            error_codes::ACCESS_DENIED,
+            json!({"path": file_path}),
+        )
+    })?;
+    if !path.exists() {
+        return Err(error_response(
+            &format!("{} does not exist", file_path),
+            error_codes::NOT_FOUND,
+            json!({"path": file_path}),
+        ));
+    }
+    Ok(path)
+}
+
+/// Validate a destination path (need not exist) and create its parent
+/// directories.
+fn validate_destination(
+    file_path: &str,
+    cwd: &Path,
+    allowed_paths: &[PathBuf],
+    overwrite: bool,
+) -> Result<PathBuf, Value> {
+    let path = resolve_and_validate_path(file_path, cwd, allowed_paths).map_err(|e| {
+        error_response(
+            &format!("Access denied: {}. Path must be within allowed paths.", e),
+            error_codes::ACCESS_DENIED,
+            json!({"path": file_path}),
+        )
+    })?;
+    if path.exists() && !overwrite {
+        return Err(error_response(
+            &format!(
+                "{} already exists. Pass overwrite=true to replace it.",
+                file_path
+            ),
+            error_codes::INVALID_ARGUMENT,
+            json!({"path": file_path}),
+        ));
+    }
+    if let Some(parent) = path.parent()
+        && !parent.exists()
+        && let Err(e) = std::fs::create_dir_all(parent)
+    {
+        return Err(error_response(
+            &format!("Failed to create directory {}: {}", parent.display(), e),
+            error_codes::IO_ERROR,
+            json!({"path": parent.display().to_string()}),
+        ));
+    }
+    Ok(path)
+}
+
+// ============================================================================
+// move_file
+// ============================================================================
+
+pub struct MoveFileTool {
+    cwd: PathBuf,
+    allowed_paths: Vec<PathBuf>,
+    events_tx: Option<mpsc::Sender<AgentEvent>>,
+    dry_run: bool,
+}
+
+impl MoveFileTool {
+    pub fn new(
+        cwd: PathBuf,
+        allowed_paths: Vec<PathBuf>,
+        events_tx: Option<mpsc::Sender<AgentEvent>>,
+    ) -> Self {
+        Self {
+            cwd,
+            allowed_paths,
+            events_tx,
+            dry_run: false,
+        }
+    }
+
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+}
+
+impl ToolEmitter for MoveFileTool {
+    fn events_tx(&self) -> &Option<mpsc::Sender<AgentEvent>> {
+        &self.events_tx
+    }
+}
+
+#[async_trait]
+impl CallableFunction for MoveFileTool {
+    fn declaration(&self) -> FunctionDeclaration {
+        FunctionDeclaration::new(
+            "move_file".to_string(),
+            "Move or rename a file or directory. Both paths are validated against allowed paths; parent directories of the destination are created as needed. Returns: {source, destination, success}".to_string(),
+            FunctionParameters::new(
+                "object".to_string(),
+                json!({
+                    "source": {
+                        "type": "string",
+                        "description": "Existing file or directory to move"
+                    },
+                    "destination": {
+                        "type": "string",
+                        "description": "New path"
+                    },
+                    "overwrite": {
+                        "type": "boolean",
+                        "description": "Replace the destination if it already exists. (default: false)"
+                    }
+                }),
+                vec!["source".to_string(), "destination".to_string()],
+            ),
+        )
+    }
+
+    #[instrument(skip(self, args))]
+    async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        let source = args
+            .get("source")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing source".to_string()))?;
+        let destination = args
+            .get("destination")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing destination".to_string()))?;
+        let overwrite = args
+            .get("overwrite")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let source_path = match validate_source(source, &self.cwd, &self.allowed_paths) {
+            Ok(p) => p,
+            Err(e) => return Ok(e),
+        };
+        let dest_path =
+            match validate_destination(destination, &self.cwd, &self.allowed_paths, overwrite) {
+                Ok(p) => p,
+                Err(e) => return Ok(e),
+            };
+
+        if self.dry_run {
+            self.emit(&format!(
+                "  {} {} -> {}",
+                "DRY RUN (not moved):".yellow(),
+                source,
+                destination
+            ));
+            return Ok(json!({
+                "source": source,
+                "destination": destination,
+                "dry_run": true,
+                "success": true
+            }));
+        }
+
+        if let Err(e) = std::fs::rename(&source_path, &dest_path) {
+            return Ok(error_response(
+                &format!("Failed to move {} to {}: {}", source, destination, e),
+                error_codes::IO_ERROR,
+                json!({"source": source, "destination": destination}),
+            ));
+        }
+
+        self.emit(&format!(
+            "  {}\n  {}",
+            format!("- {}", source).red(),
+            format!("+ {}", destination).green()
+        ));
+
+        Ok(json!({
+            "source": source,
+            "destination": destination,
+            "success": true
+        }))
+    }
+}
+
+// ============================================================================
+// copy_file
+// ============================================================================
+
+pub struct CopyFileTool {
+    cwd: PathBuf,
+    allowed_paths: Vec<PathBuf>,
+    events_tx: Option<mpsc::Sender<AgentEvent>>,
+    dry_run: bool,
+}
+
+impl CopyFileTool {
+    pub fn new(
+        cwd: PathBuf,
+        allowed_paths: Vec<PathBuf>,
+        events_tx: Option<mpsc::Sender<AgentEvent>>,
+    ) -> Self {
+        Self {
+            cwd,
+            allowed_paths,
+            events_tx,
+            dry_run: false,
+        }
+    }
+
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+}
+
+impl ToolEmitter for CopyFileTool {
+    fn events_tx(&self) -> &Option<mpsc::Sender<AgentEvent>> {
+        &self.events_tx
+    }
+}
+
+#[async_trait]
+impl CallableFunction for CopyFileTool {
+    fn declaration(&self) -> FunctionDeclaration {
+        FunctionDeclaration::new(
+            "copy_file".to_string(),
+            "Copy a file. Both paths are validated against allowed paths; parent directories of the destination are created as needed. Returns: {source, destination, bytes_copied, success}".to_string(),
+            FunctionParameters::new(
+                "object".to_string(),
+                json!({
+                    "source": {
+                        "type": "string",
+                        "description": "Existing file to copy"
+                    },
+                    "destination": {
+                        "type": "string",
+                        "description": "Path for the copy"
+                    },
+                    "overwrite": {
+                        "type": "boolean",
+                        "description": "Replace the destination if it already exists. (default: false)"
+                    }
+                }),
+                vec!["source".to_string(), "destination".to_string()],
+            ),
+        )
+    }
+
+    #[instrument(skip(self, args))]
+    async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        let source = args
+            .get("source")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing source".to_string()))?;
+        let destination = args
+            .get("destination")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing destination".to_string()))?;
+        let overwrite = args
+            .get("overwrite")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let source_path = match validate_source(source, &self.cwd, &self.allowed_paths) {
+            Ok(p) => p,
+            Err(e) => return Ok(e),
+        };
+        if source_path.is_dir() {
+            return Ok(error_response(
+                &format!("{} is a directory; copy_file handles files only", source),
+                error_codes::INVALID_ARGUMENT,
+                json!({"path": source}),
+            ));
+        }
+        let dest_path =
+            match validate_destination(destination, &self.cwd, &self.allowed_paths, overwrite) {
+                Ok(p) => p,
+                Err(e) => return Ok(e),
+            };
+
+        if self.dry_run {
+            self.emit(&format!(
+                "  {} {} -> {}",
+                "DRY RUN (not copied):".yellow(),
+                source,
+                destination
+            ));
+            return Ok(json!({
+                "source": source,
+                "destination": destination,
+                "dry_run": true,
+                "success": true
+            }));
+        }
+
+        match std::fs::copy(&source_path, &dest_path) {
+            Ok(bytes_copied) => {
+                self.emit(&format!("  {}", format!("+ {}", destination).green()));
+                Ok(json!({
+                    "source": source,
+                    "destination": destination,
+                    "bytes_copied": bytes_copied,
+                    "success": true
+                }))
+            }
+            Err(e) => Ok(error_response(
+                &format!("Failed to copy {} to {}: {}", source, destination, e),
+                error_codes::IO_ERROR,
+                json!({"source": source, "destination": destination}),
+            )),
+        }
+    }
+}
+
+// ============================================================================
+// delete_file
+// ============================================================================
+
+pub struct DeleteFileTool {
+    cwd: PathBuf,
+    allowed_paths: Vec<PathBuf>,
+    events_tx: Option<mpsc::Sender<AgentEvent>>,
+    dry_run: bool,
+}
+
+impl DeleteFileTool {
+    pub fn new(
+        cwd: PathBuf,
+        allowed_paths: Vec<PathBuf>,
+        events_tx: Option<mpsc::Sender<AgentEvent>>,
+    ) -> Self {
+        Self {
+            cwd,
+            allowed_paths,
+            events_tx,
+            dry_run: false,
+        }
+    }
+
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+}
+
+impl ToolEmitter for DeleteFileTool {
+    fn events_tx(&self) -> &Option<mpsc::Sender<AgentEvent>> {
+        &self.events_tx
+    }
+}
+
+#[async_trait]
+impl CallableFunction for DeleteFileTool {
+    fn declaration(&self) -> FunctionDeclaration {
+        FunctionDeclaration::new(
+            "delete_file".to_string(),
+            "Delete a file or directory. Nothing is destroyed: the path is moved to the session trash (~/.clemini/trash/) and the trashed location is returned, so a move_file can restore it. Returns: {path, trashed_to, success}".to_string(),
+            FunctionParameters::new(
+                "object".to_string(),
+                json!({
+                    "file_path": {
+                        "type": "string",
+                        "description": "File or directory to delete"
+                    }
+                }),
+                vec!["file_path".to_string()],
+            ),
+        )
+    }
+
+    #[instrument(skip(self, args))]
+    async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        let file_path = args
+            .get("file_path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing file_path".to_string()))?;
+
+        let path = match validate_source(file_path, &self.cwd, &self.allowed_paths) {
+            Ok(p) => p,
+            Err(e) => return Ok(e),
+        };
+
+        if self.dry_run {
+            self.emit(&format!(
+                "  {} {}",
+                "DRY RUN (not deleted):".yellow(),
+                file_path
+            ));
+            return Ok(json!({
+                "path": file_path,
+                "dry_run": true,
+                "success": true
+            }));
+        }
+
+        match move_to_trash(&path) {
+            Ok(trashed) => {
+                self.emit(&format!(
+                    "  {} {}",
+                    format!("- {}", file_path).red(),
+                    format!("(trashed to {})", trashed.display()).dimmed()
+                ));
+                Ok(json!({
+                    "path": file_path,
+                    "trashed_to": trashed.display().to_string(),
+                    "success": true
+                }))
+            }
+            Err(e) => Ok(error_response(
+                &e,
+                error_codes::IO_ERROR,
+                json!({"path": file_path}),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_move_file_renames() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("a.txt"), "content").unwrap();
+
+        let tool = MoveFileTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let result = tool
+            .call(json!({"source": "a.txt", "destination": "sub/b.txt"}))
+            .await
+            .unwrap();
+
+        assert!(result["success"].as_bool().unwrap(), "got: {result}");
+        assert!(!cwd.join("a.txt").exists());
+        assert_eq!(fs::read_to_string(cwd.join("sub/b.txt")).unwrap(), "content");
+    }
+
+    #[tokio::test]
+    async fn test_move_file_refuses_overwrite_by_default() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("a.txt"), "a").unwrap();
+        fs::write(cwd.join("b.txt"), "b").unwrap();
+
+        let tool = MoveFileTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let result = tool
+            .call(json!({"source": "a.txt", "destination": "b.txt"}))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+
+        // With overwrite=true it goes through
+        let result = tool
+            .call(json!({"source": "a.txt", "destination": "b.txt", "overwrite": true}))
+            .await
+            .unwrap();
+        assert!(result["success"].as_bool().unwrap());
+        assert_eq!(fs::read_to_string(cwd.join("b.txt")).unwrap(), "a");
+    }
+
+    #[tokio::test]
+    async fn test_move_file_validates_destination() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("a.txt"), "a").unwrap();
+
+        let tool = MoveFileTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let result = tool
+            .call(json!({"source": "a.txt", "destination": "../escaped.txt"}))
+            .await
+            .unwrap();
+
+        assert_eq!(result["error_code"], error_codes::ACCESS_DENIED);
+        assert!(cwd.join("a.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_move_file_missing_source() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let tool = MoveFileTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let result = tool
+            .call(json!({"source": "nope.txt", "destination": "b.txt"}))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_copy_file_copies() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("a.txt"), "content").unwrap();
+
+        let tool = CopyFileTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let result = tool
+            .call(json!({"source": "a.txt", "destination": "b.txt"}))
+            .await
+            .unwrap();
+
+        assert!(result["success"].as_bool().unwrap());
+        assert_eq!(result["bytes_copied"], 7);
+        assert_eq!(fs::read_to_string(cwd.join("a.txt")).unwrap(), "content");
+        assert_eq!(fs::read_to_string(cwd.join("b.txt")).unwrap(), "content");
+    }
+
+    #[tokio::test]
+    async fn test_copy_file_rejects_directory() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::create_dir(cwd.join("subdir")).unwrap();
+
+        let tool = CopyFileTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let result = tool
+            .call(json!({"source": "subdir", "destination": "copy"}))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+    }
+
+    #[tokio::test]
+    async fn test_delete_file_goes_to_trash() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("a.txt"), "precious").unwrap();
+
+        let tool = DeleteFileTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let result = tool.call(json!({"file_path": "a.txt"})).await.unwrap();
+
+        assert!(result["success"].as_bool().unwrap(), "got: {result}");
+        assert!(!cwd.join("a.txt").exists());
+
+        // Content survives in the trash for undo
+        let trashed = PathBuf::from(result["trashed_to"].as_str().unwrap());
+        assert_eq!(fs::read_to_string(&trashed).unwrap(), "precious");
+        fs::remove_file(&trashed).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_delete_directory_goes_to_trash() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::create_dir(cwd.join("subdir")).unwrap();
+        fs::write(cwd.join("subdir/inner.txt"), "inner").unwrap();
+
+        let tool = DeleteFileTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let result = tool.call(json!({"file_path": "subdir"})).await.unwrap();
+
+        assert!(result["success"].as_bool().unwrap(), "got: {result}");
+        assert!(!cwd.join("subdir").exists());
+
+        let trashed = PathBuf::from(result["trashed_to"].as_str().unwrap());
+        assert_eq!(
+            fs::read_to_string(trashed.join("inner.txt")).unwrap(),
+            "inner"
+        );
+        fs::remove_dir_all(&trashed).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_delete_same_name_twice_no_collision() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let tool = DeleteFileTool::new(cwd.clone(), vec![cwd.clone()], None);
+
+        fs::write(cwd.join("a.txt"), "first").unwrap();
+        let first = tool.call(json!({"file_path": "a.txt"})).await.unwrap();
+        fs::write(cwd.join("a.txt"), "second").unwrap();
+        let second = tool.call(json!({"file_path": "a.txt"})).await.unwrap();
+
+        let first_path = PathBuf::from(first["trashed_to"].as_str().unwrap());
+        let second_path = PathBuf::from(second["trashed_to"].as_str().unwrap());
+        assert_ne!(first_path, second_path);
+        assert_eq!(fs::read_to_string(&first_path).unwrap(), "first");
+        assert_eq!(fs::read_to_string(&second_path).unwrap(), "second");
+        fs::remove_file(first_path).unwrap();
+        fs::remove_file(second_path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_touches_nothing() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("a.txt"), "content").unwrap();
+
+        let move_tool =
+            MoveFileTool::new(cwd.clone(), vec![cwd.clone()], None).with_dry_run(true);
+        let result = move_tool
+            .call(json!({"source": "a.txt", "destination": "b.txt"}))
+            .await
+            .unwrap();
+        assert!(result["dry_run"].as_bool().unwrap());
+        assert!(cwd.join("a.txt").exists());
+        assert!(!cwd.join("b.txt").exists());
+
+        let delete_tool =
+            DeleteFileTool::new(cwd.clone(), vec![cwd.clone()], None).with_dry_run(true);
+        let result = delete_tool.call(json!({"file_path": "a.txt"})).await.unwrap();
+        assert!(result["dry_run"].as_bool().unwrap());
+        assert!(cwd.join("a.txt").exists());
+    }
+}
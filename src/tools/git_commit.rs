@@ -0,0 +1,423 @@
+use crate::agent::AgentEvent;
+use crate::tools::{
+    MAX_TOOL_OUTPUT_LEN, ToolEmitter, error_codes, error_response, resolve_and_validate_path,
+};
+use async_trait::async_trait;
+use colored::Colorize;
+use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+use tokio::sync::mpsc;
+use tracing::instrument;
+
+/// Default model for commit message generation.
+const DEFAULT_MESSAGE_MODEL: &str = "gemini-3-flash-preview";
+
+/// Trailer appended to every commit so clemini-authored commits are
+/// identifiable in history.
+const CO_AUTHOR_TRAILER: &str = "Co-Authored-By: clemini <noreply@clemini.dev>";
+
+pub struct GitCommitTool {
+    cwd: PathBuf,
+    allowed_paths: Vec<PathBuf>,
+    api_key: String,
+    events_tx: Option<mpsc::Sender<AgentEvent>>,
+    model: Option<String>,
+    dry_run: bool,
+}
+
+impl ToolEmitter for GitCommitTool {
+    fn events_tx(&self) -> &Option<mpsc::Sender<AgentEvent>> {
+        &self.events_tx
+    }
+}
+
+impl GitCommitTool {
+    pub fn new(
+        cwd: PathBuf,
+        allowed_paths: Vec<PathBuf>,
+        api_key: String,
+        events_tx: Option<mpsc::Sender<AgentEvent>>,
+    ) -> Self {
+        Self {
+            cwd,
+            allowed_paths,
+            api_key,
+            events_tx,
+            model: None,
+            dry_run: false,
+        }
+    }
+
+    /// Override the model used for commit message generation.
+    /// `None` keeps the built-in default.
+    pub fn with_model(mut self, model: Option<String>) -> Self {
+        self.model = model;
+        self
+    }
+
+    /// In dry-run mode files are staged and the message is composed, but no
+    /// commit is created.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Model used for commit message generation.
+    fn message_model(&self) -> &str {
+        self.model.as_deref().unwrap_or(DEFAULT_MESSAGE_MODEL)
+    }
+
+    /// Generate a conventional-commit message from the staged diff.
+    async fn generate_message(&self, diff: &str) -> Result<String, String> {
+        let mut diff = diff.to_string();
+        if diff.len() > MAX_TOOL_OUTPUT_LEN {
+            let mut end = MAX_TOOL_OUTPUT_LEN;
+            while end > 0 && !diff.is_char_boundary(end) {
+                end -= 1;
+            }
+            diff.truncate(end);
+            diff.push_str("\n[diff truncated]");
+        }
+
+        let client = genai_rs::Client::new(self.api_key.clone());
+        let result = client
+            .interaction()
+            .with_model(self.message_model())
+            .with_system_instruction(
+                "You write git commit messages in the Conventional Commits style \
+                 (type(scope): subject). The subject line is at most 72 characters, \
+                 imperative mood, no trailing period. Add a short body paragraph only \
+                 when the diff warrants explanation. Respond with the commit message \
+                 text only - no code fences, no commentary.",
+            )
+            .with_content(vec![genai_rs::Content::text(format!(
+                "Staged diff:\n---\n{}\n---",
+                diff
+            ))])
+            .create()
+            .await;
+
+        match result {
+            Ok(response) => {
+                let message = response.as_text().unwrap_or_default().trim().to_string();
+                if message.is_empty() {
+                    Err("Model returned an empty commit message".to_string())
+                } else {
+                    Ok(message)
+                }
+            }
+            Err(e) => Err(format!("Message generation failed: {}", e)),
+        }
+    }
+}
+
+/// Run a git subcommand in `cwd`, returning (stdout, stderr, success).
+async fn run_git(cwd: &Path, args: &[&str]) -> Result<(String, String, bool), String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    Ok((
+        String::from_utf8_lossy(&output.stdout).to_string(),
+        String::from_utf8_lossy(&output.stderr).to_string(),
+        output.status.success(),
+    ))
+}
+
+#[async_trait]
+impl CallableFunction for GitCommitTool {
+    fn declaration(&self) -> FunctionDeclaration {
+        FunctionDeclaration::new(
+            "git_commit".to_string(),
+            "Create a git commit. Stages the given files (if any), uses the provided commit message or generates a conventional-commit message from the staged diff, and appends a Co-Authored-By: clemini trailer. Returns: {sha, message, files_committed, success}".to_string(),
+            FunctionParameters::new(
+                "object".to_string(),
+                json!({
+                    "message": {
+                        "type": "string",
+                        "description": "Commit message. When omitted, one is generated from the staged diff in Conventional Commits style."
+                    },
+                    "include_files": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "Files to stage before committing. When omitted, commits whatever is already staged."
+                    }
+                }),
+                vec![],
+            ),
+        )
+    }
+
+    #[instrument(skip(self, args))]
+    async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        let message = args
+            .get("message")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        let include_files: Vec<String> = args
+            .get("include_files")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // Stage the requested files (validated against allowed paths)
+        for file in &include_files {
+            let path = match resolve_and_validate_path(file, &self.cwd, &self.allowed_paths) {
+                Ok(p) => p,
+                Err(e) => {
+                    return Ok(error_response(
+                        &e,
+                        error_codes::ACCESS_DENIED,
+                        json!({ "file_path": file }),
+                    ));
+                }
+            };
+            match run_git(&self.cwd, &["add", "--", &path.to_string_lossy()]).await {
+                Ok((_, _, true)) => {}
+                Ok((_, stderr, false)) => {
+                    return Ok(error_response(
+                        &format!("Failed to stage {}: {}", file, stderr.trim()),
+                        error_codes::IO_ERROR,
+                        json!({ "file_path": file }),
+                    ));
+                }
+                Err(e) => {
+                    return Ok(error_response(
+                        &e,
+                        error_codes::IO_ERROR,
+                        json!({ "file_path": file }),
+                    ));
+                }
+            }
+        }
+
+        // What's staged, both as a diff (for message generation) and as a
+        // file list (for the response)
+        let (staged_files, _, _) = match run_git(&self.cwd, &["diff", "--cached", "--name-only"])
+            .await
+        {
+            Ok(result) => result,
+            Err(e) => return Ok(error_response(&e, error_codes::IO_ERROR, json!({}))),
+        };
+        let staged_files: Vec<String> = staged_files.lines().map(|l| l.to_string()).collect();
+        if staged_files.is_empty() {
+            return Ok(error_response(
+                "Nothing staged to commit. Pass include_files or stage changes first.",
+                error_codes::INVALID_ARGUMENT,
+                json!({}),
+            ));
+        }
+
+        let message = match message {
+            Some(message) => message,
+            None => {
+                let (diff, _, _) = match run_git(&self.cwd, &["diff", "--cached"]).await {
+                    Ok(result) => result,
+                    Err(e) => return Ok(error_response(&e, error_codes::IO_ERROR, json!({}))),
+                };
+                match self.generate_message(&diff).await {
+                    Ok(message) => message,
+                    Err(e) => {
+                        return Ok(error_response(
+                            &format!("{}. Provide an explicit message instead.", e),
+                            error_codes::IO_ERROR,
+                            json!({ "staged_files": staged_files }),
+                        ));
+                    }
+                }
+            }
+        };
+
+        // Append the co-author trailer (unless the message already has one)
+        let full_message = if message.contains("Co-Authored-By:") {
+            message
+        } else {
+            format!("{}\n\n{}", message.trim_end(), CO_AUTHOR_TRAILER)
+        };
+
+        if self.dry_run {
+            let msg = format!("  {} git commit", "DRY RUN (not committed):".yellow());
+            self.emit(&msg);
+            return Ok(json!({
+                "message": full_message,
+                "files_committed": staged_files,
+                "dry_run": true,
+                "success": true,
+                "note": "Dry-run mode: no commit was created."
+            }));
+        }
+
+        match run_git(&self.cwd, &["commit", "-m", &full_message]).await {
+            Ok((_, _, true)) => {}
+            Ok((stdout, stderr, false)) => {
+                return Ok(error_response(
+                    &format!("git commit failed: {}", {
+                        let err = stderr.trim();
+                        if err.is_empty() { stdout.trim() } else { err }
+                    }),
+                    error_codes::IO_ERROR,
+                    json!({ "staged_files": staged_files }),
+                ));
+            }
+            Err(e) => return Ok(error_response(&e, error_codes::IO_ERROR, json!({}))),
+        }
+
+        let sha = match run_git(&self.cwd, &["rev-parse", "HEAD"]).await {
+            Ok((stdout, _, true)) => stdout.trim().to_string(),
+            _ => String::new(),
+        };
+
+        let subject = full_message.lines().next().unwrap_or_default();
+        self.emit(&format!(
+            "  {}",
+            format!("{} {}", &sha[..sha.len().min(8)], subject).dimmed()
+        ));
+
+        Ok(json!({
+            "sha": sha,
+            "message": full_message,
+            "files_committed": staged_files,
+            "success": true
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    async fn init_repo(dir: &Path) {
+        for args in [
+            vec!["init"],
+            vec!["config", "user.email", "test@example.com"],
+            vec!["config", "user.name", "Test"],
+        ] {
+            let (_, stderr, ok) = run_git(dir, &args).await.unwrap();
+            assert!(ok, "git {:?} failed: {}", args, stderr);
+        }
+    }
+
+    fn tool_for(dir: &Path) -> GitCommitTool {
+        GitCommitTool::new(
+            dir.to_path_buf(),
+            vec![dir.to_path_buf()],
+            "test-key".to_string(),
+            None,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_git_commit_with_message_and_files() {
+        let dir = tempdir().unwrap();
+        let dir = std::fs::canonicalize(dir.path()).unwrap();
+        init_repo(&dir).await;
+        std::fs::write(dir.join("a.txt"), "hello\n").unwrap();
+
+        let tool = tool_for(&dir);
+        let result = tool
+            .call(json!({
+                "message": "feat: add a.txt",
+                "include_files": ["a.txt"]
+            }))
+            .await
+            .unwrap();
+
+        assert!(result["success"].as_bool().unwrap(), "got: {result}");
+        let sha = result["sha"].as_str().unwrap();
+        assert_eq!(sha.len(), 40);
+        assert_eq!(result["files_committed"], json!(["a.txt"]));
+
+        // The trailer landed in the actual commit
+        let (body, _, _) = run_git(&dir, &["log", "-1", "--format=%B"]).await.unwrap();
+        assert!(body.contains("feat: add a.txt"));
+        assert!(body.contains(CO_AUTHOR_TRAILER), "body: {body}");
+    }
+
+    #[tokio::test]
+    async fn test_git_commit_does_not_duplicate_trailer() {
+        let dir = tempdir().unwrap();
+        let dir = std::fs::canonicalize(dir.path()).unwrap();
+        init_repo(&dir).await;
+        std::fs::write(dir.join("a.txt"), "hello\n").unwrap();
+
+        let tool = tool_for(&dir);
+        let message = format!("fix: thing\n\n{}", CO_AUTHOR_TRAILER);
+        let result = tool
+            .call(json!({ "message": message, "include_files": ["a.txt"] }))
+            .await
+            .unwrap();
+
+        assert!(result["success"].as_bool().unwrap());
+        let (body, _, _) = run_git(&dir, &["log", "-1", "--format=%B"]).await.unwrap();
+        assert_eq!(body.matches("Co-Authored-By:").count(), 1, "body: {body}");
+    }
+
+    #[tokio::test]
+    async fn test_git_commit_nothing_staged() {
+        let dir = tempdir().unwrap();
+        let dir = std::fs::canonicalize(dir.path()).unwrap();
+        init_repo(&dir).await;
+
+        let tool = tool_for(&dir);
+        let result = tool.call(json!({ "message": "feat: nothing" })).await.unwrap();
+
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+    }
+
+    #[tokio::test]
+    async fn test_git_commit_path_outside_allowed() {
+        let dir = tempdir().unwrap();
+        let dir = std::fs::canonicalize(dir.path()).unwrap();
+        init_repo(&dir).await;
+
+        let tool = tool_for(&dir);
+        let result = tool
+            .call(json!({ "message": "feat: sneaky", "include_files": ["/etc/passwd"] }))
+            .await
+            .unwrap();
+
+        assert_eq!(result["error_code"], error_codes::ACCESS_DENIED);
+    }
+
+    #[tokio::test]
+    async fn test_git_commit_dry_run() {
+        let dir = tempdir().unwrap();
+        let dir = std::fs::canonicalize(dir.path()).unwrap();
+        init_repo(&dir).await;
+        std::fs::write(dir.join("a.txt"), "hello\n").unwrap();
+
+        let tool = tool_for(&dir).with_dry_run(true);
+        let result = tool
+            .call(json!({ "message": "feat: preview", "include_files": ["a.txt"] }))
+            .await
+            .unwrap();
+
+        assert!(result["dry_run"].as_bool().unwrap());
+        assert!(result["message"].as_str().unwrap().contains(CO_AUTHOR_TRAILER));
+
+        // No commit was created
+        let (_, _, ok) = run_git(&dir, &["rev-parse", "HEAD"]).await.unwrap();
+        assert!(!ok, "HEAD should not exist yet");
+    }
+
+    #[test]
+    fn test_message_model_defaults_and_overrides() {
+        let dir = PathBuf::from("/tmp");
+        let tool = GitCommitTool::new(dir.clone(), vec![], "k".to_string(), None);
+        assert_eq!(tool.message_model(), DEFAULT_MESSAGE_MODEL);
+
+        let tool = GitCommitTool::new(dir, vec![], "k".to_string(), None)
+            .with_model(Some("gemini-flash-lite-latest".to_string()));
+        assert_eq!(tool.message_model(), "gemini-flash-lite-latest");
+    }
+}
@@ -0,0 +1,422 @@
+use crate::agent::AgentEvent;
+use crate::tools::{ToolEmitter, error_codes, error_response};
+use async_trait::async_trait;
+use colored::Colorize;
+use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+use tokio::sync::mpsc;
+use tracing::instrument;
+
+/// JSON fields requested from `gh issue view`.
+const ISSUE_FIELDS: &str = "number,title,state,author,body,labels,url,comments";
+
+/// JSON fields requested from `gh pr view`.
+const PR_FIELDS: &str = "number,title,state,author,body,url,baseRefName,headRefName,isDraft,mergeable,reviews";
+
+pub struct GitHubTool {
+    cwd: PathBuf,
+    events_tx: Option<mpsc::Sender<AgentEvent>>,
+    dry_run: bool,
+}
+
+impl ToolEmitter for GitHubTool {
+    fn events_tx(&self) -> &Option<mpsc::Sender<AgentEvent>> {
+        &self.events_tx
+    }
+}
+
+enum GhRunError {
+    /// The `gh` binary isn't on PATH.
+    NotInstalled,
+    /// Spawning failed for some other reason.
+    Io(String),
+}
+
+/// Run a `gh` subcommand in `cwd`, returning (stdout, stderr, success).
+async fn run_gh(cwd: &Path, args: &[&str]) -> Result<(String, String, bool), GhRunError> {
+    run_program(cwd, "gh", args).await
+}
+
+async fn run_program(
+    cwd: &Path,
+    program: &str,
+    args: &[&str],
+) -> Result<(String, String, bool), GhRunError> {
+    let output = Command::new(program)
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .await
+        .map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                GhRunError::NotInstalled
+            } else {
+                GhRunError::Io(format!("Failed to run {}: {}", program, e))
+            }
+        })?;
+    Ok((
+        String::from_utf8_lossy(&output.stdout).to_string(),
+        String::from_utf8_lossy(&output.stderr).to_string(),
+        output.status.success(),
+    ))
+}
+
+/// Map a failed `gh` invocation to an actionable error response.
+fn gh_failure_response(operation: &str, stderr: &str) -> Value {
+    let stderr = stderr.trim();
+    if stderr.contains("gh auth login") || stderr.contains("authentication") {
+        error_response(
+            "Not authenticated with GitHub. Run `gh auth login` in a terminal first.",
+            error_codes::ACCESS_DENIED,
+            json!({ "operation": operation, "stderr": stderr }),
+        )
+    } else {
+        error_response(
+            &format!("gh {} failed: {}", operation, stderr),
+            error_codes::IO_ERROR,
+            json!({ "operation": operation }),
+        )
+    }
+}
+
+fn not_installed_response() -> Value {
+    error_response(
+        "GitHub CLI (gh) is not installed. Install it from https://cli.github.com/ and run `gh auth login`.",
+        error_codes::NOT_FOUND,
+        json!({}),
+    )
+}
+
+impl GitHubTool {
+    pub fn new(cwd: PathBuf, events_tx: Option<mpsc::Sender<AgentEvent>>) -> Self {
+        Self {
+            cwd,
+            events_tx,
+            dry_run: false,
+        }
+    }
+
+    /// In dry-run mode, mutating operations (`pr_create`, comments) report
+    /// the command they would run without executing it. Read-only views
+    /// still execute.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Run a view operation and parse its `--json` output.
+    async fn view(&self, operation: &str, args: &[&str]) -> Value {
+        match run_gh(&self.cwd, args).await {
+            Ok((stdout, _, true)) => match serde_json::from_str::<Value>(&stdout) {
+                Ok(parsed) => json!({ "operation": operation, "data": parsed, "success": true }),
+                // gh emitted something unparsable - pass it through rather
+                // than failing
+                Err(_) => json!({
+                    "operation": operation,
+                    "raw": stdout,
+                    "success": true
+                }),
+            },
+            Ok((_, stderr, false)) => gh_failure_response(operation, &stderr),
+            Err(GhRunError::NotInstalled) => not_installed_response(),
+            Err(GhRunError::Io(e)) => error_response(&e, error_codes::IO_ERROR, json!({})),
+        }
+    }
+
+    /// Run a mutating operation. `gh` prints the resulting URL on stdout for
+    /// all of these.
+    async fn mutate(&self, operation: &str, args: Vec<String>) -> Value {
+        if self.dry_run {
+            let msg = format!("  {} gh {}", "DRY RUN (not executed):".yellow(), args.join(" "));
+            self.emit(&msg);
+            return json!({
+                "operation": operation,
+                "dry_run": true,
+                "success": true,
+                "note": format!("Dry-run mode: would run `gh {}`.", args.join(" "))
+            });
+        }
+
+        let arg_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        match run_gh(&self.cwd, &arg_refs).await {
+            Ok((stdout, _, true)) => {
+                let url = stdout.trim().to_string();
+                self.emit(&format!("  {}", url.dimmed()));
+                json!({ "operation": operation, "url": url, "success": true })
+            }
+            Ok((_, stderr, false)) => gh_failure_response(operation, &stderr),
+            Err(GhRunError::NotInstalled) => not_installed_response(),
+            Err(GhRunError::Io(e)) => error_response(&e, error_codes::IO_ERROR, json!({})),
+        }
+    }
+}
+
+#[async_trait]
+impl CallableFunction for GitHubTool {
+    fn declaration(&self) -> FunctionDeclaration {
+        FunctionDeclaration::new(
+            "github".to_string(),
+            "GitHub operations via the gh CLI: view issues and PRs as structured JSON, create PRs, and comment. Returns: {operation, data|url, success}".to_string(),
+            FunctionParameters::new(
+                "object".to_string(),
+                json!({
+                    "operation": {
+                        "type": "string",
+                        "description": "One of: issue_view, pr_view, pr_create, pr_comment, issue_comment"
+                    },
+                    "number": {
+                        "type": "integer",
+                        "description": "Issue or PR number (required for all operations except pr_create)"
+                    },
+                    "title": {
+                        "type": "string",
+                        "description": "PR title (pr_create only)"
+                    },
+                    "body": {
+                        "type": "string",
+                        "description": "PR/comment body (pr_create, pr_comment, issue_comment)"
+                    },
+                    "base": {
+                        "type": "string",
+                        "description": "Base branch for pr_create. (default: the repo's default branch)"
+                    },
+                    "draft": {
+                        "type": "boolean",
+                        "description": "Create the PR as a draft (pr_create only). (default: false)"
+                    },
+                    "repo": {
+                        "type": "string",
+                        "description": "Target repository as owner/name. (default: the repo of cwd)"
+                    }
+                }),
+                vec!["operation".to_string()],
+            ),
+        )
+    }
+
+    #[instrument(skip(self, args))]
+    async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        let operation = args
+            .get("operation")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing operation".to_string()))?;
+
+        let number = args.get("number").and_then(|v| v.as_u64());
+        let title = args.get("title").and_then(|v| v.as_str());
+        let body = args.get("body").and_then(|v| v.as_str());
+        let base = args.get("base").and_then(|v| v.as_str());
+        let draft = args.get("draft").and_then(|v| v.as_bool()).unwrap_or(false);
+        let repo = args.get("repo").and_then(|v| v.as_str());
+
+        // Everything except pr_create targets an existing issue/PR
+        let require_number = || {
+            number.map(|n| n.to_string()).ok_or_else(|| {
+                error_response(
+                    &format!("'{}' requires a number", operation),
+                    error_codes::INVALID_ARGUMENT,
+                    json!({ "operation": operation }),
+                )
+            })
+        };
+        let require_body = || {
+            body.ok_or_else(|| {
+                error_response(
+                    &format!("'{}' requires a body", operation),
+                    error_codes::INVALID_ARGUMENT,
+                    json!({ "operation": operation }),
+                )
+            })
+        };
+
+        let repo_args = |mut args: Vec<String>| {
+            if let Some(repo) = repo {
+                args.push("-R".to_string());
+                args.push(repo.to_string());
+            }
+            args
+        };
+
+        let result = match operation {
+            "issue_view" => {
+                let number = match require_number() {
+                    Ok(n) => n,
+                    Err(e) => return Ok(e),
+                };
+                let args = repo_args(vec![
+                    "issue".to_string(),
+                    "view".to_string(),
+                    number,
+                    "--json".to_string(),
+                    ISSUE_FIELDS.to_string(),
+                ]);
+                let arg_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+                self.view(operation, &arg_refs).await
+            }
+            "pr_view" => {
+                let number = match require_number() {
+                    Ok(n) => n,
+                    Err(e) => return Ok(e),
+                };
+                let args = repo_args(vec![
+                    "pr".to_string(),
+                    "view".to_string(),
+                    number,
+                    "--json".to_string(),
+                    PR_FIELDS.to_string(),
+                ]);
+                let arg_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+                self.view(operation, &arg_refs).await
+            }
+            "pr_create" => {
+                let Some(title) = title else {
+                    return Ok(error_response(
+                        "'pr_create' requires a title",
+                        error_codes::INVALID_ARGUMENT,
+                        json!({ "operation": operation }),
+                    ));
+                };
+                let body = match require_body() {
+                    Ok(b) => b,
+                    Err(e) => return Ok(e),
+                };
+                let mut gh_args = vec![
+                    "pr".to_string(),
+                    "create".to_string(),
+                    "--title".to_string(),
+                    title.to_string(),
+                    "--body".to_string(),
+                    body.to_string(),
+                ];
+                if let Some(base) = base {
+                    gh_args.push("--base".to_string());
+                    gh_args.push(base.to_string());
+                }
+                if draft {
+                    gh_args.push("--draft".to_string());
+                }
+                self.mutate(operation, repo_args(gh_args)).await
+            }
+            "pr_comment" | "issue_comment" => {
+                let number = match require_number() {
+                    Ok(n) => n,
+                    Err(e) => return Ok(e),
+                };
+                let body = match require_body() {
+                    Ok(b) => b,
+                    Err(e) => return Ok(e),
+                };
+                let subcommand = if operation == "pr_comment" { "pr" } else { "issue" };
+                let gh_args = repo_args(vec![
+                    subcommand.to_string(),
+                    "comment".to_string(),
+                    number,
+                    "--body".to_string(),
+                    body.to_string(),
+                ]);
+                self.mutate(operation, gh_args).await
+            }
+            other => error_response(
+                &format!(
+                    "Unknown operation '{}': use issue_view, pr_view, pr_create, pr_comment, or issue_comment",
+                    other
+                ),
+                error_codes::INVALID_ARGUMENT,
+                json!({ "operation": other }),
+            ),
+        };
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn tool() -> GitHubTool {
+        GitHubTool::new(PathBuf::from("/tmp"), None)
+    }
+
+    #[test]
+    fn test_declaration() {
+        let decl = tool().declaration();
+        let value = serde_json::to_value(&decl).unwrap();
+        assert_eq!(value["name"], "github");
+        assert!(
+            value["description"].as_str().unwrap().contains("gh CLI"),
+            "description: {}",
+            value["description"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unknown_operation() {
+        let result = tool().call(json!({ "operation": "repo_delete" })).await.unwrap();
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+        assert!(result["error"].as_str().unwrap().contains("issue_view"));
+    }
+
+    #[tokio::test]
+    async fn test_missing_operation() {
+        let result = tool().call(json!({})).await;
+        assert!(matches!(result, Err(FunctionError::ArgumentMismatch(_))));
+    }
+
+    #[tokio::test]
+    async fn test_view_requires_number() {
+        let result = tool().call(json!({ "operation": "issue_view" })).await.unwrap();
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+        assert!(result["error"].as_str().unwrap().contains("number"));
+    }
+
+    #[tokio::test]
+    async fn test_comment_requires_body() {
+        let result = tool()
+            .call(json!({ "operation": "pr_comment", "number": 42 }))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+        assert!(result["error"].as_str().unwrap().contains("body"));
+    }
+
+    #[tokio::test]
+    async fn test_pr_create_requires_title() {
+        let result = tool()
+            .call(json!({ "operation": "pr_create", "body": "text" }))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+        assert!(result["error"].as_str().unwrap().contains("title"));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_skips_mutation() {
+        let dir = tempdir().unwrap();
+        let tool = GitHubTool::new(dir.path().to_path_buf(), None).with_dry_run(true);
+        let result = tool
+            .call(json!({
+                "operation": "pr_comment",
+                "number": 7,
+                "body": "looks good"
+            }))
+            .await
+            .unwrap();
+
+        assert!(result["dry_run"].as_bool().unwrap());
+        assert!(
+            result["note"].as_str().unwrap().contains("pr comment 7"),
+            "note: {}",
+            result["note"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missing_binary_maps_to_not_installed() {
+        let result = run_program(Path::new("/tmp"), "gh-definitely-not-installed", &["--version"])
+            .await;
+        assert!(matches!(result, Err(GhRunError::NotInstalled)));
+    }
+}
@@ -39,6 +39,26 @@ impl ToolEmitter for GlobTool {
     }
 }
 
+/// Parse a human duration like "2d", "3h", "30m", "45s", or "1w".
+/// A bare number is seconds.
+fn parse_duration(s: &str) -> Option<std::time::Duration> {
+    let s = s.trim();
+    let (value, unit) = match s.find(|c: char| !c.is_ascii_digit()) {
+        Some(pos) => (&s[..pos], &s[pos..]),
+        None => (s, "s"),
+    };
+    let value: u64 = value.parse().ok()?;
+    let seconds = match unit {
+        "s" => value,
+        "m" => value * 60,
+        "h" => value * 3600,
+        "d" => value * 86400,
+        "w" => value * 604800,
+        _ => return None,
+    };
+    Some(std::time::Duration::from_secs(seconds))
+}
+
 #[async_trait]
 impl CallableFunction for GlobTool {
     fn declaration(&self) -> FunctionDeclaration {
@@ -61,6 +81,14 @@ impl CallableFunction for GlobTool {
                         "description": "How to sort results: 'name' (alphabetical), 'modified' (newest first), 'size' (largest first). (default: 'name')",
                         "enum": ["name", "modified", "size"]
                     },
+                    "modified_within": {
+                        "type": "string",
+                        "description": "Only include files modified within this duration (e.g., '2d', '3h', '30m'). Combine with sort: 'modified' to find recently changed files."
+                    },
+                    "details": {
+                        "type": "boolean",
+                        "description": "If true, return matches as objects with 'path', 'size' (bytes), and 'modified' (RFC 3339) instead of plain paths. (default: false)"
+                    },
                     "head_limit": {
                         "type": "integer",
                         "description": "Maximum number of results to return from the final list (applied after sorting). (default: no limit)"
@@ -84,6 +112,26 @@ impl CallableFunction for GlobTool {
 
         let search_path = args.get("directory").and_then(|v| v.as_str());
         let sort_by = args.get("sort").and_then(|v| v.as_str()).unwrap_or("name");
+        let details = args
+            .get("details")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let modified_cutoff = match args.get("modified_within").and_then(|v| v.as_str()) {
+            Some(spec) => match parse_duration(spec) {
+                Some(duration) => Some(std::time::SystemTime::now() - duration),
+                None => {
+                    return Ok(error_response(
+                        &format!(
+                            "Invalid duration '{}'. Use a number with s/m/h/d/w suffix, e.g. '2d'.",
+                            spec
+                        ),
+                        error_codes::INVALID_ARGUMENT,
+                        json!({"modified_within": spec}),
+                    ));
+                }
+            },
+            None => None,
+        };
         let head_limit = args.get("head_limit").and_then(|v| v.as_u64());
         let offset = args.get("offset").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
 
@@ -152,6 +200,11 @@ impl CallableFunction for GlobTool {
                             let modified = metadata
                                 .modified()
                                 .unwrap_or(std::time::SystemTime::UNIX_EPOCH);
+                            if let Some(cutoff) = modified_cutoff
+                                && modified < cutoff
+                            {
+                                continue;
+                            }
                             let size = metadata.len();
                             matches.push((relative, modified, size));
                         }
@@ -167,7 +220,21 @@ impl CallableFunction for GlobTool {
                     _ => matches.sort_by(|a, b| a.0.cmp(&b.0)),          // Name alphabetical
                 }
 
-                let mut matches: Vec<String> = matches.into_iter().map(|m| m.0).collect();
+                let mut matches: Vec<Value> = matches
+                    .into_iter()
+                    .map(|(path, modified, size)| {
+                        if details {
+                            json!({
+                                "path": path,
+                                "size": size,
+                                "modified": chrono::DateTime::<chrono::Utc>::from(modified)
+                                    .to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+                            })
+                        } else {
+                            json!(path)
+                        }
+                    })
+                    .collect();
                 let total_found = matches.len();
 
                 if offset > 0 {
@@ -196,8 +263,12 @@ impl CallableFunction for GlobTool {
                         ));
                     }
 
+                    let mut error_msg = format!("No files matched pattern '{}'", pattern);
+                    if let Some(spec) = args.get("modified_within").and_then(|v| v.as_str()) {
+                        error_msg.push_str(&format!(" modified within '{}'", spec));
+                    }
                     return Ok(error_response(
-                        &format!("No files matched pattern '{}'", pattern),
+                        &error_msg,
                         error_codes::NOT_FOUND,
                         json!({"pattern": pattern}),
                     ));
@@ -379,6 +450,75 @@ mod tests {
         assert_eq!(matches, vec!["b.txt", "c.txt", "a.txt"]);
     }
 
+    #[test]
+    fn test_parse_duration() {
+        use std::time::Duration;
+        assert_eq!(parse_duration("45s"), Some(Duration::from_secs(45)));
+        assert_eq!(parse_duration("30m"), Some(Duration::from_secs(1800)));
+        assert_eq!(parse_duration("3h"), Some(Duration::from_secs(10800)));
+        assert_eq!(parse_duration("2d"), Some(Duration::from_secs(172800)));
+        assert_eq!(parse_duration("1w"), Some(Duration::from_secs(604800)));
+        // Bare number is seconds
+        assert_eq!(parse_duration("90"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_duration("2 fortnights"), None);
+        assert_eq!(parse_duration("d"), None);
+        assert_eq!(parse_duration(""), None);
+    }
+
+    #[tokio::test]
+    async fn test_glob_tool_modified_within() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("fresh.txt"), "").unwrap();
+
+        let tool = GlobTool::new(cwd.clone(), vec![cwd.clone()], None);
+
+        // A generous window includes the just-written file
+        let result = tool
+            .call(json!({"pattern": "*.txt", "modified_within": "1h"}))
+            .await
+            .unwrap();
+        assert_eq!(result["count"], 1);
+
+        // A zero-second window excludes it
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        let result = tool
+            .call(json!({"pattern": "*.txt", "modified_within": "0s"}))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::NOT_FOUND);
+
+        // Invalid duration is an argument error
+        let result = tool
+            .call(json!({"pattern": "*.txt", "modified_within": "soon"}))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+    }
+
+    #[tokio::test]
+    async fn test_glob_tool_details() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("a.txt"), "hello").unwrap();
+
+        let tool = GlobTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let result = tool
+            .call(json!({"pattern": "*.txt", "details": true}))
+            .await
+            .unwrap();
+
+        let matches = result["matches"].as_array().unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0]["path"], "a.txt");
+        assert_eq!(matches[0]["size"], 5);
+        assert!(matches[0]["modified"].as_str().unwrap().contains('T'));
+
+        // Default stays plain paths
+        let result = tool.call(json!({"pattern": "*.txt"})).await.unwrap();
+        assert_eq!(result["matches"][0], "a.txt");
+    }
+
     #[tokio::test]
     async fn test_glob_tool_pagination() {
         let dir = tempdir().unwrap();
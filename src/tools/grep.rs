@@ -24,6 +24,8 @@ pub struct GrepTool {
     cwd: PathBuf,
     allowed_paths: Vec<PathBuf>,
     events_tx: Option<mpsc::Sender<AgentEvent>>,
+    /// Per-tool timeout in seconds from the `[timeouts]` config section.
+    timeout: Option<u64>,
 }
 
 impl GrepTool {
@@ -36,8 +38,16 @@ impl GrepTool {
             cwd,
             allowed_paths,
             events_tx,
+            timeout: None,
         }
     }
+
+    /// Limit the whole call to the given number of seconds
+    /// (`[timeouts]` config section). `None` means no limit.
+    pub fn with_timeout(mut self, secs: Option<u64>) -> Self {
+        self.timeout = secs;
+        self
+    }
 }
 
 impl ToolEmitter for GrepTool {
@@ -60,6 +70,7 @@ enum OutputMode {
     Content,
     FilesWithMatches,
     Count,
+    OnlyMatching,
 }
 
 struct GrepSink {
@@ -72,6 +83,9 @@ struct GrepSink {
     current_block: Option<MatchBlock>,
     output_mode: OutputMode,
     match_count: usize,
+    /// Compiled pattern for extracting matched fragments in only_matching
+    /// mode (the searcher's SinkMatch hands us whole lines).
+    only_regex: Option<regex::Regex>,
 }
 
 struct MatchBlock {
@@ -128,6 +142,26 @@ impl Sink for GrepSink {
             OutputMode::Count => {
                 return Ok(true); // Continue searching to count matches
             }
+            OutputMode::OnlyMatching => {
+                let start_line_number = mat.line_number().unwrap_or(0);
+                let content = std::str::from_utf8(mat.bytes()).unwrap_or("");
+                if let Some(re) = &self.only_regex {
+                    let mut matches = self.matches.lock().unwrap();
+                    for m in re.find_iter(content) {
+                        if matches.len() >= self.max_results {
+                            return Ok(false);
+                        }
+                        // SinkMatch bytes may span lines in multiline mode;
+                        // offset the line number by preceding newlines
+                        let line_offset = content[..m.start()].matches('\n').count() as u64;
+                        matches.push(json!({
+                            "file": self.path.clone(),
+                            "line": start_line_number + line_offset,
+                            "match": truncate_line(m.as_str())
+                        }));
+                    }
+                }
+            }
             OutputMode::Content => {
                 let start_line_number = mat.line_number().unwrap_or(0);
                 let content = std::str::from_utf8(mat.bytes())
@@ -243,7 +277,7 @@ impl Sink for GrepSink {
                     }));
                 }
             }
-            OutputMode::FilesWithMatches => {}
+            OutputMode::FilesWithMatches | OutputMode::OnlyMatching => {}
         }
         Ok(())
     }
@@ -254,7 +288,7 @@ impl CallableFunction for GrepTool {
     fn declaration(&self) -> FunctionDeclaration {
         FunctionDeclaration::new(
             "grep".to_string(),
-            "Search for a pattern in files using ripgrep. Supports regex, case-insensitive search, and different output modes (content, files_with_matches, count). Returns: {matches[], count, total_found, truncated?}".to_string(),
+            "Search for a pattern in files using ripgrep. Supports regex, case-insensitive search, and different output modes (content, files_with_matches, count, only_matching). Returns: {matches[], count, total_found, truncated?}".to_string(),
             FunctionParameters::new(
                 "object".to_string(),
                 json!({
@@ -308,8 +342,8 @@ impl CallableFunction for GrepTool {
                     },
                     "output_mode": {
                         "type": "string",
-                        "enum": ["content", "files_with_matches", "count"],
-                        "description": "Output format: 'content' for matching lines, 'files_with_matches' for just file paths, 'count' for number of matches per file (default: 'content')"
+                        "enum": ["content", "files_with_matches", "count", "only_matching"],
+                        "description": "Output format: 'content' for matching lines, 'files_with_matches' for just file paths, 'count' for number of matches per file, 'only_matching' for just the matched text fragments (default: 'content')"
                     }
                 }),
                 vec!["pattern".to_string()],
@@ -319,6 +353,13 @@ impl CallableFunction for GrepTool {
 
     #[instrument(skip(self, args))]
     async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        super::run_with_timeout("grep", self.timeout, self.run(args)).await
+    }
+}
+
+impl GrepTool {
+    /// The actual search, wrapped in the per-tool timeout by `call`.
+    async fn run(&self, args: Value) -> Result<Value, FunctionError> {
         let pattern = args
             .get("pattern")
             .and_then(|v| v.as_str())
@@ -364,9 +405,24 @@ impl CallableFunction for GrepTool {
         let output_mode = match args.get("output_mode").and_then(|v| v.as_str()) {
             Some("files_with_matches") => OutputMode::FilesWithMatches,
             Some("count") => OutputMode::Count,
+            Some("only_matching") => OutputMode::OnlyMatching,
             _ => OutputMode::Content,
         };
 
+        let only_regex = if output_mode == OutputMode::OnlyMatching {
+            Some(
+                regex::RegexBuilder::new(pattern)
+                    .case_insensitive(case_insensitive)
+                    .multi_line(true)
+                    .build()
+                    .map_err(|e| {
+                        FunctionError::ExecutionError(format!("Invalid regex: {}", e).into())
+                    })?,
+            )
+        } else {
+            None
+        };
+
         // Resolve and validate the search path
         let base_dir = if let Some(p) = search_path {
             match resolve_and_validate_path(p, &self.cwd, &self.allowed_paths) {
@@ -505,6 +561,7 @@ impl CallableFunction for GrepTool {
                 current_block: None,
                 output_mode,
                 match_count: 0,
+                only_regex: only_regex.clone(),
             };
 
             let prev_count = matches.lock().unwrap().len();
@@ -996,6 +1053,59 @@ mod tests {
         assert!(result["truncated"].as_bool().unwrap());
     }
 
+    #[tokio::test]
+    async fn test_grep_output_mode_only_matching() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(
+            cwd.join("test.rs"),
+            "use crate::agent::AgentEvent;\nuse crate::tools::ToolEmitter;\nfn main() {}",
+        )
+        .unwrap();
+
+        let tool = GrepTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "pattern": r"crate::\w+",
+            "output_mode": "only_matching"
+        });
+
+        let result = tool.call(args).await.unwrap();
+        let matches = result["matches"].as_array().unwrap();
+
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0]["match"], "crate::agent");
+        assert_eq!(matches[0]["line"], 1);
+        assert_eq!(matches[1]["match"], "crate::tools");
+        assert_eq!(matches[1]["line"], 2);
+        // Fragments only - no full line content
+        assert!(matches[0].get("content").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_grep_only_matching_multiple_per_line() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("test.txt"), "foo1 bar foo2 baz foo3").unwrap();
+
+        let tool = GrepTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "pattern": r"foo\d",
+            "output_mode": "only_matching"
+        });
+
+        let result = tool.call(args).await.unwrap();
+        let matches = result["matches"].as_array().unwrap();
+
+        assert_eq!(matches.len(), 3);
+        let found: Vec<_> = matches
+            .iter()
+            .map(|m| m["match"].as_str().unwrap())
+            .collect();
+        assert_eq!(found, vec!["foo1", "foo2", "foo3"]);
+        // All on line 1
+        assert!(matches.iter().all(|m| m["line"] == 1));
+    }
+
     #[tokio::test]
     async fn test_grep_multiline() {
         let dir = tempdir().unwrap();
@@ -0,0 +1,390 @@
+use crate::agent::AgentEvent;
+use crate::tools::{MAX_TOOL_OUTPUT_LEN, ToolEmitter};
+use async_trait::async_trait;
+use colored::Colorize;
+use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
+use serde_json::{Map, Value, json};
+use tokio::sync::mpsc;
+use tracing::instrument;
+
+/// Methods the tool accepts, mirrored in the declaration description.
+const ALLOWED_METHODS: &[&str] = &["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD"];
+
+struct RequestArgs {
+    method: reqwest::Method,
+    url: String,
+    headers: Vec<(String, String)>,
+    body: Option<Value>,
+}
+
+/// Generic HTTP client for JSON APIs (local dev servers, REST endpoints).
+/// `web_fetch` is fetch-and-summarize oriented and can't POST; this tool
+/// returns status/headers/body structured and supports all common methods.
+pub struct HttpRequestTool {
+    events_tx: Option<mpsc::Sender<AgentEvent>>,
+    /// Host allowlist from the `http_allowed_hosts` config key.
+    /// `None` permits every host; `Some` permits only listed hosts
+    /// (exact match or subdomain).
+    allowed_hosts: Option<Vec<String>>,
+}
+
+impl ToolEmitter for HttpRequestTool {
+    fn events_tx(&self) -> &Option<mpsc::Sender<AgentEvent>> {
+        &self.events_tx
+    }
+}
+
+impl HttpRequestTool {
+    pub fn new(events_tx: Option<mpsc::Sender<AgentEvent>>) -> Self {
+        Self {
+            events_tx,
+            allowed_hosts: None,
+        }
+    }
+
+    /// Restrict requests to the given hosts (`http_allowed_hosts` config key).
+    pub fn with_allowed_hosts(mut self, hosts: Option<Vec<String>>) -> Self {
+        self.allowed_hosts = hosts;
+        self
+    }
+
+    fn parse_args(&self, args: &Value) -> Result<RequestArgs, FunctionError> {
+        let method_str = args
+            .get("method")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing method".to_string()))?
+            .to_uppercase();
+        if !ALLOWED_METHODS.contains(&method_str.as_str()) {
+            return Err(FunctionError::ArgumentMismatch(format!(
+                "Unknown method '{}'. Expected one of: {}",
+                method_str,
+                ALLOWED_METHODS.join(", ")
+            )));
+        }
+        let method = method_str
+            .parse::<reqwest::Method>()
+            .map_err(|_| FunctionError::ArgumentMismatch(format!("Invalid method {method_str}")))?;
+
+        let url = args
+            .get("url")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing url".to_string()))?;
+
+        let headers = args
+            .get("headers")
+            .and_then(|v| v.as_object())
+            .map(|map| {
+                map.iter()
+                    .filter_map(|(k, v)| v.as_str().map(|v| (k.clone(), v.to_string())))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let body = args.get("body").filter(|v| !v.is_null()).cloned();
+
+        Ok(RequestArgs {
+            method,
+            url,
+            headers,
+            body,
+        })
+    }
+
+    /// Whether the URL's host passes the allowlist. No allowlist permits
+    /// everything; entries match exactly or as a parent domain.
+    fn host_allowed(&self, url: &url::Url) -> bool {
+        let Some(allowed) = &self.allowed_hosts else {
+            return true;
+        };
+        let Some(host) = url.host_str().map(|h| h.to_lowercase()) else {
+            return false;
+        };
+        allowed.iter().any(|a| {
+            let a = a.to_lowercase();
+            host == a || host.ends_with(&format!(".{a}"))
+        })
+    }
+}
+
+#[async_trait]
+impl CallableFunction for HttpRequestTool {
+    fn declaration(&self) -> FunctionDeclaration {
+        FunctionDeclaration::new(
+            "http_request".to_string(),
+            "Make an HTTP request to a JSON API (local dev server, REST endpoint) and return the structured response. Unlike web_fetch this supports POST/PUT/PATCH/DELETE and custom headers. Returns: {status, headers, body}".to_string(),
+            FunctionParameters::new(
+                "object".to_string(),
+                json!({
+                    "method": {
+                        "type": "string",
+                        "description": "HTTP method: GET, POST, PUT, PATCH, DELETE, or HEAD"
+                    },
+                    "url": {
+                        "type": "string",
+                        "description": "The URL to request (e.g., 'http://localhost:3000/api/users')"
+                    },
+                    "headers": {
+                        "type": "object",
+                        "description": "Optional request headers as string key/value pairs"
+                    },
+                    "body": {
+                        "type": ["object", "array", "string"],
+                        "description": "Optional request body. Objects/arrays are sent as JSON (with Content-Type: application/json); strings are sent verbatim"
+                    }
+                }),
+                vec!["method".to_string(), "url".to_string()],
+            ),
+        )
+    }
+
+    #[instrument(skip(self, args))]
+    async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        let request_args = self.parse_args(&args)?;
+
+        let parsed_url = match url::Url::parse(&request_args.url) {
+            Ok(u) => u,
+            Err(e) => return Ok(json!({ "error": format!("Invalid URL: {}", e) })),
+        };
+        if !self.host_allowed(&parsed_url) {
+            return Ok(json!({
+                "error": format!(
+                    "Host '{}' is not in http_allowed_hosts. Add it to ~/.clemini/config.toml to permit it.",
+                    parsed_url.host_str().unwrap_or("<none>")
+                )
+            }));
+        }
+
+        let client = match super::create_http_client() {
+            Ok(c) => c,
+            Err(e) => return Ok(json!({ "error": e })),
+        };
+
+        let mut request = client.request(request_args.method.clone(), parsed_url);
+        for (name, value) in &request_args.headers {
+            request = request.header(name, value);
+        }
+        if let Some(body) = &request_args.body {
+            request = match body {
+                Value::String(s) => request.body(s.clone()),
+                other => request.json(other),
+            };
+        }
+
+        let resp = match request.send().await {
+            Ok(r) => r,
+            Err(e) => return Ok(json!({ "error": format!("Network error: {}", e) })),
+        };
+
+        let status = resp.status().as_u16();
+        let headers: Map<String, Value> = resp
+            .headers()
+            .iter()
+            .map(|(name, value)| {
+                (
+                    name.to_string(),
+                    Value::String(String::from_utf8_lossy(value.as_bytes()).into_owned()),
+                )
+            })
+            .collect();
+        let is_json = resp
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|ct| ct.contains("json"));
+
+        let mut text = match resp.text().await {
+            Ok(t) => t,
+            Err(e) => {
+                return Ok(json!({ "error": format!("Failed to read response body: {}", e) }));
+            }
+        };
+        let original_len = text.len();
+        let truncated = original_len > MAX_TOOL_OUTPUT_LEN;
+        if truncated {
+            text.truncate(MAX_TOOL_OUTPUT_LEN);
+        }
+
+        self.emit(&format!(
+            "  {}",
+            format!(
+                "{} {} - {} bytes",
+                request_args.method, status, original_len
+            )
+            .dimmed()
+        ));
+
+        // JSON bodies come back structured so the model doesn't re-parse
+        // strings; anything else (or unparseable JSON) stays text.
+        let body = if is_json && !truncated {
+            serde_json::from_str::<Value>(&text).unwrap_or(Value::String(text))
+        } else {
+            Value::String(text)
+        };
+
+        let mut response = json!({
+            "status": status,
+            "headers": headers,
+            "body": body,
+            "length": original_len
+        });
+        if truncated {
+            response["truncated"] = json!(true);
+        }
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use genai_rs::CallableFunction;
+    use serde_json::json;
+
+    #[test]
+    fn test_declaration() {
+        let tool = HttpRequestTool::new(None);
+        let decl = tool.declaration();
+
+        assert_eq!(decl.name(), "http_request");
+        assert!(decl.description().contains("POST"));
+
+        let params = decl.parameters();
+        assert_eq!(
+            params.required(),
+            vec!["method".to_string(), "url".to_string()]
+        );
+        let properties = params.properties();
+        assert!(properties.get("headers").is_some());
+        assert!(properties.get("body").is_some());
+    }
+
+    #[test]
+    fn test_parse_args_success() {
+        let tool = HttpRequestTool::new(None);
+        let args = tool
+            .parse_args(&json!({
+                "method": "post",
+                "url": "http://localhost:3000/api",
+                "headers": {"Authorization": "Bearer xyz"},
+                "body": {"name": "test"}
+            }))
+            .unwrap();
+
+        assert_eq!(args.method, reqwest::Method::POST);
+        assert_eq!(args.url, "http://localhost:3000/api");
+        assert_eq!(
+            args.headers,
+            vec![("Authorization".to_string(), "Bearer xyz".to_string())]
+        );
+        assert_eq!(args.body.unwrap(), json!({"name": "test"}));
+    }
+
+    #[test]
+    fn test_parse_args_missing_fields() {
+        let tool = HttpRequestTool::new(None);
+
+        match tool.parse_args(&json!({"url": "http://localhost"})) {
+            Err(FunctionError::ArgumentMismatch(msg)) => assert_eq!(msg, "Missing method"),
+            _ => panic!("Expected ArgumentMismatch error"),
+        }
+        match tool.parse_args(&json!({"method": "GET"})) {
+            Err(FunctionError::ArgumentMismatch(msg)) => assert_eq!(msg, "Missing url"),
+            _ => panic!("Expected ArgumentMismatch error"),
+        }
+    }
+
+    #[test]
+    fn test_parse_args_unknown_method() {
+        let tool = HttpRequestTool::new(None);
+        match tool.parse_args(&json!({"method": "TRACE", "url": "http://localhost"})) {
+            Err(FunctionError::ArgumentMismatch(msg)) => {
+                assert!(msg.contains("Unknown method 'TRACE'"));
+                assert!(msg.contains("GET"));
+            }
+            _ => panic!("Expected ArgumentMismatch error"),
+        }
+    }
+
+    #[test]
+    fn test_host_allowed() {
+        let open = HttpRequestTool::new(None);
+        assert!(open.host_allowed(&url::Url::parse("https://example.com/x").unwrap()));
+
+        let restricted = HttpRequestTool::new(None).with_allowed_hosts(Some(vec![
+            "localhost".to_string(),
+            "example.com".to_string(),
+        ]));
+        assert!(restricted.host_allowed(&url::Url::parse("http://localhost:3000/api").unwrap()));
+        assert!(restricted.host_allowed(&url::Url::parse("https://api.example.com/v1").unwrap()));
+        assert!(!restricted.host_allowed(&url::Url::parse("https://evil.com/").unwrap()));
+        assert!(!restricted.host_allowed(&url::Url::parse("https://notexample.com/").unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_blocked_host_returns_error_without_network() {
+        let tool =
+            HttpRequestTool::new(None).with_allowed_hosts(Some(vec!["localhost".to_string()]));
+        let result = tool
+            .call(json!({"method": "GET", "url": "https://example.com/api"}))
+            .await
+            .unwrap();
+        assert!(
+            result["error"]
+                .as_str()
+                .unwrap()
+                .contains("http_allowed_hosts")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_json_round_trip() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/data")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"ok": true, "items": [1, 2]}"#)
+            .create_async()
+            .await;
+
+        let tool = HttpRequestTool::new(None);
+        let result = tool
+            .call(json!({"method": "GET", "url": format!("{}/data", server.url())}))
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(result["status"], 200);
+        assert_eq!(result["body"]["ok"], true);
+        assert_eq!(result["body"]["items"], json!([1, 2]));
+        assert!(result["headers"]["content-type"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_post_json_body() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/users")
+            .match_header("content-type", "application/json")
+            .match_body(mockito::Matcher::Json(json!({"name": "test"})))
+            .with_status(201)
+            .with_body("created")
+            .create_async()
+            .await;
+
+        let tool = HttpRequestTool::new(None);
+        let result = tool
+            .call(json!({
+                "method": "POST",
+                "url": format!("{}/users", server.url()),
+                "body": {"name": "test"}
+            }))
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(result["status"], 201);
+        assert_eq!(result["body"], "created");
+    }
+}
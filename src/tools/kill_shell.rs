@@ -1,13 +1,18 @@
 use crate::agent::AgentEvent;
-use crate::tools::tasks::{TASKS, Task};
+use crate::tools::tasks::{self, TASKS, Task};
 use crate::tools::{ToolEmitter, error_codes, error_response};
 use async_trait::async_trait;
 use colored::Colorize;
 use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
 use serde_json::{Value, json};
 use tokio::sync::mpsc;
+use tokio::time::{Duration, sleep, timeout};
 use tracing::instrument;
 
+/// How long a SIGTERM'd process gets to exit cleanly before escalating
+/// to SIGKILL.
+const TERM_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
 pub struct KillShellTool {
     events_tx: Option<mpsc::Sender<AgentEvent>>,
 }
@@ -24,18 +29,122 @@ impl ToolEmitter for KillShellTool {
     }
 }
 
+/// Kill a tokio child, optionally trying SIGTERM first with a grace period.
+async fn kill_child(
+    task_id: &str,
+    mut child: tokio::process::Child,
+    graceful: bool,
+) -> Result<(), String> {
+    if graceful && let Some(pid) = child.id() {
+        let _ = tasks::send_signal(pid, libc::SIGTERM);
+        if timeout(TERM_GRACE_PERIOD, child.wait()).await.is_ok() {
+            return Ok(());
+        }
+    }
+    child
+        .kill()
+        .await
+        .map_err(|e| format!("Failed to kill task {}: {}", task_id, e))
+}
+
+/// Poll `is_dead` for up to the grace period after a SIGTERM.
+/// Returns true if the process exited within it.
+async fn wait_for_exit(mut is_dead: impl FnMut() -> bool) -> bool {
+    let polls = TERM_GRACE_PERIOD.as_millis() / 100;
+    for _ in 0..polls {
+        if is_dead() {
+            return true;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    is_dead()
+}
+
+/// Kill one task (already removed from the registry). With `graceful`, the
+/// process gets SIGTERM and `TERM_GRACE_PERIOD` to exit before SIGKILL.
+/// Returns the task type on success.
+async fn kill_task(task_id: &str, mut task: Task, graceful: bool) -> Result<&'static str, String> {
+    let task_type = task.task_type();
+
+    // For ACP tasks, send cancel signal first to allow graceful shutdown
+    if let Task::Acp(ref acp) = task
+        && let Some(cancel_tx) = acp.cancel_tx()
+    {
+        // Non-blocking send - if receiver is gone, that's fine
+        let _ = cancel_tx.try_send(());
+    }
+
+    match &mut task {
+        Task::Background(bg) => {
+            tasks::remove_persisted_task(task_id);
+            let Some(child) = bg.take_child() else {
+                return Err(format!(
+                    "Task {} already finished or process missing",
+                    task_id
+                ));
+            };
+            kill_child(task_id, child, graceful).await?;
+        }
+        Task::Acp(acp) => {
+            let Some(child) = acp.take_child() else {
+                return Err(format!(
+                    "Task {} already finished or process missing",
+                    task_id
+                ));
+            };
+            kill_child(task_id, child, graceful).await?;
+        }
+        // PTY children are killed synchronously (portable-pty, not tokio)
+        Task::Pty(pty) => {
+            if graceful && let Some(pid) = pty.process_id() {
+                let _ = tasks::send_signal(pid, libc::SIGTERM);
+                let exited = wait_for_exit(|| {
+                    pty.update_status();
+                    pty.is_completed()
+                })
+                .await;
+                if exited {
+                    return Ok(task_type);
+                }
+            }
+            pty.kill()
+                .map_err(|e| format!("Failed to kill task {}: {}", task_id, e))?;
+        }
+        // Detached tasks (reattached from a previous process) have no
+        // Child handle; they're killed by PID
+        Task::Detached(detached) => {
+            tasks::remove_persisted_task(task_id);
+            if graceful {
+                let pid = detached.pid();
+                let _ = tasks::send_signal(pid, libc::SIGTERM);
+                if wait_for_exit(|| !tasks::pid_alive(pid)).await {
+                    return Ok(task_type);
+                }
+            }
+            detached
+                .kill()
+                .map_err(|e| format!("Failed to kill task {}: {}", task_id, e))?;
+        }
+    }
+    Ok(task_type)
+}
+
 #[async_trait]
 impl CallableFunction for KillShellTool {
     fn declaration(&self) -> FunctionDeclaration {
         FunctionDeclaration::new(
             "kill_shell".to_string(),
-            "Kill a background task. Works for both bash tasks (run_in_background=true) and ACP subagent tasks. Returns: {task_id, status, success}".to_string(),
+            "Kill a background task, or all tasks at once. Works for bash tasks (run_in_background=true), interactive PTY tasks, and ACP subagent tasks. Returns: {task_id, task_type, status, signal, success} or {killed, errors?, signal, success} for task_id='all'".to_string(),
             FunctionParameters::new(
                 "object".to_string(),
                 json!({
                     "task_id": {
                         "type": "string",
-                        "description": "The task ID to kill (e.g., 'bg-1' for bash, 'acp-1' for subagent)"
+                        "description": "The task ID to kill (e.g., 'bg-1' for bash, 'acp-1' for subagent), or 'all' to kill every registered task"
+                    },
+                    "signal": {
+                        "type": "string",
+                        "description": "TERM sends SIGTERM and escalates to SIGKILL after a 5s grace period; KILL kills immediately. (default: TERM)"
                     }
                 }),
                 vec!["task_id".to_string()],
@@ -50,59 +159,89 @@ impl CallableFunction for KillShellTool {
             .and_then(|v| v.as_str())
             .ok_or_else(|| FunctionError::ArgumentMismatch("Missing task_id".to_string()))?;
 
-        let task = {
-            let mut tasks = TASKS.lock().unwrap();
-            tasks.remove(task_id)
-        };
-
-        if let Some(mut task) = task {
-            let task_type = task.task_type();
-
-            // For ACP tasks, send cancel signal first to allow graceful shutdown
-            if let Task::Acp(ref acp) = task
-                && let Some(cancel_tx) = acp.cancel_tx()
-            {
-                // Non-blocking send - if receiver is gone, that's fine
-                let _ = cancel_tx.try_send(());
+        let signal = args
+            .get("signal")
+            .and_then(|v| v.as_str())
+            .unwrap_or("TERM")
+            .to_uppercase();
+        let graceful = match signal.as_str() {
+            "TERM" => true,
+            "KILL" => false,
+            other => {
+                return Ok(error_response(
+                    &format!("Unknown signal '{}': use TERM or KILL", other),
+                    error_codes::INVALID_ARGUMENT,
+                    json!({ "signal": other }),
+                ));
             }
+        };
 
-            // Extract child process based on task type
-            let child = match &mut task {
-                Task::Background(bg) => bg.take_child(),
-                Task::Acp(acp) => acp.take_child(),
+        if task_id == "all" {
+            let to_kill: Vec<(String, Task)> = {
+                let mut tasks = TASKS.lock().unwrap();
+                tasks.drain().collect()
             };
 
-            if let Some(mut child) = child {
-                match child.kill().await {
-                    Ok(_) => {
-                        self.emit(&format!("  {}", "killed".dimmed()));
-                        Ok(json!({
-                            "task_id": task_id,
-                            "task_type": task_type,
-                            "status": "killed",
-                            "success": true
-                        }))
-                    }
-                    Err(e) => Ok(error_response(
-                        &format!("Failed to kill task {}: {}", task_id, e),
-                        error_codes::IO_ERROR,
-                        json!({ "task_id": task_id }),
-                    )),
+            let mut killed = Vec::new();
+            let mut errors = Vec::new();
+            for (id, task) in to_kill {
+                match kill_task(&id, task, graceful).await {
+                    Ok(task_type) => killed.push(json!({
+                        "task_id": id,
+                        "task_type": task_type
+                    })),
+                    Err(e) => errors.push(json!({ "task_id": id, "error": e })),
                 }
-            } else {
-                // Task object exists but child is missing (already finished?)
-                Ok(error_response(
-                    &format!("Task {} already finished or process missing", task_id),
-                    error_codes::NOT_FOUND,
-                    json!({ "task_id": task_id }),
-                ))
             }
-        } else {
-            Ok(error_response(
+
+            self.emit(&format!(
+                "  {}",
+                format!("killed {} task(s)", killed.len()).dimmed()
+            ));
+            let mut resp = json!({
+                "killed": killed,
+                "signal": signal,
+                "success": true
+            });
+            if !errors.is_empty() {
+                resp["errors"] = json!(errors);
+            }
+            return Ok(resp);
+        }
+
+        let task = {
+            let mut tasks = TASKS.lock().unwrap();
+            tasks.remove(task_id)
+        };
+
+        let Some(task) = task else {
+            return Ok(error_response(
                 &format!("Task {} not found", task_id),
                 error_codes::NOT_FOUND,
                 json!({ "task_id": task_id }),
-            ))
+            ));
+        };
+
+        match kill_task(task_id, task, graceful).await {
+            Ok(task_type) => {
+                self.emit(&format!("  {}", "killed".dimmed()));
+                Ok(json!({
+                    "task_id": task_id,
+                    "task_type": task_type,
+                    "status": "killed",
+                    "signal": signal,
+                    "success": true
+                }))
+            }
+            Err(e) => Ok(error_response(
+                &e,
+                if e.contains("already finished") {
+                    error_codes::NOT_FOUND
+                } else {
+                    error_codes::IO_ERROR
+                },
+                json!({ "task_id": task_id }),
+            )),
         }
     }
 }
@@ -141,12 +280,92 @@ mod tests {
 
         assert!(kill_result["success"].as_bool().unwrap());
         assert_eq!(kill_result["status"], "killed");
+        assert_eq!(kill_result["signal"], "TERM");
 
         // Verify it's gone from the unified registry
         let tasks = TASKS.lock().unwrap();
         assert!(!tasks.contains_key(task_id));
     }
 
+    #[tokio::test]
+    async fn test_kill_shell_sigkill() {
+        let dir = tempdir().unwrap();
+        let bash = BashTool::new_without_confirmation_tracking(
+            dir.path().to_path_buf(),
+            vec![dir.path().to_path_buf()],
+            5,
+            false,
+            None,
+        );
+
+        let bash_result = bash
+            .call(json!({
+                "command": "sleep 100",
+                "run_in_background": true
+            }))
+            .await
+            .unwrap();
+        let task_id = bash_result["task_id"].as_str().unwrap();
+
+        let kill_tool = KillShellTool::new(None);
+        let kill_result = kill_tool
+            .call(json!({ "task_id": task_id, "signal": "KILL" }))
+            .await
+            .unwrap();
+
+        assert!(kill_result["success"].as_bool().unwrap());
+        assert_eq!(kill_result["signal"], "KILL");
+    }
+
+    #[tokio::test]
+    async fn test_kill_shell_invalid_signal() {
+        let kill_tool = KillShellTool::new(None);
+        let result = kill_tool
+            .call(json!({ "task_id": "bg-1", "signal": "HUP" }))
+            .await
+            .unwrap();
+
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_kill_shell_all() {
+        let dir = tempdir().unwrap();
+        let bash = BashTool::new_without_confirmation_tracking(
+            dir.path().to_path_buf(),
+            vec![dir.path().to_path_buf()],
+            5,
+            false,
+            None,
+        );
+
+        let mut ids = Vec::new();
+        for _ in 0..3 {
+            let result = bash
+                .call(json!({
+                    "command": "sleep 100",
+                    "run_in_background": true
+                }))
+                .await
+                .unwrap();
+            ids.push(result["task_id"].as_str().unwrap().to_string());
+        }
+
+        let kill_tool = KillShellTool::new(None);
+        let result = kill_tool.call(json!({ "task_id": "all" })).await.unwrap();
+
+        assert!(result["success"].as_bool().unwrap());
+        let killed = result["killed"].as_array().unwrap();
+        for id in &ids {
+            assert!(
+                killed.iter().any(|k| k["task_id"] == id.as_str()),
+                "{id} not reported as killed: {result}"
+            );
+        }
+        assert!(TASKS.lock().unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn test_kill_shell_not_found() {
         let kill_tool = KillShellTool::new(None);
@@ -0,0 +1,367 @@
+//! JSON-RPC client for a language server speaking LSP over stdio.
+//!
+//! One `LspClient` owns one server process. A reader task parses the
+//! Content-Length framed stream: responses are routed to their waiting
+//! request by id, and `textDocument/publishDiagnostics` notifications are
+//! cached per-URI so the `diagnostics` operation can report what the server
+//! has pushed.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicI64, Ordering};
+
+use serde_json::{Value, json};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::process::{Child, ChildStdin, Command};
+use tokio::sync::{Mutex, oneshot};
+use tokio::time::{Duration, timeout};
+
+/// How long to wait for a response to a single request. Language servers
+/// can take a while on first use (rust-analyzer indexes the workspace).
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+type PendingMap = Arc<Mutex<HashMap<i64, oneshot::Sender<Value>>>>;
+type DiagnosticsMap = Arc<Mutex<HashMap<String, Value>>>;
+
+pub struct LspClient {
+    child: Child,
+    stdin: ChildStdin,
+    next_id: AtomicI64,
+    pending: PendingMap,
+    /// Latest `publishDiagnostics` per document URI.
+    diagnostics: DiagnosticsMap,
+    /// Versions for didOpen/didChange bookkeeping, keyed by URI.
+    open_docs: HashMap<String, i32>,
+}
+
+/// Convert a filesystem path to a file:// URI.
+pub fn path_to_uri(path: &Path) -> String {
+    format!("file://{}", path.display())
+}
+
+impl LspClient {
+    /// Spawn `command` rooted at `root` and complete the initialize
+    /// handshake.
+    pub async fn spawn(command: &[String], root: &Path) -> Result<Self, String> {
+        let (program, args) = command
+            .split_first()
+            .ok_or_else(|| "Empty language server command".to_string())?;
+
+        let mut child = Command::new(program)
+            .args(args)
+            .current_dir(root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| {
+                if e.kind() == std::io::ErrorKind::NotFound {
+                    format!(
+                        "Language server '{}' is not installed (not found on PATH)",
+                        program
+                    )
+                } else {
+                    format!("Failed to spawn language server '{}': {}", program, e)
+                }
+            })?;
+
+        let stdin = child.stdin.take().ok_or("Failed to open server stdin")?;
+        let stdout = child.stdout.take().ok_or("Failed to open server stdout")?;
+
+        let pending: PendingMap = Arc::new(Mutex::new(HashMap::new()));
+        let diagnostics: DiagnosticsMap = Arc::new(Mutex::new(HashMap::new()));
+        spawn_reader(stdout, pending.clone(), diagnostics.clone());
+
+        let mut client = Self {
+            child,
+            stdin,
+            next_id: AtomicI64::new(1),
+            pending,
+            diagnostics,
+            open_docs: HashMap::new(),
+        };
+
+        client
+            .request(
+                "initialize",
+                json!({
+                    "processId": std::process::id(),
+                    "rootUri": path_to_uri(root),
+                    "capabilities": {
+                        "textDocument": {
+                            "publishDiagnostics": {},
+                            "hover": { "contentFormat": ["plaintext", "markdown"] }
+                        }
+                    }
+                }),
+            )
+            .await?;
+        client.notify("initialized", json!({})).await?;
+
+        Ok(client)
+    }
+
+    /// Send a request and wait for its response. Returns the `result` field,
+    /// or an error for JSON-RPC errors and timeouts.
+    pub async fn request(&mut self, method: &str, params: Value) -> Result<Value, String> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().await.insert(id, tx);
+
+        let message = json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params
+        });
+        self.send(&message).await?;
+
+        let response = match timeout(REQUEST_TIMEOUT, rx).await {
+            Ok(Ok(response)) => response,
+            Ok(Err(_)) => return Err(format!("Language server closed during '{}'", method)),
+            Err(_) => {
+                self.pending.lock().await.remove(&id);
+                return Err(format!(
+                    "Language server did not respond to '{}' within {}s",
+                    method,
+                    REQUEST_TIMEOUT.as_secs()
+                ));
+            }
+        };
+
+        if let Some(error) = response.get("error") {
+            return Err(format!(
+                "Language server error for '{}': {}",
+                method,
+                error.get("message").and_then(|m| m.as_str()).unwrap_or("unknown")
+            ));
+        }
+        Ok(response.get("result").cloned().unwrap_or(Value::Null))
+    }
+
+    /// Send a notification (no response expected).
+    pub async fn notify(&mut self, method: &str, params: Value) -> Result<(), String> {
+        let message = json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params
+        });
+        self.send(&message).await
+    }
+
+    /// Ensure the server has the current on-disk contents of `path`
+    /// (didOpen on first sight, didChange with a version bump after).
+    pub async fn sync_document(
+        &mut self,
+        path: &Path,
+        language_id: &str,
+        text: &str,
+    ) -> Result<(), String> {
+        let uri = path_to_uri(path);
+        match self.open_docs.get(&uri).copied() {
+            None => {
+                self.open_docs.insert(uri.clone(), 1);
+                self.notify(
+                    "textDocument/didOpen",
+                    json!({
+                        "textDocument": {
+                            "uri": uri,
+                            "languageId": language_id,
+                            "version": 1,
+                            "text": text
+                        }
+                    }),
+                )
+                .await
+            }
+            Some(version) => {
+                let version = version + 1;
+                self.open_docs.insert(uri.clone(), version);
+                self.notify(
+                    "textDocument/didChange",
+                    json!({
+                        "textDocument": { "uri": uri, "version": version },
+                        "contentChanges": [{ "text": text }]
+                    }),
+                )
+                .await
+            }
+        }
+    }
+
+    /// Latest pushed diagnostics for `uri` (the `diagnostics` array from
+    /// `publishDiagnostics`), or None if the server hasn't published any.
+    pub async fn diagnostics_for(&self, uri: &str) -> Option<Value> {
+        self.diagnostics.lock().await.get(uri).cloned()
+    }
+
+    /// Whether the server process is still running.
+    pub fn is_alive(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    async fn send(&mut self, message: &Value) -> Result<(), String> {
+        let body = message.to_string();
+        let framed = format!("Content-Length: {}\r\n\r\n{}", body.len(), body);
+        self.stdin
+            .write_all(framed.as_bytes())
+            .await
+            .map_err(|e| format!("Failed to write to language server: {}", e))?;
+        self.stdin
+            .flush()
+            .await
+            .map_err(|e| format!("Failed to flush language server stdin: {}", e))
+    }
+}
+
+/// Read framed messages from the server, routing responses to pending
+/// requests and caching pushed diagnostics.
+fn spawn_reader(
+    mut stdout: tokio::process::ChildStdout,
+    pending: PendingMap,
+    diagnostics: DiagnosticsMap,
+) {
+    tokio::spawn(async move {
+        let mut buffer: Vec<u8> = Vec::new();
+        let mut chunk = [0u8; 8192];
+        loop {
+            match stdout.read(&mut chunk).await {
+                Ok(0) | Err(_) => break,
+                Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+            }
+
+            while let Some(message) = take_message(&mut buffer) {
+                dispatch(message, &pending, &diagnostics).await;
+            }
+        }
+        // Server exited: fail any requests still waiting
+        pending.lock().await.clear();
+    });
+}
+
+/// Extract one complete Content-Length framed message from `buffer`,
+/// or None if more bytes are needed.
+fn take_message(buffer: &mut Vec<u8>) -> Option<Value> {
+    let header_end = buffer.windows(4).position(|w| w == b"\r\n\r\n")?;
+    let headers = String::from_utf8_lossy(&buffer[..header_end]);
+    let content_length: usize = headers.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.eq_ignore_ascii_case("Content-Length")
+            .then(|| value.trim().parse().ok())?
+    })?;
+
+    let body_start = header_end + 4;
+    if buffer.len() < body_start + content_length {
+        return None;
+    }
+
+    let body = buffer[body_start..body_start + content_length].to_vec();
+    buffer.drain(..body_start + content_length);
+    serde_json::from_slice(&body).ok()
+}
+
+async fn dispatch(message: Value, pending: &PendingMap, diagnostics: &DiagnosticsMap) {
+    // Response to one of our requests
+    if let Some(id) = message.get("id").and_then(|id| id.as_i64())
+        && message.get("method").is_none()
+    {
+        if let Some(tx) = pending.lock().await.remove(&id) {
+            let _ = tx.send(message);
+        }
+        return;
+    }
+
+    match message.get("method").and_then(|m| m.as_str()) {
+        Some("textDocument/publishDiagnostics") => {
+            if let Some(params) = message.get("params")
+                && let Some(uri) = params.get("uri").and_then(|u| u.as_str())
+            {
+                let diags = params.get("diagnostics").cloned().unwrap_or(json!([]));
+                diagnostics.lock().await.insert(uri.to_string(), diags);
+            }
+        }
+        // Server-to-client requests (e.g. workspace/configuration) aren't
+        // needed for our operations; everything else is logged and dropped
+        Some(method) => {
+            tracing::debug!("Ignoring language server message: {}", method);
+        }
+        None => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_message_complete_frame() {
+        let body = r#"{"jsonrpc":"2.0","id":1,"result":null}"#;
+        let mut buffer = format!("Content-Length: {}\r\n\r\n{}", body.len(), body).into_bytes();
+
+        let message = take_message(&mut buffer).unwrap();
+        assert_eq!(message["id"], 1);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_take_message_partial_frame() {
+        let mut buffer = b"Content-Length: 100\r\n\r\n{\"partial\":".to_vec();
+        assert!(take_message(&mut buffer).is_none());
+        // Buffer untouched so the rest can arrive
+        assert!(!buffer.is_empty());
+    }
+
+    #[test]
+    fn test_take_message_two_frames() {
+        let a = r#"{"id":1}"#;
+        let b = r#"{"id":2}"#;
+        let mut buffer = format!(
+            "Content-Length: {}\r\n\r\n{}Content-Length: {}\r\n\r\n{}",
+            a.len(),
+            a,
+            b.len(),
+            b
+        )
+        .into_bytes();
+
+        assert_eq!(take_message(&mut buffer).unwrap()["id"], 1);
+        assert_eq!(take_message(&mut buffer).unwrap()["id"], 2);
+        assert!(take_message(&mut buffer).is_none());
+    }
+
+    #[test]
+    fn test_take_message_extra_headers() {
+        let body = r#"{"id":3}"#;
+        let mut buffer = format!(
+            "Content-Type: application/vscode-jsonrpc\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        )
+        .into_bytes();
+
+        assert_eq!(take_message(&mut buffer).unwrap()["id"], 3);
+    }
+
+    #[test]
+    fn test_path_to_uri() {
+        assert_eq!(
+            path_to_uri(Path::new("/home/user/main.rs")),
+            "file:///home/user/main.rs"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_spawn_missing_server_reports_not_installed() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = LspClient::spawn(
+            &["definitely-not-a-language-server".to_string()],
+            dir.path(),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.contains("not installed"), "error: {err}");
+    }
+}
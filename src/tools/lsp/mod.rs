@@ -0,0 +1,855 @@
+//! Code intelligence via the Language Server Protocol.
+//!
+//! Grep-based exploration misses semantics: a symbol's definition, its real
+//! references, and type information all require a language server. The `lsp`
+//! tool spawns the configured server for a file's language (rust-analyzer,
+//! pyright, ...) on first use, keeps it running for the session, and exposes
+//! `definition`, `references`, `hover`, `diagnostics`, and `rename`
+//! operations over it. Positions use the same 1-indexed lines (and columns)
+//! as `read_file` output.
+
+mod client;
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+
+use async_trait::async_trait;
+use colored::Colorize;
+use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
+use serde_json::{Value, json};
+use tokio::sync::{Mutex, mpsc};
+use tracing::instrument;
+
+use crate::agent::AgentEvent;
+use crate::tools::{ToolEmitter, error_codes, error_response, resolve_and_validate_path};
+use client::{LspClient, path_to_uri};
+
+/// How long to wait for pushed diagnostics after syncing a document.
+const DIAGNOSTICS_WAIT: tokio::time::Duration = tokio::time::Duration::from_secs(10);
+
+/// `[lsp]` section of config.toml: per-language server command overrides,
+/// e.g. `servers = { rust = "rust-analyzer", python = "pyright-langserver --stdio" }`.
+/// Unset languages fall back to the built-in commands.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct LspConfigToml {
+    #[serde(default)]
+    pub servers: HashMap<String, String>,
+}
+
+/// One language server per (language, workspace root), kept alive across
+/// calls - servers are expensive to start and index lazily.
+static CLIENTS: LazyLock<Mutex<HashMap<String, Arc<Mutex<LspClient>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// LSP language ID for a file, by extension.
+fn language_for_path(path: &Path) -> Option<&'static str> {
+    match path.extension()?.to_str()? {
+        "rs" => Some("rust"),
+        "py" | "pyi" => Some("python"),
+        "ts" | "tsx" => Some("typescript"),
+        "js" | "jsx" => Some("javascript"),
+        "go" => Some("go"),
+        _ => None,
+    }
+}
+
+/// Server command for a language: configured override, else built-in.
+fn server_command(language: &str, config: &LspConfigToml) -> Option<Vec<String>> {
+    if let Some(command) = config.servers.get(language) {
+        let parts: Vec<String> = command.split_whitespace().map(|s| s.to_string()).collect();
+        return (!parts.is_empty()).then_some(parts);
+    }
+    let builtin: &[&str] = match language {
+        "rust" => &["rust-analyzer"],
+        "python" => &["pyright-langserver", "--stdio"],
+        "typescript" | "javascript" => &["typescript-language-server", "--stdio"],
+        "go" => &["gopls"],
+        _ => return None,
+    };
+    Some(builtin.iter().map(|s| s.to_string()).collect())
+}
+
+/// Byte offset of a 0-indexed LSP position in `text`.
+///
+/// LSP columns count UTF-16 code units; counting chars is close enough for
+/// source code and errs on the safe side for applying edits.
+fn offset_for_position(text: &str, line: u32, character: u32) -> usize {
+    let mut offset = 0;
+    for (i, l) in text.split_inclusive('\n').enumerate() {
+        if i as u32 == line {
+            let column: usize = l
+                .chars()
+                .take(character as usize)
+                .map(|c| c.len_utf8())
+                .sum();
+            return offset + column.min(l.len());
+        }
+        offset += l.len();
+    }
+    text.len()
+}
+
+/// Apply LSP text edits to `text`, last-to-first so earlier offsets stay valid.
+fn apply_text_edits(text: &str, edits: &[Value]) -> String {
+    let mut edits: Vec<(usize, usize, String)> = edits
+        .iter()
+        .filter_map(|edit| {
+            let range = edit.get("range")?;
+            let start = range.get("start")?;
+            let end = range.get("end")?;
+            let start_offset = offset_for_position(
+                text,
+                start.get("line")?.as_u64()? as u32,
+                start.get("character")?.as_u64()? as u32,
+            );
+            let end_offset = offset_for_position(
+                text,
+                end.get("line")?.as_u64()? as u32,
+                end.get("character")?.as_u64()? as u32,
+            );
+            let new_text = edit.get("newText")?.as_str()?.to_string();
+            Some((start_offset, end_offset, new_text))
+        })
+        .collect();
+    edits.sort_by(|a, b| b.0.cmp(&a.0));
+
+    let mut result = text.to_string();
+    for (start, end, new_text) in edits {
+        if start <= end && end <= result.len() {
+            result.replace_range(start..end, &new_text);
+        }
+    }
+    result
+}
+
+/// Normalize a Location / Location[] / LocationLink[] result to
+/// `[{file, line, character}]` with 1-indexed positions.
+fn normalize_locations(result: &Value) -> Vec<Value> {
+    let items: Vec<&Value> = match result {
+        Value::Array(arr) => arr.iter().collect(),
+        Value::Object(_) => vec![result],
+        _ => vec![],
+    };
+    items
+        .iter()
+        .filter_map(|item| {
+            // LocationLink uses targetUri/targetRange; Location uses uri/range
+            let uri = item
+                .get("uri")
+                .or_else(|| item.get("targetUri"))?
+                .as_str()?;
+            let range = item
+                .get("range")
+                .or_else(|| item.get("targetSelectionRange"))?;
+            let start = range.get("start")?;
+            Some(json!({
+                "file": uri.strip_prefix("file://").unwrap_or(uri),
+                "line": start.get("line")?.as_u64()? + 1,
+                "character": start.get("character")?.as_u64()? + 1,
+            }))
+        })
+        .collect()
+}
+
+/// Flatten LSP hover contents (MarkupContent, MarkedString, or arrays of
+/// either) to plain text.
+fn hover_text(contents: &Value) -> String {
+    match contents {
+        Value::String(s) => s.clone(),
+        Value::Array(arr) => arr
+            .iter()
+            .map(hover_text)
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Value::Object(obj) => obj
+            .get("value")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        _ => String::new(),
+    }
+}
+
+fn severity_name(severity: u64) -> &'static str {
+    match severity {
+        1 => "error",
+        2 => "warning",
+        3 => "information",
+        4 => "hint",
+        _ => "unknown",
+    }
+}
+
+pub struct LspTool {
+    cwd: PathBuf,
+    allowed_paths: Vec<PathBuf>,
+    events_tx: Option<mpsc::Sender<AgentEvent>>,
+    config: LspConfigToml,
+    dry_run: bool,
+}
+
+impl ToolEmitter for LspTool {
+    fn events_tx(&self) -> &Option<mpsc::Sender<AgentEvent>> {
+        &self.events_tx
+    }
+}
+
+impl LspTool {
+    pub fn new(
+        cwd: PathBuf,
+        allowed_paths: Vec<PathBuf>,
+        events_tx: Option<mpsc::Sender<AgentEvent>>,
+    ) -> Self {
+        Self {
+            cwd,
+            allowed_paths,
+            events_tx,
+            config: LspConfigToml::default(),
+            dry_run: false,
+        }
+    }
+
+    /// Set per-language server command overrides from the `[lsp]` config
+    /// section.
+    pub fn with_config(mut self, config: LspConfigToml) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// In dry-run mode `rename` reports its proposed edits without writing.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Get (or spawn) the client for `language`, rooted at cwd.
+    async fn client(&self, language: &str) -> Result<Arc<Mutex<LspClient>>, String> {
+        let key = format!("{}:{}", language, self.cwd.display());
+        let mut clients = CLIENTS.lock().await;
+
+        if let Some(client) = clients.get(&key) {
+            if client.lock().await.is_alive() {
+                return Ok(client.clone());
+            }
+            // Server died; drop it and respawn below
+            clients.remove(&key);
+        }
+
+        let command = server_command(language, &self.config)
+            .ok_or_else(|| format!("No language server configured for '{}'", language))?;
+        self.emit(&format!(
+            "  {}",
+            format!("starting {} ({})", command[0], language).dimmed()
+        ));
+        let client = Arc::new(Mutex::new(LspClient::spawn(&command, &self.cwd).await?));
+        clients.insert(key, client.clone());
+        Ok(client)
+    }
+
+    /// Apply a WorkspaceEdit's changes to disk. Returns per-file edit counts.
+    fn apply_workspace_edit(&self, edit: &Value) -> Result<Vec<Value>, Value> {
+        // WorkspaceEdit comes as either `changes: {uri: edits}` or
+        // `documentChanges: [{textDocument: {uri}, edits}]`
+        let mut per_file: Vec<(String, Vec<Value>)> = Vec::new();
+        if let Some(changes) = edit.get("changes").and_then(|c| c.as_object()) {
+            for (uri, edits) in changes {
+                per_file.push((
+                    uri.clone(),
+                    edits.as_array().cloned().unwrap_or_default(),
+                ));
+            }
+        } else if let Some(doc_changes) = edit.get("documentChanges").and_then(|c| c.as_array()) {
+            for change in doc_changes {
+                if let Some(uri) = change
+                    .pointer("/textDocument/uri")
+                    .and_then(|u| u.as_str())
+                {
+                    per_file.push((
+                        uri.to_string(),
+                        change
+                            .get("edits")
+                            .and_then(|e| e.as_array())
+                            .cloned()
+                            .unwrap_or_default(),
+                    ));
+                }
+            }
+        }
+
+        let mut results = Vec::new();
+        for (uri, edits) in per_file {
+            let file_path = uri.strip_prefix("file://").unwrap_or(&uri).to_string();
+            let path = resolve_and_validate_path(&file_path, &self.cwd, &self.allowed_paths)
+                .map_err(|e| {
+                    error_response(&e, error_codes::ACCESS_DENIED, json!({ "file_path": file_path }))
+                })?;
+            let text = std::fs::read_to_string(&path).map_err(|e| {
+                error_response(
+                    &format!("Failed to read {}: {}", file_path, e),
+                    error_codes::IO_ERROR,
+                    json!({ "file_path": file_path }),
+                )
+            })?;
+            let new_text = apply_text_edits(&text, &edits);
+            if !self.dry_run {
+                std::fs::write(&path, &new_text).map_err(|e| {
+                    error_response(
+                        &format!("Failed to write {}: {}", file_path, e),
+                        error_codes::IO_ERROR,
+                        json!({ "file_path": file_path }),
+                    )
+                })?;
+            }
+            results.push(json!({ "file": file_path, "edits": edits.len() }));
+        }
+        Ok(results)
+    }
+}
+
+#[async_trait]
+impl CallableFunction for LspTool {
+    fn declaration(&self) -> FunctionDeclaration {
+        FunctionDeclaration::new(
+            "lsp".to_string(),
+            "Code intelligence via a language server: find where a symbol is defined, list its references, show type/doc info, report diagnostics, or rename it across the workspace. Positions are 1-indexed, matching read_file line numbers. Returns: {operation, locations|hover|diagnostics|files_changed, success}".to_string(),
+            FunctionParameters::new(
+                "object".to_string(),
+                json!({
+                    "operation": {
+                        "type": "string",
+                        "description": "One of: definition, references, hover, diagnostics, rename"
+                    },
+                    "file_path": {
+                        "type": "string",
+                        "description": "File to operate on (relative or absolute)"
+                    },
+                    "line": {
+                        "type": "integer",
+                        "description": "1-indexed line of the symbol (required for all operations except diagnostics)"
+                    },
+                    "character": {
+                        "type": "integer",
+                        "description": "1-indexed column of the symbol (required for all operations except diagnostics)"
+                    },
+                    "new_name": {
+                        "type": "string",
+                        "description": "New symbol name (rename only)"
+                    }
+                }),
+                vec!["operation".to_string(), "file_path".to_string()],
+            ),
+        )
+    }
+
+    #[instrument(skip(self, args))]
+    async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        let operation = args
+            .get("operation")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing operation".to_string()))?;
+        let file_path = args
+            .get("file_path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing file_path".to_string()))?;
+
+        if !matches!(
+            operation,
+            "definition" | "references" | "hover" | "diagnostics" | "rename"
+        ) {
+            return Ok(error_response(
+                &format!(
+                    "Unknown operation '{}': use definition, references, hover, diagnostics, or rename",
+                    operation
+                ),
+                error_codes::INVALID_ARGUMENT,
+                json!({ "operation": operation }),
+            ));
+        }
+
+        let path = match resolve_and_validate_path(file_path, &self.cwd, &self.allowed_paths) {
+            Ok(p) => p,
+            Err(e) => {
+                return Ok(error_response(
+                    &e,
+                    error_codes::ACCESS_DENIED,
+                    json!({ "file_path": file_path }),
+                ));
+            }
+        };
+
+        let Some(language) = language_for_path(&path) else {
+            return Ok(error_response(
+                &format!("No language server mapping for '{}'", file_path),
+                error_codes::INVALID_ARGUMENT,
+                json!({ "file_path": file_path }),
+            ));
+        };
+
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) => {
+                return Ok(error_response(
+                    &format!("Failed to read {}: {}", file_path, e),
+                    error_codes::IO_ERROR,
+                    json!({ "file_path": file_path }),
+                ));
+            }
+        };
+
+        // 1-indexed (read_file convention) -> 0-indexed (LSP)
+        let position = || -> Result<Value, Value> {
+            let line = args.get("line").and_then(|v| v.as_u64()).ok_or_else(|| {
+                error_response(
+                    &format!("'{}' requires a line", operation),
+                    error_codes::INVALID_ARGUMENT,
+                    json!({ "operation": operation }),
+                )
+            })?;
+            let character = args
+                .get("character")
+                .and_then(|v| v.as_u64())
+                .ok_or_else(|| {
+                    error_response(
+                        &format!("'{}' requires a character", operation),
+                        error_codes::INVALID_ARGUMENT,
+                        json!({ "operation": operation }),
+                    )
+                })?;
+            Ok(json!({
+                "line": line.saturating_sub(1),
+                "character": character.saturating_sub(1)
+            }))
+        };
+
+        let client = match self.client(language).await {
+            Ok(client) => client,
+            Err(e) => {
+                return Ok(error_response(
+                    &e,
+                    if e.contains("not installed") {
+                        error_codes::NOT_FOUND
+                    } else {
+                        error_codes::IO_ERROR
+                    },
+                    json!({ "language": language }),
+                ));
+            }
+        };
+        let mut client = client.lock().await;
+
+        let uri = path_to_uri(&path);
+        if let Err(e) = client.sync_document(&path, language, &text).await {
+            return Ok(error_response(&e, error_codes::IO_ERROR, json!({})));
+        }
+
+        let doc_position = |position: Value| {
+            json!({
+                "textDocument": { "uri": uri },
+                "position": position
+            })
+        };
+
+        let result = match operation {
+            "definition" | "references" => {
+                let position = match position() {
+                    Ok(p) => p,
+                    Err(e) => return Ok(e),
+                };
+                let (method, params) = if operation == "definition" {
+                    ("textDocument/definition", doc_position(position))
+                } else {
+                    let mut params = doc_position(position);
+                    params["context"] = json!({ "includeDeclaration": true });
+                    ("textDocument/references", params)
+                };
+                match client.request(method, params).await {
+                    Ok(response) => {
+                        let locations = normalize_locations(&response);
+                        self.emit(&format!(
+                            "  {}",
+                            format!("{} location(s)", locations.len()).dimmed()
+                        ));
+                        json!({
+                            "operation": operation,
+                            "locations": locations,
+                            "success": true
+                        })
+                    }
+                    Err(e) => error_response(&e, error_codes::IO_ERROR, json!({})),
+                }
+            }
+            "hover" => {
+                let position = match position() {
+                    Ok(p) => p,
+                    Err(e) => return Ok(e),
+                };
+                match client
+                    .request("textDocument/hover", doc_position(position))
+                    .await
+                {
+                    Ok(response) => {
+                        let hover = response
+                            .get("contents")
+                            .map(hover_text)
+                            .unwrap_or_default();
+                        json!({ "operation": operation, "hover": hover, "success": true })
+                    }
+                    Err(e) => error_response(&e, error_codes::IO_ERROR, json!({})),
+                }
+            }
+            "diagnostics" => {
+                // Diagnostics are pushed, not pulled: wait for the server to
+                // publish for this document after the sync
+                let deadline = tokio::time::Instant::now() + DIAGNOSTICS_WAIT;
+                let diagnostics = loop {
+                    if let Some(diags) = client.diagnostics_for(&uri).await {
+                        break diags;
+                    }
+                    if tokio::time::Instant::now() >= deadline {
+                        break json!([]);
+                    }
+                    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+                };
+                let diagnostics: Vec<Value> = diagnostics
+                    .as_array()
+                    .cloned()
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|d| {
+                        let start = d.pointer("/range/start")?;
+                        Some(json!({
+                            "line": start.get("line")?.as_u64()? + 1,
+                            "character": start.get("character")?.as_u64()? + 1,
+                            "severity": severity_name(
+                                d.get("severity").and_then(|s| s.as_u64()).unwrap_or(0)
+                            ),
+                            "message": d.get("message")?.as_str()?,
+                        }))
+                    })
+                    .collect();
+                self.emit(&format!(
+                    "  {}",
+                    format!("{} diagnostic(s)", diagnostics.len()).dimmed()
+                ));
+                json!({
+                    "operation": operation,
+                    "diagnostics": diagnostics,
+                    "success": true
+                })
+            }
+            "rename" => {
+                let position = match position() {
+                    Ok(p) => p,
+                    Err(e) => return Ok(e),
+                };
+                let Some(new_name) = args.get("new_name").and_then(|v| v.as_str()) else {
+                    return Ok(error_response(
+                        "'rename' requires new_name",
+                        error_codes::INVALID_ARGUMENT,
+                        json!({ "operation": operation }),
+                    ));
+                };
+                let mut params = doc_position(position);
+                params["newName"] = json!(new_name);
+                match client.request("textDocument/rename", params).await {
+                    Ok(edit) if !edit.is_null() => match self.apply_workspace_edit(&edit) {
+                        Ok(files_changed) => {
+                            let total: u64 = files_changed
+                                .iter()
+                                .filter_map(|f| f.get("edits").and_then(|e| e.as_u64()))
+                                .sum();
+                            self.emit(&format!(
+                                "  {}",
+                                format!(
+                                    "{} edit(s) across {} file(s){}",
+                                    total,
+                                    files_changed.len(),
+                                    if self.dry_run { " (dry run)" } else { "" }
+                                )
+                                .dimmed()
+                            ));
+                            json!({
+                                "operation": operation,
+                                "files_changed": files_changed,
+                                "applied": !self.dry_run,
+                                "success": true
+                            })
+                        }
+                        Err(e) => e,
+                    },
+                    Ok(_) => error_response(
+                        "Rename not possible at this position",
+                        error_codes::INVALID_ARGUMENT,
+                        json!({ "file_path": file_path }),
+                    ),
+                    Err(e) => error_response(&e, error_codes::IO_ERROR, json!({})),
+                }
+            }
+            _ => unreachable!("operation validated above"),
+        };
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_language_for_path() {
+        assert_eq!(language_for_path(Path::new("src/main.rs")), Some("rust"));
+        assert_eq!(language_for_path(Path::new("app.py")), Some("python"));
+        assert_eq!(language_for_path(Path::new("a/b.tsx")), Some("typescript"));
+        assert_eq!(language_for_path(Path::new("x.go")), Some("go"));
+        assert_eq!(language_for_path(Path::new("notes.txt")), None);
+        assert_eq!(language_for_path(Path::new("Makefile")), None);
+    }
+
+    #[test]
+    fn test_server_command_builtin_and_override() {
+        let config = LspConfigToml::default();
+        assert_eq!(
+            server_command("rust", &config),
+            Some(vec!["rust-analyzer".to_string()])
+        );
+        assert_eq!(
+            server_command("python", &config),
+            Some(vec!["pyright-langserver".to_string(), "--stdio".to_string()])
+        );
+        assert_eq!(server_command("cobol", &config), None);
+
+        let mut config = LspConfigToml::default();
+        config
+            .servers
+            .insert("rust".to_string(), "my-analyzer --stdio".to_string());
+        assert_eq!(
+            server_command("rust", &config),
+            Some(vec!["my-analyzer".to_string(), "--stdio".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_offset_for_position() {
+        let text = "fn main() {\n    let x = 1;\n}\n";
+        assert_eq!(offset_for_position(text, 0, 0), 0);
+        assert_eq!(offset_for_position(text, 0, 3), 3);
+        assert_eq!(offset_for_position(text, 1, 4), 16);
+        // Past end of line clamps to the line
+        assert_eq!(offset_for_position(text, 0, 999), 12);
+        // Past end of file clamps to the text
+        assert_eq!(offset_for_position(text, 99, 0), text.len());
+    }
+
+    #[test]
+    fn test_apply_text_edits_reverse_order() {
+        let text = "let old = old + 1;";
+        let edits = vec![
+            json!({
+                "range": {"start": {"line": 0, "character": 4}, "end": {"line": 0, "character": 7}},
+                "newText": "renamed"
+            }),
+            json!({
+                "range": {"start": {"line": 0, "character": 10}, "end": {"line": 0, "character": 13}},
+                "newText": "renamed"
+            }),
+        ];
+        assert_eq!(apply_text_edits(text, &edits), "let renamed = renamed + 1;");
+    }
+
+    #[test]
+    fn test_normalize_locations_variants() {
+        // Single Location
+        let single = json!({
+            "uri": "file:///tmp/a.rs",
+            "range": {"start": {"line": 4, "character": 7}, "end": {"line": 4, "character": 10}}
+        });
+        let locations = normalize_locations(&single);
+        assert_eq!(locations.len(), 1);
+        assert_eq!(locations[0]["file"], "/tmp/a.rs");
+        assert_eq!(locations[0]["line"], 5);
+        assert_eq!(locations[0]["character"], 8);
+
+        // LocationLink array
+        let links = json!([{
+            "targetUri": "file:///tmp/b.rs",
+            "targetSelectionRange": {"start": {"line": 0, "character": 0}, "end": {"line": 0, "character": 3}}
+        }]);
+        assert_eq!(normalize_locations(&links)[0]["file"], "/tmp/b.rs");
+
+        assert!(normalize_locations(&Value::Null).is_empty());
+    }
+
+    #[test]
+    fn test_hover_text_variants() {
+        assert_eq!(hover_text(&json!("plain")), "plain");
+        assert_eq!(
+            hover_text(&json!({"kind": "markdown", "value": "**bold**"})),
+            "**bold**"
+        );
+        assert_eq!(
+            hover_text(&json!(["first", {"value": "second"}])),
+            "first\nsecond"
+        );
+    }
+
+    fn tool_for(dir: &Path) -> LspTool {
+        LspTool::new(dir.to_path_buf(), vec![dir.to_path_buf()], None)
+    }
+
+    #[tokio::test]
+    async fn test_unknown_operation() {
+        let dir = tempdir().unwrap();
+        let result = tool_for(dir.path())
+            .call(json!({ "operation": "format", "file_path": "a.rs" }))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+        assert!(result["error"].as_str().unwrap().contains("definition"));
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_language() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "hello").unwrap();
+        let result = tool_for(dir.path())
+            .call(json!({ "operation": "hover", "file_path": "notes.txt" }))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+        assert!(result["error"].as_str().unwrap().contains("language server"));
+    }
+
+    #[tokio::test]
+    async fn test_server_not_installed() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("a.rs"), "fn main() {}\n").unwrap();
+        let mut config = LspConfigToml::default();
+        config
+            .servers
+            .insert("rust".to_string(), "definitely-not-a-server".to_string());
+        let tool = tool_for(dir.path()).with_config(config);
+        let result = tool
+            .call(json!({
+                "operation": "definition",
+                "file_path": "a.rs",
+                "line": 1,
+                "character": 4
+            }))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::NOT_FOUND, "got: {result}");
+    }
+
+    /// A minimal scripted language server: answers initialize, echoes a
+    /// fixed definition location, and publishes one diagnostic on didOpen.
+    fn write_fake_server(dir: &Path) -> PathBuf {
+        let script = r#"#!/usr/bin/env python3
+import json, sys
+
+def read_msg():
+    length = None
+    while True:
+        line = sys.stdin.buffer.readline()
+        if not line:
+            return None
+        if line == b"\r\n":
+            break
+        if line.lower().startswith(b"content-length:"):
+            length = int(line.split(b":")[1])
+    return json.loads(sys.stdin.buffer.read(length))
+
+def send(msg):
+    body = json.dumps(msg).encode()
+    sys.stdout.buffer.write(b"Content-Length: %d\r\n\r\n" % len(body) + body)
+    sys.stdout.buffer.flush()
+
+while True:
+    msg = read_msg()
+    if msg is None:
+        break
+    method = msg.get("method", "")
+    if "id" in msg:
+        if method == "initialize":
+            send({"jsonrpc": "2.0", "id": msg["id"], "result": {"capabilities": {}}})
+        elif method == "textDocument/definition":
+            uri = msg["params"]["textDocument"]["uri"]
+            send({"jsonrpc": "2.0", "id": msg["id"], "result": [{
+                "uri": uri,
+                "range": {"start": {"line": 0, "character": 3},
+                          "end": {"line": 0, "character": 7}}}]})
+        else:
+            send({"jsonrpc": "2.0", "id": msg["id"], "result": None})
+    elif method == "textDocument/didOpen":
+        uri = msg["params"]["textDocument"]["uri"]
+        send({"jsonrpc": "2.0", "method": "textDocument/publishDiagnostics",
+              "params": {"uri": uri, "diagnostics": [{
+                  "message": "fake warning", "severity": 2,
+                  "range": {"start": {"line": 0, "character": 0},
+                            "end": {"line": 0, "character": 1}}}]}})
+"#;
+        let path = dir.join("fake_server.py");
+        std::fs::write(&path, script).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn test_definition_against_scripted_server() {
+        let dir = tempdir().unwrap();
+        let dir = std::fs::canonicalize(dir.path()).unwrap();
+        std::fs::write(dir.join("a.rs"), "fn main() {}\n").unwrap();
+        let server = write_fake_server(&dir);
+
+        let mut config = LspConfigToml::default();
+        config
+            .servers
+            .insert("rust".to_string(), server.to_string_lossy().to_string());
+
+        let tool = tool_for(&dir).with_config(config);
+        let result = tool
+            .call(json!({
+                "operation": "definition",
+                "file_path": "a.rs",
+                "line": 1,
+                "character": 4
+            }))
+            .await
+            .unwrap();
+
+        assert!(result["success"].as_bool().unwrap(), "got: {result}");
+        let locations = result["locations"].as_array().unwrap();
+        assert_eq!(locations.len(), 1);
+        assert_eq!(locations[0]["line"], 1);
+        assert_eq!(locations[0]["character"], 4);
+    }
+
+    #[tokio::test]
+    async fn test_diagnostics_against_scripted_server() {
+        let dir = tempdir().unwrap();
+        let dir = std::fs::canonicalize(dir.path()).unwrap();
+        std::fs::write(dir.join("b.rs"), "fn main() {}\n").unwrap();
+        let server = write_fake_server(&dir);
+
+        let mut config = LspConfigToml::default();
+        config
+            .servers
+            .insert("rust".to_string(), server.to_string_lossy().to_string());
+
+        let tool = tool_for(&dir).with_config(config);
+        let result = tool
+            .call(json!({ "operation": "diagnostics", "file_path": "b.rs" }))
+            .await
+            .unwrap();
+
+        assert!(result["success"].as_bool().unwrap(), "got: {result}");
+        let diagnostics = result["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 1, "got: {result}");
+        assert_eq!(diagnostics[0]["severity"], "warning");
+        assert_eq!(diagnostics[0]["message"], "fake warning");
+    }
+}
@@ -0,0 +1,387 @@
+//! Persistent memory tool: durable notes that survive across sessions.
+//!
+//! Notes live in `~/.clemini/memory/<project-hash>.md`, keyed by the
+//! workspace directory, as `## key` markdown sections. The file is
+//! injected into the system prompt at startup (alongside CLAUDE.md), so
+//! anything stored here - build quirks, decisions, gotchas - is in
+//! context on the next session without the model having to rediscover it.
+
+use async_trait::async_trait;
+use colored::Colorize;
+use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use tokio::sync::mpsc;
+use tracing::instrument;
+
+use super::{ToolEmitter, error_codes, error_response};
+use crate::agent::AgentEvent;
+
+/// Stable 64-bit FNV-1a hash. `DefaultHasher` is explicitly not stable
+/// across Rust releases, and the files keyed by these hashes must outlive
+/// clemini upgrades.
+pub(crate) fn fnv1a_hex(input: &str) -> String {
+    fnv1a_hex_bytes(input.as_bytes())
+}
+
+/// Byte-level variant, for hashing raw file contents (`tools::file_info`).
+pub(crate) fn fnv1a_hex_bytes(input: &[u8]) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in input {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    format!("{hash:016x}")
+}
+
+/// Hash a workspace path to a stable file key. Also used by todo
+/// persistence (`tools::todo_write`) and the per-project REPL history
+/// to key their files.
+pub fn project_hash(path: &Path) -> String {
+    fnv1a_hex(&path.to_string_lossy())
+}
+
+/// Path of the memory file for a workspace. The cwd is canonicalized so
+/// `/proj` and `/proj/.` map to the same file.
+pub fn memory_file_path(cwd: &Path) -> PathBuf {
+    let canonical = cwd.canonicalize().unwrap_or_else(|_| cwd.to_path_buf());
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".clemini")
+        .join("memory")
+        .join(format!("{}.md", project_hash(&canonical)))
+}
+
+/// Load the workspace's memory file for system prompt injection.
+/// Returns `None` when there is no memory or it's empty.
+pub fn load_memory(cwd: &Path) -> Option<String> {
+    let contents = std::fs::read_to_string(memory_file_path(cwd)).ok()?;
+    let trimmed = contents.trim();
+    (!trimmed.is_empty()).then(|| trimmed.to_string())
+}
+
+/// Parse `## key` sections into (key, content) pairs, preserving order.
+/// Text before the first heading is ignored (there shouldn't be any -
+/// the tool only writes headed sections).
+fn parse_sections(text: &str) -> Vec<(String, String)> {
+    let mut sections: Vec<(String, String)> = Vec::new();
+    for line in text.lines() {
+        if let Some(key) = line.strip_prefix("## ") {
+            sections.push((key.trim().to_string(), String::new()));
+        } else if let Some((_, content)) = sections.last_mut() {
+            content.push_str(line);
+            content.push('\n');
+        }
+    }
+    for (_, content) in &mut sections {
+        *content = content.trim().to_string();
+    }
+    sections
+}
+
+fn render_sections(sections: &[(String, String)]) -> String {
+    let mut out = String::new();
+    for (key, content) in sections {
+        out.push_str(&format!("## {}\n\n{}\n\n", key, content));
+    }
+    out
+}
+
+pub struct MemoryTool {
+    cwd: PathBuf,
+    events_tx: Option<mpsc::Sender<AgentEvent>>,
+    dry_run: bool,
+}
+
+impl MemoryTool {
+    pub fn new(cwd: PathBuf, events_tx: Option<mpsc::Sender<AgentEvent>>) -> Self {
+        Self {
+            cwd,
+            events_tx,
+            dry_run: false,
+        }
+    }
+
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    fn read_sections(&self) -> Vec<(String, String)> {
+        std::fs::read_to_string(memory_file_path(&self.cwd))
+            .map(|text| parse_sections(&text))
+            .unwrap_or_default()
+    }
+
+    fn write_sections(&self, sections: &[(String, String)]) -> Result<(), String> {
+        let path = memory_file_path(&self.cwd);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create memory directory: {}", e))?;
+        }
+        std::fs::write(&path, render_sections(sections))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+impl ToolEmitter for MemoryTool {
+    fn events_tx(&self) -> &Option<mpsc::Sender<AgentEvent>> {
+        &self.events_tx
+    }
+}
+
+#[async_trait]
+impl CallableFunction for MemoryTool {
+    fn declaration(&self) -> FunctionDeclaration {
+        FunctionDeclaration::new(
+            "memory".to_string(),
+            "Store and retrieve durable notes that persist across sessions for this workspace (build quirks, decisions, gotchas). Stored notes are injected into the system prompt on the next session. 'write' replaces the note under key; 'read' without a key returns everything. Returns: {keys} for list, {key, content} for read, {key, success} for write/delete".to_string(),
+            FunctionParameters::new(
+                "object".to_string(),
+                json!({
+                    "action": {
+                        "type": "string",
+                        "enum": ["read", "write", "delete", "list"],
+                        "description": "What to do with the memory file"
+                    },
+                    "key": {
+                        "type": "string",
+                        "description": "Note name, e.g. 'build' or 'api-quirks'. Required for write and delete; optional for read."
+                    },
+                    "content": {
+                        "type": "string",
+                        "description": "Note body (markdown). Required for write."
+                    }
+                }),
+                vec!["action".to_string()],
+            ),
+        )
+    }
+
+    #[instrument(skip(self, args))]
+    async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        let action = args
+            .get("action")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing action".to_string()))?;
+        let key = args.get("key").and_then(|v| v.as_str());
+        let content = args.get("content").and_then(|v| v.as_str());
+
+        let sections = self.read_sections();
+        match action {
+            "read" => match key {
+                Some(key) => match sections.iter().find(|(k, _)| k == key) {
+                    Some((_, content)) => Ok(json!({"key": key, "content": content})),
+                    None => Ok(error_response(
+                        &format!("No memory stored under '{}'", key),
+                        error_codes::NOT_FOUND,
+                        json!({"key": key, "keys": sections.iter().map(|(k, _)| k).collect::<Vec<_>>()}),
+                    )),
+                },
+                None => Ok(json!({
+                    "sections": sections
+                        .iter()
+                        .map(|(k, c)| json!({"key": k, "content": c}))
+                        .collect::<Vec<_>>(),
+                })),
+            },
+            "list" => Ok(json!({
+                "keys": sections.iter().map(|(k, _)| k).collect::<Vec<_>>(),
+            })),
+            "write" => {
+                let Some(key) = key else {
+                    return Err(FunctionError::ArgumentMismatch(
+                        "Missing key for write".to_string(),
+                    ));
+                };
+                let Some(content) = content else {
+                    return Err(FunctionError::ArgumentMismatch(
+                        "Missing content for write".to_string(),
+                    ));
+                };
+                let mut sections = sections;
+                let replaced = match sections.iter_mut().find(|(k, _)| k == key) {
+                    Some((_, existing)) => {
+                        *existing = content.trim().to_string();
+                        true
+                    }
+                    None => {
+                        sections.push((key.to_string(), content.trim().to_string()));
+                        false
+                    }
+                };
+                if !self.dry_run
+                    && let Err(e) = self.write_sections(&sections)
+                {
+                    return Ok(error_response(
+                        &e,
+                        error_codes::IO_ERROR,
+                        json!({"key": key}),
+                    ));
+                }
+                self.emit(
+                    &format!(
+                        "  {} '{}'",
+                        if replaced { "updated" } else { "remembered" },
+                        key
+                    )
+                    .dimmed()
+                    .to_string(),
+                );
+                let mut response = json!({"key": key, "replaced": replaced, "success": true});
+                if self.dry_run {
+                    response["dry_run"] = json!(true);
+                }
+                Ok(response)
+            }
+            "delete" => {
+                let Some(key) = key else {
+                    return Err(FunctionError::ArgumentMismatch(
+                        "Missing key for delete".to_string(),
+                    ));
+                };
+                let mut sections = sections;
+                let before = sections.len();
+                sections.retain(|(k, _)| k != key);
+                if sections.len() == before {
+                    return Ok(error_response(
+                        &format!("No memory stored under '{}'", key),
+                        error_codes::NOT_FOUND,
+                        json!({"key": key}),
+                    ));
+                }
+                if !self.dry_run
+                    && let Err(e) = self.write_sections(&sections)
+                {
+                    return Ok(error_response(
+                        &e,
+                        error_codes::IO_ERROR,
+                        json!({"key": key}),
+                    ));
+                }
+                self.emit(&format!("  forgot '{}'", key).dimmed().to_string());
+                let mut response = json!({"key": key, "success": true});
+                if self.dry_run {
+                    response["dry_run"] = json!(true);
+                }
+                Ok(response)
+            }
+            other => Ok(error_response(
+                &format!(
+                    "Unknown action '{}'. Use read, write, delete, or list.",
+                    other
+                ),
+                error_codes::INVALID_ARGUMENT,
+                json!({"action": other}),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    // The memory file location is derived from the home directory, so these
+    // tests exercise parse/render round trips and the tool's logic against
+    // whatever file the test environment maps this tempdir to. Each test
+    // uses its own tempdir, which hashes to its own memory file.
+
+    #[test]
+    fn test_project_hash_stable_and_distinct() {
+        let a = project_hash(Path::new("/proj/a"));
+        assert_eq!(a, project_hash(Path::new("/proj/a")));
+        assert_ne!(a, project_hash(Path::new("/proj/b")));
+        assert_eq!(a.len(), 16);
+    }
+
+    #[test]
+    fn test_parse_render_round_trip() {
+        let text =
+            "## build\n\nuse make, not cargo directly\n\n## api\n\nretry on 503\nwith backoff\n\n";
+        let sections = parse_sections(text);
+        assert_eq!(sections.len(), 2);
+        assert_eq!(
+            sections[0],
+            ("build".into(), "use make, not cargo directly".into())
+        );
+        assert_eq!(sections[1].1, "retry on 503\nwith backoff");
+        assert_eq!(render_sections(&sections), text);
+    }
+
+    #[tokio::test]
+    async fn test_memory_write_read_delete() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let tool = MemoryTool::new(cwd.clone(), None);
+
+        let result = tool
+            .call(json!({"action": "write", "key": "build", "content": "make check is fastest"}))
+            .await
+            .unwrap();
+        assert_eq!(result["replaced"], false);
+        assert!(result["success"].as_bool().unwrap());
+
+        let result = tool
+            .call(json!({"action": "read", "key": "build"}))
+            .await
+            .unwrap();
+        assert_eq!(result["content"], "make check is fastest");
+
+        // Overwrite under the same key
+        let result = tool
+            .call(json!({"action": "write", "key": "build", "content": "use make test-all"}))
+            .await
+            .unwrap();
+        assert_eq!(result["replaced"], true);
+        assert_eq!(load_memory(&cwd).unwrap(), "## build\n\nuse make test-all");
+
+        let result = tool
+            .call(json!({"action": "delete", "key": "build"}))
+            .await
+            .unwrap();
+        assert!(result["success"].as_bool().unwrap());
+        assert!(load_memory(&cwd).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_memory_read_missing_key_lists_alternatives() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let tool = MemoryTool::new(cwd.clone(), None);
+
+        tool.call(
+            json!({"action": "write", "key": "decisions", "content": "sqlite over postgres"}),
+        )
+        .await
+        .unwrap();
+        let result = tool
+            .call(json!({"action": "read", "key": "nope"}))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::NOT_FOUND);
+        assert_eq!(result["context"]["keys"], json!(["decisions"]));
+
+        let result = tool.call(json!({"action": "list"})).await.unwrap();
+        assert_eq!(result["keys"], json!(["decisions"]));
+
+        tool.call(json!({"action": "delete", "key": "decisions"}))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_memory_dry_run_does_not_write() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let tool = MemoryTool::new(cwd.clone(), None).with_dry_run(true);
+
+        let result = tool
+            .call(json!({"action": "write", "key": "x", "content": "y"}))
+            .await
+            .unwrap();
+        assert!(result["dry_run"].as_bool().unwrap());
+        assert!(load_memory(&cwd).is_none());
+    }
+}
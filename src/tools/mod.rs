@@ -1,28 +1,58 @@
+mod apply_patch;
+mod archive;
 mod ask_user;
 pub mod background;
 mod bash;
+pub mod checkpoint;
+mod create_directory;
+mod custom;
 mod edit;
+mod edit_lines;
 mod enter_plan_mode;
+mod env_info;
 mod event_bus_tools;
 mod exit_plan_mode;
+mod file_info;
+mod file_ops;
+mod git_commit;
+mod github;
 mod glob;
 mod grep;
+mod http_request;
 mod kill_shell;
+mod lsp;
+pub mod memory;
+mod multi_edit;
+mod notebook;
+pub(crate) mod outline;
 mod read;
+mod read_many;
+pub mod remember;
+mod replace;
+mod request_path_access;
+mod revert_file;
+pub mod review;
+mod run_python;
+mod screenshot;
+mod send_input;
+pub mod stats;
 mod task;
 mod task_output;
 pub mod tasks;
-mod todo_write;
+mod todo_read;
+pub mod todo_write;
+mod watch;
 mod web_fetch;
 mod web_search;
 mod write;
 
 use anyhow::Result;
-use genai_rs::{CallableFunction, ToolService};
+use genai_rs::{CallableFunction, FunctionError, ToolService};
 use serde_json::Value;
 use std::collections::HashSet;
 use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 
 use crate::agent::AgentEvent;
@@ -50,24 +80,53 @@ pub trait ToolEmitter {
     }
 }
 
+pub use apply_patch::ApplyPatchTool;
+pub use archive::ArchiveTool;
 pub use ask_user::AskUserTool;
 pub use bash::BashTool;
+pub use bash::cleanup_sessions as cleanup_shell_sessions;
+pub use bash::{BashSafetyToml, SafetyPolicy};
+pub use create_directory::CreateDirectoryTool;
+pub use custom::{CustomArgDef, CustomTool, CustomToolDef, CustomToolsToml};
 pub use edit::EditTool;
+pub use edit_lines::EditLinesTool;
 pub use enter_plan_mode::EnterPlanModeTool;
+pub use env_info::EnvInfoTool;
 pub use event_bus_tools::{
     EventBusGetEventsTool, EventBusListChannelsTool, EventBusListSessionsTool, EventBusPublishTool,
     EventBusRegisterTool, EventBusUnregisterTool,
 };
 pub use exit_plan_mode::ExitPlanModeTool;
+pub use file_info::FileInfoTool;
+pub use file_ops::{CopyFileTool, DeleteFileTool, MoveFileTool};
+pub use git_commit::GitCommitTool;
+pub use github::GitHubTool;
 pub use glob::GlobTool;
 pub use grep::GrepTool;
+pub use http_request::HttpRequestTool;
 pub use kill_shell::KillShellTool;
+pub use lsp::{LspConfigToml, LspTool};
+pub use memory::MemoryTool;
+pub use multi_edit::MultiEditTool;
+pub use notebook::{NotebookEditTool, NotebookReadTool};
+pub use outline::OutlineTool;
 pub use read::ReadTool;
-pub use task::TaskTool;
+pub use read_many::ReadManyTool;
+pub use remember::RememberTool;
+pub use replace::ReplaceTool;
+pub use request_path_access::RequestPathAccessTool;
+pub use revert_file::RevertFileTool;
+pub use run_python::RunPythonTool;
+pub use screenshot::ScreenshotTool;
+pub use send_input::SendInputTool;
+pub use stats::{ToolStats, ToolStatsMap};
+pub use task::{AgentProfile, TaskLimits, TaskTool};
 pub use task_output::TaskOutputTool;
+pub use todo_read::TodoReadTool;
 pub use todo_write::TodoWriteTool;
+pub use watch::WatchTool;
 pub use web_fetch::WebFetchTool;
-pub use web_search::WebSearchTool;
+pub use web_search::{SearchConfig, WebSearchTool};
 pub use write::WriteTool;
 
 pub const DEFAULT_EXCLUDES: &[&str] = &[".git", "node_modules", "target", "__pycache__", ".venv"];
@@ -81,6 +140,169 @@ pub const MAX_BACKGROUND_BUFFER_LEN: usize = 1_000_000;
 /// Maximum length for suggestion text previews in error messages.
 pub const MAX_SUGGESTION_PREVIEW_LEN: usize = 100;
 
+/// Filter applied to the tool list: an optional allowlist plus a denylist,
+/// both matching declared tool names (e.g., "read_file", "bash", "web_fetch").
+///
+/// The denylist wins over the allowlist. An empty filter permits everything.
+#[derive(Debug, Clone, Default)]
+pub struct ToolFilter {
+    allowed: Option<Vec<String>>,
+    disallowed: Vec<String>,
+}
+
+impl ToolFilter {
+    pub fn new(allowed: Option<Vec<String>>, disallowed: Vec<String>) -> Self {
+        Self {
+            allowed,
+            disallowed,
+        }
+    }
+
+    /// Whether the named tool passes this filter.
+    pub fn permits(&self, name: &str) -> bool {
+        if self.disallowed.iter().any(|n| n == name) {
+            return false;
+        }
+        match &self.allowed {
+            Some(allowed) => allowed.iter().any(|n| n == name),
+            None => true,
+        }
+    }
+
+    /// True when the filter permits every tool (no allowlist, empty denylist).
+    pub fn is_unrestricted(&self) -> bool {
+        self.allowed.is_none() && self.disallowed.is_empty()
+    }
+
+    /// Names in the filter that don't match any declared tool name.
+    /// Used to warn about typos at startup rather than silently ignoring them.
+    pub fn unknown_names(&self, declared: &[&str]) -> Vec<String> {
+        self.allowed
+            .iter()
+            .flatten()
+            .chain(self.disallowed.iter())
+            .filter(|name| !declared.contains(&name.as_str()))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Global permission mode governing mutating tools, selected via the
+/// `--permission-mode` CLI flag or the `/mode` REPL command.
+///
+/// Generalizes the plan-mode read-only classification: `tool_is_read_only()`
+/// decides which tools count as mutating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PermissionMode {
+    /// Run every tool without extra prompting (default).
+    #[default]
+    Auto,
+    /// Prompt the user before each mutating tool call, with a diff preview
+    /// where the tool supports one.
+    Ask,
+    /// Reject mutating tools outright.
+    ReadOnly,
+}
+
+impl PermissionMode {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "auto" => Some(Self::Auto),
+            "ask" => Some(Self::Ask),
+            "read-only" | "readonly" => Some(Self::ReadOnly),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Ask => "ask",
+            Self::ReadOnly => "read-only",
+        }
+    }
+}
+
+/// Per-operation model routing for internal LLM-powered tool operations.
+///
+/// Loaded from the `[models]` section of config.toml, e.g.:
+///
+/// ```toml
+/// [models]
+/// web_fetch = "gemini-flash-lite-latest"
+/// task = "gemini-flash-lite-latest"
+/// ```
+///
+/// Unset operations fall back to their built-in defaults (web_fetch) or the
+/// main conversation model (task subagents).
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct ModelRouting {
+    /// Model for `web_fetch` prompt extraction.
+    pub web_fetch: Option<String>,
+    /// Model passed to `task` subagents via `--model`.
+    pub task: Option<String>,
+    /// Model for `git_commit` message generation.
+    pub git_commit: Option<String>,
+}
+
+/// Per-tool timeouts in seconds, loaded from the `[timeouts]` section of
+/// config.toml, e.g.:
+///
+/// ```toml
+/// [timeouts]
+/// web_fetch = 60
+/// default = 120
+/// ```
+///
+/// A tool's own entry wins over `default`; tools with neither run without a
+/// limit. Bash keeps its separate `bash_timeout` key.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct TimeoutsToml {
+    pub web_fetch: Option<u64>,
+    pub web_search: Option<u64>,
+    pub grep: Option<u64>,
+    pub task: Option<u64>,
+    pub default: Option<u64>,
+}
+
+impl TimeoutsToml {
+    /// Effective timeout for the named tool: its own entry, else `default`.
+    pub fn for_tool(&self, name: &str) -> Option<u64> {
+        let specific = match name {
+            "web_fetch" => self.web_fetch,
+            "web_search" => self.web_search,
+            "grep" => self.grep,
+            "task" => self.task,
+            _ => None,
+        };
+        specific.or(self.default)
+    }
+}
+
+/// Wrap a tool's work in its configured timeout (`None` = no limit).
+/// A timeout surfaces as the standard TIMEOUT error code rather than
+/// stalling the whole interaction on a hung call.
+pub(crate) async fn run_with_timeout<F>(
+    tool_name: &str,
+    timeout_secs: Option<u64>,
+    work: F,
+) -> Result<Value, FunctionError>
+where
+    F: Future<Output = Result<Value, FunctionError>>,
+{
+    let Some(secs) = timeout_secs else {
+        return work.await;
+    };
+    match tokio::time::timeout(std::time::Duration::from_secs(secs), work).await {
+        Ok(result) => result,
+        Err(_) => Ok(error_response(
+            &format!("{tool_name} timed out after {secs}s"),
+            error_codes::TIMEOUT,
+            serde_json::json!({"timeout_secs": secs}),
+        )),
+    }
+}
+
 /// Tool service that provides file and command execution capabilities.
 pub struct CleminiToolService {
     cwd: PathBuf,
@@ -102,6 +324,70 @@ pub struct CleminiToolService {
     /// Only commands in this set can be executed with `confirmed: true`.
     /// This prevents the LLM from bypassing confirmation by simply claiming confirmed=true.
     pending_confirmations: Arc<RwLock<HashSet<String>>>,
+    /// Allow/deny filter applied to `tools()`.
+    /// Uses interior mutability so it can be set after construction without
+    /// churning the constructor signatures; defaults to unrestricted.
+    tool_filter: Arc<RwLock<ToolFilter>>,
+    /// When set, mutating tools (write, edit, bash) simulate their changes -
+    /// reporting diffs and success without touching disk or running commands.
+    dry_run: std::sync::atomic::AtomicBool,
+    /// Per-operation model overrides for internal LLM calls.
+    /// Uses interior mutability so it can be set after construction without
+    /// churning the constructor signatures; defaults to built-in models.
+    model_routing: Arc<RwLock<ModelRouting>>,
+    /// Bash safety patterns: built-in defaults plus user-configured
+    /// additions/exemptions from the `[bash]` config section.
+    /// Uses interior mutability so it can be set after construction without
+    /// churning the constructor signatures; defaults to built-ins only.
+    safety_policy: Arc<RwLock<SafetyPolicy>>,
+    /// Per-language server command overrides from the `[lsp]` config
+    /// section. Uses interior mutability so it can be set after construction
+    /// without churning the constructor signatures; defaults to built-ins.
+    lsp_config: Arc<RwLock<LspConfigToml>>,
+    /// Web search backend selection (`search_provider`/`search_api_key`
+    /// config keys). Defaults to keyless DuckDuckGo.
+    search_config: Arc<RwLock<SearchConfig>>,
+    /// Cache TTL for `web_fetch` in seconds (`web_cache_ttl` config key).
+    /// `None` uses the tool default; 0 disables the cache.
+    web_cache_ttl: Arc<RwLock<Option<u64>>>,
+    /// Whether `web_fetch` may render pages with headless Chromium
+    /// (`web_render` config key, default off).
+    web_render: std::sync::atomic::AtomicBool,
+    /// Host allowlist for `http_request` (`http_allowed_hosts` config key).
+    /// `None` permits every host.
+    http_allowed_hosts: Arc<RwLock<Option<Vec<String>>>>,
+    /// Per-tool timeouts from the `[timeouts]` config section.
+    timeouts: Arc<RwLock<TimeoutsToml>>,
+    /// Per-interaction cache of files the model already read, so unchanged
+    /// re-reads return a compact response instead of full contents.
+    read_cache: read::ReadCache,
+    /// Masks secrets in tool results before they reach the model or logs.
+    /// Built-in patterns plus the `redact_patterns` config key.
+    redactor: Arc<RwLock<crate::redact::Redactor>>,
+    /// User-defined tools from `~/.clemini/tools.toml`, materialized in
+    /// `tools()` as shell-command-backed functions.
+    custom_tools: Arc<RwLock<Vec<CustomToolDef>>>,
+    /// Named subagent profiles for the `task` tool (`[agents]` config
+    /// section).
+    agent_profiles: Arc<RwLock<std::collections::HashMap<String, AgentProfile>>>,
+    /// Delegation guardrails for the `task` tool (`[task]` config section).
+    task_limits: Arc<RwLock<TaskLimits>>,
+    /// Per-tool usage statistics for the session, recorded on every
+    /// `execute()` call and persisted per workspace.
+    tool_stats: Arc<RwLock<stats::ToolStatsMap>>,
+    /// Session-scoped sandbox extensions granted by the user via the
+    /// `request_path_access` tool. Appended to `allowed_paths` when
+    /// constructing tools; cleared only by restarting.
+    session_allowed_paths: Arc<RwLock<Vec<PathBuf>>>,
+    /// Permission mode gating mutating tools in `execute()`.
+    /// Uses interior mutability so `/mode` can change it mid-session.
+    permission_mode: Arc<RwLock<PermissionMode>>,
+    /// Whether review mode is on: write/edit stage into `review_changes`
+    /// instead of writing to disk, until `/apply` or `/discard`.
+    review_enabled: std::sync::atomic::AtomicBool,
+    /// The pending changeset for review mode. Allocated once; only handed to
+    /// tools while review mode is enabled.
+    review_changes: review::ReviewChanges,
 }
 
 impl CleminiToolService {
@@ -121,6 +407,26 @@ impl CleminiToolService {
             events_tx: Arc::new(RwLock::new(None)),
             plan_manager: Arc::new(RwLock::new(PlanManager::new())),
             pending_confirmations: Arc::new(RwLock::new(HashSet::new())),
+            tool_filter: Arc::new(RwLock::new(ToolFilter::default())),
+            dry_run: std::sync::atomic::AtomicBool::new(false),
+            model_routing: Arc::new(RwLock::new(ModelRouting::default())),
+            safety_policy: Arc::new(RwLock::new(SafetyPolicy::default())),
+            lsp_config: Arc::new(RwLock::new(LspConfigToml::default())),
+            search_config: Arc::new(RwLock::new(SearchConfig::default())),
+            web_cache_ttl: Arc::new(RwLock::new(None)),
+            web_render: std::sync::atomic::AtomicBool::new(false),
+            http_allowed_hosts: Arc::new(RwLock::new(None)),
+            timeouts: Arc::new(RwLock::new(TimeoutsToml::default())),
+            read_cache: Arc::new(RwLock::new(std::collections::HashMap::new())),
+            redactor: Arc::new(RwLock::new(crate::redact::Redactor::default())),
+            custom_tools: Arc::new(RwLock::new(Vec::new())),
+            agent_profiles: Arc::new(RwLock::new(std::collections::HashMap::new())),
+            task_limits: Arc::new(RwLock::new(TaskLimits::default())),
+            tool_stats: Arc::new(RwLock::new(stats::ToolStatsMap::new())),
+            session_allowed_paths: Arc::new(RwLock::new(Vec::new())),
+            permission_mode: Arc::new(RwLock::new(PermissionMode::default())),
+            review_enabled: std::sync::atomic::AtomicBool::new(false),
+            review_changes: Arc::new(RwLock::new(review::PendingChanges::default())),
         }
     }
 
@@ -145,6 +451,26 @@ impl CleminiToolService {
             events_tx: Arc::new(RwLock::new(None)),
             plan_manager,
             pending_confirmations: Arc::new(RwLock::new(HashSet::new())),
+            tool_filter: Arc::new(RwLock::new(ToolFilter::default())),
+            dry_run: std::sync::atomic::AtomicBool::new(false),
+            model_routing: Arc::new(RwLock::new(ModelRouting::default())),
+            safety_policy: Arc::new(RwLock::new(SafetyPolicy::default())),
+            lsp_config: Arc::new(RwLock::new(LspConfigToml::default())),
+            search_config: Arc::new(RwLock::new(SearchConfig::default())),
+            web_cache_ttl: Arc::new(RwLock::new(None)),
+            web_render: std::sync::atomic::AtomicBool::new(false),
+            http_allowed_hosts: Arc::new(RwLock::new(None)),
+            timeouts: Arc::new(RwLock::new(TimeoutsToml::default())),
+            read_cache: Arc::new(RwLock::new(std::collections::HashMap::new())),
+            redactor: Arc::new(RwLock::new(crate::redact::Redactor::default())),
+            custom_tools: Arc::new(RwLock::new(Vec::new())),
+            agent_profiles: Arc::new(RwLock::new(std::collections::HashMap::new())),
+            task_limits: Arc::new(RwLock::new(TaskLimits::default())),
+            tool_stats: Arc::new(RwLock::new(stats::ToolStatsMap::new())),
+            session_allowed_paths: Arc::new(RwLock::new(Vec::new())),
+            permission_mode: Arc::new(RwLock::new(PermissionMode::default())),
+            review_enabled: std::sync::atomic::AtomicBool::new(false),
+            review_changes: Arc::new(RwLock::new(review::PendingChanges::default())),
         }
     }
 
@@ -158,11 +484,430 @@ impl CleminiToolService {
         self.pending_confirmations.clone()
     }
 
+    /// The effective sandbox: configured `allowed_paths` plus any
+    /// session-scoped grants approved via `request_path_access`.
+    pub fn allowed_paths(&self) -> Vec<PathBuf> {
+        let mut paths = self.allowed_paths.clone();
+        match self.session_allowed_paths.read() {
+            Ok(guard) => paths.extend(guard.iter().cloned()),
+            Err(poisoned) => {
+                tracing::warn!("session_allowed_paths lock was poisoned, recovering");
+                paths.extend(poisoned.into_inner().iter().cloned());
+            }
+        }
+        paths
+    }
+
+    /// Set the permission mode gating mutating tools.
+    pub fn set_permission_mode(&self, mode: PermissionMode) {
+        match self.permission_mode.write() {
+            Ok(mut guard) => *guard = mode,
+            Err(poisoned) => {
+                tracing::warn!("permission_mode lock was poisoned, recovering");
+                *poisoned.into_inner() = mode;
+            }
+        }
+    }
+
+    /// The current permission mode.
+    pub fn permission_mode(&self) -> PermissionMode {
+        match self.permission_mode.read() {
+            Ok(guard) => *guard,
+            Err(poisoned) => {
+                tracing::warn!("permission_mode lock was poisoned, recovering");
+                *poisoned.into_inner()
+            }
+        }
+    }
+
+    /// Enter or leave review mode. Entering starts accumulating write/edit
+    /// changes in memory; leaving is normally done through `apply_review` or
+    /// `discard_review` so pending changes aren't silently dropped.
+    pub fn set_review_mode(&self, enabled: bool) {
+        self.review_enabled
+            .store(enabled, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Whether review mode is active.
+    pub fn review_mode(&self) -> bool {
+        self.review_enabled
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// The pending review changeset: file count and rendered unified diff.
+    pub fn review_pending(&self) -> (usize, String) {
+        match self.review_changes.read() {
+            Ok(guard) => (guard.len(), guard.render()),
+            Err(poisoned) => {
+                tracing::warn!("review_changes lock was poisoned, recovering");
+                let guard = poisoned.into_inner();
+                (guard.len(), guard.render())
+            }
+        }
+    }
+
+    /// Write every pending review change to disk and leave review mode.
+    /// On failure the unapplied remainder stays staged (and review mode
+    /// stays on) so nothing is lost.
+    pub fn apply_review(&self) -> Result<Vec<PathBuf>, String> {
+        let applied = match self.review_changes.write() {
+            Ok(mut guard) => guard.apply(&self.cwd),
+            Err(poisoned) => {
+                tracing::warn!("review_changes lock was poisoned, recovering");
+                poisoned.into_inner().apply(&self.cwd)
+            }
+        }?;
+        self.set_review_mode(false);
+        Ok(applied)
+    }
+
+    /// Drop every pending review change and leave review mode. Returns how
+    /// many files were discarded.
+    pub fn discard_review(&self) -> usize {
+        let count = match self.review_changes.write() {
+            Ok(mut guard) => guard.discard(),
+            Err(poisoned) => {
+                tracing::warn!("review_changes lock was poisoned, recovering");
+                poisoned.into_inner().discard()
+            }
+        };
+        self.set_review_mode(false);
+        count
+    }
+
+    /// Enable or disable dry-run mode for mutating tools (write, edit, bash).
+    pub fn set_dry_run(&self, dry_run: bool) {
+        self.dry_run
+            .store(dry_run, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Whether dry-run mode is active.
+    fn dry_run(&self) -> bool {
+        self.dry_run.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Set the per-operation model routing for internal LLM calls.
+    pub fn set_model_routing(&self, routing: ModelRouting) {
+        match self.model_routing.write() {
+            Ok(mut guard) => *guard = routing,
+            Err(poisoned) => {
+                tracing::warn!("model_routing lock was poisoned, recovering");
+                *poisoned.into_inner() = routing;
+            }
+        }
+    }
+
+    /// Get a clone of the current model routing.
+    fn model_routing(&self) -> ModelRouting {
+        match self.model_routing.read() {
+            Ok(guard) => guard.clone(),
+            Err(poisoned) => {
+                tracing::warn!("model_routing lock was poisoned, recovering");
+                poisoned.into_inner().clone()
+            }
+        }
+    }
+
+    /// Set the bash safety policy (built-in patterns plus `[bash]` config
+    /// section additions/exemptions).
+    pub fn set_safety_policy(&self, policy: SafetyPolicy) {
+        match self.safety_policy.write() {
+            Ok(mut guard) => *guard = policy,
+            Err(poisoned) => {
+                tracing::warn!("safety_policy lock was poisoned, recovering");
+                *poisoned.into_inner() = policy;
+            }
+        }
+    }
+
+    /// Get a clone of the current safety policy.
+    fn safety_policy(&self) -> SafetyPolicy {
+        match self.safety_policy.read() {
+            Ok(guard) => guard.clone(),
+            Err(poisoned) => {
+                tracing::warn!("safety_policy lock was poisoned, recovering");
+                poisoned.into_inner().clone()
+            }
+        }
+    }
+
+    /// Set the per-language server overrides from the `[lsp]` config section.
+    pub fn set_lsp_config(&self, config: LspConfigToml) {
+        match self.lsp_config.write() {
+            Ok(mut guard) => *guard = config,
+            Err(poisoned) => {
+                tracing::warn!("lsp_config lock was poisoned, recovering");
+                *poisoned.into_inner() = config;
+            }
+        }
+    }
+
+    /// Get a clone of the current LSP configuration.
+    fn lsp_config(&self) -> LspConfigToml {
+        match self.lsp_config.read() {
+            Ok(guard) => guard.clone(),
+            Err(poisoned) => {
+                tracing::warn!("lsp_config lock was poisoned, recovering");
+                poisoned.into_inner().clone()
+            }
+        }
+    }
+
+    /// Set the web search backend from the `search_*` config keys.
+    pub fn set_search_config(&self, config: SearchConfig) {
+        match self.search_config.write() {
+            Ok(mut guard) => *guard = config,
+            Err(poisoned) => {
+                tracing::warn!("search_config lock was poisoned, recovering");
+                *poisoned.into_inner() = config;
+            }
+        }
+    }
+
+    /// Get a clone of the current search configuration.
+    fn search_config(&self) -> SearchConfig {
+        match self.search_config.read() {
+            Ok(guard) => guard.clone(),
+            Err(poisoned) => {
+                tracing::warn!("search_config lock was poisoned, recovering");
+                poisoned.into_inner().clone()
+            }
+        }
+    }
+
+    /// Set the `web_fetch` cache TTL from the `web_cache_ttl` config key.
+    pub fn set_web_cache_ttl(&self, ttl: Option<u64>) {
+        match self.web_cache_ttl.write() {
+            Ok(mut guard) => *guard = ttl,
+            Err(poisoned) => {
+                tracing::warn!("web_cache_ttl lock was poisoned, recovering");
+                *poisoned.into_inner() = ttl;
+            }
+        }
+    }
+
+    /// Get the current `web_fetch` cache TTL override.
+    fn web_cache_ttl(&self) -> Option<u64> {
+        match self.web_cache_ttl.read() {
+            Ok(guard) => *guard,
+            Err(poisoned) => {
+                tracing::warn!("web_cache_ttl lock was poisoned, recovering");
+                *poisoned.into_inner()
+            }
+        }
+    }
+
+    /// Enable headless rendering for `web_fetch` (`web_render` config key).
+    pub fn set_web_render(&self, enabled: bool) {
+        self.web_render
+            .store(enabled, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Whether headless rendering is enabled.
+    fn web_render(&self) -> bool {
+        self.web_render.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Set the `http_request` host allowlist from the `http_allowed_hosts`
+    /// config key.
+    pub fn set_http_allowed_hosts(&self, hosts: Option<Vec<String>>) {
+        match self.http_allowed_hosts.write() {
+            Ok(mut guard) => *guard = hosts,
+            Err(poisoned) => {
+                tracing::warn!("http_allowed_hosts lock was poisoned, recovering");
+                *poisoned.into_inner() = hosts;
+            }
+        }
+    }
+
+    /// Get a clone of the current host allowlist.
+    fn http_allowed_hosts(&self) -> Option<Vec<String>> {
+        match self.http_allowed_hosts.read() {
+            Ok(guard) => guard.clone(),
+            Err(poisoned) => {
+                tracing::warn!("http_allowed_hosts lock was poisoned, recovering");
+                poisoned.into_inner().clone()
+            }
+        }
+    }
+
+    /// Set per-tool timeouts from the `[timeouts]` config section.
+    pub fn set_timeouts(&self, timeouts: TimeoutsToml) {
+        match self.timeouts.write() {
+            Ok(mut guard) => *guard = timeouts,
+            Err(poisoned) => {
+                tracing::warn!("timeouts lock was poisoned, recovering");
+                *poisoned.into_inner() = timeouts;
+            }
+        }
+    }
+
+    /// Get a clone of the current per-tool timeouts.
+    fn timeouts(&self) -> TimeoutsToml {
+        match self.timeouts.read() {
+            Ok(guard) => guard.clone(),
+            Err(poisoned) => {
+                tracing::warn!("timeouts lock was poisoned, recovering");
+                poisoned.into_inner().clone()
+            }
+        }
+    }
+
+    /// Rebuild the redactor with extra patterns from the `redact_patterns`
+    /// config key (built-ins always apply).
+    pub fn set_redact_patterns(&self, patterns: &[String]) {
+        let redactor = crate::redact::Redactor::new(patterns);
+        match self.redactor.write() {
+            Ok(mut guard) => *guard = redactor,
+            Err(poisoned) => {
+                tracing::warn!("redactor lock was poisoned, recovering");
+                *poisoned.into_inner() = redactor;
+            }
+        }
+    }
+
+    /// Get a clone of the current redactor.
+    fn redactor(&self) -> crate::redact::Redactor {
+        match self.redactor.read() {
+            Ok(guard) => guard.clone(),
+            Err(poisoned) => {
+                tracing::warn!("redactor lock was poisoned, recovering");
+                poisoned.into_inner().clone()
+            }
+        }
+    }
+
+    /// Set the named subagent profiles from the `[agents]` config section.
+    pub fn set_agent_profiles(&self, profiles: std::collections::HashMap<String, AgentProfile>) {
+        match self.agent_profiles.write() {
+            Ok(mut guard) => *guard = profiles,
+            Err(poisoned) => {
+                tracing::warn!("agent_profiles lock was poisoned, recovering");
+                *poisoned.into_inner() = profiles;
+            }
+        }
+    }
+
+    /// Get a clone of the current agent profiles.
+    fn agent_profiles(&self) -> std::collections::HashMap<String, AgentProfile> {
+        match self.agent_profiles.read() {
+            Ok(guard) => guard.clone(),
+            Err(poisoned) => {
+                tracing::warn!("agent_profiles lock was poisoned, recovering");
+                poisoned.into_inner().clone()
+            }
+        }
+    }
+
+    /// Set the delegation guardrails from the `[task]` config section.
+    pub fn set_task_limits(&self, limits: TaskLimits) {
+        match self.task_limits.write() {
+            Ok(mut guard) => *guard = limits,
+            Err(poisoned) => {
+                tracing::warn!("task_limits lock was poisoned, recovering");
+                *poisoned.into_inner() = limits;
+            }
+        }
+    }
+
+    /// Get a clone of the current task limits.
+    fn task_limits(&self) -> TaskLimits {
+        match self.task_limits.read() {
+            Ok(guard) => guard.clone(),
+            Err(poisoned) => {
+                tracing::warn!("task_limits lock was poisoned, recovering");
+                poisoned.into_inner().clone()
+            }
+        }
+    }
+
+    /// Get a snapshot of the session's per-tool usage statistics.
+    pub fn tool_stats(&self) -> stats::ToolStatsMap {
+        match self.tool_stats.read() {
+            Ok(guard) => guard.clone(),
+            Err(poisoned) => {
+                tracing::warn!("tool_stats lock was poisoned, recovering");
+                poisoned.into_inner().clone()
+            }
+        }
+    }
+
+    /// Fold one invocation into the session stats and persist them
+    /// (best-effort - stats are diagnostics, not state worth failing over).
+    fn record_tool_call(&self, name: &str, duration: Duration, tokens: u32, is_error: bool) {
+        let snapshot = {
+            let mut guard = match self.tool_stats.write() {
+                Ok(guard) => guard,
+                Err(poisoned) => {
+                    tracing::warn!("tool_stats lock was poisoned, recovering");
+                    poisoned.into_inner()
+                }
+            };
+            guard
+                .entry(name.to_string())
+                .or_default()
+                .record(duration, tokens, is_error);
+            guard.clone()
+        };
+        if let Err(e) = stats::save_stats(&self.cwd, &snapshot) {
+            tracing::warn!("Failed to persist tool stats: {}", e);
+        }
+    }
+
+    /// Set the user-defined tool definitions from `~/.clemini/tools.toml`.
+    pub fn set_custom_tools(&self, defs: Vec<CustomToolDef>) {
+        match self.custom_tools.write() {
+            Ok(mut guard) => *guard = defs,
+            Err(poisoned) => {
+                tracing::warn!("custom_tools lock was poisoned, recovering");
+                *poisoned.into_inner() = defs;
+            }
+        }
+    }
+
+    /// Get a clone of the current custom tool definitions.
+    fn custom_tools(&self) -> Vec<CustomToolDef> {
+        match self.custom_tools.read() {
+            Ok(guard) => guard.clone(),
+            Err(poisoned) => {
+                tracing::warn!("custom_tools lock was poisoned, recovering");
+                poisoned.into_inner().clone()
+            }
+        }
+    }
+
+    /// Set the allow/deny filter applied to `tools()`.
+    pub fn set_tool_filter(&self, filter: ToolFilter) {
+        match self.tool_filter.write() {
+            Ok(mut guard) => *guard = filter,
+            Err(poisoned) => {
+                tracing::warn!("tool_filter lock was poisoned, recovering");
+                *poisoned.into_inner() = filter;
+            }
+        }
+    }
+
+    /// Get a clone of the current tool filter.
+    fn tool_filter(&self) -> ToolFilter {
+        match self.tool_filter.read() {
+            Ok(guard) => guard.clone(),
+            Err(poisoned) => {
+                tracing::warn!("tool_filter lock was poisoned, recovering");
+                poisoned.into_inner().clone()
+            }
+        }
+    }
+
     /// Set the events sender and return an RAII guard that clears it when dropped.
     ///
     /// This ensures cleanup even if the interaction panics or errors.
     /// Preferred over `set_events_tx` for production use.
     pub fn with_events_tx(&self, tx: mpsc::Sender<AgentEvent>) -> EventsGuard<'_> {
+        // A new interaction means a fresh context window: nothing has been
+        // "already read", so the unchanged-file shortcut must start empty.
+        if let Ok(mut cache) = self.read_cache.write() {
+            cache.clear();
+        }
         self.set_events_tx(Some(tx));
         EventsGuard { service: self }
     }
@@ -193,13 +938,130 @@ impl CleminiToolService {
         }
     }
 
+    /// Ask-mode approval prompt for a mutating tool call. Shows a diff
+    /// preview when the tool supports one (a `preview` parameter and a
+    /// `diff` in the response - write, edit, replace), then reads y/N from
+    /// stderr/stdin like bash's destructive-command confirmation.
+    async fn approve_tool_call(tool: &Arc<dyn CallableFunction>, name: &str, args: &Value) -> bool {
+        if tool
+            .declaration()
+            .parameters()
+            .properties()
+            .get("preview")
+            .is_some()
+            && let Value::Object(map) = args
+        {
+            let mut preview_args = map.clone();
+            preview_args.insert("preview".to_string(), Value::Bool(true));
+            if let Ok(result) = tool.call(Value::Object(preview_args)).await
+                && let Some(diff) = result.get("diff").and_then(|v| v.as_str())
+            {
+                eprintln!("{diff}");
+            }
+        }
+
+        eprintln!(
+            "\n⚠️  Ask mode - about to run:\n    {}",
+            crate::format::format_tool_args(name, args)
+        );
+        eprint!("Proceed? [y/N] ");
+        let _ = std::io::Write::flush(&mut std::io::stderr());
+
+        let mut answer = String::new();
+        if std::io::stdin().read_line(&mut answer).is_ok() {
+            let answer = answer.trim().to_lowercase();
+            answer == "y" || answer == "yes"
+        } else {
+            false
+        }
+    }
+
     pub async fn execute(&self, name: &str, args: Value) -> Result<Value> {
         let tools = self.tools();
         let tool = tools
             .iter()
             .find(|t| t.declaration().name() == name)
             .ok_or_else(|| anyhow::anyhow!("Tool not found: {}", name))?;
-        tool.call(args).await.map_err(|e| anyhow::anyhow!(e))
+
+        // Permission mode gate: mutating tools are prompted for (ask) or
+        // rejected (read-only) at the same choke point that handles
+        // redaction and stats, so every caller gets the same behavior.
+        let mode = self.permission_mode();
+        if mode != PermissionMode::Auto && !tool_is_read_only(name) {
+            match mode {
+                PermissionMode::ReadOnly => {
+                    return Ok(error_response(
+                        &format!(
+                            "Tool '{name}' is not allowed in read-only mode. Only read-only tools are available; the user can switch with /mode."
+                        ),
+                        error_codes::BLOCKED,
+                        serde_json::json!({"tool": name, "permission_mode": mode.as_str()}),
+                    ));
+                }
+                PermissionMode::Ask => {
+                    if self.is_mcp_mode {
+                        return Ok(error_response(
+                            &format!(
+                                "Tool '{name}' requires approval, but ask mode cannot prompt in MCP mode. Switch to auto or read-only."
+                            ),
+                            error_codes::BLOCKED,
+                            serde_json::json!({"tool": name, "permission_mode": mode.as_str()}),
+                        ));
+                    }
+                    if !Self::approve_tool_call(tool, name, &args).await {
+                        return Ok(error_response(
+                            &format!(
+                                "Tool call '{name}' declined by user. Do not retry without new direction from the user."
+                            ),
+                            error_codes::BLOCKED,
+                            serde_json::json!({"tool": name, "permission_mode": mode.as_str()}),
+                        ));
+                    }
+                }
+                PermissionMode::Auto => unreachable!("gated above"),
+            }
+        }
+
+        // Review mode gate: only tools that stage into the pending changeset
+        // may mutate while /review is active. Anything else that writes (a
+        // bash command, a file move, a patch) would land on disk behind the
+        // diff the user believes is holding all pending work.
+        if self.review_mode() && !tool_is_read_only(name) && !tool_stages_review(name) {
+            return Ok(error_response(
+                &format!(
+                    "Tool '{name}' is not allowed while review mode is active because its changes cannot be staged into the pending changeset. Use write_file/edit/multi_edit/edit_lines/replace/notebook_edit instead, or ask the user to /apply or /discard the pending review first."
+                ),
+                error_codes::BLOCKED,
+                serde_json::json!({"tool": name, "review_mode": true}),
+            ));
+        }
+
+        // Redact secrets at the single choke point every tool result flows
+        // through, so the masked value is what reaches the model, the
+        // ToolResult event, and the logs alike.
+        let start = Instant::now();
+        let result = tool
+            .call(args.clone())
+            .await
+            .map(|result| self.redactor().redact_value(result));
+        let duration = start.elapsed();
+
+        let (is_error, tokens) = match &result {
+            Ok(value) => (
+                value.get("error").is_some(),
+                crate::format::estimate_tokens(&args) + crate::format::estimate_tokens(value),
+            ),
+            Err(_) => (true, crate::format::estimate_tokens(&args)),
+        };
+        self.record_tool_call(name, duration, tokens, is_error);
+
+        // Mutations additionally land in the append-only audit journal
+        // (~/.clemini/audit.jsonl), regardless of the logging sink.
+        if !tool_is_read_only(name) {
+            crate::audit::record(name, &args, result.as_ref().ok(), is_error);
+        }
+
+        result.map_err(|e| anyhow::anyhow!(e))
     }
 }
 
@@ -237,61 +1099,225 @@ impl ToolService for CleminiToolService {
     ///
     /// Available tools:
     /// - `read`: Read file contents
+    /// - `read_many`: Read several files in one call
     /// - `write`: Create or overwrite files
     /// - `edit`: Surgical string replacement in files
+    /// - `edit_lines`: Replace a 1-indexed line range in a file
+    /// - `multi_edit`: Atomic batch of string replacements in one file
+    /// - `apply_patch`: Apply a unified diff to the working tree
+    /// - `move_file`: Move or rename a file or directory
+    /// - `copy_file`: Copy a file
+    /// - `delete_file`: Delete a file or directory (to session trash)
+    /// - `create_directory`: Create a directory
+    /// - `revert_file`: Restore a file from its pre-mutation checkpoint
+    /// - `notebook_read`: Read a Jupyter notebook as structured cells
+    /// - `notebook_edit`: Replace, insert, or delete a notebook cell
     /// - `bash`: Execute shell commands
     /// - `glob`: Find files by pattern
     /// - `grep`: Search for text in files
+    /// - `replace`: Regex search-and-replace across files
+    /// - `git_commit`: Stage files and create a git commit
+    /// - `github`: GitHub operations via the gh CLI
     /// - `kill_shell`: Kill a background task
+    /// - `lsp`: Code intelligence via a language server
+    /// - `outline`: Structural skeleton of a source file
+    /// - `send_input`: Inject keystrokes into an interactive PTY task
     /// - `task`: Spawn a clemini subagent
     /// - `task_output`: Get output from a background task
     /// - `web_fetch`: Fetch web content
-    /// - `web_search`: Search the web using DuckDuckGo
+    /// - `web_search`: Search the web (configurable provider)
+    /// - `http_request`: Generic HTTP request to a JSON API
     /// - `ask_user`: Ask the user a question
-    /// - `todo_write`: Display a todo list
+    /// - `request_path_access`: Ask the user to extend the sandbox for this session
+    /// - `memory`: Durable cross-session notes for this workspace
+    /// - `remember`: Append confirmed guidance to CLAUDE.md
+    /// - `todo_write`: Display a todo list (persisted per workspace)
+    /// - `todo_read`: Read back the persisted todo list
+    ///
+    /// Plus any user-defined tools from `~/.clemini/tools.toml`.
     fn tools(&self) -> Vec<Arc<dyn CallableFunction>> {
         let events_tx = self.events_tx();
-        vec![
-            Arc::new(ReadTool::new(
+        let allowed_paths = self.allowed_paths();
+        let dry_run = self.dry_run();
+        let review = self.review_mode().then(|| self.review_changes.clone());
+        let routing = self.model_routing();
+        let timeouts = self.timeouts();
+        let mut tools: Vec<Arc<dyn CallableFunction>> = vec![
+            Arc::new(
+                ReadTool::new(self.cwd.clone(), allowed_paths.clone(), events_tx.clone())
+                    .with_cache(self.read_cache.clone())
+                    .with_review(review.clone()),
+            ),
+            Arc::new(ReadManyTool::new(
                 self.cwd.clone(),
-                self.allowed_paths.clone(),
+                allowed_paths.clone(),
                 events_tx.clone(),
             )),
-            Arc::new(WriteTool::new(
+            Arc::new(
+                WriteTool::new(self.cwd.clone(), allowed_paths.clone(), events_tx.clone())
+                    .with_dry_run(dry_run)
+                    .with_review(review.clone()),
+            ),
+            Arc::new(
+                EditTool::new(self.cwd.clone(), allowed_paths.clone(), events_tx.clone())
+                    .with_dry_run(dry_run)
+                    .with_review(review.clone()),
+            ),
+            Arc::new(
+                EditLinesTool::new(self.cwd.clone(), allowed_paths.clone(), events_tx.clone())
+                    .with_dry_run(dry_run)
+                    .with_review(review.clone()),
+            ),
+            Arc::new(
+                MultiEditTool::new(self.cwd.clone(), allowed_paths.clone(), events_tx.clone())
+                    .with_dry_run(dry_run)
+                    .with_review(review.clone()),
+            ),
+            Arc::new(
+                ApplyPatchTool::new(self.cwd.clone(), allowed_paths.clone(), events_tx.clone())
+                    .with_dry_run(dry_run),
+            ),
+            Arc::new(
+                MoveFileTool::new(self.cwd.clone(), allowed_paths.clone(), events_tx.clone())
+                    .with_dry_run(dry_run),
+            ),
+            Arc::new(
+                CopyFileTool::new(self.cwd.clone(), allowed_paths.clone(), events_tx.clone())
+                    .with_dry_run(dry_run),
+            ),
+            Arc::new(
+                DeleteFileTool::new(self.cwd.clone(), allowed_paths.clone(), events_tx.clone())
+                    .with_dry_run(dry_run),
+            ),
+            Arc::new(
+                CreateDirectoryTool::new(
+                    self.cwd.clone(),
+                    allowed_paths.clone(),
+                    events_tx.clone(),
+                )
+                .with_dry_run(dry_run),
+            ),
+            Arc::new(RevertFileTool::new(
                 self.cwd.clone(),
-                self.allowed_paths.clone(),
+                allowed_paths.clone(),
                 events_tx.clone(),
             )),
-            Arc::new(EditTool::new(
+            Arc::new(NotebookReadTool::new(
                 self.cwd.clone(),
-                self.allowed_paths.clone(),
+                allowed_paths.clone(),
                 events_tx.clone(),
             )),
-            Arc::new(BashTool::new(
+            Arc::new(
+                NotebookEditTool::new(self.cwd.clone(), allowed_paths.clone(), events_tx.clone())
+                    .with_dry_run(dry_run)
+                    .with_review(review.clone()),
+            ),
+            Arc::new(
+                BashTool::new(
+                    self.cwd.clone(),
+                    allowed_paths.clone(),
+                    self.bash_timeout,
+                    self.is_mcp_mode,
+                    events_tx.clone(),
+                    self.pending_confirmations.clone(),
+                )
+                .with_dry_run(dry_run)
+                .with_safety_policy(self.safety_policy()),
+            ),
+            Arc::new(RunPythonTool::new(self.cwd.clone(), events_tx.clone()).with_dry_run(dry_run)),
+            Arc::new(
+                WatchTool::new(self.cwd.clone(), allowed_paths.clone(), events_tx.clone())
+                    .with_safety_policy(self.safety_policy())
+                    .with_dry_run(dry_run),
+            ),
+            Arc::new(GlobTool::new(
                 self.cwd.clone(),
-                self.allowed_paths.clone(),
-                self.bash_timeout,
-                self.is_mcp_mode,
+                allowed_paths.clone(),
                 events_tx.clone(),
-                self.pending_confirmations.clone(),
             )),
-            Arc::new(GlobTool::new(
+            Arc::new(
+                GrepTool::new(self.cwd.clone(), allowed_paths.clone(), events_tx.clone())
+                    .with_timeout(timeouts.for_tool("grep")),
+            ),
+            Arc::new(
+                ReplaceTool::new(self.cwd.clone(), allowed_paths.clone(), events_tx.clone())
+                    .with_dry_run(dry_run)
+                    .with_review(review.clone()),
+            ),
+            Arc::new(
+                GitCommitTool::new(
+                    self.cwd.clone(),
+                    allowed_paths.clone(),
+                    self.api_key.clone(),
+                    events_tx.clone(),
+                )
+                .with_model(routing.git_commit.clone())
+                .with_dry_run(dry_run),
+            ),
+            Arc::new(
+                ArchiveTool::new(self.cwd.clone(), allowed_paths.clone(), events_tx.clone())
+                    .with_dry_run(dry_run),
+            ),
+            Arc::new(FileInfoTool::new(
                 self.cwd.clone(),
-                self.allowed_paths.clone(),
+                allowed_paths.clone(),
                 events_tx.clone(),
             )),
-            Arc::new(GrepTool::new(
+            Arc::new(GitHubTool::new(self.cwd.clone(), events_tx.clone()).with_dry_run(dry_run)),
+            Arc::new(KillShellTool::new(events_tx.clone())),
+            Arc::new(
+                LspTool::new(self.cwd.clone(), allowed_paths.clone(), events_tx.clone())
+                    .with_config(self.lsp_config())
+                    .with_dry_run(dry_run),
+            ),
+            Arc::new(OutlineTool::new(
                 self.cwd.clone(),
-                self.allowed_paths.clone(),
+                allowed_paths.clone(),
                 events_tx.clone(),
             )),
-            Arc::new(KillShellTool::new(events_tx.clone())),
-            Arc::new(TaskTool::new(self.cwd.clone(), events_tx.clone())),
+            Arc::new(ScreenshotTool::new(
+                self.cwd.clone(),
+                allowed_paths.clone(),
+                events_tx.clone(),
+            )),
+            Arc::new(SendInputTool::new(events_tx.clone())),
+            Arc::new(
+                TaskTool::new(self.cwd.clone(), events_tx.clone())
+                    .with_model(routing.task.clone())
+                    .with_timeout(timeouts.for_tool("task"))
+                    .with_profiles(self.agent_profiles())
+                    .with_limits(self.task_limits()),
+            ),
             Arc::new(TaskOutputTool::new(events_tx.clone())),
-            Arc::new(WebFetchTool::new(self.api_key.clone(), events_tx.clone())),
-            Arc::new(WebSearchTool::new(events_tx.clone())),
-            Arc::new(AskUserTool::new(events_tx.clone())),
-            Arc::new(TodoWriteTool::new(events_tx.clone())),
+            Arc::new(
+                WebFetchTool::new(self.api_key.clone(), events_tx.clone())
+                    .with_model(routing.web_fetch.clone())
+                    .with_cache_ttl(self.web_cache_ttl())
+                    .with_render(self.web_render())
+                    .with_timeout(timeouts.for_tool("web_fetch")),
+            ),
+            Arc::new(
+                WebSearchTool::new(events_tx.clone())
+                    .with_config(self.search_config())
+                    .with_timeout(timeouts.for_tool("web_search")),
+            ),
+            Arc::new(
+                HttpRequestTool::new(events_tx.clone())
+                    .with_allowed_hosts(self.http_allowed_hosts()),
+            ),
+            Arc::new(AskUserTool::new(self.cwd.clone(), events_tx.clone())),
+            Arc::new(RequestPathAccessTool::new(
+                self.cwd.clone(),
+                allowed_paths.clone(),
+                self.session_allowed_paths.clone(),
+                self.is_mcp_mode,
+                events_tx.clone(),
+            )),
+            Arc::new(EnvInfoTool::new(self.cwd.clone(), events_tx.clone())),
+            Arc::new(MemoryTool::new(self.cwd.clone(), events_tx.clone()).with_dry_run(dry_run)),
+            Arc::new(RememberTool::new(self.cwd.clone(), events_tx.clone()).with_dry_run(dry_run)),
+            Arc::new(TodoWriteTool::new(self.cwd.clone(), events_tx.clone())),
+            Arc::new(TodoReadTool::new(self.cwd.clone(), events_tx.clone())),
             Arc::new(EnterPlanModeTool::new(
                 events_tx.clone(),
                 self.plan_manager.clone(),
@@ -306,8 +1332,35 @@ impl ToolService for CleminiToolService {
             Arc::new(EventBusListChannelsTool::new(events_tx.clone())),
             Arc::new(EventBusPublishTool::new(events_tx.clone())),
             Arc::new(EventBusGetEventsTool::new(events_tx.clone())),
-            Arc::new(EventBusUnregisterTool::new(events_tx)),
-        ]
+            Arc::new(EventBusUnregisterTool::new(events_tx.clone())),
+        ];
+
+        // Custom user-defined tools from ~/.clemini/tools.toml. Invalid
+        // definitions and names that shadow a built-in are skipped with a
+        // warning rather than failing the whole tool list.
+        for def in self.custom_tools() {
+            if let Err(reason) = def.validate() {
+                tracing::warn!("Skipping custom tool: {reason}");
+                continue;
+            }
+            if tools.iter().any(|t| t.declaration().name() == def.name) {
+                tracing::warn!(
+                    "Skipping custom tool '{}': name shadows a built-in tool",
+                    def.name
+                );
+                continue;
+            }
+            tools.push(Arc::new(
+                CustomTool::new(def, self.cwd.clone(), self.bash_timeout, events_tx.clone())
+                    .with_dry_run(dry_run),
+            ));
+        }
+
+        let filter = self.tool_filter();
+        if !filter.is_unrestricted() {
+            tools.retain(|t| filter.permits(t.declaration().name()));
+        }
+        tools
     }
 }
 
@@ -339,42 +1392,46 @@ pub fn validate_path(path: &std::path::Path, allowed_paths: &[PathBuf]) -> Resul
         let parent = path.parent().unwrap_or(std::path::Path::new("."));
         let filename = path.file_name().ok_or("Invalid path")?;
 
-        let canonical_parent =
-            if parent.as_os_str().is_empty() || parent == std::path::Path::new(".") {
-                // If parent is empty or ".", we use the first allowed path (which is always CWD)
-                // but we need to resolve it against all allowed paths.
-                // Actually, if it's relative, it's relative to CWD.
-                let cwd = &allowed_paths[0];
-                cwd.to_path_buf()
-            } else if parent.exists() {
-                parent
-                    .canonicalize()
-                    .map_err(|e| format!("Cannot resolve parent: {e}"))?
+        let canonical_parent = if parent.as_os_str().is_empty()
+            || parent == std::path::Path::new(".")
+        {
+            // If parent is empty or ".", we use the first allowed path (which is always CWD)
+            // but we need to resolve it against all allowed paths.
+            // Actually, if it's relative, it's relative to CWD.
+            let cwd = &allowed_paths[0];
+            cwd.to_path_buf()
+        } else if parent.exists() {
+            parent
+                .canonicalize()
+                .map_err(|e| format!("Cannot resolve parent: {e}"))?
+        } else {
+            // Parent doesn't exist - check if it would be under any allowed path
+            let mut resolved_parent = None;
+
+            // Relative paths are relative to CWD (first allowed path)
+            let full_parent = if parent.is_absolute() {
+                parent.to_path_buf()
             } else {
-                // Parent doesn't exist - check if it would be under any allowed path
-                let mut resolved_parent = None;
-
-                // Relative paths are relative to CWD (first allowed path)
-                let full_parent = if parent.is_absolute() {
-                    parent.to_path_buf()
-                } else {
-                    allowed_paths[0].join(parent)
-                };
-
-                for allowed in allowed_paths {
-                    if full_parent.starts_with(allowed) {
-                        resolved_parent = Some(full_parent);
-                        break;
-                    }
+                allowed_paths[0].join(parent)
+            };
+
+            for allowed in allowed_paths {
+                if full_parent.starts_with(allowed) {
+                    resolved_parent = Some(full_parent);
+                    break;
                 }
+            }
 
-                match resolved_parent {
-                    Some(p) => p,
-                    None => {
-                        return Err(format!("Path {} is outside allowed paths", path.display(),));
-                    }
+            match resolved_parent {
+                Some(p) => p,
+                None => {
+                    return Err(format!(
+                        "Path {} is outside allowed paths. Use request_path_access to ask the user for access.",
+                        path.display(),
+                    ));
                 }
-            };
+            }
+        };
 
         canonical_parent.join(filename)
     };
@@ -391,7 +1448,7 @@ pub fn validate_path(path: &std::path::Path, allowed_paths: &[PathBuf]) -> Resul
     }
 
     Err(format!(
-        "Path {} is outside allowed paths",
+        "Path {} is outside allowed paths. Use request_path_access to ask the user for access.",
         check_path.display(),
     ))
 }
@@ -485,6 +1542,38 @@ pub const ALL_TOOL_NAMES: &[&str] = &[
     "event_bus_publish",
     "event_bus_get_events",
     "event_bus_unregister",
+    // File reading and inspection
+    "read_many",
+    "outline",
+    "notebook_read",
+    "file_info",
+    "env_info",
+    "todo_read",
+    // File mutation
+    "multi_edit",
+    "edit_lines",
+    "apply_patch",
+    "replace",
+    "notebook_edit",
+    "create_directory",
+    "move_file",
+    "copy_file",
+    "delete_file",
+    "archive",
+    "revert_file",
+    // Execution and external services
+    "run_python",
+    "send_input",
+    "watch",
+    "screenshot",
+    "git_commit",
+    "github",
+    "http_request",
+    "lsp",
+    // Session state
+    "memory",
+    "remember",
+    "request_path_access",
 ];
 
 /// Check if a tool is read-only (safe to run in plan mode).
@@ -499,12 +1588,13 @@ pub const ALL_TOOL_NAMES: &[&str] = &[
 pub fn tool_is_read_only(tool_name: &str) -> bool {
     matches!(
         tool_name,
-        // File reading
-        "read" | "glob" | "grep" |
+        // File reading ("read_file" is the declared function name)
+        "read" | "read_file" | "read_many" | "outline" | "notebook_read" |
+        "file_info" | "env_info" | "glob" | "grep" |
         // Web reading
         "web_fetch" | "web_search" |
         // User interaction (no side effects)
-        "ask_user" | "todo_write" |
+        "ask_user" | "todo_write" | "todo_read" |
         // Plan mode management
         "enter_plan_mode" | "exit_plan_mode" |
         // Event bus reading (these don't modify state significantly)
@@ -514,6 +1604,20 @@ pub fn tool_is_read_only(tool_name: &str) -> bool {
     )
 }
 
+/// Check if a tool stages its writes into the review changeset instead of
+/// touching disk while review mode is active ("write" is declared as
+/// `write_file`).
+///
+/// Mutating tools not listed here are blocked for the duration of a review
+/// session, so the pending diff the user inspects with `/review` really
+/// covers everything the model has done.
+pub fn tool_stages_review(tool_name: &str) -> bool {
+    matches!(
+        tool_name,
+        "write_file" | "edit" | "multi_edit" | "edit_lines" | "replace" | "notebook_edit"
+    )
+}
+
 /// Structured response from tool execution.
 ///
 /// Provides type-safe tool results while serializing to the same JSON format
@@ -590,6 +1694,7 @@ pub mod error_codes {
     pub const BINARY_FILE: &str = "BINARY_FILE";
     pub const TIMEOUT: &str = "TIMEOUT";
     pub const BLOCKED: &str = "BLOCKED";
+    pub const LIMIT_EXCEEDED: &str = "LIMIT_EXCEEDED";
 }
 
 /// Create a standardized error response
@@ -734,6 +1839,7 @@ mod tests {
         // Verify expected categorizations
         // Read-only tools
         assert!(tool_is_read_only("read"));
+        assert!(tool_is_read_only("read_file"));
         assert!(tool_is_read_only("glob"));
         assert!(tool_is_read_only("grep"));
         assert!(tool_is_read_only("web_fetch"));
@@ -746,9 +1852,16 @@ mod tests {
         assert!(tool_is_read_only("event_bus_list_sessions"));
         assert!(tool_is_read_only("event_bus_list_channels"));
         assert!(tool_is_read_only("event_bus_get_events"));
+        assert!(tool_is_read_only("read_many"));
+        assert!(tool_is_read_only("outline"));
+        assert!(tool_is_read_only("notebook_read"));
+        assert!(tool_is_read_only("todo_read"));
+        assert!(tool_is_read_only("file_info"));
+        assert!(tool_is_read_only("env_info"));
 
         // Write tools (side effects)
         assert!(!tool_is_read_only("write"));
+        assert!(!tool_is_read_only("write_file"));
         assert!(!tool_is_read_only("edit"));
         assert!(!tool_is_read_only("bash"));
         assert!(!tool_is_read_only("kill_shell"));
@@ -756,6 +1869,17 @@ mod tests {
         assert!(!tool_is_read_only("event_bus_register"));
         assert!(!tool_is_read_only("event_bus_publish"));
         assert!(!tool_is_read_only("event_bus_unregister"));
+        assert!(!tool_is_read_only("multi_edit"));
+        assert!(!tool_is_read_only("apply_patch"));
+        assert!(!tool_is_read_only("delete_file"));
+        assert!(!tool_is_read_only("run_python"));
+        assert!(!tool_is_read_only("screenshot"));
+        // These have read operations but can also mutate (lsp rename, github
+        // PR creation, http POST, memory write), so they stay conservative
+        assert!(!tool_is_read_only("lsp"));
+        assert!(!tool_is_read_only("github"));
+        assert!(!tool_is_read_only("http_request"));
+        assert!(!tool_is_read_only("memory"));
     }
 
     #[test]
@@ -765,6 +1889,358 @@ mod tests {
         assert!(!tool_is_read_only(""));
     }
 
+    // ============================================================================
+    // ToolFilter tests
+    // ============================================================================
+
+    fn test_service(temp: &tempfile::TempDir) -> CleminiToolService {
+        CleminiToolService::new(
+            temp.path().to_path_buf(),
+            120,
+            false,
+            vec![temp.path().to_path_buf()],
+            "fake-key".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_tool_filter_unrestricted_permits_everything() {
+        let filter = ToolFilter::default();
+        assert!(filter.is_unrestricted());
+        assert!(filter.permits("bash"));
+        assert!(filter.permits("read_file"));
+    }
+
+    #[test]
+    fn test_tool_filter_denylist_wins() {
+        let filter = ToolFilter::new(
+            Some(vec!["bash".to_string(), "read_file".to_string()]),
+            vec!["bash".to_string()],
+        );
+        assert!(!filter.permits("bash"));
+        assert!(filter.permits("read_file"));
+        assert!(!filter.permits("grep"), "Not in allowlist");
+    }
+
+    #[test]
+    fn test_tool_filter_unknown_names() {
+        let filter = ToolFilter::new(
+            Some(vec!["read_file".to_string(), "raed".to_string()]),
+            vec!["web_ftech".to_string()],
+        );
+        assert_eq!(
+            filter.unknown_names(&["read_file", "web_fetch"]),
+            vec!["raed", "web_ftech"]
+        );
+    }
+
+    #[test]
+    fn test_tools_respects_filter() {
+        let temp = tempdir().unwrap();
+        let service = test_service(&temp);
+        let full_count = service.tools().len();
+
+        // Denylist removes only the named tools
+        service.set_tool_filter(ToolFilter::new(None, vec!["bash".to_string()]));
+        let tools = service.tools();
+        assert_eq!(tools.len(), full_count - 1);
+        assert!(!tools.iter().any(|t| t.declaration().name() == "bash"));
+
+        // Allowlist keeps only the named tools
+        service.set_tool_filter(ToolFilter::new(
+            Some(vec!["read_file".to_string(), "grep".to_string()]),
+            vec![],
+        ));
+        let tools = service.tools();
+        assert_eq!(tools.len(), 2);
+
+        // Clearing the filter restores everything
+        service.set_tool_filter(ToolFilter::default());
+        assert_eq!(service.tools().len(), full_count);
+    }
+
+    // ============================================================================
+    // Tool stats tests
+    // ============================================================================
+
+    #[tokio::test]
+    async fn test_execute_records_tool_stats() {
+        let temp = tempdir().unwrap();
+        let service = test_service(&temp);
+
+        // A successful call and an error-result call both get recorded;
+        // only the latter counts as an error.
+        let _ = service
+            .execute("glob", serde_json::json!({"pattern": "*.rs"}))
+            .await;
+        let _ = service
+            .execute("read_file", serde_json::json!({"file_path": "missing.txt"}))
+            .await;
+
+        let recorded = service.tool_stats();
+        assert_eq!(recorded["glob"].calls, 1);
+        assert_eq!(recorded["glob"].errors, 0);
+        assert_eq!(recorded["read_file"].calls, 1);
+        assert_eq!(recorded["read_file"].errors, 1);
+        assert!(recorded["read_file"].total_tokens > 0);
+
+        // Stats are persisted per workspace.
+        let persisted = stats::load_stats(temp.path()).unwrap();
+        assert_eq!(persisted, recorded);
+        let _ = std::fs::remove_file(stats::stats_file_path(temp.path()));
+    }
+
+    #[tokio::test]
+    async fn test_session_grant_extends_sandbox() {
+        let temp = tempdir().unwrap();
+        let service = test_service(&temp);
+
+        let outside = tempdir().unwrap();
+        let outside_file = outside.path().join("data.txt");
+        fs::write(&outside_file, "outside").unwrap();
+        let args = serde_json::json!({"file_path": outside_file.to_str().unwrap()});
+
+        // Denied before a grant...
+        let result = service.execute("read_file", args.clone()).await.unwrap();
+        assert_eq!(result["error_code"], "ACCESS_DENIED");
+
+        // ...allowed on the next call once the session grant is recorded,
+        // because tools are rebuilt per execute() with the combined sandbox.
+        service
+            .session_allowed_paths
+            .write()
+            .unwrap()
+            .push(outside.path().canonicalize().unwrap());
+        assert!(service.allowed_paths().len() > 1);
+        let result = service.execute("read_file", args).await.unwrap();
+        assert!(result.get("error").is_none(), "{result}");
+        assert!(result["contents"].as_str().unwrap().contains("outside"));
+    }
+
+    #[test]
+    fn test_permission_mode_parse() {
+        assert_eq!(PermissionMode::parse("auto"), Some(PermissionMode::Auto));
+        assert_eq!(PermissionMode::parse("ask"), Some(PermissionMode::Ask));
+        assert_eq!(
+            PermissionMode::parse("read-only"),
+            Some(PermissionMode::ReadOnly)
+        );
+        assert_eq!(
+            PermissionMode::parse("readonly"),
+            Some(PermissionMode::ReadOnly)
+        );
+        assert_eq!(PermissionMode::parse("yolo"), None);
+    }
+
+    #[tokio::test]
+    async fn test_read_only_mode_rejects_mutating_tools() {
+        let temp = tempdir().unwrap();
+        let service = test_service(&temp);
+        service.set_permission_mode(PermissionMode::ReadOnly);
+
+        let result = service
+            .execute(
+                "write_file",
+                serde_json::json!({"file_path": "a.txt", "content": "hi"}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], "BLOCKED");
+        assert!(!temp.path().join("a.txt").exists());
+
+        // Read-only tools still run.
+        let result = service
+            .execute("glob", serde_json::json!({"pattern": "*.rs"}))
+            .await
+            .unwrap();
+        assert!(result.get("error").is_none(), "{result}");
+
+        // Switching back to auto lifts the gate.
+        service.set_permission_mode(PermissionMode::Auto);
+        let result = service
+            .execute(
+                "write_file",
+                serde_json::json!({"file_path": "a.txt", "content": "hi"}),
+            )
+            .await
+            .unwrap();
+        assert!(result.get("error").is_none(), "{result}");
+        assert!(temp.path().join("a.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_read_tools_skip_audit_journal() {
+        let temp = tempdir().unwrap();
+        let service = test_service(&temp);
+        fs::write(temp.path().join("a.txt"), "fn main() {}\n").unwrap();
+
+        service
+            .execute("read_many", serde_json::json!({"file_paths": ["a.txt"]}))
+            .await
+            .unwrap();
+        service
+            .execute("outline", serde_json::json!({"file_path": "a.txt"}))
+            .await
+            .unwrap();
+        service
+            .execute("todo_read", serde_json::json!({}))
+            .await
+            .unwrap();
+
+        // Regression: these pure reads used to be missing from
+        // tool_is_read_only() and landed in the audit journal as mutations.
+        let recorded = crate::audit::recorded_tools();
+        for name in [
+            "read_many",
+            "outline",
+            "notebook_read",
+            "todo_read",
+            "read_file",
+        ] {
+            assert!(
+                !recorded.contains(&name.to_string()),
+                "{name} was audited as a mutating tool"
+            );
+        }
+
+        // ...while an actual mutation is journaled.
+        service
+            .execute(
+                "write_file",
+                serde_json::json!({"file_path": "b.txt", "content": "x\n"}),
+            )
+            .await
+            .unwrap();
+        assert!(crate::audit::recorded_tools().contains(&"write_file".to_string()));
+
+        let _ = fs::remove_file(checkpoint::journal_path(&service.cwd));
+    }
+
+    #[tokio::test]
+    async fn test_review_mode_stages_and_applies() {
+        let temp = tempdir().unwrap();
+        let service = test_service(&temp);
+        service.set_review_mode(true);
+
+        let result = service
+            .execute(
+                "write_file",
+                serde_json::json!({"file_path": "a.txt", "content": "pending\n"}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result["staged"], true);
+        assert!(!temp.path().join("a.txt").exists());
+
+        let (count, diff) = service.review_pending();
+        assert_eq!(count, 1);
+        assert!(diff.contains("+pending"));
+
+        let applied = service.apply_review().unwrap();
+        assert_eq!(applied.len(), 1);
+        assert!(!service.review_mode());
+        assert_eq!(
+            fs::read_to_string(temp.path().join("a.txt")).unwrap(),
+            "pending\n"
+        );
+
+        let _ = fs::remove_file(checkpoint::journal_path(&service.cwd));
+    }
+
+    #[tokio::test]
+    async fn test_review_mode_edits_compound_and_discard() {
+        let temp = tempdir().unwrap();
+        let service = test_service(&temp);
+        fs::write(temp.path().join("a.txt"), "one\n").unwrap();
+        service.set_review_mode(true);
+
+        service
+            .execute(
+                "edit",
+                serde_json::json!({"file_path": "a.txt", "old_string": "one", "new_string": "two"}),
+            )
+            .await
+            .unwrap();
+        // The second edit sees the staged content, not the disk.
+        let result = service
+            .execute(
+                "edit",
+                serde_json::json!({"file_path": "a.txt", "old_string": "two", "new_string": "three"}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result["staged"], true, "{result}");
+
+        // read_file serves the staged version too.
+        let result = service
+            .execute("read_file", serde_json::json!({"file_path": "a.txt"}))
+            .await
+            .unwrap();
+        assert!(result["contents"].as_str().unwrap().contains("three"));
+        assert_eq!(result["staged"], true);
+
+        // Discard: disk never changed, mode is off.
+        assert_eq!(service.discard_review(), 1);
+        assert!(!service.review_mode());
+        assert_eq!(
+            fs::read_to_string(temp.path().join("a.txt")).unwrap(),
+            "one\n"
+        );
+        let result = service
+            .execute(
+                "write_file",
+                serde_json::json!({"file_path": "b.txt", "content": "direct"}),
+            )
+            .await
+            .unwrap();
+        assert!(result["staged"].is_null());
+        assert!(temp.path().join("b.txt").exists());
+
+        let _ = fs::remove_file(checkpoint::journal_path(&service.cwd));
+    }
+
+    #[tokio::test]
+    async fn test_review_mode_blocks_unstageable_mutations() {
+        let temp = tempdir().unwrap();
+        let service = test_service(&temp);
+        fs::write(temp.path().join("a.txt"), "keep\n").unwrap();
+        service.set_review_mode(true);
+
+        // Mutations that can't be staged are blocked, not silently written.
+        let result = service
+            .execute("delete_file", serde_json::json!({"file_path": "a.txt"}))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::BLOCKED, "{result}");
+        assert!(result["error"].as_str().unwrap().contains("review mode"));
+        assert!(temp.path().join("a.txt").exists());
+
+        let result = service
+            .execute("bash", serde_json::json!({"command": "rm a.txt"}))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::BLOCKED);
+        assert!(temp.path().join("a.txt").exists());
+
+        // Read-only tools still pass.
+        let result = service
+            .execute("read_file", serde_json::json!({"file_path": "a.txt"}))
+            .await
+            .unwrap();
+        assert!(result["error"].is_null(), "{result}");
+
+        // Ending the review lifts the gate.
+        service.discard_review();
+        let result = service
+            .execute("delete_file", serde_json::json!({"file_path": "a.txt"}))
+            .await
+            .unwrap();
+        assert!(result["error"].is_null(), "{result}");
+        assert!(!temp.path().join("a.txt").exists());
+
+        let _ = fs::remove_file(checkpoint::journal_path(&service.cwd));
+    }
+
     // ============================================================================
     // ToolResponse tests
     // ============================================================================
@@ -809,4 +2285,45 @@ mod tests {
         let value: serde_json::Value = response.into();
         assert_eq!(value["ok"], true);
     }
+
+    #[test]
+    fn test_timeouts_for_tool() {
+        let timeouts = TimeoutsToml {
+            grep: Some(10),
+            default: Some(60),
+            ..Default::default()
+        };
+        // A tool's own entry wins over default.
+        assert_eq!(timeouts.for_tool("grep"), Some(10));
+        // Default fills the gaps.
+        assert_eq!(timeouts.for_tool("web_fetch"), Some(60));
+
+        // No entries at all means no limit.
+        assert_eq!(TimeoutsToml::default().for_tool("grep"), None);
+    }
+
+    #[tokio::test]
+    async fn test_run_with_timeout_passes_through_without_limit() {
+        let result = run_with_timeout("grep", None, async { Ok(serde_json::json!({"ok": true})) })
+            .await
+            .unwrap();
+        assert_eq!(result["ok"], true);
+    }
+
+    #[tokio::test]
+    async fn test_run_with_timeout_returns_timeout_error() {
+        let result = run_with_timeout("web_fetch", Some(0), async {
+            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+            Ok(serde_json::json!({"ok": true}))
+        })
+        .await
+        .unwrap();
+        assert_eq!(result["error_code"], error_codes::TIMEOUT);
+        assert!(
+            result["error"]
+                .as_str()
+                .unwrap()
+                .contains("web_fetch timed out")
+        );
+    }
 }
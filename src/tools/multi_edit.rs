@@ -0,0 +1,604 @@
+use crate::agent::AgentEvent;
+use async_trait::async_trait;
+use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use tokio::sync::mpsc;
+use tracing::instrument;
+
+use super::edit::{find_similar_strings, offset_to_line};
+use super::{
+    MAX_SUGGESTION_PREVIEW_LEN, ToolEmitter, error_codes, error_response, resolve_and_validate_path,
+};
+
+/// Batched edits to one file, applied atomically: every edit is validated
+/// against the in-memory content (each against the result of the previous)
+/// before anything is written, so a failure partway through leaves the file
+/// untouched. This avoids the re-read-per-edit drift of sequential `edit`
+/// calls against the same file.
+pub struct MultiEditTool {
+    cwd: PathBuf,
+    allowed_paths: Vec<PathBuf>,
+    events_tx: Option<mpsc::Sender<AgentEvent>>,
+    dry_run: bool,
+    review: Option<super::review::ReviewChanges>,
+}
+
+impl MultiEditTool {
+    pub fn new(
+        cwd: PathBuf,
+        allowed_paths: Vec<PathBuf>,
+        events_tx: Option<mpsc::Sender<AgentEvent>>,
+    ) -> Self {
+        Self {
+            cwd,
+            allowed_paths,
+            events_tx,
+            dry_run: false,
+            review: None,
+        }
+    }
+
+    /// In dry-run mode the proposed edits are emitted as diffs and reported
+    /// as a success, but the file is not modified.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// In review mode the edited content is staged into the pending
+    /// changeset instead of hitting disk, until the user runs `/apply` or
+    /// `/discard`.
+    pub fn with_review(mut self, review: Option<super::review::ReviewChanges>) -> Self {
+        self.review = review;
+        self
+    }
+}
+
+impl ToolEmitter for MultiEditTool {
+    fn events_tx(&self) -> &Option<mpsc::Sender<AgentEvent>> {
+        &self.events_tx
+    }
+}
+
+/// One parsed entry of the `edits` array.
+struct Edit {
+    old_string: String,
+    new_string: String,
+    replace_all: bool,
+}
+
+fn parse_edit(value: &Value, index: usize) -> Result<Edit, Value> {
+    let old_string = value
+        .get("old_string")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| {
+            error_response(
+                &format!("Edit {} is missing 'old_string'.", index),
+                error_codes::INVALID_ARGUMENT,
+                json!({"edit_index": index}),
+            )
+        })?;
+    let new_string = value
+        .get("new_string")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| {
+            error_response(
+                &format!("Edit {} is missing 'new_string'.", index),
+                error_codes::INVALID_ARGUMENT,
+                json!({"edit_index": index}),
+            )
+        })?;
+    if old_string == new_string {
+        return Err(error_response(
+            &format!(
+                "Edit {}: 'old_string' and 'new_string' are the same. No replacement needed.",
+                index
+            ),
+            error_codes::INVALID_ARGUMENT,
+            json!({"edit_index": index}),
+        ));
+    }
+    Ok(Edit {
+        old_string: old_string.to_string(),
+        new_string: new_string.to_string(),
+        replace_all: value
+            .get("replace_all")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+    })
+}
+
+#[async_trait]
+impl CallableFunction for MultiEditTool {
+    fn declaration(&self) -> FunctionDeclaration {
+        FunctionDeclaration::new(
+            "multi_edit".to_string(),
+            "Apply a sequence of string replacements to one file atomically. All edits are validated before any write - if any edit fails, the file is left unchanged. Edits apply in order, each against the result of the previous one. Prefer this over several sequential 'edit' calls to the same file. Returns: {success, edits_applied, replacements, file_size} or {error, edit_index, suggestions?}".to_string(),
+            FunctionParameters::new(
+                "object".to_string(),
+                json!({
+                    "file_path": {
+                        "type": "string",
+                        "description": "Path to the file to edit"
+                    },
+                    "edits": {
+                        "type": "array",
+                        "description": "Replacements to apply in order. Each item: {old_string, new_string, replace_all?}. Same matching rules as the 'edit' tool: 'old_string' must match exactly and, unless 'replace_all' is true, uniquely.",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "old_string": {"type": "string"},
+                                "new_string": {"type": "string"},
+                                "replace_all": {"type": "boolean"}
+                            },
+                            "required": ["old_string", "new_string"]
+                        }
+                    }
+                }),
+                vec!["file_path".to_string(), "edits".to_string()],
+            ),
+        )
+    }
+
+    #[instrument(skip(self, args))]
+    async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        let file_path = args
+            .get("file_path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing file_path".to_string()))?;
+
+        let edits = args
+            .get("edits")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing edits array".to_string()))?;
+
+        if edits.is_empty() {
+            return Ok(error_response(
+                "The 'edits' array is empty. Provide at least one {old_string, new_string} entry.",
+                error_codes::INVALID_ARGUMENT,
+                json!({"path": file_path}),
+            ));
+        }
+
+        // Resolve and validate path
+        let path = match resolve_and_validate_path(file_path, &self.cwd, &self.allowed_paths) {
+            Ok(p) => p,
+            Err(e) => {
+                return Ok(error_response(
+                    &format!("Access denied: {}. Path must be within allowed paths.", e),
+                    error_codes::ACCESS_DENIED,
+                    json!({"path": file_path}),
+                ));
+            }
+        };
+
+        // Read the file; staged review content shadows the disk so repeated
+        // edits in review mode build on each other
+        let content = match super::review::staged_content(&self.review, &path) {
+            Some(staged) => staged,
+            None => match tokio::fs::read_to_string(&path).await {
+                Ok(c) => c,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    return Ok(error_response(
+                        &format!(
+                            "File not found: {}. Use 'edit' with 'create_if_not_exists' or 'write_file' to create files.",
+                            file_path
+                        ),
+                        error_codes::NOT_FOUND,
+                        json!({"path": file_path}),
+                    ));
+                }
+                Err(e) => {
+                    return Ok(error_response(
+                        &format!(
+                            "Failed to read {}: {}. Ensure the file exists and is not a directory.",
+                            path.display(),
+                            e
+                        ),
+                        error_codes::IO_ERROR,
+                        json!({"path": file_path}),
+                    ));
+                }
+            },
+        };
+
+        // Validate and apply every edit in memory; nothing touches disk until
+        // the whole sequence has succeeded.
+        let mut new_content = content;
+        let mut applied: Vec<Edit> = Vec::new();
+        let mut total_replacements = 0usize;
+        for (index, value) in edits.iter().enumerate() {
+            let edit = match parse_edit(value, index) {
+                Ok(edit) => edit,
+                Err(error) => return Ok(error),
+            };
+
+            let matches: Vec<_> = new_content
+                .match_indices(edit.old_string.as_str())
+                .collect();
+
+            if matches.is_empty() {
+                let suggestions = find_similar_strings(&new_content, &edit.old_string, 3, 0.6);
+
+                let mut context = json!({
+                    "path": file_path,
+                    "edit_index": index,
+                    "edits_validated": index
+                });
+
+                if !suggestions.is_empty() {
+                    let suggestion_details: Vec<Value> = suggestions
+                        .iter()
+                        .map(|(text, line, similarity)| {
+                            json!({
+                                "line": line,
+                                "similarity": format!("{:.0}%", similarity * 100.0),
+                                "text": if text.len() > MAX_SUGGESTION_PREVIEW_LEN {
+                                    format!("{}...", &text[..MAX_SUGGESTION_PREVIEW_LEN])
+                                } else {
+                                    text.clone()
+                                }
+                            })
+                        })
+                        .collect();
+
+                    context["suggestions"] = json!(suggestion_details);
+                    context["hint"] = json!(
+                        "Similar content found. Check for whitespace differences or use read_file to verify current content."
+                    );
+                }
+
+                return Ok(error_response(
+                    &format!(
+                        "Edit {}: 'old_string' was not found in {}. No edits were applied. Note that earlier edits in the sequence change what later edits match against.",
+                        index, file_path
+                    ),
+                    error_codes::NOT_FOUND,
+                    context,
+                ));
+            }
+
+            if !edit.replace_all && matches.len() > 1 {
+                let lines: Vec<_> = matches
+                    .iter()
+                    .map(|(offset, _)| offset_to_line(&new_content, *offset))
+                    .collect();
+
+                let lines_str = lines
+                    .iter()
+                    .map(|l| l.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                return Ok(error_response(
+                    &format!(
+                        "Edit {}: 'old_string' was found {} times in {} at lines {}. No edits were applied. Provide more surrounding context to make it unique, or set 'replace_all' to true.",
+                        index,
+                        matches.len(),
+                        file_path,
+                        lines_str
+                    ),
+                    error_codes::NOT_UNIQUE,
+                    json!({
+                        "path": file_path,
+                        "edit_index": index,
+                        "occurrences": matches.len(),
+                        "lines": lines
+                    }),
+                ));
+            }
+
+            let count = if edit.replace_all { matches.len() } else { 1 };
+            new_content = if edit.replace_all {
+                new_content.replace(&edit.old_string, &edit.new_string)
+            } else {
+                new_content.replacen(&edit.old_string, &edit.new_string, 1)
+            };
+            total_replacements += count;
+            applied.push(edit);
+        }
+
+        let file_size = new_content.len();
+
+        let mut staged = false;
+        if !self.dry_run {
+            if let Some(review) = &self.review {
+                super::review::stage(review, &path, new_content.clone());
+                staged = true;
+            } else {
+                // Snapshot the pre-edit state so /undo and revert_file can restore it.
+                super::checkpoint::snapshot(&self.cwd, &path);
+                if let Err(e) = tokio::fs::write(&path, &new_content).await {
+                    return Ok(error_response(
+                        &format!(
+                            "Failed to write {}: {}. Check file permissions.",
+                            path.display(),
+                            e
+                        ),
+                        error_codes::IO_ERROR,
+                        json!({"path": file_path}),
+                    ));
+                }
+            }
+        }
+
+        // Log one diff per edit, in application order
+        for edit in &applied {
+            let diff_output =
+                crate::diff::format_diff(&edit.old_string, &edit.new_string, 2, Some(file_path));
+            if !diff_output.is_empty() {
+                self.emit(&diff_output);
+            }
+        }
+
+        let mut result = json!({
+            "file_path": file_path,
+            "success": true,
+            "edits_applied": applied.len(),
+            "replacements": total_replacements,
+            "file_size": file_size
+        });
+        if self.dry_run {
+            result["dry_run"] = json!(true);
+        }
+        if staged {
+            result["staged"] = json!(true);
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_multi_edit_applies_all_edits() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("test.txt");
+        fs::write(&file_path, "alpha\nbeta\ngamma").unwrap();
+
+        let tool = MultiEditTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "test.txt",
+            "edits": [
+                {"old_string": "alpha", "new_string": "first"},
+                {"old_string": "gamma", "new_string": "third"}
+            ]
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["success"].as_bool().unwrap());
+        assert_eq!(result["edits_applied"], 2);
+        assert_eq!(result["replacements"], 2);
+        assert_eq!(
+            fs::read_to_string(&file_path).unwrap(),
+            "first\nbeta\nthird"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_multi_edit_checkpoints_for_undo() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("test.txt");
+        fs::write(&file_path, "alpha\nbeta").unwrap();
+
+        let tool = MultiEditTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "test.txt",
+            "edits": [{"old_string": "alpha", "new_string": "first"}]
+        });
+        tool.call(args).await.unwrap();
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "first\nbeta");
+
+        // /undo restores the pre-edit state from the snapshot.
+        let entry = crate::tools::checkpoint::undo_last(&cwd).unwrap();
+        assert!(entry.is_some(), "multi_edit must checkpoint before writing");
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "alpha\nbeta");
+        let _ = fs::remove_file(crate::tools::checkpoint::journal_path(&cwd));
+    }
+
+    #[tokio::test]
+    async fn test_multi_edit_review_stages_instead_of_writing() {
+        use std::sync::{Arc, RwLock};
+
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("test.txt");
+        fs::write(&file_path, "alpha\nbeta").unwrap();
+
+        let review: super::super::review::ReviewChanges =
+            Arc::new(RwLock::new(super::super::review::PendingChanges::default()));
+        let tool = MultiEditTool::new(cwd.clone(), vec![cwd.clone()], None)
+            .with_review(Some(review.clone()));
+
+        let result = tool
+            .call(json!({
+                "file_path": "test.txt",
+                "edits": [{"old_string": "alpha", "new_string": "first"}]
+            }))
+            .await
+            .unwrap();
+        assert!(result["staged"].as_bool().unwrap(), "got: {result}");
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "alpha\nbeta");
+
+        // A follow-up edit matches against the staged content, not the disk.
+        let result = tool
+            .call(json!({
+                "file_path": "test.txt",
+                "edits": [{"old_string": "first", "new_string": "FIRST"}]
+            }))
+            .await
+            .unwrap();
+        assert!(result["staged"].as_bool().unwrap());
+
+        let staged =
+            super::super::review::staged_content(&Some(review), &file_path.canonicalize().unwrap());
+        assert_eq!(staged.as_deref(), Some("FIRST\nbeta"));
+    }
+
+    #[tokio::test]
+    async fn test_multi_edit_is_atomic_on_failure() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("test.txt");
+        fs::write(&file_path, "alpha\nbeta").unwrap();
+
+        let tool = MultiEditTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "test.txt",
+            "edits": [
+                {"old_string": "alpha", "new_string": "first"},
+                {"old_string": "missing", "new_string": "whatever"}
+            ]
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["error"].as_str().unwrap().contains("was not found"));
+        assert_eq!(result["error_code"], error_codes::NOT_FOUND);
+        assert_eq!(result["context"]["edit_index"], 1);
+        // First edit validated fine, but nothing was written
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "alpha\nbeta");
+    }
+
+    #[tokio::test]
+    async fn test_multi_edit_sequential_edits_see_earlier_results() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("test.txt");
+        fs::write(&file_path, "step one").unwrap();
+
+        let tool = MultiEditTool::new(cwd.clone(), vec![cwd.clone()], None);
+        // The second edit matches text produced by the first
+        let args = json!({
+            "file_path": "test.txt",
+            "edits": [
+                {"old_string": "step one", "new_string": "step two"},
+                {"old_string": "two", "new_string": "three"}
+            ]
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["success"].as_bool().unwrap());
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "step three");
+    }
+
+    #[tokio::test]
+    async fn test_multi_edit_not_unique_without_replace_all() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("test.txt");
+        fs::write(&file_path, "repeat\nrepeat").unwrap();
+
+        let tool = MultiEditTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "test.txt",
+            "edits": [{"old_string": "repeat", "new_string": "once"}]
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert_eq!(result["error_code"], error_codes::NOT_UNIQUE);
+        assert_eq!(result["context"]["occurrences"], 2);
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "repeat\nrepeat");
+    }
+
+    #[tokio::test]
+    async fn test_multi_edit_replace_all() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("test.txt");
+        fs::write(&file_path, "repeat repeat done").unwrap();
+
+        let tool = MultiEditTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "test.txt",
+            "edits": [
+                {"old_string": "repeat", "new_string": "replaced", "replace_all": true},
+                {"old_string": "done", "new_string": "finished"}
+            ]
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["success"].as_bool().unwrap());
+        assert_eq!(result["replacements"], 3);
+        assert_eq!(
+            fs::read_to_string(&file_path).unwrap(),
+            "replaced replaced finished"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_multi_edit_empty_edits_array() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("test.txt"), "content").unwrap();
+
+        let tool = MultiEditTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({"file_path": "test.txt", "edits": []});
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["error"].as_str().unwrap().contains("empty"));
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+    }
+
+    #[tokio::test]
+    async fn test_multi_edit_file_not_found() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+
+        let tool = MultiEditTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "nonexistent.txt",
+            "edits": [{"old_string": "old", "new_string": "new"}]
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["error"].as_str().unwrap().contains("File not found"));
+        assert_eq!(result["error_code"], error_codes::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_multi_edit_dry_run_does_not_modify_file() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("test.txt");
+        fs::write(&file_path, "alpha\nbeta").unwrap();
+
+        let tool = MultiEditTool::new(cwd.clone(), vec![cwd.clone()], None).with_dry_run(true);
+        let args = json!({
+            "file_path": "test.txt",
+            "edits": [
+                {"old_string": "alpha", "new_string": "first"},
+                {"old_string": "beta", "new_string": "second"}
+            ]
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["success"].as_bool().unwrap());
+        assert!(result["dry_run"].as_bool().unwrap());
+        assert_eq!(result["edits_applied"], 2);
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "alpha\nbeta");
+    }
+
+    #[tokio::test]
+    async fn test_multi_edit_identical_old_and_new_rejected() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("test.txt"), "content").unwrap();
+
+        let tool = MultiEditTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "test.txt",
+            "edits": [{"old_string": "content", "new_string": "content"}]
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["error"].as_str().unwrap().contains("the same"));
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+    }
+}
@@ -0,0 +1,762 @@
+//! Jupyter notebook tools: read and edit `.ipynb` files structurally.
+//!
+//! Notebooks are JSON with fragile metadata (kernelspec, cell ids,
+//! execution counts). Hand-editing the raw JSON corrupts them easily, so
+//! these tools expose cells as the unit of work and leave everything else
+//! untouched.
+
+use async_trait::async_trait;
+use colored::Colorize;
+use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use tokio::sync::mpsc;
+use tracing::instrument;
+
+use super::{ToolEmitter, error_codes, error_response, resolve_and_validate_path};
+use crate::agent::AgentEvent;
+
+/// Cap on characters of output text summarized per cell.
+const MAX_OUTPUT_CHARS: usize = 2000;
+
+/// Join a cell's `source` field, which the format allows as either a
+/// string or a list of lines.
+fn cell_source(cell: &Value) -> String {
+    match cell.get("source") {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(lines)) => lines
+            .iter()
+            .filter_map(|l| l.as_str())
+            .collect::<Vec<_>>()
+            .concat(),
+        _ => String::new(),
+    }
+}
+
+/// Split source back into the list-of-lines form the format prefers
+/// (each line keeps its trailing newline).
+fn source_lines(source: &str) -> Vec<String> {
+    let mut lines: Vec<String> = source
+        .split_inclusive('\n')
+        .map(|l| l.to_string())
+        .collect();
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+    lines
+}
+
+/// Summarize a code cell's outputs as plain text, truncated.
+fn summarize_outputs(cell: &Value) -> Option<String> {
+    let outputs = cell.get("outputs")?.as_array()?;
+    if outputs.is_empty() {
+        return None;
+    }
+    let mut summary = String::new();
+    for output in outputs {
+        let output_type = output.get("output_type").and_then(|t| t.as_str());
+        match output_type {
+            Some("stream") => {
+                if let Some(Value::Array(lines)) = output.get("text") {
+                    summary.extend(lines.iter().filter_map(|l| l.as_str().map(String::from)));
+                } else if let Some(Value::String(s)) = output.get("text") {
+                    summary.push_str(s);
+                }
+            }
+            Some("execute_result") | Some("display_data") => {
+                if let Some(text) = output.pointer("/data/text~1plain") {
+                    match text {
+                        Value::Array(lines) => summary
+                            .extend(lines.iter().filter_map(|l| l.as_str().map(String::from))),
+                        Value::String(s) => summary.push_str(s),
+                        _ => {}
+                    }
+                } else if let Some(data) = output.get("data").and_then(|d| d.as_object()) {
+                    let mimes: Vec<&String> = data.keys().collect();
+                    summary.push_str(&format!("[non-text output: {:?}]\n", mimes));
+                }
+            }
+            Some("error") => {
+                let ename = output.get("ename").and_then(|v| v.as_str()).unwrap_or("");
+                let evalue = output.get("evalue").and_then(|v| v.as_str()).unwrap_or("");
+                summary.push_str(&format!("[error] {}: {}\n", ename, evalue));
+            }
+            _ => summary.push_str("[unknown output]\n"),
+        }
+    }
+    if summary.len() > MAX_OUTPUT_CHARS {
+        let mut end = MAX_OUTPUT_CHARS;
+        while end > 0 && !summary.is_char_boundary(end) {
+            end -= 1;
+        }
+        summary.truncate(end);
+        summary.push_str("... [truncated]");
+    }
+    Some(summary)
+}
+
+/// Load and parse a notebook, or return a tool error value.
+async fn load_notebook(path: &Path, file_path: &str) -> Result<Value, Value> {
+    let raw = tokio::fs::read_to_string(path).await.map_err(|e| {
+        error_response(
+            &format!("Failed to read {}: {}", file_path, e),
+            error_codes::IO_ERROR,
+            json!({"path": file_path}),
+        )
+    })?;
+    parse_notebook(&raw, file_path)
+}
+
+/// Parse raw notebook JSON, or return a tool error value.
+fn parse_notebook(raw: &str, file_path: &str) -> Result<Value, Value> {
+    let notebook: Value = serde_json::from_str(raw).map_err(|e| {
+        error_response(
+            &format!("{} is not valid notebook JSON: {}", file_path, e),
+            error_codes::INVALID_ARGUMENT,
+            json!({"path": file_path}),
+        )
+    })?;
+    if notebook.get("cells").and_then(|c| c.as_array()).is_none() {
+        return Err(error_response(
+            &format!("{} has no 'cells' array - not a notebook?", file_path),
+            error_codes::INVALID_ARGUMENT,
+            json!({"path": file_path}),
+        ));
+    }
+    Ok(notebook)
+}
+
+// ============================================================================
+// notebook_read
+// ============================================================================
+
+pub struct NotebookReadTool {
+    cwd: PathBuf,
+    allowed_paths: Vec<PathBuf>,
+    events_tx: Option<mpsc::Sender<AgentEvent>>,
+}
+
+impl NotebookReadTool {
+    pub fn new(
+        cwd: PathBuf,
+        allowed_paths: Vec<PathBuf>,
+        events_tx: Option<mpsc::Sender<AgentEvent>>,
+    ) -> Self {
+        Self {
+            cwd,
+            allowed_paths,
+            events_tx,
+        }
+    }
+}
+
+impl ToolEmitter for NotebookReadTool {
+    fn events_tx(&self) -> &Option<mpsc::Sender<AgentEvent>> {
+        &self.events_tx
+    }
+}
+
+#[async_trait]
+impl CallableFunction for NotebookReadTool {
+    fn declaration(&self) -> FunctionDeclaration {
+        FunctionDeclaration::new(
+            "notebook_read".to_string(),
+            "Read a Jupyter notebook (.ipynb) as structured cells instead of raw JSON. Returns each cell's index, type, and source, with output summaries for executed code cells. Returns: {cells: [{index, cell_type, source, outputs?}], cell_count, language?}".to_string(),
+            FunctionParameters::new(
+                "object".to_string(),
+                json!({
+                    "file_path": {
+                        "type": "string",
+                        "description": "Path to the .ipynb file (absolute or relative to cwd)"
+                    },
+                    "include_outputs": {
+                        "type": "boolean",
+                        "description": "Include summarized cell outputs (stream text, results, errors). (default: true)"
+                    }
+                }),
+                vec!["file_path".to_string()],
+            ),
+        )
+    }
+
+    #[instrument(skip(self, args))]
+    async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        let file_path = args
+            .get("file_path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing file_path".to_string()))?;
+        let include_outputs = args
+            .get("include_outputs")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+
+        let path = match resolve_and_validate_path(file_path, &self.cwd, &self.allowed_paths) {
+            Ok(p) => p,
+            Err(e) => {
+                return Ok(error_response(
+                    &format!("Access denied: {}. Path must be within allowed paths.", e),
+                    error_codes::ACCESS_DENIED,
+                    json!({"path": file_path}),
+                ));
+            }
+        };
+        let notebook = match load_notebook(&path, file_path).await {
+            Ok(n) => n,
+            Err(e) => return Ok(e),
+        };
+
+        let cells: Vec<Value> = notebook["cells"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| {
+                let mut entry = json!({
+                    "index": index,
+                    "cell_type": cell.get("cell_type").and_then(|t| t.as_str()).unwrap_or("unknown"),
+                    "source": cell_source(cell),
+                });
+                if include_outputs && let Some(outputs) = summarize_outputs(cell) {
+                    entry["outputs"] = json!(outputs);
+                }
+                if let Some(count) = cell.get("execution_count").filter(|c| !c.is_null()) {
+                    entry["execution_count"] = count.clone();
+                }
+                entry
+            })
+            .collect();
+
+        let language = notebook
+            .pointer("/metadata/kernelspec/language")
+            .and_then(|l| l.as_str());
+
+        self.emit(&format!("  {} cells", cells.len()).dimmed().to_string());
+
+        let mut response = json!({
+            "path": file_path,
+            "cells": cells,
+            "cell_count": notebook["cells"].as_array().unwrap().len(),
+        });
+        if let Some(language) = language {
+            response["language"] = json!(language);
+        }
+        Ok(response)
+    }
+}
+
+// ============================================================================
+// notebook_edit
+// ============================================================================
+
+pub struct NotebookEditTool {
+    cwd: PathBuf,
+    allowed_paths: Vec<PathBuf>,
+    events_tx: Option<mpsc::Sender<AgentEvent>>,
+    dry_run: bool,
+    review: Option<super::review::ReviewChanges>,
+}
+
+impl NotebookEditTool {
+    pub fn new(
+        cwd: PathBuf,
+        allowed_paths: Vec<PathBuf>,
+        events_tx: Option<mpsc::Sender<AgentEvent>>,
+    ) -> Self {
+        Self {
+            cwd,
+            allowed_paths,
+            events_tx,
+            dry_run: false,
+            review: None,
+        }
+    }
+
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// In review mode the edited notebook is staged into the pending
+    /// changeset instead of hitting disk, until the user runs `/apply` or
+    /// `/discard`.
+    pub fn with_review(mut self, review: Option<super::review::ReviewChanges>) -> Self {
+        self.review = review;
+        self
+    }
+}
+
+impl ToolEmitter for NotebookEditTool {
+    fn events_tx(&self) -> &Option<mpsc::Sender<AgentEvent>> {
+        &self.events_tx
+    }
+}
+
+#[async_trait]
+impl CallableFunction for NotebookEditTool {
+    fn declaration(&self) -> FunctionDeclaration {
+        FunctionDeclaration::new(
+            "notebook_edit".to_string(),
+            "Edit a Jupyter notebook (.ipynb) by cell: replace a cell's source, insert a new cell, or delete one. Cell indices match notebook_read. Metadata and the other cells are preserved exactly; replaced code cells have their outputs cleared. Returns: {operation, cell_index, cell_count, success}".to_string(),
+            FunctionParameters::new(
+                "object".to_string(),
+                json!({
+                    "file_path": {
+                        "type": "string",
+                        "description": "Path to the .ipynb file (absolute or relative to cwd)"
+                    },
+                    "operation": {
+                        "type": "string",
+                        "enum": ["replace", "insert", "delete"],
+                        "description": "replace a cell's source, insert a new cell at the index, or delete the cell"
+                    },
+                    "cell_index": {
+                        "type": "integer",
+                        "description": "0-indexed cell position (as reported by notebook_read). For insert, the new cell lands at this index; use cell_count to append."
+                    },
+                    "source": {
+                        "type": "string",
+                        "description": "New cell source. Required for replace and insert."
+                    },
+                    "cell_type": {
+                        "type": "string",
+                        "enum": ["code", "markdown"],
+                        "description": "Cell type for insert. (default: code)"
+                    }
+                }),
+                vec![
+                    "file_path".to_string(),
+                    "operation".to_string(),
+                    "cell_index".to_string(),
+                ],
+            ),
+        )
+    }
+
+    #[instrument(skip(self, args))]
+    async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        let file_path = args
+            .get("file_path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing file_path".to_string()))?;
+        let operation = args
+            .get("operation")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing operation".to_string()))?;
+        let cell_index = args
+            .get("cell_index")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing cell_index".to_string()))?
+            as usize;
+        let source = args.get("source").and_then(|v| v.as_str());
+
+        let path = match resolve_and_validate_path(file_path, &self.cwd, &self.allowed_paths) {
+            Ok(p) => p,
+            Err(e) => {
+                return Ok(error_response(
+                    &format!("Access denied: {}. Path must be within allowed paths.", e),
+                    error_codes::ACCESS_DENIED,
+                    json!({"path": file_path}),
+                ));
+            }
+        };
+        // Staged review content shadows the disk so repeated cell edits in
+        // review mode build on each other
+        let loaded = match super::review::staged_content(&self.review, &path) {
+            Some(staged) => parse_notebook(&staged, file_path),
+            None => load_notebook(&path, file_path).await,
+        };
+        let mut notebook = match loaded {
+            Ok(n) => n,
+            Err(e) => return Ok(e),
+        };
+        let cell_count = notebook["cells"].as_array().unwrap().len();
+
+        // Bounds: insert may target one past the end (append)
+        let max_index = if operation == "insert" {
+            cell_count
+        } else {
+            cell_count.saturating_sub(1)
+        };
+        if cell_count == 0 && operation != "insert" || cell_index > max_index {
+            return Ok(error_response(
+                &format!(
+                    "Cell index {} is out of bounds (notebook has {} cells)",
+                    cell_index, cell_count
+                ),
+                error_codes::INVALID_ARGUMENT,
+                json!({"path": file_path, "cell_index": cell_index, "cell_count": cell_count}),
+            ));
+        }
+
+        let cells = notebook["cells"].as_array_mut().unwrap();
+        match operation {
+            "replace" => {
+                let Some(source) = source else {
+                    return Err(FunctionError::ArgumentMismatch(
+                        "Missing source for replace".to_string(),
+                    ));
+                };
+                let cell = &mut cells[cell_index];
+                let old_source = cell_source(cell);
+                cell["source"] = json!(source_lines(source));
+                // Stale outputs are misleading once the code changes
+                if cell.get("cell_type").and_then(|t| t.as_str()) == Some("code") {
+                    cell["outputs"] = json!([]);
+                    cell["execution_count"] = Value::Null;
+                }
+                let diff = crate::diff::format_diff(&old_source, source, 2, Some(file_path));
+                if !diff.is_empty() {
+                    self.emit(&diff);
+                }
+            }
+            "insert" => {
+                let Some(source) = source else {
+                    return Err(FunctionError::ArgumentMismatch(
+                        "Missing source for insert".to_string(),
+                    ));
+                };
+                let cell_type = args
+                    .get("cell_type")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("code");
+                let mut cell = json!({
+                    "cell_type": cell_type,
+                    "id": uuid::Uuid::new_v4().to_string(),
+                    "metadata": {},
+                    "source": source_lines(source),
+                });
+                if cell_type == "code" {
+                    cell["outputs"] = json!([]);
+                    cell["execution_count"] = Value::Null;
+                }
+                cells.insert(cell_index, cell);
+                self.emit(
+                    &format!("  inserted {} cell at {}", cell_type, cell_index)
+                        .dimmed()
+                        .to_string(),
+                );
+            }
+            "delete" => {
+                cells.remove(cell_index);
+                self.emit(
+                    &format!("  deleted cell {}", cell_index)
+                        .dimmed()
+                        .to_string(),
+                );
+            }
+            other => {
+                return Ok(error_response(
+                    &format!(
+                        "Unknown operation '{}'. Use replace, insert, or delete.",
+                        other
+                    ),
+                    error_codes::INVALID_ARGUMENT,
+                    json!({"operation": other}),
+                ));
+            }
+        }
+        let new_count = notebook["cells"].as_array().unwrap().len();
+
+        if self.dry_run {
+            return Ok(json!({
+                "operation": operation,
+                "cell_index": cell_index,
+                "cell_count": new_count,
+                "dry_run": true,
+                "success": true
+            }));
+        }
+
+        let mut serialized = serde_json::to_string_pretty(&notebook)
+            .map_err(|e| FunctionError::ExecutionError(e.to_string().into()))?;
+        serialized.push('\n');
+        if let Some(review) = &self.review {
+            super::review::stage(review, &path, serialized);
+            return Ok(json!({
+                "operation": operation,
+                "cell_index": cell_index,
+                "cell_count": new_count,
+                "staged": true,
+                "success": true
+            }));
+        }
+        // Snapshot the pre-edit state so /undo and revert_file can restore it.
+        super::checkpoint::snapshot(&self.cwd, &path);
+        if let Err(e) = tokio::fs::write(&path, serialized).await {
+            return Ok(error_response(
+                &format!("Failed to write {}: {}", file_path, e),
+                error_codes::IO_ERROR,
+                json!({"path": file_path}),
+            ));
+        }
+
+        Ok(json!({
+            "operation": operation,
+            "cell_index": cell_index,
+            "cell_count": new_count,
+            "success": true
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn sample_notebook() -> Value {
+        json!({
+            "cells": [
+                {
+                    "cell_type": "markdown",
+                    "id": "intro",
+                    "metadata": {},
+                    "source": ["# Analysis\n", "Setup below."]
+                },
+                {
+                    "cell_type": "code",
+                    "id": "setup",
+                    "metadata": {"tags": ["keep-me"]},
+                    "execution_count": 3,
+                    "source": ["import pandas as pd\n", "df = pd.read_csv('data.csv')"],
+                    "outputs": [
+                        {"output_type": "stream", "name": "stdout", "text": ["loaded 100 rows\n"]}
+                    ]
+                }
+            ],
+            "metadata": {
+                "kernelspec": {"language": "python", "name": "python3"}
+            },
+            "nbformat": 4,
+            "nbformat_minor": 5
+        })
+    }
+
+    fn write_notebook(dir: &Path) -> PathBuf {
+        let path = dir.join("analysis.ipynb");
+        fs::write(
+            &path,
+            serde_json::to_string_pretty(&sample_notebook()).unwrap(),
+        )
+        .unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn test_notebook_read_cells() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        write_notebook(&cwd);
+
+        let tool = NotebookReadTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let result = tool
+            .call(json!({"file_path": "analysis.ipynb"}))
+            .await
+            .unwrap();
+
+        assert_eq!(result["cell_count"], 2);
+        assert_eq!(result["language"], "python");
+        let cells = result["cells"].as_array().unwrap();
+        assert_eq!(cells[0]["cell_type"], "markdown");
+        assert_eq!(cells[0]["source"], "# Analysis\nSetup below.");
+        assert_eq!(cells[1]["cell_type"], "code");
+        assert_eq!(cells[1]["execution_count"], 3);
+        assert!(
+            cells[1]["outputs"]
+                .as_str()
+                .unwrap()
+                .contains("loaded 100 rows")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_notebook_read_without_outputs() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        write_notebook(&cwd);
+
+        let tool = NotebookReadTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let result = tool
+            .call(json!({"file_path": "analysis.ipynb", "include_outputs": false}))
+            .await
+            .unwrap();
+
+        assert!(result["cells"][1].get("outputs").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_notebook_read_invalid_json() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("broken.ipynb"), "not json").unwrap();
+
+        let tool = NotebookReadTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let result = tool
+            .call(json!({"file_path": "broken.ipynb"}))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+    }
+
+    #[tokio::test]
+    async fn test_notebook_edit_replace_clears_outputs_and_preserves_metadata() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let path = write_notebook(&cwd);
+
+        let tool = NotebookEditTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let result = tool
+            .call(json!({
+                "file_path": "analysis.ipynb",
+                "operation": "replace",
+                "cell_index": 1,
+                "source": "import polars as pl\ndf = pl.read_csv('data.csv')"
+            }))
+            .await
+            .unwrap();
+        assert!(result["success"].as_bool().unwrap(), "got: {result}");
+
+        let saved: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let cell = &saved["cells"][1];
+        assert_eq!(
+            cell["source"],
+            json!(["import polars as pl\n", "df = pl.read_csv('data.csv')"])
+        );
+        // Outputs cleared, execution count reset
+        assert_eq!(cell["outputs"], json!([]));
+        assert!(cell["execution_count"].is_null());
+        // Cell metadata and notebook metadata survive
+        assert_eq!(cell["metadata"]["tags"], json!(["keep-me"]));
+        assert_eq!(saved["metadata"]["kernelspec"]["name"], "python3");
+        assert_eq!(saved["nbformat"], 4);
+    }
+
+    #[tokio::test]
+    async fn test_notebook_edit_insert_and_delete() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let path = write_notebook(&cwd);
+
+        let tool = NotebookEditTool::new(cwd.clone(), vec![cwd.clone()], None);
+
+        // Append a markdown cell (index == cell_count)
+        let result = tool
+            .call(json!({
+                "file_path": "analysis.ipynb",
+                "operation": "insert",
+                "cell_index": 2,
+                "source": "## Results",
+                "cell_type": "markdown"
+            }))
+            .await
+            .unwrap();
+        assert_eq!(result["cell_count"], 3);
+
+        let saved: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved["cells"][2]["cell_type"], "markdown");
+        assert!(saved["cells"][2]["id"].is_string());
+
+        // Delete the first cell
+        let result = tool
+            .call(json!({
+                "file_path": "analysis.ipynb",
+                "operation": "delete",
+                "cell_index": 0
+            }))
+            .await
+            .unwrap();
+        assert_eq!(result["cell_count"], 2);
+
+        let saved: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved["cells"][0]["cell_type"], "code");
+    }
+
+    #[tokio::test]
+    async fn test_notebook_edit_out_of_bounds() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        write_notebook(&cwd);
+
+        let tool = NotebookEditTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let result = tool
+            .call(json!({
+                "file_path": "analysis.ipynb",
+                "operation": "replace",
+                "cell_index": 5,
+                "source": "x"
+            }))
+            .await
+            .unwrap();
+
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+        assert_eq!(result["context"]["cell_count"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_notebook_edit_review_stages_instead_of_writing() {
+        use std::sync::{Arc, RwLock};
+
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let path = write_notebook(&cwd);
+        let before = fs::read_to_string(&path).unwrap();
+
+        let review: super::super::review::ReviewChanges =
+            Arc::new(RwLock::new(super::super::review::PendingChanges::default()));
+        let tool = NotebookEditTool::new(cwd.clone(), vec![cwd.clone()], None)
+            .with_review(Some(review.clone()));
+
+        let result = tool
+            .call(json!({
+                "file_path": "analysis.ipynb",
+                "operation": "replace",
+                "cell_index": 1,
+                "source": "print('staged')"
+            }))
+            .await
+            .unwrap();
+        assert!(result["staged"].as_bool().unwrap(), "got: {result}");
+        assert_eq!(fs::read_to_string(&path).unwrap(), before);
+
+        // A follow-up edit sees the staged notebook, not the disk.
+        let result = tool
+            .call(json!({
+                "file_path": "analysis.ipynb",
+                "operation": "delete",
+                "cell_index": 0
+            }))
+            .await
+            .unwrap();
+        assert!(result["staged"].as_bool().unwrap());
+        assert_eq!(result["cell_count"], 1);
+
+        let staged =
+            super::super::review::staged_content(&Some(review), &path.canonicalize().unwrap())
+                .unwrap();
+        let staged: Value = serde_json::from_str(&staged).unwrap();
+        assert_eq!(staged["cells"].as_array().unwrap().len(), 1);
+        assert_eq!(staged["cells"][0]["source"], json!(["print('staged')"]));
+    }
+
+    #[tokio::test]
+    async fn test_notebook_edit_dry_run() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let path = write_notebook(&cwd);
+        let before = fs::read_to_string(&path).unwrap();
+
+        let tool = NotebookEditTool::new(cwd.clone(), vec![cwd.clone()], None).with_dry_run(true);
+        let result = tool
+            .call(json!({
+                "file_path": "analysis.ipynb",
+                "operation": "delete",
+                "cell_index": 0
+            }))
+            .await
+            .unwrap();
+
+        assert!(result["dry_run"].as_bool().unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap(), before);
+    }
+}
@@ -0,0 +1,441 @@
+//! Structural file outlines via tree-sitter.
+//!
+//! Reading a 3k-line file into context to find one function is wasteful.
+//! `outline` parses the file and returns just its skeleton - functions,
+//! structs, impls, classes - with line numbers, so the model can jump
+//! straight to a targeted `read_file` with an offset.
+
+use crate::agent::AgentEvent;
+use crate::tools::{ToolEmitter, error_codes, error_response, resolve_and_validate_path};
+use async_trait::async_trait;
+use colored::Colorize;
+use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use tokio::sync::mpsc;
+use tracing::instrument;
+use tree_sitter::Node;
+
+/// Longest rendered signature before truncation.
+const MAX_SIGNATURE_LEN: usize = 120;
+
+/// Grammar for a file, by extension.
+pub(crate) fn language_for_path(path: &Path) -> Option<(&'static str, tree_sitter::Language)> {
+    match path.extension()?.to_str()? {
+        "rs" => Some(("rust", tree_sitter_rust::LANGUAGE.into())),
+        "py" | "pyi" => Some(("python", tree_sitter_python::LANGUAGE.into())),
+        "js" | "jsx" => Some(("javascript", tree_sitter_javascript::LANGUAGE.into())),
+        "ts" => Some((
+            "typescript",
+            tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+        )),
+        "tsx" => Some(("typescript", tree_sitter_typescript::LANGUAGE_TSX.into())),
+        "go" => Some(("go", tree_sitter_go::LANGUAGE.into())),
+        _ => None,
+    }
+}
+
+/// Display label for an outline-worthy node kind, or None for nodes that
+/// don't belong in the skeleton.
+fn item_label(language: &str, kind: &str) -> Option<&'static str> {
+    match (language, kind) {
+        ("rust", "function_item") => Some("fn"),
+        ("rust", "struct_item") => Some("struct"),
+        ("rust", "enum_item") => Some("enum"),
+        ("rust", "trait_item") => Some("trait"),
+        ("rust", "impl_item") => Some("impl"),
+        ("rust", "mod_item") => Some("mod"),
+        ("rust", "macro_definition") => Some("macro"),
+        ("python", "function_definition") => Some("def"),
+        ("python", "class_definition") => Some("class"),
+        ("javascript" | "typescript", "function_declaration") => Some("function"),
+        ("javascript" | "typescript", "class_declaration") => Some("class"),
+        ("javascript" | "typescript", "method_definition") => Some("method"),
+        ("typescript", "interface_declaration") => Some("interface"),
+        ("typescript", "enum_declaration") => Some("enum"),
+        ("typescript", "type_alias_declaration") => Some("type"),
+        ("go", "function_declaration") => Some("func"),
+        ("go", "method_declaration") => Some("method"),
+        ("go", "type_declaration") => Some("type"),
+        _ => None,
+    }
+}
+
+/// Name of an item node. Most grammars put it in a `name` field; rust impls
+/// use `trait`/`type`, and anything else falls back to the first identifier.
+fn item_name(node: Node, source: &str) -> String {
+    if let Some(name) = node.child_by_field_name("name") {
+        return name.utf8_text(source.as_bytes()).unwrap_or("?").to_string();
+    }
+    // `impl Trait for Type` / `impl Type`
+    if node.kind() == "impl_item" {
+        let type_name = node
+            .child_by_field_name("type")
+            .and_then(|n| n.utf8_text(source.as_bytes()).ok())
+            .unwrap_or("?");
+        return match node
+            .child_by_field_name("trait")
+            .and_then(|n| n.utf8_text(source.as_bytes()).ok())
+        {
+            Some(trait_name) => format!("{} for {}", trait_name, type_name),
+            None => type_name.to_string(),
+        };
+    }
+    // e.g. go type_declaration wraps its name in a type_spec child
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind().contains("identifier") {
+            return child
+                .utf8_text(source.as_bytes())
+                .unwrap_or("?")
+                .to_string();
+        }
+        if let Some(name) = child.child_by_field_name("name") {
+            return name.utf8_text(source.as_bytes()).unwrap_or("?").to_string();
+        }
+    }
+    "?".to_string()
+}
+
+/// The item's header: its source up to (not including) the body, collapsed
+/// to one line.
+fn item_signature(node: Node, source: &str) -> String {
+    let end = node
+        .child_by_field_name("body")
+        .map(|body| body.start_byte())
+        .unwrap_or_else(|| node.end_byte());
+    let header = &source[node.start_byte()..end];
+    let collapsed = header.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut collapsed = collapsed.trim_end_matches(['{', ':']).trim_end().to_string();
+    if collapsed.chars().count() > MAX_SIGNATURE_LEN {
+        collapsed = collapsed.chars().take(MAX_SIGNATURE_LEN).collect();
+        collapsed.push_str("...");
+    }
+    collapsed
+}
+
+/// Parse `source` (grammar chosen by `path`'s extension) and return its
+/// outline items, or None if the language is unsupported or parsing fails.
+/// Used by both the `outline` tool and the repo map.
+pub(crate) fn outline_items(path: &Path, source: &str) -> Option<Vec<Value>> {
+    let (language, grammar) = language_for_path(path)?;
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(&grammar).ok()?;
+    let tree = parser.parse(source, None)?;
+    let mut items = Vec::new();
+    collect_items(tree.root_node(), source, language, 0, &mut items);
+    Some(items)
+}
+
+fn collect_items(
+    node: Node,
+    source: &str,
+    language: &str,
+    depth: usize,
+    items: &mut Vec<Value>,
+) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        // Python wraps decorated defs; outline the inner definition at the
+        // decorator's line
+        let target = if language == "python" && child.kind() == "decorated_definition" {
+            child.child_by_field_name("definition").unwrap_or(child)
+        } else {
+            child
+        };
+
+        match item_label(language, target.kind()) {
+            Some(label) => {
+                items.push(json!({
+                    "line": child.start_position().row + 1,
+                    "end_line": target.end_position().row + 1,
+                    "depth": depth,
+                    "kind": label,
+                    "name": item_name(target, source),
+                    "signature": item_signature(target, source),
+                }));
+                collect_items(target, source, language, depth + 1, items);
+            }
+            None => collect_items(child, source, language, depth, items),
+        }
+    }
+}
+
+/// Render items as an indented text skeleton with line numbers.
+fn render_outline(items: &[Value]) -> String {
+    items
+        .iter()
+        .map(|item| {
+            format!(
+                "{:>5} {}{}",
+                item["line"],
+                "  ".repeat(item["depth"].as_u64().unwrap_or(0) as usize),
+                item["signature"].as_str().unwrap_or("")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub struct OutlineTool {
+    cwd: PathBuf,
+    allowed_paths: Vec<PathBuf>,
+    events_tx: Option<mpsc::Sender<AgentEvent>>,
+}
+
+impl ToolEmitter for OutlineTool {
+    fn events_tx(&self) -> &Option<mpsc::Sender<AgentEvent>> {
+        &self.events_tx
+    }
+}
+
+impl OutlineTool {
+    pub fn new(
+        cwd: PathBuf,
+        allowed_paths: Vec<PathBuf>,
+        events_tx: Option<mpsc::Sender<AgentEvent>>,
+    ) -> Self {
+        Self {
+            cwd,
+            allowed_paths,
+            events_tx,
+        }
+    }
+}
+
+#[async_trait]
+impl CallableFunction for OutlineTool {
+    fn declaration(&self) -> FunctionDeclaration {
+        FunctionDeclaration::new(
+            "outline".to_string(),
+            "Get the structural skeleton of a source file - functions, structs, impls, classes - with line numbers, without reading the whole file into context. Use the line numbers with read_file's offset to jump to an item. Returns: {file_path, items, outline, total_lines}".to_string(),
+            FunctionParameters::new(
+                "object".to_string(),
+                json!({
+                    "file_path": {
+                        "type": "string",
+                        "description": "Source file to outline (relative or absolute)"
+                    }
+                }),
+                vec!["file_path".to_string()],
+            ),
+        )
+    }
+
+    #[instrument(skip(self, args))]
+    async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        let file_path = args
+            .get("file_path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing file_path".to_string()))?;
+
+        let path = match resolve_and_validate_path(file_path, &self.cwd, &self.allowed_paths) {
+            Ok(p) => p,
+            Err(e) => {
+                return Ok(error_response(
+                    &e,
+                    error_codes::ACCESS_DENIED,
+                    json!({ "file_path": file_path }),
+                ));
+            }
+        };
+
+        let Some((language, grammar)) = language_for_path(&path) else {
+            return Ok(error_response(
+                &format!("No outline support for '{}'", file_path),
+                error_codes::INVALID_ARGUMENT,
+                json!({ "file_path": file_path }),
+            ));
+        };
+
+        let source = match std::fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(e) => {
+                return Ok(error_response(
+                    &format!("Failed to read {}: {}", file_path, e),
+                    error_codes::IO_ERROR,
+                    json!({ "file_path": file_path }),
+                ));
+            }
+        };
+
+        let mut parser = tree_sitter::Parser::new();
+        if let Err(e) = parser.set_language(&grammar) {
+            return Ok(error_response(
+                &format!("Failed to load {} grammar: {}", language, e),
+                error_codes::IO_ERROR,
+                json!({ "file_path": file_path }),
+            ));
+        }
+        let Some(tree) = parser.parse(&source, None) else {
+            return Ok(error_response(
+                &format!("Failed to parse {}", file_path),
+                error_codes::IO_ERROR,
+                json!({ "file_path": file_path }),
+            ));
+        };
+
+        let mut items = Vec::new();
+        collect_items(tree.root_node(), &source, language, 0, &mut items);
+
+        self.emit(&format!(
+            "  {}",
+            format!("{} item(s)", items.len()).dimmed()
+        ));
+
+        Ok(json!({
+            "file_path": file_path,
+            "items": items,
+            "outline": render_outline(&items),
+            "total_lines": source.lines().count(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn tool_for(dir: &Path) -> OutlineTool {
+        OutlineTool::new(dir.to_path_buf(), vec![dir.to_path_buf()], None)
+    }
+
+    #[tokio::test]
+    async fn test_outline_rust_file() {
+        let dir = tempdir().unwrap();
+        let source = r#"
+pub struct Config {
+    model: String,
+}
+
+impl Config {
+    pub fn new(model: String) -> Self {
+        Self { model }
+    }
+}
+
+fn helper() -> u32 {
+    42
+}
+"#;
+        std::fs::write(dir.path().join("lib.rs"), source).unwrap();
+
+        let result = tool_for(dir.path())
+            .call(json!({ "file_path": "lib.rs" }))
+            .await
+            .unwrap();
+
+        let items = result["items"].as_array().unwrap();
+        let summary: Vec<(&str, &str, u64)> = items
+            .iter()
+            .map(|i| {
+                (
+                    i["kind"].as_str().unwrap(),
+                    i["name"].as_str().unwrap(),
+                    i["depth"].as_u64().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("struct", "Config", 0),
+                ("impl", "Config", 0),
+                ("fn", "new", 1),
+                ("fn", "helper", 0),
+            ],
+            "got: {result}"
+        );
+
+        // Line numbers are 1-indexed and match the source
+        assert_eq!(items[0]["line"], 2);
+        assert!(
+            items[2]["signature"]
+                .as_str()
+                .unwrap()
+                .contains("pub fn new(model: String) -> Self")
+        );
+        assert_eq!(result["total_lines"], 14);
+
+        // Rendered outline indents nested items
+        let outline = result["outline"].as_str().unwrap();
+        assert!(outline.contains("  pub fn new"), "outline:\n{outline}");
+    }
+
+    #[tokio::test]
+    async fn test_outline_python_file() {
+        let dir = tempdir().unwrap();
+        let source = r#"
+class Greeter:
+    def greet(self, name):
+        return f"hi {name}"
+
+@decorator
+def main():
+    pass
+"#;
+        std::fs::write(dir.path().join("app.py"), source).unwrap();
+
+        let result = tool_for(dir.path())
+            .call(json!({ "file_path": "app.py" }))
+            .await
+            .unwrap();
+
+        let items = result["items"].as_array().unwrap();
+        let summary: Vec<(&str, &str)> = items
+            .iter()
+            .map(|i| (i["kind"].as_str().unwrap(), i["name"].as_str().unwrap()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("class", "Greeter"), ("def", "greet"), ("def", "main")],
+            "got: {result}"
+        );
+        // Decorated def is reported at the decorator's line
+        assert_eq!(items[2]["line"], 6);
+    }
+
+    #[tokio::test]
+    async fn test_outline_unsupported_extension() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "hello").unwrap();
+
+        let result = tool_for(dir.path())
+            .call(json!({ "file_path": "notes.txt" }))
+            .await
+            .unwrap();
+
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+    }
+
+    #[tokio::test]
+    async fn test_outline_missing_file() {
+        let dir = tempdir().unwrap();
+        let result = tool_for(dir.path())
+            .call(json!({ "file_path": "nope.rs" }))
+            .await
+            .unwrap();
+
+        assert!(result.get("error").is_some(), "got: {result}");
+    }
+
+    #[test]
+    fn test_signature_truncation() {
+        let long_params = (0..30)
+            .map(|i| format!("arg{}: u64", i))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let source = format!("fn long({}) {{}}", long_params);
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_rust::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(&source, None).unwrap();
+        let node = tree.root_node().child(0).unwrap();
+
+        let signature = item_signature(node, &source);
+        assert!(signature.ends_with("..."), "signature: {signature}");
+        assert!(signature.chars().count() <= MAX_SIGNATURE_LEN + 3);
+    }
+}
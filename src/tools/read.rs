@@ -1,8 +1,12 @@
 use async_trait::async_trait;
+use base64::Engine as _;
 use colored::Colorize;
 use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
 use serde_json::{Value, json};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
 use tokio::io::AsyncReadExt;
 use tokio::sync::mpsc;
 use tracing::instrument;
@@ -10,10 +14,44 @@ use tracing::instrument;
 use super::{ToolEmitter, error_codes, error_response, resolve_and_validate_path};
 use crate::agent::AgentEvent;
 
+/// Images larger than this are rejected rather than base64-encoded into
+/// the context window.
+const MAX_IMAGE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// What the model last saw for a file: its mtime/size plus the offset/limit
+/// window of that read. When a re-read matches, the tool returns a compact
+/// "unchanged" response instead of re-sending the contents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ReadCacheEntry {
+    mtime: SystemTime,
+    size: u64,
+    offset: usize,
+    limit: usize,
+}
+
+/// Per-interaction read cache owned by the tool service. Cleared when a new
+/// interaction starts so stale "unchanged" answers can't leak across
+/// conversations.
+pub(crate) type ReadCache = Arc<RwLock<HashMap<PathBuf, ReadCacheEntry>>>;
+
+/// Image MIME type for a lowercased file extension, if it's one we inline.
+fn image_mime(extension: &str) -> Option<&'static str> {
+    match extension {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        _ => None,
+    }
+}
+
 pub struct ReadTool {
     cwd: PathBuf,
     allowed_paths: Vec<PathBuf>,
     events_tx: Option<mpsc::Sender<AgentEvent>>,
+    /// Per-interaction cache of what the model already read (see `ReadCache`).
+    cache: Option<ReadCache>,
+    review: Option<super::review::ReviewChanges>,
 }
 
 impl ReadTool {
@@ -26,8 +64,23 @@ impl ReadTool {
             cwd,
             allowed_paths,
             events_tx,
+            cache: None,
+            review: None,
         }
     }
+
+    /// Attach the service's per-interaction read cache.
+    pub(crate) fn with_cache(mut self, cache: ReadCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// In review mode staged content shadows the disk, so the model sees
+    /// its own pending edits when re-reading a file.
+    pub fn with_review(mut self, review: Option<super::review::ReviewChanges>) -> Self {
+        self.review = review;
+        self
+    }
 }
 
 impl ToolEmitter for ReadTool {
@@ -36,12 +89,167 @@ impl ToolEmitter for ReadTool {
     }
 }
 
+impl ReadTool {
+    /// Inline an image as base64 for Gemini's multimodal input.
+    async fn read_image(&self, path: &Path, mime_type: &str) -> Result<Value, FunctionError> {
+        let metadata = match tokio::fs::metadata(path).await {
+            Ok(m) => m,
+            Err(e) => {
+                return Ok(error_response(
+                    &format!("Failed to read {}: {}", path.display(), e),
+                    error_codes::IO_ERROR,
+                    json!({"path": path.display().to_string()}),
+                ));
+            }
+        };
+        if metadata.len() > MAX_IMAGE_SIZE {
+            return Ok(error_response(
+                &format!(
+                    "Image is {} bytes, larger than the {} byte inline limit",
+                    metadata.len(),
+                    MAX_IMAGE_SIZE
+                ),
+                error_codes::INVALID_ARGUMENT,
+                json!({"path": path.display().to_string(), "size_bytes": metadata.len()}),
+            ));
+        }
+
+        match tokio::fs::read(path).await {
+            Ok(bytes) => {
+                let size_bytes = bytes.len();
+                let data = base64::engine::general_purpose::STANDARD.encode(&bytes);
+                self.emit(
+                    &format!("  {} image, {} KB", mime_type, size_bytes / 1024)
+                        .dimmed()
+                        .to_string(),
+                );
+                // Inline preview for kitty-protocol terminals; other
+                // terminals keep just the summary line above.
+                if mime_type == "image/png"
+                    && let Some(preview) = crate::term_image::kitty_inline_png(&bytes)
+                {
+                    self.emit(&preview);
+                }
+                Ok(json!({
+                    "path": path.display().to_string(),
+                    "type": "image",
+                    "mime_type": mime_type,
+                    "encoding": "base64",
+                    "data": data,
+                    "size_bytes": size_bytes
+                }))
+            }
+            Err(e) => Ok(error_response(
+                &format!("Failed to read {}: {}", path.display(), e),
+                error_codes::IO_ERROR,
+                json!({"path": path.display().to_string()}),
+            )),
+        }
+    }
+
+    /// Extract a PDF's text and return it with the usual line pagination.
+    async fn read_pdf(
+        &self,
+        path: &Path,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Value, FunctionError> {
+        let owned = path.to_path_buf();
+        let extracted = tokio::task::spawn_blocking(move || pdf_extract::extract_text(&owned))
+            .await
+            .map_err(|e| {
+                FunctionError::ExecutionError(format!("PDF extraction panicked: {}", e).into())
+            })?;
+        match extracted {
+            Ok(text) => {
+                let mut response = self.paginated_response(path, &text, offset, limit);
+                if response.get("error").is_none() {
+                    response["format"] = json!("pdf");
+                }
+                Ok(response)
+            }
+            Err(e) => Ok(error_response(
+                &format!("Failed to extract text from {}: {}", path.display(), e),
+                error_codes::IO_ERROR,
+                json!({"path": path.display().to_string()}),
+            )),
+        }
+    }
+
+    /// Slice `contents` by 1-indexed line offset/limit, with line numbers
+    /// and truncation info. Shared by the text and PDF paths.
+    fn paginated_response(
+        &self,
+        path: &Path,
+        contents: &str,
+        offset: usize,
+        limit: usize,
+    ) -> Value {
+        let lines: Vec<&str> = contents.lines().collect();
+        let total_lines = lines.len();
+
+        let start = offset.saturating_sub(1);
+        let end = (start + limit).min(total_lines);
+
+        if start >= total_lines && total_lines > 0 {
+            return error_response(
+                &format!(
+                    "Offset {} is out of bounds (total lines: {})",
+                    offset, total_lines
+                ),
+                error_codes::INVALID_ARGUMENT,
+                json!({"path": path.display().to_string(), "offset": offset, "total_lines": total_lines}),
+            );
+        }
+
+        let mut formatted_contents = String::new();
+        for (i, line) in lines.iter().enumerate().take(end).skip(start) {
+            let line_num = i + 1;
+            formatted_contents.push_str(&format!("{:>4}→{line}\n", line_num));
+        }
+
+        let mut response = json!({
+            "path": path.display().to_string(),
+            "contents": formatted_contents,
+            "total_lines": total_lines,
+        });
+
+        if end < total_lines {
+            response["truncated"] = json!(format!(
+                "Showing lines {}-{} of {}. Use offset to read more.",
+                start + 1,
+                end,
+                total_lines
+            ));
+        }
+
+        // Emit visual output
+        let lines_shown = end.saturating_sub(start);
+        let msg = if end < total_lines {
+            format!(
+                "  {} lines ({}-{} of {})",
+                lines_shown,
+                start + 1,
+                end,
+                total_lines
+            )
+            .dimmed()
+            .to_string()
+        } else {
+            format!("  {} lines", total_lines).dimmed().to_string()
+        };
+        self.emit(&msg);
+
+        response
+    }
+}
+
 #[async_trait]
 impl CallableFunction for ReadTool {
     fn declaration(&self) -> FunctionDeclaration {
         FunctionDeclaration::new(
             "read_file".to_string(),
-            "Read the contents of a file. Returns the file contents as text with line numbers. Use this to examine source code, configuration files, or other text documents. For large files, use offset and limit to read in chunks. Returns: {contents, total_lines, truncated?}".to_string(),
+            "Read the contents of a file. Text files return line-numbered contents; PNG/JPEG/GIF/WebP images return inline base64 data for multimodal input; PDFs return their extracted text. For large files, use offset and limit to read in chunks. Returns: {contents, total_lines, truncated?} or {type: 'image', mime_type, data}".to_string(),
             FunctionParameters::new(
                 "object".to_string(),
                 json!({
@@ -86,6 +294,53 @@ impl CallableFunction for ReadTool {
             }
         };
 
+        // Staged review content shadows the disk so the model reads its own
+        // pending edits, not the stale on-disk version
+        if let Some(contents) = super::review::staged_content(&self.review, &path) {
+            let mut response = self.paginated_response(&path, &contents, offset, limit);
+            if response.get("error").is_none() {
+                response["staged"] = json!(true);
+            }
+            return Ok(response);
+        }
+
+        // Images and PDFs get multimodal handling instead of the binary check
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .unwrap_or_default();
+        if let Some(mime_type) = image_mime(&extension) {
+            return self.read_image(&path, mime_type).await;
+        }
+        if extension == "pdf" {
+            return self.read_pdf(&path, offset, limit).await;
+        }
+
+        // Same file, same window, unchanged mtime/size: the model already
+        // has these contents in context, so skip re-sending them.
+        let cache_entry = tokio::fs::metadata(&path).await.ok().and_then(|meta| {
+            meta.modified().ok().map(|mtime| ReadCacheEntry {
+                mtime,
+                size: meta.len(),
+                offset,
+                limit,
+            })
+        });
+        if let (Some(cache), Some(entry)) = (&self.cache, &cache_entry)
+            && cache
+                .read()
+                .map(|map| map.get(&path) == Some(entry))
+                .unwrap_or(false)
+        {
+            self.emit(&"  unchanged since last read".dimmed().to_string());
+            return Ok(json!({
+                "path": path.display().to_string(),
+                "unchanged": true,
+                "note": "File unchanged since last read this session; contents omitted. They are already in context above. Re-read with a different offset/limit or after modifying the file to get contents again."
+            }));
+        }
+
         // Check if binary
         let mut file = match tokio::fs::File::open(&path).await {
             Ok(f) => f,
@@ -125,61 +380,13 @@ impl CallableFunction for ReadTool {
 
         match tokio::fs::read_to_string(&path).await {
             Ok(contents) => {
-                let lines: Vec<&str> = contents.lines().collect();
-                let total_lines = lines.len();
-
-                let start = offset.saturating_sub(1);
-                let end = (start + limit).min(total_lines);
-
-                if start >= total_lines && total_lines > 0 {
-                    return Ok(error_response(
-                        &format!(
-                            "Offset {} is out of bounds (total lines: {})",
-                            offset, total_lines
-                        ),
-                        error_codes::INVALID_ARGUMENT,
-                        json!({"path": path.display().to_string(), "offset": offset, "total_lines": total_lines}),
-                    ));
-                }
-
-                let mut formatted_contents = String::new();
-                for (i, line) in lines.iter().enumerate().take(end).skip(start) {
-                    let line_num = i + 1;
-                    formatted_contents.push_str(&format!("{:>4}→{line}\n", line_num));
-                }
-
-                let mut response = json!({
-                    "path": path.display().to_string(),
-                    "contents": formatted_contents,
-                    "total_lines": total_lines,
-                });
-
-                if end < total_lines {
-                    response["truncated"] = json!(format!(
-                        "Showing lines {}-{} of {}. Use offset to read more.",
-                        start + 1,
-                        end,
-                        total_lines
-                    ));
+                let response = self.paginated_response(&path, &contents, offset, limit);
+                if response.get("error").is_none()
+                    && let (Some(cache), Some(entry)) = (&self.cache, cache_entry)
+                    && let Ok(mut map) = cache.write()
+                {
+                    map.insert(path.clone(), entry);
                 }
-
-                // Emit visual output
-                let lines_shown = end.saturating_sub(start);
-                let msg = if end < total_lines {
-                    format!(
-                        "  {} lines ({}-{} of {})",
-                        lines_shown,
-                        start + 1,
-                        end,
-                        total_lines
-                    )
-                    .dimmed()
-                    .to_string()
-                } else {
-                    format!("  {} lines", total_lines).dimmed().to_string()
-                };
-                self.emit(&msg);
-
                 Ok(response)
             }
             Err(e) => Ok(error_response(
@@ -195,7 +402,7 @@ impl CallableFunction for ReadTool {
     }
 }
 
-fn is_binary(bytes: &[u8]) -> bool {
+pub(crate) fn is_binary(bytes: &[u8]) -> bool {
     if bytes.is_empty() {
         return false;
     }
@@ -358,6 +565,70 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_image_mime() {
+        assert_eq!(image_mime("png"), Some("image/png"));
+        assert_eq!(image_mime("jpg"), Some("image/jpeg"));
+        assert_eq!(image_mime("jpeg"), Some("image/jpeg"));
+        assert_eq!(image_mime("webp"), Some("image/webp"));
+        assert_eq!(image_mime("rs"), None);
+        assert_eq!(image_mime("pdf"), None);
+    }
+
+    #[tokio::test]
+    async fn test_read_tool_image_returns_base64() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let png_bytes = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0DIHDR";
+        fs::write(cwd.join("shot.png"), png_bytes).unwrap();
+
+        let tool = ReadTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let result = tool.call(json!({"file_path": "shot.png"})).await.unwrap();
+
+        assert_eq!(result["type"], "image");
+        assert_eq!(result["mime_type"], "image/png");
+        assert_eq!(result["size_bytes"], png_bytes.len());
+        let decoded = base64::engine::general_purpose::STANDARD
+            .decode(result["data"].as_str().unwrap())
+            .unwrap();
+        assert_eq!(decoded, png_bytes);
+    }
+
+    #[tokio::test]
+    async fn test_read_tool_image_too_large() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(
+            cwd.join("huge.png"),
+            vec![0u8; (MAX_IMAGE_SIZE + 1) as usize],
+        )
+        .unwrap();
+
+        let tool = ReadTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let result = tool.call(json!({"file_path": "huge.png"})).await.unwrap();
+
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+        assert!(result["error"].as_str().unwrap().contains("inline limit"));
+    }
+
+    #[tokio::test]
+    async fn test_read_tool_invalid_pdf() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("broken.pdf"), "not actually a pdf").unwrap();
+
+        let tool = ReadTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let result = tool.call(json!({"file_path": "broken.pdf"})).await.unwrap();
+
+        assert_eq!(result["error_code"], error_codes::IO_ERROR);
+        assert!(
+            result["error"]
+                .as_str()
+                .unwrap()
+                .contains("Failed to extract text")
+        );
+    }
+
     #[tokio::test]
     async fn test_read_tool_binary_file() {
         let dir = tempdir().unwrap();
@@ -379,4 +650,89 @@ mod tests {
         assert_eq!(result["error_code"], error_codes::BINARY_FILE);
         assert!(result["context"]["path"].is_string());
     }
+
+    #[tokio::test]
+    async fn test_read_cache_unchanged_reread() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("cached.txt");
+        fs::write(&file_path, "line 1\nline 2").unwrap();
+
+        let cache: ReadCache = Arc::new(RwLock::new(HashMap::new()));
+        let tool = ReadTool::new(cwd.clone(), vec![cwd.clone()], None).with_cache(cache);
+
+        let first = tool.call(json!({"file_path": "cached.txt"})).await.unwrap();
+        assert_eq!(first["total_lines"], 2);
+
+        // Same window, unchanged file: compact response, no contents.
+        let second = tool.call(json!({"file_path": "cached.txt"})).await.unwrap();
+        assert_eq!(second["unchanged"], true);
+        assert!(second.get("contents").is_none());
+
+        // Changing the file invalidates the entry.
+        fs::write(&file_path, "line 1\nline 2\nline 3 is longer").unwrap();
+        let third = tool.call(json!({"file_path": "cached.txt"})).await.unwrap();
+        assert_eq!(third["total_lines"], 3);
+        assert!(third["contents"].as_str().unwrap().contains("line 3"));
+    }
+
+    #[tokio::test]
+    async fn test_read_cache_different_window_misses() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("windowed.txt"), "line 1\nline 2\nline 3").unwrap();
+
+        let cache: ReadCache = Arc::new(RwLock::new(HashMap::new()));
+        let tool = ReadTool::new(cwd.clone(), vec![cwd.clone()], None).with_cache(cache);
+
+        tool.call(json!({"file_path": "windowed.txt", "limit": 1}))
+            .await
+            .unwrap();
+
+        // A different offset/limit is a different view, so contents return.
+        let result = tool
+            .call(json!({"file_path": "windowed.txt", "limit": 2}))
+            .await
+            .unwrap();
+        assert!(result["contents"].as_str().unwrap().contains("line 2"));
+        assert!(result.get("unchanged").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_read_tool_staged_content_shadows_disk() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("staged.txt");
+        fs::write(&file_path, "disk version").unwrap();
+
+        let review: super::super::review::ReviewChanges =
+            Arc::new(RwLock::new(super::super::review::PendingChanges::default()));
+        super::super::review::stage(
+            &review,
+            &file_path.canonicalize().unwrap(),
+            "staged version".to_string(),
+        );
+
+        let tool = ReadTool::new(cwd.clone(), vec![cwd.clone()], None).with_review(Some(review));
+        let result = tool.call(json!({"file_path": "staged.txt"})).await.unwrap();
+        assert_eq!(result["staged"], true);
+        assert!(
+            result["contents"]
+                .as_str()
+                .unwrap()
+                .contains("staged version")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_without_cache_always_returns_contents() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("plain.txt"), "line 1").unwrap();
+
+        let tool = ReadTool::new(cwd.clone(), vec![cwd.clone()], None);
+        tool.call(json!({"file_path": "plain.txt"})).await.unwrap();
+        let again = tool.call(json!({"file_path": "plain.txt"})).await.unwrap();
+        assert!(again["contents"].as_str().unwrap().contains("line 1"));
+    }
 }
@@ -0,0 +1,280 @@
+use async_trait::async_trait;
+use colored::Colorize;
+use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use tokio::sync::mpsc;
+use tracing::instrument;
+
+use super::{ToolEmitter, error_codes, error_response, resolve_and_validate_path};
+use crate::agent::AgentEvent;
+
+/// Cap on files per call - beyond this the combined output stops being
+/// "several small files" and starts being a context problem.
+const MAX_FILES_PER_CALL: usize = 25;
+
+const DEFAULT_LIMIT_PER_FILE: usize = 500;
+
+pub struct ReadManyTool {
+    cwd: PathBuf,
+    allowed_paths: Vec<PathBuf>,
+    events_tx: Option<mpsc::Sender<AgentEvent>>,
+}
+
+impl ReadManyTool {
+    pub fn new(
+        cwd: PathBuf,
+        allowed_paths: Vec<PathBuf>,
+        events_tx: Option<mpsc::Sender<AgentEvent>>,
+    ) -> Self {
+        Self {
+            cwd,
+            allowed_paths,
+            events_tx,
+        }
+    }
+
+    /// Read one file as a line-numbered entry, or an inline error entry.
+    /// Per-file failures don't fail the batch - the model sees which
+    /// files were readable and which weren't.
+    async fn read_one(&self, file_path: &str, limit: usize) -> Value {
+        let path = match resolve_and_validate_path(file_path, &self.cwd, &self.allowed_paths) {
+            Ok(p) => p,
+            Err(e) => {
+                return json!({
+                    "path": file_path,
+                    "error": format!("Access denied: {}. Path must be within allowed paths.", e),
+                    "error_code": error_codes::ACCESS_DENIED
+                });
+            }
+        };
+
+        let contents = match tokio::fs::read_to_string(&path).await {
+            Ok(c) => c,
+            Err(e) => {
+                return json!({
+                    "path": file_path,
+                    "error": format!("Failed to read: {}", e),
+                    "error_code": error_codes::IO_ERROR
+                });
+            }
+        };
+
+        let lines: Vec<&str> = contents.lines().collect();
+        let total_lines = lines.len();
+        let end = limit.min(total_lines);
+
+        let mut formatted_contents = String::new();
+        for (i, line) in lines.iter().enumerate().take(end) {
+            formatted_contents.push_str(&format!("{:>4}→{line}\n", i + 1));
+        }
+
+        let mut entry = json!({
+            "path": file_path,
+            "contents": formatted_contents,
+            "total_lines": total_lines,
+        });
+        if end < total_lines {
+            entry["truncated"] = json!(format!(
+                "Showing lines 1-{} of {}. Use read_file with offset for the rest.",
+                end, total_lines
+            ));
+        }
+        entry
+    }
+}
+
+impl ToolEmitter for ReadManyTool {
+    fn events_tx(&self) -> &Option<mpsc::Sender<AgentEvent>> {
+        &self.events_tx
+    }
+}
+
+#[async_trait]
+impl CallableFunction for ReadManyTool {
+    fn declaration(&self) -> FunctionDeclaration {
+        FunctionDeclaration::new(
+            "read_many".to_string(),
+            "Read several files in one call - e.g. a handful of related modules. Each file is line-numbered and truncated at limit_per_file lines; unreadable files produce inline per-file errors instead of failing the batch. Returns: {files: [{path, contents, total_lines, truncated?} | {path, error}], files_read, estimated_tokens}".to_string(),
+            FunctionParameters::new(
+                "object".to_string(),
+                json!({
+                    "file_paths": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "Paths of the files to read (absolute or relative to cwd). At most 25 per call."
+                    },
+                    "limit_per_file": {
+                        "type": "integer",
+                        "description": "Maximum lines returned per file. (default: 500)"
+                    }
+                }),
+                vec!["file_paths".to_string()],
+            ),
+        )
+    }
+
+    #[instrument(skip(self, args))]
+    async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        let file_paths: Vec<String> = args
+            .get("file_paths")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing file_paths".to_string()))?
+            .iter()
+            .filter_map(|v| v.as_str().map(|s| s.to_string()))
+            .collect();
+        let limit = args
+            .get("limit_per_file")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_LIMIT_PER_FILE as u64) as usize;
+
+        if file_paths.is_empty() {
+            return Ok(error_response(
+                "file_paths is empty",
+                error_codes::INVALID_ARGUMENT,
+                json!({"file_paths": []}),
+            ));
+        }
+        if file_paths.len() > MAX_FILES_PER_CALL {
+            return Ok(error_response(
+                &format!(
+                    "{} files requested, max is {} per call",
+                    file_paths.len(),
+                    MAX_FILES_PER_CALL
+                ),
+                error_codes::INVALID_ARGUMENT,
+                json!({"requested": file_paths.len(), "max": MAX_FILES_PER_CALL}),
+            ));
+        }
+
+        let mut files = Vec::with_capacity(file_paths.len());
+        let mut files_read = 0;
+        let mut estimated_tokens: u64 = 0;
+        for file_path in &file_paths {
+            let entry = self.read_one(file_path, limit).await;
+            if entry.get("error").is_none() {
+                files_read += 1;
+                estimated_tokens += u64::from(crate::tokens::heuristic_tokens(
+                    entry["contents"].as_str().unwrap_or(""),
+                ));
+            }
+            files.push(entry);
+        }
+
+        self.emit(
+            &format!(
+                "  {} of {} files, ~{} tok",
+                files_read,
+                file_paths.len(),
+                estimated_tokens
+            )
+            .dimmed()
+            .to_string(),
+        );
+
+        Ok(json!({
+            "files": files,
+            "files_read": files_read,
+            "estimated_tokens": estimated_tokens
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_read_many_success() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("a.rs"), "fn a() {}\n").unwrap();
+        fs::write(cwd.join("b.rs"), "fn b() {}\nfn b2() {}\n").unwrap();
+
+        let tool = ReadManyTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let result = tool
+            .call(json!({"file_paths": ["a.rs", "b.rs"]}))
+            .await
+            .unwrap();
+
+        assert_eq!(result["files_read"], 2);
+        assert!(result["estimated_tokens"].as_u64().unwrap() > 0);
+        let files = result["files"].as_array().unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0]["path"], "a.rs");
+        assert!(files[0]["contents"].as_str().unwrap().contains("fn a()"));
+        assert_eq!(files[1]["total_lines"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_read_many_per_file_errors_do_not_fail_batch() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("good.rs"), "fn good() {}\n").unwrap();
+
+        let tool = ReadManyTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let result = tool
+            .call(json!({"file_paths": ["good.rs", "missing.rs", "../outside.rs"]}))
+            .await
+            .unwrap();
+
+        assert_eq!(result["files_read"], 1);
+        let files = result["files"].as_array().unwrap();
+        assert_eq!(files.len(), 3);
+        assert!(files[0].get("error").is_none());
+        assert_eq!(files[1]["error_code"], error_codes::IO_ERROR);
+        assert_eq!(files[2]["error_code"], error_codes::ACCESS_DENIED);
+    }
+
+    #[tokio::test]
+    async fn test_read_many_limit_per_file() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let content = (1..=10)
+            .map(|i| format!("line {i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        fs::write(cwd.join("long.txt"), content).unwrap();
+
+        let tool = ReadManyTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let result = tool
+            .call(json!({"file_paths": ["long.txt"], "limit_per_file": 3}))
+            .await
+            .unwrap();
+
+        let entry = &result["files"][0];
+        assert_eq!(entry["total_lines"], 10);
+        assert!(entry["contents"].as_str().unwrap().contains("line 3"));
+        assert!(!entry["contents"].as_str().unwrap().contains("line 4"));
+        assert!(
+            entry["truncated"]
+                .as_str()
+                .unwrap()
+                .contains("Showing lines 1-3 of 10")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_many_empty_list() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let tool = ReadManyTool::new(cwd.clone(), vec![cwd.clone()], None);
+
+        let result = tool.call(json!({"file_paths": []})).await.unwrap();
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+    }
+
+    #[tokio::test]
+    async fn test_read_many_too_many_files() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let tool = ReadManyTool::new(cwd.clone(), vec![cwd.clone()], None);
+
+        let paths: Vec<String> = (0..26).map(|i| format!("f{i}.txt")).collect();
+        let result = tool.call(json!({"file_paths": paths})).await.unwrap();
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+        assert!(result["error"].as_str().unwrap().contains("max is 25"));
+    }
+}
@@ -0,0 +1,283 @@
+//! `remember` tool: append vetted guidance to CLAUDE.md.
+//!
+//! The system prompt used to suggest self-improvement by editing
+//! `src/system_prompt.md`, which only works when running from a checkout.
+//! This tool appends guidance to the project's CLAUDE.md (or the global
+//! `~/.clemini/CLEMINI.md`) instead - both are injected into the system
+//! prompt at startup, so the learning persists for binary installs too.
+//! Because it changes how the agent behaves in every future session, the
+//! append requires explicit user confirmation first.
+
+use async_trait::async_trait;
+use colored::Colorize;
+use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use tokio::sync::mpsc;
+use tracing::instrument;
+
+use super::{ToolEmitter, error_codes, error_response};
+use crate::agent::AgentEvent;
+
+/// Heading learned guidance is grouped under in the target file.
+const GUIDANCE_HEADING: &str = "## Learned Guidance";
+
+/// Global guidance file, injected for every workspace.
+pub fn global_guidance_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".clemini")
+        .join("CLEMINI.md")
+}
+
+/// Append `instruction` as a bullet under the guidance heading, creating
+/// the heading (and file) if needed.
+fn append_guidance(existing: &str, instruction: &str) -> String {
+    let mut out = existing.trim_end().to_string();
+    if !out.is_empty() {
+        out.push_str("\n\n");
+    }
+    if !existing.contains(GUIDANCE_HEADING) {
+        out.push_str(GUIDANCE_HEADING);
+        out.push_str("\n\n");
+    }
+    out.push_str(&format!("- {}\n", instruction.trim()));
+    out
+}
+
+pub struct RememberTool {
+    cwd: PathBuf,
+    events_tx: Option<mpsc::Sender<AgentEvent>>,
+    dry_run: bool,
+}
+
+impl RememberTool {
+    pub fn new(cwd: PathBuf, events_tx: Option<mpsc::Sender<AgentEvent>>) -> Self {
+        Self {
+            cwd,
+            events_tx,
+            dry_run: false,
+        }
+    }
+
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+}
+
+impl ToolEmitter for RememberTool {
+    fn events_tx(&self) -> &Option<mpsc::Sender<AgentEvent>> {
+        &self.events_tx
+    }
+}
+
+#[async_trait]
+impl CallableFunction for RememberTool {
+    fn declaration(&self) -> FunctionDeclaration {
+        FunctionDeclaration::new(
+            "remember".to_string(),
+            "Persist a piece of guidance for all future sessions by appending it to the project's CLAUDE.md (scope=project) or the global ~/.clemini/CLEMINI.md (scope=global). First call returns {needs_confirmation: true} - show the user the exact instruction and target, and only retry with confirmed=true after they approve. Returns: {instruction, target, success}".to_string(),
+            FunctionParameters::new(
+                "object".to_string(),
+                json!({
+                    "instruction": {
+                        "type": "string",
+                        "description": "The guidance to persist, phrased as a standing instruction (e.g. 'Run make fmt before committing'). Keep it concise and broadly applicable."
+                    },
+                    "scope": {
+                        "type": "string",
+                        "enum": ["project", "global"],
+                        "description": "project appends to CLAUDE.md in the working directory; global appends to ~/.clemini/CLEMINI.md. (default: project)"
+                    },
+                    "confirmed": {
+                        "type": "boolean",
+                        "description": "Set to true only after the user explicitly approves the instruction in conversation. (default: false)"
+                    }
+                }),
+                vec!["instruction".to_string()],
+            ),
+        )
+    }
+
+    #[instrument(skip(self, args))]
+    async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        let instruction = args
+            .get("instruction")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing instruction".to_string()))?;
+        let scope = args
+            .get("scope")
+            .and_then(|v| v.as_str())
+            .unwrap_or("project");
+        let confirmed = args
+            .get("confirmed")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        if instruction.trim().is_empty() {
+            return Ok(error_response(
+                "instruction is empty",
+                error_codes::INVALID_ARGUMENT,
+                json!({"instruction": instruction}),
+            ));
+        }
+        let path = match scope {
+            "project" => self.cwd.join("CLAUDE.md"),
+            "global" => global_guidance_path(),
+            other => {
+                return Ok(error_response(
+                    &format!("Unknown scope '{}'. Use project or global.", other),
+                    error_codes::INVALID_ARGUMENT,
+                    json!({"scope": other}),
+                ));
+            }
+        };
+        let target = path.display().to_string();
+
+        if !confirmed {
+            let msg = format!(
+                "  {} {}",
+                "CONFIRM (guidance change):".yellow(),
+                instruction.dimmed()
+            );
+            self.emit(&msg);
+            return Ok(json!({
+                "needs_confirmation": true,
+                "instruction": instruction,
+                "target": target,
+                "message": format!(
+                    "This will permanently add guidance to {}: \"{}\". Please confirm.",
+                    target, instruction
+                )
+            }));
+        }
+
+        if self.dry_run {
+            return Ok(json!({
+                "instruction": instruction,
+                "target": target,
+                "dry_run": true,
+                "success": true
+            }));
+        }
+
+        let existing = std::fs::read_to_string(&path).unwrap_or_default();
+        let updated = append_guidance(&existing, instruction);
+        if let Some(parent) = path.parent()
+            && let Err(e) = std::fs::create_dir_all(parent)
+        {
+            return Ok(error_response(
+                &format!("Failed to create {}: {}", parent.display(), e),
+                error_codes::IO_ERROR,
+                json!({"target": target}),
+            ));
+        }
+        if let Err(e) = std::fs::write(&path, updated) {
+            return Ok(error_response(
+                &format!("Failed to write {}: {}", target, e),
+                error_codes::IO_ERROR,
+                json!({"target": target}),
+            ));
+        }
+
+        self.emit(
+            &format!("  remembered in {}", target)
+                .dimmed()
+                .to_string(),
+        );
+        Ok(json!({
+            "instruction": instruction,
+            "target": target,
+            "success": true
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_append_guidance_creates_heading_once() {
+        let first = append_guidance("", "Run make fmt before committing");
+        assert_eq!(
+            first,
+            "## Learned Guidance\n\n- Run make fmt before committing\n"
+        );
+
+        let second = append_guidance(&first, "Prefer read_many for related files");
+        assert_eq!(second.matches(GUIDANCE_HEADING).count(), 1);
+        assert!(second.ends_with("- Prefer read_many for related files\n"));
+    }
+
+    #[test]
+    fn test_append_guidance_preserves_existing_content() {
+        let existing = "# CLAUDE.md\n\nProject notes here.\n";
+        let updated = append_guidance(existing, "Tests need --include-ignored");
+        assert!(updated.starts_with("# CLAUDE.md\n\nProject notes here."));
+        assert!(updated.contains(GUIDANCE_HEADING));
+    }
+
+    #[tokio::test]
+    async fn test_remember_requires_confirmation() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let tool = RememberTool::new(cwd.clone(), None);
+
+        let result = tool
+            .call(json!({"instruction": "Always run make check"}))
+            .await
+            .unwrap();
+        assert!(result["needs_confirmation"].as_bool().unwrap());
+        assert!(!cwd.join("CLAUDE.md").exists());
+    }
+
+    #[tokio::test]
+    async fn test_remember_confirmed_appends_to_claude_md() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("CLAUDE.md"), "# Project\n\nNotes.\n").unwrap();
+
+        let tool = RememberTool::new(cwd.clone(), None);
+        let result = tool
+            .call(json!({"instruction": "Always run make check", "confirmed": true}))
+            .await
+            .unwrap();
+        assert!(result["success"].as_bool().unwrap(), "got: {result}");
+
+        let contents = fs::read_to_string(cwd.join("CLAUDE.md")).unwrap();
+        assert!(contents.starts_with("# Project"));
+        assert!(contents.contains("## Learned Guidance"));
+        assert!(contents.contains("- Always run make check"));
+    }
+
+    #[tokio::test]
+    async fn test_remember_dry_run_does_not_write() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let tool = RememberTool::new(cwd.clone(), None).with_dry_run(true);
+
+        let result = tool
+            .call(json!({"instruction": "x", "confirmed": true}))
+            .await
+            .unwrap();
+        assert!(result["dry_run"].as_bool().unwrap());
+        assert!(!cwd.join("CLAUDE.md").exists());
+    }
+
+    #[tokio::test]
+    async fn test_remember_unknown_scope() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let tool = RememberTool::new(cwd.clone(), None);
+
+        let result = tool
+            .call(json!({"instruction": "x", "scope": "galactic"}))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+    }
+}
@@ -0,0 +1,469 @@
+use async_trait::async_trait;
+use colored::Colorize;
+use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
+use globset::{Glob, GlobSetBuilder};
+use ignore::WalkBuilder;
+use ignore::overrides::OverrideBuilder;
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use tokio::sync::mpsc;
+use tracing::instrument;
+
+use crate::agent::AgentEvent;
+use crate::tools::{
+    DEFAULT_EXCLUDES, ToolEmitter, error_codes, error_response, make_relative, validate_path,
+};
+
+/// Cap on files modified in one call, so a too-broad pattern can't rewrite
+/// an entire monorepo before anyone notices.
+const MAX_FILES_CHANGED: usize = 500;
+
+pub struct ReplaceTool {
+    cwd: PathBuf,
+    allowed_paths: Vec<PathBuf>,
+    events_tx: Option<mpsc::Sender<AgentEvent>>,
+    dry_run: bool,
+    review: Option<super::review::ReviewChanges>,
+}
+
+impl ReplaceTool {
+    pub fn new(
+        cwd: PathBuf,
+        allowed_paths: Vec<PathBuf>,
+        events_tx: Option<mpsc::Sender<AgentEvent>>,
+    ) -> Self {
+        Self {
+            cwd,
+            allowed_paths,
+            events_tx,
+            dry_run: false,
+            review: None,
+        }
+    }
+
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// In review mode every rewritten file is staged into the pending
+    /// changeset instead of hitting disk, until the user runs `/apply` or
+    /// `/discard`.
+    pub fn with_review(mut self, review: Option<super::review::ReviewChanges>) -> Self {
+        self.review = review;
+        self
+    }
+}
+
+impl ToolEmitter for ReplaceTool {
+    fn events_tx(&self) -> &Option<mpsc::Sender<AgentEvent>> {
+        &self.events_tx
+    }
+}
+
+#[async_trait]
+impl CallableFunction for ReplaceTool {
+    fn declaration(&self) -> FunctionDeclaration {
+        FunctionDeclaration::new(
+            "replace".to_string(),
+            "Regex search-and-replace across files - sed for the whole workspace. Replaces every match of 'pattern' in files matching 'glob' ($1, $2 reference capture groups). Set 'preview' to true to get the combined diff without writing. Prefer over many individual edit calls for mass renames. Returns: {files_changed, total_replacements, files: [{file, replacements}], diff?}".to_string(),
+            FunctionParameters::new(
+                "object".to_string(),
+                json!({
+                    "pattern": {
+                        "type": "string",
+                        "description": "Regex pattern to replace (e.g., 'old_name', 'fn (\\w+)_impl')"
+                    },
+                    "replacement": {
+                        "type": "string",
+                        "description": "Replacement text; $1, $2, ... reference capture groups"
+                    },
+                    "glob": {
+                        "type": "string",
+                        "description": "Glob pattern for files to touch (e.g., '**/*.rs', 'src/**'). (default: '**/*')"
+                    },
+                    "case_insensitive": {
+                        "type": "boolean",
+                        "description": "If true, perform case-insensitive matching (default: false)"
+                    },
+                    "preview": {
+                        "type": "boolean",
+                        "description": "If true, compute the changes and return them as a unified diff in the 'diff' field without writing any file. (default: false)"
+                    }
+                }),
+                vec!["pattern".to_string(), "replacement".to_string()],
+            ),
+        )
+    }
+
+    #[instrument(skip(self, args))]
+    async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        let pattern = args
+            .get("pattern")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing pattern".to_string()))?;
+        let replacement = args
+            .get("replacement")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing replacement".to_string()))?;
+        let glob_pattern = args.get("glob").and_then(|v| v.as_str()).unwrap_or("**/*");
+        let case_insensitive = args
+            .get("case_insensitive")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let preview = args
+            .get("preview")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let regex = regex::RegexBuilder::new(pattern)
+            .case_insensitive(case_insensitive)
+            .multi_line(true)
+            .build()
+            .map_err(|e| FunctionError::ExecutionError(format!("Invalid regex: {}", e).into()))?;
+
+        let mut glob_builder = GlobSetBuilder::new();
+        glob_builder.add(Glob::new(glob_pattern).map_err(|e| {
+            FunctionError::ExecutionError(format!("Invalid glob pattern: {}", e).into())
+        })?);
+        let glob_set = glob_builder.build().map_err(|e| {
+            FunctionError::ExecutionError(format!("Failed to build glob set: {}", e).into())
+        })?;
+
+        let mut override_builder = OverrideBuilder::new(&self.cwd);
+        for exclude in DEFAULT_EXCLUDES {
+            override_builder
+                .add(&format!("!{}", exclude))
+                .map_err(|e| {
+                    FunctionError::ExecutionError(format!("Invalid exclude: {}", e).into())
+                })?;
+        }
+        let overrides = override_builder.build().map_err(|e| {
+            FunctionError::ExecutionError(format!("Failed to build overrides: {}", e).into())
+        })?;
+
+        let write_changes = !self.dry_run && !preview;
+        let mut files: Vec<Value> = Vec::new();
+        let mut total_replacements: usize = 0;
+        let mut combined_diff = String::new();
+        let mut truncated = false;
+
+        let mut walker = WalkBuilder::new(&self.cwd);
+        walker.overrides(overrides);
+        for result in walker.build() {
+            let Ok(entry) = result else { continue };
+            if !entry.file_type().is_some_and(|ft| ft.is_file()) {
+                continue;
+            }
+            let path = entry.path();
+            // The canonical path is also the key staged review content is
+            // filed under, matching what the single-file edit tools resolve.
+            let Ok(canonical) = validate_path(path, &self.allowed_paths) else {
+                continue;
+            };
+            let relative = make_relative(path, &self.cwd);
+            if !glob_set.is_match(&relative) {
+                continue;
+            }
+            // Staged review content shadows the disk; skip binary/non-utf8
+            // files
+            let content = match super::review::staged_content(&self.review, &canonical) {
+                Some(staged) => staged,
+                None => {
+                    let Ok(content) = std::fs::read_to_string(path) else {
+                        continue;
+                    };
+                    content
+                }
+            };
+
+            let replacements = regex.find_iter(&content).count();
+            if replacements == 0 {
+                continue;
+            }
+            if files.len() >= MAX_FILES_CHANGED {
+                truncated = true;
+                break;
+            }
+
+            let new_content = regex.replace_all(&content, replacement).into_owned();
+            combined_diff.push_str(&crate::diff::unified_diff(
+                &content,
+                &new_content,
+                2,
+                Some(relative.as_str()),
+            ));
+
+            if write_changes {
+                if let Some(review) = &self.review {
+                    super::review::stage(review, &canonical, new_content.clone());
+                } else {
+                    // Snapshot the pre-replace state so /undo and revert_file
+                    // can restore it.
+                    super::checkpoint::snapshot(&self.cwd, path);
+                    if let Err(e) = std::fs::write(path, &new_content) {
+                        return Ok(error_response(
+                            &format!("Failed to write {}: {}", relative, e),
+                            error_codes::IO_ERROR,
+                            json!({"path": relative}),
+                        ));
+                    }
+                }
+            }
+
+            total_replacements += replacements;
+            files.push(json!({
+                "file": relative,
+                "replacements": replacements
+            }));
+        }
+
+        if files.is_empty() {
+            let error_msg = format!(
+                "No matches found for pattern '{}' in files matching '{}'",
+                pattern, glob_pattern
+            );
+            self.emit(&format!("  {}", "no matches".dimmed()));
+            return Ok(error_response(
+                &error_msg,
+                error_codes::NOT_FOUND,
+                json!({"pattern": pattern, "glob": glob_pattern}),
+            ));
+        }
+
+        let action = if write_changes {
+            "replacements"
+        } else {
+            "replacements (not written)"
+        };
+        self.emit(
+            &format!(
+                "  {} {} in {} files",
+                total_replacements,
+                action,
+                files.len()
+            )
+            .dimmed()
+            .to_string(),
+        );
+
+        let mut response = json!({
+            "files_changed": files.len(),
+            "total_replacements": total_replacements,
+            "files": files,
+            "success": true
+        });
+        if self.dry_run {
+            response["dry_run"] = json!(true);
+        }
+        if preview {
+            response["preview"] = json!(true);
+        }
+        if write_changes && self.review.is_some() {
+            response["staged"] = json!(true);
+        }
+        if !write_changes {
+            response["diff"] = json!(combined_diff);
+        }
+        if truncated {
+            response["truncated"] = json!(true);
+        }
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_replace_across_files() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("a.rs"), "old_name();\nold_name();\n").unwrap();
+        fs::write(cwd.join("b.rs"), "call(old_name)\n").unwrap();
+        fs::write(cwd.join("c.rs"), "unrelated\n").unwrap();
+
+        let tool = ReplaceTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let result = tool
+            .call(json!({"pattern": "old_name", "replacement": "new_name"}))
+            .await
+            .unwrap();
+
+        assert!(result["success"].as_bool().unwrap(), "got: {result}");
+        assert_eq!(result["files_changed"], 2);
+        assert_eq!(result["total_replacements"], 3);
+        assert_eq!(
+            fs::read_to_string(cwd.join("a.rs")).unwrap(),
+            "new_name();\nnew_name();\n"
+        );
+        assert_eq!(
+            fs::read_to_string(cwd.join("b.rs")).unwrap(),
+            "call(new_name)\n"
+        );
+        assert_eq!(fs::read_to_string(cwd.join("c.rs")).unwrap(), "unrelated\n");
+    }
+
+    #[tokio::test]
+    async fn test_replace_capture_groups() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("a.rs"), "fn foo_impl() {}\nfn bar_impl() {}\n").unwrap();
+
+        let tool = ReplaceTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let result = tool
+            .call(json!({"pattern": r"fn (\w+)_impl", "replacement": "fn $1"}))
+            .await
+            .unwrap();
+
+        assert_eq!(result["total_replacements"], 2);
+        assert_eq!(
+            fs::read_to_string(cwd.join("a.rs")).unwrap(),
+            "fn foo() {}\nfn bar() {}\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replace_respects_glob() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("a.rs"), "target\n").unwrap();
+        fs::write(cwd.join("a.md"), "target\n").unwrap();
+
+        let tool = ReplaceTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let result = tool
+            .call(json!({"pattern": "target", "replacement": "hit", "glob": "**/*.rs"}))
+            .await
+            .unwrap();
+
+        assert_eq!(result["files_changed"], 1);
+        assert_eq!(fs::read_to_string(cwd.join("a.rs")).unwrap(), "hit\n");
+        assert_eq!(fs::read_to_string(cwd.join("a.md")).unwrap(), "target\n");
+    }
+
+    #[tokio::test]
+    async fn test_replace_preview_returns_diff_without_writing() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("a.rs"), "old line\n").unwrap();
+        fs::write(cwd.join("b.rs"), "old stuff\n").unwrap();
+
+        let tool = ReplaceTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let result = tool
+            .call(json!({"pattern": "old", "replacement": "new", "preview": true}))
+            .await
+            .unwrap();
+
+        assert!(result["preview"].as_bool().unwrap());
+        assert_eq!(result["files_changed"], 2);
+
+        // Combined diff covers both files
+        let diff = result["diff"].as_str().unwrap();
+        assert!(diff.contains("a.rs"), "diff:\n{diff}");
+        assert!(diff.contains("b.rs"), "diff:\n{diff}");
+        assert!(diff.contains("-old line"));
+        assert!(diff.contains("+new line"));
+
+        assert_eq!(fs::read_to_string(cwd.join("a.rs")).unwrap(), "old line\n");
+        assert_eq!(fs::read_to_string(cwd.join("b.rs")).unwrap(), "old stuff\n");
+    }
+
+    #[tokio::test]
+    async fn test_replace_review_stages_instead_of_writing() {
+        use std::sync::{Arc, RwLock};
+
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("a.rs"), "old\n").unwrap();
+        fs::write(cwd.join("b.rs"), "old old\n").unwrap();
+
+        let review: super::super::review::ReviewChanges =
+            Arc::new(RwLock::new(super::super::review::PendingChanges::default()));
+        let tool = ReplaceTool::new(cwd.clone(), vec![cwd.clone()], None)
+            .with_review(Some(review.clone()));
+
+        let result = tool
+            .call(json!({"pattern": "old", "replacement": "new"}))
+            .await
+            .unwrap();
+        assert!(result["staged"].as_bool().unwrap(), "got: {result}");
+        assert_eq!(result["files_changed"], 2);
+
+        // Disk untouched; both files are staged under their canonical paths.
+        assert_eq!(fs::read_to_string(cwd.join("a.rs")).unwrap(), "old\n");
+        assert_eq!(fs::read_to_string(cwd.join("b.rs")).unwrap(), "old old\n");
+        let handle = Some(review);
+        let staged = super::super::review::staged_content(
+            &handle,
+            &cwd.join("a.rs").canonicalize().unwrap(),
+        );
+        assert_eq!(staged.as_deref(), Some("new\n"));
+        let staged = super::super::review::staged_content(
+            &handle,
+            &cwd.join("b.rs").canonicalize().unwrap(),
+        );
+        assert_eq!(staged.as_deref(), Some("new new\n"));
+    }
+
+    #[tokio::test]
+    async fn test_replace_dry_run_does_not_write() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("a.rs"), "old\n").unwrap();
+
+        let tool = ReplaceTool::new(cwd.clone(), vec![cwd.clone()], None).with_dry_run(true);
+        let result = tool
+            .call(json!({"pattern": "old", "replacement": "new"}))
+            .await
+            .unwrap();
+
+        assert!(result["dry_run"].as_bool().unwrap());
+        assert_eq!(fs::read_to_string(cwd.join("a.rs")).unwrap(), "old\n");
+    }
+
+    #[tokio::test]
+    async fn test_replace_no_matches() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("a.rs"), "content\n").unwrap();
+
+        let tool = ReplaceTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let result = tool
+            .call(json!({"pattern": "missing", "replacement": "x"}))
+            .await
+            .unwrap();
+
+        assert_eq!(result["error_code"], error_codes::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_replace_security_boundary() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let allowed_dir = cwd.join("allowed");
+        let restricted_dir = cwd.join("restricted");
+        fs::create_dir(&allowed_dir).unwrap();
+        fs::create_dir(&restricted_dir).unwrap();
+        fs::write(allowed_dir.join("a.rs"), "target\n").unwrap();
+        fs::write(restricted_dir.join("b.rs"), "target\n").unwrap();
+
+        // Tool walks from cwd, but only allowed_dir is in allowed_paths
+        let tool = ReplaceTool::new(cwd.clone(), vec![allowed_dir.clone()], None);
+        let result = tool
+            .call(json!({"pattern": "target", "replacement": "hit"}))
+            .await
+            .unwrap();
+
+        assert_eq!(result["files_changed"], 1);
+        assert_eq!(
+            fs::read_to_string(allowed_dir.join("a.rs")).unwrap(),
+            "hit\n"
+        );
+        assert_eq!(
+            fs::read_to_string(restricted_dir.join("b.rs")).unwrap(),
+            "target\n"
+        );
+    }
+}
@@ -0,0 +1,269 @@
+use async_trait::async_trait;
+use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
+use serde_json::{Value, json};
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use tokio::sync::mpsc;
+use tracing::instrument;
+
+use colored::Colorize;
+
+use super::{ToolEmitter, error_codes, error_response, validate_path};
+use crate::agent::AgentEvent;
+
+/// Ask the user to extend the sandbox to a path outside `allowed_paths`.
+///
+/// Grants are session-scoped: approved directories are recorded in
+/// `CleminiToolService` and honored by every sandboxed tool for the rest of
+/// the session, without restarting with `-C` or editing config. Approval is
+/// interactive (y/n on stdin) so the model cannot grant itself access.
+pub struct RequestPathAccessTool {
+    cwd: PathBuf,
+    allowed_paths: Vec<PathBuf>,
+    /// Session grants shared with `CleminiToolService`; approved directories
+    /// are pushed here and picked up on the next tool call.
+    grants: Arc<RwLock<Vec<PathBuf>>>,
+    is_mcp_mode: bool,
+    events_tx: Option<mpsc::Sender<AgentEvent>>,
+}
+
+impl ToolEmitter for RequestPathAccessTool {
+    fn events_tx(&self) -> &Option<mpsc::Sender<AgentEvent>> {
+        &self.events_tx
+    }
+}
+
+impl RequestPathAccessTool {
+    pub fn new(
+        cwd: PathBuf,
+        allowed_paths: Vec<PathBuf>,
+        grants: Arc<RwLock<Vec<PathBuf>>>,
+        is_mcp_mode: bool,
+        events_tx: Option<mpsc::Sender<AgentEvent>>,
+    ) -> Self {
+        Self {
+            cwd,
+            allowed_paths,
+            grants,
+            is_mcp_mode,
+            events_tx,
+        }
+    }
+
+    /// Resolve the requested path to the directory a grant would cover.
+    ///
+    /// The path must exist - grants on typos or not-yet-created paths would
+    /// silently open unintended directories. A file resolves to its parent
+    /// directory, since per-file grants are too fine-grained to be useful.
+    fn resolve_target(&self, raw: &str) -> Result<PathBuf, Value> {
+        let path = if std::path::Path::new(raw).is_absolute() {
+            PathBuf::from(raw)
+        } else {
+            self.cwd.join(raw)
+        };
+        let canonical = path.canonicalize().map_err(|e| {
+            error_response(
+                &format!("Path {} does not exist: {e}", path.display()),
+                error_codes::NOT_FOUND,
+                json!({"path": raw}),
+            )
+        })?;
+        if canonical.is_file() {
+            match canonical.parent() {
+                Some(parent) => Ok(parent.to_path_buf()),
+                None => Ok(canonical),
+            }
+        } else {
+            Ok(canonical)
+        }
+    }
+}
+
+#[async_trait]
+impl CallableFunction for RequestPathAccessTool {
+    fn declaration(&self) -> FunctionDeclaration {
+        FunctionDeclaration::new(
+            "request_path_access".to_string(),
+            "Ask the user to grant access to a path outside the sandbox for the rest of the session. Use after a tool returns ACCESS_DENIED for a path the user referred to. The user approves or declines interactively; a file path grants its parent directory. Returns: {granted, path}. If declined, do not retry without new direction from the user.".to_string(),
+            FunctionParameters::new(
+                "object".to_string(),
+                json!({
+                    "path": {
+                        "type": "string",
+                        "description": "The path that was denied (absolute, or relative to cwd). Must exist."
+                    },
+                    "reason": {
+                        "type": "string",
+                        "description": "One line shown to the user explaining why access is needed"
+                    }
+                }),
+                vec!["path".to_string()],
+            ),
+        )
+    }
+
+    #[instrument(skip(self, args))]
+    async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        let raw = args
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing path".to_string()))?;
+        let reason = args.get("reason").and_then(|v| v.as_str());
+
+        let target = match self.resolve_target(raw) {
+            Ok(dir) => dir,
+            Err(response) => return Ok(response),
+        };
+
+        if validate_path(&target, &self.allowed_paths).is_ok() {
+            return Ok(json!({
+                "granted": true,
+                "already_allowed": true,
+                "path": target.display().to_string()
+            }));
+        }
+
+        if self.is_mcp_mode {
+            return Ok(error_response(
+                "Cannot prompt for path approval in MCP mode. Add the path to allowed_paths in ~/.clemini/config.toml and restart.",
+                error_codes::BLOCKED,
+                json!({"path": target.display().to_string()}),
+            ));
+        }
+
+        self.emit(&format!(
+            "  Allow access to {} for this session?",
+            target.display()
+        ));
+        if let Some(reason) = reason {
+            self.emit(&format!("  Reason: {reason}"));
+        }
+        self.emit("  (y/n)");
+
+        let mut answer = String::new();
+        if let Err(e) = io::stdin().read_line(&mut answer) {
+            return Ok(error_response(
+                &format!("Failed to read from stdin: {e}"),
+                error_codes::IO_ERROR,
+                json!({"path": target.display().to_string()}),
+            ));
+        }
+
+        if !super::AskUserTool::parse_confirm(&answer) {
+            return Ok(json!({
+                "granted": false,
+                "path": target.display().to_string(),
+                "note": "User declined. Do not retry without new direction from the user."
+            }));
+        }
+
+        if let Ok(mut grants) = self.grants.write() {
+            grants.push(target.clone());
+        }
+        self.emit(
+            &format!("  access granted: {}", target.display())
+                .dimmed()
+                .to_string(),
+        );
+        Ok(json!({
+            "granted": true,
+            "path": target.display().to_string()
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn test_tool(cwd: PathBuf, allowed: Vec<PathBuf>) -> RequestPathAccessTool {
+        RequestPathAccessTool::new(cwd, allowed, Arc::new(RwLock::new(Vec::new())), false, None)
+    }
+
+    #[test]
+    fn test_declaration() {
+        let tool = test_tool(std::env::temp_dir(), vec![]);
+        let decl = tool.declaration();
+        assert_eq!(decl.name(), "request_path_access");
+        assert!(decl.description().contains("ACCESS_DENIED"));
+        assert_eq!(decl.parameters().required(), vec!["path".to_string()]);
+    }
+
+    #[test]
+    fn test_resolve_target_missing_path() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().canonicalize().unwrap();
+        let tool = test_tool(cwd.clone(), vec![cwd]);
+
+        let err = tool.resolve_target("no/such/dir").unwrap_err();
+        assert_eq!(err["error_code"], "NOT_FOUND");
+    }
+
+    #[test]
+    fn test_resolve_target_file_grants_parent() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().canonicalize().unwrap();
+        std::fs::write(cwd.join("notes.txt"), "hi").unwrap();
+        let tool = test_tool(cwd.clone(), vec![cwd.clone()]);
+
+        let target = tool
+            .resolve_target(cwd.join("notes.txt").to_str().unwrap())
+            .unwrap();
+        assert_eq!(target, cwd);
+    }
+
+    #[test]
+    fn test_resolve_target_relative_directory() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().canonicalize().unwrap();
+        std::fs::create_dir(cwd.join("sub")).unwrap();
+        let tool = test_tool(cwd.clone(), vec![cwd.clone()]);
+
+        let target = tool.resolve_target("sub").unwrap();
+        assert_eq!(target, cwd.join("sub"));
+    }
+
+    #[tokio::test]
+    async fn test_already_allowed_skips_prompt() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().canonicalize().unwrap();
+        let tool = test_tool(cwd.clone(), vec![cwd.clone()]);
+
+        // No stdin interaction needed: the path is already inside the sandbox.
+        let result = tool
+            .call(json!({"path": cwd.to_str().unwrap()}))
+            .await
+            .unwrap();
+        assert_eq!(result["granted"], true);
+        assert_eq!(result["already_allowed"], true);
+    }
+
+    #[tokio::test]
+    async fn test_mcp_mode_cannot_prompt() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().canonicalize().unwrap();
+        let outside = tempdir().unwrap();
+        let tool = RequestPathAccessTool::new(
+            cwd.clone(),
+            vec![cwd],
+            Arc::new(RwLock::new(Vec::new())),
+            true,
+            None,
+        );
+
+        let result = tool
+            .call(json!({"path": outside.path().to_str().unwrap()}))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], "BLOCKED");
+    }
+
+    #[tokio::test]
+    async fn test_missing_path_arg() {
+        let tool = test_tool(std::env::temp_dir(), vec![]);
+        let result = tool.call(json!({})).await;
+        assert!(matches!(result, Err(FunctionError::ArgumentMismatch(_))));
+    }
+}
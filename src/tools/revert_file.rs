@@ -0,0 +1,166 @@
+use async_trait::async_trait;
+use colored::Colorize;
+use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use tokio::sync::mpsc;
+use tracing::instrument;
+
+use super::{ToolEmitter, checkpoint, error_codes, error_response, resolve_and_validate_path};
+use crate::agent::AgentEvent;
+
+/// Restore a file from its most recent checkpoint.
+///
+/// Checkpoints are taken automatically before every `write_file`/`edit`
+/// (see `tools::checkpoint`), so a bad edit is one call away from recovery
+/// instead of a git reset.
+pub struct RevertFileTool {
+    cwd: PathBuf,
+    allowed_paths: Vec<PathBuf>,
+    events_tx: Option<mpsc::Sender<AgentEvent>>,
+}
+
+impl ToolEmitter for RevertFileTool {
+    fn events_tx(&self) -> &Option<mpsc::Sender<AgentEvent>> {
+        &self.events_tx
+    }
+}
+
+impl RevertFileTool {
+    pub fn new(
+        cwd: PathBuf,
+        allowed_paths: Vec<PathBuf>,
+        events_tx: Option<mpsc::Sender<AgentEvent>>,
+    ) -> Self {
+        Self {
+            cwd,
+            allowed_paths,
+            events_tx,
+        }
+    }
+}
+
+#[async_trait]
+impl CallableFunction for RevertFileTool {
+    fn declaration(&self) -> FunctionDeclaration {
+        FunctionDeclaration::new(
+            "revert_file".to_string(),
+            "Restore a file to its state before the most recent write_file/edit, from the automatic checkpoint taken before each mutation. Use when an edit turned out wrong. A file created since its checkpoint is deleted. Returns: {reverted, path} or a NOT_FOUND error when the file has no checkpoint.".to_string(),
+            FunctionParameters::new(
+                "object".to_string(),
+                json!({
+                    "path": {
+                        "type": "string",
+                        "description": "The file to revert (absolute or relative to cwd)"
+                    }
+                }),
+                vec!["path".to_string()],
+            ),
+        )
+    }
+
+    #[instrument(skip(self, args))]
+    async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        let file_path = args
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing path".to_string()))?;
+
+        let path = match resolve_and_validate_path(file_path, &self.cwd, &self.allowed_paths) {
+            Ok(p) => p,
+            Err(e) => {
+                return Ok(error_response(
+                    &format!("Access denied: {}. Path must be within allowed paths.", e),
+                    error_codes::ACCESS_DENIED,
+                    json!({"path": file_path}),
+                ));
+            }
+        };
+
+        match checkpoint::revert_file(&self.cwd, &path) {
+            Ok(Some(entry)) => {
+                let action = if entry.hash.is_some() {
+                    "reverted"
+                } else {
+                    "deleted (created after checkpoint)"
+                };
+                self.emit(&format!("  {} {}", action, file_path).dimmed().to_string());
+                Ok(json!({
+                    "reverted": true,
+                    "path": path.display().to_string(),
+                    "deleted": entry.hash.is_none(),
+                    "checkpoint_timestamp": entry.timestamp
+                }))
+            }
+            Ok(None) => Ok(error_response(
+                &format!(
+                    "No checkpoint recorded for {}. Only files touched by write_file/edit this or a previous session can be reverted.",
+                    file_path
+                ),
+                error_codes::NOT_FOUND,
+                json!({"path": file_path}),
+            )),
+            Err(e) => Ok(error_response(
+                &format!("Failed to revert {}: {}", file_path, e),
+                error_codes::IO_ERROR,
+                json!({"path": file_path}),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_declaration() {
+        let tool = RevertFileTool::new(std::env::temp_dir(), vec![], None);
+        let decl = tool.declaration();
+        assert_eq!(decl.name(), "revert_file");
+        assert!(decl.description().contains("checkpoint"));
+        assert_eq!(decl.parameters().required(), vec!["path".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_revert_restores_checkpointed_file() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().canonicalize().unwrap();
+        let file = cwd.join("a.txt");
+        fs::write(&file, "before").unwrap();
+        checkpoint::snapshot(&cwd, &file);
+        fs::write(&file, "after").unwrap();
+
+        let tool = RevertFileTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let result = tool.call(json!({"path": "a.txt"})).await.unwrap();
+        assert_eq!(result["reverted"], true);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "before");
+
+        let _ = fs::remove_file(checkpoint::journal_path(&cwd));
+    }
+
+    #[tokio::test]
+    async fn test_revert_without_checkpoint_is_not_found() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().canonicalize().unwrap();
+        fs::write(cwd.join("a.txt"), "content").unwrap();
+
+        let tool = RevertFileTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let result = tool.call(json!({"path": "a.txt"})).await.unwrap();
+        assert_eq!(result["error_code"], "NOT_FOUND");
+
+        let _ = fs::remove_file(checkpoint::journal_path(&cwd));
+    }
+
+    #[tokio::test]
+    async fn test_revert_outside_allowed_paths() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().canonicalize().unwrap();
+
+        let tool = RevertFileTool::new(cwd.clone(), vec![cwd], None);
+        let result = tool.call(json!({"path": "/etc/passwd"})).await.unwrap();
+        assert_eq!(result["error_code"], "ACCESS_DENIED");
+    }
+}
@@ -0,0 +1,224 @@
+//! In-memory changeset backing review mode.
+//!
+//! With review mode on, the content-editing tools (`write_file`, `edit`,
+//! `multi_edit`, `edit_lines`, `replace`, `notebook_edit`) stage their
+//! changes here instead of touching disk, and read through the changeset so
+//! the model sees its own pending work. Mutating tools that can't stage
+//! (`bash`, `delete_file`, `apply_patch`, ...) are blocked for the duration
+//! by `CleminiToolService::execute`. The user inspects the accumulated diff
+//! with `/review` and lands or drops the whole set with `/apply` /
+//! `/discard` - which is what makes bigger refactors safe to delegate:
+//! nothing reaches disk until the result has been looked at.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+/// Shared handle tools use to read and stage pending content. `None` in a
+/// tool means review mode is off and it should hit the disk directly.
+pub type ReviewChanges = Arc<RwLock<PendingChanges>>;
+
+/// The staged state of one file.
+#[derive(Debug, Clone)]
+struct PendingFile {
+    /// Disk contents when the file was first staged; `None` means it did not
+    /// exist, so the diff (and a discard) treat it as a new file.
+    original: Option<String>,
+    /// The pending contents, updated by every subsequent write/edit.
+    content: String,
+}
+
+/// All changes staged during the current review session, keyed by resolved
+/// path so repeated edits to one file collapse into a single pending entry.
+#[derive(Debug, Default)]
+pub struct PendingChanges {
+    files: BTreeMap<PathBuf, PendingFile>,
+}
+
+impl PendingChanges {
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Number of files with pending changes.
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// The pending contents of `path`, if it has been staged.
+    pub fn content_of(&self, path: &Path) -> Option<&str> {
+        self.files.get(path).map(|file| file.content.as_str())
+    }
+
+    /// Stage new contents for `path`. `original` is the disk state, recorded
+    /// only on the first stage so the rendered diff stays anchored to what
+    /// the user's tree actually looks like.
+    pub fn stage(&mut self, path: PathBuf, original: Option<String>, content: String) {
+        self.files
+            .entry(path)
+            .and_modify(|file| file.content = content.clone())
+            .or_insert(PendingFile { original, content });
+    }
+
+    /// Render every pending change as a unified diff, one file after
+    /// another, for `/review`.
+    pub fn render(&self) -> String {
+        self.files
+            .iter()
+            .map(|(path, file)| {
+                crate::diff::unified_diff(
+                    file.original.as_deref().unwrap_or(""),
+                    &file.content,
+                    3,
+                    Some(&path.display().to_string()),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Write every pending change to disk (checkpointing each file first so
+    /// `/undo` still works) and clear the set. Stops at the first failure,
+    /// leaving the unapplied remainder staged.
+    pub fn apply(&mut self, cwd: &Path) -> Result<Vec<PathBuf>, String> {
+        let mut applied = Vec::new();
+        while let Some((path, file)) = self.files.pop_first() {
+            super::checkpoint::snapshot(cwd, &path);
+            if let Some(parent) = path.parent()
+                && let Err(e) = std::fs::create_dir_all(parent)
+            {
+                self.files.insert(path.clone(), file);
+                return Err(format!("failed to create {}: {e}", parent.display()));
+            }
+            if let Err(e) = std::fs::write(&path, &file.content) {
+                let display = path.display().to_string();
+                self.files.insert(path, file);
+                return Err(format!("failed to write {display}: {e}"));
+            }
+            applied.push(path);
+        }
+        Ok(applied)
+    }
+
+    /// Drop every pending change without writing anything. Returns how many
+    /// files were discarded.
+    pub fn discard(&mut self) -> usize {
+        let count = self.files.len();
+        self.files.clear();
+        count
+    }
+}
+
+/// Staged content for `path`, if review mode is on and has a pending version
+/// of it. Tools call this before reading from disk.
+pub fn staged_content(review: &Option<ReviewChanges>, path: &Path) -> Option<String> {
+    let review = review.as_ref()?;
+    let guard = match review.read() {
+        Ok(guard) => guard,
+        Err(poisoned) => {
+            tracing::warn!("review lock was poisoned, recovering");
+            poisoned.into_inner()
+        }
+    };
+    guard.content_of(path).map(str::to_string)
+}
+
+/// Stage `content` as the pending version of `path`, reading the disk state
+/// for the diff anchor on first stage.
+pub fn stage(review: &ReviewChanges, path: &Path, content: String) {
+    let original = std::fs::read_to_string(path).ok();
+    match review.write() {
+        Ok(mut guard) => guard.stage(path.to_path_buf(), original, content),
+        Err(poisoned) => {
+            tracing::warn!("review lock was poisoned, recovering");
+            poisoned
+                .into_inner()
+                .stage(path.to_path_buf(), original, content)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_stage_and_content_of() {
+        let mut changes = PendingChanges::default();
+        let path = PathBuf::from("/tmp/a.txt");
+        assert!(changes.content_of(&path).is_none());
+
+        changes.stage(path.clone(), Some("old".to_string()), "new".to_string());
+        assert_eq!(changes.content_of(&path), Some("new"));
+        assert_eq!(changes.len(), 1);
+
+        // A second stage replaces the content but keeps the first original.
+        changes.stage(path.clone(), Some("new".to_string()), "newer".to_string());
+        assert_eq!(changes.content_of(&path), Some("newer"));
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes.files[&path].original.as_deref(), Some("old"));
+    }
+
+    #[test]
+    fn test_render_diffs_against_first_original() {
+        let mut changes = PendingChanges::default();
+        let path = PathBuf::from("/tmp/a.txt");
+        changes.stage(path.clone(), Some("one\n".to_string()), "two\n".to_string());
+        changes.stage(path, Some("two\n".to_string()), "three\n".to_string());
+
+        let rendered = changes.render();
+        assert!(rendered.contains("-one"));
+        assert!(rendered.contains("+three"));
+        // The intermediate state never shows up.
+        assert!(!rendered.contains("+two"));
+    }
+
+    #[test]
+    fn test_apply_writes_all_and_clears() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let a = cwd.join("a.txt");
+        let b = cwd.join("sub").join("b.txt");
+        fs::write(&a, "old").unwrap();
+
+        let mut changes = PendingChanges::default();
+        changes.stage(a.clone(), Some("old".to_string()), "new a".to_string());
+        changes.stage(b.clone(), None, "new b".to_string());
+
+        let applied = changes.apply(&cwd).unwrap();
+        assert_eq!(applied.len(), 2);
+        assert!(changes.is_empty());
+        assert_eq!(fs::read_to_string(&a).unwrap(), "new a");
+        // Parent directories are created on apply.
+        assert_eq!(fs::read_to_string(&b).unwrap(), "new b");
+
+        let _ = fs::remove_file(super::super::checkpoint::journal_path(&cwd));
+    }
+
+    #[test]
+    fn test_discard_drops_everything() {
+        let dir = tempdir().unwrap();
+        let a = dir.path().join("a.txt");
+
+        let mut changes = PendingChanges::default();
+        changes.stage(a.clone(), None, "pending".to_string());
+        assert_eq!(changes.discard(), 1);
+        assert!(changes.is_empty());
+        assert!(!a.exists());
+    }
+
+    #[test]
+    fn test_staged_content_helper() {
+        let path = PathBuf::from("/tmp/x.txt");
+        assert!(staged_content(&None, &path).is_none());
+
+        let review: ReviewChanges = Arc::new(RwLock::new(PendingChanges::default()));
+        let handle = Some(review.clone());
+        assert!(staged_content(&handle, &path).is_none());
+
+        stage(&review, &path, "pending".to_string());
+        assert_eq!(staged_content(&handle, &path).as_deref(), Some("pending"));
+    }
+}
@@ -0,0 +1,322 @@
+use async_trait::async_trait;
+use colored::Colorize;
+use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use std::process::Stdio;
+use tokio::process::Command;
+use tokio::sync::mpsc;
+use tracing::instrument;
+
+use super::{MAX_TOOL_OUTPUT_LEN, ToolEmitter, error_codes, error_response};
+use crate::agent::AgentEvent;
+use crate::tools::BashTool;
+
+/// Default wall-clock limit for a snippet, in seconds.
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// Harness that runs the snippet inside the subprocess. It applies
+/// best-effort resource limits (CPU seconds matching the wall-clock
+/// timeout, 512 MiB of address space), then executes the snippet with the
+/// last expression split off REPL-style: if the final statement is a bare
+/// expression, its value's repr is written to the result file so the tool
+/// can return it without the snippet needing an explicit print.
+///
+/// argv: `harness.py <snippet.py> <result file> <cpu seconds>`
+const HARNESS: &str = r#"
+import ast
+import sys
+
+try:
+    import resource
+    cpu = int(sys.argv[3])
+    resource.setrlimit(resource.RLIMIT_CPU, (cpu, cpu))
+    resource.setrlimit(resource.RLIMIT_AS, (512 * 1024 * 1024,) * 2)
+except Exception:
+    pass  # non-Unix or restricted environment: timeout still applies
+
+with open(sys.argv[1], encoding="utf-8") as f:
+    src = f.read()
+
+tree = ast.parse(src)
+env = {"__name__": "__main__"}
+if tree.body and isinstance(tree.body[-1], ast.Expr):
+    last = ast.Expression(tree.body[-1].value)
+    tree.body = tree.body[:-1]
+    exec(compile(tree, "<snippet>", "exec"), env)
+    value = eval(compile(last, "<snippet>", "eval"), env)
+    if value is not None:
+        with open(sys.argv[2], "w", encoding="utf-8") as f:
+            f.write(repr(value))
+else:
+    exec(compile(tree, "<snippet>", "exec"), env)
+"#;
+
+pub struct RunPythonTool {
+    cwd: PathBuf,
+    events_tx: Option<mpsc::Sender<AgentEvent>>,
+    dry_run: bool,
+}
+
+impl RunPythonTool {
+    pub fn new(cwd: PathBuf, events_tx: Option<mpsc::Sender<AgentEvent>>) -> Self {
+        Self {
+            cwd,
+            events_tx,
+            dry_run: false,
+        }
+    }
+
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+}
+
+impl ToolEmitter for RunPythonTool {
+    fn events_tx(&self) -> &Option<mpsc::Sender<AgentEvent>> {
+        &self.events_tx
+    }
+}
+
+#[async_trait]
+impl CallableFunction for RunPythonTool {
+    fn declaration(&self) -> FunctionDeclaration {
+        FunctionDeclaration::new(
+            "run_python".to_string(),
+            "Run a Python snippet in an isolated interpreter (python3 -I, resource-limited subprocess). \
+             Use for quick calculations and data munging instead of bash heredocs. \
+             If the last statement is an expression, its value is returned REPL-style. \
+             Returns: {stdout, stderr, exit_code, value?}"
+                .to_string(),
+            FunctionParameters::new(
+                "object".to_string(),
+                json!({
+                    "code": {
+                        "type": "string",
+                        "description": "The Python code to execute"
+                    },
+                    "timeout": {
+                        "type": "integer",
+                        "description": "Wall-clock limit in seconds. (default: 30)"
+                    }
+                }),
+                vec!["code".to_string()],
+            ),
+        )
+    }
+
+    #[instrument(skip(self, args))]
+    async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        let code = args
+            .get("code")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing code".to_string()))?;
+        let timeout_secs = args
+            .get("timeout")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_TIMEOUT_SECS);
+
+        if self.dry_run {
+            self.emit(&format!(
+                "  {} python snippet ({} bytes)",
+                "DRY RUN (not executed):".yellow(),
+                code.len()
+            ));
+            return Ok(json!({
+                "stdout": "",
+                "stderr": "",
+                "exit_code": 0,
+                "dry_run": true
+            }));
+        }
+
+        // Snippet, harness, and result value all live in a per-call temp dir;
+        // the snippet itself runs with cwd so it can read workspace files.
+        let scratch = match tempfile::tempdir() {
+            Ok(d) => d,
+            Err(e) => {
+                return Ok(error_response(
+                    &format!("Failed to create scratch directory: {}", e),
+                    error_codes::IO_ERROR,
+                    json!({}),
+                ));
+            }
+        };
+        let snippet_path = scratch.path().join("snippet.py");
+        let harness_path = scratch.path().join("harness.py");
+        let result_path = scratch.path().join("result.txt");
+        if let Err(e) = std::fs::write(&snippet_path, code)
+            .and_then(|()| std::fs::write(&harness_path, HARNESS))
+        {
+            return Ok(error_response(
+                &format!("Failed to write snippet: {}", e),
+                error_codes::IO_ERROR,
+                json!({}),
+            ));
+        }
+
+        self.emit("  running python snippet...");
+
+        // -I (isolated): no user site-packages, no PYTHON* env vars, so the
+        // snippet sees a predictable interpreter.
+        let child = Command::new("python3")
+            .arg("-I")
+            .arg(&harness_path)
+            .arg(&snippet_path)
+            .arg(&result_path)
+            .arg(timeout_secs.to_string())
+            .current_dir(&self.cwd)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn();
+
+        let child = match child {
+            Ok(c) => c,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(error_response(
+                    "python3 not found on PATH",
+                    error_codes::NOT_FOUND,
+                    json!({}),
+                ));
+            }
+            Err(e) => {
+                return Err(FunctionError::ExecutionError(
+                    format!("Failed to spawn python3: {}", e).into(),
+                ));
+            }
+        };
+
+        let output = match tokio::time::timeout(
+            std::time::Duration::from_secs(timeout_secs),
+            child.wait_with_output(),
+        )
+        .await
+        {
+            Ok(Ok(output)) => output,
+            Ok(Err(e)) => {
+                return Err(FunctionError::ExecutionError(
+                    format!("Failed to run python3: {}", e).into(),
+                ));
+            }
+            // kill_on_drop reaps the interpreter when the future is dropped
+            Err(_elapsed) => {
+                return Ok(error_response(
+                    &format!("Python snippet timed out after {}s", timeout_secs),
+                    error_codes::TIMEOUT,
+                    json!({"timeout": timeout_secs}),
+                ));
+            }
+        };
+
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        let exit_code = output.status.code().unwrap_or(-1);
+
+        let mut resp = json!({
+            "stdout": BashTool::truncate_output(stdout, MAX_TOOL_OUTPUT_LEN),
+            "stderr": BashTool::truncate_output(stderr, MAX_TOOL_OUTPUT_LEN),
+            "exit_code": exit_code,
+        });
+
+        // The harness only writes the result file for a non-None trailing
+        // expression value.
+        if let Ok(value) = std::fs::read_to_string(&result_path) {
+            resp["value"] = json!(BashTool::truncate_output(value, MAX_TOOL_OUTPUT_LEN));
+        }
+
+        Ok(resp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn tool() -> (tempfile::TempDir, RunPythonTool) {
+        let dir = tempdir().unwrap();
+        let tool = RunPythonTool::new(dir.path().to_path_buf(), None);
+        (dir, tool)
+    }
+
+    #[tokio::test]
+    async fn test_stdout_capture() {
+        let (_dir, tool) = tool();
+        let result = tool.call(json!({"code": "print('hello')"})).await.unwrap();
+        assert_eq!(result["exit_code"], 0);
+        assert_eq!(result["stdout"].as_str().unwrap().trim(), "hello");
+    }
+
+    #[tokio::test]
+    async fn test_last_expression_value() {
+        let (_dir, tool) = tool();
+        let result = tool
+            .call(json!({"code": "x = 6\nx * 7"}))
+            .await
+            .unwrap();
+        assert_eq!(result["exit_code"], 0);
+        assert_eq!(result["value"].as_str().unwrap(), "42");
+    }
+
+    #[tokio::test]
+    async fn test_no_value_for_statement() {
+        let (_dir, tool) = tool();
+        let result = tool.call(json!({"code": "x = 1"})).await.unwrap();
+        assert_eq!(result["exit_code"], 0);
+        assert!(result.get("value").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_exception_surfaces_in_stderr() {
+        let (_dir, tool) = tool();
+        let result = tool
+            .call(json!({"code": "raise ValueError('boom')"}))
+            .await
+            .unwrap();
+        assert_ne!(result["exit_code"], 0);
+        assert!(result["stderr"].as_str().unwrap().contains("boom"));
+    }
+
+    #[tokio::test]
+    async fn test_timeout_kills_snippet() {
+        let (_dir, tool) = tool();
+        let result = tool
+            .call(json!({"code": "import time\ntime.sleep(30)", "timeout": 1}))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::TIMEOUT);
+    }
+
+    #[tokio::test]
+    async fn test_snippet_runs_in_cwd() {
+        let (dir, tool) = tool();
+        std::fs::write(dir.path().join("data.txt"), "payload").unwrap();
+        let result = tool
+            .call(json!({"code": "print(open('data.txt').read())"}))
+            .await
+            .unwrap();
+        assert!(result["stdout"].as_str().unwrap().contains("payload"));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_skips_execution() {
+        let dir = tempdir().unwrap();
+        let tool = RunPythonTool::new(dir.path().to_path_buf(), None).with_dry_run(true);
+        let result = tool
+            .call(json!({"code": "open('side_effect', 'w').write('x')"}))
+            .await
+            .unwrap();
+        assert!(result["dry_run"].as_bool().unwrap());
+        assert!(!dir.path().join("side_effect").exists());
+    }
+
+    #[tokio::test]
+    async fn test_missing_code_is_an_error() {
+        let (_dir, tool) = tool();
+        let result = tool.call(json!({})).await;
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,304 @@
+use async_trait::async_trait;
+use colored::Colorize;
+use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+use tokio::sync::mpsc;
+use tracing::instrument;
+
+use super::{ToolEmitter, error_codes, error_response, resolve_and_validate_path};
+use crate::agent::AgentEvent;
+
+/// A screen region to capture, in pixels from the top-left corner.
+#[derive(Debug, Clone, Copy)]
+struct Region {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+/// Capture the screen (or a region of it) to a PNG in the workspace.
+///
+/// Uses whichever platform capture command is available: `screencapture`
+/// on macOS, `grim` (Wayland) or ImageMagick's `import` (X11) on Linux.
+/// Combined with `read_file`'s image support, this lets the agent see the
+/// rendered UI it just changed instead of iterating blind.
+pub struct ScreenshotTool {
+    cwd: PathBuf,
+    allowed_paths: Vec<PathBuf>,
+    events_tx: Option<mpsc::Sender<AgentEvent>>,
+}
+
+impl ScreenshotTool {
+    pub fn new(
+        cwd: PathBuf,
+        allowed_paths: Vec<PathBuf>,
+        events_tx: Option<mpsc::Sender<AgentEvent>>,
+    ) -> Self {
+        Self {
+            cwd,
+            allowed_paths,
+            events_tx,
+        }
+    }
+
+    /// Candidate capture commands for this platform, in preference order.
+    /// Each is `(program, args)` ready to run; the first whose binary exists
+    /// wins.
+    fn capture_commands(output: &Path, region: Option<Region>) -> Vec<(String, Vec<String>)> {
+        let out = output.to_string_lossy().to_string();
+        if cfg!(target_os = "macos") {
+            // -x: no shutter sound
+            let mut args = vec!["-x".to_string()];
+            if let Some(r) = region {
+                args.push("-R".to_string());
+                args.push(format!("{},{},{},{}", r.x, r.y, r.width, r.height));
+            }
+            args.push(out);
+            vec![("screencapture".to_string(), args)]
+        } else {
+            // grim for Wayland compositors, import (ImageMagick) for X11
+            let mut grim_args = Vec::new();
+            if let Some(r) = region {
+                grim_args.push("-g".to_string());
+                grim_args.push(format!("{},{} {}x{}", r.x, r.y, r.width, r.height));
+            }
+            grim_args.push(out.clone());
+
+            let mut import_args = vec!["-window".to_string(), "root".to_string()];
+            if let Some(r) = region {
+                import_args.push("-crop".to_string());
+                import_args.push(format!("{}x{}+{}+{}", r.width, r.height, r.x, r.y));
+            }
+            import_args.push(out);
+
+            vec![
+                ("grim".to_string(), grim_args),
+                ("import".to_string(), import_args),
+            ]
+        }
+    }
+
+    /// Parse the optional region argument.
+    fn parse_region(args: &Value) -> Result<Option<Region>, String> {
+        let Some(region) = args.get("region") else {
+            return Ok(None);
+        };
+        let field = |name: &str| -> Result<u32, String> {
+            region
+                .get(name)
+                .and_then(|v| v.as_u64())
+                .map(|n| n as u32)
+                .ok_or_else(|| format!("region.{} must be a non-negative integer", name))
+        };
+        Ok(Some(Region {
+            x: field("x")?,
+            y: field("y")?,
+            width: field("width")?,
+            height: field("height")?,
+        }))
+    }
+
+    /// Default output path: `screenshots/screenshot-<timestamp>.png` in cwd.
+    fn default_output_path() -> String {
+        let ts = chrono::Local::now().format("%Y%m%d-%H%M%S");
+        format!("screenshots/screenshot-{ts}.png")
+    }
+}
+
+impl ToolEmitter for ScreenshotTool {
+    fn events_tx(&self) -> &Option<mpsc::Sender<AgentEvent>> {
+        &self.events_tx
+    }
+}
+
+#[async_trait]
+impl CallableFunction for ScreenshotTool {
+    fn declaration(&self) -> FunctionDeclaration {
+        FunctionDeclaration::new(
+            "screenshot".to_string(),
+            "Capture the screen (or a region) to a PNG in the workspace, then view it with \
+             read_file to see the rendered UI. Uses screencapture (macOS) or grim/import (Linux). \
+             Returns: {path, success}"
+                .to_string(),
+            FunctionParameters::new(
+                "object".to_string(),
+                json!({
+                    "region": {
+                        "type": "object",
+                        "description": "Capture only this region: {x, y, width, height} in pixels from the top-left. Omit for the full screen."
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "Output PNG path (absolute or relative to cwd). (default: screenshots/screenshot-<timestamp>.png)"
+                    }
+                }),
+                vec![],
+            ),
+        )
+    }
+
+    #[instrument(skip(self, args))]
+    async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        let region = match Self::parse_region(&args) {
+            Ok(r) => r,
+            Err(e) => {
+                return Ok(error_response(
+                    &e,
+                    error_codes::INVALID_ARGUMENT,
+                    json!({"region": args.get("region")}),
+                ));
+            }
+        };
+
+        let out_arg = args
+            .get("path")
+            .and_then(|v| v.as_str())
+            .map(String::from)
+            .unwrap_or_else(Self::default_output_path);
+
+        let output = match resolve_and_validate_path(&out_arg, &self.cwd, &self.allowed_paths) {
+            Ok(p) => p,
+            Err(e) => {
+                return Ok(error_response(
+                    &format!("Access denied: {}. Path must be within allowed paths.", e),
+                    error_codes::ACCESS_DENIED,
+                    json!({"path": out_arg}),
+                ));
+            }
+        };
+        if let Some(parent) = output.parent()
+            && let Err(e) = std::fs::create_dir_all(parent)
+        {
+            return Ok(error_response(
+                &format!("Failed to create output directory: {}", e),
+                error_codes::IO_ERROR,
+                json!({"path": out_arg}),
+            ));
+        }
+
+        let mut last_missing = Vec::new();
+        for (program, cmd_args) in Self::capture_commands(&output, region) {
+            let result = Command::new(&program).args(&cmd_args).output().await;
+            match result {
+                Ok(out) if out.status.success() && output.exists() => {
+                    self.emit(&format!("  captured {}", out_arg).dimmed().to_string());
+                    // Inline preview for kitty-protocol terminals; others
+                    // just get the path line above.
+                    if let Ok(bytes) = tokio::fs::read(&output).await
+                        && let Some(preview) = crate::term_image::kitty_inline_png(&bytes)
+                    {
+                        self.emit(&preview);
+                    }
+                    return Ok(json!({
+                        "path": out_arg,
+                        "success": true,
+                        "hint": "Use read_file to view the image"
+                    }));
+                }
+                Ok(out) => {
+                    // Capture command exists but failed (no display, denied
+                    // permission) - report its stderr rather than trying a
+                    // tool for a different display server.
+                    let stderr = String::from_utf8_lossy(&out.stderr).trim().to_string();
+                    return Ok(error_response(
+                        &format!("{} failed: {}", program, stderr),
+                        error_codes::IO_ERROR,
+                        json!({"program": program}),
+                    ));
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    last_missing.push(program);
+                }
+                Err(e) => {
+                    return Err(FunctionError::ExecutionError(
+                        format!("Failed to run {}: {}", program, e).into(),
+                    ));
+                }
+            }
+        }
+
+        Ok(error_response(
+            &format!(
+                "No screen capture command found (tried: {}). Install one to enable screenshots.",
+                last_missing.join(", ")
+            ),
+            error_codes::NOT_FOUND,
+            json!({"tried": last_missing}),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_capture_commands_include_region() {
+        let region = Region {
+            x: 10,
+            y: 20,
+            width: 640,
+            height: 480,
+        };
+        let commands = ScreenshotTool::capture_commands(Path::new("/tmp/shot.png"), Some(region));
+        assert!(!commands.is_empty());
+        // Every candidate ends with the output path and mentions the region
+        // in some flag form.
+        for (_, args) in &commands {
+            assert_eq!(args.last().unwrap(), "/tmp/shot.png");
+            assert!(args.iter().any(|a| a.contains("640")));
+        }
+    }
+
+    #[test]
+    fn test_capture_commands_full_screen() {
+        let commands = ScreenshotTool::capture_commands(Path::new("/tmp/shot.png"), None);
+        for (_, args) in &commands {
+            assert_eq!(args.last().unwrap(), "/tmp/shot.png");
+        }
+    }
+
+    #[test]
+    fn test_parse_region_requires_all_fields() {
+        let err = ScreenshotTool::parse_region(&json!({"region": {"x": 0, "y": 0, "width": 100}}));
+        assert!(err.is_err());
+        assert!(err.unwrap_err().contains("height"));
+    }
+
+    #[test]
+    fn test_parse_region_absent_is_none() {
+        let region = ScreenshotTool::parse_region(&json!({})).unwrap();
+        assert!(region.is_none());
+    }
+
+    #[test]
+    fn test_default_output_path_is_png() {
+        let path = ScreenshotTool::default_output_path();
+        assert!(path.starts_with("screenshots/"));
+        assert!(path.ends_with(".png"));
+    }
+
+    #[tokio::test]
+    async fn test_output_path_outside_sandbox_is_denied() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let tool = ScreenshotTool::new(cwd.clone(), vec![cwd], None);
+
+        let result = tool.call(json!({"path": "../escaped.png"})).await.unwrap();
+        assert_eq!(result["error_code"], error_codes::ACCESS_DENIED);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_region_is_an_error() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let tool = ScreenshotTool::new(cwd.clone(), vec![cwd], None);
+
+        let result = tool.call(json!({"region": {"x": "left"}})).await.unwrap();
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+    }
+}
@@ -0,0 +1,210 @@
+use crate::agent::AgentEvent;
+use crate::tools::tasks::TASKS;
+use crate::tools::{ToolEmitter, error_codes, error_response};
+use async_trait::async_trait;
+use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
+use serde_json::{Value, json};
+use tokio::sync::mpsc;
+use tracing::instrument;
+
+pub struct SendInputTool {
+    events_tx: Option<mpsc::Sender<AgentEvent>>,
+}
+
+impl SendInputTool {
+    pub fn new(events_tx: Option<mpsc::Sender<AgentEvent>>) -> Self {
+        Self { events_tx }
+    }
+}
+
+impl ToolEmitter for SendInputTool {
+    fn events_tx(&self) -> &Option<mpsc::Sender<AgentEvent>> {
+        &self.events_tx
+    }
+}
+
+#[async_trait]
+impl CallableFunction for SendInputTool {
+    fn declaration(&self) -> FunctionDeclaration {
+        FunctionDeclaration::new(
+            "send_input".to_string(),
+            "Inject keystrokes into an interactive PTY task started with bash interactive=true. Input is sent verbatim - include a trailing newline (\\n) to submit a line. Use ask_user first when the input should come from the user. Returns: {task_id, bytes_sent, success}".to_string(),
+            FunctionParameters::new(
+                "object".to_string(),
+                json!({
+                    "task_id": {
+                        "type": "string",
+                        "description": "The PTY task ID to send input to (e.g., 'pty-1')"
+                    },
+                    "input": {
+                        "type": "string",
+                        "description": "The keystrokes to send, verbatim. End with \\n to submit a line; control characters like \\u0003 (ctrl-c) are passed through."
+                    }
+                }),
+                vec!["task_id".to_string(), "input".to_string()],
+            ),
+        )
+    }
+
+    #[instrument(skip(self, args))]
+    async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        let task_id = args
+            .get("task_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing task_id".to_string()))?;
+
+        let input = args
+            .get("input")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing input".to_string()))?;
+
+        let mut tasks = TASKS.lock().unwrap();
+        let Some(task) = tasks.get_mut(task_id) else {
+            return Ok(error_response(
+                &format!("Task {} not found", task_id),
+                error_codes::NOT_FOUND,
+                json!({ "task_id": task_id }),
+            ));
+        };
+
+        let task_type = task.task_type();
+        let Some(pty) = task.as_pty_mut() else {
+            return Ok(error_response(
+                &format!(
+                    "Task {} is a {} task, not a PTY task. Only tasks started with bash interactive=true accept input.",
+                    task_id, task_type
+                ),
+                error_codes::INVALID_ARGUMENT,
+                json!({ "task_id": task_id, "task_type": task_type }),
+            ));
+        };
+
+        pty.update_status();
+        if pty.is_completed() {
+            return Ok(error_response(
+                &format!("Task {} has already exited.", task_id),
+                error_codes::NOT_FOUND,
+                json!({ "task_id": task_id }),
+            ));
+        }
+
+        match pty.send_input(input) {
+            Ok(()) => Ok(json!({
+                "task_id": task_id,
+                "bytes_sent": input.len(),
+                "success": true
+            })),
+            Err(e) => Ok(error_response(
+                &format!("Failed to send input to task {}: {}", task_id, e),
+                error_codes::IO_ERROR,
+                json!({ "task_id": task_id }),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::bash::BashTool;
+    use crate::tools::tasks::Task;
+    use tempfile::tempdir;
+    use tokio::time::{Duration, sleep};
+
+    #[tokio::test]
+    async fn test_send_input_to_interactive_task() {
+        let dir = tempdir().unwrap();
+        let bash = BashTool::new_without_confirmation_tracking(
+            dir.path().to_path_buf(),
+            vec![dir.path().to_path_buf()],
+            5,
+            false,
+            None,
+        );
+
+        let bash_result = bash
+            .call(json!({
+                "command": "read -r answer; echo \"answer: $answer\"",
+                "interactive": true
+            }))
+            .await
+            .unwrap();
+
+        let task_id = bash_result["task_id"].as_str().unwrap().to_string();
+        assert!(task_id.starts_with("pty-"));
+        assert_eq!(bash_result["status"], "running");
+
+        let tool = SendInputTool::new(None);
+        let result = tool
+            .call(json!({ "task_id": task_id, "input": "forty-two\n" }))
+            .await
+            .unwrap();
+        assert!(result["success"].as_bool().unwrap(), "got: {result}");
+
+        sleep(Duration::from_millis(400)).await;
+
+        let output = {
+            let mut tasks = TASKS.lock().unwrap();
+            let task = tasks.get_mut(&task_id).unwrap();
+            task.update_status();
+            task.output()
+        };
+        assert!(output.contains("answer: forty-two"), "output: {output}");
+
+        // Cleanup
+        TASKS.lock().unwrap().remove(&task_id);
+    }
+
+    #[tokio::test]
+    async fn test_send_input_rejects_non_pty_task() {
+        let dir = tempdir().unwrap();
+        let bash = BashTool::new_without_confirmation_tracking(
+            dir.path().to_path_buf(),
+            vec![dir.path().to_path_buf()],
+            5,
+            false,
+            None,
+        );
+
+        let bash_result = bash
+            .call(json!({
+                "command": "sleep 5",
+                "run_in_background": true
+            }))
+            .await
+            .unwrap();
+        let task_id = bash_result["task_id"].as_str().unwrap().to_string();
+
+        let tool = SendInputTool::new(None);
+        let result = tool
+            .call(json!({ "task_id": task_id, "input": "hello\n" }))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+
+        // Cleanup
+        let child = {
+            let mut tasks = TASKS.lock().unwrap();
+            tasks.remove(&task_id).and_then(|mut task| {
+                if let Task::Background(ref mut bg) = task {
+                    bg.take_child()
+                } else {
+                    None
+                }
+            })
+        };
+        if let Some(mut child) = child {
+            let _ = child.kill().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_input_task_not_found() {
+        let tool = SendInputTool::new(None);
+        let result = tool
+            .call(json!({ "task_id": "pty-99999", "input": "x" }))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::NOT_FOUND);
+    }
+}
@@ -0,0 +1,185 @@
+//! Per-tool usage statistics.
+//!
+//! Every call through `CleminiToolService::execute` is recorded: invocation
+//! count, wall-clock duration, whether the result was an error, and a
+//! heuristic token estimate of args + result. The session's stats are
+//! persisted to `~/.clemini/sessions/<project-hash>-stats.json` so
+//! pathological tool behavior (retry storms, token-hungry tools) can be
+//! spotted after the fact. Surfaced live via the `/stats` REPL command and
+//! `InteractionResult::tool_stats`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Cumulative statistics for one tool.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolStats {
+    /// Number of invocations.
+    pub calls: u64,
+    /// Invocations whose result was an error (JSON `error` field or a
+    /// propagated execution failure).
+    pub errors: u64,
+    /// Total wall-clock time spent in this tool.
+    pub total_duration_ms: u64,
+    /// Heuristic token estimate of args + results across all calls - the
+    /// context cost this tool has incurred.
+    pub total_tokens: u64,
+}
+
+impl ToolStats {
+    /// Fold one invocation into the stats.
+    pub fn record(&mut self, duration: Duration, tokens: u32, is_error: bool) {
+        self.calls += 1;
+        if is_error {
+            self.errors += 1;
+        }
+        self.total_duration_ms += u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+        self.total_tokens += u64::from(tokens);
+    }
+
+    /// Fraction of calls that errored, 0.0 when never called.
+    pub fn failure_rate(&self) -> f64 {
+        if self.calls == 0 {
+            0.0
+        } else {
+            self.errors as f64 / self.calls as f64
+        }
+    }
+
+    /// Mean duration per call in milliseconds, 0 when never called.
+    pub fn avg_duration_ms(&self) -> u64 {
+        if self.calls == 0 {
+            0
+        } else {
+            self.total_duration_ms / self.calls
+        }
+    }
+}
+
+/// Stats keyed by tool name. BTreeMap so display order is stable.
+pub type ToolStatsMap = BTreeMap<String, ToolStats>;
+
+/// Per-interaction delta: `after` minus `before`, dropping tools that
+/// weren't called in between. Used to populate
+/// `InteractionResult::tool_stats` from two snapshots of the session map.
+pub fn diff(before: &ToolStatsMap, after: &ToolStatsMap) -> ToolStatsMap {
+    after
+        .iter()
+        .filter_map(|(name, stats)| {
+            let prior = before.get(name).cloned().unwrap_or_default();
+            let delta = ToolStats {
+                calls: stats.calls.saturating_sub(prior.calls),
+                errors: stats.errors.saturating_sub(prior.errors),
+                total_duration_ms: stats.total_duration_ms.saturating_sub(prior.total_duration_ms),
+                total_tokens: stats.total_tokens.saturating_sub(prior.total_tokens),
+            };
+            (delta.calls > 0).then(|| (name.clone(), delta))
+        })
+        .collect()
+}
+
+/// Where the session's stats are persisted. Keyed by workspace, like the
+/// todo and memory files.
+pub fn stats_file_path(cwd: &Path) -> PathBuf {
+    let canonical = cwd.canonicalize().unwrap_or_else(|_| cwd.to_path_buf());
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".clemini")
+        .join("sessions")
+        .join(format!(
+            "{}-stats.json",
+            crate::tools::memory::project_hash(&canonical)
+        ))
+}
+
+/// Persist the session's stats (best-effort; overwrites the previous
+/// session's file for this workspace).
+pub fn save_stats(cwd: &Path, stats: &ToolStatsMap) -> std::io::Result<()> {
+    let path = stats_file_path(cwd);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(stats).map_err(std::io::Error::other)?;
+    std::fs::write(path, json)
+}
+
+/// Load the last persisted stats for this workspace, if any.
+pub fn load_stats(cwd: &Path) -> Option<ToolStatsMap> {
+    let raw = std::fs::read_to_string(stats_file_path(cwd)).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_accumulates() {
+        let mut stats = ToolStats::default();
+        stats.record(Duration::from_millis(100), 50, false);
+        stats.record(Duration::from_millis(300), 150, true);
+
+        assert_eq!(stats.calls, 2);
+        assert_eq!(stats.errors, 1);
+        assert_eq!(stats.total_duration_ms, 400);
+        assert_eq!(stats.total_tokens, 200);
+        assert_eq!(stats.avg_duration_ms(), 200);
+        assert!((stats.failure_rate() - 0.5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_empty_stats_rates_are_zero() {
+        let stats = ToolStats::default();
+        assert_eq!(stats.failure_rate(), 0.0);
+        assert_eq!(stats.avg_duration_ms(), 0);
+    }
+
+    #[test]
+    fn test_diff_drops_uncalled_tools() {
+        let mut before = ToolStatsMap::new();
+        before.insert(
+            "bash".to_string(),
+            ToolStats {
+                calls: 3,
+                errors: 1,
+                total_duration_ms: 900,
+                total_tokens: 300,
+            },
+        );
+        before.insert("grep".to_string(), ToolStats::default());
+
+        let mut after = before.clone();
+        after.get_mut("bash").unwrap().record(Duration::from_millis(100), 40, false);
+        after
+            .entry("read_file".to_string())
+            .or_default()
+            .record(Duration::from_millis(5), 10, false);
+
+        let delta = diff(&before, &after);
+        assert_eq!(delta.len(), 2);
+        assert_eq!(delta["bash"].calls, 1);
+        assert_eq!(delta["bash"].errors, 0);
+        assert_eq!(delta["bash"].total_tokens, 40);
+        assert_eq!(delta["read_file"].calls, 1);
+        assert!(!delta.contains_key("grep"));
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut stats = ToolStatsMap::new();
+        stats
+            .entry("bash".to_string())
+            .or_default()
+            .record(Duration::from_millis(250), 80, true);
+
+        save_stats(dir.path(), &stats).unwrap();
+        let loaded = load_stats(dir.path()).unwrap();
+        assert_eq!(loaded, stats);
+
+        // Clean up the shared sessions directory entry.
+        let _ = std::fs::remove_file(stats_file_path(dir.path()));
+    }
+}
@@ -1,20 +1,150 @@
 use async_trait::async_trait;
 use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
 use serde_json::{Value, json};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::process::Stdio;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{LazyLock, Mutex};
 use tokio::process::Command;
 use tokio::sync::mpsc;
 use tracing::instrument;
 
-use super::ToolEmitter;
+use super::{ToolEmitter, error_codes, error_response};
 use crate::agent::AgentEvent;
-use crate::tools::background::BackgroundTask;
-use crate::tools::tasks::register_background_task;
+use crate::tools::tasks::{TASKS, register_streaming_background_task};
+
+/// Environment variable carrying the delegation depth. A top-level clemini
+/// runs at depth 0; each subagent it spawns runs at depth + 1, so a
+/// recursive delegation loop bottoms out at `TaskLimits::max_depth` instead
+/// of forking until the API key runs dry.
+pub const TASK_DEPTH_ENV: &str = "CLEMINI_TASK_DEPTH";
+
+/// Guardrails for subagent delegation (`[task]` config section):
+///
+/// ```toml
+/// [task]
+/// max_depth = 2
+/// max_concurrent = 2
+/// token_budget = 100000
+/// ```
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct TaskLimits {
+    /// How deep delegation may nest (a subagent spawning subagents).
+    /// Depth 0 is the top-level clemini; at `max_depth` the task tool
+    /// refuses to spawn. 0 disables delegation entirely. Default 3.
+    #[serde(default = "default_max_depth")]
+    pub max_depth: usize,
+    /// How many subagents (foreground + background) may run at once across
+    /// the process. 0 means unlimited. Default 4.
+    #[serde(default = "default_max_concurrent")]
+    pub max_concurrent: usize,
+    /// Cumulative token budget per subagent interaction, passed down as
+    /// `--max-tokens`. `None` leaves subagents unbudgeted.
+    #[serde(default)]
+    pub token_budget: Option<u32>,
+}
+
+fn default_max_depth() -> usize {
+    3
+}
+
+fn default_max_concurrent() -> usize {
+    4
+}
+
+impl Default for TaskLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: default_max_depth(),
+            max_concurrent: default_max_concurrent(),
+            token_budget: None,
+        }
+    }
+}
+
+/// Subagents currently running in the foreground, held by `ForegroundSlot`
+/// guards so a panicking spawn can't leak a slot.
+static FOREGROUND_SUBAGENTS: AtomicUsize = AtomicUsize::new(0);
+
+/// Task IDs of background subagents, checked against the task registry so
+/// finished ones stop counting toward the concurrency cap.
+static BACKGROUND_SUBAGENTS: LazyLock<Mutex<Vec<String>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// RAII claim on a foreground subagent slot.
+struct ForegroundSlot;
+
+impl ForegroundSlot {
+    fn claim() -> Self {
+        FOREGROUND_SUBAGENTS.fetch_add(1, Ordering::SeqCst);
+        Self
+    }
+}
+
+impl Drop for ForegroundSlot {
+    fn drop(&mut self) {
+        FOREGROUND_SUBAGENTS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// The delegation depth this process runs at (0 when not a subagent).
+fn current_task_depth() -> usize {
+    std::env::var(TASK_DEPTH_ENV)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Count subagents still running: foreground slots plus background tasks
+/// that the registry hasn't seen exit yet. Prunes finished background IDs
+/// as a side effect.
+fn running_subagents() -> usize {
+    let mut ids = BACKGROUND_SUBAGENTS.lock().unwrap();
+    let mut tasks = TASKS.lock().unwrap();
+    ids.retain(|id| match tasks.get_mut(id) {
+        Some(task) => {
+            task.update_status();
+            !task.is_completed()
+        }
+        None => false,
+    });
+    FOREGROUND_SUBAGENTS.load(Ordering::SeqCst) + ids.len()
+}
+
+/// A named subagent role from the `[agents]` config section, e.g.:
+///
+/// ```toml
+/// [agents.explorer]
+/// system_prompt = "You are a read-only explorer. Summarize, never modify."
+/// model = "gemini-flash-lite-latest"
+/// allowed_tools = ["read_file", "grep", "glob", "outline"]
+/// max_turns = 15
+/// ```
+///
+/// Every field is optional; unset fields fall back to the subagent's normal
+/// defaults.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct AgentProfile {
+    /// Role instructions appended to the subagent's system prompt.
+    pub system_prompt: Option<String>,
+    /// Model override; wins over `[models].task` routing.
+    pub model: Option<String>,
+    /// Tools the subagent may use (passed as `--allowed-tools`).
+    pub allowed_tools: Option<Vec<String>>,
+    /// Turn budget for the subagent (passed as `--max-turns`).
+    pub max_turns: Option<usize>,
+}
 
 pub struct TaskTool {
     cwd: PathBuf,
     events_tx: Option<mpsc::Sender<AgentEvent>>,
+    model: Option<String>,
+    /// Per-tool timeout in seconds from the `[timeouts]` config section.
+    timeout: Option<u64>,
+    /// Named agent profiles from the `[agents]` config section.
+    profiles: HashMap<String, AgentProfile>,
+    /// Delegation guardrails from the `[task]` config section.
+    limits: TaskLimits,
 }
 
 impl ToolEmitter for TaskTool {
@@ -25,13 +155,95 @@ impl ToolEmitter for TaskTool {
 
 impl TaskTool {
     pub fn new(cwd: PathBuf, events_tx: Option<mpsc::Sender<AgentEvent>>) -> Self {
-        Self { cwd, events_tx }
+        Self {
+            cwd,
+            events_tx,
+            model: None,
+            timeout: None,
+            profiles: HashMap::new(),
+            limits: TaskLimits::default(),
+        }
+    }
+
+    /// Override the model subagents run with (passed via `--model`).
+    /// `None` lets the subagent pick its own default.
+    pub fn with_model(mut self, model: Option<String>) -> Self {
+        self.model = model;
+        self
+    }
+
+    /// Limit foreground subagent runs to the given number of seconds
+    /// (`[timeouts]` config section). `None` means no limit.
+    pub fn with_timeout(mut self, secs: Option<u64>) -> Self {
+        self.timeout = secs;
+        self
+    }
+
+    /// Set the named agent profiles from the `[agents]` config section.
+    pub fn with_profiles(mut self, profiles: HashMap<String, AgentProfile>) -> Self {
+        self.profiles = profiles;
+        self
+    }
+
+    /// Set the delegation guardrails from the `[task]` config section.
+    pub fn with_limits(mut self, limits: TaskLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Sorted profile names, for the declaration and error messages.
+    fn profile_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.profiles.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Arguments appended to the base clemini command for a subagent run.
+    fn subagent_args(&self, prompt: &str, profile: Option<&AgentProfile>) -> Vec<String> {
+        let mut args = vec!["-p".to_string(), prompt.to_string()];
+        // Note: subagent gets its own sandbox based on cwd. It does not inherit the parent's
+        // allowed_paths - this is intentional as the subagent operates as an independent instance.
+        args.extend(["--cwd".to_string(), self.cwd.to_string_lossy().to_string()]);
+        // Profile model wins over [models].task routing.
+        let model = profile
+            .and_then(|p| p.model.as_ref())
+            .or(self.model.as_ref());
+        if let Some(model) = model {
+            args.extend(["--model".to_string(), model.clone()]);
+        }
+        if let Some(profile) = profile {
+            if let Some(system_prompt) = &profile.system_prompt {
+                args.extend([
+                    "--append-system-prompt".to_string(),
+                    system_prompt.clone(),
+                ]);
+            }
+            if let Some(allowed) = &profile.allowed_tools {
+                args.extend(["--allowed-tools".to_string(), allowed.join(",")]);
+            }
+            if let Some(max_turns) = profile.max_turns {
+                args.extend(["--max-turns".to_string(), max_turns.to_string()]);
+            }
+        }
+        if let Some(budget) = self.limits.token_budget {
+            args.extend(["--max-tokens".to_string(), budget.to_string()]);
+        }
+        args
     }
 }
 
 #[async_trait]
 impl CallableFunction for TaskTool {
     fn declaration(&self) -> FunctionDeclaration {
+        let agent_description = if self.profiles.is_empty() {
+            "Named agent profile from the [agents] config section (none configured)".to_string()
+        } else {
+            format!(
+                "Named agent profile applying a preset role (system prompt, model, tool \
+                 allowlist, turn budget). Available: {}",
+                self.profile_names().join(", ")
+            )
+        };
         FunctionDeclaration::new(
             "task".to_string(),
             "Spawn a clemini subagent to handle a delegated task. Use for parallel work, \
@@ -46,6 +258,10 @@ impl CallableFunction for TaskTool {
                         "type": "string",
                         "description": "The task/prompt to give to the subagent"
                     },
+                    "agent": {
+                        "type": "string",
+                        "description": agent_description
+                    },
                     "background": {
                         "type": "boolean",
                         "description": "Run in background (default: false). If true, returns immediately with task_id. Use kill_shell to terminate."
@@ -58,6 +274,13 @@ impl CallableFunction for TaskTool {
 
     #[instrument(skip(self, args))]
     async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        super::run_with_timeout("task", self.timeout, self.run(args)).await
+    }
+}
+
+impl TaskTool {
+    /// The actual subagent spawn, wrapped in the per-tool timeout by `call`.
+    async fn run(&self, args: Value) -> Result<Value, FunctionError> {
         let prompt = args
             .get("prompt")
             .and_then(|v| v.as_str())
@@ -67,17 +290,65 @@ impl CallableFunction for TaskTool {
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
 
+        let profile = match args.get("agent").and_then(|v| v.as_str()) {
+            Some(name) => match self.profiles.get(name) {
+                Some(profile) => Some(profile),
+                None => {
+                    let available = self.profile_names();
+                    let hint = if available.is_empty() {
+                        "no profiles are configured in the [agents] config section".to_string()
+                    } else {
+                        format!("available: {}", available.join(", "))
+                    };
+                    return Ok(error_response(
+                        &format!("Unknown agent profile '{name}' - {hint}"),
+                        error_codes::INVALID_ARGUMENT,
+                        json!({"agent": name}),
+                    ));
+                }
+            },
+            None => None,
+        };
+
+        // Delegation guardrails: refuse before spawning rather than letting a
+        // recursive loop or a subagent swarm spend unbounded tokens.
+        let depth = current_task_depth();
+        if depth >= self.limits.max_depth {
+            return Ok(error_response(
+                &format!(
+                    "Delegation depth limit reached (depth {depth}, max {}). \
+                     Handle this task directly instead of spawning another subagent.",
+                    self.limits.max_depth
+                ),
+                error_codes::LIMIT_EXCEEDED,
+                json!({"depth": depth, "max_depth": self.limits.max_depth}),
+            ));
+        }
+        if self.limits.max_concurrent > 0 {
+            let running = running_subagents();
+            if running >= self.limits.max_concurrent {
+                return Ok(error_response(
+                    &format!(
+                        "Subagent concurrency limit reached ({running} of {} running). \
+                         Wait for one to finish (task_output) or terminate one \
+                         (kill_shell) before spawning more.",
+                        self.limits.max_concurrent
+                    ),
+                    error_codes::LIMIT_EXCEEDED,
+                    json!({"running": running, "max_concurrent": self.limits.max_concurrent}),
+                ));
+            }
+        }
+
         let (cmd, mut cmd_args) = super::get_clemini_command();
-        cmd_args.extend(["-p".to_string(), prompt.to_string()]);
-        // Note: subagent gets its own sandbox based on cwd. It does not inherit the parent's
-        // allowed_paths - this is intentional as the subagent operates as an independent instance.
-        cmd_args.extend(["--cwd".to_string(), self.cwd.to_string_lossy().to_string()]);
+        cmd_args.extend(self.subagent_args(prompt, profile));
 
         if background {
             // Background mode: spawn detached, store in registry
             // Note: subprocess inherits environment including GEMINI_API_KEY (required for subagent)
             let child = Command::new(&cmd)
                 .args(&cmd_args)
+                .env(TASK_DEPTH_ENV, (depth + 1).to_string())
                 .stdin(Stdio::null())
                 .stdout(Stdio::piped())
                 .stderr(Stdio::piped())
@@ -86,8 +357,11 @@ impl CallableFunction for TaskTool {
                     FunctionError::ExecutionError(format!("Failed to spawn task: {}", e).into())
                 })?;
 
-            // Register in unified task registry with namespaced ID (bg-1, bg-2, etc.)
-            let task_id = register_background_task(BackgroundTask::new(child));
+            // Register in unified task registry with namespaced ID (bg-1, bg-2, etc.).
+            // Output lines stream to the UI as they arrive, tagged with the ID.
+            let task_id =
+                register_streaming_background_task(child, self.events_tx.clone(), prompt);
+            BACKGROUND_SUBAGENTS.lock().unwrap().push(task_id.clone());
 
             self.emit(&format!("  task {} running in background", task_id));
 
@@ -100,9 +374,11 @@ impl CallableFunction for TaskTool {
             // Foreground mode: wait for completion, capture output
             self.emit("  running subagent...");
 
+            let _slot = ForegroundSlot::claim();
             let mut child = Command::new(&cmd);
             child
                 .args(&cmd_args)
+                .env(TASK_DEPTH_ENV, (depth + 1).to_string())
                 .stdin(Stdio::null())
                 .stdout(Stdio::piped())
                 .stderr(Stdio::piped())
@@ -156,6 +432,183 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_subagent_args_without_model() {
+        let dir = tempdir().unwrap();
+        let tool = TaskTool::new(dir.path().to_path_buf(), None);
+        let args = tool.subagent_args("do the thing", None);
+
+        assert_eq!(args[0], "-p");
+        assert_eq!(args[1], "do the thing");
+        assert!(args.contains(&"--cwd".to_string()));
+        assert!(!args.contains(&"--model".to_string()));
+    }
+
+    #[test]
+    fn test_subagent_args_with_model_routing() {
+        let dir = tempdir().unwrap();
+        let tool = TaskTool::new(dir.path().to_path_buf(), None)
+            .with_model(Some("gemini-flash-lite-latest".to_string()));
+        let args = tool.subagent_args("do the thing", None);
+
+        let model_pos = args.iter().position(|a| a == "--model").unwrap();
+        assert_eq!(args[model_pos + 1], "gemini-flash-lite-latest");
+    }
+
+    fn explorer_profiles() -> HashMap<String, AgentProfile> {
+        HashMap::from([(
+            "explorer".to_string(),
+            AgentProfile {
+                system_prompt: Some("You are a read-only explorer.".to_string()),
+                model: Some("gemini-flash-lite-latest".to_string()),
+                allowed_tools: Some(vec!["read_file".to_string(), "grep".to_string()]),
+                max_turns: Some(15),
+            },
+        )])
+    }
+
+    #[test]
+    fn test_subagent_args_with_profile() {
+        let dir = tempdir().unwrap();
+        let profiles = explorer_profiles();
+        let tool = TaskTool::new(dir.path().to_path_buf(), None)
+            .with_model(Some("gemini-3-flash-preview".to_string()))
+            .with_profiles(profiles.clone());
+        let args = tool.subagent_args("explore", profiles.get("explorer"));
+
+        // Profile model wins over the routed model.
+        let model_pos = args.iter().position(|a| a == "--model").unwrap();
+        assert_eq!(args[model_pos + 1], "gemini-flash-lite-latest");
+
+        let prompt_pos = args
+            .iter()
+            .position(|a| a == "--append-system-prompt")
+            .unwrap();
+        assert_eq!(args[prompt_pos + 1], "You are a read-only explorer.");
+
+        let tools_pos = args.iter().position(|a| a == "--allowed-tools").unwrap();
+        assert_eq!(args[tools_pos + 1], "read_file,grep");
+
+        let turns_pos = args.iter().position(|a| a == "--max-turns").unwrap();
+        assert_eq!(args[turns_pos + 1], "15");
+    }
+
+    #[test]
+    fn test_subagent_args_with_empty_profile_adds_nothing() {
+        let dir = tempdir().unwrap();
+        let tool = TaskTool::new(dir.path().to_path_buf(), None);
+        let profile = AgentProfile::default();
+        let args = tool.subagent_args("explore", Some(&profile));
+
+        assert!(!args.contains(&"--model".to_string()));
+        assert!(!args.contains(&"--append-system-prompt".to_string()));
+        assert!(!args.contains(&"--allowed-tools".to_string()));
+        assert!(!args.contains(&"--max-turns".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_agent_profile_is_an_error() {
+        let dir = tempdir().unwrap();
+        let tool =
+            TaskTool::new(dir.path().to_path_buf(), None).with_profiles(explorer_profiles());
+
+        let result = tool
+            .call(json!({"prompt": "go", "agent": "nonexistent"}))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+        assert!(
+            result["error"]
+                .as_str()
+                .unwrap()
+                .contains("available: explorer")
+        );
+    }
+
+    #[test]
+    fn test_declaration_lists_profile_names() {
+        let dir = tempdir().unwrap();
+        let tool =
+            TaskTool::new(dir.path().to_path_buf(), None).with_profiles(explorer_profiles());
+        let decl = tool.declaration();
+        let params = decl.parameters();
+        let properties = params.properties();
+        assert!(
+            properties["agent"]["description"]
+                .as_str()
+                .unwrap()
+                .contains("Available: explorer")
+        );
+    }
+
+    #[test]
+    fn test_subagent_args_with_token_budget() {
+        let dir = tempdir().unwrap();
+        let tool = TaskTool::new(dir.path().to_path_buf(), None).with_limits(TaskLimits {
+            token_budget: Some(50_000),
+            ..TaskLimits::default()
+        });
+        let args = tool.subagent_args("do the thing", None);
+
+        let budget_pos = args.iter().position(|a| a == "--max-tokens").unwrap();
+        assert_eq!(args[budget_pos + 1], "50000");
+    }
+
+    #[test]
+    fn test_subagent_args_without_token_budget() {
+        let dir = tempdir().unwrap();
+        let tool = TaskTool::new(dir.path().to_path_buf(), None);
+        let args = tool.subagent_args("do the thing", None);
+
+        assert!(!args.contains(&"--max-tokens".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_max_depth_zero_disables_delegation() {
+        let dir = tempdir().unwrap();
+        let tool = TaskTool::new(dir.path().to_path_buf(), None).with_limits(TaskLimits {
+            max_depth: 0,
+            ..TaskLimits::default()
+        });
+
+        let result = tool.call(json!({"prompt": "go"})).await.unwrap();
+        assert_eq!(result["error_code"], error_codes::LIMIT_EXCEEDED);
+        assert!(result["error"].as_str().unwrap().contains("depth"));
+        assert_eq!(result["context"]["max_depth"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_cap_refuses_spawn() {
+        let dir = tempdir().unwrap();
+        let tool = TaskTool::new(dir.path().to_path_buf(), None).with_limits(TaskLimits {
+            max_concurrent: 1,
+            ..TaskLimits::default()
+        });
+
+        // Occupy the only slot as if a foreground subagent were running.
+        let _slot = ForegroundSlot::claim();
+
+        let result = tool.call(json!({"prompt": "go"})).await.unwrap();
+        assert_eq!(result["error_code"], error_codes::LIMIT_EXCEEDED);
+        assert!(result["error"].as_str().unwrap().contains("concurrency"));
+    }
+
+    #[test]
+    fn test_task_limits_defaults() {
+        let limits = TaskLimits::default();
+        assert_eq!(limits.max_depth, 3);
+        assert_eq!(limits.max_concurrent, 4);
+        assert!(limits.token_budget.is_none());
+    }
+
+    #[test]
+    fn test_task_limits_deserializes_partial_section() {
+        let limits: TaskLimits = toml::from_str("max_depth = 1").unwrap();
+        assert_eq!(limits.max_depth, 1);
+        assert_eq!(limits.max_concurrent, 4);
+        assert!(limits.token_budget.is_none());
+    }
+
     #[test]
     fn test_task_tool_declaration() {
         let dir = tempdir().unwrap();
@@ -29,7 +29,8 @@ impl CallableFunction for TaskOutputTool {
     fn declaration(&self) -> FunctionDeclaration {
         FunctionDeclaration::new(
             "task_output".to_string(),
-            "Get the output and status of a background task. Returns: {task_id, status, exit_code, stdout, stderr}".to_string(),
+            "Get the output and status of a background task. Returns: {task_id, status, exit_code, stdout, stderr}. \
+             With follow/since_line, returns only new output lines plus next_line to pass on the next poll.".to_string(),
             FunctionParameters::new(
                 "object".to_string(),
                 json!({
@@ -43,7 +44,15 @@ impl CallableFunction for TaskOutputTool {
                     },
                     "timeout": {
                         "type": "integer",
-                        "description": "Maximum time to wait in seconds if wait=true. (default: 30)"
+                        "description": "Maximum time to wait in seconds if wait=true or follow=true. (default: 30)"
+                    },
+                    "follow": {
+                        "type": "boolean",
+                        "description": "Tail mode: block up to timeout seconds until output beyond since_line arrives (or the task completes), then return only the new lines. (default: false)"
+                    },
+                    "since_line": {
+                        "type": "integer",
+                        "description": "Skip output lines before this offset (use next_line from the previous poll). Implies tail mode: only new lines are returned. (default: 0)"
                     }
                 }),
                 vec!["task_id".to_string()],
@@ -62,6 +71,18 @@ impl CallableFunction for TaskOutputTool {
 
         let timeout_secs = args.get("timeout").and_then(|v| v.as_u64()).unwrap_or(30);
 
+        let follow = args
+            .get("follow")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let since_line = args
+            .get("since_line")
+            .and_then(|v| v.as_u64())
+            .map(|n| n as usize);
+        // An explicit since_line is a tail request even without follow.
+        let tail = follow || since_line.is_some();
+        let since_line = since_line.unwrap_or(0);
+
         // First check - update status and verify task exists
         {
             let mut tasks = TASKS.lock().unwrap();
@@ -104,6 +125,34 @@ impl CallableFunction for TaskOutputTool {
                     break;
                 }
 
+                sleep(Duration::from_millis(200)).await;
+            }
+        } else if follow {
+            // Tail mode: block until output grows past since_line, the task
+            // completes (so a final empty poll terminates cleanly), or the
+            // timeout elapses - whichever comes first.
+            let start = Instant::now();
+            let duration = Duration::from_secs(timeout_secs);
+
+            loop {
+                let (line_count, completed) = {
+                    let mut tasks = TASKS.lock().unwrap();
+                    if let Some(task) = tasks.get_mut(task_id) {
+                        task.update_status();
+                        (task.output().lines().count(), task.is_completed())
+                    } else {
+                        return Ok(error_response(
+                            &format!("Task {} not found during follow", task_id),
+                            error_codes::NOT_FOUND,
+                            json!({ "task_id": task_id }),
+                        ));
+                    }
+                };
+
+                if line_count > since_line || completed || start.elapsed() >= duration {
+                    break;
+                }
+
                 sleep(Duration::from_millis(200)).await;
             }
         }
@@ -124,6 +173,15 @@ impl CallableFunction for TaskOutputTool {
                 "stdout": output,
             });
 
+            // Tail mode: replace the full buffer with just the new lines and
+            // report where the next poll should start from.
+            if tail {
+                let new_lines: Vec<&str> = output.lines().skip(since_line).collect();
+                resp["stdout"] = json!(new_lines.join("\n"));
+                resp["lines"] = json!(new_lines.len());
+                resp["next_line"] = json!(since_line + new_lines.len());
+            }
+
             // Add stderr/error if present
             if let Some(err) = error {
                 resp["stderr"] = json!(err);
@@ -308,6 +366,85 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_task_output_tail_returns_only_new_lines() {
+        let dir = tempdir().unwrap();
+        let bash = BashTool::new_without_confirmation_tracking(
+            dir.path().to_path_buf(),
+            vec![dir.path().to_path_buf()],
+            5,
+            false,
+            None,
+        );
+
+        let bash_result = bash
+            .call(json!({
+                "command": "echo one; echo two; echo three",
+                "run_in_background": true
+            }))
+            .await
+            .unwrap();
+        let task_id = bash_result["task_id"].as_str().unwrap();
+
+        let tool = TaskOutputTool::new(None);
+
+        // First poll with follow waits for completion output and returns all
+        // lines plus the offset for the next poll.
+        let result = tool
+            .call(json!({ "task_id": task_id, "follow": true, "timeout": 5 }))
+            .await
+            .unwrap();
+        assert!(result["stdout"].as_str().unwrap().contains("one"));
+        let next_line = result["next_line"].as_u64().unwrap();
+        assert_eq!(next_line, result["lines"].as_u64().unwrap());
+
+        // Second poll from next_line has nothing new.
+        let result = tool
+            .call(json!({ "task_id": task_id, "since_line": next_line }))
+            .await
+            .unwrap();
+        assert_eq!(result["stdout"].as_str().unwrap(), "");
+        assert_eq!(result["lines"].as_u64().unwrap(), 0);
+        assert_eq!(result["next_line"].as_u64().unwrap(), next_line);
+    }
+
+    #[tokio::test]
+    async fn test_task_output_since_line_skips_earlier_lines() {
+        let dir = tempdir().unwrap();
+        let bash = BashTool::new_without_confirmation_tracking(
+            dir.path().to_path_buf(),
+            vec![dir.path().to_path_buf()],
+            5,
+            false,
+            None,
+        );
+
+        let bash_result = bash
+            .call(json!({
+                "command": "echo first; echo second",
+                "run_in_background": true
+            }))
+            .await
+            .unwrap();
+        let task_id = bash_result["task_id"].as_str().unwrap();
+
+        let tool = TaskOutputTool::new(None);
+
+        // Let the task finish so the buffer is stable.
+        let _ = tool
+            .call(json!({ "task_id": task_id, "wait": true, "timeout": 5 }))
+            .await
+            .unwrap();
+
+        let result = tool
+            .call(json!({ "task_id": task_id, "since_line": 1 }))
+            .await
+            .unwrap();
+        let stdout = result["stdout"].as_str().unwrap();
+        assert!(!stdout.contains("first"));
+        assert!(stdout.contains("second"));
+    }
+
     #[tokio::test]
     async fn test_task_output_tool_nonzero_exit_code() {
         let dir = tempdir().unwrap();
@@ -2,13 +2,24 @@
 //!
 //! This module consolidates BACKGROUND_TASKS and ACP_TASKS into a single
 //! registry with namespaced IDs to prevent collisions.
+//!
+//! Background task metadata (pid, command, log file path) is persisted under
+//! `~/.clemini/tasks/` so that `task_output bg-1` keeps working after the
+//! process restarts: [`reattach_persisted_tasks`] rebuilds registry entries
+//! for still-running processes and salvages the output of ones that finished
+//! while clemini was down.
 
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{LazyLock, Mutex};
 
+use super::MAX_BACKGROUND_BUFFER_LEN;
+
 // Re-export task types from their modules
 pub use super::background::BackgroundTask;
+pub use super::bash::PtyTask;
 pub use crate::acp_client::AcpTask;
 
 /// Global counter for generating unique task IDs.
@@ -18,16 +29,25 @@ static NEXT_TASK_ID: AtomicUsize = AtomicUsize::new(1);
 ///
 /// - Background tasks: "bg-1", "bg-2", etc.
 /// - ACP tasks: "acp-1", "acp-2", etc.
+/// - PTY tasks: "pty-1", "pty-2", etc.
 pub fn next_task_id(prefix: &str) -> String {
     format!("{}-{}", prefix, NEXT_TASK_ID.fetch_add(1, Ordering::SeqCst))
 }
 
-/// Unified task type that can hold either a background shell task or an ACP subagent task.
+/// Unified task type that can hold a background shell task, an ACP subagent
+/// task, an interactive PTY task, or a task reattached from a previous
+/// process.
 pub enum Task {
     /// Background bash command.
     Background(BackgroundTask),
     /// ACP subagent task.
     Acp(AcpTask),
+    /// Interactive command running under a pseudo-terminal.
+    Pty(PtyTask),
+    /// Background task started by a previous clemini process, reattached
+    /// from persisted metadata. Tracked by PID; output comes from its log
+    /// file.
+    Detached(DetachedTask),
 }
 
 impl Task {
@@ -36,14 +56,19 @@ impl Task {
         match self {
             Task::Background(task) => task.is_completed(),
             Task::Acp(task) => task.is_completed(),
+            Task::Pty(task) => task.is_completed(),
+            Task::Detached(task) => task.is_completed(),
         }
     }
 
-    /// Get the task output (stdout for background, output_buffer for ACP).
+    /// Get the task output (stdout for background, output_buffer for ACP,
+    /// combined stream for PTY, log file for detached).
     pub fn output(&self) -> String {
         match self {
             Task::Background(task) => task.stdout(),
             Task::Acp(task) => task.output(),
+            Task::Pty(task) => task.output(),
+            Task::Detached(task) => task.output(),
         }
     }
 
@@ -59,6 +84,10 @@ impl Task {
                 }
             }
             Task::Acp(task) => task.error(),
+            // PTYs merge stderr into the combined output stream
+            Task::Pty(_) => None,
+            // Detached task logs interleave stdout and stderr
+            Task::Detached(_) => None,
         }
     }
 
@@ -73,6 +102,15 @@ impl Task {
                 }
             }
             Task::Acp(_) => None, // ACP tasks don't have exit codes
+            Task::Pty(task) => {
+                if task.is_completed() {
+                    Some(task.exit_code())
+                } else {
+                    None
+                }
+            }
+            // The process that could have waited on it is gone
+            Task::Detached(_) => None,
         }
     }
 
@@ -81,13 +119,18 @@ impl Task {
         match self {
             Task::Background(_) => "background",
             Task::Acp(_) => "acp",
+            Task::Pty(_) => "pty",
+            Task::Detached(_) => "detached",
         }
     }
 
-    /// Update status for background tasks (no-op for ACP).
+    /// Update status for background, PTY, and detached tasks (no-op for ACP).
     pub fn update_status(&mut self) {
-        if let Task::Background(task) = self {
-            task.update_status();
+        match self {
+            Task::Background(task) => task.update_status(),
+            Task::Pty(task) => task.update_status(),
+            Task::Detached(task) => task.update_status(),
+            Task::Acp(_) => {}
         }
     }
 
@@ -95,19 +138,247 @@ impl Task {
     pub fn as_background_mut(&mut self) -> Option<&mut BackgroundTask> {
         match self {
             Task::Background(task) => Some(task),
-            Task::Acp(_) => None,
+            _ => None,
         }
     }
 
     /// Get as mutable AcpTask if this is an Acp variant.
     pub fn as_acp_mut(&mut self) -> Option<&mut AcpTask> {
         match self {
-            Task::Background(_) => None,
             Task::Acp(task) => Some(task),
+            _ => None,
+        }
+    }
+
+    /// Get as mutable PtyTask if this is a Pty variant.
+    pub fn as_pty_mut(&mut self) -> Option<&mut PtyTask> {
+        match self {
+            Task::Pty(task) => Some(task),
+            _ => None,
+        }
+    }
+
+    /// Get as mutable DetachedTask if this is a Detached variant.
+    pub fn as_detached_mut(&mut self) -> Option<&mut DetachedTask> {
+        match self {
+            Task::Detached(task) => Some(task),
+            _ => None,
         }
     }
 }
 
+/// A background task started by a previous clemini process, rebuilt from
+/// persisted metadata. There's no `Child` handle to wait on, so liveness is
+/// tracked by PID and output is read from the log file the original
+/// collectors wrote.
+pub struct DetachedTask {
+    pid: u32,
+    command: String,
+    log_path: PathBuf,
+    completed: bool,
+    /// Output salvaged from the log file when the process was already dead
+    /// at reattach time (the files on disk are cleaned up in that case).
+    salvaged_output: Option<String>,
+}
+
+impl DetachedTask {
+    /// Check if the task has completed.
+    pub fn is_completed(&self) -> bool {
+        self.completed
+    }
+
+    /// The command line the original process was started with.
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    /// The PID of the original process.
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// Get the task output: the salvaged copy if the process was already
+    /// dead at reattach, otherwise the current contents of the log file.
+    pub fn output(&self) -> String {
+        if let Some(salvaged) = &self.salvaged_output {
+            return salvaged.clone();
+        }
+        match std::fs::read_to_string(&self.log_path) {
+            Ok(content) => truncate_buffer(content),
+            Err(_) => String::new(),
+        }
+    }
+
+    /// Re-check whether the process is still alive.
+    pub fn update_status(&mut self) {
+        if !self.completed && !pid_alive(self.pid) {
+            self.completed = true;
+        }
+    }
+
+    /// Kill the process by PID.
+    pub fn kill(&mut self) -> std::io::Result<()> {
+        // SAFETY: sending SIGKILL to a pid is inherently racy (the pid could
+        // have been reused) but matches what `kill -9` would do
+        let result = unsafe { libc::kill(self.pid as i32, libc::SIGKILL) };
+        if result == 0 {
+            self.completed = true;
+            Ok(())
+        } else {
+            Err(std::io::Error::last_os_error())
+        }
+    }
+}
+
+/// Check whether a process with this PID exists (signal 0 performs error
+/// checking without sending anything).
+pub(crate) fn pid_alive(pid: u32) -> bool {
+    unsafe { libc::kill(pid as i32, 0) == 0 }
+}
+
+/// Send a signal to a process by PID.
+pub(crate) fn send_signal(pid: u32, signal: i32) -> std::io::Result<()> {
+    if unsafe { libc::kill(pid as i32, signal) } == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
+/// Cap salvaged/log output at the same limit the in-memory buffers use.
+fn truncate_buffer(mut content: String) -> String {
+    if content.len() > MAX_BACKGROUND_BUFFER_LEN {
+        let len = content.len();
+        content.truncate(MAX_BACKGROUND_BUFFER_LEN);
+        content.push_str(&format!("\n... [truncated, {} bytes total]", len));
+    }
+    content
+}
+
+/// Metadata persisted for each background task under `~/.clemini/tasks/`
+/// (`<task_id>.json` alongside a `<task_id>.log` with the captured output).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaskMetadata {
+    pub task_id: String,
+    pub pid: u32,
+    pub command: String,
+    pub log_path: PathBuf,
+}
+
+/// Directory where background task metadata and logs are persisted.
+pub fn tasks_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".clemini")
+        .join("tasks")
+}
+
+/// Persist metadata for a background task so it can be reattached by a
+/// later process. Failures are logged, not propagated - persistence is
+/// best-effort and shouldn't fail the spawn.
+fn persist_task_metadata(dir: &Path, metadata: &TaskMetadata) {
+    let write = || -> std::io::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        let json = serde_json::to_string_pretty(metadata)?;
+        std::fs::write(dir.join(format!("{}.json", metadata.task_id)), json)
+    };
+    if let Err(e) = write() {
+        tracing::warn!("Failed to persist metadata for {}: {}", metadata.task_id, e);
+    }
+}
+
+/// Remove the persisted metadata and log files for a task (if any).
+pub fn remove_persisted_task(task_id: &str) {
+    remove_persisted_task_in(&tasks_dir(), task_id);
+}
+
+fn remove_persisted_task_in(dir: &Path, task_id: &str) {
+    let _ = std::fs::remove_file(dir.join(format!("{task_id}.json")));
+    let _ = std::fs::remove_file(dir.join(format!("{task_id}.log")));
+}
+
+/// Reattach background tasks persisted by previous processes.
+///
+/// Called once at startup. Still-running processes become `Task::Detached`
+/// entries that `task_output`/`kill_shell` can address by their original
+/// IDs. Processes that died while clemini was down have their log output
+/// salvaged into a completed entry and their files removed; unparsable
+/// metadata is cleaned up too.
+pub fn reattach_persisted_tasks() {
+    reattach_persisted_tasks_in(&tasks_dir());
+}
+
+fn reattach_persisted_tasks_in(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return; // No tasks dir yet - nothing persisted
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+
+        let metadata: TaskMetadata = match std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|s| serde_json::from_str(&s).map_err(|e| e.to_string()))
+        {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                tracing::warn!("Removing unparsable task metadata {}: {}", path.display(), e);
+                let _ = std::fs::remove_file(&path);
+                continue;
+            }
+        };
+
+        // Keep the global counter ahead of reattached IDs so new tasks in
+        // this process can't collide with them
+        if let Some(n) = metadata
+            .task_id
+            .rsplit('-')
+            .next()
+            .and_then(|s| s.parse::<usize>().ok())
+        {
+            NEXT_TASK_ID.fetch_max(n + 1, Ordering::SeqCst);
+        }
+
+        let mut tasks = TASKS.lock().unwrap();
+        if tasks.contains_key(&metadata.task_id) {
+            continue;
+        }
+
+        let alive = pid_alive(metadata.pid);
+        let salvaged_output = if alive {
+            None
+        } else {
+            // The process finished while clemini was down: keep its output
+            // in memory for this session and clean up the files
+            let output = std::fs::read_to_string(&metadata.log_path)
+                .map(truncate_buffer)
+                .unwrap_or_default();
+            remove_persisted_task_in(dir, &metadata.task_id);
+            Some(output)
+        };
+
+        tracing::info!(
+            "Reattached task {} (pid {}, {})",
+            metadata.task_id,
+            metadata.pid,
+            if alive { "running" } else { "finished" }
+        );
+        tasks.insert(
+            metadata.task_id.clone(),
+            Task::Detached(DetachedTask {
+                pid: metadata.pid,
+                command: metadata.command,
+                log_path: metadata.log_path,
+                completed: !alive,
+                salvaged_output,
+            }),
+        );
+    }
+}
+
 /// Global registry of all tasks (background and ACP).
 pub static TASKS: LazyLock<Mutex<HashMap<String, Task>>> =
     LazyLock::new(|| Mutex::new(HashMap::new()));
@@ -120,6 +391,47 @@ pub fn register_background_task(task: BackgroundTask) -> String {
     id
 }
 
+/// Register a background task for `child` that streams its output lines to
+/// the UI as `AgentEvent::ToolOutput` (tagged with the task ID) as they
+/// arrive, instead of only surfacing them when `task_output` is polled.
+///
+/// The ID is generated before the task is constructed so the reader tasks
+/// can tag their lines with it. Output is also teed to a log file under
+/// `~/.clemini/tasks/` and the task metadata (pid, `command`) is persisted
+/// there so a later process can reattach via [`reattach_persisted_tasks`].
+pub fn register_streaming_background_task(
+    child: tokio::process::Child,
+    events_tx: Option<tokio::sync::mpsc::Sender<crate::agent::AgentEvent>>,
+    command: &str,
+) -> String {
+    let id = next_task_id("bg");
+
+    // Best-effort persistence: a task that can't be persisted still runs,
+    // it just won't survive a restart
+    let dir = tasks_dir();
+    let log_path = dir.join(format!("{id}.log"));
+    let log_file = std::fs::create_dir_all(&dir)
+        .and_then(|()| std::fs::File::create(&log_path))
+        .map_err(|e| tracing::warn!("Failed to create log file for {}: {}", id, e))
+        .ok();
+    if let (Some(_), Some(pid)) = (&log_file, child.id()) {
+        persist_task_metadata(
+            &dir,
+            &TaskMetadata {
+                task_id: id.clone(),
+                pid,
+                command: command.to_string(),
+                log_path: log_path.clone(),
+            },
+        );
+    }
+
+    let task = BackgroundTask::new_streaming(child, events_tx, &id, log_file);
+    let mut tasks = TASKS.lock().unwrap();
+    tasks.insert(id.clone(), Task::Background(task));
+    id
+}
+
 /// Register an ACP task and return its ID.
 pub fn register_acp_task(task: AcpTask) -> String {
     let id = next_task_id("acp");
@@ -128,6 +440,14 @@ pub fn register_acp_task(task: AcpTask) -> String {
     id
 }
 
+/// Register a PTY task and return its ID.
+pub fn register_pty_task(task: PtyTask) -> String {
+    let id = next_task_id("pty");
+    let mut tasks = TASKS.lock().unwrap();
+    tasks.insert(id.clone(), Task::Pty(task));
+    id
+}
+
 /// Get a list of all task IDs.
 pub fn list_task_ids() -> Vec<String> {
     let tasks = TASKS.lock().unwrap();
@@ -169,6 +489,98 @@ mod tests {
         assert!(tasks.contains_key(&id));
     }
 
+    #[tokio::test]
+    async fn test_reattach_running_task() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut child = Command::new("sleep").arg("5").spawn().unwrap();
+        let pid = child.id().unwrap();
+
+        let log_path = dir.path().join("bg-911001.log");
+        std::fs::write(&log_path, "partial output\n").unwrap();
+        let metadata = TaskMetadata {
+            task_id: "bg-911001".to_string(),
+            pid,
+            command: "sleep 5".to_string(),
+            log_path: log_path.clone(),
+        };
+        std::fs::write(
+            dir.path().join("bg-911001.json"),
+            serde_json::to_string(&metadata).unwrap(),
+        )
+        .unwrap();
+
+        reattach_persisted_tasks_in(dir.path());
+
+        {
+            let mut tasks = TASKS.lock().unwrap();
+            let task = tasks.get_mut("bg-911001").expect("task reattached");
+            assert_eq!(task.task_type(), "detached");
+            task.update_status();
+            assert!(!task.is_completed());
+            assert!(task.output().contains("partial output"));
+        }
+        // Files stay on disk while the process is alive
+        assert!(dir.path().join("bg-911001.json").exists());
+
+        // Cleanup
+        let _ = child.kill().await;
+        TASKS.lock().unwrap().remove("bg-911001");
+    }
+
+    #[tokio::test]
+    async fn test_reattach_dead_task_salvages_output_and_cleans_up() {
+        let dir = tempfile::tempdir().unwrap();
+        // Get a PID that's guaranteed dead: spawn and reap a short command
+        let mut child = Command::new("true").spawn().unwrap();
+        let pid = child.id().unwrap();
+        let _ = child.wait().await;
+
+        let log_path = dir.path().join("bg-922001.log");
+        std::fs::write(&log_path, "finished build output\n").unwrap();
+        let metadata = TaskMetadata {
+            task_id: "bg-922001".to_string(),
+            pid,
+            command: "cargo build".to_string(),
+            log_path: log_path.clone(),
+        };
+        std::fs::write(
+            dir.path().join("bg-922001.json"),
+            serde_json::to_string(&metadata).unwrap(),
+        )
+        .unwrap();
+
+        reattach_persisted_tasks_in(dir.path());
+
+        {
+            let tasks = TASKS.lock().unwrap();
+            let task = tasks.get("bg-922001").expect("task reattached");
+            assert!(task.is_completed());
+            // Output was salvaged into memory before the files were removed
+            assert!(task.output().contains("finished build output"));
+        }
+        assert!(!dir.path().join("bg-922001.json").exists());
+        assert!(!log_path.exists());
+
+        // New IDs can't collide with reattached ones
+        let next = next_task_id("bg");
+        let suffix: usize = next.rsplit('-').next().unwrap().parse().unwrap();
+        assert!(suffix > 922001, "counter not bumped: {next}");
+
+        TASKS.lock().unwrap().remove("bg-922001");
+    }
+
+    #[tokio::test]
+    async fn test_reattach_removes_unparsable_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bg-933001.json");
+        std::fs::write(&path, "not json {").unwrap();
+
+        reattach_persisted_tasks_in(dir.path());
+
+        assert!(!path.exists());
+        assert!(!TASKS.lock().unwrap().contains_key("bg-933001"));
+    }
+
     #[tokio::test]
     async fn test_task_type_discrimination() {
         let child = Command::new("echo")
@@ -0,0 +1,122 @@
+use crate::agent::AgentEvent;
+use crate::tools::ToolEmitter;
+use async_trait::async_trait;
+use colored::Colorize;
+use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use tokio::sync::mpsc;
+use tracing::instrument;
+
+/// Reads back the todo list persisted by `todo_write`
+/// (`~/.clemini/sessions/<project-hash>-todos.json`), so the model can
+/// rehydrate its plan after context compaction or when resuming a session.
+pub struct TodoReadTool {
+    cwd: PathBuf,
+    events_tx: Option<mpsc::Sender<AgentEvent>>,
+}
+
+impl TodoReadTool {
+    pub fn new(cwd: PathBuf, events_tx: Option<mpsc::Sender<AgentEvent>>) -> Self {
+        Self { cwd, events_tx }
+    }
+}
+
+impl ToolEmitter for TodoReadTool {
+    fn events_tx(&self) -> &Option<mpsc::Sender<AgentEvent>> {
+        &self.events_tx
+    }
+}
+
+#[async_trait]
+impl CallableFunction for TodoReadTool {
+    fn declaration(&self) -> FunctionDeclaration {
+        FunctionDeclaration::new(
+            "todo_read".to_string(),
+            "Read back the todo list from the last todo_write call, persisted across sessions for this workspace. Use it to rehydrate your plan when resuming previous work or after losing context. Returns: {todos: [{content, activeForm, status}], count}".to_string(),
+            FunctionParameters::new("object".to_string(), json!({}), vec![]),
+        )
+    }
+
+    #[instrument(skip(self, _args))]
+    async fn call(&self, _args: Value) -> Result<Value, FunctionError> {
+        let todos = super::todo_write::load_todos(&self.cwd).unwrap_or_default();
+
+        self.emit(&format!("  {} todos", todos.len()).dimmed().to_string());
+
+        let mut response = json!({
+            "todos": todos,
+            "count": todos.len(),
+        });
+        if response["count"] == 0 {
+            response["note"] = json!("No saved todo list for this workspace.");
+        }
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::TodoWriteTool;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_todo_read_round_trip() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+
+        let write = TodoWriteTool::new(cwd.clone(), None);
+        write
+            .call(json!({
+                "todos": [
+                    { "content": "Read code", "activeForm": "Reading code", "status": "completed" },
+                    { "content": "Write tests", "activeForm": "Writing tests", "status": "in_progress" }
+                ]
+            }))
+            .await
+            .unwrap();
+
+        let read = TodoReadTool::new(cwd.clone(), None);
+        let result = read.call(json!({})).await.unwrap();
+        assert_eq!(result["count"], 2);
+        assert_eq!(result["todos"][0]["content"], "Read code");
+        assert_eq!(result["todos"][1]["status"], "in_progress");
+
+        // Clearing the list removes the saved file
+        write.call(json!({"todos": []})).await.unwrap();
+        let result = read.call(json!({})).await.unwrap();
+        assert_eq!(result["count"], 0);
+        assert!(result["note"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_todo_read_empty_workspace() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+
+        let tool = TodoReadTool::new(cwd.clone(), None);
+        let result = tool.call(json!({})).await.unwrap();
+        assert_eq!(result["count"], 0);
+        assert_eq!(result["todos"], json!([]));
+    }
+
+    #[test]
+    fn test_restore_summary_renders_saved_list() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        assert!(super::super::todo_write::restore_summary(&cwd).is_none());
+
+        let path = super::super::todo_write::todos_file_path(&cwd);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(
+            &path,
+            r#"[{"content": "Ship it", "activeForm": "Shipping it", "status": "pending"}]"#,
+        )
+        .unwrap();
+
+        let summary = super::super::todo_write::restore_summary(&cwd).unwrap();
+        assert!(summary.contains("Ship it"));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
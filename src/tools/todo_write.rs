@@ -4,10 +4,63 @@ use async_trait::async_trait;
 use colored::Colorize;
 use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
 use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
 use tokio::sync::mpsc;
 use tracing::instrument;
 
+/// Where the workspace's todo list is persisted. Keyed by workspace (like
+/// the memory file) since interaction IDs live server-side - resuming with
+/// `--interaction` from the same directory finds the same list.
+pub fn todos_file_path(cwd: &Path) -> PathBuf {
+    let canonical = cwd.canonicalize().unwrap_or_else(|_| cwd.to_path_buf());
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".clemini")
+        .join("sessions")
+        .join(format!(
+            "{}-todos.json",
+            crate::tools::memory::project_hash(&canonical)
+        ))
+}
+
+/// Load the persisted todo list (raw items as last written by
+/// `todo_write`). Returns `None` when there is no saved list.
+pub fn load_todos(cwd: &Path) -> Option<Vec<Value>> {
+    let raw = std::fs::read_to_string(todos_file_path(cwd)).ok()?;
+    let todos: Vec<Value> = serde_json::from_str(&raw).ok()?;
+    (!todos.is_empty()).then_some(todos)
+}
+
+/// Render the persisted list for display when resuming a session.
+pub fn restore_summary(cwd: &Path) -> Option<String> {
+    let todos = load_todos(cwd)?;
+    let lines: Vec<String> = todos
+        .iter()
+        .map(|todo| {
+            TodoWriteTool::render_todo(&TodoItem {
+                content: todo
+                    .get("content")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                active_form: todo
+                    .get("activeForm")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                status: todo
+                    .get("status")
+                    .and_then(|v| v.as_str())
+                    .map(TodoStatus::from)
+                    .unwrap_or(TodoStatus::Pending),
+            })
+        })
+        .collect();
+    Some(lines.join("\n"))
+}
+
 pub struct TodoWriteTool {
+    cwd: PathBuf,
     events_tx: Option<mpsc::Sender<AgentEvent>>,
 }
 
@@ -42,8 +95,41 @@ impl ToolEmitter for TodoWriteTool {
 }
 
 impl TodoWriteTool {
-    pub fn new(events_tx: Option<mpsc::Sender<AgentEvent>>) -> Self {
-        Self { events_tx }
+    pub fn new(cwd: PathBuf, events_tx: Option<mpsc::Sender<AgentEvent>>) -> Self {
+        Self { cwd, events_tx }
+    }
+
+    /// Persist the validated list so `todo_read` (and the next session's
+    /// resume banner) can rehydrate it. Failures are logged, not surfaced -
+    /// losing persistence shouldn't fail the display.
+    fn persist(&self, todos: &[TodoItem]) {
+        let path = todos_file_path(&self.cwd);
+        if todos.is_empty() {
+            let _ = std::fs::remove_file(&path);
+            return;
+        }
+        let items: Vec<Value> = todos
+            .iter()
+            .map(|t| {
+                json!({
+                    "content": t.content,
+                    "activeForm": t.active_form,
+                    "status": match t.status {
+                        TodoStatus::Pending => "pending",
+                        TodoStatus::InProgress => "in_progress",
+                        TodoStatus::Completed => "completed",
+                    },
+                })
+            })
+            .collect();
+        let result = path
+            .parent()
+            .map(std::fs::create_dir_all)
+            .unwrap_or(Ok(()))
+            .and_then(|()| std::fs::write(&path, serde_json::to_string_pretty(&items).unwrap()));
+        if let Err(e) = result {
+            tracing::warn!("Failed to persist todo list to {}: {}", path.display(), e);
+        }
     }
 
     fn parse_args(&self, args: Value) -> Result<Vec<TodoItem>, FunctionError> {
@@ -121,7 +207,7 @@ impl CallableFunction for TodoWriteTool {
     fn declaration(&self) -> FunctionDeclaration {
         FunctionDeclaration::new(
             "todo_write".to_string(),
-            "Track progress on multi-step tasks. Call this to create or update your todo list. Each call replaces the entire list, so include all items (completed, in_progress, pending). Mark exactly one item as in_progress at a time. Returns: {success, count}".to_string(),
+            "Track progress on multi-step tasks. Call this to create or update your todo list. Each call replaces the entire list, so include all items (completed, in_progress, pending). Mark exactly one item as in_progress at a time. The list persists across sessions - rehydrate it with todo_read. Returns: {success, count}".to_string(),
             FunctionParameters::new(
                 "object".to_string(),
                 json!({
@@ -159,6 +245,7 @@ impl CallableFunction for TodoWriteTool {
         for todo in &todos {
             self.emit(&Self::render_todo(todo));
         }
+        self.persist(&todos);
 
         Ok(json!({
             "success": true,
@@ -175,13 +262,13 @@ mod tests {
 
     #[test]
     fn test_declaration() {
-        let tool = TodoWriteTool::new(None);
+        let tool = TodoWriteTool::new(std::env::temp_dir(), None);
         let decl = tool.declaration();
 
         assert_eq!(decl.name(), "todo_write");
         assert_eq!(
             decl.description(),
-            "Track progress on multi-step tasks. Call this to create or update your todo list. Each call replaces the entire list, so include all items (completed, in_progress, pending). Mark exactly one item as in_progress at a time. Returns: {success, count}"
+            "Track progress on multi-step tasks. Call this to create or update your todo list. Each call replaces the entire list, so include all items (completed, in_progress, pending). Mark exactly one item as in_progress at a time. The list persists across sessions - rehydrate it with todo_read. Returns: {success, count}"
         );
 
         let params = decl.parameters();
@@ -210,7 +297,7 @@ mod tests {
 
     #[test]
     fn test_parse_args_success() {
-        let tool = TodoWriteTool::new(None);
+        let tool = TodoWriteTool::new(std::env::temp_dir(), None);
         let args = json!({
             "todos": [
                 { "content": "Task 1", "activeForm": "Running Task 1", "status": "completed" },
@@ -249,7 +336,7 @@ mod tests {
 
     #[test]
     fn test_parse_args_missing_todos() {
-        let tool = TodoWriteTool::new(None);
+        let tool = TodoWriteTool::new(std::env::temp_dir(), None);
         let args = json!({});
 
         let result = tool.parse_args(args);
@@ -262,7 +349,7 @@ mod tests {
 
     #[test]
     fn test_parse_args_empty_array() {
-        let tool = TodoWriteTool::new(None);
+        let tool = TodoWriteTool::new(std::env::temp_dir(), None);
         let args = json!({ "todos": [] });
 
         let todos = tool.parse_args(args).unwrap();
@@ -271,7 +358,7 @@ mod tests {
 
     #[test]
     fn test_parse_args_invalid_status() {
-        let tool = TodoWriteTool::new(None);
+        let tool = TodoWriteTool::new(std::env::temp_dir(), None);
         let args = json!({
             "todos": [
                 { "content": "Unknown status", "activeForm": "Doing something", "status": "something_else" }
@@ -319,7 +406,7 @@ mod tests {
 
     #[test]
     fn test_parse_args_all_empty_content_errors() {
-        let tool = TodoWriteTool::new(None);
+        let tool = TodoWriteTool::new(std::env::temp_dir(), None);
         let args = json!({
             "todos": [
                 { "content": "", "activeForm": "", "status": "pending" },
@@ -340,7 +427,7 @@ mod tests {
 
     #[test]
     fn test_parse_args_skips_empty_content() {
-        let tool = TodoWriteTool::new(None);
+        let tool = TodoWriteTool::new(std::env::temp_dir(), None);
         let args = json!({
             "todos": [
                 { "content": "Valid task", "activeForm": "Doing valid task", "status": "pending" },
@@ -358,7 +445,7 @@ mod tests {
 
     #[test]
     fn test_parse_args_trims_whitespace() {
-        let tool = TodoWriteTool::new(None);
+        let tool = TodoWriteTool::new(std::env::temp_dir(), None);
         let args = json!({
             "todos": [
                 { "content": "  Task with spaces  ", "activeForm": "  Doing task  ", "status": "pending" }
@@ -0,0 +1,372 @@
+use async_trait::async_trait;
+use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use std::process::Stdio;
+use tokio::process::Command;
+use tokio::sync::mpsc;
+use tracing::instrument;
+
+use super::custom::shell_quote;
+use super::{SafetyPolicy, ToolEmitter, error_codes, error_response, resolve_and_validate_path};
+use crate::agent::AgentEvent;
+use crate::tools::tasks::register_streaming_background_task;
+
+/// Default watch duration in seconds (10 minutes).
+const DEFAULT_DURATION_SECS: u64 = 600;
+
+/// How often the watch loop polls for changes, in seconds.
+const POLL_INTERVAL_SECS: u64 = 1;
+
+/// Re-run a check command whenever watched files change.
+///
+/// Runs as a streaming background task (a shell loop polling mtimes with
+/// `find -newer`), so results flow through `ToolOutput` as they happen and
+/// the model doesn't have to poll - "keep `cargo check` green while I
+/// refactor" becomes one tool call. Stop early with `kill_shell`.
+pub struct WatchTool {
+    cwd: PathBuf,
+    allowed_paths: Vec<PathBuf>,
+    events_tx: Option<mpsc::Sender<AgentEvent>>,
+    safety_policy: SafetyPolicy,
+    dry_run: bool,
+}
+
+impl WatchTool {
+    pub fn new(
+        cwd: PathBuf,
+        allowed_paths: Vec<PathBuf>,
+        events_tx: Option<mpsc::Sender<AgentEvent>>,
+    ) -> Self {
+        Self {
+            cwd,
+            allowed_paths,
+            events_tx,
+            safety_policy: SafetyPolicy::default(),
+            dry_run: false,
+        }
+    }
+
+    /// Apply the same safety policy bash uses: the check command is shell.
+    pub fn with_safety_policy(mut self, policy: SafetyPolicy) -> Self {
+        self.safety_policy = policy;
+        self
+    }
+
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Shell loop that re-runs `command` when anything under `paths` gets a
+    /// newer mtime than the sentinel file, until `duration` elapses.
+    fn watch_script(paths: &[PathBuf], command: &str, duration: u64) -> String {
+        let quoted_paths: Vec<String> = paths
+            .iter()
+            .map(|p| shell_quote(&p.to_string_lossy()))
+            .collect();
+        format!(
+            r#"sentinel=$(mktemp)
+end=$(( $(date +%s) + {duration} ))
+echo "[watch] initial run"
+{command}
+echo "[watch] exit: $?"
+while [ "$(date +%s)" -lt "$end" ]; do
+  changed=$(find {paths} -type f -newer "$sentinel" -not -path '*/.git/*' -not -path '*/target/*' -not -path '*/node_modules/*' -print -quit 2>/dev/null)
+  if [ -n "$changed" ]; then
+    touch "$sentinel"
+    echo "[watch] changed: $changed"
+    {command}
+    echo "[watch] exit: $?"
+  fi
+  sleep {interval}
+done
+rm -f "$sentinel"
+echo "[watch] duration elapsed"
+"#,
+            duration = duration,
+            paths = quoted_paths.join(" "),
+            command = command,
+            interval = POLL_INTERVAL_SECS,
+        )
+    }
+}
+
+impl ToolEmitter for WatchTool {
+    fn events_tx(&self) -> &Option<mpsc::Sender<AgentEvent>> {
+        &self.events_tx
+    }
+}
+
+#[async_trait]
+impl CallableFunction for WatchTool {
+    fn declaration(&self) -> FunctionDeclaration {
+        FunctionDeclaration::new(
+            "watch".to_string(),
+            "Watch paths and re-run a check command whenever files under them change. \
+             Runs in the background with results streamed as they happen - use for verification \
+             loops like keeping 'cargo check' green during a refactor. Stop with kill_shell. \
+             Returns: {task_id, status}"
+                .to_string(),
+            FunctionParameters::new(
+                "object".to_string(),
+                json!({
+                    "paths": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Files or directories to watch (absolute or relative to cwd)"
+                    },
+                    "command": {
+                        "type": "string",
+                        "description": "Check command to run on each change (e.g. 'cargo check')"
+                    },
+                    "duration": {
+                        "type": "integer",
+                        "description": "Stop watching after this many seconds. (default: 600)"
+                    }
+                }),
+                vec!["paths".to_string(), "command".to_string()],
+            ),
+        )
+    }
+
+    #[instrument(skip(self, args))]
+    async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        let paths: Vec<String> = args
+            .get("paths")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default();
+        if paths.is_empty() {
+            return Err(FunctionError::ArgumentMismatch(
+                "Missing paths".to_string(),
+            ));
+        }
+        let command = args
+            .get("command")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| FunctionError::ArgumentMismatch("Missing command".to_string()))?;
+        let duration = args
+            .get("duration")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_DURATION_SECS);
+
+        // The check command re-runs unattended, so blocked patterns are
+        // refused outright and caution patterns aren't allowed either -
+        // there's no confirmation flow once the loop is running.
+        if let Some(reason) = self.safety_policy.is_blocked(command) {
+            return Ok(error_response(
+                &format!("Command blocked: {}", reason),
+                error_codes::BLOCKED,
+                json!({"command": command}),
+            ));
+        }
+        if self.safety_policy.needs_caution(command) {
+            return Ok(error_response(
+                "Command requires confirmation and can't run unattended in a watch loop. \
+                 Use a non-destructive check command.",
+                error_codes::BLOCKED,
+                json!({"command": command}),
+            ));
+        }
+
+        let mut resolved = Vec::with_capacity(paths.len());
+        for path in &paths {
+            match resolve_and_validate_path(path, &self.cwd, &self.allowed_paths) {
+                Ok(p) if p.exists() => resolved.push(p),
+                Ok(_) => {
+                    return Ok(error_response(
+                        &format!("Path not found: {}", path),
+                        error_codes::NOT_FOUND,
+                        json!({"path": path}),
+                    ));
+                }
+                Err(e) => {
+                    return Ok(error_response(
+                        &format!("Access denied: {}. Path must be within allowed paths.", e),
+                        error_codes::ACCESS_DENIED,
+                        json!({"path": path}),
+                    ));
+                }
+            }
+        }
+
+        if self.dry_run {
+            self.emit(&format!(
+                "  DRY RUN (not watching): {} path(s), command: {}",
+                resolved.len(),
+                command
+            ));
+            return Ok(json!({
+                "status": "skipped",
+                "dry_run": true,
+                "command": command
+            }));
+        }
+
+        let script = Self::watch_script(&resolved, command, duration);
+        let child = Command::new("bash")
+            .arg("-c")
+            .arg(&script)
+            .current_dir(&self.cwd)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| {
+                FunctionError::ExecutionError(format!("Failed to spawn watch: {}", e).into())
+            })?;
+
+        // Streams through the unified registry like any background task, so
+        // output lines arrive tagged with the ID and kill_shell stops it.
+        let label = format!("watch: {}", command);
+        let task_id = register_streaming_background_task(child, self.events_tx.clone(), &label);
+
+        self.emit(&format!(
+            "  watching {} path(s), task {} (up to {}s)",
+            resolved.len(),
+            task_id,
+            duration
+        ));
+
+        Ok(json!({
+            "task_id": task_id,
+            "status": "running",
+            "command": command,
+            "duration": duration
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_watch_script_quotes_paths() {
+        let script = WatchTool::watch_script(
+            &[PathBuf::from("/tmp/my dir"), PathBuf::from("/tmp/src")],
+            "cargo check",
+            60,
+        );
+        assert!(script.contains("'/tmp/my dir' '/tmp/src'"));
+        assert!(script.contains("cargo check"));
+        assert!(script.contains("+ 60"));
+    }
+
+    #[tokio::test]
+    async fn test_missing_paths_is_an_error() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let tool = WatchTool::new(cwd.clone(), vec![cwd], None);
+
+        let result = tool.call(json!({"command": "true"})).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_nonexistent_path_is_an_error() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let tool = WatchTool::new(cwd.clone(), vec![cwd], None);
+
+        let result = tool
+            .call(json!({"paths": ["no-such-dir"], "command": "true"}))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_path_outside_sandbox_is_denied() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let tool = WatchTool::new(cwd.clone(), vec![cwd], None);
+
+        let result = tool
+            .call(json!({"paths": ["../escaped"], "command": "true"}))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::ACCESS_DENIED);
+    }
+
+    #[tokio::test]
+    async fn test_destructive_command_is_refused() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let tool = WatchTool::new(cwd.clone(), vec![cwd], None);
+
+        let result = tool
+            .call(json!({"paths": ["."], "command": "rm -rf target/"}))
+            .await
+            .unwrap();
+        assert_eq!(result["error_code"], error_codes::BLOCKED);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_spawns_nothing() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let tool = WatchTool::new(cwd.clone(), vec![cwd], None).with_dry_run(true);
+
+        let result = tool
+            .call(json!({"paths": ["."], "command": "true"}))
+            .await
+            .unwrap();
+        assert!(result["dry_run"].as_bool().unwrap());
+        assert!(result.get("task_id").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_watch_detects_change_and_reruns() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let tool = WatchTool::new(cwd.clone(), vec![cwd.clone()], None);
+
+        let result = tool
+            .call(json!({
+                "paths": ["."],
+                "command": "echo checked",
+                "duration": 5
+            }))
+            .await
+            .unwrap();
+        assert_eq!(result["status"], "running");
+        let task_id = result["task_id"].as_str().unwrap().to_string();
+
+        // Touch a file and give the loop time to notice.
+        tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+        std::fs::write(cwd.join("changed.txt"), "new").unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(2500)).await;
+
+        let output = {
+            let mut tasks = crate::tools::tasks::TASKS.lock().unwrap();
+            let task = tasks.get_mut(&task_id).unwrap();
+            task.update_status();
+            task.output()
+        };
+        // Initial run plus at least one change-triggered rerun.
+        assert!(output.matches("checked").count() >= 2, "got: {output}");
+        assert!(output.contains("[watch] changed:"), "got: {output}");
+
+        // Clean up the loop.
+        let child = {
+            let mut tasks = crate::tools::tasks::TASKS.lock().unwrap();
+            tasks.remove(&task_id).and_then(|mut task| {
+                if let crate::tools::tasks::Task::Background(ref mut bg) = task {
+                    bg.take_child()
+                } else {
+                    None
+                }
+            })
+        };
+        if let Some(mut child) = child {
+            let _ = child.kill().await;
+        }
+    }
+}
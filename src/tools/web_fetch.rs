@@ -3,13 +3,179 @@ use crate::tools::{MAX_TOOL_OUTPUT_LEN, ToolEmitter};
 use async_trait::async_trait;
 use colored::Colorize;
 use genai_rs::{CallableFunction, FunctionDeclaration, FunctionError, FunctionParameters};
+use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::mpsc;
 use tracing::instrument;
 
+/// Default model for prompt-based content extraction.
+const DEFAULT_EXTRACTION_MODEL: &str = "gemini-3-flash-preview";
+
+/// Default cache TTL. Within this window the same URL is served from disk
+/// without any network round trip; after it, a conditional request
+/// revalidates via ETag/Last-Modified.
+const DEFAULT_CACHE_TTL_SECS: u64 = 900;
+
+/// Upper bound on a headless-render round trip (launch, navigate, settle).
+const RENDER_TIMEOUT_SECS: u64 = 30;
+
+/// Binary names probed on PATH for headless rendering, most specific first.
+const CHROME_BINARIES: &[&str] = &[
+    "chromium",
+    "chromium-browser",
+    "google-chrome",
+    "google-chrome-stable",
+    "chrome",
+];
+
+/// On-disk cache entry for one URL, including processed extractions keyed
+/// by prompt hash so the same prompt against unchanged content doesn't
+/// re-run the LLM.
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    url: String,
+    fetched_at: u64,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    body: String,
+    #[serde(default)]
+    processed: HashMap<String, String>,
+    /// Whether `body` is the headless-rendered DOM rather than the raw
+    /// response. Rendered and raw fetches are cached under distinct keys.
+    #[serde(default)]
+    rendered: bool,
+}
+
+impl CacheEntry {
+    fn is_fresh(&self, now: u64, ttl: u64) -> bool {
+        now.saturating_sub(self.fetched_at) < ttl
+    }
+}
+
+fn cache_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".clemini")
+        .join("cache")
+        .join("web")
+}
+
+fn cache_path(url: &str, rendered: bool) -> PathBuf {
+    let key = if rendered {
+        format!("render:{url}")
+    } else {
+        url.to_string()
+    };
+    cache_dir().join(format!("{}.json", super::memory::fnv1a_hex(&key)))
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn load_entry(url: &str, rendered: bool) -> Option<CacheEntry> {
+    let raw = std::fs::read_to_string(cache_path(url, rendered)).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+/// Best-effort: a failed cache write just means the next fetch re-downloads.
+fn store_entry(entry: &CacheEntry) {
+    let path = cache_path(&entry.url, entry.rendered);
+    let result = std::fs::create_dir_all(cache_dir())
+        .and_then(|()| std::fs::write(&path, serde_json::to_string(entry).unwrap_or_default()));
+    if let Err(e) = result {
+        tracing::warn!("Failed to write web cache {}: {}", path.display(), e);
+    }
+}
+
+/// How a fetch was satisfied, reported in the response.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CacheStatus {
+    /// Served from disk within the TTL, no network.
+    Hit,
+    /// Stale entry revalidated with a 304, body reused.
+    Revalidated,
+    /// Downloaded fresh.
+    Miss,
+}
+
+impl CacheStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            CacheStatus::Hit => "hit",
+            CacheStatus::Revalidated => "revalidated",
+            CacheStatus::Miss => "miss",
+        }
+    }
+}
+
+/// Locate a Chromium/Chrome binary on PATH, if any.
+fn find_chrome() -> Option<PathBuf> {
+    let path_var = std::env::var_os("PATH")?;
+    for dir in std::env::split_paths(&path_var) {
+        for name in CHROME_BINARIES {
+            let candidate = dir.join(name);
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+        }
+    }
+    None
+}
+
+/// Drive headless Chromium to the URL and return the rendered DOM.
+async fn render_url(url: &str, chrome: &std::path::Path) -> Result<String, String> {
+    use chromiumoxide::browser::{Browser, BrowserConfig};
+    use futures_util::StreamExt;
+
+    let config = BrowserConfig::builder()
+        .chrome_executable(chrome)
+        .build()
+        .map_err(|e| format!("Failed to configure headless browser: {}", e))?;
+
+    let (mut browser, mut handler) = Browser::launch(config)
+        .await
+        .map_err(|e| format!("Failed to launch headless browser: {}", e))?;
+
+    // The handler must be polled for the browser connection to make progress.
+    let handler_task = tokio::spawn(async move { while handler.next().await.is_some() {} });
+
+    let result = async {
+        let page = browser
+            .new_page(url)
+            .await
+            .map_err(|e| format!("Failed to open page: {}", e))?;
+        page.wait_for_navigation()
+            .await
+            .map_err(|e| format!("Navigation failed: {}", e))?;
+        page.content()
+            .await
+            .map_err(|e| format!("Failed to read rendered DOM: {}", e))
+    }
+    .await;
+
+    let _ = browser.close().await;
+    let _ = browser.wait().await;
+    handler_task.abort();
+    result
+}
+
 pub struct WebFetchTool {
     api_key: String,
     events_tx: Option<mpsc::Sender<AgentEvent>>,
+    model: Option<String>,
+    /// Cache TTL in seconds; 0 disables caching entirely.
+    cache_ttl: u64,
+    /// Whether `render: true` is permitted (`web_render` config key).
+    render_enabled: bool,
+    /// Per-tool timeout in seconds from the `[timeouts]` config section.
+    timeout: Option<u64>,
 }
 
 impl ToolEmitter for WebFetchTool {
@@ -20,10 +186,51 @@ impl ToolEmitter for WebFetchTool {
 
 impl WebFetchTool {
     pub fn new(api_key: String, events_tx: Option<mpsc::Sender<AgentEvent>>) -> Self {
-        Self { api_key, events_tx }
+        Self {
+            api_key,
+            events_tx,
+            model: None,
+            cache_ttl: DEFAULT_CACHE_TTL_SECS,
+            render_enabled: false,
+            timeout: None,
+        }
     }
 
-    fn parse_args(&self, args: Value) -> Result<(String, Option<String>), FunctionError> {
+    /// Override the model used for prompt-based extraction.
+    /// `None` keeps the built-in default.
+    pub fn with_model(mut self, model: Option<String>) -> Self {
+        self.model = model;
+        self
+    }
+
+    /// Override the cache TTL (`web_cache_ttl` config key, seconds).
+    /// `None` keeps the default; 0 disables the cache.
+    pub fn with_cache_ttl(mut self, ttl: Option<u64>) -> Self {
+        if let Some(ttl) = ttl {
+            self.cache_ttl = ttl;
+        }
+        self
+    }
+
+    /// Allow headless rendering (`web_render` config key, default off).
+    pub fn with_render(mut self, enabled: bool) -> Self {
+        self.render_enabled = enabled;
+        self
+    }
+
+    /// Limit the whole call to the given number of seconds
+    /// (`[timeouts]` config section). `None` means no limit.
+    pub fn with_timeout(mut self, secs: Option<u64>) -> Self {
+        self.timeout = secs;
+        self
+    }
+
+    /// Model used for prompt-based extraction.
+    fn extraction_model(&self) -> &str {
+        self.model.as_deref().unwrap_or(DEFAULT_EXTRACTION_MODEL)
+    }
+
+    fn parse_args(&self, args: Value) -> Result<(String, Option<String>, bool), FunctionError> {
         let url = args
             .get("url")
             .and_then(|v| v.as_str())
@@ -35,7 +242,143 @@ impl WebFetchTool {
             .and_then(|v| v.as_str())
             .map(|s| s.to_string());
 
-        Ok((url, prompt))
+        let render = args
+            .get("render")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        Ok((url, prompt, render))
+    }
+
+    /// Fetch the URL through the cache: fresh entries skip the network,
+    /// stale ones are revalidated with conditional headers, everything
+    /// else downloads and is stored for next time.
+    async fn fetch_cached(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+    ) -> Result<(CacheEntry, CacheStatus), Value> {
+        let now = unix_now();
+        let cached = match (self.cache_ttl > 0)
+            .then(|| load_entry(url, false))
+            .flatten()
+        {
+            Some(entry) if entry.is_fresh(now, self.cache_ttl) => {
+                return Ok((entry, CacheStatus::Hit));
+            }
+            other => other,
+        };
+
+        let mut request = client.get(url);
+        if let Some(entry) = &cached {
+            if let Some(etag) = &entry.etag {
+                request = request.header("If-None-Match", etag);
+            }
+            if let Some(last_modified) = &entry.last_modified {
+                request = request.header("If-Modified-Since", last_modified);
+            }
+        }
+
+        let resp = match request.send().await {
+            Ok(r) => r,
+            Err(e) => return Err(json!({ "error": format!("Network error: {}", e) })),
+        };
+        let status = resp.status();
+
+        if status == reqwest::StatusCode::NOT_MODIFIED
+            && let Some(mut entry) = cached
+        {
+            entry.fetched_at = now;
+            store_entry(&entry);
+            return Ok((entry, CacheStatus::Revalidated));
+        }
+        if !status.is_success() {
+            return Err(json!({
+                "error": format!("HTTP error: {}", status),
+                "status": status.as_u16()
+            }));
+        }
+
+        let header = |name: &str| {
+            resp.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(String::from)
+        };
+        let etag = header("etag");
+        let last_modified = header("last-modified");
+
+        let body = match resp.text().await {
+            Ok(t) => t,
+            Err(e) => {
+                return Err(json!({ "error": format!("Failed to read response body: {}", e) }));
+            }
+        };
+
+        let entry = CacheEntry {
+            url: url.to_string(),
+            fetched_at: now,
+            etag,
+            last_modified,
+            // Content changed (or is new), so prior extractions don't apply
+            processed: HashMap::new(),
+            body,
+            rendered: false,
+        };
+        if self.cache_ttl > 0 {
+            store_entry(&entry);
+        }
+        Ok((entry, CacheStatus::Miss))
+    }
+
+    /// Rendered variant of [`fetch_cached`](Self::fetch_cached). Rendered DOM
+    /// has no HTTP validators, so stale entries are re-rendered rather than
+    /// revalidated.
+    async fn fetch_rendered(&self, url: &str) -> Result<(CacheEntry, CacheStatus), Value> {
+        let now = unix_now();
+        if let Some(entry) = (self.cache_ttl > 0).then(|| load_entry(url, true)).flatten()
+            && entry.is_fresh(now, self.cache_ttl)
+        {
+            return Ok((entry, CacheStatus::Hit));
+        }
+
+        let Some(chrome) = find_chrome() else {
+            return Err(json!({
+                "error": format!(
+                    "No Chromium/Chrome binary found on PATH (looked for: {}). Install one or fetch without render.",
+                    CHROME_BINARIES.join(", ")
+                )
+            }));
+        };
+
+        let rendered = tokio::time::timeout(
+            std::time::Duration::from_secs(RENDER_TIMEOUT_SECS),
+            render_url(url, &chrome),
+        )
+        .await;
+        let body = match rendered {
+            Ok(Ok(body)) => body,
+            Ok(Err(e)) => return Err(json!({ "error": e })),
+            Err(_) => {
+                return Err(json!({
+                    "error": format!("Rendering timed out after {}s", RENDER_TIMEOUT_SECS)
+                }));
+            }
+        };
+
+        let entry = CacheEntry {
+            url: url.to_string(),
+            fetched_at: now,
+            etag: None,
+            last_modified: None,
+            body,
+            processed: HashMap::new(),
+            rendered: true,
+        };
+        if self.cache_ttl > 0 {
+            store_entry(&entry);
+        }
+        Ok((entry, CacheStatus::Miss))
     }
 }
 
@@ -44,7 +387,7 @@ impl CallableFunction for WebFetchTool {
     fn declaration(&self) -> FunctionDeclaration {
         FunctionDeclaration::new(
             "web_fetch".to_string(),
-            "Fetch the content of a web page from a URL and optionally process it with a prompt. Returns: {content} or {processed_content} if prompt provided".to_string(),
+            "Fetch the content of a web page from a URL and optionally process it with a prompt. Repeated fetches of the same URL are served from an on-disk cache (revalidated via ETag/Last-Modified once stale). Returns: {content} or {processed_content} if prompt provided".to_string(),
             FunctionParameters::new(
                 "object".to_string(),
                 json!({
@@ -55,6 +398,10 @@ impl CallableFunction for WebFetchTool {
                     "prompt": {
                         "type": "string",
                         "description": "Optional prompt to process the fetched content with Gemini"
+                    },
+                    "render": {
+                        "type": "boolean",
+                        "description": "Render the page with headless Chromium before conversion (for JS-heavy sites that return empty shells). Requires web_render = true in config. (default: false)"
                     }
                 }),
                 vec!["url".to_string()],
@@ -64,102 +411,135 @@ impl CallableFunction for WebFetchTool {
 
     #[instrument(skip(self, args))]
     async fn call(&self, args: Value) -> Result<Value, FunctionError> {
-        let (url, prompt) = self.parse_args(args)?;
+        super::run_with_timeout("web_fetch", self.timeout, self.run(args)).await
+    }
+}
+
+impl WebFetchTool {
+    /// The actual fetch, wrapped in the per-tool timeout by `call`.
+    async fn run(&self, args: Value) -> Result<Value, FunctionError> {
+        let (url, prompt, render) = self.parse_args(args)?;
+
+        if render && !self.render_enabled {
+            return Ok(json!({
+                "error": "Headless rendering is disabled. Set web_render = true in ~/.clemini/config.toml to enable it."
+            }));
+        }
 
-        let client = match super::create_http_client() {
-            Ok(c) => c,
-            Err(e) => return Ok(json!({ "error": e })),
+        let fetched = if render {
+            self.fetch_rendered(&url).await
+        } else {
+            let client = match super::create_http_client() {
+                Ok(c) => c,
+                Err(e) => return Ok(json!({ "error": e })),
+            };
+            self.fetch_cached(&client, &url).await
         };
+        let (mut entry, cache_status) = match fetched {
+            Ok(r) => r,
+            Err(e) => return Ok(e),
+        };
+        let original_len = entry.body.len();
+        let suffix = match cache_status {
+            CacheStatus::Hit => " (cached)",
+            CacheStatus::Revalidated => " (revalidated)",
+            CacheStatus::Miss if entry.rendered => " (rendered)",
+            CacheStatus::Miss => "",
+        };
+        self.emit(&format!(
+            "  {}",
+            format!("{} bytes{}", original_len, suffix).dimmed()
+        ));
+
+        if let Some(prompt) = prompt {
+            let prompt_key = super::memory::fnv1a_hex(&prompt);
+            // Unchanged content + same prompt: reuse the stored extraction
+            // instead of re-running the LLM.
+            if cache_status != CacheStatus::Miss
+                && let Some(processed) = entry.processed.get(&prompt_key)
+            {
+                return Ok(json!({
+                    "url": url,
+                    "processed_content": processed,
+                    "original_length": original_len,
+                    "cache": cache_status.as_str()
+                }));
+            }
 
-        match client.get(&url).send().await {
-            Ok(resp) => {
-                let status = resp.status();
-                if !status.is_success() {
+            // Convert HTML to markdown
+            let markdown = html2md::parse_html(&entry.body);
+            let mut truncated_md = markdown.clone();
+            if truncated_md.len() > MAX_TOOL_OUTPUT_LEN {
+                truncated_md.truncate(MAX_TOOL_OUTPUT_LEN);
+                truncated_md.push_str(&format!(
+                    "\n\n[Content truncated to {} characters]",
+                    MAX_TOOL_OUTPUT_LEN
+                ));
+            }
+
+            // Process with Gemini
+            let ai_client = genai_rs::Client::new(self.api_key.clone());
+            let ai_result = ai_client
+                .interaction()
+                .with_model(self.extraction_model())
+                .with_system_instruction(
+                    "You are a helpful assistant that processes web content.",
+                )
+                .with_content(vec![genai_rs::Content::text(format!(
+                    "Content:\n---\n{}\n---\n\nPrompt: {}",
+                    truncated_md, prompt
+                ))])
+                .create()
+                .await;
+
+            match ai_result {
+                Ok(response) => {
+                    let processed = response.as_text().unwrap_or_default().to_string();
+                    if self.cache_ttl > 0 {
+                        entry.processed.insert(prompt_key, processed.clone());
+                        store_entry(&entry);
+                    }
                     return Ok(json!({
-                        "error": format!("HTTP error: {}", status),
-                        "status": status.as_u16()
+                        "url": url,
+                        "processed_content": processed,
+                        "original_length": original_len,
+                        "cache": cache_status.as_str()
                     }));
                 }
-
-                match resp.text().await {
-                    Ok(mut text) => {
-                        let original_len = text.len();
-                        self.emit(&format!("  {}", format!("{} bytes", original_len).dimmed()));
-
-                        if let Some(prompt) = prompt {
-                            // Convert HTML to markdown
-                            let markdown = html2md::parse_html(&text);
-                            let mut truncated_md = markdown.clone();
-                            if truncated_md.len() > MAX_TOOL_OUTPUT_LEN {
-                                truncated_md.truncate(MAX_TOOL_OUTPUT_LEN);
-                                truncated_md.push_str(&format!(
-                                    "\n\n[Content truncated to {} characters]",
-                                    MAX_TOOL_OUTPUT_LEN
-                                ));
-                            }
-
-                            // Process with Gemini
-                            let ai_client = genai_rs::Client::new(self.api_key.clone());
-                            let ai_result = ai_client
-                                .interaction()
-                                .with_model("gemini-3-flash-preview")
-                                .with_system_instruction(
-                                    "You are a helpful assistant that processes web content.",
-                                )
-                                .with_content(vec![genai_rs::Content::text(format!(
-                                    "Content:\n---\n{}\n---\n\nPrompt: {}",
-                                    truncated_md, prompt
-                                ))])
-                                .create()
-                                .await;
-
-                            match ai_result {
-                                Ok(response) => {
-                                    return Ok(json!({
-                                        "url": url,
-                                        "processed_content": response.as_text().unwrap_or_default(),
-                                        "original_length": original_len
-                                    }));
-                                }
-                                Err(e) => {
-                                    // If LLM fails, return raw content with a note
-                                    if text.len() > MAX_TOOL_OUTPUT_LEN {
-                                        text.truncate(MAX_TOOL_OUTPUT_LEN);
-                                        text.push_str(&format!(
-                                            "\n\n[Content truncated to {} characters]",
-                                            MAX_TOOL_OUTPUT_LEN
-                                        ));
-                                    }
-                                    return Ok(json!({
-                                        "url": url,
-                                        "content": text,
-                                        "length": original_len,
-                                        "note": format!("LLM processing failed: {}", e)
-                                    }));
-                                }
-                            }
-                        }
-
-                        if text.len() > MAX_TOOL_OUTPUT_LEN {
-                            text.truncate(MAX_TOOL_OUTPUT_LEN);
-                            text.push_str(&format!(
-                                "\n\n[Content truncated to {} characters]",
-                                MAX_TOOL_OUTPUT_LEN
-                            ));
-                        }
-                        Ok(json!({
-                            "url": url,
-                            "content": text,
-                            "length": original_len
-                        }))
-                    }
-                    Err(e) => {
-                        Ok(json!({ "error": format!("Failed to read response body: {}", e) }))
+                Err(e) => {
+                    // If LLM fails, return raw content with a note
+                    let mut text = entry.body;
+                    if text.len() > MAX_TOOL_OUTPUT_LEN {
+                        text.truncate(MAX_TOOL_OUTPUT_LEN);
+                        text.push_str(&format!(
+                            "\n\n[Content truncated to {} characters]",
+                            MAX_TOOL_OUTPUT_LEN
+                        ));
                     }
+                    return Ok(json!({
+                        "url": url,
+                        "content": text,
+                        "length": original_len,
+                        "note": format!("LLM processing failed: {}", e)
+                    }));
                 }
             }
-            Err(e) => Ok(json!({ "error": format!("Network error: {}", e) })),
         }
+
+        let mut text = entry.body;
+        if text.len() > MAX_TOOL_OUTPUT_LEN {
+            text.truncate(MAX_TOOL_OUTPUT_LEN);
+            text.push_str(&format!(
+                "\n\n[Content truncated to {} characters]",
+                MAX_TOOL_OUTPUT_LEN
+            ));
+        }
+        Ok(json!({
+            "url": url,
+            "content": text,
+            "length": original_len,
+            "cache": cache_status.as_str()
+        }))
     }
 }
 
@@ -176,6 +556,7 @@ mod tests {
 
         assert_eq!(decl.name(), "web_fetch");
         assert!(decl.description().contains("process it with a prompt"));
+        assert!(decl.description().contains("on-disk cache"));
 
         let params = decl.parameters();
         let params_json = serde_json::to_value(params).unwrap();
@@ -189,6 +570,88 @@ mod tests {
         assert_eq!(properties["prompt"]["type"], "string");
     }
 
+    #[test]
+    fn test_extraction_model_defaults_and_overrides() {
+        let tool = WebFetchTool::new("test-key".to_string(), None);
+        assert_eq!(tool.extraction_model(), DEFAULT_EXTRACTION_MODEL);
+
+        let tool = WebFetchTool::new("test-key".to_string(), None)
+            .with_model(Some("gemini-flash-lite-latest".to_string()));
+        assert_eq!(tool.extraction_model(), "gemini-flash-lite-latest");
+
+        let tool = WebFetchTool::new("test-key".to_string(), None).with_model(None);
+        assert_eq!(tool.extraction_model(), DEFAULT_EXTRACTION_MODEL);
+    }
+
+    #[test]
+    fn test_cache_ttl_defaults_and_overrides() {
+        let tool = WebFetchTool::new("test-key".to_string(), None);
+        assert_eq!(tool.cache_ttl, DEFAULT_CACHE_TTL_SECS);
+
+        let tool = WebFetchTool::new("test-key".to_string(), None).with_cache_ttl(Some(0));
+        assert_eq!(tool.cache_ttl, 0);
+
+        let tool = WebFetchTool::new("test-key".to_string(), None).with_cache_ttl(None);
+        assert_eq!(tool.cache_ttl, DEFAULT_CACHE_TTL_SECS);
+    }
+
+    #[test]
+    fn test_cache_entry_freshness() {
+        let entry = CacheEntry {
+            url: "https://example.com".to_string(),
+            fetched_at: 1000,
+            etag: None,
+            last_modified: None,
+            body: String::new(),
+            processed: HashMap::new(),
+            rendered: false,
+        };
+        assert!(entry.is_fresh(1000, 900));
+        assert!(entry.is_fresh(1899, 900));
+        assert!(!entry.is_fresh(1900, 900));
+        assert!(!entry.is_fresh(1000, 0));
+    }
+
+    #[test]
+    fn test_cache_entry_round_trip() {
+        let url = "https://cache-test.invalid/round-trip";
+        let mut entry = CacheEntry {
+            url: url.to_string(),
+            fetched_at: 42,
+            etag: Some("\"abc\"".to_string()),
+            last_modified: None,
+            body: "<html>hello</html>".to_string(),
+            processed: HashMap::new(),
+            rendered: false,
+        };
+        entry
+            .processed
+            .insert("prompt-hash".to_string(), "a summary".to_string());
+        store_entry(&entry);
+
+        let loaded = load_entry(url, false).unwrap();
+        assert_eq!(loaded.url, url);
+        assert_eq!(loaded.etag.as_deref(), Some("\"abc\""));
+        assert_eq!(loaded.body, "<html>hello</html>");
+        assert_eq!(loaded.processed["prompt-hash"], "a summary");
+
+        std::fs::remove_file(cache_path(url, false)).unwrap();
+        assert!(load_entry(url, false).is_none());
+    }
+
+    #[test]
+    fn test_cache_paths_distinct_per_url() {
+        assert_ne!(
+            cache_path("https://example.com/a", false),
+            cache_path("https://example.com/b", false)
+        );
+        // Rendered and raw bodies for the same URL must not collide.
+        assert_ne!(
+            cache_path("https://example.com/a", false),
+            cache_path("https://example.com/a", true)
+        );
+    }
+
     #[test]
     fn test_parse_args_success() {
         let tool = WebFetchTool::new("test-key".to_string(), None);
@@ -196,9 +659,10 @@ mod tests {
             "url": "https://example.com"
         });
 
-        let (url, prompt) = tool.parse_args(args).unwrap();
+        let (url, prompt, render) = tool.parse_args(args).unwrap();
         assert_eq!(url, "https://example.com");
         assert!(prompt.is_none());
+        assert!(!render);
     }
 
     #[test]
@@ -209,11 +673,35 @@ mod tests {
             "prompt": "summarize this"
         });
 
-        let (url, prompt) = tool.parse_args(args).unwrap();
+        let (url, prompt, _) = tool.parse_args(args).unwrap();
         assert_eq!(url, "https://example.com");
         assert_eq!(prompt.unwrap(), "summarize this");
     }
 
+    #[test]
+    fn test_parse_args_render_flag() {
+        let tool = WebFetchTool::new("test-key".to_string(), None);
+        let (_, _, render) = tool
+            .parse_args(json!({"url": "https://example.com", "render": true}))
+            .unwrap();
+        assert!(render);
+    }
+
+    #[tokio::test]
+    async fn test_render_requires_config_flag() {
+        let tool = WebFetchTool::new("test-key".to_string(), None);
+        let result = tool
+            .call(json!({"url": "https://example.com", "render": true}))
+            .await
+            .unwrap();
+        assert!(
+            result["error"]
+                .as_str()
+                .unwrap()
+                .contains("web_render = true")
+        );
+    }
+
     #[test]
     fn test_parse_args_missing_url() {
         let tool = WebFetchTool::new("test-key".to_string(), None);
@@ -7,6 +7,306 @@ use serde_json::{Value, json};
 use tokio::sync::mpsc;
 use tracing::instrument;
 
+/// Search backend selection from config.toml (`search_provider`,
+/// `search_api_key`, `search_engine_id`). Defaults to DuckDuckGo, the only
+/// keyless option.
+#[derive(Debug, Default, Clone)]
+pub struct SearchConfig {
+    pub provider: Option<String>,
+    pub api_key: Option<String>,
+    /// Google CSE engine ID (`cx`); only the google provider uses it.
+    pub engine_id: Option<String>,
+}
+
+/// One search hit, normalized across providers.
+#[derive(Debug, PartialEq)]
+struct SearchResult {
+    title: String,
+    url: Option<String>,
+    snippet: String,
+}
+
+impl SearchResult {
+    fn to_json(&self) -> Value {
+        let mut v = json!({"title": self.title, "snippet": self.snippet});
+        if let Some(url) = &self.url {
+            v["url"] = json!(url);
+        }
+        v
+    }
+}
+
+/// A web search backend. Implementations only fetch and normalize;
+/// domain filtering and response shaping stay in the tool.
+#[async_trait]
+trait SearchProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+    async fn search(
+        &self,
+        client: &reqwest::Client,
+        query: &str,
+    ) -> Result<Vec<SearchResult>, String>;
+}
+
+/// Select the provider from config. Keyed providers fail here (not at
+/// request time) when `search_api_key` is missing, so the model sees an
+/// actionable configuration error.
+fn provider_from_config(config: &SearchConfig) -> Result<Box<dyn SearchProvider>, String> {
+    let api_key = || {
+        config.api_key.clone().ok_or_else(|| {
+            format!(
+                "search_api_key is required for the {} provider (set it in ~/.clemini/config.toml)",
+                config.provider.as_deref().unwrap_or_default()
+            )
+        })
+    };
+    match config.provider.as_deref() {
+        None | Some("duckduckgo") => Ok(Box::new(DuckDuckGo)),
+        Some("brave") => Ok(Box::new(Brave { api_key: api_key()? })),
+        Some("serpapi") => Ok(Box::new(SerpApi { api_key: api_key()? })),
+        Some("google") => Ok(Box::new(GoogleCse {
+            api_key: api_key()?,
+            engine_id: config.engine_id.clone().ok_or_else(|| {
+                "search_engine_id (the CSE cx value) is required for the google provider"
+                    .to_string()
+            })?,
+        })),
+        Some(other) => Err(format!(
+            "Unknown search_provider '{}'. Use duckduckgo, brave, serpapi, or google.",
+            other
+        )),
+    }
+}
+
+/// Run a GET request and parse the body as JSON, mapping transport and
+/// HTTP errors to strings.
+async fn fetch_json(builder: reqwest::RequestBuilder) -> Result<Value, String> {
+    let resp = builder
+        .send()
+        .await
+        .map_err(|e| format!("Network error: {}", e))?;
+    let status = resp.status();
+    if !status.is_success() {
+        return Err(format!("HTTP error: {}", status));
+    }
+    resp.json::<Value>()
+        .await
+        .map_err(|e| format!("Failed to parse JSON response: {}", e))
+}
+
+struct DuckDuckGo;
+
+fn parse_ddg(data: &Value) -> Vec<SearchResult> {
+    let mut results = Vec::new();
+    if let Some(abstract_text) = data.get("AbstractText").and_then(|v| v.as_str())
+        && !abstract_text.is_empty()
+    {
+        results.push(SearchResult {
+            title: data
+                .get("Heading")
+                .and_then(|v| v.as_str())
+                .unwrap_or("Abstract")
+                .to_string(),
+            url: data
+                .get("AbstractURL")
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .map(String::from),
+            snippet: abstract_text.to_string(),
+        });
+    }
+    if let Some(topics) = data.get("RelatedTopics").and_then(|v| v.as_array()) {
+        for topic in topics {
+            if let Some(text) = topic.get("Text").and_then(|v| v.as_str()) {
+                results.push(SearchResult {
+                    title: text.to_string(),
+                    url: topic
+                        .get("FirstURL")
+                        .and_then(|v| v.as_str())
+                        .map(String::from),
+                    snippet: text.to_string(),
+                });
+            }
+        }
+    }
+    results
+}
+
+#[async_trait]
+impl SearchProvider for DuckDuckGo {
+    fn name(&self) -> &'static str {
+        "duckduckgo"
+    }
+
+    async fn search(
+        &self,
+        client: &reqwest::Client,
+        query: &str,
+    ) -> Result<Vec<SearchResult>, String> {
+        let data = fetch_json(
+            client
+                .get("https://api.duckduckgo.com/")
+                .query(&[("q", query), ("format", "json")]),
+        )
+        .await?;
+        Ok(parse_ddg(&data))
+    }
+}
+
+struct Brave {
+    api_key: String,
+}
+
+fn parse_brave(data: &Value) -> Vec<SearchResult> {
+    data.pointer("/web/results")
+        .and_then(|v| v.as_array())
+        .map(|results| {
+            results
+                .iter()
+                .map(|r| SearchResult {
+                    title: r
+                        .get("title")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                    url: r.get("url").and_then(|v| v.as_str()).map(String::from),
+                    snippet: r
+                        .get("description")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[async_trait]
+impl SearchProvider for Brave {
+    fn name(&self) -> &'static str {
+        "brave"
+    }
+
+    async fn search(
+        &self,
+        client: &reqwest::Client,
+        query: &str,
+    ) -> Result<Vec<SearchResult>, String> {
+        let data = fetch_json(
+            client
+                .get("https://api.search.brave.com/res/v1/web/search")
+                .header("X-Subscription-Token", &self.api_key)
+                .header("Accept", "application/json")
+                .query(&[("q", query)]),
+        )
+        .await?;
+        Ok(parse_brave(&data))
+    }
+}
+
+struct SerpApi {
+    api_key: String,
+}
+
+fn parse_serpapi(data: &Value) -> Vec<SearchResult> {
+    data.get("organic_results")
+        .and_then(|v| v.as_array())
+        .map(|results| {
+            results
+                .iter()
+                .map(|r| SearchResult {
+                    title: r
+                        .get("title")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                    url: r.get("link").and_then(|v| v.as_str()).map(String::from),
+                    snippet: r
+                        .get("snippet")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[async_trait]
+impl SearchProvider for SerpApi {
+    fn name(&self) -> &'static str {
+        "serpapi"
+    }
+
+    async fn search(
+        &self,
+        client: &reqwest::Client,
+        query: &str,
+    ) -> Result<Vec<SearchResult>, String> {
+        let data = fetch_json(client.get("https://serpapi.com/search.json").query(&[
+            ("engine", "google"),
+            ("q", query),
+            ("api_key", self.api_key.as_str()),
+        ]))
+        .await?;
+        Ok(parse_serpapi(&data))
+    }
+}
+
+struct GoogleCse {
+    api_key: String,
+    engine_id: String,
+}
+
+fn parse_google(data: &Value) -> Vec<SearchResult> {
+    data.get("items")
+        .and_then(|v| v.as_array())
+        .map(|results| {
+            results
+                .iter()
+                .map(|r| SearchResult {
+                    title: r
+                        .get("title")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                    url: r.get("link").and_then(|v| v.as_str()).map(String::from),
+                    snippet: r
+                        .get("snippet")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[async_trait]
+impl SearchProvider for GoogleCse {
+    fn name(&self) -> &'static str {
+        "google"
+    }
+
+    async fn search(
+        &self,
+        client: &reqwest::Client,
+        query: &str,
+    ) -> Result<Vec<SearchResult>, String> {
+        let data = fetch_json(
+            client
+                .get("https://www.googleapis.com/customsearch/v1")
+                .query(&[
+                    ("key", self.api_key.as_str()),
+                    ("cx", self.engine_id.as_str()),
+                    ("q", query),
+                ]),
+        )
+        .await?;
+        Ok(parse_google(&data))
+    }
+}
+
 #[derive(Debug, PartialEq)]
 struct SearchArgs {
     query: String,
@@ -16,6 +316,9 @@ struct SearchArgs {
 
 pub struct WebSearchTool {
     events_tx: Option<mpsc::Sender<AgentEvent>>,
+    config: SearchConfig,
+    /// Per-tool timeout in seconds from the `[timeouts]` config section.
+    timeout: Option<u64>,
 }
 
 impl ToolEmitter for WebSearchTool {
@@ -26,7 +329,23 @@ impl ToolEmitter for WebSearchTool {
 
 impl WebSearchTool {
     pub fn new(events_tx: Option<mpsc::Sender<AgentEvent>>) -> Self {
-        Self { events_tx }
+        Self {
+            events_tx,
+            config: SearchConfig::default(),
+            timeout: None,
+        }
+    }
+
+    pub fn with_config(mut self, config: SearchConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Limit the whole call to the given number of seconds
+    /// (`[timeouts]` config section). `None` means no limit.
+    pub fn with_timeout(mut self, secs: Option<u64>) -> Self {
+        self.timeout = secs;
+        self
     }
 
     fn parse_args(&self, args: Value) -> Result<SearchArgs, FunctionError> {
@@ -65,8 +384,7 @@ impl CallableFunction for WebSearchTool {
     fn declaration(&self) -> FunctionDeclaration {
         FunctionDeclaration::new(
             "web_search".to_string(),
-            "Search the web using DuckDuckGo's instant answer API. Returns: {results[], query}"
-                .to_string(),
+            "Search the web. The backend is configured via search_provider (brave, serpapi, google, or the keyless duckduckgo default). Returns: {results: [{title, url?, snippet}], query, provider}".to_string(),
             FunctionParameters::new(
                 "object".to_string(),
                 json!({
@@ -92,80 +410,49 @@ impl CallableFunction for WebSearchTool {
 
     #[instrument(skip(self, args))]
     async fn call(&self, args: Value) -> Result<Value, FunctionError> {
+        super::run_with_timeout("web_search", self.timeout, self.run(args)).await
+    }
+}
+
+impl WebSearchTool {
+    /// The actual search, wrapped in the per-tool timeout by `call`.
+    async fn run(&self, args: Value) -> Result<Value, FunctionError> {
         let search_args = self.parse_args(args)?;
 
+        let provider = match provider_from_config(&self.config) {
+            Ok(p) => p,
+            Err(e) => return Ok(json!({ "error": e })),
+        };
         let client = match super::create_http_client() {
             Ok(c) => c,
             Err(e) => return Ok(json!({ "error": e })),
         };
 
-        let url = "https://api.duckduckgo.com/";
-        match client
-            .get(url)
-            .query(&[("q", search_args.query.as_str()), ("format", "json")])
-            .send()
-            .await
-        {
-            Ok(resp) => {
-                let status = resp.status();
-                if !status.is_success() {
-                    return Ok(json!({
-                        "error": format!("HTTP error: {}", status),
-                        "status": status.as_u16()
-                    }));
-                }
-
-                match resp.json::<Value>().await {
-                    Ok(data) => {
-                        let abstract_text = data
-                            .get("AbstractText")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("");
-                        let related_topics = data.get("RelatedTopics").and_then(|v| v.as_array());
-
-                        let mut results = Vec::new();
-                        if let Some(topics) = related_topics {
-                            for topic in topics {
-                                let text = topic.get("Text").and_then(|v| v.as_str());
-                                let first_url = topic.get("FirstURL").and_then(|v| v.as_str());
-
-                                if let Some(url_str) = first_url {
-                                    if !self.should_include(url_str, &search_args) {
-                                        continue;
-                                    }
-                                } else if search_args.allowed_domains.is_some() {
-                                    // If we have no URL but allowed_domains is set,
-                                    // we can't verify it belongs to an allowed domain.
-                                    // For DDG "RelatedTopics", some might be just text.
-                                    // We'll skip them if filtering is active and we can't verify.
-                                    continue;
-                                }
-
-                                if let Some(t) = text {
-                                    results.push(t.to_string());
-                                }
-                            }
-                        }
-
-                        let result_count = results.len();
-                        self.emit(&format!(
-                            "  {}",
-                            format!("{} results", result_count).dimmed()
-                        ));
-
-                        Ok(json!({
-                            "query": search_args.query,
-                            "abstract": abstract_text,
-                            "related_topics": results
-                        }))
-                    }
-                    Err(e) => {
-                        Ok(json!({ "error": format!("Failed to parse JSON response: {}", e) }))
-                    }
-                }
-            }
-            Err(e) => Ok(json!({ "error": format!("Network error: {}", e) })),
-        }
+        let results = match provider.search(&client, &search_args.query).await {
+            Ok(r) => r,
+            Err(e) => return Ok(json!({ "error": e, "provider": provider.name() })),
+        };
+
+        let filtered: Vec<Value> = results
+            .iter()
+            .filter(|r| match &r.url {
+                Some(url) => self.should_include(url, &search_args),
+                // No URL to verify: only keep when no allowlist is active.
+                None => search_args.allowed_domains.is_none(),
+            })
+            .map(SearchResult::to_json)
+            .collect();
+
+        self.emit(&format!(
+            "  {}",
+            format!("{} results via {}", filtered.len(), provider.name()).dimmed()
+        ));
+
+        Ok(json!({
+            "query": search_args.query,
+            "provider": provider.name(),
+            "results": filtered
+        }))
     }
 }
 
@@ -213,10 +500,8 @@ mod tests {
         let decl = tool.declaration();
 
         assert_eq!(decl.name(), "web_search");
-        assert_eq!(
-            decl.description(),
-            "Search the web using DuckDuckGo's instant answer API. Returns: {results[], query}"
-        );
+        assert!(decl.description().contains("search_provider"));
+        assert!(decl.description().contains("duckduckgo"));
 
         let params = decl.parameters();
         let params_json = serde_json::to_value(params).unwrap();
@@ -329,4 +614,103 @@ mod tests {
         assert!(!tool.should_include("https://docs.github.com/en", &args));
         assert!(!tool.should_include("https://google.com", &args));
     }
+
+    #[test]
+    fn test_provider_selection() {
+        let duckduckgo = provider_from_config(&SearchConfig::default()).unwrap();
+        assert_eq!(duckduckgo.name(), "duckduckgo");
+
+        let brave = provider_from_config(&SearchConfig {
+            provider: Some("brave".to_string()),
+            api_key: Some("key".to_string()),
+            engine_id: None,
+        })
+        .unwrap();
+        assert_eq!(brave.name(), "brave");
+
+        // Keyed provider without a key is a config error
+        let err = provider_from_config(&SearchConfig {
+            provider: Some("serpapi".to_string()),
+            api_key: None,
+            engine_id: None,
+        })
+        .unwrap_err();
+        assert!(err.contains("search_api_key"));
+
+        // Google additionally needs the engine ID
+        let err = provider_from_config(&SearchConfig {
+            provider: Some("google".to_string()),
+            api_key: Some("key".to_string()),
+            engine_id: None,
+        })
+        .unwrap_err();
+        assert!(err.contains("search_engine_id"));
+
+        let err = provider_from_config(&SearchConfig {
+            provider: Some("bing".to_string()),
+            api_key: None,
+            engine_id: None,
+        })
+        .unwrap_err();
+        assert!(err.contains("Unknown search_provider"));
+    }
+
+    #[test]
+    fn test_parse_ddg() {
+        let data = json!({
+            "Heading": "Rust",
+            "AbstractText": "A systems programming language.",
+            "AbstractURL": "https://www.rust-lang.org/",
+            "RelatedTopics": [
+                {"Text": "Cargo - the Rust package manager", "FirstURL": "https://doc.rust-lang.org/cargo/"},
+                {"Name": "category-only entry, no Text"}
+            ]
+        });
+        let results = parse_ddg(&data);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].title, "Rust");
+        assert_eq!(results[0].url.as_deref(), Some("https://www.rust-lang.org/"));
+        assert_eq!(results[1].snippet, "Cargo - the Rust package manager");
+    }
+
+    #[test]
+    fn test_parse_brave() {
+        let data = json!({
+            "web": {"results": [
+                {"title": "Rust", "url": "https://rust-lang.org", "description": "The language"}
+            ]}
+        });
+        let results = parse_brave(&data);
+        assert_eq!(
+            results,
+            vec![SearchResult {
+                title: "Rust".to_string(),
+                url: Some("https://rust-lang.org".to_string()),
+                snippet: "The language".to_string(),
+            }]
+        );
+        assert!(parse_brave(&json!({})).is_empty());
+    }
+
+    #[test]
+    fn test_parse_serpapi_and_google() {
+        let serp = json!({
+            "organic_results": [
+                {"title": "Rust", "link": "https://rust-lang.org", "snippet": "The language"}
+            ]
+        });
+        let results = parse_serpapi(&serp);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].url.as_deref(), Some("https://rust-lang.org"));
+
+        let google = json!({
+            "items": [
+                {"title": "Rust", "link": "https://rust-lang.org", "snippet": "The language"}
+            ]
+        });
+        let results = parse_google(&google);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title, "Rust");
+        assert!(parse_google(&json!({"items": []})).is_empty());
+    }
 }
@@ -13,6 +13,8 @@ pub struct WriteTool {
     cwd: PathBuf,
     allowed_paths: Vec<PathBuf>,
     events_tx: Option<mpsc::Sender<AgentEvent>>,
+    dry_run: bool,
+    review: Option<super::review::ReviewChanges>,
 }
 
 impl WriteTool {
@@ -25,8 +27,82 @@ impl WriteTool {
             cwd,
             allowed_paths,
             events_tx,
+            dry_run: false,
+            review: None,
         }
     }
+
+    /// In dry-run mode the proposed write is emitted as a diff and reported
+    /// as a success, but nothing is written to disk.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// In review mode writes are staged into the pending changeset instead
+    /// of hitting disk, until the user runs `/apply` or `/discard`.
+    pub fn with_review(mut self, review: Option<super::review::ReviewChanges>) -> Self {
+        self.review = review;
+        self
+    }
+
+    /// Stage the write into the review changeset. Mirrors the disk path's
+    /// semantics (append concatenates, create_new refuses an existing file)
+    /// but nothing is written until `/apply`.
+    fn stage_review(
+        &self,
+        review: &super::review::ReviewChanges,
+        path: &std::path::Path,
+        file_path: &str,
+        content: &str,
+        mode: &str,
+    ) -> Value {
+        let previous = super::review::staged_content(&self.review, path)
+            .or_else(|| std::fs::read_to_string(path).ok());
+        let exists = previous.is_some();
+        if mode == "create_new" && exists {
+            return error_response(
+                &format!(
+                    "{} already exists. Use mode 'overwrite' to replace it.",
+                    file_path
+                ),
+                error_codes::INVALID_ARGUMENT,
+                json!({"path": file_path}),
+            );
+        }
+
+        let new_content = if mode == "append" {
+            format!("{}{}", previous.as_deref().unwrap_or(""), content)
+        } else {
+            content.to_string()
+        };
+        super::review::stage(review, path, new_content.clone());
+
+        let diff_output = crate::diff::format_diff(
+            previous.as_deref().unwrap_or(""),
+            &new_content,
+            2,
+            Some(file_path),
+        );
+        if !diff_output.is_empty() {
+            self.emit(&diff_output);
+        }
+
+        let mut response = json!({
+            "path": path.display().to_string(),
+            "bytes_written": content.len(),
+            "staged": true,
+            "success": true
+        });
+        if mode == "append" && exists {
+            response["appended"] = json!(true);
+        } else if exists {
+            response["overwritten"] = json!(true);
+        } else {
+            response["created"] = json!(true);
+        }
+        response
+    }
 }
 
 impl ToolEmitter for WriteTool {
@@ -40,7 +116,7 @@ impl CallableFunction for WriteTool {
     fn declaration(&self) -> FunctionDeclaration {
         FunctionDeclaration::new(
             "write_file".to_string(),
-            "Write content to a file. Creates the file if it doesn't exist, overwrites if it does. Creates parent directories as needed. Returns: {success, bytes_written, created?, overwritten?, backup_created?}".to_string(),
+            "Write content to a file. Creates the file if it doesn't exist, overwrites if it does. Creates parent directories as needed. Set mode to 'append' to add to the end of the file, or 'create_new' to fail if the file already exists instead of clobbering it. Set 'preview' to true to get a unified diff of the would-be change without writing. Returns: {success, bytes_written, created?, overwritten?, appended?, backup_created?, diff?}".to_string(),
             FunctionParameters::new(
                 "object".to_string(),
                 json!({
@@ -52,9 +128,17 @@ impl CallableFunction for WriteTool {
                         "type": "string",
                         "description": "The content to write to the file"
                     },
+                    "mode": {
+                        "type": "string",
+                        "description": "'overwrite' replaces the file, 'append' adds to the end (creating it if missing), 'create_new' fails if the file already exists. (default: overwrite)"
+                    },
                     "backup": {
                         "type": "boolean",
                         "description": "Whether to create a backup of the existing file (as {filename}.bak) before overwriting. (default: false)"
+                    },
+                    "preview": {
+                        "type": "boolean",
+                        "description": "If true, compute the change and return it as a unified diff in the 'diff' field without writing the file. (default: false)"
                     }
                 }),
                 vec!["file_path".to_string(), "content".to_string()],
@@ -79,6 +163,26 @@ impl CallableFunction for WriteTool {
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
 
+        let preview = args
+            .get("preview")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let mode = args
+            .get("mode")
+            .and_then(|v| v.as_str())
+            .unwrap_or("overwrite");
+        if !matches!(mode, "overwrite" | "append" | "create_new") {
+            return Ok(error_response(
+                &format!(
+                    "Unknown mode '{}'. Use 'overwrite', 'append', or 'create_new'.",
+                    mode
+                ),
+                error_codes::INVALID_ARGUMENT,
+                json!({"mode": mode}),
+            ));
+        }
+
         // Resolve and validate path
         let path = match resolve_and_validate_path(file_path, &self.cwd, &self.allowed_paths) {
             Ok(p) => p,
@@ -93,6 +197,75 @@ impl CallableFunction for WriteTool {
 
         // Logging is handled by main.rs event loop with timing info
 
+        // Preview behaves like a per-call dry run: compute everything,
+        // write nothing
+        if self.dry_run || preview {
+            // Staged review content shadows the disk as the diff base.
+            let previous = match super::review::staged_content(&self.review, &path) {
+                Some(staged) => Some(staged),
+                None => tokio::fs::read_to_string(&path).await.ok(),
+            };
+            let exists = previous.is_some();
+            if mode == "create_new" && exists {
+                return Ok(error_response(
+                    &format!(
+                        "{} already exists. Use mode 'overwrite' to replace it.",
+                        file_path
+                    ),
+                    error_codes::INVALID_ARGUMENT,
+                    json!({"path": file_path}),
+                ));
+            }
+            // Appending diffs against the would-be concatenation; the other
+            // modes diff against the replacement content.
+            let new_content = if mode == "append" {
+                format!("{}{}", previous.as_deref().unwrap_or(""), content)
+            } else {
+                content.to_string()
+            };
+            let diff_output = crate::diff::format_diff(
+                previous.as_deref().unwrap_or(""),
+                &new_content,
+                2,
+                Some(file_path),
+            );
+            if !diff_output.is_empty() {
+                self.emit(&diff_output);
+            }
+
+            let mut response = json!({
+                "path": path.display().to_string(),
+                "bytes_written": content.len(),
+                "success": true
+            });
+            if mode == "append" && exists {
+                response["appended"] = json!(true);
+            } else if exists {
+                response["overwritten"] = json!(true);
+            } else {
+                response["created"] = json!(true);
+            }
+            if self.dry_run {
+                response["dry_run"] = json!(true);
+            }
+            if preview {
+                response["preview"] = json!(true);
+                response["diff"] = json!(crate::diff::unified_diff(
+                    previous.as_deref().unwrap_or(""),
+                    &new_content,
+                    2,
+                    Some(file_path)
+                ));
+            }
+            return Ok(response);
+        }
+
+        // Review mode: accumulate into the pending changeset; the user lands
+        // or drops the whole set with /apply and /discard.
+        if let Some(review) = &self.review {
+            return Ok(self.stage_review(review, &path, file_path, content, mode));
+        }
+
         // Create parent directories if needed
         if let Some(parent) = path.parent()
             && !parent.exists()
@@ -113,6 +286,17 @@ impl CallableFunction for WriteTool {
         let previous_size = metadata.as_ref().map(|m| m.len());
         let exists = metadata.is_some();
 
+        if mode == "create_new" && exists {
+            return Ok(error_response(
+                &format!(
+                    "{} already exists. Use mode 'overwrite' to replace it.",
+                    file_path
+                ),
+                error_codes::INVALID_ARGUMENT,
+                json!({"path": file_path}),
+            ));
+        }
+
         let mut backup_created = false;
         if backup && exists {
             let mut backup_path_os = path.clone().into_os_string();
@@ -133,7 +317,33 @@ impl CallableFunction for WriteTool {
             backup_created = true;
         }
 
-        match tokio::fs::write(&path, content).await {
+        // Previous contents anchor the post-write diff; unreadable (e.g.
+        // non-UTF-8) files diff against an empty base.
+        let previous = if exists {
+            tokio::fs::read_to_string(&path).await.ok()
+        } else {
+            None
+        };
+
+        // Snapshot the pre-write state so /undo and revert_file can restore it.
+        super::checkpoint::snapshot(&self.cwd, &path);
+
+        let write_result = if mode == "append" {
+            use tokio::io::AsyncWriteExt;
+            match tokio::fs::OpenOptions::new()
+                .append(true)
+                .create(true)
+                .open(&path)
+                .await
+            {
+                Ok(mut file) => file.write_all(content.as_bytes()).await,
+                Err(e) => Err(e),
+            }
+        } else {
+            tokio::fs::write(&path, content).await
+        };
+
+        match write_result {
             Ok(()) => {
                 let mut response = json!({
                     "path": path.display().to_string(),
@@ -142,7 +352,11 @@ impl CallableFunction for WriteTool {
                 });
 
                 if exists {
-                    response["overwritten"] = json!(true);
+                    if mode == "append" {
+                        response["appended"] = json!(true);
+                    } else {
+                        response["overwritten"] = json!(true);
+                    }
                     if backup_created {
                         response["backup_created"] = json!(true);
                     }
@@ -153,13 +367,35 @@ impl CallableFunction for WriteTool {
                     response["created"] = json!(true);
                 }
 
-                // Emit visual output
-                let line_count = content.lines().count();
-                let action = if exists { "overwritten" } else { "created" };
-                let msg = format!("  {} lines {}", line_count, action)
-                    .dimmed()
-                    .to_string();
-                self.emit(&msg);
+                // Emit the change as a colorized diff so the user can review
+                // it inline; no-op writes fall back to the line-count summary.
+                let new_content = if mode == "append" {
+                    format!("{}{}", previous.as_deref().unwrap_or(""), content)
+                } else {
+                    content.to_string()
+                };
+                let diff_output = crate::diff::format_diff(
+                    previous.as_deref().unwrap_or(""),
+                    &new_content,
+                    2,
+                    Some(file_path),
+                );
+                if diff_output.is_empty() {
+                    let line_count = content.lines().count();
+                    let action = if !exists {
+                        "created"
+                    } else if mode == "append" {
+                        "appended"
+                    } else {
+                        "overwritten"
+                    };
+                    let msg = format!("  {} lines {}", line_count, action)
+                        .dimmed()
+                        .to_string();
+                    self.emit(&msg);
+                } else {
+                    self.emit(&diff_output);
+                }
 
                 Ok(response)
             }
@@ -299,6 +535,290 @@ mod tests {
         assert!(!backup_path.exists());
     }
 
+    #[tokio::test]
+    async fn test_write_tool_dry_run_does_not_touch_disk() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let tool = WriteTool::new(cwd.clone(), vec![cwd.clone()], None).with_dry_run(true);
+        let args = json!({
+            "file_path": "test.txt",
+            "content": "hello"
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["success"].as_bool().unwrap());
+        assert!(result["dry_run"].as_bool().unwrap());
+        assert!(result["created"].as_bool().unwrap());
+        assert!(!cwd.join("test.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_write_tool_dry_run_overwrite_preserves_content() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("test.txt");
+        fs::write(&file_path, "old content").unwrap();
+
+        let tool = WriteTool::new(cwd.clone(), vec![cwd.clone()], None).with_dry_run(true);
+        let args = json!({
+            "file_path": "test.txt",
+            "content": "new content"
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["success"].as_bool().unwrap());
+        assert!(result["dry_run"].as_bool().unwrap());
+        assert!(result["overwritten"].as_bool().unwrap());
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "old content");
+    }
+
+    #[tokio::test]
+    async fn test_write_tool_preview_returns_diff_without_writing() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("test.txt");
+        fs::write(&file_path, "old line\n").unwrap();
+
+        let tool = WriteTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "test.txt",
+            "content": "new line\n",
+            "preview": true
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["success"].as_bool().unwrap());
+        assert!(result["preview"].as_bool().unwrap());
+        assert!(result["dry_run"].is_null());
+        assert!(result["overwritten"].as_bool().unwrap());
+
+        let diff = result["diff"].as_str().unwrap();
+        assert!(diff.contains("--- a/test.txt"));
+        assert!(diff.contains("-old line"));
+        assert!(diff.contains("+new line"));
+
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "old line\n");
+    }
+
+    #[tokio::test]
+    async fn test_write_tool_preview_new_file() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+
+        let tool = WriteTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "test.txt",
+            "content": "hello\n",
+            "preview": true
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["success"].as_bool().unwrap());
+        assert!(result["preview"].as_bool().unwrap());
+        assert!(result["created"].as_bool().unwrap());
+        assert!(result["diff"].as_str().unwrap().contains("+hello"));
+        assert!(!cwd.join("test.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_write_tool_append_adds_to_existing() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("log.txt");
+        fs::write(&file_path, "line 1\n").unwrap();
+
+        let tool = WriteTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "log.txt",
+            "content": "line 2\n",
+            "mode": "append"
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["success"].as_bool().unwrap());
+        assert!(result["appended"].as_bool().unwrap());
+        assert!(result["overwritten"].is_null());
+
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "line 1\nline 2\n");
+    }
+
+    #[tokio::test]
+    async fn test_write_tool_append_creates_missing_file() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+
+        let tool = WriteTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "log.txt",
+            "content": "first\n",
+            "mode": "append"
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["success"].as_bool().unwrap());
+        assert!(result["created"].as_bool().unwrap());
+        assert_eq!(fs::read_to_string(cwd.join("log.txt")).unwrap(), "first\n");
+    }
+
+    #[tokio::test]
+    async fn test_write_tool_create_new_fails_on_existing() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("test.txt");
+        fs::write(&file_path, "precious").unwrap();
+
+        let tool = WriteTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "test.txt",
+            "content": "clobber",
+            "mode": "create_new"
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["error"].as_str().unwrap().contains("already exists"));
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "precious");
+    }
+
+    #[tokio::test]
+    async fn test_write_tool_create_new_succeeds_for_new_file() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+
+        let tool = WriteTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "test.txt",
+            "content": "hello",
+            "mode": "create_new"
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["success"].as_bool().unwrap());
+        assert!(result["created"].as_bool().unwrap());
+        assert_eq!(fs::read_to_string(cwd.join("test.txt")).unwrap(), "hello");
+    }
+
+    #[tokio::test]
+    async fn test_write_tool_unknown_mode_is_an_error() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+
+        let tool = WriteTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "test.txt",
+            "content": "hello",
+            "mode": "truncate"
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+        assert!(!cwd.join("test.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_write_tool_preview_append_diffs_concatenation() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        let file_path = cwd.join("log.txt");
+        fs::write(&file_path, "line 1\n").unwrap();
+
+        let tool = WriteTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "log.txt",
+            "content": "line 2\n",
+            "mode": "append",
+            "preview": true
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert!(result["preview"].as_bool().unwrap());
+        assert!(result["appended"].as_bool().unwrap());
+        let diff = result["diff"].as_str().unwrap();
+        assert!(diff.contains("+line 2"));
+        assert!(
+            !diff.contains("-line 1"),
+            "existing content is kept: {diff}"
+        );
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "line 1\n");
+    }
+
+    #[tokio::test]
+    async fn test_write_tool_create_new_fails_fast_in_preview() {
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("test.txt"), "precious").unwrap();
+
+        let tool = WriteTool::new(cwd.clone(), vec![cwd.clone()], None);
+        let args = json!({
+            "file_path": "test.txt",
+            "content": "clobber",
+            "mode": "create_new",
+            "preview": true
+        });
+
+        let result = tool.call(args).await.unwrap();
+        assert_eq!(result["error_code"], error_codes::INVALID_ARGUMENT);
+    }
+
+    #[tokio::test]
+    async fn test_write_tool_review_stages_without_touching_disk() {
+        use std::sync::{Arc, RwLock};
+
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+        fs::write(cwd.join("test.txt"), "old").unwrap();
+
+        let review: super::super::review::ReviewChanges =
+            Arc::new(RwLock::new(super::super::review::PendingChanges::default()));
+        let tool =
+            WriteTool::new(cwd.clone(), vec![cwd.clone()], None).with_review(Some(review.clone()));
+
+        let result = tool
+            .call(json!({"file_path": "test.txt", "content": "new"}))
+            .await
+            .unwrap();
+        assert!(result["success"].as_bool().unwrap());
+        assert!(result["staged"].as_bool().unwrap());
+        assert!(result["overwritten"].as_bool().unwrap());
+
+        // Disk untouched; the changeset holds the pending version.
+        assert_eq!(fs::read_to_string(cwd.join("test.txt")).unwrap(), "old");
+        let staged = super::super::review::staged_content(
+            &Some(review),
+            &cwd.join("test.txt").canonicalize().unwrap(),
+        );
+        assert_eq!(staged.as_deref(), Some("new"));
+    }
+
+    #[tokio::test]
+    async fn test_write_tool_review_append_builds_on_staged() {
+        use std::sync::{Arc, RwLock};
+
+        let dir = tempdir().unwrap();
+        let cwd = dir.path().to_path_buf();
+
+        let review: super::super::review::ReviewChanges =
+            Arc::new(RwLock::new(super::super::review::PendingChanges::default()));
+        let tool =
+            WriteTool::new(cwd.clone(), vec![cwd.clone()], None).with_review(Some(review.clone()));
+
+        tool.call(json!({"file_path": "log.txt", "content": "one\n"}))
+            .await
+            .unwrap();
+        let result = tool
+            .call(json!({"file_path": "log.txt", "content": "two\n", "mode": "append"}))
+            .await
+            .unwrap();
+        assert!(result["appended"].as_bool().unwrap());
+        assert!(!cwd.join("log.txt").exists());
+
+        let staged = super::super::review::staged_content(
+            &Some(review),
+            &cwd.canonicalize().unwrap().join("log.txt"),
+        );
+        assert_eq!(staged.as_deref(), Some("one\ntwo\n"));
+    }
+
     #[tokio::test]
     async fn test_write_tool_backup_failure() {
         let dir = tempdir().unwrap();
@@ -0,0 +1,392 @@
+//! Session transcript recording and export.
+//!
+//! `TranscriptRecorder` sits in the agent event pipeline: the UI layer feeds
+//! it every `AgentEvent` (alongside normal dispatch) and it accumulates a
+//! structured, shareable record of the session - user prompts, agent
+//! narration, reasoning, and tool calls with args and results. Unlike the
+//! colored log file, transcripts render to clean Markdown or JSON.
+//!
+//! Two export paths:
+//! - `/export <path>` in the REPL writes the live session
+//! - `clemini export <path>` converts the most recent autosaved session
+//!
+//! The REPL autosaves the transcript as JSON to `~/.clemini/transcripts/`
+//! on exit so sessions can be exported after the fact.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::agent::AgentEvent;
+
+/// One entry in a session transcript.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TranscriptEntry {
+    /// A prompt the user sent.
+    UserPrompt { text: String },
+    /// Agent response text (narration or final answer).
+    AgentText { text: String },
+    /// Model reasoning, kept separate from response text.
+    Thinking { text: String },
+    /// A completed tool call.
+    ToolCall {
+        name: String,
+        args: Value,
+        result: Value,
+        duration_ms: u64,
+        is_error: bool,
+    },
+    /// A steering message injected mid-task.
+    Steering { text: String },
+    /// An interaction finished (boundary marker with the resumable ID).
+    InteractionComplete { interaction_id: Option<String> },
+    /// The user cancelled the interaction.
+    Cancelled,
+}
+
+/// Accumulates `AgentEvent`s into a structured session transcript.
+///
+/// Streaming text and thinking deltas are buffered and flushed into single
+/// entries at event boundaries, mirroring how `TerminalEventHandler` renders
+/// them.
+#[derive(Debug, Default)]
+pub struct TranscriptRecorder {
+    entries: Vec<TranscriptEntry>,
+    pending_text: String,
+    pending_thinking: String,
+    cwd: Option<String>,
+}
+
+impl TranscriptRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the workspace this session ran in; `clemini sessions list`
+    /// shows it alongside age and size.
+    pub fn set_cwd(&mut self, cwd: &Path) {
+        self.cwd = Some(cwd.display().to_string());
+    }
+
+    /// Record a prompt the user sent (the agent doesn't emit an event for this).
+    pub fn record_user_prompt(&mut self, text: &str) {
+        self.flush_pending();
+        self.entries.push(TranscriptEntry::UserPrompt {
+            text: text.to_string(),
+        });
+    }
+
+    /// Record an agent event. Call for every event, alongside normal dispatch.
+    pub fn record_event(&mut self, event: &AgentEvent) {
+        match event {
+            AgentEvent::TextDelta(text) => self.pending_text.push_str(text),
+            AgentEvent::Thinking(text) => self.pending_thinking.push_str(text),
+            AgentEvent::ToolResult(result) => {
+                self.flush_pending();
+                self.entries.push(TranscriptEntry::ToolCall {
+                    name: result.name.clone(),
+                    args: result.args.clone(),
+                    result: result.result.clone(),
+                    duration_ms: result.duration.as_millis() as u64,
+                    is_error: result.is_error(),
+                });
+            }
+            AgentEvent::UserSteering(message) => {
+                self.flush_pending();
+                self.entries.push(TranscriptEntry::Steering {
+                    text: message.clone(),
+                });
+            }
+            AgentEvent::Complete { interaction_id, .. } => {
+                self.flush_pending();
+                self.entries.push(TranscriptEntry::InteractionComplete {
+                    interaction_id: interaction_id.clone(),
+                });
+            }
+            AgentEvent::Cancelled => {
+                self.flush_pending();
+                self.entries.push(TranscriptEntry::Cancelled);
+            }
+            // ToolExecuting is redundant with ToolCall; warnings/retries are
+            // session diagnostics, not conversation content
+            _ => {}
+        }
+    }
+
+    /// Flush buffered text/thinking deltas into entries.
+    fn flush_pending(&mut self) {
+        if !self.pending_thinking.is_empty() {
+            self.entries.push(TranscriptEntry::Thinking {
+                text: std::mem::take(&mut self.pending_thinking),
+            });
+        }
+        if !self.pending_text.is_empty() {
+            self.entries.push(TranscriptEntry::AgentText {
+                text: std::mem::take(&mut self.pending_text),
+            });
+        }
+    }
+
+    /// Whether anything has been recorded yet.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty() && self.pending_text.is_empty() && self.pending_thinking.is_empty()
+    }
+
+    /// Render the transcript as JSON (versioned for forward compatibility).
+    pub fn to_json(&mut self) -> Value {
+        self.flush_pending();
+        serde_json::json!({
+            "version": 1,
+            "cwd": self.cwd,
+            "entries": self.entries,
+        })
+    }
+
+    /// Render the transcript as Markdown.
+    pub fn to_markdown(&mut self) -> String {
+        self.flush_pending();
+        render_markdown(&self.entries)
+    }
+
+    /// Write the transcript to `path`: JSON for `.json`, Markdown otherwise.
+    pub fn save(&mut self, path: &Path) -> std::io::Result<()> {
+        let content = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::to_string_pretty(&self.to_json())?
+        } else {
+            self.to_markdown()
+        };
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, content)
+    }
+}
+
+/// Render transcript entries as Markdown.
+///
+/// Also used by `clemini export` to convert autosaved JSON transcripts.
+pub fn render_markdown(entries: &[TranscriptEntry]) -> String {
+    let mut out = String::from("# clemini session transcript\n");
+    for entry in entries {
+        match entry {
+            TranscriptEntry::UserPrompt { text } => {
+                out.push_str(&format!("\n## User\n\n{}\n", text));
+            }
+            TranscriptEntry::AgentText { text } => {
+                out.push_str(&format!("\n## Agent\n\n{}\n", text));
+            }
+            TranscriptEntry::Thinking { text } => {
+                out.push_str("\n### Thinking\n\n");
+                for line in text.lines() {
+                    out.push_str(&format!("> {}\n", line));
+                }
+            }
+            TranscriptEntry::ToolCall {
+                name,
+                args,
+                result,
+                duration_ms,
+                is_error,
+            } => {
+                let status = if *is_error { " (error)" } else { "" };
+                out.push_str(&format!(
+                    "\n### Tool: {}{} [{}ms]\n",
+                    name, status, duration_ms
+                ));
+                out.push_str(&format!(
+                    "\n```json\n{}\n```\n",
+                    serde_json::to_string_pretty(args).unwrap_or_default()
+                ));
+                out.push_str(&format!(
+                    "\n```json\n{}\n```\n",
+                    serde_json::to_string_pretty(result).unwrap_or_default()
+                ));
+            }
+            TranscriptEntry::Steering { text } => {
+                out.push_str(&format!("\n### Steering\n\n{}\n", text));
+            }
+            TranscriptEntry::InteractionComplete { interaction_id } => {
+                if let Some(id) = interaction_id {
+                    out.push_str(&format!("\n---\n*interaction: {}*\n", id));
+                }
+            }
+            TranscriptEntry::Cancelled => {
+                out.push_str("\n*[cancelled]*\n");
+            }
+        }
+    }
+    out
+}
+
+/// The most recent resumable interaction ID in a transcript, if any.
+///
+/// Used by `clemini sessions resume` to pick up where a saved session left
+/// off via `with_previous_interaction`.
+pub fn last_interaction_id(entries: &[TranscriptEntry]) -> Option<String> {
+    entries.iter().rev().find_map(|entry| match entry {
+        TranscriptEntry::InteractionComplete {
+            interaction_id: Some(id),
+        } => Some(id.clone()),
+        _ => None,
+    })
+}
+
+/// Parse the entries out of an autosaved JSON transcript.
+pub fn parse_json(content: &str) -> Result<Vec<TranscriptEntry>, String> {
+    let value: Value =
+        serde_json::from_str(content).map_err(|e| format!("Invalid transcript JSON: {}", e))?;
+    let entries = value
+        .get("entries")
+        .cloned()
+        .ok_or_else(|| "Transcript missing 'entries' field".to_string())?;
+    serde_json::from_value(entries).map_err(|e| format!("Invalid transcript entries: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use genai_rs::{FunctionExecutionResult, InteractionResponse, InteractionStatus};
+    use std::time::Duration;
+
+    fn empty_response() -> InteractionResponse {
+        InteractionResponse {
+            id: Some("id-1".to_string()),
+            model: None,
+            agent: None,
+            input: vec![],
+            outputs: vec![],
+            status: InteractionStatus::Completed,
+            usage: None,
+            tools: None,
+            grounding_metadata: None,
+            url_context_metadata: None,
+            previous_interaction_id: None,
+            created: None,
+            updated: None,
+        }
+    }
+
+    fn recorded_session() -> TranscriptRecorder {
+        let mut recorder = TranscriptRecorder::new();
+        recorder.record_user_prompt("list the files");
+        recorder.record_event(&AgentEvent::Thinking("need a directory ".to_string()));
+        recorder.record_event(&AgentEvent::Thinking("listing".to_string()));
+        recorder.record_event(&AgentEvent::TextDelta("Listing files".to_string()));
+        recorder.record_event(&AgentEvent::ToolResult(FunctionExecutionResult::new(
+            "bash".to_string(),
+            "call-1".to_string(),
+            serde_json::json!({"command": "ls"}),
+            serde_json::json!({"output": "a.txt"}),
+            Duration::from_millis(42),
+        )));
+        recorder.record_event(&AgentEvent::TextDelta("Done: a.txt".to_string()));
+        recorder.record_event(&AgentEvent::Complete {
+            interaction_id: Some("id-1".to_string()),
+            response: Box::new(empty_response()),
+        });
+        recorder
+    }
+
+    #[test]
+    fn test_recorder_buffers_deltas_into_entries() {
+        let mut recorder = recorded_session();
+        let json = recorder.to_json();
+        let entries = json["entries"].as_array().unwrap();
+
+        assert_eq!(entries[0]["type"], "user_prompt");
+        assert_eq!(entries[1]["type"], "thinking");
+        assert_eq!(entries[1]["text"], "need a directory listing");
+        assert_eq!(entries[2]["type"], "agent_text");
+        assert_eq!(entries[2]["text"], "Listing files");
+        assert_eq!(entries[3]["type"], "tool_call");
+        assert_eq!(entries[3]["name"], "bash");
+        assert_eq!(entries[3]["duration_ms"], 42);
+        assert_eq!(entries[3]["is_error"], false);
+        assert_eq!(entries[4]["type"], "agent_text");
+        assert_eq!(entries[5]["type"], "interaction_complete");
+        assert_eq!(entries[5]["interaction_id"], "id-1");
+    }
+
+    #[test]
+    fn test_markdown_rendering() {
+        let markdown = recorded_session().to_markdown();
+
+        assert!(markdown.starts_with("# clemini session transcript"));
+        assert!(markdown.contains("## User\n\nlist the files"));
+        assert!(markdown.contains("> need a directory listing"));
+        assert!(markdown.contains("### Tool: bash [42ms]"));
+        assert!(markdown.contains("\"command\": \"ls\""));
+        assert!(markdown.contains("*interaction: id-1*"));
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let mut recorder = recorded_session();
+        let content = serde_json::to_string_pretty(&recorder.to_json()).unwrap();
+
+        let entries = parse_json(&content).unwrap();
+        assert_eq!(entries.len(), 6);
+        assert!(render_markdown(&entries).contains("### Tool: bash"));
+    }
+
+    #[test]
+    fn test_parse_json_rejects_garbage() {
+        assert!(parse_json("not json").is_err());
+        assert!(parse_json("{}").is_err());
+    }
+
+    #[test]
+    fn test_save_picks_format_by_extension() {
+        let temp = tempfile::tempdir().unwrap();
+
+        let json_path = temp.path().join("session.json");
+        recorded_session().save(&json_path).unwrap();
+        let saved = std::fs::read_to_string(&json_path).unwrap();
+        assert!(parse_json(&saved).is_ok());
+
+        let md_path = temp.path().join("session.md");
+        recorded_session().save(&md_path).unwrap();
+        let saved = std::fs::read_to_string(&md_path).unwrap();
+        assert!(saved.starts_with("# clemini session transcript"));
+    }
+
+    #[test]
+    fn test_to_json_includes_cwd() {
+        let mut recorder = recorded_session();
+        recorder.set_cwd(Path::new("/home/user/project"));
+        let json = recorder.to_json();
+        assert_eq!(json["cwd"], "/home/user/project");
+
+        let mut without = TranscriptRecorder::new();
+        assert!(without.to_json()["cwd"].is_null());
+    }
+
+    #[test]
+    fn test_last_interaction_id_picks_most_recent() {
+        let entries = vec![
+            TranscriptEntry::InteractionComplete {
+                interaction_id: Some("id-1".to_string()),
+            },
+            TranscriptEntry::InteractionComplete {
+                interaction_id: Some("id-2".to_string()),
+            },
+            TranscriptEntry::InteractionComplete {
+                interaction_id: None,
+            },
+        ];
+        assert_eq!(last_interaction_id(&entries), Some("id-2".to_string()));
+        assert_eq!(last_interaction_id(&[]), None);
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let mut recorder = TranscriptRecorder::new();
+        assert!(recorder.is_empty());
+        recorder.record_event(&AgentEvent::TextDelta("hi".to_string()));
+        assert!(!recorder.is_empty());
+    }
+}
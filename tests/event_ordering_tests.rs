@@ -348,3 +348,51 @@ async fn test_edit_tool_emits_diff_output() {
         );
     }
 }
+
+#[tokio::test]
+async fn test_write_tool_emits_diff_output() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let (events_tx, events_rx) = mpsc::channel::<AgentEvent>(100);
+
+    let tool_service = create_tool_service_with_events(&temp_dir, events_tx);
+
+    // Overwrite an existing file so the diff has both removed and added lines
+    let test_file = temp_dir.path().join("test.txt");
+    std::fs::write(&test_file, "old line\n").unwrap();
+
+    let tools = tool_service.tools();
+    let write_tool = tools
+        .iter()
+        .find(|t: &&Arc<dyn CallableFunction>| t.declaration().name() == "write_file")
+        .unwrap();
+
+    let _result: Value = write_tool
+        .call(json!({
+            "file_path": test_file.to_string_lossy(),
+            "content": "new line\n"
+        }))
+        .await
+        .unwrap();
+
+    let events = collect_events(events_rx).await;
+
+    // Should have ToolOutput events carrying the rendered diff, not just a
+    // "1 lines overwritten" summary
+    let outputs: Vec<&str> = events
+        .iter()
+        .filter_map(|e| {
+            if let AgentEvent::ToolOutput(s) = e {
+                Some(s.as_str())
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    let combined = outputs.join("");
+    assert!(
+        combined.contains("old line") && combined.contains("new line"),
+        "Expected write_file output to contain diff lines, got: {:?}",
+        outputs
+    );
+}